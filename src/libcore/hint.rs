@@ -47,6 +47,7 @@ use crate::intrinsics;
 /// ```
 #[inline]
 #[stable(feature = "unreachable", since = "1.27.0")]
+#[rustc_diagnostic_item = "unreachable_unchecked"]
 pub unsafe fn unreachable_unchecked() -> ! {
     intrinsics::unreachable()
 }
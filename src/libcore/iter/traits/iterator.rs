@@ -94,6 +94,7 @@ fn _assert_is_object_safe(_: &dyn Iterator<Item = ()>) {}
 )]
 #[doc(spotlight)]
 #[must_use = "iterators are lazy and do nothing unless consumed"]
+#[rustc_diagnostic_item = "Iterator"]
 pub trait Iterator {
     /// The type of the elements being iterated over.
     #[stable(feature = "rust1", since = "1.0.0")]
@@ -80,6 +80,8 @@
 #![feature(const_saturating_int_methods)]
 #![feature(const_int_unchecked_arith)]
 #![feature(const_int_pow)]
+#![feature(const_intrinsic_copy)]
+#![feature(const_intrinsic_write_bytes)]
 #![feature(constctlz)]
 #![feature(const_panic)]
 #![feature(const_fn_union)]
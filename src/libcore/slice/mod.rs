@@ -5976,6 +5976,21 @@ macro_rules! impl_marker_for {
 impl_marker_for!(BytewiseEquality,
                  u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize char bool);
 
+/// Compares two values for equality using their bytewise representation.
+///
+/// The MIR optimizations redirect calls to `PartialEq::eq` on arrays of `BytewiseEquality`
+/// types here, so that they become a single `memcmp` even before inlining.
+#[cfg(not(bootstrap))]
+#[lang = "bytewise_eq"]
+#[inline]
+fn bytewise_eq<T>(a: &T, b: &T) -> bool {
+    // SAFETY: the compiler only calls this for types without padding, whose values are
+    // equal exactly when their bytes are.
+    unsafe {
+        memcmp(a as *const T as *const u8, b as *const T as *const u8, mem::size_of::<T>()) == 0
+    }
+}
+
 #[doc(hidden)]
 unsafe impl<'a, T> TrustedRandomAccess for Iter<'a, T> {
     unsafe fn get_unchecked(&mut self, i: usize) -> &'a T {
@@ -25,6 +25,8 @@ macro_rules! arena_types {
             [] adt_def: rustc::ty::AdtDef,
             [] steal_mir: rustc::ty::steal::Steal<rustc::mir::BodyAndCache<$tcx>>,
             [] mir: rustc::mir::BodyAndCache<$tcx>,
+            [] mir_block: rustc::mir::BasicBlockData<$tcx>,
+            [] mir_outline: rustc::mir::BodyOutline<$tcx>,
             [] steal_promoted: rustc::ty::steal::Steal<
                 rustc_index::vec::IndexVec<
                     rustc::mir::Promoted,
@@ -43,6 +45,7 @@ macro_rules! arena_types {
                 rustc::ty::subst::SubstsRef<$tcx>
             )>,
             [few, decode] mir_keys: rustc_hir::def_id::DefIdSet,
+            [few] mir_callgraph: rustc::mir::CallGraph,
             [decode] specialization_graph: rustc::traits::specialization_graph::Graph,
             [] region_scope_tree: rustc::middle::region::ScopeTree,
             [] item_local_set: rustc_hir::ItemLocalSet,
@@ -80,6 +80,12 @@ bitflags! {
         const NO_SANITIZE_THREAD  = 1 << 14;
         /// All `#[no_sanitize(...)]` attributes.
         const NO_SANITIZE_ANY = Self::NO_SANITIZE_ADDRESS.bits | Self::NO_SANITIZE_MEMORY.bits | Self::NO_SANITIZE_THREAD.bits;
+        /// `#[rustc_mir_inline]`: the MIR inliner inlines this function wherever it can, even
+        /// when it is `#[inline(never)]` for LLVM.
+        const MIR_INLINE          = 1 << 15;
+        /// `#[rustc_no_mir_inline]`: the MIR inliner never inlines this function, even when it
+        /// is `#[inline(always)]` for LLVM.
+        const NO_MIR_INLINE       = 1 << 16;
     }
 }
 
@@ -106,6 +112,12 @@ impl CodegenFnAttrs {
         }
     }
 
+    /// Returns `true` if `#[inline]`, `#[inline(always)]` or `#[rustc_mir_inline]` is present,
+    /// so that the MIR of the function must be available to the other crates.
+    pub fn requests_mir_inline(&self) -> bool {
+        self.requests_inline() || self.flags.contains(CodegenFnAttrFlags::MIR_INLINE)
+    }
+
     /// Returns `true` if it looks like this symbol needs to be exported, for example:
     ///
     /// * `#[no_mangle]` is present
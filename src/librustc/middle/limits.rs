@@ -1,4 +1,5 @@
-//! Registering limits, recursion_limit, type_length_limit and const_eval_limit
+//! Registering limits, recursion_limit, type_length_limit, const_eval_limit and
+//! huge_function_body_limit, as well as the crate's mir_opt_level
 //!
 //! There are various parts of the compiler that must impose arbitrary limits
 //! on how deeply they recurse to prevent stack overflow. Users can override
@@ -17,6 +18,15 @@ pub fn update_limits(sess: &Session, krate: &ast::Crate) {
     update_limit(sess, krate, &sess.recursion_limit, sym::recursion_limit, 128);
     update_limit(sess, krate, &sess.type_length_limit, sym::type_length_limit, 1048576);
     update_limit(sess, krate, &sess.const_eval_limit, sym::const_eval_limit, 1_000_000);
+    update_limit(
+        sess,
+        krate,
+        &sess.huge_function_body_limit,
+        sym::huge_function_body_limit,
+        10_000,
+    );
+    let mir_opt_level = sess.opts.debugging_opts.mir_opt_level;
+    update_limit(sess, krate, &sess.mir_opt_level, sym::mir_opt_level, mir_opt_level);
 }
 
 fn update_limit(
@@ -11,6 +11,7 @@ use backtrace::Backtrace;
 use rustc_errors::{struct_span_err, DiagnosticBuilder};
 use rustc_hir as hir;
 use rustc_macros::HashStable;
+use rustc_session::config::ConstEvalBacktrace;
 use rustc_span::{Pos, Span};
 use rustc_target::spec::abi::Abi;
 use std::{any::Any, env, fmt};
@@ -48,22 +49,33 @@ pub struct ConstEvalErr<'tcx> {
     pub span: Span,
     pub error: crate::mir::interpret::InterpError<'tcx>,
     pub stacktrace: Vec<FrameInfo<'tcx>>,
+    /// The history of the allocation the error is about, if it was recorded.
+    pub alloc_history: Vec<AllocEvent>,
 }
 
 #[derive(Debug)]
 pub struct FrameInfo<'tcx> {
     /// This span is in the caller.
     pub call_site: Span,
+    /// The span of the statement or terminator this frame was evaluating.
+    pub span: Span,
     pub instance: ty::Instance<'tcx>,
     pub lint_root: Option<hir::HirId>,
 }
 
+impl<'tcx> FrameInfo<'tcx> {
+    /// Whether the function of this frame is a closure.
+    fn is_closure(&self) -> bool {
+        ty::tls::with(|tcx| {
+            tcx.def_key(self.instance.def_id()).disambiguated_data.data == DefPathData::ClosureExpr
+        })
+    }
+}
+
 impl<'tcx> fmt::Display for FrameInfo<'tcx> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         ty::tls::with(|tcx| {
-            if tcx.def_key(self.instance.def_id()).disambiguated_data.data
-                == DefPathData::ClosureExpr
-            {
+            if self.is_closure() {
                 write!(f, "inside call to closure")?;
             } else {
                 write!(f, "inside call to `{}`", self.instance)?;
@@ -77,6 +89,34 @@ impl<'tcx> fmt::Display for FrameInfo<'tcx> {
     }
 }
 
+/// What happened to an allocation in an `AllocEvent`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AllocEventKind {
+    Created,
+    Written,
+    Deallocated,
+}
+
+impl fmt::Display for AllocEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllocEventKind::Created => write!(f, "created"),
+            AllocEventKind::Written => write!(f, "written to"),
+            AllocEventKind::Deallocated => write!(f, "deallocated"),
+        }
+    }
+}
+
+/// An entry of the history of an allocation, as recorded by the interpreter with
+/// `-Z track-alloc-history`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AllocEvent {
+    pub kind: AllocEventKind,
+    /// The span of the statement that caused the event, followed by the call sites of the
+    /// functions it is in, innermost first.
+    pub spans: Vec<Span>,
+}
+
 impl<'tcx> ConstEvalErr<'tcx> {
     pub fn struct_error(
         &self,
@@ -172,8 +212,20 @@ impl<'tcx> ConstEvalErr<'tcx> {
             // is sometimes empty because we create "fake" eval contexts in CTFE to do work
             // on constant values.
             if !self.stacktrace.is_empty() {
-                for frame_info in &self.stacktrace[..self.stacktrace.len() - 1] {
-                    err.span_label(frame_info.call_site, frame_info.to_string());
+                let frames = &self.stacktrace[..self.stacktrace.len() - 1];
+                match tcx.sess.opts.debugging_opts.const_eval_backtrace {
+                    Some(backtrace) => note_backtrace(&mut err, frames, backtrace),
+                    None => {
+                        for frame_info in frames {
+                            err.span_label(frame_info.call_site, frame_info.to_string());
+                        }
+                    }
+                }
+            }
+            // Explain how the allocation got into its state, innermost frame only.
+            for event in &self.alloc_history {
+                if let Some(&span) = event.spans.first() {
+                    err.span_note(span, &format!("the allocation was {} here", event.kind));
                 }
             }
             // Let the caller finish the job.
@@ -211,6 +263,35 @@ impl<'tcx> ConstEvalErr<'tcx> {
     }
 }
 
+/// Adds a note for each of the `frames` of a failed evaluation, innermost first, pointing at
+/// what the frame was evaluating. With `ConstEvalBacktrace::Short`, the runs of frames of
+/// functions from other crates are elided.
+fn note_backtrace(
+    err: &mut DiagnosticBuilder<'_>,
+    frames: &[FrameInfo<'_>],
+    backtrace: ConstEvalBacktrace,
+) {
+    let mut elided = 0;
+    for frame_info in frames {
+        if backtrace == ConstEvalBacktrace::Short && !frame_info.instance.def_id().is_local() {
+            elided += 1;
+            continue;
+        }
+        if elided > 0 {
+            err.note(&format!("{} frames of functions from other crates elided", elided));
+            elided = 0;
+        }
+        if frame_info.is_closure() {
+            err.span_note(frame_info.span, "inside closure");
+        } else {
+            err.span_note(frame_info.span, &format!("inside `{}`", frame_info.instance));
+        }
+    }
+    if elided > 0 {
+        err.note(&format!("{} frames of functions from other crates elided", elided));
+    }
+}
+
 pub fn struct_error<'tcx>(tcx: TyCtxtAt<'tcx>, msg: &str) -> DiagnosticBuilder<'tcx> {
     struct_span_err!(tcx.sess, tcx.span, E0080, "{}", msg)
 }
@@ -27,7 +27,7 @@ use rustc_index::bit_set::BitMatrix;
 use rustc_index::vec::{Idx, IndexVec};
 use rustc_macros::HashStable;
 use rustc_serialize::{Decodable, Encodable};
-use rustc_span::symbol::Symbol;
+use rustc_span::symbol::{sym, Symbol};
 use rustc_span::{Span, DUMMY_SP};
 use std::borrow::Cow;
 use std::fmt::{self, Debug, Display, Formatter, Write};
@@ -45,6 +45,7 @@ pub mod mono;
 mod query;
 pub mod tcx;
 pub mod traversal;
+pub mod type_intrinsics;
 pub mod visit;
 
 /// Types for locals
@@ -66,7 +67,9 @@ impl<'tcx> HasLocalDecls<'tcx> for Body<'tcx> {
     }
 }
 
-/// The various "big phases" that MIR goes through.
+/// The various "big phases" that MIR goes through. A body is in a phase once all the passes of
+/// that phase ran on it, and the passes of the next phase may rely on the guarantees of the phase,
+/// which `rustc_mir::transform::validate` checks in debug builds.
 ///
 /// Warning: ordering of variants is significant.
 #[derive(
@@ -85,7 +88,12 @@ pub enum MirPhase {
     Build = 0,
     Const = 1,
     Validated = 2,
-    Optimized = 3,
+    /// The statements only needed by the analyses (`FakeRead`, `AscribeUserType` and shallow
+    /// borrows) and the `FalseEdges` and `FalseUnwind` terminators are removed, and the drops are
+    /// elaborated, so there are no `DropAndReplace` terminators left.
+    DropsLowered = 3,
+    /// The optimizations ran. They are the only passes that may add `Assume` statements.
+    Optimized = 4,
 }
 
 impl MirPhase {
@@ -173,6 +181,10 @@ pub struct Body<'tcx> {
     /// implementation without the flag hid this situation silently.
     /// FIXME(oli-obk): rewrite the promoted during promotion to eliminate the cell components.
     pub ignore_interior_mut_in_const_validation: bool,
+
+    /// The headers of the loops that never terminate and have no side effects. Codegen inserts a
+    /// side effect in them, so that LLVM doesn't assume they terminate and remove them.
+    pub side_effect_free_loops: Vec<BasicBlock>,
 }
 
 impl<'tcx> Body<'tcx> {
@@ -211,6 +223,7 @@ impl<'tcx> Body<'tcx> {
             span,
             ignore_interior_mut_in_const_validation: false,
             control_flow_destroyed,
+            side_effect_free_loops: Vec::new(),
         }
     }
 
@@ -236,6 +249,7 @@ impl<'tcx> Body<'tcx> {
             generator_kind: None,
             var_debug_info: Vec::new(),
             ignore_interior_mut_in_const_validation: false,
+            side_effect_free_loops: Vec::new(),
         }
     }
 
@@ -365,6 +379,47 @@ impl<'tcx> Body<'tcx> {
         true
     }
 
+    /// Returns a scope nested in `scope`, which attributes the statements using it to the MIR
+    /// pass `pass`. Passes use this for statements they synthesize or rewrite, so that they can
+    /// be told apart in MIR dumps and bug reports.
+    ///
+    /// The new scope has the same span and crate-local data as `scope`, and is reused by later
+    /// calls for the same scope and pass.
+    pub fn source_scope_for_pass(&mut self, scope: SourceScope, pass: &str) -> SourceScope {
+        let pass = Symbol::intern(pass);
+        if self.source_scopes[scope].synthesized_by == Some(pass) {
+            return scope;
+        }
+        let existing = self.source_scopes.iter_enumerated().find(|(_, data)| {
+            data.parent_scope == Some(scope) && data.synthesized_by == Some(pass)
+        });
+        if let Some((existing, _)) = existing {
+            return existing;
+        }
+
+        let parent = &self.source_scopes[scope];
+        let data = SourceScopeData {
+            span: parent.span,
+            parent_scope: Some(scope),
+            local_data: parent.local_data.clone(),
+            synthesized_by: Some(pass),
+        };
+        self.source_scopes.push(data)
+    }
+
+    /// Returns the MIR passes that synthesized or rewrote the code in `scope`, innermost first.
+    pub fn source_scope_passes(&self, mut scope: SourceScope) -> Vec<Symbol> {
+        let mut passes = vec![];
+        loop {
+            let data = &self.source_scopes[scope];
+            passes.extend(data.synthesized_by);
+            match data.parent_scope {
+                Some(parent) => scope = parent,
+                None => return passes,
+            }
+        }
+    }
+
     /// Returns the return type; it always return first element from `local_decls` array.
     pub fn return_ty(&self) -> Ty<'tcx> {
         self.local_decls[RETURN_PLACE].ty
@@ -376,6 +431,36 @@ impl<'tcx> Body<'tcx> {
     }
 }
 
+/// The parts of a body needed to tell what its basic blocks are about, without the blocks
+/// themselves. The MIR of other crates is decoded one block at a time through it, see the
+/// `optimized_mir_outline` and `optimized_mir_block` queries.
+#[derive(Clone, Debug, HashStable)]
+pub struct BodyOutline<'tcx> {
+    pub span: Span,
+    pub yield_ty: Option<Ty<'tcx>>,
+    pub local_decls: LocalDecls<'tcx>,
+    pub arg_count: usize,
+    pub block_count: usize,
+}
+
+impl<'tcx> BodyOutline<'tcx> {
+    pub fn new(body: &Body<'tcx>) -> Self {
+        BodyOutline {
+            span: body.span,
+            yield_ty: body.yield_ty,
+            local_decls: body.local_decls.clone(),
+            arg_count: body.arg_count,
+            block_count: body.basic_blocks().len(),
+        }
+    }
+}
+
+impl<'tcx> HasLocalDecls<'tcx> for BodyOutline<'tcx> {
+    fn local_decls(&self) -> &LocalDecls<'tcx> {
+        &self.local_decls
+    }
+}
+
 #[derive(Copy, Clone, Debug, RustcEncodable, RustcDecodable, HashStable)]
 pub enum Safety {
     Safe,
@@ -948,9 +1033,41 @@ pub struct VarDebugInfo<'tcx> {
     pub source_info: SourceInfo,
 
     /// Where the data for this user variable is to be found.
+    pub value: VarDebugInfoContents<'tcx>,
+}
+
+/// The value of a user variable, as described to debuginfo.
+///
+/// MIR building always describes variables by their place; optimizations that remove or merge
+/// locals can instead rewrite the description, e.g. into a projection of the local they merged
+/// the variable into, or into the constant they propagated into all its uses.
+#[derive(Clone, PartialEq, RustcEncodable, RustcDecodable, HashStable, TypeFoldable)]
+pub enum VarDebugInfoContents<'tcx> {
+    /// The data of the variable is found in this place.
     /// NOTE(eddyb) There's an unenforced invariant that this `Place` is
     /// based on a `Local`, not a `Static`, and contains no indexing.
-    pub place: Place<'tcx>,
+    Place(Place<'tcx>),
+    /// The variable always has this value.
+    Const(Constant<'tcx>),
+}
+
+impl<'tcx> VarDebugInfoContents<'tcx> {
+    /// Returns the place the variable is found in, if it is described by one.
+    pub fn place(&self) -> Option<&Place<'tcx>> {
+        match self {
+            VarDebugInfoContents::Place(place) => Some(place),
+            VarDebugInfoContents::Const(_) => None,
+        }
+    }
+}
+
+impl<'tcx> Debug for VarDebugInfoContents<'tcx> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VarDebugInfoContents::Place(place) => write!(fmt, "{:?}", place),
+            VarDebugInfoContents::Const(constant) => write!(fmt, "{}", constant),
+        }
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -1032,6 +1149,11 @@ pub enum TerminatorKind<'tcx> {
         // However we’ve decided to keep this as-is until we figure a case
         // where some other approach seems to be strictly better than other.
         targets: Vec<BasicBlock>,
+
+        /// How likely each of the `targets` is to be taken, relative to the others, if known.
+        /// Passed on to codegen as branch weights. Passes changing the number or the order
+        /// of the `targets` have to update the weights or drop them.
+        target_weights: Option<Vec<u32>>,
     },
 
     /// Indicates that the landing pad is finished and unwinding should
@@ -1204,6 +1326,7 @@ impl<'tcx> TerminatorKind<'tcx> {
             switch_ty: tcx.types.bool,
             values: From::from(BOOL_SWITCH_FALSE),
             targets: vec![f, t],
+            target_weights: None,
         }
     }
 
@@ -1329,6 +1452,21 @@ impl<'tcx> BasicBlockData<'tcx> {
         self.terminator.as_mut().expect("invalid terminator state")
     }
 
+    /// Whether the statements may not return, which only `asm!` with side effects can do.
+    pub fn statements_may_diverge(&self) -> bool {
+        self.statements.iter().any(|statement| match &statement.kind {
+            StatementKind::InlineAsm(asm) => asm.has_side_effects(),
+            _ => false,
+        })
+    }
+
+    /// Whether the block is an `unreachable` terminator alone, which is what `NormalizeUnreachable`
+    /// leaves of the blocks that are undefined behavior to enter. The blocks with statements before
+    /// their `unreachable` are treated as reachable, as the statements may diverge.
+    pub fn is_empty_unreachable(&self) -> bool {
+        self.statements.is_empty() && self.terminator().kind == TerminatorKind::Unreachable
+    }
+
     pub fn retain_statements<F>(&mut self, mut f: F)
     where
         F: FnMut(&mut Statement<'_>) -> bool,
@@ -1626,6 +1764,11 @@ pub enum StatementKind<'tcx> {
     /// - `Bivariant` -- no effect
     AscribeUserType(Box<(Place<'tcx>, UserTypeProjection)>, ty::Variance),
 
+    /// Denotes a fact the program guarantees: the `bool` operand is `true`, and the behavior is
+    /// undefined otherwise. This is lowered from calls to the `assume` intrinsic so that the
+    /// optimizations can rely on it, and codegen emits an `llvm.assume` for it.
+    Assume(Box<Operand<'tcx>>),
+
     /// No-op. Useful for deleting instructions without affecting statement indices.
     Nop,
 }
@@ -1635,6 +1778,8 @@ pub enum StatementKind<'tcx> {
 pub enum RetagKind {
     /// The initial retag when entering a function.
     FnEntry,
+    /// Retag of the return value of a call, right after the call returns.
+    FnReturn,
     /// Retag preparing for a two-phase borrow.
     TwoPhase,
     /// Retagging raw pointers.
@@ -1651,7 +1796,11 @@ pub enum FakeReadCause {
     ///
     /// This should ensure that you cannot change the variant for an enum while
     /// you are in the midst of matching on it.
-    ForMatchGuard,
+    ///
+    /// The payload is the index of the arm of the guard, used by diagnostics,
+    /// and why the input is borrowed. The index is a `u16` (saturating) to keep
+    /// `StatementKind` small.
+    ForMatchGuard(u16, MatchedPlace),
 
     /// `let x: !; match x {}` doesn't generate any read of x so we need to
     /// generate a read of x to check that it is initialized and safe.
@@ -1659,8 +1808,9 @@ pub enum FakeReadCause {
 
     /// A fake read of the RefWithinGuard version of a bind-by-value variable
     /// in a match guard to ensure that it's value hasn't change by the time
-    /// we create the OutsideGuard version.
-    ForGuardBinding,
+    /// we create the OutsideGuard version. The payload is the index of the arm
+    /// of the guard, as for `ForMatchGuard`.
+    ForGuardBinding(u16),
 
     /// Officially, the semantics of
     ///
@@ -1686,6 +1836,17 @@ pub enum FakeReadCause {
     ForIndex,
 }
 
+/// Why a place of the scrutinee of a `match` is borrowed during its guards, see
+/// `FakeReadCause::ForMatchGuard`.
+#[derive(Copy, Clone, RustcEncodable, RustcDecodable, Debug, HashStable)]
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchedPlace {
+    /// The patterns test the value of the place.
+    Tested,
+    /// The patterns test or bind a place behind a reference or a box in the place.
+    Dereferenced,
+}
+
 #[derive(Clone, Debug, PartialEq, RustcEncodable, RustcDecodable, HashStable, TypeFoldable)]
 pub struct InlineAsm<'tcx> {
     pub asm: hir::InlineAsmInner,
@@ -1693,6 +1854,60 @@ pub struct InlineAsm<'tcx> {
     pub inputs: Box<[(Span, Operand<'tcx>)]>,
 }
 
+/// What the asm can do, from its operands and clobbers. Passes should use these instead of
+/// treating every asm statement as a barrier.
+impl InlineAsm<'_> {
+    /// Whether the asm clobbers memory, i.e. may read or write any memory.
+    pub fn clobbers_memory(&self) -> bool {
+        self.asm.clobbers.iter().any(|&clobber| clobber == sym::memory)
+    }
+
+    /// Whether the asm may read or write memory. Besides with a memory clobber, it does so
+    /// through the pointers passed as indirect outputs, and as memory inputs (`*` and `m`
+    /// constraints).
+    pub fn accesses_memory(&self) -> bool {
+        self.clobbers_memory()
+            || self.asm.outputs.iter().any(|output| output.is_indirect)
+            || self.asm.inputs.iter().any(|constraint| {
+                let constraint = constraint.as_str();
+                constraint.contains('*') || constraint.contains('m')
+            })
+    }
+
+    /// Whether the asm does more than computing its outputs from its inputs, e.g. accessing
+    /// memory, or not returning at all. Asm without side effects is removed by codegen if its
+    /// outputs are unused, so it cannot be relied on to stop the program.
+    pub fn has_side_effects(&self) -> bool {
+        self.asm.volatile || self.accesses_memory()
+    }
+
+    /// Whether the asm overwrites `local`, as a whole or in part. Indirect outputs, and outputs
+    /// behind a dereference, write to memory instead.
+    pub fn writes_local(&self, local: Local) -> bool {
+        self.outputs.iter().zip(&self.asm.outputs).any(|(place, output)| {
+            !output.is_indirect && !place.is_indirect() && place.local == local
+        })
+    }
+
+    /// Whether the asm may read the value of `local`: it is used by an input, or by an output
+    /// that is read as well (`+` constraint), or that is indirect. The local an output writes to
+    /// directly is not read, but the indices and pointers used to get to it are.
+    pub fn reads_local(&self, local: Local) -> bool {
+        let indexes_with_local = |place: &Place<'_>| {
+            place.projection.iter().any(|elem| *elem == ProjectionElem::Index(local))
+        };
+        let mentions_local = |place: &Place<'_>| place.local == local || indexes_with_local(place);
+        self.inputs.iter().any(|(_, input)| input.place().map_or(false, mentions_local))
+            || self.outputs.iter().zip(&self.asm.outputs).any(|(place, output)| {
+                if output.is_indirect || output.is_rw || place.is_indirect() {
+                    mentions_local(place)
+                } else {
+                    indexes_with_local(place)
+                }
+            })
+    }
+}
+
 impl Debug for Statement<'_> {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         use self::StatementKind::*;
@@ -1704,6 +1919,7 @@ impl Debug for Statement<'_> {
                 "Retag({}{:?})",
                 match kind {
                     RetagKind::FnEntry => "[fn entry] ",
+                    RetagKind::FnReturn => "[fn return] ",
                     RetagKind::TwoPhase => "[2phase] ",
                     RetagKind::Raw => "[raw] ",
                     RetagKind::Default => "",
@@ -1721,6 +1937,7 @@ impl Debug for Statement<'_> {
             AscribeUserType(box (ref place, ref c_ty), ref variance) => {
                 write!(fmt, "AscribeUserType({:?}, {:?}, {:?})", place, variance, c_ty)
             }
+            Assume(ref op) => write!(fmt, "assume({:?})", op),
             Nop => write!(fmt, "nop"),
         }
     }
@@ -1977,6 +2194,10 @@ pub struct SourceScopeData {
     /// Crate-local information for this source scope, that can't (and
     /// needn't) be tracked across crates.
     pub local_data: ClearCrossCrate<SourceScopeLocalData>,
+
+    /// The MIR pass that created this scope for the code it synthesized or rewrote, or `None`
+    /// for the scopes built from the source. See `Body::source_scope_for_pass`.
+    pub synthesized_by: Option<Symbol>,
 }
 
 #[derive(Clone, Debug, RustcEncodable, RustcDecodable, HashStable)]
@@ -2367,6 +2588,18 @@ impl Constant<'tcx> {
     }
 }
 
+/// An unevaluated constant used by a body, which has to evaluate successfully for every
+/// instantiation of that body that gets codegened. Optimizations may remove the use itself (e.g.
+/// `let _ = T::CONST;`), so these are collected before optimizing, see the
+/// `mir_required_consts` query.
+#[derive(Copy, Clone, Debug, RustcEncodable, RustcDecodable, HashStable, TypeFoldable)]
+pub struct RequiredConst<'tcx> {
+    /// The span of the use of the constant.
+    pub span: Span,
+    /// The constant itself, still generic over the parameters of the body using it.
+    pub literal: &'tcx ty::Const<'tcx>,
+}
+
 /// A collection of projections into user types.
 ///
 /// They are projections because a binding can occur a part of a
@@ -2677,6 +2910,7 @@ impl Location {
 */
 
 CloneTypeFoldableAndLiftImpls! {
+    BasicBlock,
     BlockTailInfo,
     MirPhase,
     SourceInfo,
@@ -2694,12 +2928,15 @@ impl<'tcx> TypeFoldable<'tcx> for Terminator<'tcx> {
 
         let kind = match self.kind {
             Goto { target } => Goto { target },
-            SwitchInt { ref discr, switch_ty, ref values, ref targets } => SwitchInt {
-                discr: discr.fold_with(folder),
-                switch_ty: switch_ty.fold_with(folder),
-                values: values.clone(),
-                targets: targets.clone(),
-            },
+            SwitchInt { ref discr, switch_ty, ref values, ref targets, ref target_weights } => {
+                SwitchInt {
+                    discr: discr.fold_with(folder),
+                    switch_ty: switch_ty.fold_with(folder),
+                    values: values.clone(),
+                    targets: targets.clone(),
+                    target_weights: target_weights.clone(),
+                }
+            }
             Drop { ref location, target, unwind } => {
                 Drop { location: location.fold_with(folder), target, unwind }
             }
@@ -5,13 +5,13 @@ use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::sync::Lrc;
 use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
-use rustc_index::bit_set::BitMatrix;
+use rustc_index::bit_set::{BitMatrix, BitSet};
 use rustc_index::vec::IndexVec;
 use rustc_span::{Span, Symbol};
 use rustc_target::abi::VariantIdx;
 use smallvec::SmallVec;
 
-use super::{Field, SourceInfo};
+use super::{Field, Local, SourceInfo};
 
 #[derive(Copy, Clone, PartialEq, RustcEncodable, RustcDecodable, HashStable)]
 pub enum UnsafetyViolationKind {
@@ -69,6 +69,10 @@ pub struct BorrowCheckResult<'tcx> {
     pub concrete_opaque_types: FxHashMap<DefId, ty::ResolvedOpaqueTy<'tcx>>,
     pub closure_requirements: Option<ClosureRegionRequirements<'tcx>>,
     pub used_mut_upvars: SmallVec<[Field; 8]>,
+    /// The user variables and arguments declared `mut` that are never mutated, indexed by the
+    /// locals of `mir_validated`. These are the candidates for the `unused_mut` lint, which
+    /// skips the ones starting with an underscore.
+    pub unused_mut_locals: BitSet<Local>,
 }
 
 /// The result of the `mir_const_qualif` query.
@@ -82,6 +86,34 @@ pub struct ConstQualifs {
     pub needs_drop: bool,
 }
 
+/// Identifies one of the `Qualif`s, and the field of `ConstQualifs` holding its result.
+///
+/// Adding a qualif only requires a new variant here and a new field in `ConstQualifs`, besides
+/// implementing `Qualif` for it and registering it in `for_each_qualif` in
+/// `librustc_mir/transform/check_consts/qualifs.rs`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QualifKind {
+    HasMutInterior,
+    NeedsDrop,
+}
+
+impl ConstQualifs {
+    pub fn get(&self, kind: QualifKind) -> bool {
+        match kind {
+            QualifKind::HasMutInterior => self.has_mut_interior,
+            QualifKind::NeedsDrop => self.needs_drop,
+        }
+    }
+
+    pub fn set(&mut self, kind: QualifKind, value: bool) {
+        let field = match kind {
+            QualifKind::HasMutInterior => &mut self.has_mut_interior,
+            QualifKind::NeedsDrop => &mut self.needs_drop,
+        };
+        *field = value;
+    }
+}
+
 /// After we borrow check a closure, we are left with various
 /// requirements that we have inferred between the free regions that
 /// appear in the closure's signature or on its field types. These
@@ -227,3 +259,98 @@ pub struct DestructuredConst<'tcx> {
     pub variant: VariantIdx,
     pub fields: &'tcx [&'tcx ty::Const<'tcx>],
 }
+
+/// What a call in the MIR of a local body may call, see the `mir_callees` query.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable, HashStable)]
+pub enum Callee {
+    /// A function, method, closure or generator of this crate, which has MIR and is a node of
+    /// the `CallGraph`.
+    Local(DefId),
+
+    /// An item without MIR in this crate: a function of another crate, an intrinsic, or a shim
+    /// like drop glue. The local functions and closures a generic function of another crate is
+    /// instantiated with are recorded as `Local` callees of the same call site, as it may call
+    /// them back.
+    Extern(DefId),
+
+    /// A call through a function pointer or a trait object, or a call of a trait method that
+    /// can't be resolved in the generic body. Anything may be called.
+    Unknown,
+}
+
+/// A call terminator of the MIR of a local body.
+#[derive(Copy, Clone, Debug, RustcEncodable, RustcDecodable, HashStable)]
+pub struct CallSite {
+    pub callee: Callee,
+    pub span: Span,
+    /// Whether the body can't return without calling the `Callee::Local` callee, here or at
+    /// another call site. Always `false` in promoteds, which are evaluated at compile-time.
+    pub unconditional: bool,
+}
+
+rustc_index::newtype_index! {
+    pub struct CallGraphScc {
+        derive [HashStable]
+        DEBUG_FORMAT = "scc{}"
+    }
+}
+
+/// The strongly connected components of the graph of the `Callee::Local` calls between the
+/// bodies of the crate, see the `mir_callgraph` query.
+#[derive(Debug, HashStable)]
+pub struct CallGraph {
+    /// The component of each body with MIR.
+    scc_indices: FxHashMap<DefId, CallGraphScc>,
+
+    /// The bodies in each component. The components are numbered in postorder: a component
+    /// only calls into itself and components with smaller indices.
+    scc_members: IndexVec<CallGraphScc, Vec<DefId>>,
+
+    /// The components whose bodies call each other, i.e. with more than one body, or with a body
+    /// calling itself.
+    recursive_sccs: BitSet<CallGraphScc>,
+}
+
+impl CallGraph {
+    pub fn new(
+        scc_indices: FxHashMap<DefId, CallGraphScc>,
+        scc_members: IndexVec<CallGraphScc, Vec<DefId>>,
+        recursive_sccs: BitSet<CallGraphScc>,
+    ) -> Self {
+        CallGraph { scc_indices, scc_members, recursive_sccs }
+    }
+
+    /// Returns the component of `def_id`, if it is a local body with MIR.
+    pub fn scc(&self, def_id: DefId) -> Option<CallGraphScc> {
+        self.scc_indices.get(&def_id).copied()
+    }
+
+    /// Returns the components in postorder, callees before their callers.
+    pub fn sccs(&self) -> impl Iterator<Item = CallGraphScc> + '_ {
+        self.scc_members.indices()
+    }
+
+    pub fn scc_members(&self, scc: CallGraphScc) -> &[DefId] {
+        &self.scc_members[scc]
+    }
+
+    pub fn is_recursive_scc(&self, scc: CallGraphScc) -> bool {
+        self.recursive_sccs.contains(scc)
+    }
+
+    /// Returns whether `def_id` may call itself, directly or through other local bodies.
+    pub fn is_recursive(&self, def_id: DefId) -> bool {
+        self.scc(def_id).map_or(false, |scc| self.is_recursive_scc(scc))
+    }
+
+    /// Returns whether `caller` and `callee` may call each other, e.g. to check that inlining
+    /// `callee` into `caller` can't lead to inlining `caller` into itself.
+    pub fn in_same_cycle(&self, caller: DefId, callee: DefId) -> bool {
+        match (self.scc(caller), self.scc(callee)) {
+            (Some(caller_scc), Some(callee_scc)) => {
+                caller_scc == callee_scc && self.is_recursive_scc(caller_scc)
+            }
+            _ => false,
+        }
+    }
+}
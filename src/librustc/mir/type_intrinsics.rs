@@ -0,0 +1,65 @@
+//! The calls whose value only depends on the type they are instantiated with: the `size_of`,
+//! `min_align_of`, `pref_align_of` and `needs_drop` intrinsics, and their `core::mem` wrappers.
+//!
+//! Const propagation replaces the calls instantiated with a concrete type by their value, and
+//! codegen does the same for the ones only known after monomorphization, so that branches like
+//! `if size_of::<T>() == 0` are folded even in debug builds.
+
+use crate::ty::{self, Ty, TyCtxt, TypeFoldable};
+use rustc_hir::def_id::DefId;
+use rustc_span::symbol::sym;
+use rustc_target::spec::abi::Abi;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TypeIntrinsic {
+    SizeOf,
+    MinAlignOf,
+    PrefAlignOf,
+    NeedsDrop,
+}
+
+impl TypeIntrinsic {
+    /// Returns the type intrinsic that the function `def_id` computes, if any. `def_id` may be
+    /// the one of any instance, including closures and generators, which have no `fn_sig`.
+    pub fn of_fn(tcx: TyCtxt<'_>, def_id: DefId) -> Option<TypeIntrinsic> {
+        if tcx.is_diagnostic_item(sym::mem_size_of, def_id) {
+            Some(TypeIntrinsic::SizeOf)
+        } else if tcx.is_diagnostic_item(sym::mem_align_of, def_id) {
+            Some(TypeIntrinsic::MinAlignOf)
+        } else if tcx.is_diagnostic_item(sym::mem_needs_drop, def_id) {
+            Some(TypeIntrinsic::NeedsDrop)
+        } else if tcx.is_closure(def_id) || tcx.fn_sig(def_id).abi() != Abi::RustIntrinsic {
+            None
+        } else {
+            match tcx.item_name(def_id) {
+                sym::size_of => Some(TypeIntrinsic::SizeOf),
+                sym::min_align_of => Some(TypeIntrinsic::MinAlignOf),
+                sym::pref_align_of => Some(TypeIntrinsic::PrefAlignOf),
+                sym::needs_drop => Some(TypeIntrinsic::NeedsDrop),
+                _ => None,
+            }
+        }
+    }
+
+    /// Computes the value of the intrinsic for `ty`. Returns `None` if `ty` still depends on
+    /// generic parameters, or if its layout can't be computed.
+    pub fn eval<'tcx>(self, tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<&'tcx ty::Const<'tcx>> {
+        let ty = tcx.erase_regions(&ty);
+        if ty.needs_subst() {
+            return None;
+        }
+        // Nothing about `ty` is left to the caller, so its layout is the one codegen computes.
+        let param_env = ty::ParamEnv::reveal_all();
+        if self == TypeIntrinsic::NeedsDrop {
+            return Some(ty::Const::from_bool(tcx, ty.needs_drop(tcx, param_env)));
+        }
+        let layout = tcx.layout_of(param_env.and(ty)).ok()?;
+        let n = match self {
+            TypeIntrinsic::SizeOf => layout.size.bytes(),
+            TypeIntrinsic::MinAlignOf => layout.align.abi.bytes(),
+            TypeIntrinsic::PrefAlignOf => layout.align.pref.bytes(),
+            TypeIntrinsic::NeedsDrop => bug!(),
+        };
+        Some(ty::Const::from_usize(tcx, n))
+    }
+}
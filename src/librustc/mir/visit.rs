@@ -334,6 +334,7 @@ macro_rules! make_mir_visitor {
                     span,
                     parent_scope,
                     local_data: _,
+                    synthesized_by: _,
                 } = scope_data;
 
                 self.visit_span(span);
@@ -407,6 +408,9 @@ macro_rules! make_mir_visitor {
                     ) => {
                         self.visit_ascribe_user_ty(place, variance, user_ty, location);
                     }
+                    StatementKind::Assume(op) => {
+                        self.visit_operand(op, location);
+                    }
                     StatementKind::Nop => {}
                 }
             }
@@ -450,7 +454,8 @@ macro_rules! make_mir_visitor {
                         discr,
                         switch_ty,
                         values: _,
-                        targets: _
+                        targets: _,
+                        target_weights: _
                     } => {
                         self.visit_operand(discr, source_location);
                         self.visit_ty(switch_ty, TyContext::Location(source_location));
@@ -745,16 +750,23 @@ macro_rules! make_mir_visitor {
                 let VarDebugInfo {
                     name: _,
                     source_info,
-                    place,
+                    value,
                 } = var_debug_info;
 
                 self.visit_source_info(source_info);
                 let location = START_BLOCK.start_location();
-                self.visit_place(
-                    place,
-                    PlaceContext::NonUse(NonUseContext::VarDebugInfo),
-                    location,
-                );
+                match value {
+                    VarDebugInfoContents::Place(place) => {
+                        self.visit_place(
+                            place,
+                            PlaceContext::NonUse(NonUseContext::VarDebugInfo),
+                            location,
+                        );
+                    }
+                    VarDebugInfoContents::Const(constant) => {
+                        self.visit_constant(constant, location);
+                    }
+                }
             }
 
             fn super_source_scope(&mut self,
@@ -149,6 +149,27 @@ rustc_queries! {
             no_hash
         }
 
+        /// The unevaluated constants used by the MIR of a local item, collected before
+        /// optimizations can remove their uses. The monomorphization collector evaluates them
+        /// for each instance, so that erroneous constants are reported even if unused.
+        query mir_required_consts(key: DefId) -> &'tcx [mir::RequiredConst<'tcx>] {
+            desc { |tcx| "collecting the required constants of `{}`", tcx.def_path_str(key) }
+        }
+
+        /// The calls in the MIR of a local body and its promoteds, collected before optimizations
+        /// inline or devirtualize them. Trait methods are resolved in the generic body.
+        query mir_callees(key: DefId) -> &'tcx [mir::CallSite] {
+            desc { |tcx| "collecting the calls of `{}`", tcx.def_path_str(key) }
+        }
+
+        /// The graph of the calls between the local bodies with MIR, from `mir_callees`, with its
+        /// strongly connected components. As `mir_callees` uses the MIR before optimizations,
+        /// this can be used by the optimizations of function bodies, although not during
+        /// type-checking, where evaluating constants builds their MIR.
+        query mir_callgraph(_: CrateNum) -> &'tcx mir::CallGraph {
+            desc { "building the call graph of the crate" }
+        }
+
         /// MIR after our optimization passes have run. This is MIR that is ready
         /// for codegen. This is also the only query that can fetch non-local MIR, at present.
         query optimized_mir(key: DefId) -> &'tcx mir::BodyAndCache<'tcx> {
@@ -164,6 +185,21 @@ rustc_queries! {
             }
         }
 
+        /// The basic block `key.1` of `optimized_mir(key.0)`. The blocks of the MIR of other
+        /// crates are decoded one at a time, so this is much cheaper than `optimized_mir` when
+        /// only some of the blocks are needed, like when estimating the cost of inlining a callee.
+        query optimized_mir_block(
+            key: (DefId, mir::BasicBlock)
+        ) -> &'tcx mir::BasicBlockData<'tcx> {
+            no_force
+            desc { |tcx| "decoding `{:?}` of the MIR of `{}`", key.1, tcx.def_path_str(key.0) }
+        }
+
+        /// `optimized_mir(key)`, without its basic blocks, see `optimized_mir_block`.
+        query optimized_mir_outline(key: DefId) -> &'tcx mir::BodyOutline<'tcx> {
+            desc { |tcx| "decoding the outline of the MIR of `{}`", tcx.def_path_str(key) }
+        }
+
         query promoted_mir(key: DefId) -> &'tcx IndexVec<mir::Promoted, mir::BodyAndCache<'tcx>> {
             cache_on_disk_if { key.is_local() }
             load_cached(tcx, id) {
@@ -181,6 +217,13 @@ rustc_queries! {
                 })
             }
         }
+
+        /// A hash of `optimized_mir` and `promoted_mir`, without their spans, recorded in the
+        /// metadata with `-Z print-mir-hashes`. `None` for the bodies of other crates built
+        /// without it.
+        query optimized_mir_hash(key: DefId) -> Option<Fingerprint> {
+            desc { |tcx| "hashing the optimized MIR of `{}`", tcx.def_path_str(key) }
+        }
     }
 
     TypeChecking {
@@ -472,6 +515,13 @@ rustc_queries! {
                         || opt_result.map_or(false, |r| !r.concrete_opaque_types.is_empty()))
             }
         }
+
+        /// The `mut` variables and arguments of the function body that don't need to be mutable,
+        /// see `BorrowCheckResult::unused_mut_locals`. Meant for tools that suggest removing the
+        /// `mut` of bindings.
+        query unused_mut_locals(key: DefId) -> &'tcx BitSet<mir::Local> {
+            desc { |tcx| "finding the unneeded `mut`s of `{}`", tcx.def_path_str(key) }
+        }
     }
 
     TypeChecking {
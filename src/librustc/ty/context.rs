@@ -948,6 +948,10 @@ pub struct GlobalCtxt<'tcx> {
     /// we can upcast to `Any` for some additional type safety.
     pub lint_store: Lrc<dyn Any + sync::Sync + sync::Send>,
 
+    /// This only ever stores a `rustc_mir::transform::CustomMirPasses`, the MIR passes
+    /// registered by the driver.
+    pub mir_passes: Lrc<dyn Any + sync::Sync + sync::Send>,
+
     pub dep_graph: DepGraph,
 
     pub prof: SelfProfilerRef,
@@ -1112,6 +1116,7 @@ impl<'tcx> TyCtxt<'tcx> {
     pub fn create_global_ctxt(
         s: &'tcx Session,
         lint_store: Lrc<dyn Any + sync::Send + sync::Sync>,
+        mir_passes: Lrc<dyn Any + sync::Send + sync::Sync>,
         local_providers: ty::query::Providers<'tcx>,
         extern_providers: ty::query::Providers<'tcx>,
         arena: &'tcx WorkerLocal<Arena<'tcx>>,
@@ -1171,6 +1176,7 @@ impl<'tcx> TyCtxt<'tcx> {
         GlobalCtxt {
             sess: s,
             lint_store,
+            mir_passes,
             cstore,
             arena,
             interners,
@@ -1513,6 +1519,18 @@ impl<'tcx> TyCtxt<'tcx> {
         )
     }
 
+    /// Returns the `core::panic::Location` of `span`. This is the caller location of the
+    /// `#[track_caller]` functions called at `span` by a function that doesn't track its caller.
+    pub fn span_as_caller_location(self, span: Span) -> ConstValue<'tcx> {
+        let topmost = span.ctxt().outer_expn().expansion_cause().unwrap_or(span);
+        let caller = self.sess.source_map().lookup_char_pos(topmost.lo());
+        self.const_caller_location((
+            Symbol::intern(&caller.file.name.to_string()),
+            caller.line as u32,
+            caller.col_display as u32 + 1,
+        ))
+    }
+
     /// Returns a displayable description and article for the given `def_id` (e.g. `("a", "struct")`).
     pub fn article_and_description(&self, def_id: DefId) -> (&'static str, &'static str) {
         match self.def_key(def_id).disambiguated_data.data {
@@ -46,6 +46,7 @@ use rustc_hir as hir;
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::{CrateNum, DefId, DefIdMap, DefIdSet, DefIndex};
 use rustc_hir::{Crate, HirIdSet, ItemLocalId, TraitCandidate};
+use rustc_index::bit_set::BitSet;
 use rustc_index::vec::IndexVec;
 use rustc_target::spec::PanicStrategy;
 
@@ -61,7 +62,7 @@ use std::sync::Arc;
 #[macro_use]
 mod plumbing;
 use self::plumbing::*;
-pub use self::plumbing::{force_from_dep_node, CycleError};
+pub use self::plumbing::{force_from_dep_node, poisoned_query_count, CycleError};
 
 mod stats;
 pub use self::stats::print_stats;
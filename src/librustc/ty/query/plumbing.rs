@@ -24,9 +24,18 @@ use std::hash::{Hash, Hasher};
 use std::mem;
 use std::num::NonZeroU32;
 use std::ptr;
-#[cfg(debug_assertions)]
 use std::sync::atomic::{AtomicUsize, Ordering};
 
+/// The number of queries poisoned by a panic unwinding out of them.
+static POISONED_QUERIES: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the number of queries poisoned by a panic unwinding out of them so far. Code catching a
+/// panic compares it to the count from before, as it can't recover from a panic that went through
+/// a query: the poisoned query can't be run again.
+pub fn poisoned_query_count() -> usize {
+    POISONED_QUERIES.load(Ordering::SeqCst)
+}
+
 pub(crate) struct QueryStateShard<'tcx, D: QueryAccessors<'tcx> + ?Sized> {
     pub(super) cache: <<D as QueryAccessors<'tcx>>::Cache as QueryCache<D::Key, D::Value>>::Sharded,
     pub(super) active: FxHashMap<D::Key, QueryResult<'tcx>>,
@@ -265,6 +274,7 @@ impl<'tcx, Q: QueryDescription<'tcx>> Drop for JobOwner<'tcx, Q> {
             shard.active.insert(self.key.clone(), QueryResult::Poisoned);
             job
         };
+        POISONED_QUERIES.fetch_add(1, Ordering::SeqCst);
         // Also signal the completion of the job, so waiters
         // will continue execution.
         job.signal_complete();
@@ -175,6 +175,17 @@ impl BuilderMethods<'a, 'tcx> for Builder<'a, 'll, 'tcx> {
         }
     }
 
+    fn cond_br_with_weights(
+        &mut self,
+        cond: &'ll Value,
+        then_llbb: &'ll BasicBlock,
+        else_llbb: &'ll BasicBlock,
+        weights: (u32, u32),
+    ) {
+        let br = unsafe { llvm::LLVMBuildCondBr(self.llbuilder, cond, then_llbb, else_llbb) };
+        self.branch_weights_metadata(br, &[weights.0, weights.1]);
+    }
+
     fn switch(
         &mut self,
         v: &'ll Value,
@@ -189,6 +200,26 @@ impl BuilderMethods<'a, 'tcx> for Builder<'a, 'll, 'tcx> {
         }
     }
 
+    fn switch_with_weights(
+        &mut self,
+        v: &'ll Value,
+        else_llbb: &'ll BasicBlock,
+        else_weight: u32,
+        cases: impl ExactSizeIterator<Item = (u128, &'ll BasicBlock, u32)> + TrustedLen,
+    ) {
+        let switch =
+            unsafe { llvm::LLVMBuildSwitch(self.llbuilder, v, else_llbb, cases.len() as c_uint) };
+        // The weight of the default destination comes first.
+        let mut weights = Vec::with_capacity(cases.len() + 1);
+        weights.push(else_weight);
+        for (on_val, dest, weight) in cases {
+            let on_val = self.const_uint_big(self.val_ty(v), on_val);
+            unsafe { llvm::LLVMAddCase(switch, on_val, dest) }
+            weights.push(weight);
+        }
+        self.branch_weights_metadata(switch, &weights);
+    }
+
     fn invoke(
         &mut self,
         llfn: &'ll Value,
@@ -1056,6 +1087,23 @@ impl Builder<'a, 'll, 'tcx> {
         }
     }
 
+    fn branch_weights_metadata(&mut self, branch: &'ll Value, weights: &[u32]) {
+        let kind = "branch_weights";
+        unsafe {
+            let mut node = vec![llvm::LLVMMDStringInContext(
+                self.cx.llcx,
+                kind.as_ptr() as *const c_char,
+                kind.len() as c_uint,
+            )];
+            node.extend(weights.iter().map(|&weight| self.cx.const_u32(weight)));
+            llvm::LLVMSetMetadata(
+                branch,
+                llvm::MD_prof as c_uint,
+                llvm::LLVMMDNodeInContext(self.cx.llcx, node.as_ptr(), node.len() as c_uint),
+            );
+        }
+    }
+
     pub fn minnum(&mut self, lhs: &'ll Value, rhs: &'ll Value) -> &'ll Value {
         unsafe { llvm::LLVMRustBuildMinNum(self.llbuilder, lhs, rhs) }
     }
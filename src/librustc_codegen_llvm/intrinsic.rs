@@ -800,11 +800,15 @@ impl IntrinsicCallMethods<'tcx> for Builder<'a, 'll, 'tcx> {
 
     fn sideeffect(&mut self) {
         if self.tcx.sess.opts.debugging_opts.insert_sideeffect {
-            let fnname = self.get_intrinsic(&("llvm.sideeffect"));
-            self.call(fnname, &[], None);
+            self.force_sideeffect();
         }
     }
 
+    fn force_sideeffect(&mut self) {
+        let fnname = self.get_intrinsic(&("llvm.sideeffect"));
+        self.call(fnname, &[], None);
+    }
+
     fn va_start(&mut self, va_list: &'ll Value) -> &'ll Value {
         let intrinsic = self.cx().get_intrinsic("llvm.va_start");
         self.call(intrinsic, &[va_list], None)
@@ -11,6 +11,7 @@ use crate::MemFlags;
 
 use rustc::middle::lang_items;
 use rustc::mir;
+use rustc::mir::type_intrinsics::TypeIntrinsic;
 use rustc::mir::AssertKind;
 use rustc::ty::layout::{self, FnAbiExt, HasTyCtxt, LayoutOf};
 use rustc::ty::{self, Instance, Ty, TypeFoldable};
@@ -146,7 +147,8 @@ impl<'a, 'tcx> TerminatorCodegenHelper<'tcx> {
     }
 
     // Generate sideeffect intrinsic if jumping to any of the targets can form
-    // a loop.
+    // a loop. The loops without side effects always get one, so that LLVM
+    // doesn't assume they terminate.
     fn maybe_sideeffect<Bx: BuilderMethods<'a, 'tcx>>(
         &self,
         mir: mir::ReadOnlyBodyAndCache<'tcx, 'tcx>,
@@ -160,6 +162,11 @@ impl<'a, 'tcx> TerminatorCodegenHelper<'tcx> {
             }) {
                 bx.sideeffect();
             }
+        } else if targets.iter().any(|&target| {
+            mir.side_effect_free_loops.contains(&target)
+                && target.start_location().is_predecessor_of(self.bb.start_location(), mir)
+        }) {
+            bx.force_sideeffect();
         }
     }
 }
@@ -198,39 +205,79 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
         switch_ty: Ty<'tcx>,
         values: &Cow<'tcx, [u128]>,
         targets: &Vec<mir::BasicBlock>,
+        target_weights: Option<&[u32]>,
     ) {
+        if let Some(weights) = target_weights {
+            assert_eq!(weights.len(), targets.len(), "branch weights don't match the targets");
+        }
         let discr = self.codegen_operand(&mut bx, &discr);
+        // The discriminant may only be known after monomorphization, e.g. when it compares the
+        // size of a type parameter: then branch to the target taken.
+        if let Some(discr_value) = bx.const_to_opt_u128(discr.immediate(), false) {
+            let target = match values.iter().position(|&value| value == discr_value) {
+                Some(index) => targets[index],
+                None => *targets.last().unwrap(),
+            };
+            helper.maybe_sideeffect(self.mir, &mut bx, &[target]);
+            helper.funclet_br(self, &mut bx, target);
+            return;
+        }
         if targets.len() == 2 {
             // If there are two targets, emit br instead of switch
             let lltrue = helper.llblock(self, targets[0]);
             let llfalse = helper.llblock(self, targets[1]);
-            if switch_ty == bx.tcx().types.bool {
-                helper.maybe_sideeffect(self.mir, &mut bx, targets.as_slice());
+            // The condition, and the indices of the targets to branch to when it is true and
+            // when it is false.
+            let (cond, then_target, else_target) = if switch_ty == bx.tcx().types.bool {
                 // Don't generate trivial icmps when switching on bool
                 if let [0] = values[..] {
-                    bx.cond_br(discr.immediate(), llfalse, lltrue);
+                    (discr.immediate(), 1, 0)
                 } else {
                     assert_eq!(&values[..], &[1]);
-                    bx.cond_br(discr.immediate(), lltrue, llfalse);
+                    (discr.immediate(), 0, 1)
                 }
             } else {
                 let switch_llty = bx.immediate_backend_type(bx.layout_of(switch_ty));
                 let llval = bx.const_uint_big(switch_llty, values[0]);
-                let cmp = bx.icmp(IntPredicate::IntEQ, discr.immediate(), llval);
-                helper.maybe_sideeffect(self.mir, &mut bx, targets.as_slice());
-                bx.cond_br(cmp, lltrue, llfalse);
+                (bx.icmp(IntPredicate::IntEQ, discr.immediate(), llval), 0, 1)
+            };
+            helper.maybe_sideeffect(self.mir, &mut bx, targets.as_slice());
+            let llblocks = [lltrue, llfalse];
+            let (then_llbb, else_llbb) = (llblocks[then_target], llblocks[else_target]);
+            match target_weights {
+                Some(weights) => bx.cond_br_with_weights(
+                    cond,
+                    then_llbb,
+                    else_llbb,
+                    (weights[then_target], weights[else_target]),
+                ),
+                None => bx.cond_br(cond, then_llbb, else_llbb),
             }
         } else {
             helper.maybe_sideeffect(self.mir, &mut bx, targets.as_slice());
             let (otherwise, targets) = targets.split_last().unwrap();
-            bx.switch(
-                discr.immediate(),
-                helper.llblock(self, *otherwise),
-                values
-                    .iter()
-                    .zip(targets)
-                    .map(|(&value, target)| (value, helper.llblock(self, *target))),
-            );
+            let llotherwise = helper.llblock(self, *otherwise);
+            match target_weights {
+                Some(weights) => {
+                    let (otherwise_weight, weights) = weights.split_last().unwrap();
+                    bx.switch_with_weights(
+                        discr.immediate(),
+                        llotherwise,
+                        *otherwise_weight,
+                        values.iter().zip(targets).zip(weights).map(|((&value, target), &weight)| {
+                            (value, helper.llblock(self, *target), weight)
+                        }),
+                    )
+                }
+                None => bx.switch(
+                    discr.immediate(),
+                    llotherwise,
+                    values
+                        .iter()
+                        .zip(targets)
+                        .map(|(&value, target)| (value, helper.llblock(self, *target))),
+                ),
+            }
         }
     }
 
@@ -469,6 +516,24 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
             return;
         }
 
+        // The type intrinsics that const propagation left generic are known now.
+        if let (Some(instance), Some(&(ref dest, target))) = (instance, destination.as_ref()) {
+            let value = match instance.def {
+                ty::InstanceDef::Item(def_id) | ty::InstanceDef::Intrinsic(def_id) => {
+                    TypeIntrinsic::of_fn(bx.tcx(), def_id)
+                }
+                _ => None,
+            };
+            let value =
+                value.and_then(|intrinsic| intrinsic.eval(bx.tcx(), instance.substs.type_at(0)));
+            if let Some(value) = value {
+                self.codegen_type_intrinsic_value(&mut bx, dest, value);
+                helper.maybe_sideeffect(self.mir, &mut bx, &[target]);
+                helper.funclet_br(self, &mut bx, target);
+                return;
+            }
+        }
+
         // FIXME(eddyb) avoid computing this if possible, when `instance` is
         // available - right now `sig` is only needed for getting the `abi`
         // and figuring out how many extra args were passed to a C-variadic `fn`.
@@ -805,8 +870,22 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
                 helper.funclet_br(self, &mut bx, target);
             }
 
-            mir::TerminatorKind::SwitchInt { ref discr, switch_ty, ref values, ref targets } => {
-                self.codegen_switchint_terminator(helper, bx, discr, switch_ty, values, targets);
+            mir::TerminatorKind::SwitchInt {
+                ref discr,
+                switch_ty,
+                ref values,
+                ref targets,
+                ref target_weights,
+            } => {
+                self.codegen_switchint_terminator(
+                    helper,
+                    bx,
+                    discr,
+                    switch_ty,
+                    values,
+                    targets,
+                    target_weights.as_deref(),
+                );
             }
 
             mir::TerminatorKind::Return => {
@@ -981,13 +1060,7 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
 
     fn get_caller_location(&mut self, bx: &mut Bx, span: Span) -> OperandRef<'tcx, Bx::Value> {
         self.caller_location.unwrap_or_else(|| {
-            let topmost = span.ctxt().outer_expn().expansion_cause().unwrap_or(span);
-            let caller = bx.tcx().sess.source_map().lookup_char_pos(topmost.lo());
-            let const_loc = bx.tcx().const_caller_location((
-                Symbol::intern(&caller.file.name.to_string()),
-                caller.line as u32,
-                caller.col_display as u32 + 1,
-            ));
+            let const_loc = bx.tcx().span_as_caller_location(span);
             OperandRef::from_const(bx, const_loc, bx.tcx().caller_location_ty())
         })
     }
@@ -1064,6 +1137,32 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
         bx
     }
 
+    fn codegen_type_intrinsic_value(
+        &mut self,
+        bx: &mut Bx,
+        dest: &mir::Place<'tcx>,
+        value: &'tcx ty::Const<'tcx>,
+    ) {
+        let op = match value.val {
+            ty::ConstKind::Value(val) => OperandRef::from_const(bx, val, value.ty),
+            _ => bug!("type intrinsic evaluated to {:?}", value),
+        };
+        if let Some(index) = dest.as_local() {
+            match self.locals[index] {
+                LocalRef::Place(place) => op.val.store(bx, place),
+                LocalRef::Operand(None) => {
+                    self.locals[index] = LocalRef::Operand(Some(op));
+                    self.debug_introduce_local(bx, index);
+                }
+                LocalRef::UnsizedPlace(_) => bug!("type intrinsic values are sized"),
+                LocalRef::Operand(Some(_)) => bug!("place local already assigned to"),
+            }
+        } else {
+            let place = self.codegen_place(bx, dest.as_ref());
+            op.val.store(bx, place);
+        }
+    }
+
     fn make_return_dest(
         &mut self,
         bx: &mut Bx,
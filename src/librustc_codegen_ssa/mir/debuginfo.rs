@@ -33,7 +33,7 @@ pub struct PerLocalVarDebugInfo<'tcx, D> {
     /// `DIVariable` returned by `create_dbg_var`.
     pub dbg_var: Option<D>,
 
-    /// `.place.projection` from `mir::VarDebugInfo`.
+    /// The projection of the `VarDebugInfoContents::Place` of the `mir::VarDebugInfo`.
     pub projection: &'tcx ty::List<mir::PlaceElem<'tcx>>,
 }
 
@@ -298,6 +298,47 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
         }
     }
 
+    /// Apply debuginfo to the variables whose value is a constant, by "spilling" the constant
+    /// onto the stack, like operand locals.
+    pub fn debug_introduce_constants(&mut self, bx: &mut Bx) {
+        if bx.sess().opts.debuginfo != DebugInfo::Full {
+            return;
+        }
+
+        let mir = self.mir;
+        for var in &mir.var_debug_info {
+            let constant = match &var.value {
+                mir::VarDebugInfoContents::Const(constant) => constant,
+                mir::VarDebugInfoContents::Place(_) => continue,
+            };
+            let (scope, span) = self.debug_loc(var.source_info);
+            let scope = match scope {
+                Some(scope) => scope,
+                None => continue,
+            };
+            // Errors in the constant are reported where it is used, if anywhere.
+            let operand = match self.eval_mir_constant_to_operand(bx, constant) {
+                Ok(operand) => operand,
+                Err(_) => continue,
+            };
+
+            let dbg_var = self.cx.create_dbg_var(
+                self.debug_context.as_ref().unwrap(),
+                var.name,
+                operand.layout.ty,
+                scope,
+                VariableKind::LocalVariable,
+                span,
+            );
+            let spill_slot = PlaceRef::alloca(bx, operand.layout);
+            if !bx.sess().fewer_names() {
+                bx.set_var_name(spill_slot.llval, &format!("{}.dbg.spill", var.name));
+            }
+            operand.val.store(bx, spill_slot);
+            bx.dbg_var_addr(dbg_var, scope, spill_slot.llval, Size::ZERO, &[], span);
+        }
+    }
+
     /// Partition all `VarDebugInfo` in `self.mir`, by their base `Local`.
     pub fn compute_per_local_var_debug_info(
         &self,
@@ -310,13 +351,17 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
 
         let mut per_local = IndexVec::from_elem(vec![], &self.mir.local_decls);
         for var in &self.mir.var_debug_info {
+            let place = match var.value {
+                mir::VarDebugInfoContents::Place(place) => place,
+                // These are handled by `debug_introduce_constants`.
+                mir::VarDebugInfoContents::Const(_) => continue,
+            };
             let (scope, span) = if full_debug_info {
                 self.debug_loc(var.source_info)
             } else {
                 (None, var.source_info.span)
             };
             let dbg_var = scope.map(|scope| {
-                let place = var.place;
                 let var_ty = self.monomorphized_place_ty(place.as_ref());
                 let var_kind = if self.mir.local_kind(place.local) == mir::LocalKind::Arg
                     && place.projection.is_empty()
@@ -341,11 +386,11 @@ impl<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>> FunctionCx<'a, 'tcx, Bx> {
                 )
             });
 
-            per_local[var.place.local].push(PerLocalVarDebugInfo {
+            per_local[place.local].push(PerLocalVarDebugInfo {
                 name: var.name,
                 source_info: var.source_info,
                 dbg_var,
-                projection: var.place.projection,
+                projection: place.projection,
             });
         }
         Some(per_local)
@@ -224,6 +224,7 @@ pub fn codegen_mir<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>>(
 
     // Apply debuginfo to the newly allocated locals.
     fx.debug_introduce_locals(&mut bx);
+    fx.debug_introduce_constants(&mut bx);
 
     // Branch to the START block, if it's not the entry block.
     if reentrant_start_block {
@@ -53,12 +53,30 @@ pub trait BuilderMethods<'a, 'tcx>:
         then_llbb: Self::BasicBlock,
         else_llbb: Self::BasicBlock,
     );
+    /// Like `cond_br`, with the relative likelihoods of branching to `then_llbb` and
+    /// `else_llbb`.
+    fn cond_br_with_weights(
+        &mut self,
+        cond: Self::Value,
+        then_llbb: Self::BasicBlock,
+        else_llbb: Self::BasicBlock,
+        weights: (u32, u32),
+    );
     fn switch(
         &mut self,
         v: Self::Value,
         else_llbb: Self::BasicBlock,
         cases: impl ExactSizeIterator<Item = (u128, Self::BasicBlock)> + TrustedLen,
     );
+    /// Like `switch`, with the relative likelihoods of branching to `else_llbb` and to each
+    /// of the cases.
+    fn switch_with_weights(
+        &mut self,
+        v: Self::Value,
+        else_llbb: Self::BasicBlock,
+        else_weight: u32,
+        cases: impl ExactSizeIterator<Item = (u128, Self::BasicBlock, u32)> + TrustedLen,
+    );
     fn invoke(
         &mut self,
         llfn: Self::Value,
@@ -21,6 +21,9 @@ pub trait IntrinsicCallMethods<'tcx>: BackendTypes {
     fn assume(&mut self, val: Self::Value);
     fn expect(&mut self, cond: Self::Value, expected: bool) -> Self::Value;
     fn sideeffect(&mut self);
+    /// Inserts a side effect even without `-Z insert-sideeffect`, to keep a loop
+    /// that has none from being assumed to terminate.
+    fn force_sideeffect(&mut self);
     /// Trait method used to inject `va_start` on the "spoofed" `VaListImpl` in
     /// Rust defined C-variadic functions.
     fn va_start(&mut self, val: Self::Value) -> Self::Value;
@@ -109,6 +109,43 @@ impl<Node: Idx> Dominators<Node> {
     }
 }
 
+/// Computes the dominance frontier of every node of `graph`, i.e., the nodes at which the
+/// dominance of each node ends. These are the points where SSA construction has to place phis.
+///
+/// Unreachable nodes have an empty frontier and never appear in the frontier of other nodes.
+pub fn dominance_frontiers<G: ControlFlowGraph>(
+    graph: G,
+    dominators: &Dominators<G::Node>,
+) -> IndexVec<G::Node, Vec<G::Node>> {
+    let mut frontiers: IndexVec<G::Node, Vec<G::Node>> =
+        (0..graph.num_nodes()).map(|_| Vec::new()).collect();
+
+    for node in (0..graph.num_nodes()).map(G::Node::new) {
+        if !dominators.is_reachable(node) {
+            continue;
+        }
+
+        let predecessors: Vec<_> =
+            graph.predecessors(node).filter(|&pred| dominators.is_reachable(pred)).collect();
+        if predecessors.len() < 2 {
+            continue;
+        }
+
+        let idom = dominators.immediate_dominator(node);
+        for pred in predecessors {
+            let mut runner = pred;
+            while runner != idom {
+                if !frontiers[runner].contains(&node) {
+                    frontiers[runner].push(node);
+                }
+                runner = dominators.immediate_dominator(runner);
+            }
+        }
+    }
+
+    frontiers
+}
+
 pub struct Iter<'dom, Node: Idx> {
     dominators: &'dom Dominators<Node>,
     node: Option<Node>,
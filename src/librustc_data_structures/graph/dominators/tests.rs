@@ -32,3 +32,28 @@ fn paper() {
     assert_eq!(immediate_dominators[5], Some(6));
     assert_eq!(immediate_dominators[6], Some(6));
 }
+
+#[test]
+fn diamond_frontiers() {
+    let graph = TestGraph::new(0, &[(0, 1), (0, 2), (1, 3), (2, 3)]);
+
+    let dominators = dominators(&graph);
+    let frontiers = dominance_frontiers(&graph, &dominators);
+    assert_eq!(frontiers[0], vec![]);
+    assert_eq!(frontiers[1], vec![3]);
+    assert_eq!(frontiers[2], vec![3]);
+    assert_eq!(frontiers[3], vec![]);
+}
+
+#[test]
+fn loop_frontiers() {
+    let graph = TestGraph::new(0, &[(0, 1), (1, 2), (2, 1), (2, 3), (4, 3)]);
+
+    let dominators = dominators(&graph);
+    let frontiers = dominance_frontiers(&graph, &dominators);
+    assert_eq!(frontiers[0], vec![]);
+    assert_eq!(frontiers[1], vec![1]);
+    assert_eq!(frontiers[2], vec![1]);
+    assert_eq!(frontiers[3], vec![]);
+    assert_eq!(frontiers[4], vec![]);
+}
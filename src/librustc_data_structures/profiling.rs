@@ -603,8 +603,9 @@ pub fn duration_to_secs_str(dur: std::time::Duration) -> String {
 }
 
 // Memory reporting
+/// Returns the resident memory of the process, in bytes.
 #[cfg(unix)]
-fn get_resident() -> Option<usize> {
+pub fn get_resident() -> Option<usize> {
     let field = 1;
     let contents = fs::read("/proc/self/statm").ok()?;
     let contents = String::from_utf8(contents).ok()?;
@@ -613,8 +614,9 @@ fn get_resident() -> Option<usize> {
     Some(npages * 4096)
 }
 
+/// Returns the resident memory of the process, in bytes.
 #[cfg(windows)]
-fn get_resident() -> Option<usize> {
+pub fn get_resident() -> Option<usize> {
     use std::mem::{self, MaybeUninit};
     use winapi::shared::minwindef::DWORD;
     use winapi::um::processthreadsapi::GetCurrentProcess;
@@ -184,6 +184,7 @@ pub fn run_compiler(
             crate_name: None,
             lint_caps: Default::default(),
             register_lints: None,
+            register_mir_passes: None,
             override_queries: None,
             registry: diagnostics_registry(),
         };
@@ -260,6 +261,7 @@ pub fn run_compiler(
         crate_name: None,
         lint_caps: Default::default(),
         register_lints: None,
+        register_mir_passes: None,
         override_queries: None,
         registry: diagnostics_registry(),
     };
@@ -405,6 +407,14 @@ pub fn run_compiler(
                 sess.code_stats.print_type_sizes();
             }
 
+            if sess.borrowck_stats() {
+                sess.code_stats.print_borrowck_stats();
+            }
+
+            if sess.mir_pass_stats() {
+                sess.code_stats.print_mir_pass_stats();
+            }
+
             let linker = queries.linker()?;
             Ok(Some(linker))
         })?;
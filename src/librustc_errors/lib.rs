@@ -307,6 +307,10 @@ struct HandlerInner {
     /// The stashed diagnostics count towards the total error count.
     /// When `.abort_if_errors()` is called, these are also emitted.
     stashed_diagnostics: FxIndexMap<(Span, StashKey), Diagnostic>,
+
+    /// The number of `with_recoverable_bugs` calls being run. The bugs are reported as warnings
+    /// while it is not zero.
+    recoverable_bugs: usize,
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -415,6 +419,7 @@ impl Handler {
                 emitted_diagnostic_codes: Default::default(),
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
+                recoverable_bugs: 0,
             }),
         }
     }
@@ -646,6 +651,23 @@ impl Handler {
         self.inner.borrow_mut().bug(msg)
     }
 
+    /// Runs `f`, reporting the bugs it hits with `bug` and `span_bug` as warnings instead of
+    /// errors. The caller catches the `ExplicitBug` panic and recovers from it, so these bugs
+    /// must not fail the compilation.
+    pub fn with_recoverable_bugs<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Guard<'a>(&'a Handler);
+
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                self.0.inner.borrow_mut().recoverable_bugs -= 1;
+            }
+        }
+
+        self.inner.borrow_mut().recoverable_bugs += 1;
+        let _guard = Guard(self);
+        f()
+    }
+
     pub fn err_count(&self) -> usize {
         self.inner.borrow().err_count()
     }
@@ -831,10 +853,14 @@ impl HandlerInner {
     }
 
     fn span_bug(&mut self, sp: impl Into<MultiSpan>, msg: &str) -> ! {
-        self.emit_diag_at_span(Diagnostic::new(Bug, msg), sp);
+        self.emit_diag_at_span(Diagnostic::new(self.bug_level(), msg), sp);
         panic!(ExplicitBug);
     }
 
+    fn bug_level(&self) -> Level {
+        if self.recoverable_bugs > 0 { Warning } else { Bug }
+    }
+
     fn emit_diag_at_span(&mut self, mut diag: Diagnostic, sp: impl Into<MultiSpan>) {
         self.emit_diagnostic(diag.set_span(sp));
     }
@@ -871,7 +897,7 @@ impl HandlerInner {
     }
 
     fn bug(&mut self, msg: &str) -> ! {
-        self.emit_diagnostic(&Diagnostic::new(Bug, msg));
+        self.emit_diagnostic(&Diagnostic::new(self.bug_level(), msg));
         panic!(ExplicitBug);
     }
 
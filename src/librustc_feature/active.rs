@@ -555,6 +555,21 @@ declare_features! (
     // Allows limiting the evaluation steps of const expressions
     (active, const_eval_limit, "1.43.0", Some(67217), None),
 
+    /// Allows calling function pointers in constants and `const fn`s.
+    (active, const_fn_ptr_calls, "1.43.0", None, None),
+
+    /// Allows setting the limit of the `huge_function_body` lint.
+    (active, huge_function_body_limit, "1.43.0", None, None),
+
+    /// Allows overriding `-Z mir-opt-level` for a crate with `#![mir_opt_level]`.
+    (active, mir_opt_level, "1.43.0", None, None),
+
+    /// Allows inline `const` blocks in expressions: `const { ... }`.
+    (active, inline_const, "1.43.0", None, None),
+
+    /// Allows calling the methods of trait objects in constants and `const fn`s.
+    (active, const_trait_object_calls, "1.43.0", None, None),
+
     // -------------------------------------------------------------------------
     // feature-group-end: actual feature gates
     // -------------------------------------------------------------------------
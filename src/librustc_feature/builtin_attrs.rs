@@ -243,6 +243,10 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
         const_eval_limit, CrateLevel, template!(NameValueStr: "N"), const_eval_limit,
         experimental!(const_eval_limit)
     ),
+    gated!(
+        huge_function_body_limit, CrateLevel, template!(NameValueStr: "N"),
+        huge_function_body_limit, experimental!(huge_function_body_limit)
+    ),
 
     // Entry point:
     ungated!(main, Normal, template!(Word)),
@@ -334,6 +338,10 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
         optimize, Whitelisted, template!(List: "size|speed"), optimize_attribute,
         experimental!(optimize),
     ),
+    gated!(
+        mir_opt_level, CrateLevel, template!(NameValueStr: "N"), mir_opt_level,
+        experimental!(mir_opt_level)
+    ),
 
     gated!(ffi_returns_twice, Whitelisted, template!(Word), experimental!(ffi_returns_twice)),
     gated!(track_caller, Whitelisted, template!(Word), experimental!(track_caller)),
@@ -433,6 +441,9 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
         "the `linkage` attribute is experimental and not portable across platforms",
     ),
     rustc_attr!(rustc_std_internal_symbol, Whitelisted, template!(Word), INTERNAL_UNSTABLE),
+    // Make the MIR inliner always, or never, inline a function, whatever its `#[inline]` hint.
+    rustc_attr!(rustc_mir_inline, Whitelisted, template!(Word), INTERNAL_UNSTABLE),
+    rustc_attr!(rustc_no_mir_inline, Whitelisted, template!(Word), INTERNAL_UNSTABLE),
 
     // ==========================================================================
     // Internal attributes, Macro related:
@@ -469,6 +480,17 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
     rustc_attr!(rustc_allow_const_fn_ptr, Whitelisted, template!(Word), IMPL_DETAIL),
     rustc_attr!(rustc_args_required_const, Whitelisted, template!(List: "N"), INTERNAL_UNSTABLE),
 
+    // ==========================================================================
+    // Internal attributes, Taint analysis related:
+    // ==========================================================================
+
+    // Functions returning untrusted data, checked by the taint analysis in `librustc_mir`.
+    rustc_attr!(rustc_taint_source, Whitelisted, template!(Word), INTERNAL_UNSTABLE),
+    // Functions which must not be passed untrusted data.
+    rustc_attr!(rustc_taint_sink, Whitelisted, template!(Word), INTERNAL_UNSTABLE),
+    // Functions returning a checked version of the data passed to them.
+    rustc_attr!(rustc_taint_sanitizer, Whitelisted, template!(Word), INTERNAL_UNSTABLE),
+
     // ==========================================================================
     // Internal attributes, Layout related:
     // ==========================================================================
@@ -548,6 +570,7 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
     rustc_attr!(TEST, rustc_outlives, Normal, template!(Word)),
     rustc_attr!(TEST, rustc_variance, Normal, template!(Word)),
     rustc_attr!(TEST, rustc_layout, Normal, template!(List: "field1, field2, ...")),
+    rustc_attr!(TEST, rustc_dump_callees, Normal, template!(Word)),
     rustc_attr!(TEST, rustc_regions, Normal, template!(Word)),
     rustc_attr!(
         TEST, rustc_error, Whitelisted,
@@ -1362,6 +1362,7 @@ impl Expr<'_> {
         match self.kind {
             ExprKind::Box(_) => ExprPrecedence::Box,
             ExprKind::Array(_) => ExprPrecedence::Array,
+            ExprKind::ConstBlock(_) => ExprPrecedence::ConstBlock,
             ExprKind::Call(..) => ExprPrecedence::Call,
             ExprKind::MethodCall(..) => ExprPrecedence::MethodCall,
             ExprKind::Tup(_) => ExprPrecedence::Tup,
@@ -1433,6 +1434,7 @@ impl Expr<'_> {
             | ExprKind::Block(..)
             | ExprKind::Repeat(..)
             | ExprKind::Array(..)
+            | ExprKind::ConstBlock(..)
             | ExprKind::Break(..)
             | ExprKind::Continue(..)
             | ExprKind::Ret(..)
@@ -1545,6 +1547,8 @@ pub enum ExprKind<'hir> {
     Box(&'hir Expr<'hir>),
     /// An array (e.g., `[a, b, c, d]`).
     Array(&'hir [Expr<'hir>]),
+    /// An inline `const` block (e.g., `const { N * 2 }`).
+    ConstBlock(AnonConst),
     /// A function call.
     ///
     /// The first field resolves to the function itself (usually an `ExprKind::Path`),
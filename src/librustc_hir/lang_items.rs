@@ -230,6 +230,9 @@ language_item_table! {
     PanicImplLangItem,           "panic_impl",         panic_impl,              Target::Fn;
     // Libstd panic entry point. Necessary for const eval to be able to catch it
     BeginPanicFnLangItem,        "begin_panic",        begin_panic_fn,          Target::Fn;
+    // The entry points of formatted panics, so that const eval can render their message.
+    PanicFmtLangItem,            "panic_fmt",          panic_fmt_fn,            Target::Fn;
+    BeginPanicFmtLangItem,       "begin_panic_fmt",    begin_panic_fmt_fn,      Target::Fn;
 
     ExchangeMallocFnLangItem,    "exchange_malloc",    exchange_malloc_fn,      Target::Fn;
     BoxFreeFnLangItem,           "box_free",           box_free_fn,             Target::Fn;
@@ -251,6 +254,9 @@ language_item_table! {
 
     MaybeUninitLangItem,         "maybe_uninit",       maybe_uninit,            Target::Union;
 
+    // Compares arrays of integers with `memcmp`, see the `BytewiseArrayEq` MIR pass.
+    BytewiseEqFnLangItem,        "bytewise_eq",        bytewise_eq_fn,          Target::Fn;
+
     // Align offset for stride != 1; must not panic.
     AlignOffsetLangItem,         "align_offset",       align_offset_fn,         Target::Fn;
 
@@ -15,6 +15,7 @@ use rustc_data_structures::sync::Lrc;
 use rustc_data_structures::OnDrop;
 use rustc_errors::registry::Registry;
 use rustc_lint::LintStore;
+use rustc_mir::transform::CustomMirPasses;
 use rustc_parse::new_parser_from_source_str;
 use rustc_session::parse::{CrateConfig, ParseSess};
 use rustc_span::edition;
@@ -38,6 +39,8 @@ pub struct Compiler {
     pub(crate) output_file: Option<PathBuf>,
     pub(crate) crate_name: Option<String>,
     pub(crate) register_lints: Option<Box<dyn Fn(&Session, &mut LintStore) + Send + Sync>>,
+    pub(crate) register_mir_passes:
+        Option<Box<dyn Fn(&Session, &mut CustomMirPasses) + Send + Sync>>,
     pub(crate) override_queries:
         Option<fn(&Session, &mut ty::query::Providers<'_>, &mut ty::query::Providers<'_>)>,
 }
@@ -152,6 +155,11 @@ pub struct Config {
     /// function being registered.
     pub register_lints: Option<Box<dyn Fn(&Session, &mut LintStore) + Send + Sync>>,
 
+    /// This is a callback from the driver that is called before the global context is created,
+    /// to register the driver's own MIR passes. See `MirPassInsertionPoint` for where in the
+    /// pipeline they can run.
+    pub register_mir_passes: Option<Box<dyn Fn(&Session, &mut CustomMirPasses) + Send + Sync>>,
+
     /// This is a callback from the driver that is called just after we have populated
     /// the list of queries.
     ///
@@ -188,6 +196,7 @@ pub fn run_compiler_in_existing_thread_pool<R>(
         output_file: config.output_file,
         crate_name: config.crate_name,
         register_lints: config.register_lints,
+        register_mir_passes: config.register_mir_passes,
         override_queries: config.override_queries,
     };
 
@@ -30,6 +30,7 @@ use rustc_hir::Crate;
 use rustc_infer::traits;
 use rustc_lint::LintStore;
 use rustc_mir as mir;
+use rustc_mir::transform::CustomMirPasses;
 use rustc_mir_build as mir_build;
 use rustc_parse::{parse_crate_from_file, parse_crate_from_source_str};
 use rustc_passes::{self, hir_stats, layout_test};
@@ -733,11 +734,17 @@ pub fn create_global_ctxt<'tcx>(
         callback(sess, &mut local_providers, &mut extern_providers);
     }
 
+    let mut mir_passes = CustomMirPasses::default();
+    if let Some(register) = &compiler.register_mir_passes {
+        register(sess, &mut mir_passes);
+    }
+
     let gcx = sess.time("setup_global_ctxt", || {
         global_ctxt.init_locking(|| {
             TyCtxt::create_global_ctxt(
                 sess,
                 lint_store,
+                Lrc::new(mir_passes),
                 local_providers,
                 extern_providers,
                 arena,
@@ -823,6 +830,11 @@ fn analysis(tcx: TyCtxt<'_>, cnum: CrateNum) -> Result<()> {
         tcx.par_body_owners(|def_id| tcx.ensure().mir_borrowck(def_id));
     });
 
+    sess.time("MIR_recursion_checking", || {
+        mir::transform::check_mutual_recursion::check_mutual_recursion(tcx);
+        mir::transform::callgraph::dump_callees(tcx);
+    });
+
     sess.time("dumping_chalk_like_clauses", || {
         rustc_traits::lowering::dump_program_clauses(tcx);
     });
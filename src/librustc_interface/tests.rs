@@ -7,7 +7,10 @@ use rustc::middle::cstore;
 use rustc::session::config::{build_configuration, build_session_options, to_crate_config};
 use rustc::session::config::{rustc_optgroups, ErrorOutputType, ExternLocation, Options, Passes};
 use rustc::session::config::{ExternEntry, LinkerPluginLto, LtoCli, SwitchWithOptPath};
-use rustc::session::config::{Externs, OutputType, OutputTypes, SymbolManglingVersion};
+use rustc::session::config::{Externs, OutputType, OutputTypes};
+use rustc::session::config::{ConstEvalBacktrace, MirEmitRetag, MirPassRisk, MirSpanview};
+use rustc::session::config::{InterpWatchpoint, WatchKind};
+use rustc::session::config::SymbolManglingVersion;
 use rustc::session::search_paths::SearchPath;
 use rustc::session::{build_session, Session};
 use rustc_data_structures::fx::FxHashSet;
@@ -528,6 +531,8 @@ fn test_debugging_options_tracking_hash() {
     assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
     opts.debugging_opts.borrowck_stats = true;
     assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+    opts.debugging_opts.mir_pass_stats = true;
+    assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
     opts.debugging_opts.meta_stats = true;
     assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
     opts.debugging_opts.print_link_args = true;
@@ -570,6 +575,12 @@ fn test_debugging_options_tracking_hash() {
     assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
     opts.debugging_opts.dump_mir_graphviz = true;
     assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+    opts.debugging_opts.dump_mir_spanview = Some(MirSpanview::Block);
+    assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+    opts.debugging_opts.mir_opt_remarks = Some(String::from("all"));
+    assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+    opts.debugging_opts.const_eval_backtrace = Some(ConstEvalBacktrace::Short);
+    assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
     // Make sure changing a [TRACKED] option changes the hash
     opts = reference.clone();
@@ -627,6 +638,26 @@ fn test_debugging_options_tracking_hash() {
     opts = reference.clone();
     opts.debugging_opts.symbol_mangling_version = SymbolManglingVersion::V0;
     assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.debugging_opts.mir_emit_retag = Some(MirEmitRetag::FnEntry);
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.debugging_opts.mir_enable_experimental_passes = vec![MirPassRisk::Experimental];
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.debugging_opts.mir_opt_fixpoint_limit = 4;
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.debugging_opts.print_mir_hashes = true;
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.debugging_opts.recover_mir_pass_panics = true;
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 }
 
 #[test]
@@ -639,3 +670,21 @@ fn test_edition_parsing() {
     let (sessopts, _) = build_session_options_and_crate_config(matches);
     assert!(sessopts.edition == Edition::Edition2018)
 }
+
+#[test]
+fn test_interp_watchpoints_parsing() {
+    let matches = optgroups()
+        .parse(&["-Z".to_string(), "interp-watchpoints=alloc3 4@8..16:write".to_string()])
+        .unwrap();
+    let (sessopts, _) = build_session_options_and_crate_config(matches);
+    let end = u64::max_value();
+    let whole = InterpWatchpoint { alloc_id: 3, start: 0, end, kind: WatchKind::Access };
+    let range = InterpWatchpoint { alloc_id: 4, start: 8, end: 16, kind: WatchKind::Write };
+    assert_eq!(sessopts.debugging_opts.interp_watchpoints, vec![whole, range]);
+
+    let read = "7@0..1:read".parse::<InterpWatchpoint>();
+    assert_eq!(read, Ok(InterpWatchpoint { alloc_id: 7, start: 0, end: 1, kind: WatchKind::Read }));
+    for invalid in &["", "alloc", "x1", "1@4..2", "1@2..2", "1@0..", "1@0-4", "1:exec"] {
+        assert_eq!(invalid.parse::<InterpWatchpoint>(), Err(()));
+    }
+}
@@ -2,10 +2,12 @@
 //! Clippy.
 
 use crate::{EarlyContext, EarlyLintPass, LateContext, LateLintPass, LintContext};
+use rustc::ty;
 use rustc_ast::ast::{Ident, Item, ItemKind};
 use rustc_data_structures::fx::FxHashMap;
 use rustc_errors::Applicability;
-use rustc_hir::{GenericArg, HirId, MutTy, Mutability, Path, PathSegment, QPath, Ty, TyKind};
+use rustc_hir::{Expr, ExprKind, GenericArg, HirId, MatchSource, MutTy, Mutability, Path};
+use rustc_hir::{PathSegment, QPath, Ty, TyKind};
 use rustc_session::{declare_lint_pass, declare_tool_lint, impl_lint_pass};
 use rustc_span::hygiene::{ExpnKind, MacroKind};
 use rustc_span::symbol::{sym, Symbol};
@@ -248,3 +250,56 @@ impl EarlyLintPass for LintPassImpl {
         }
     }
 }
+
+declare_tool_lint! {
+    pub rustc::UNORDERED_ITERATION,
+    Allow,
+    "iterating over a `HashMap` or `HashSet`, whose order depends on the hashes of the elements",
+    report_in_external_macro: true
+}
+
+declare_lint_pass!(UnorderedIteration => [UNORDERED_ITERATION]);
+
+/// The methods iterating over the elements of a `HashMap` or a `HashSet`.
+const ITERATION_METHODS: &[&str] =
+    &["drain", "into_iter", "iter", "iter_mut", "keys", "values", "values_mut"];
+
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnorderedIteration {
+    fn check_expr(&mut self, cx: &LateContext<'a, 'tcx>, expr: &'tcx Expr<'tcx>) {
+        let iterated = match &expr.kind {
+            ExprKind::MethodCall(segment, _, args)
+                if ITERATION_METHODS.contains(&&*segment.ident.as_str()) =>
+            {
+                &args[0]
+            }
+            // The head of a `for` loop is passed to `IntoIterator::into_iter`.
+            ExprKind::Match(head, _, MatchSource::ForLoopDesugar) => match &head.kind {
+                ExprKind::Call(_, args) if args.len() == 1 => &args[0],
+                _ => return,
+            },
+            _ => return,
+        };
+        let ty = cx.tables.expr_ty_adjusted(iterated).peel_refs();
+        let name = match ty.kind {
+            ty::Adt(adt_def, _) if cx.tcx.is_diagnostic_item(sym::hashmap_type, adt_def.did) => {
+                "HashMap"
+            }
+            ty::Adt(adt_def, _) if cx.tcx.is_diagnostic_item(sym::hashset_type, adt_def.did) => {
+                "HashSet"
+            }
+            _ => return,
+        };
+        cx.struct_span_lint(UNORDERED_ITERATION, iterated.span, |lint| {
+            lint.build(&format!("iterating over a `{}`", name))
+                .note(
+                    "the order depends on the hashes of the elements, which can differ between \
+                     compilations for the elements hashed by address",
+                )
+                .help(&format!(
+                    "use an `FxIndex{}` instead, or sort the elements before using their order",
+                    &name[4..],
+                ))
+                .emit();
+        });
+    }
+}
@@ -439,6 +439,8 @@ fn register_internals(store: &mut LintStore) {
     store.register_early_pass(|| box LintPassImpl);
     store.register_lints(&TyTyKind::get_lints());
     store.register_late_pass(|| box TyTyKind);
+    store.register_lints(&UnorderedIteration::get_lints());
+    store.register_late_pass(|| box UnorderedIteration);
     store.register_group(
         false,
         "rustc::internal",
@@ -87,6 +87,9 @@ crate struct CrateMetadata {
     /// Do not access the value directly, as it might not have been initialized yet.
     /// The field must always be initialized to `DepNodeIndex::INVALID`.
     dep_node_index: AtomicCell<DepNodeIndex>,
+    /// The spans of the optimized MIR bodies of which single blocks were decoded, shared by all
+    /// their blocks, see `get_optimized_mir_block`.
+    optimized_mir_spans: Lock<FxHashMap<DefIndex, Lrc<[Span]>>>,
 
     // --- Other significant crate properties ---
     /// ID of this crate, from the current compilation session's point of view.
@@ -136,6 +139,9 @@ pub(super) struct DecodeContext<'a, 'tcx> {
 
     // Used for decoding interpret::AllocIds in a cached & thread-safe manner.
     alloc_decoding_session: Option<AllocDecodingSession<'a>>,
+
+    /// The spans of the MIR body being decoded, see `MirEntry`.
+    mir_spans: Option<Lrc<[Span]>>,
 }
 
 /// Abstract over the various ways one can create metadata decoders.
@@ -163,6 +169,7 @@ pub(super) trait Metadata<'a, 'tcx>: Copy {
             alloc_decoding_session: self
                 .cdata()
                 .map(|cdata| cdata.alloc_decoding_state.new_decoding_session()),
+            mir_spans: None,
         }
     }
 }
@@ -224,6 +231,15 @@ impl<'a, 'tcx, T: Decodable> Lazy<T> {
         dcx.lazy_state = LazyState::NodeStart(self.position);
         T::decode(&mut dcx).unwrap()
     }
+
+    /// Decodes a part of the MIR of a `MirEntry` or of an `OptimizedMirEntry`, whose spans are
+    /// `mir_spans`.
+    fn decode_mir<M: Metadata<'a, 'tcx>>(self, metadata: M, mir_spans: Lrc<[Span]>) -> T {
+        let mut dcx = metadata.decoder(self.position.get());
+        dcx.lazy_state = LazyState::NodeStart(self.position);
+        dcx.mir_spans = Some(mir_spans);
+        T::decode(&mut dcx).unwrap()
+    }
 }
 
 impl<'a: 'x, 'tcx: 'x, 'x, T: Decodable> Lazy<[T]> {
@@ -237,6 +253,13 @@ impl<'a: 'x, 'tcx: 'x, 'x, T: Decodable> Lazy<[T]> {
     }
 }
 
+impl<'a, 'tcx, T: Decodable> MirEntry<T> {
+    fn decode<M: Metadata<'a, 'tcx>>(self, metadata: M) -> T {
+        let mir_spans = self.spans.decode(metadata).collect();
+        self.body.decode_mir(metadata, mir_spans)
+    }
+}
+
 impl<'a, 'tcx> DecodeContext<'a, 'tcx> {
     fn tcx(&self) -> TyCtxt<'tcx> {
         self.tcx.expect("missing TyCtxt in DecodeContext")
@@ -380,6 +403,11 @@ impl<'a, 'tcx> SpecializedDecoder<interpret::AllocId> for DecodeContext<'a, 'tcx
 
 impl<'a, 'tcx> SpecializedDecoder<Span> for DecodeContext<'a, 'tcx> {
     fn specialized_decode(&mut self) -> Result<Span, Self::Error> {
+        if self.mir_spans.is_some() {
+            let index = usize::decode(self)?;
+            return Ok(self.mir_spans.as_ref().unwrap()[index]);
+        }
+
         let tag = u8::decode(self)?;
 
         if tag == TAG_INVALID_SPAN {
@@ -591,6 +619,7 @@ impl<'a, 'tcx> CrateMetadata {
             source_map_import_info: Once::new(),
             alloc_decoding_state,
             dep_node_index: AtomicCell::new(DepNodeIndex::INVALID),
+            optimized_mir_spans: Default::default(),
             cnum,
             cnum_map,
             dependencies,
@@ -1061,9 +1090,8 @@ impl<'a, 'tcx> CrateMetadata {
         !self.is_proc_macro(id) && self.root.per_def.mir.get(self, id).is_some()
     }
 
-    fn get_optimized_mir(&self, tcx: TyCtxt<'tcx>, id: DefIndex) -> BodyAndCache<'tcx> {
-        let mut cache = self
-            .root
+    fn optimized_mir_entry(&self, id: DefIndex) -> OptimizedMirEntry<'static> {
+        self.root
             .per_def
             .mir
             .get(self, id)
@@ -1071,11 +1099,72 @@ impl<'a, 'tcx> CrateMetadata {
             .unwrap_or_else(|| {
                 bug!("get_optimized_mir: missing MIR for `{:?}`", self.local_def_id(id))
             })
-            .decode((self, tcx));
+            .decode(self)
+    }
+
+    fn get_optimized_mir(&self, tcx: TyCtxt<'tcx>, id: DefIndex) -> BodyAndCache<'tcx> {
+        let entry = self.optimized_mir_entry(id);
+        // The spans are only shared with the blocks decoded one at a time before, if any.
+        let mir_spans = self
+            .optimized_mir_spans
+            .lock()
+            .remove(&id)
+            .unwrap_or_else(|| entry.spans.decode((self, tcx)).collect());
+        let mut cache = entry.body.decode_mir((self, tcx), mir_spans.clone());
+        *cache.basic_blocks_mut() = entry
+            .blocks
+            .decode(self)
+            .map(|block| block.decode_mir((self, tcx), mir_spans.clone()))
+            .collect();
         cache.ensure_predecessors();
         cache
     }
 
+    fn get_optimized_mir_block(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        id: DefIndex,
+        bb: mir::BasicBlock,
+    ) -> mir::BasicBlockData<'tcx> {
+        let entry = self.optimized_mir_entry(id);
+        let block = entry.blocks.decode(self).nth(bb.index()).unwrap_or_else(|| {
+            bug!("get_optimized_mir_block: missing `{:?}` in `{:?}`", bb, self.local_def_id(id))
+        });
+        block.decode_mir((self, tcx), self.optimized_mir_spans(tcx, id, entry.spans))
+    }
+
+    fn get_optimized_mir_outline(&self, tcx: TyCtxt<'tcx>, id: DefIndex) -> mir::BodyOutline<'tcx> {
+        let entry = self.optimized_mir_entry(id);
+        let mir_spans = self.optimized_mir_spans(tcx, id, entry.spans);
+        let body = entry.body.decode_mir((self, tcx), mir_spans);
+        mir::BodyOutline { block_count: entry.blocks.meta, ..mir::BodyOutline::new(&body) }
+    }
+
+    /// Decodes the spans of the optimized MIR of `id` once for all of its blocks decoded one at
+    /// a time.
+    fn optimized_mir_spans(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        id: DefIndex,
+        spans: Lazy<[Span]>,
+    ) -> Lrc<[Span]> {
+        if let Some(mir_spans) = self.optimized_mir_spans.lock().get(&id) {
+            return mir_spans.clone();
+        }
+        let mir_spans: Lrc<[Span]> = spans.decode((self, tcx)).collect();
+        self.optimized_mir_spans.lock().insert(id, mir_spans.clone());
+        mir_spans
+    }
+
+    fn get_optimized_mir_hash(&self, id: DefIndex) -> Option<Fingerprint> {
+        self.root
+            .per_def
+            .mir_hash
+            .get(self, id)
+            .filter(|_| !self.is_proc_macro(id))
+            .map(|hash| hash.decode(self))
+    }
+
     fn get_promoted_mir(
         &self,
         tcx: TyCtxt<'tcx>,
@@ -1090,6 +1179,7 @@ impl<'a, 'tcx> CrateMetadata {
             .unwrap_or_else(|| {
                 bug!("get_promoted_mir: missing MIR for `{:?}`", self.local_def_id(id))
             })
+            .decode((self, tcx))
             .decode((self, tcx));
         for body in cache.iter_mut() {
             body.ensure_predecessors();
@@ -10,6 +10,7 @@ use rustc::hir::map::{DefKey, DefPath, DefPathHash};
 use rustc::middle::cstore::{CrateSource, CrateStore, EncodedMetadata, NativeLibraryKind};
 use rustc::middle::exported_symbols::ExportedSymbol;
 use rustc::middle::stability::DeprecationEntry;
+use rustc::mir;
 use rustc::session::{CrateDisambiguator, Session};
 use rustc::ty::query::Providers;
 use rustc::ty::query::QueryConfig;
@@ -41,9 +42,9 @@ macro_rules! provide {
         pub fn provide_extern<$lt>(providers: &mut Providers<$lt>) {
             // HACK(eddyb) `$lt: $lt` forces `$lt` to be early-bound, which
             // allows the associated type in the return type to be normalized.
-            $(fn $name<$lt: $lt, T: IntoArgs>(
+            $(fn $name<$lt: $lt>(
                 $tcx: TyCtxt<$lt>,
-                def_id_arg: T,
+                def_id_arg: <ty::queries::$name<$lt> as QueryConfig<$lt>>::Key,
             ) -> <ty::queries::$name<$lt> as QueryConfig<$lt>>::Value {
                 let _prof_timer =
                     $tcx.prof.generic_activity("metadata_decode_entry");
@@ -73,27 +74,43 @@ macro_rules! provide {
 // small trait to work around different signature queries all being defined via
 // the macro above.
 trait IntoArgs {
-    fn into_args(self) -> (DefId, DefId);
+    type Other;
+
+    fn into_args(self) -> (DefId, Self::Other);
 }
 
 impl IntoArgs for DefId {
+    type Other = DefId;
+
     fn into_args(self) -> (DefId, DefId) {
         (self, self)
     }
 }
 
 impl IntoArgs for CrateNum {
+    type Other = DefId;
+
     fn into_args(self) -> (DefId, DefId) {
         (self.as_def_id(), self.as_def_id())
     }
 }
 
 impl IntoArgs for (CrateNum, DefId) {
+    type Other = DefId;
+
     fn into_args(self) -> (DefId, DefId) {
         (self.0.as_def_id(), self.1)
     }
 }
 
+impl IntoArgs for (DefId, mir::BasicBlock) {
+    type Other = mir::BasicBlock;
+
+    fn into_args(self) -> (DefId, mir::BasicBlock) {
+        self
+    }
+}
+
 provide! { <'tcx> tcx, def_id, other, cdata,
     type_of => { cdata.get_type(def_id.index, tcx) }
     generics_of => {
@@ -126,7 +143,14 @@ provide! { <'tcx> tcx, def_id, other, cdata,
         })
     }
     optimized_mir => { tcx.arena.alloc(cdata.get_optimized_mir(tcx, def_id.index)) }
+    optimized_mir_block => {
+        tcx.arena.alloc(cdata.get_optimized_mir_block(tcx, def_id.index, other))
+    }
+    optimized_mir_outline => {
+        tcx.arena.alloc(cdata.get_optimized_mir_outline(tcx, def_id.index))
+    }
     promoted_mir => { tcx.arena.alloc(cdata.get_promoted_mir(tcx, def_id.index)) }
+    optimized_mir_hash => { cdata.get_optimized_mir_hash(def_id.index) }
     mir_const_qualif => { cdata.mir_const_qualif(def_id.index) }
     fn_sig => { cdata.fn_sig(def_id.index, tcx) }
     inherent_impls => { cdata.get_inherent_implementations_for_type(tcx, def_id.index) }
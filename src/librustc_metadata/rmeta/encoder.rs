@@ -19,7 +19,7 @@ use rustc_hir::{AnonConst, GenericParamKind};
 use rustc_index::vec::Idx;
 
 use rustc::session::config::{self, CrateType};
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::Lrc;
 use rustc_serialize::{opaque, Encodable, Encoder, SpecializedEncoder};
@@ -54,6 +54,9 @@ struct EncodeContext<'tcx> {
 
     // This is used to speed up Span encoding.
     source_file_cache: Lrc<SourceFile>,
+
+    /// The spans of the MIR body being encoded, see `MirEntry`.
+    mir_spans: Option<FxIndexSet<Span>>,
 }
 
 macro_rules! encoder_methods {
@@ -146,6 +149,11 @@ impl<'tcx> SpecializedEncoder<DefIndex> for EncodeContext<'tcx> {
 
 impl<'tcx> SpecializedEncoder<Span> for EncodeContext<'tcx> {
     fn specialized_encode(&mut self, span: &Span) -> Result<(), Self::Error> {
+        if let Some(mir_spans) = &mut self.mir_spans {
+            let (index, _) = mir_spans.insert_full(*span);
+            return index.encode(self);
+        }
+
         if span.is_dummy() {
             return TAG_INVALID_SPAN.encode(self);
         }
@@ -931,7 +939,7 @@ impl EncodeContext<'tcx> {
             hir::ImplItemKind::Method(ref sig, _) => {
                 let generics = self.tcx.generics_of(def_id);
                 let needs_inline = (generics.requires_monomorphization(self.tcx)
-                    || tcx.codegen_fn_attrs(def_id).requests_inline())
+                    || tcx.codegen_fn_attrs(def_id).requests_mir_inline())
                     && !self.metadata_output_only();
                 let is_const_fn = sig.header.constness == hir::Constness::Const;
                 let always_encode_mir = self.tcx.sess.opts.debugging_opts.always_encode_mir;
@@ -962,17 +970,50 @@ impl EncodeContext<'tcx> {
     fn encode_optimized_mir(&mut self, def_id: DefId) {
         debug!("EntryBuilder::encode_mir({:?})", def_id);
         if self.tcx.mir_keys(LOCAL_CRATE).contains(&def_id) {
-            record!(self.per_def.mir[def_id] <- self.tcx.optimized_mir(def_id));
+            let entry = self.encode_optimized_mir_entry(self.tcx.optimized_mir(def_id));
+            record!(self.per_def.mir[def_id] <- entry);
+
+            if self.tcx.sess.opts.debugging_opts.print_mir_hashes {
+                let hash = self.tcx.optimized_mir_hash(def_id).unwrap();
+                println!("MIR_HASH {} {}", self.tcx.def_path_str(def_id), hash.to_hex());
+                record!(self.per_def.mir_hash[def_id] <- hash);
+            }
         }
     }
 
     fn encode_promoted_mir(&mut self, def_id: DefId) {
         debug!("EncodeContext::encode_promoted_mir({:?})", def_id);
         if self.tcx.mir_keys(LOCAL_CRATE).contains(&def_id) {
-            record!(self.per_def.promoted_mir[def_id] <- self.tcx.promoted_mir(def_id));
+            let entry = self.encode_mir_entry(self.tcx.promoted_mir(def_id));
+            record!(self.per_def.promoted_mir[def_id] <- entry);
         }
     }
 
+    fn encode_mir_entry<T: Encodable>(&mut self, body: &T) -> MirEntry<T> {
+        assert!(self.mir_spans.is_none());
+        self.mir_spans = Some(FxIndexSet::default());
+        let body = self.lazy(body);
+        let mir_spans = self.mir_spans.take().unwrap();
+        let spans = self.lazy(mir_spans.iter());
+        MirEntry { body, spans }
+    }
+
+    fn encode_optimized_mir_entry(
+        &mut self,
+        body: &mir::BodyAndCache<'tcx>,
+    ) -> OptimizedMirEntry<'tcx> {
+        assert!(self.mir_spans.is_none());
+        self.mir_spans = Some(FxIndexSet::default());
+        let blocks: Vec<_> = body.basic_blocks().iter().map(|block| self.lazy(block)).collect();
+        let blocks = self.lazy(blocks);
+        let mut outline = body.clone();
+        outline.basic_blocks_mut().raw.clear();
+        let body = self.lazy(&outline);
+        let mir_spans = self.mir_spans.take().unwrap();
+        let spans = self.lazy(mir_spans.iter());
+        OptimizedMirEntry { blocks, body, spans }
+    }
+
     // Encodes the inherent implementations of a structure, enumeration, or trait.
     fn encode_inherent_implementations(&mut self, def_id: DefId) {
         debug!("EncodeContext::encode_inherent_implementations({:?})", def_id);
@@ -1220,7 +1261,7 @@ impl EncodeContext<'tcx> {
             hir::ItemKind::Fn(ref sig, ..) => {
                 let generics = tcx.generics_of(def_id);
                 let needs_inline = (generics.requires_monomorphization(tcx)
-                    || tcx.codegen_fn_attrs(def_id).requests_inline())
+                    || tcx.codegen_fn_attrs(def_id).requests_mir_inline())
                     && !self.metadata_output_only();
                 let always_encode_mir = self.tcx.sess.opts.debugging_opts.always_encode_mir;
                 needs_inline || sig.header.constness == hir::Constness::Const || always_encode_mir
@@ -1709,6 +1750,7 @@ pub(super) fn encode_metadata(tcx: TyCtxt<'_>) -> EncodedMetadata {
             source_file_cache: tcx.sess.source_map().files()[0].clone(),
             interpret_allocs: Default::default(),
             interpret_allocs_inverse: Default::default(),
+            mir_spans: None,
         };
 
         // Encode the rustc version string in a predictable location.
@@ -12,6 +12,7 @@ use rustc::session::CrateDisambiguator;
 use rustc::ty::{self, ReprOptions, Ty};
 use rustc_ast::ast;
 use rustc_attr as attr;
+use rustc_data_structures::fingerprint::Fingerprint;
 use rustc_data_structures::svh::Svh;
 use rustc_data_structures::sync::MetadataRef;
 use rustc_hir as hir;
@@ -274,8 +275,10 @@ define_per_def_tables! {
     // Also, as an optimization, a missing entry indicates an empty `&[]`.
     inferred_outlives: Table<DefIndex, Lazy!(&'tcx [(ty::Predicate<'tcx>, Span)])>,
     super_predicates: Table<DefIndex, Lazy!(ty::GenericPredicates<'tcx>)>,
-    mir: Table<DefIndex, Lazy!(mir::BodyAndCache<'tcx>)>,
-    promoted_mir: Table<DefIndex, Lazy!(IndexVec<mir::Promoted, mir::BodyAndCache<'tcx>>)>,
+    mir: Table<DefIndex, Lazy!(OptimizedMirEntry<'tcx>)>,
+    promoted_mir:
+        Table<DefIndex, Lazy!(MirEntry<IndexVec<mir::Promoted, mir::BodyAndCache<'tcx>>>)>,
+    mir_hash: Table<DefIndex, Lazy<Fingerprint>>,
 }
 
 #[derive(Copy, Clone, RustcEncodable, RustcDecodable)]
@@ -312,6 +315,25 @@ enum EntryKind {
     TraitAlias,
 }
 
+/// The MIR of a body (or of its promoteds). MIR repeats the same few spans in many places, so
+/// each distinct span is only stored once in `spans`, and the spans in `body` are encoded as
+/// indices into it.
+#[derive(RustcEncodable, RustcDecodable)]
+struct MirEntry<T> {
+    body: Lazy<T>,
+    spans: Lazy<[Span]>,
+}
+
+/// The optimized MIR of a body. Unlike in a `MirEntry`, each basic block is stored on its own, so
+/// that the blocks can be decoded one at a time, see the `optimized_mir_block` query. `body` holds
+/// the rest of the body, with no basic blocks, and shares `spans` with them.
+#[derive(RustcEncodable, RustcDecodable)]
+struct OptimizedMirEntry<'tcx> {
+    blocks: Lazy<[Lazy<mir::BasicBlockData<'tcx>>]>,
+    body: Lazy<mir::BodyAndCache<'tcx>>,
+    spans: Lazy<[Span]>,
+}
+
 /// Contains a constant which has been rendered to a String.
 /// Used by rustdoc.
 #[derive(RustcEncodable, RustcDecodable)]
@@ -1,18 +1,21 @@
 use rustc::mir::{
     self, AggregateKind, BindingForm, BorrowKind, ClearCrossCrate, ConstraintCategory,
-    FakeReadCause, Local, LocalDecl, LocalInfo, LocalKind, Location, Operand, Place, PlaceRef,
-    ProjectionElem, Rvalue, Statement, StatementKind, TerminatorKind, VarBindingForm,
+    FakeReadCause, Local, LocalDecl, LocalInfo, LocalKind, Location, MatchedPlace, Operand, Place,
+    PlaceRef, ProjectionElem, Rvalue, Statement, StatementKind, TerminatorKind, VarBindingForm,
 };
+use rustc::mir::visit::{MutatingUseContext, PlaceContext, Visitor};
 use rustc::ty::{self, Ty};
 use rustc_data_structures::fx::FxHashSet;
 use rustc_errors::{Applicability, DiagnosticBuilder};
 use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
 use rustc_hir::{AsyncGeneratorKind, GeneratorKind};
+use rustc_index::bit_set::BitSet;
 use rustc_index::vec::Idx;
 use rustc_infer::traits::error_reporting::suggest_constraining_type_param;
+use rustc_infer::traits::type_known_to_meet_bound_modulo_regions;
 use rustc_span::source_map::DesugaringKind;
-use rustc_span::Span;
+use rustc_span::{Span, Symbol};
 
 use crate::dataflow::drop_flag_effects;
 use crate::dataflow::indexes::{MoveOutIndex, MovePathIndex};
@@ -27,6 +30,30 @@ use super::{
     explain_borrow::BorrowExplanation, IncludingDowncast, RegionName, RegionNameSource, UseSpans,
 };
 
+/// What a closure needs of one of the variables it captures, for it to take ownership of its
+/// captures with the `move` keyword.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(super) enum CaptureNeed {
+    /// The variable can be moved into the closure: it is not used after the closure is created,
+    /// or it is `Copy`.
+    Move,
+    /// The variable is used after the closure is created, but it can be cloned before, and the
+    /// clone moved into the closure.
+    Clone,
+    /// The variable is used after the closure is created and can't be cloned, so the closure has
+    /// to share it, e.g. through an `Rc`.
+    Share,
+}
+
+/// A variable captured by a closure, with what the closure needs of it.
+#[derive(Debug)]
+pub(super) struct MinimalCapture {
+    pub(super) name: Symbol,
+    /// A use of the variable after the closure is created.
+    pub(super) later_use: Option<Span>,
+    pub(super) need: CaptureNeed,
+}
+
 #[derive(Debug)]
 struct MoveSite {
     /// Index of the "move out" that we found. The `MoveData` can
@@ -52,7 +79,7 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
         location: Location,
         desired_action: InitializationRequiringAction,
         (moved_place, used_place, span): (PlaceRef<'cx, 'tcx>, PlaceRef<'cx, 'tcx>, Span),
-        mpi: MovePathIndex,
+        mpi: Option<MovePathIndex>,
     ) {
         debug!(
             "report_use_of_moved_or_uninitialized: location={:?} desired_action={:?} \
@@ -64,7 +91,11 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
             self.move_spans(moved_place, location).or_else(|| self.borrow_spans(span, location));
         let span = use_spans.args_or_use();
 
-        let move_site_vec = self.get_moved_indexes(location, mpi);
+        let move_site_vec = match mpi {
+            Some(mpi) => self.get_moved_indexes(location, mpi),
+            // Without a move path, the place was never initialized, let alone moved.
+            None => vec![],
+        };
         debug!("report_use_of_moved_or_uninitialized: move_site_vec={:?}", move_site_vec);
         let move_out_indices: Vec<_> =
             move_site_vec.iter().map(|move_site| move_site.moi).collect();
@@ -420,8 +451,11 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
 
             (BorrowKind::Mut { .. }, BorrowKind::Shallow)
             | (BorrowKind::Unique, BorrowKind::Shallow) => {
-                if let Some(immutable_section_description) =
-                    self.classify_immutable_section(&issued_borrow.assigned_place)
+                if let Some((immutable_section_description, guard_arm)) =
+                    self.classify_immutable_section(
+                        &issued_borrow.assigned_place,
+                        issued_borrow.reserve_location,
+                    )
                 {
                     let mut err = self.cannot_mutate_in_immutable_section(
                         span,
@@ -430,6 +464,13 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                         immutable_section_description,
                         "mutably borrow",
                     );
+                    if let Some(guard) = guard_arm {
+                        self.note_guard_of_fake_borrow(
+                            &mut err,
+                            &issued_borrow.borrowed_place,
+                            guard,
+                        );
+                    }
                     borrow_spans.var_span_label(
                         &mut err,
                         format!(
@@ -802,6 +843,7 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                 },
             ) if borrow_spans.for_closure() => self.report_escaping_closure_capture(
                 borrow_spans,
+                borrow.reserve_location,
                 borrow_span,
                 region_name,
                 category,
@@ -819,6 +861,7 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                 },
             ) if borrow_spans.for_generator() => self.report_escaping_closure_capture(
                 borrow_spans,
+                borrow.reserve_location,
                 borrow_span,
                 region_name,
                 category,
@@ -1203,6 +1246,7 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
     fn report_escaping_closure_capture(
         &mut self,
         use_span: UseSpans,
+        borrow_location: Location,
         var_span: Span,
         fr_name: &RegionName,
         category: ConstraintCategory,
@@ -1237,6 +1281,35 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
             },
             None => "closure",
         };
+
+        // `move` also moves the other captures, which breaks the uses of the ones that are used
+        // after the closure is created.
+        let captures = self
+            .closure_location(borrow_location)
+            .and_then(|location| self.minimal_capture_set(location))
+            .unwrap_or_default();
+        let mut applicability = Applicability::MachineApplicable;
+        for capture in &captures {
+            let later_use = match (capture.need, capture.later_use) {
+                (CaptureNeed::Move, _) | (_, None) => continue,
+                (_, Some(later_use)) => later_use,
+            };
+            applicability = Applicability::MaybeIncorrect;
+            let msg = match capture.need {
+                CaptureNeed::Clone => format!(
+                    "`{0}` is used here after the {1}, so it can't be moved into the {1}: clone \
+                     it before the {1}, with `let {0} = {0}.clone();`, to move the clone instead",
+                    capture.name, kind
+                ),
+                _ => format!(
+                    "`{0}` is used here after the {1}, so it can't be moved into the {1}, and it \
+                     can't be cloned: it has to be shared with the {1}, e.g. with `Rc` or `Arc`",
+                    capture.name, kind
+                ),
+            };
+            err.span_help(later_use, &msg);
+        }
+
         err.span_suggestion(
             args_span,
             &format!(
@@ -1245,7 +1318,7 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                 kind, captured_var
             ),
             suggestion,
-            Applicability::MachineApplicable,
+            applicability,
         );
 
         let msg = match category {
@@ -1265,6 +1338,120 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
         err
     }
 
+    /// Returns the location creating the closure that the borrow at `borrow_location` is captured
+    /// by, if any.
+    fn closure_location(&self, borrow_location: Location) -> Option<Location> {
+        let statements = &self.body[borrow_location.block].statements;
+        (borrow_location.statement_index + 1..statements.len())
+            .find(|&statement_index| match &statements[statement_index].kind {
+                StatementKind::Assign(box (_, Rvalue::Aggregate(kind, _))) => match **kind {
+                    AggregateKind::Closure(..) | AggregateKind::Generator(..) => true,
+                    _ => false,
+                },
+                _ => false,
+            })
+            .map(|statement_index| Location { block: borrow_location.block, statement_index })
+    }
+
+    /// Returns what the closure created at `location` needs of each of the variables it captures,
+    /// for it to take ownership of its captures with the `move` keyword. `None` if the captures
+    /// are not all local variables of the current function.
+    pub(super) fn minimal_capture_set(&self, location: Location) -> Option<Vec<MinimalCapture>> {
+        let tcx = self.infcx.tcx;
+        let statement = self.body[location.block].statements.get(location.statement_index)?;
+        let (def_id, operands) = match &statement.kind {
+            StatementKind::Assign(box (_, Rvalue::Aggregate(kind, operands))) => match **kind {
+                AggregateKind::Closure(def_id, _) | AggregateKind::Generator(def_id, ..) => {
+                    (def_id, operands)
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let param_env = tcx.param_env(self.mir_def_id);
+        let clone_trait = tcx.lang_items().clone_trait();
+
+        tcx.upvars(def_id)?
+            .iter()
+            .zip(operands)
+            .map(|((&var_hir_id, upvar), operand)| {
+                let local = self.captured_local(location, operand)?;
+                let ty = self.body.local_decls[local].ty;
+                let later_use = self.first_use_after(local, location);
+                let need = if later_use.is_none()
+                    || self.infcx.type_is_copy_modulo_regions(param_env, ty, upvar.span)
+                {
+                    CaptureNeed::Move
+                } else if clone_trait.map_or(false, |clone_trait| {
+                    type_known_to_meet_bound_modulo_regions(
+                        self.infcx,
+                        param_env,
+                        ty,
+                        clone_trait,
+                        upvar.span,
+                    )
+                }) {
+                    CaptureNeed::Clone
+                } else {
+                    CaptureNeed::Share
+                };
+                Some(MinimalCapture { name: tcx.hir().name(var_hir_id), later_use, need })
+            })
+            .collect()
+    }
+
+    /// Returns the local captured through `operand` by the closure created at `location`: the
+    /// operand itself for a capture by value, and the borrowed local for a capture by reference.
+    fn captured_local(&self, location: Location, operand: &Operand<'tcx>) -> Option<Local> {
+        let local = match operand {
+            Operand::Copy(place) | Operand::Move(place) => place.as_local()?,
+            Operand::Constant(_) => return None,
+        };
+        if self.body.local_kind(local) != LocalKind::Temp {
+            return Some(local);
+        }
+        let statements = &self.body[location.block].statements[..location.statement_index];
+        statements.iter().rev().find_map(|statement| match &statement.kind {
+            StatementKind::Assign(box (temp, Rvalue::Ref(_, _, borrowed)))
+                if temp.as_local() == Some(local) =>
+            {
+                Some(borrowed.as_local())
+            }
+            _ => None,
+        })?
+    }
+
+    /// Returns the span of a use of `local` that may happen after `location`, if any. Assigning
+    /// or dropping the local doesn't count, as both are allowed after moving it.
+    fn first_use_after(&self, local: Local, location: Location) -> Option<Span> {
+        struct UseFinder {
+            local: Local,
+            uses: Vec<Location>,
+        }
+
+        impl<'tcx> Visitor<'tcx> for UseFinder {
+            fn visit_local(&mut self, &local: &Local, context: PlaceContext, location: Location) {
+                match context {
+                    PlaceContext::NonUse(_)
+                    | PlaceContext::MutatingUse(MutatingUseContext::Store)
+                    | PlaceContext::MutatingUse(MutatingUseContext::Drop) => {}
+                    _ if local == self.local => self.uses.push(location),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut finder = UseFinder { local, uses: vec![] };
+        finder.visit_body(self.body);
+        finder
+            .uses
+            .into_iter()
+            .find(|&use_location| {
+                use_location != location && location.is_predecessor_of(use_location, self.body)
+            })
+            .map(|use_location| self.body.source_info(use_location).span)
+    }
+
     fn report_escaping_data(
         &mut self,
         borrow_span: Span,
@@ -1338,9 +1525,19 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                 // worry about the other case: that is, if there is a move of a.b.c, it is already
                 // marked as a move of a.b and a as well, so we will generate the correct errors
                 // there.
+                //
+                // Moves out of places inside of a union field also move out of the other fields
+                // of the union, so we look for moves of those places as well.
                 let mut mpis = vec![mpi];
                 let move_paths = &self.move_data.move_paths;
                 mpis.extend(move_paths[mpi].parents(move_paths).map(|(mpi, _)| mpi));
+                drop_flag_effects::on_overlapping_union_fields_bits(
+                    self.infcx.tcx,
+                    &self.body,
+                    self.move_data,
+                    mpi,
+                    |overlapping| mpis.push(overlapping),
+                );
 
                 for moi in &self.move_data.loc_map[location] {
                     debug!("report_use_of_moved_or_uninitialized: moi={:?}", moi);
@@ -1405,7 +1602,9 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
         let loan_span = loan_spans.args_or_use();
 
         if loan.kind == BorrowKind::Shallow {
-            if let Some(section) = self.classify_immutable_section(&loan.assigned_place) {
+            if let Some((section, guard_arm)) =
+                self.classify_immutable_section(&loan.assigned_place, loan.reserve_location)
+            {
                 let mut err = self.cannot_mutate_in_immutable_section(
                     span,
                     loan_span,
@@ -1413,6 +1612,9 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                     section,
                     "assign",
                 );
+                if let Some(guard) = guard_arm {
+                    self.note_guard_of_fake_borrow(&mut err, &loan.borrowed_place, guard);
+                }
                 loan_spans.var_span_label(
                     &mut err,
                     format!("borrow occurs due to use{}", loan_spans.describe()),
@@ -1568,9 +1770,17 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
         }
     }
 
-    /// Describe the reason for the fake borrow that was assigned to `place`.
-    fn classify_immutable_section(&self, place: &Place<'tcx>) -> Option<&'static str> {
-        use rustc::mir::visit::Visitor;
+    /// Describe the reason for the fake borrow that was assigned to `place` at `location`.
+    ///
+    /// For match guards, also returns the arm whose guard the fake borrow was taken for, and how
+    /// the patterns of the `match` use the borrowed place. Each guard takes its own fake borrows,
+    /// which are read at the end of the guard, so this follows the CFG from `location` to the
+    /// first such read of `place`.
+    fn classify_immutable_section(
+        &self,
+        place: &Place<'tcx>,
+        location: Location,
+    ) -> Option<(&'static str, Option<(u16, MatchedPlace)>)> {
         struct FakeReadCauseFinder<'a, 'tcx> {
             place: &'a Place<'tcx>,
             cause: Option<FakeReadCause>,
@@ -1590,12 +1800,67 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
         let mut visitor = FakeReadCauseFinder { place, cause: None };
         visitor.visit_body(self.body);
         match visitor.cause {
-            Some(FakeReadCause::ForMatchGuard) => Some("match guard"),
-            Some(FakeReadCause::ForIndex) => Some("indexing expression"),
+            Some(FakeReadCause::ForMatchGuard(..)) => {
+                Some(("match guard", self.find_guard_of_fake_borrow(place, location)))
+            }
+            Some(FakeReadCause::ForIndex) => Some(("indexing expression", None)),
             _ => None,
         }
     }
 
+    /// Finds the `FakeRead(ForMatchGuard(..))` of the fake borrow assigned to `place` at
+    /// `location`, without going through another assignment to `place`.
+    fn find_guard_of_fake_borrow(
+        &self,
+        place: &Place<'tcx>,
+        location: Location,
+    ) -> Option<(u16, MatchedPlace)> {
+        let mut visited = BitSet::new_empty(self.body.basic_blocks().len());
+        let mut stack = vec![location.successor_within_block()];
+        'blocks: while let Some(location) = stack.pop() {
+            let block = &self.body[location.block];
+            for statement in &block.statements[location.statement_index..] {
+                match statement.kind {
+                    StatementKind::FakeRead(FakeReadCause::ForMatchGuard(arm, matched), box read)
+                        if read == *place =>
+                    {
+                        return Some((arm, matched));
+                    }
+                    StatementKind::Assign(box (assigned, _)) if assigned == *place => {
+                        continue 'blocks;
+                    }
+                    _ => {}
+                }
+            }
+            for &successor in block.terminator().successors() {
+                if visited.insert(successor) {
+                    stack.push(successor.start_location());
+                }
+            }
+        }
+        None
+    }
+
+    /// Notes which guard the fake borrow of `borrowed_place` was taken for, and why.
+    fn note_guard_of_fake_borrow(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        borrowed_place: &Place<'tcx>,
+        (arm, matched): (u16, MatchedPlace),
+    ) {
+        let borrowed = self.describe_place(borrowed_place.as_ref()).unwrap_or_else(|| "_".into());
+        let uses = match matched {
+            MatchedPlace::Tested => "test",
+            MatchedPlace::Dereferenced => "look behind",
+        };
+        err.note(&format!(
+            "this happens in the guard of match arm {}, and the patterns of the match {} `{}`",
+            usize::from(arm) + 1,
+            uses,
+            borrowed,
+        ));
+    }
+
     /// Annotate argument and return type of function and closure with (synthesized) lifetime for
     /// borrow of local value that does not live long enough.
     fn annotate_argument_and_return_for_borrow(
@@ -47,6 +47,9 @@ pub(in crate::borrow_check) enum LaterUseKind {
     ClosureCapture,
     Call,
     FakeLetRead,
+    /// Read by a by-value binding at the end of the guard of a match arm, see
+    /// `FakeReadCause::ForGuardBinding`.
+    GuardBinding { arm: u16, binding: Symbol },
     Other,
 }
 
@@ -68,11 +71,20 @@ impl BorrowExplanation {
     ) {
         match *self {
             BorrowExplanation::UsedLater(later_use_kind, var_or_use_span) => {
+                let guard_binding_message;
                 let message = match later_use_kind {
                     LaterUseKind::TraitCapture => "captured here by trait object",
                     LaterUseKind::ClosureCapture => "captured here by closure",
                     LaterUseKind::Call => "used by call",
                     LaterUseKind::FakeLetRead => "stored here",
+                    LaterUseKind::GuardBinding { arm, binding } => {
+                        guard_binding_message = format!(
+                            "used by the binding `{}` of match arm {}, after its guard",
+                            binding,
+                            usize::from(arm) + 1,
+                        );
+                        &guard_binding_message
+                    }
                     LaterUseKind::Other => "used here",
                 };
                 if !borrow_span.map(|sp| sp.overlaps(var_or_use_span)).unwrap_or(false) {
@@ -83,6 +95,7 @@ impl BorrowExplanation {
                 }
             }
             BorrowExplanation::UsedLaterInLoop(later_use_kind, var_or_use_span) => {
+                let guard_binding_message;
                 let message = match later_use_kind {
                     LaterUseKind::TraitCapture => {
                         "borrow captured here by trait object, in later iteration of loop"
@@ -92,6 +105,15 @@ impl BorrowExplanation {
                     }
                     LaterUseKind::Call => "borrow used by call, in later iteration of loop",
                     LaterUseKind::FakeLetRead => "borrow later stored here",
+                    LaterUseKind::GuardBinding { arm, binding } => {
+                        guard_binding_message = format!(
+                            "borrow used by the binding `{}` of match arm {}, \
+                             in later iteration of loop",
+                            binding,
+                            usize::from(arm) + 1,
+                        );
+                        &guard_binding_message
+                    }
                     LaterUseKind::Other => "borrow used here, in later iteration of loop",
                 };
                 err.span_label(var_or_use_span, format!("{}{}", borrow_desc, message));
@@ -517,12 +539,25 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
             UseSpans::OtherUse(span) => {
                 let block = &self.body.basic_blocks()[location.block];
 
+                let statement = block.statements.get(location.statement_index);
+                let guard_binding = match statement {
+                    Some(Statement {
+                        kind: StatementKind::FakeRead(FakeReadCause::ForGuardBinding(arm), place),
+                        ..
+                    }) => place
+                        .as_local()
+                        .and_then(|local| self.local_names[local])
+                        .map(|binding| (*arm, binding)),
+                    _ => None,
+                };
                 let kind = if let Some(&Statement {
                     kind: StatementKind::FakeRead(FakeReadCause::ForLet, _),
                     ..
-                }) = block.statements.get(location.statement_index)
+                }) = statement
                 {
                     LaterUseKind::FakeLetRead
+                } else if let Some((arm, binding)) = guard_binding {
+                    LaterUseKind::GuardBinding { arm, binding }
                 } else if self.was_captured_by_trait_object(borrow) {
                     LaterUseKind::TraitCapture
                 } else if location.statement_index == block.statements.len() {
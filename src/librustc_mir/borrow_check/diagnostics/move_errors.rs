@@ -319,6 +319,16 @@ impl<'a, 'tcx> MirBorrowckCtxt<'a, 'tcx> {
                     "variables bound in patterns cannot be moved from \
                      until after the end of the pattern guard",
                 );
+                if let Some(arm) = self.guard_binding_arm(local) {
+                    err.span_note(
+                        decl.source_info.span,
+                        &format!(
+                            "`{}` is bound by the pattern of match arm {}",
+                            self.local_names[local].unwrap(),
+                            usize::from(arm) + 1,
+                        ),
+                    );
+                }
                 return err;
             } else if decl.is_ref_to_static() {
                 return self.report_cannot_move_from_static(move_place, span);
@@ -546,4 +556,19 @@ impl<'a, 'tcx> MirBorrowckCtxt<'a, 'tcx> {
             );
         }
     }
+
+    /// Returns the match arm whose by-value binding is read through `ref_for_guard` at the end
+    /// of its guard, see `FakeReadCause::ForGuardBinding`.
+    fn guard_binding_arm(&self, ref_for_guard: Local) -> Option<u16> {
+        self.body.basic_blocks().iter().flat_map(|block| &block.statements).find_map(|statement| {
+            match statement.kind {
+                StatementKind::FakeRead(FakeReadCause::ForGuardBinding(arm), box read)
+                    if read.as_local() == Some(ref_for_guard) =>
+                {
+                    Some(arm)
+                }
+                _ => None,
+            }
+        })
+    }
 }
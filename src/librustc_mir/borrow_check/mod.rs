@@ -34,7 +34,7 @@ use rustc_span::{Span, DUMMY_SP};
 use crate::dataflow;
 use crate::dataflow::generic::{Analysis, BorrowckFlowState as Flows, BorrowckResults};
 use crate::dataflow::indexes::{BorrowIndex, InitIndex, MoveOutIndex, MovePathIndex};
-use crate::dataflow::move_paths::{InitLocation, LookupResult, MoveData, MoveError};
+use crate::dataflow::move_paths::{dump_move_paths, InitLocation, LookupResult, MoveData, MoveError};
 use crate::dataflow::Borrows;
 use crate::dataflow::EverInitializedPlaces;
 use crate::dataflow::MoveDataParamEnv;
@@ -87,7 +87,11 @@ crate struct Upvar {
 }
 
 pub fn provide(providers: &mut Providers<'_>) {
-    *providers = Providers { mir_borrowck, ..*providers };
+    *providers = Providers { mir_borrowck, unused_mut_locals, ..*providers };
+}
+
+fn unused_mut_locals(tcx: TyCtxt<'_>, def_id: DefId) -> &BitSet<Local> {
+    &tcx.mir_borrowck(def_id).unused_mut_locals
 }
 
 fn mir_borrowck(tcx: TyCtxt<'_>, def_id: DefId) -> &BorrowCheckResult<'_> {
@@ -118,7 +122,7 @@ fn do_mir_borrowck<'a, 'tcx>(
 
     let mut local_names = IndexVec::from_elem(None, &input_body.local_decls);
     for var_debug_info in &input_body.var_debug_info {
-        if let Some(local) = var_debug_info.place.as_local() {
+        if let Some(local) = var_debug_info.value.place().and_then(|place| place.as_local()) {
             if let Some(prev_name) = local_names[local] {
                 if var_debug_info.name != prev_name {
                     span_bug!(
@@ -185,8 +189,8 @@ fn do_mir_borrowck<'a, 'tcx>(
             Ok(move_data) => (move_data, None),
             Err((move_data, move_errors)) => (move_data, Some(move_errors)),
         };
-
     let mdpe = MoveDataParamEnv { move_data, param_env };
+    dump_move_paths(tcx, "borrowck", MirSource::item(def_id), &body, &mdpe);
 
     let mut flow_inits = MaybeInitializedPlaces::new(tcx, &body, &mdpe)
         .into_engine(tcx, &body, def_id)
@@ -355,28 +359,30 @@ fn do_mir_borrowck<'a, 'tcx>(
 
     debug!("mbcx.used_mut: {:?}", mbcx.used_mut);
     let used_mut = mbcx.used_mut;
+    let mut unused_mut_locals = BitSet::new_empty(mbcx.body.local_decls.len());
     for local in mbcx.body.mut_vars_and_args_iter().filter(|local| !used_mut.contains(local)) {
+        // Skip over locals that have no name, or whose `mut` arises as part of a desugaring.
+        if mbcx.local_names[local].is_none()
+            || mbcx.body.local_decls[local].source_info.span.desugaring_kind().is_some()
+        {
+            continue;
+        }
+        unused_mut_locals.insert(local);
+    }
+
+    for local in unused_mut_locals.iter() {
         let local_decl = &mbcx.body.local_decls[local];
         let lint_root = match &mbcx.body.source_scopes[local_decl.source_info.scope].local_data {
             ClearCrossCrate::Set(data) => data.lint_root,
             _ => continue,
         };
 
-        // Skip over locals that begin with an underscore or have no name
-        match mbcx.local_names[local] {
-            Some(name) => {
-                if name.as_str().starts_with('_') {
-                    continue;
-                }
-            }
-            None => continue,
+        // Skip over locals that begin with an underscore.
+        if mbcx.local_names[local].map_or(false, |name| name.as_str().starts_with('_')) {
+            continue;
         }
 
         let span = local_decl.source_info.span;
-        if span.desugaring_kind().is_some() {
-            // If the `mut` arises as part of a desugaring, we should ignore it.
-            continue;
-        }
 
         tcx.struct_span_lint_hir(UNUSED_MUT, lint_root, span, |lint| {
             let mut_span = tcx.sess.source_map().span_until_non_whitespace(span);
@@ -408,6 +414,7 @@ fn do_mir_borrowck<'a, 'tcx>(
         concrete_opaque_types: opaque_type_values,
         closure_requirements: opt_closure_req,
         used_mut_upvars: mbcx.used_mut_upvars,
+        unused_mut_locals,
     };
 
     debug!("do_mir_borrowck: result = {:#?}", result);
@@ -583,6 +590,9 @@ impl<'cx, 'tcx> dataflow::generic::ResultsVisitor<'cx, 'tcx> for MirBorrowckCtxt
                     self.consume_operand(location, (input, span), flow_state);
                 }
             }
+            StatementKind::Assume(box ref op) => {
+                self.consume_operand(location, (op, span), flow_state);
+            }
             StatementKind::Nop
             | StatementKind::AscribeUserType(..)
             | StatementKind::Retag { .. }
@@ -614,7 +624,13 @@ impl<'cx, 'tcx> dataflow::generic::ResultsVisitor<'cx, 'tcx> for MirBorrowckCtxt
         self.check_activations(loc, span, flow_state);
 
         match term.kind {
-            TerminatorKind::SwitchInt { ref discr, switch_ty: _, values: _, targets: _ } => {
+            TerminatorKind::SwitchInt {
+                ref discr,
+                switch_ty: _,
+                values: _,
+                targets: _,
+                target_weights: _,
+            } => {
                 self.consume_operand(loc, (discr, span), flow_state);
             }
             TerminatorKind::Drop { location: ref drop_place, target: _, unwind: _ } => {
@@ -1321,7 +1337,8 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                         // capture comes from and mark it as being used as mut.
 
                         let temp_mpi = self.move_data.rev_lookup.find_local(local);
-                        let init = if let [init_index] = *self.move_data.init_path_map[temp_mpi] {
+                        let inits = temp_mpi.map(|mpi| &*self.move_data.init_path_map[mpi]);
+                        let init = if let Some(&[init_index]) = inits {
                             &self.move_data.inits[init_index]
                         } else {
                             bug!("temporary should be initialized exactly once")
@@ -1568,7 +1585,8 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
 
         debug!("check_if_full_path_is_moved place: {:?}", place_span.0);
         let (prefix, mpi) = self.move_path_closest_to(place_span.0);
-        if maybe_uninits.contains(mpi) {
+        // A local without a move path is never initialized.
+        if mpi.map_or(true, |mpi| maybe_uninits.contains(mpi)) {
             self.report_use_of_moved_or_uninitialized(
                 location,
                 desired_action,
@@ -1617,7 +1635,7 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                                 location,
                                 desired_action,
                                 (place_span.0, place_span.0, place_span.1),
-                                uninit_child,
+                                Some(uninit_child),
                             );
                             return; // don't bother finding other problems.
                         }
@@ -1692,7 +1710,7 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                     location,
                     desired_action,
                     (place_span.0, place_span.0, place_span.1),
-                    uninit_mpi,
+                    Some(uninit_mpi),
                 );
                 return; // don't bother finding other problems.
             }
@@ -1706,18 +1724,20 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
     /// prefixes, searching for a foundational place that *is*
     /// tracked in the MoveData.
     ///
-    /// An Err result includes a tag indicated why the search failed.
-    /// Currently this can only occur if the place is built off of a
-    /// static variable, as we do not track those in the MoveData.
+    /// Returns the longest prefix of `place` that has a move path, and
+    /// that path. The search fails if the local of the place has no
+    /// move path, as move paths are only created for the locals that
+    /// are initialized, moved out of or dropped: the local alone is
+    /// then returned, with no path, and it is never initialized.
     fn move_path_closest_to(
         &mut self,
         place: PlaceRef<'_, 'tcx>,
-    ) -> (PlaceRef<'cx, 'tcx>, MovePathIndex) {
+    ) -> (PlaceRef<'cx, 'tcx>, Option<MovePathIndex>) {
         match self.move_data.rev_lookup.find(place) {
             LookupResult::Parent(Some(mpi)) | LookupResult::Exact(mpi) => {
-                (self.move_data.move_paths[mpi].place.as_ref(), mpi)
+                (self.move_data.move_paths[mpi].place.as_ref(), Some(mpi))
             }
-            LookupResult::Parent(None) => panic!("should have move path for every Local"),
+            LookupResult::Parent(None) => (PlaceRef { local: place.local, projection: &[] }, None),
         }
     }
 
@@ -1897,7 +1917,7 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
                     location,
                     InitializationRequiringAction::PartialAssignment,
                     (prefix, base, span),
-                    mpi,
+                    Some(mpi),
                 );
             }
         }
@@ -2018,7 +2038,7 @@ impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
         local: Local,
         flow_state: &Flows<'cx, 'tcx>,
     ) -> Option<InitIndex> {
-        let mpi = self.move_data.rev_lookup.find_local(local);
+        let mpi = self.move_data.rev_lookup.find_local(local)?;
         let ii = &self.move_data.init_path_map[mpi];
         for &index in ii {
             if flow_state.ever_inits.contains(index) {
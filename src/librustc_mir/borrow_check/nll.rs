@@ -2,8 +2,9 @@
 
 use rustc::mir::{
     BasicBlock, Body, BodyAndCache, ClosureOutlivesSubject, ClosureRegionRequirements, LocalKind,
-    Location, Promoted, ReadOnlyBodyAndCache,
+    Location, Promoted, ReadOnlyBodyAndCache, TerminatorKind,
 };
+use rustc::session::BorrowckStatsInfo;
 use rustc::ty::{self, RegionKind, RegionVid};
 use rustc_data_structures::fx::FxHashMap;
 use rustc_errors::Diagnostic;
@@ -17,6 +18,7 @@ use std::io;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::str::FromStr;
+use std::time::Instant;
 
 use self::mir_util::PassWhere;
 use polonius_engine::{Algorithm, Output};
@@ -87,7 +89,7 @@ fn populate_polonius_move_facts(
 ) {
     all_facts
         .path_is_var
-        .extend(move_data.rev_lookup.iter_locals_enumerated().map(|(v, &m)| (m, v)));
+        .extend(move_data.rev_lookup.iter_locals_enumerated().map(|(v, m)| (m, v)));
 
     for (child, move_path) in move_data.move_paths.iter_enumerated() {
         if let Some(parent) = move_path.parent {
@@ -121,6 +123,15 @@ fn populate_polonius_move_facts(
                             .path_assigned_at_base
                             .push((init.path, location_table.start_index(first_statement)));
                     }
+                } else if init.kind == InitKind::ResumePathOnly {
+                    // The resume argument of a yield is initialized when arriving at the resume
+                    // block, but not in the drop block.
+                    if let TerminatorKind::Yield { resume, .. } = block_data.terminator().kind {
+                        let first_statement = Location { block: resume, statement_index: 0 };
+                        all_facts
+                            .path_assigned_at_base
+                            .push((init.path, location_table.start_index(first_statement)));
+                    }
                 } else {
                     // In all other cases, the initialization just happens at the
                     // midpoint, like any other effect.
@@ -137,7 +148,7 @@ fn populate_polonius_move_facts(
         }
     }
 
-    for (local, &path) in move_data.rev_lookup.iter_locals_enumerated() {
+    for (local, path) in move_data.rev_lookup.iter_locals_enumerated() {
         if body.local_kind(local) != LocalKind::Arg {
             // Non-arguments start out deinitialised; we simulate this with an
             // initial move:
@@ -253,6 +264,9 @@ pub(in crate::borrow_check) fn compute_regions<'cx, 'tcx>(
         borrow_set,
     );
 
+    let region_vars = var_origins.len();
+    let constraints = outlives_constraints.outlives().len();
+
     let mut regioncx = RegionInferenceContext::new(
         var_origins,
         universal_regions,
@@ -291,9 +305,23 @@ pub(in crate::borrow_check) fn compute_regions<'cx, 'tcx>(
     });
 
     // Solve the region constraints.
+    let solve_start = Instant::now();
     let (closure_region_requirements, nll_errors) =
         regioncx.solve(infcx, &body, def_id, polonius_output.clone());
 
+    if infcx.tcx.sess.borrowck_stats() {
+        let (sccs, largest_scc) = regioncx.scc_sizes();
+        infcx.tcx.sess.code_stats.record_borrowck_stats(BorrowckStatsInfo {
+            body_description: infcx.tcx.def_path_str(def_id),
+            region_vars,
+            constraints,
+            sccs,
+            largest_scc,
+            liveness_points: elements.num_points(),
+            solve_time: solve_start.elapsed(),
+        });
+    }
+
     if !nll_errors.is_empty() {
         // Suppress unhelpful extra errors in `infer_opaque_types`.
         infcx.set_tainted_by_errors();
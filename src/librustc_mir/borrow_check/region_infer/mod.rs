@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::rc::Rc;
 
@@ -103,6 +104,17 @@ pub struct RegionInferenceContext<'tcx> {
     /// you first find which scc it is a part of.
     scc_values: RegionValues<ConstraintSccIndex>,
 
+    /// The results of `eval_outlives`, by SCC. Outlives relations are only evaluated once the
+    /// values of the SCCs are final, so a relation between two SCCs never changes, and is
+    /// often tested again, e.g. by the type tests of a generic type used many times.
+    scc_outlives_cache: RefCell<FxHashMap<(ConstraintSccIndex, ConstraintSccIndex), bool>>,
+
+    /// The breadth-first searches of the constraint graph, by the region they start from. They
+    /// are only needed to blame constraints when reporting an error or propagating a requirement
+    /// to our creator, so they only go as far as needed to find a match, and are shared by the
+    /// searches starting from the same region, until `MAX_CONSTRAINT_SEARCHES` are kept.
+    constraint_searches: RefCell<FxHashMap<RegionVid, Rc<RefCell<ConstraintSearch>>>>,
+
     /// Type constraints that we check after solving.
     type_tests: Vec<TypeTest<'tcx>>,
 
@@ -234,6 +246,20 @@ enum Trace {
     NotVisited,
 }
 
+/// The number of `ConstraintSearch`es kept by a `RegionInferenceContext`.
+const MAX_CONSTRAINT_SEARCHES: usize = 16;
+
+/// A breadth-first search of the constraint graph from some region, which
+/// is taken further as needed.
+struct ConstraintSearch {
+    /// How each region was first reached.
+    traces: IndexVec<RegionVid, Trace>,
+    /// The regions reached, in the order they were reached in.
+    order: Vec<RegionVid>,
+    /// The regions reached whose outgoing constraints were not walked yet.
+    queue: VecDeque<RegionVid>,
+}
+
 impl<'tcx> RegionInferenceContext<'tcx> {
     /// Creates a new region inference context with a total of
     /// `num_region_variables` valid inference variables; the first N
@@ -296,6 +322,8 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             scc_universes,
             scc_representatives,
             scc_values,
+            scc_outlives_cache: Default::default(),
+            constraint_searches: Default::default(),
             type_tests,
             universal_regions,
             universal_region_relations,
@@ -1187,6 +1215,23 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         let sub_region_scc = self.constraint_sccs.scc(sub_region);
         let sup_region_scc = self.constraint_sccs.scc(sup_region);
 
+        let key = (sup_region_scc, sub_region_scc);
+        if let Some(&outlives) = self.scc_outlives_cache.borrow().get(&key) {
+            return outlives;
+        }
+        let outlives = self.eval_scc_outlives(sup_region, sup_region_scc, sub_region_scc);
+        self.scc_outlives_cache.borrow_mut().insert(key, outlives);
+        outlives
+    }
+
+    // Evaluate whether `sup_region_scc: sub_region_scc`, where
+    // `sup_region` is part of `sup_region_scc`.
+    fn eval_scc_outlives(
+        &self,
+        sup_region: RegionVid,
+        sup_region_scc: ConstraintSccIndex,
+        sub_region_scc: ConstraintSccIndex,
+    ) -> bool {
         // Both the `sub_region` and `sup_region` consist of the union
         // of some number of universal regions (along with the union
         // of various points in the CFG; ignore those points for
@@ -1683,89 +1728,129 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         from_region: RegionVid,
         target_test: impl Fn(RegionVid) -> bool,
     ) -> Option<(Vec<OutlivesConstraint>, RegionVid)> {
-        let mut context = IndexVec::from_elem(Trace::NotVisited, &self.definitions);
-        context[from_region] = Trace::StartRegion;
-
-        // Use a deque so that we do a breadth-first search. We will
-        // stop at the first match, which ought to be the shortest
-        // path (fewest constraints).
-        let mut deque = VecDeque::new();
-        deque.push_back(from_region);
-
-        while let Some(r) = deque.pop_front() {
+        let search = self.constraint_search(from_region);
+
+        // The regions are reached in breadth-first order, so we stop at
+        // the first match, which ought to be the shortest path (fewest
+        // constraints). The regions reached by the earlier searches from
+        // `from_region` are tested first, and the search only goes on if
+        // none of them matches.
+        let mut index = 0;
+        let r = loop {
+            let r = self.nth_reached_region(&search, index)?;
+            index += 1;
             debug!(
                 "find_constraint_paths_between_regions: from_region={:?} r={:?} value={}",
                 from_region,
                 r,
                 self.region_value_str(r),
             );
-
-            // Check if we reached the region we were looking for. If so,
-            // we can reconstruct the path that led to it and return it.
             if target_test(r) {
-                let mut result = vec![];
-                let mut p = r;
-                loop {
-                    match context[p] {
-                        Trace::NotVisited => {
-                            bug!("found unvisited region {:?} on path to {:?}", p, r)
-                        }
-
-                        Trace::FromOutlivesConstraint(c) => {
-                            result.push(c);
-                            p = c.sup;
-                        }
-
-                        Trace::StartRegion => {
-                            result.reverse();
-                            return Some((result, r));
-                        }
-                    }
-                }
+                break r;
             }
+        };
 
-            // Otherwise, walk over the outgoing constraints and
-            // enqueue any regions we find, keeping track of how we
-            // reached them.
-
-            // A constraint like `'r: 'x` can come from our constraint
-            // graph.
-            let fr_static = self.universal_regions.fr_static;
-            let outgoing_edges_from_graph =
-                self.constraint_graph.outgoing_edges(r, &self.constraints, fr_static);
-
-            // Always inline this closure because it can be hot.
-            let mut handle_constraint = #[inline(always)]
-            |constraint: OutlivesConstraint| {
-                debug_assert_eq!(constraint.sup, r);
-                let sub_region = constraint.sub;
-                if let Trace::NotVisited = context[sub_region] {
-                    context[sub_region] = Trace::FromOutlivesConstraint(constraint);
-                    deque.push_back(sub_region);
+        // We reached the region we were looking for, so we can
+        // reconstruct the path that led to it and return it.
+        let search = search.borrow();
+        let mut result = vec![];
+        let mut p = r;
+        loop {
+            match search.traces[p] {
+                Trace::NotVisited => bug!("found unvisited region {:?} on path to {:?}", p, r),
+
+                Trace::FromOutlivesConstraint(c) => {
+                    result.push(c);
+                    p = c.sup;
                 }
-            };
 
-            // This loop can be hot.
-            for constraint in outgoing_edges_from_graph {
-                handle_constraint(constraint);
+                Trace::StartRegion => {
+                    result.reverse();
+                    return Some((result, r));
+                }
             }
+        }
+    }
 
-            // Member constraints can also give rise to `'r: 'x` edges that
-            // were not part of the graph initially, so watch out for those.
-            // (But they are extremely rare; this loop is very cold.)
-            for constraint in self.applied_member_constraints(r) {
-                let p_c = &self.member_constraints[constraint.member_constraint_index];
-                let constraint = OutlivesConstraint {
-                    sup: r,
-                    sub: constraint.min_choice,
-                    locations: Locations::All(p_c.definition_span),
-                    category: ConstraintCategory::OpaqueType,
-                };
-                handle_constraint(constraint);
+    /// Returns the breadth-first search of the constraint graph from
+    /// `from_region`, as far as the earlier searches from it went.
+    fn constraint_search(&self, from_region: RegionVid) -> Rc<RefCell<ConstraintSearch>> {
+        if let Some(search) = self.constraint_searches.borrow().get(&from_region) {
+            return search.clone();
+        }
+
+        let mut traces = IndexVec::from_elem(Trace::NotVisited, &self.definitions);
+        traces[from_region] = Trace::StartRegion;
+        let mut queue = VecDeque::new();
+        queue.push_back(from_region);
+        let search = Rc::new(RefCell::new(ConstraintSearch { traces, order: vec![], queue }));
+
+        // Each search takes memory linear in the number of regions, so
+        // only the latest ones are kept.
+        let mut searches = self.constraint_searches.borrow_mut();
+        if searches.len() == MAX_CONSTRAINT_SEARCHES {
+            searches.clear();
+        }
+        searches.insert(from_region, search.clone());
+        search
+    }
+
+    /// Returns the `index`-th region reached by `search`, taking the
+    /// search one region further if needed, or `None` if it reaches
+    /// fewer regions.
+    fn nth_reached_region(
+        &self,
+        search: &RefCell<ConstraintSearch>,
+        index: usize,
+    ) -> Option<RegionVid> {
+        let mut search = search.borrow_mut();
+        if let Some(&r) = search.order.get(index) {
+            return Some(r);
+        }
+        let ConstraintSearch { traces, order, queue } = &mut *search;
+        let r = queue.pop_front()?;
+        order.push(r);
+
+        // Walk over the outgoing constraints and enqueue any
+        // regions we find, keeping track of how we reached them.
+
+        // A constraint like `'r: 'x` can come from our constraint
+        // graph.
+        let fr_static = self.universal_regions.fr_static;
+        let outgoing_edges_from_graph =
+            self.constraint_graph.outgoing_edges(r, &self.constraints, fr_static);
+
+        // Always inline this closure because it can be hot.
+        let mut handle_constraint = #[inline(always)]
+        |constraint: OutlivesConstraint| {
+            debug_assert_eq!(constraint.sup, r);
+            let sub_region = constraint.sub;
+            if let Trace::NotVisited = traces[sub_region] {
+                traces[sub_region] = Trace::FromOutlivesConstraint(constraint);
+                queue.push_back(sub_region);
             }
+        };
+
+        // This loop can be hot.
+        for constraint in outgoing_edges_from_graph {
+            handle_constraint(constraint);
         }
 
-        None
+        // Member constraints can also give rise to `'r: 'x` edges that
+        // were not part of the graph initially, so watch out for those.
+        // (But they are extremely rare; this loop is very cold.)
+        for constraint in self.applied_member_constraints(r) {
+            let p_c = &self.member_constraints[constraint.member_constraint_index];
+            let constraint = OutlivesConstraint {
+                sup: r,
+                sub: constraint.min_choice,
+                locations: Locations::All(p_c.definition_span),
+                category: ConstraintCategory::OpaqueType,
+            };
+            handle_constraint(constraint);
+        }
+
+        Some(r)
     }
 
     /// Finds some region R such that `fr1: R` and `R` is live at `elem`.
@@ -1822,6 +1907,17 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         }
     }
 
+    /// Returns the number of SCCs, and the number of regions in the largest
+    /// one, for `-Z borrowck-stats`.
+    crate fn scc_sizes(&self) -> (usize, usize) {
+        let mut sizes: IndexVec<ConstraintSccIndex, usize> =
+            IndexVec::from_elem_n(0, self.constraint_sccs.num_sccs());
+        for r in self.definitions.indices() {
+            sizes[self.constraint_sccs.scc(r)] += 1;
+        }
+        (sizes.len(), sizes.iter().copied().max().unwrap_or(0))
+    }
+
     /// Get the region definition of `r`.
     crate fn region_definition(&self, r: RegionVid) -> &RegionDefinition<'tcx> {
         &self.definitions[r]
@@ -228,7 +228,11 @@ impl LivenessResults<'me, 'typeck, 'flow, 'tcx> {
     fn compute_drop_live_points_for(&mut self, local: Local) {
         debug!("compute_drop_live_points_for(local={:?})", local);
 
-        let mpi = self.cx.move_data.rev_lookup.find_local(local);
+        // A local without a move path is never initialized, so its drops are no-ops.
+        let mpi = match self.cx.move_data.rev_lookup.find_local(local) {
+            Some(mpi) => mpi,
+            None => return,
+        };
         debug!("compute_drop_live_points_for: mpi = {:?}", mpi);
 
         // Find the drops where `local` is initialized.
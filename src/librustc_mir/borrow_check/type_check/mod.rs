@@ -1523,6 +1523,12 @@ impl<'a, 'tcx> TypeChecker<'a, 'tcx> {
                     );
                 }
             }
+            StatementKind::Assume(box ref op) => {
+                let op_ty = op.ty(*body, tcx);
+                if op_ty != tcx.types.bool {
+                    span_mirbug!(self, stmt, "bad Assume operand type: {:?}, expected bool", op_ty);
+                }
+            }
             StatementKind::FakeRead(..)
             | StatementKind::StorageLive(..)
             | StatementKind::StorageDead(..)
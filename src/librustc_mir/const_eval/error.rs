@@ -5,13 +5,19 @@ use rustc::mir::AssertKind;
 use rustc_span::Symbol;
 
 use super::InterpCx;
-use crate::interpret::{ConstEvalErr, InterpError, InterpErrorInfo, Machine};
+use crate::interpret::{
+    ConstEvalErr, InterpError, InterpErrorInfo, Machine, UndefinedBehaviorInfo, UnsupportedOpInfo,
+};
 
 /// The CTFE machine has some custom error kinds.
 #[derive(Clone, Debug)]
 pub enum ConstEvalErrKind {
     NeedsRfc(String),
     ConstAccessesStatic,
+    /// A function pointer that points to a function which is not a `const fn` was called.
+    NonConstFnPtrCall(String),
+    /// A method of a trait object whose impl of that method is not a `const fn` was called.
+    NonConstTraitObjectCall(String),
     AssertFailure(AssertKind<u64>),
     Panic { msg: Symbol, line: u32, col: u32, file: Symbol },
 }
@@ -33,6 +39,12 @@ impl fmt::Display for ConstEvalErrKind {
                 write!(f, "\"{}\" needs an rfc before being allowed inside constants", msg)
             }
             ConstAccessesStatic => write!(f, "constant accesses static"),
+            NonConstFnPtrCall(ref callee) => {
+                write!(f, "calling non-const function `{}` through a function pointer", callee)
+            }
+            NonConstTraitObjectCall(ref callee) => {
+                write!(f, "calling non-const function `{}` through a trait object", callee)
+            }
             AssertFailure(ref msg) => write!(f, "{:?}", msg),
             Panic { msg, line, col, file } => {
                 write!(f, "the evaluated program panicked at '{}', {}:{}:{}", msg, file, line, col)
@@ -52,5 +64,36 @@ pub fn error_to_const_error<'mir, 'tcx, M: Machine<'mir, 'tcx>>(
 ) -> ConstEvalErr<'tcx> {
     error.print_backtrace();
     let stacktrace = ecx.generate_stacktrace(None);
-    ConstEvalErr { error: error.kind, stacktrace, span: ecx.tcx.span }
+    let alloc_history = match ecx.memory.last_accessed_alloc() {
+        Some(id) if is_memory_error(&error.kind) => ecx.memory.alloc_history(id).to_vec(),
+        _ => vec![],
+    };
+    ConstEvalErr { error: error.kind, stacktrace, span: ecx.tcx.span, alloc_history }
+}
+
+/// Whether `error` is caused by the state of the allocation accessed last, so that the history
+/// of that allocation helps explaining it.
+fn is_memory_error(error: &InterpError<'_>) -> bool {
+    use UnsupportedOpInfo::*;
+    match error {
+        InterpError::UndefinedBehavior(UndefinedBehaviorInfo::InvalidDiscriminant(_)) => true,
+        InterpError::Unsupported(info) => match info {
+            DanglingPointerDeref
+            | DoubleFree
+            | InvalidMemoryAccess
+            | InvalidBool
+            | InvalidChar(_)
+            | PointerOutOfBounds { .. }
+            | ReadPointerAsBytes
+            | ReadBytesAsPointer
+            | ReadUndefBytes(_)
+            | UnterminatedCString(_)
+            | ModifiedConstantMemory
+            | DeallocatedWrongMemoryKind(..)
+            | IncorrectAllocationInformation(..)
+            | DeallocateNonBasePtr => true,
+            _ => false,
+        },
+        _ => false,
+    }
 }
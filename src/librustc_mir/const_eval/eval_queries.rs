@@ -241,7 +241,8 @@ pub fn const_eval_validated_provider<'tcx>(
         };
         return eval_nullary_intrinsic(tcx, key.param_env, def_id, substs).map_err(|error| {
             let span = tcx.def_span(def_id);
-            let error = ConstEvalErr { error: error.kind, stacktrace: vec![], span };
+            let error =
+                ConstEvalErr { error: error.kind, stacktrace: vec![], span, alloc_history: vec![] };
             error.report_as_error(tcx.at(span), "could not evaluate nullary intrinsic")
         });
     }
@@ -1,19 +1,21 @@
 use rustc::mir;
-use rustc::ty::layout::HasTyCtxt;
+use rustc::ty::layout::{HasTyCtxt, LayoutOf};
+use rustc::ty::subst::Subst;
 use rustc::ty::{self, Ty};
 use std::borrow::{Borrow, Cow};
 use std::collections::hash_map::Entry;
 use std::convert::TryFrom;
 use std::hash::Hash;
 
+use rustc_ast::ast::FloatTy;
 use rustc_data_structures::fx::FxHashMap;
 
 use rustc::mir::AssertMessage;
 use rustc_span::source_map::Span;
-use rustc_span::symbol::Symbol;
+use rustc_span::symbol::{sym, Symbol};
 
 use crate::interpret::{
-    self, snapshot, AllocId, Allocation, GlobalId, ImmTy, InterpCx, InterpResult, Memory,
+    self, snapshot, AllocId, Allocation, GlobalId, ImmTy, InterpCx, InterpResult, MPlaceTy, Memory,
     MemoryKind, OpTy, PlaceTy, Pointer, Scalar,
 };
 
@@ -59,6 +61,40 @@ impl<'mir, 'tcx> InterpCx<'mir, 'tcx, CompileTimeInterpreter<'mir, 'tcx>> {
         return Ok(true);
     }
 
+    /// Returns the type of the callee of the call being evaluated, if any.
+    fn callee_ty(&self) -> Option<Ty<'tcx>> {
+        let frame = self.stack().last()?;
+        let block = frame.block?;
+        match &frame.body[block].terminator().kind {
+            mir::TerminatorKind::Call { func, .. } => Some(func.ty(frame.body, *self.tcx)),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the function about to be called is called through a function pointer,
+    /// rather than by name.
+    fn is_fn_ptr_call(&self) -> bool {
+        match self.callee_ty().map(|ty| &ty.kind) {
+            Some(ty::FnPtr(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the function about to be called is the method of a trait object, found
+    /// in its vtable.
+    fn is_trait_object_call(&self) -> bool {
+        match self.callee_ty().map(|ty| &ty.kind) {
+            Some(&ty::FnDef(def_id, substs)) => {
+                self.tcx.trait_of_item(def_id).is_some()
+                    && match substs.type_at(0).kind {
+                        ty::Dynamic(..) => true,
+                        _ => false,
+                    }
+            }
+            _ => false,
+        }
+    }
+
     /// "Intercept" a function call to a panic-related function
     /// because we have something special to do for it.
     /// If this returns successfully (`Ok`), the function should just be evaluated normally.
@@ -80,10 +116,96 @@ impl<'mir, 'tcx> InterpCx<'mir, 'tcx, CompileTimeInterpreter<'mir, 'tcx>> {
             let span = self.find_closest_untracked_caller_location().unwrap_or(span);
             let (file, line, col) = self.location_triple_for_span(span);
             Err(ConstEvalErrKind::Panic { msg, file, line, col }.into())
+        } else if Some(def_id) == self.tcx.lang_items().panic_fmt_fn()
+            || Some(def_id) == self.tcx.lang_items().begin_panic_fmt_fn()
+        {
+            // `core` passes the `fmt::Arguments` by value, `std` by reference.
+            assert!(!args.is_empty());
+            let fmt_args = match args[0].layout.ty.kind {
+                ty::Ref(..) => self.deref_operand(args[0])?.into(),
+                _ => args[0],
+            };
+            let msg = Symbol::intern(&self.render_fmt_arguments(fmt_args)?);
+            let span = self.find_closest_untracked_caller_location().unwrap_or(span);
+            let (file, line, col) = self.location_triple_for_span(span);
+            Err(ConstEvalErrKind::Panic { msg, file, line, col }.into())
         } else {
             Ok(())
         }
     }
+
+    /// Renders the message of the `fmt::Arguments` built by `format_args!`, as the panic handler
+    /// would print it. Only the arguments of primitive types and `str`, with the default format
+    /// specification, are rendered; the other placeholders are kept as `{}`.
+    fn render_fmt_arguments(&self, fmt_args: OpTy<'tcx>) -> InterpResult<'tcx, String> {
+        let pieces = self.deref_operand(self.operand_field(fmt_args, 0)?)?;
+        // `fmt` is `None` when all the placeholders use the default format specification.
+        let has_specs = self.read_discriminant(self.operand_field(fmt_args, 1)?)?.0 != 0;
+        let args = self.deref_operand(self.operand_field(fmt_args, 2)?)?;
+        let args_len = args.len(self)?;
+
+        let pieces_len = pieces.len(self)?;
+
+        let mut msg = String::new();
+        for index in 0..pieces_len {
+            let piece = self.deref_operand(self.mplace_field(pieces, index)?.into())?;
+            msg.push_str(self.read_str(piece)?);
+            if !has_specs && index < args_len {
+                match self.render_fmt_argument(self.mplace_field(args, index)?)? {
+                    Some(arg) => msg.push_str(&arg),
+                    None => msg.push_str("{}"),
+                }
+            } else if has_specs && index + 1 < pieces_len {
+                msg.push_str("{}");
+            }
+        }
+        Ok(msg)
+    }
+
+    /// Renders a `fmt::ArgumentV1`, if it formats a value of a primitive type or `str` with
+    /// `Display` or `Debug`.
+    fn render_fmt_argument(&self, arg: MPlaceTy<'tcx>) -> InterpResult<'tcx, Option<String>> {
+        let formatter = self.read_scalar(self.mplace_field(arg, 1)?.into())?.not_undef()?;
+        let instance = self.memory.get_fn(formatter)?.as_instance()?;
+        let tcx = *self.tcx;
+        let trait_ref = match tcx.impl_of_method(instance.def_id()) {
+            Some(impl_def_id) => match tcx.impl_trait_ref(impl_def_id) {
+                Some(trait_ref) => trait_ref.subst(tcx, instance.substs),
+                None => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+        let debug = Some(trait_ref.def_id) == tcx.get_diagnostic_item(sym::debug_trait);
+        if !debug && Some(trait_ref.def_id) != tcx.get_diagnostic_item(sym::display_trait) {
+            return Ok(None);
+        }
+
+        // The argument points to the value formatted, and references are formatted as the
+        // values they point to.
+        let value_ref_ty = tcx.mk_imm_ref(tcx.lifetimes.re_static, trait_ref.self_ty());
+        let value = self.read_scalar(self.mplace_field(arg, 0)?.into())?.not_undef()?;
+        let value = ImmTy::from_scalar(value, self.layout_of(value_ref_ty)?);
+        let mut place = self.ref_to_mplace(value)?;
+        while let ty::Ref(..) = place.layout.ty.kind {
+            place = self.deref_operand(place.into())?;
+        }
+        Ok(match place.layout.ty.kind {
+            ty::Str if debug => Some(format!("{:?}", self.read_str(place)?)),
+            ty::Str => Some(self.read_str(place)?.to_owned()),
+            ty::Char if debug => Some(format!("{:?}", self.read_scalar(place.into())?.to_char()?)),
+            // `Debug` always shows a fractional part, unlike `Display`.
+            ty::Float(FloatTy::F32) if debug => {
+                Some(format!("{:?}", f32::from_bits(self.read_scalar(place.into())?.to_u32()?)))
+            }
+            ty::Float(FloatTy::F64) if debug => {
+                Some(format!("{:?}", f64::from_bits(self.read_scalar(place.into())?.to_u64()?)))
+            }
+            ty::Int(_) | ty::Uint(_) | ty::Float(_) | ty::Bool | ty::Char => {
+                Some(self.read_immediate(place.into())?.to_string())
+            }
+            _ => None,
+        })
+    }
 }
 
 /// The number of steps between loop detector snapshots.
@@ -231,6 +353,17 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir,
                 // Some functions we support even if they are non-const -- but avoid testing
                 // that for const fn!
                 ecx.hook_panic_fn(span, instance, args)?;
+                // Calls through function pointers are only checked here, as the pointer's target
+                // is only known during evaluation.
+                if ecx.is_fn_ptr_call() {
+                    return Err(ConstEvalErrKind::NonConstFnPtrCall(instance.to_string()).into());
+                }
+                // So are the calls of the methods of trait objects, whose impl is only known
+                // from the vtable.
+                if ecx.is_trait_object_call() {
+                    let callee = instance.to_string();
+                    return Err(ConstEvalErrKind::NonConstTraitObjectCall(callee).into());
+                }
                 // We certainly do *not* want to actually call the fn
                 // though, so be sure we return here.
                 throw_unsup_format!("calling non-const function `{}`", instance)
@@ -44,6 +44,9 @@ where
 /// In both cases, the contents can only be accessed if and only if
 /// their parents are initialized. This implies for example that there
 /// is no need to maintain separate drop flags to track such state.
+///
+/// The fields of unions are tracked, even though they overlap. See
+/// `on_overlapping_union_fields_bits`.
 //
 // FIXME: we have to do something for moving slice patterns.
 fn place_contents_drop_state_cannot_differ<'tcx>(
@@ -67,7 +70,7 @@ fn place_contents_drop_state_cannot_differ<'tcx>(
             );
             true
         }
-        ty::Adt(def, _) if (def.has_dtor(tcx) && !def.is_box()) || def.is_union() => {
+        ty::Adt(def, _) if def.has_dtor(tcx) && !def.is_box() => {
             debug!(
                 "place_contents_drop_state_cannot_differ place: {:?} ty: {:?} Drop => true",
                 place, ty
@@ -137,6 +140,67 @@ pub(crate) fn on_all_children_bits<'tcx, F>(
     on_all_children_bits(tcx, body, move_data, move_path_index, &mut each_child);
 }
 
+/// Calls `each_child` on the paths overlapping `move_path_index` that are neither its parents
+/// nor its children, i.e., on the other fields of the unions it is inside of, and on all their
+/// children.
+///
+/// Moving out of a place inside of a union field also moves out of these.
+pub(crate) fn on_overlapping_union_fields_bits<'tcx, F>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    move_data: &MoveData<'tcx>,
+    move_path_index: MovePathIndex,
+    mut each_child: F,
+) where
+    F: FnMut(MovePathIndex),
+{
+    let mut path = move_path_index;
+    while let Some(parent) = move_data.move_paths[path].parent {
+        if is_union(tcx, body, move_data, parent) {
+            for (field, _) in move_data.move_paths[parent].children(&move_data.move_paths) {
+                if field != path {
+                    on_all_children_bits(tcx, body, move_data, field, &mut each_child);
+                }
+            }
+        }
+        path = parent;
+    }
+}
+
+fn is_union<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    move_data: &MoveData<'tcx>,
+    path: MovePathIndex,
+) -> bool {
+    match move_data.move_paths[path].place.ty(body, tcx).ty.kind {
+        ty::Adt(def, _) => def.is_union(),
+        _ => false,
+    }
+}
+
+/// Returns `true` if `path` is inside of a field of a union that is `root` or one of its
+/// children. Dropping a union never drops its fields.
+fn is_inside_union<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    move_data: &MoveData<'tcx>,
+    root: MovePathIndex,
+    mut path: MovePathIndex,
+) -> bool {
+    while path != root {
+        let parent = match move_data.move_paths[path].parent {
+            Some(parent) => parent,
+            None => return false,
+        };
+        if is_union(tcx, body, move_data, parent) {
+            return true;
+        }
+        path = parent;
+    }
+    false
+}
+
 pub(crate) fn on_all_drop_children_bits<'tcx, F>(
     tcx: TyCtxt<'tcx>,
     body: &Body<'tcx>,
@@ -146,7 +210,13 @@ pub(crate) fn on_all_drop_children_bits<'tcx, F>(
 ) where
     F: FnMut(MovePathIndex),
 {
-    on_all_children_bits(tcx, body, &ctxt.move_data, path, |child| {
+    let move_data = &ctxt.move_data;
+    on_all_children_bits(tcx, body, move_data, path, |child| {
+        if is_inside_union(tcx, body, move_data, path, child) {
+            debug!("on_all_drop_children_bits - skipping union field {:?}", child);
+            return;
+        }
+
         let place = &ctxt.move_data.move_paths[path].place;
         let ty = place.ty(body, tcx).ty;
         debug!("on_all_drop_children_bits({:?}, {:?} : {:?})", path, place, ty);
@@ -195,7 +265,12 @@ pub(crate) fn drop_flag_effects_for_location<'tcx, F>(
         let path = mi.move_path_index(move_data);
         debug!("moving out of path {:?}", move_data.move_paths[path]);
 
-        on_all_children_bits(tcx, body, move_data, path, |mpi| callback(mpi, DropFlagState::Absent))
+        on_all_children_bits(tcx, body, move_data, path, |mpi| {
+            callback(mpi, DropFlagState::Absent)
+        });
+        on_overlapping_union_fields_bits(tcx, body, move_data, path, |mpi| {
+            callback(mpi, DropFlagState::Absent)
+        });
     }
 
     debug!("drop_flag_effects: assignment for location({:?})", loc);
@@ -224,7 +299,7 @@ pub(crate) fn for_location_inits<'tcx, F>(
                 let mpi = init.path;
                 callback(mpi);
             }
-            InitKind::NonPanicPathOnly => (),
+            InitKind::NonPanicPathOnly | InitKind::ResumePathOnly => (),
         }
     }
 }
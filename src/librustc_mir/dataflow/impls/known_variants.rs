@@ -0,0 +1,203 @@
+//! An analysis tracking which variant of an enum local is known to be active, because it was just
+//! matched on, or assigned.
+
+use std::ops::Range;
+
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::{self, Body, Local, Location, Place, Rvalue, StatementKind};
+use rustc::ty;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::{Idx, IndexVec};
+use rustc_target::abi::VariantIdx;
+
+use crate::dataflow::generic::{Analysis, AnalysisDomain};
+use crate::dataflow::BottomValue;
+
+rustc_index::newtype_index! {
+    /// The fact that some enum local holds some variant.
+    pub struct VariantFact {
+        DEBUG_FORMAT = "vf{}"
+    }
+}
+
+/// A dataflow analysis whose state holds, for the enum locals it tracks, which variant is
+/// definitely active. The variant becomes known along the edges of switches on the discriminant
+/// of a local, and by assigning an aggregate or setting the discriminant. Copying a local copies
+/// what is known about it, and any other write forgets it.
+///
+/// Locals whose address is taken are not tracked, as they could be changed through pointers.
+pub struct KnownVariants {
+    /// The facts about each tracked local, one for each of its variants.
+    facts: IndexVec<Local, Option<Range<usize>>>,
+    num_facts: usize,
+}
+
+impl KnownVariants {
+    pub fn new(body: &Body<'_>) -> Self {
+        let mut borrowed = BitSet::new_empty(body.local_decls.len());
+        for data in body.basic_blocks() {
+            for statement in &data.statements {
+                if let StatementKind::Assign(box (_, rvalue)) = &statement.kind {
+                    match rvalue {
+                        Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place)
+                            if !place.is_indirect() =>
+                        {
+                            borrowed.insert(place.local);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        let mut num_facts = 0;
+        let facts = body
+            .local_decls
+            .iter_enumerated()
+            .map(|(local, decl)| match decl.ty.kind {
+                ty::Adt(adt_def, _) if adt_def.is_enum() && !borrowed.contains(local) => {
+                    let start = num_facts;
+                    num_facts += adt_def.variants.len();
+                    Some(start..num_facts)
+                }
+                _ => None,
+            })
+            .collect();
+        KnownVariants { facts, num_facts }
+    }
+
+    /// Returns the variant `place` is known to hold in `state`.
+    pub fn known_variant(
+        &self,
+        state: &BitSet<VariantFact>,
+        place: &Place<'_>,
+    ) -> Option<VariantIdx> {
+        let facts = self.facts[place.as_local()?].clone()?;
+        let start = facts.start;
+        facts.map(VariantFact::new).find(|&fact| state.contains(fact)).map(|fact| {
+            VariantIdx::new(fact.index() - start)
+        })
+    }
+
+    fn forget(&self, state: &mut BitSet<VariantFact>, local: Local) {
+        if let Some(facts) = self.facts[local].clone() {
+            for fact in facts {
+                state.remove(VariantFact::new(fact));
+            }
+        }
+    }
+
+    fn set_variant(&self, state: &mut BitSet<VariantFact>, local: Local, variant: VariantIdx) {
+        if let Some(facts) = self.facts[local].clone() {
+            self.forget(state, local);
+            state.insert(VariantFact::new(facts.start + variant.index()));
+        }
+    }
+}
+
+impl BottomValue for KnownVariants {
+    // bottom = every variant is known, so that the facts are intersected at joins
+    const BOTTOM_VALUE: bool = true;
+}
+
+impl AnalysisDomain<'tcx> for KnownVariants {
+    type Idx = VariantFact;
+
+    const NAME: &'static str = "known_variants";
+
+    fn bits_per_block(&self, _: &Body<'tcx>) -> usize {
+        self.num_facts
+    }
+
+    fn initialize_start_block(&self, _: &Body<'tcx>, state: &mut BitSet<Self::Idx>) {
+        // Nothing is known about the arguments.
+        state.clear();
+    }
+}
+
+impl Analysis<'tcx> for KnownVariants {
+    fn apply_statement_effect(
+        &self,
+        state: &mut BitSet<VariantFact>,
+        statement: &mir::Statement<'tcx>,
+        location: Location,
+    ) {
+        ForgetWritten { analysis: self, state }.visit_statement(statement, location);
+        match &statement.kind {
+            StatementKind::Assign(box (place, rvalue)) => {
+                let dest = match place.as_local() {
+                    Some(dest) => dest,
+                    None => return,
+                };
+                match rvalue {
+                    Rvalue::Aggregate(box mir::AggregateKind::Adt(_, variant, ..), _) => {
+                        self.set_variant(state, dest, *variant);
+                    }
+                    Rvalue::Use(operand) => {
+                        let source = operand.place();
+                        if let Some(variant) = source.and_then(|s| self.known_variant(state, s)) {
+                            self.set_variant(state, dest, variant);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            StatementKind::SetDiscriminant { place, variant_index } => {
+                if let Some(local) = place.as_local() {
+                    self.set_variant(state, local, *variant_index);
+                }
+            }
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                self.forget(state, *local);
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        state: &mut BitSet<VariantFact>,
+        terminator: &mir::Terminator<'tcx>,
+        location: Location,
+    ) {
+        ForgetWritten { analysis: self, state }.visit_terminator(terminator, location);
+    }
+
+    fn apply_call_return_effect(
+        &self,
+        _state: &mut BitSet<VariantFact>,
+        _block: mir::BasicBlock,
+        _func: &mir::Operand<'tcx>,
+        _args: &[mir::Operand<'tcx>],
+        _return_place: &Place<'tcx>,
+    ) {
+        // The return place was forgotten by the terminator effect already.
+    }
+
+    fn apply_discriminant_switch_effect(
+        &self,
+        state: &mut BitSet<VariantFact>,
+        _block: mir::BasicBlock,
+        enum_place: &Place<'tcx>,
+        _adt: &ty::AdtDef,
+        variant: VariantIdx,
+    ) {
+        if let Some(local) = enum_place.as_local() {
+            self.set_variant(state, local, variant);
+        }
+    }
+}
+
+/// Forgets the variants of the locals written to.
+struct ForgetWritten<'a> {
+    analysis: &'a KnownVariants,
+    state: &'a mut BitSet<VariantFact>,
+}
+
+impl Visitor<'_> for ForgetWritten<'_> {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if context.is_mutating_use() {
+            self.analysis.forget(self.state, local);
+        }
+    }
+}
@@ -22,10 +22,14 @@ use super::on_lookup_result_bits;
 use crate::dataflow::drop_flag_effects;
 
 mod borrowed_locals;
+mod known_variants;
 mod storage_liveness;
+mod taint;
 
 pub use self::borrowed_locals::*;
+pub use self::known_variants::*;
 pub use self::storage_liveness::*;
+pub use self::taint::*;
 
 pub(super) mod borrows;
 
@@ -339,6 +343,24 @@ impl<'tcx> GenKillAnalysis<'tcx> for MaybeInitializedPlaces<'_, 'tcx> {
         );
     }
 
+    fn yield_resume_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        _block: mir::BasicBlock,
+        resume_place: &mir::Place<'tcx>,
+    ) {
+        // The resume argument is initialized when the generator is resumed.
+        on_lookup_result_bits(
+            self.tcx,
+            self.body,
+            self.move_data(),
+            self.move_data().rev_lookup.find(resume_place.as_ref()),
+            |mpi| {
+                trans.gen(mpi);
+            },
+        );
+    }
+
     fn discriminant_switch_effect(
         &self,
         trans: &mut impl GenKill<Self::Idx>,
@@ -440,6 +462,24 @@ impl<'tcx> GenKillAnalysis<'tcx> for MaybeUninitializedPlaces<'_, 'tcx> {
             },
         );
     }
+
+    fn yield_resume_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        _block: mir::BasicBlock,
+        resume_place: &mir::Place<'tcx>,
+    ) {
+        // The resume argument is initialized when the generator is resumed.
+        on_lookup_result_bits(
+            self.tcx,
+            self.body,
+            self.move_data(),
+            self.move_data().rev_lookup.find(resume_place.as_ref()),
+            |mpi| {
+                trans.kill(mpi);
+            },
+        );
+    }
 }
 
 impl<'a, 'tcx> AnalysisDomain<'tcx> for DefinitelyInitializedPlaces<'a, 'tcx> {
@@ -509,6 +549,24 @@ impl<'tcx> GenKillAnalysis<'tcx> for DefinitelyInitializedPlaces<'_, 'tcx> {
             },
         );
     }
+
+    fn yield_resume_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        _block: mir::BasicBlock,
+        resume_place: &mir::Place<'tcx>,
+    ) {
+        // The resume argument is initialized when the generator is resumed.
+        on_lookup_result_bits(
+            self.tcx,
+            self.body,
+            self.move_data(),
+            self.move_data().rev_lookup.find(resume_place.as_ref()),
+            |mpi| {
+                trans.gen(mpi);
+            },
+        );
+    }
 }
 
 impl<'tcx> AnalysisDomain<'tcx> for EverInitializedPlaces<'_, 'tcx> {
@@ -549,12 +607,13 @@ impl<'tcx> GenKillAnalysis<'tcx> for EverInitializedPlaces<'_, 'tcx> {
             mir::StatementKind::StorageDead(local) => {
                 // End inits for StorageDead, so that an immutable variable can
                 // be reinitialized on the next iteration of the loop.
-                let move_path_index = rev_lookup.find_local(local);
-                debug!(
-                    "stmt {:?} at loc {:?} clears the ever initialized status of {:?}",
-                    stmt, location, &init_path_map[move_path_index]
-                );
-                trans.kill_all(init_path_map[move_path_index].iter().copied());
+                if let Some(move_path_index) = rev_lookup.find_local(local) {
+                    debug!(
+                        "stmt {:?} at loc {:?} clears the ever initialized status of {:?}",
+                        stmt, location, &init_path_map[move_path_index]
+                    );
+                    trans.kill_all(init_path_map[move_path_index].iter().copied());
+                }
             }
             _ => {}
         }
@@ -576,8 +635,9 @@ impl<'tcx> GenKillAnalysis<'tcx> for EverInitializedPlaces<'_, 'tcx> {
         trans.gen_all(
             init_loc_map[location]
                 .iter()
-                .filter(|init_index| {
-                    move_data.inits[**init_index].kind != InitKind::NonPanicPathOnly
+                .filter(|init_index| match move_data.inits[**init_index].kind {
+                    InitKind::NonPanicPathOnly | InitKind::ResumePathOnly => false,
+                    InitKind::Deep | InitKind::Shallow => true,
                 })
                 .copied(),
         );
@@ -599,6 +659,21 @@ impl<'tcx> GenKillAnalysis<'tcx> for EverInitializedPlaces<'_, 'tcx> {
             trans.gen(*init_index);
         }
     }
+
+    fn yield_resume_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        block: mir::BasicBlock,
+        _resume_place: &mir::Place<'tcx>,
+    ) {
+        let move_data = self.move_data();
+        let init_loc_map = &move_data.init_loc_map;
+
+        let yield_loc = self.body.terminator_loc(block);
+        for init_index in &init_loc_map[yield_loc] {
+            trans.gen(*init_index);
+        }
+    }
 }
 
 impl<'a, 'tcx> BottomValue for MaybeInitializedPlaces<'a, 'tcx> {
@@ -0,0 +1,386 @@
+//! A taint analysis, tracking which locals may hold data derived from untrusted input.
+//!
+//! The functions involved are marked with attributes:
+//!
+//! - `#[rustc_taint_source]` functions return tainted data, and taint whatever the references
+//!   passed to them point to, like the buffer passed to a `read` method.
+//! - `#[rustc_taint_sanitizer]` functions return untainted data, whatever they are passed.
+//! - `#[rustc_taint_sink]` functions must not be passed tainted data, see `tainted_sink_calls`.
+//!
+//! Taint propagates through assignments and field projections, at the granularity of locals:
+//! writing tainted data to a field taints the whole local, and reading any field of a tainted
+//! local is tainted. References and raw pointers are tainted if the locals they may point to are.
+//! The return values of calls to local functions are tainted according to summaries of the
+//! callees, see `TaintSummaries`. Calls to other functions are assumed to return tainted data if
+//! any of their arguments is tainted.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rustc::mir::traversal;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::{self, BasicBlock, Body, Local, Location, Operand, Place, Rvalue, SourceInfo};
+use rustc::mir::{StatementKind, TerminatorKind};
+use rustc::ty::{self, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::DefId;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_span::symbol::{sym, Symbol};
+
+use crate::dataflow::generic::{Analysis, AnalysisDomain};
+use crate::dataflow::BottomValue;
+
+/// How data flows from the arguments of a local function to its return value.
+#[derive(Clone, Debug)]
+pub struct TaintSummary {
+    /// Whether the function may return tainted data even if none of its arguments is tainted.
+    pub returns_taint: bool,
+    /// For every argument, whether the function may return data derived from it.
+    pub arg_flows_to_return: Vec<bool>,
+}
+
+/// Computes and caches the `TaintSummary`s of the local functions called by the analyzed code.
+///
+/// Summaries are computed from the optimized MIR of the callees. The function the analysis is
+/// started from is never summarized, so that calls back into it do not require its own MIR.
+pub struct TaintSummaries<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// `None` while the summary is being computed, so that recursive calls fall back to the
+    /// assumptions made for functions without summaries.
+    summaries: RefCell<FxHashMap<DefId, Option<Rc<TaintSummary>>>>,
+}
+
+impl TaintSummaries<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>, root: DefId) -> Self {
+        let mut summaries = FxHashMap::default();
+        summaries.insert(root, None);
+        TaintSummaries { tcx, summaries: RefCell::new(summaries) }
+    }
+
+    /// Returns the summary of the function `def_id`, or `None` if it cannot be summarized.
+    pub fn summary(&self, def_id: DefId) -> Option<Rc<TaintSummary>> {
+        // Calls to trait methods may resolve to any impl.
+        if !def_id.is_local()
+            || !self.tcx.is_mir_available(def_id)
+            || self.tcx.trait_of_item(def_id).is_some()
+        {
+            return None;
+        }
+        if let Some(summary) = self.summaries.borrow().get(&def_id) {
+            return summary.clone();
+        }
+
+        self.summaries.borrow_mut().insert(def_id, None);
+        let body = self.tcx.optimized_mir(def_id);
+        let summary = Rc::new(TaintSummary {
+            returns_taint: self.returns_taint(def_id, body, None),
+            arg_flows_to_return: body
+                .args_iter()
+                .map(|arg| self.returns_taint(def_id, body, Some(arg)))
+                .collect(),
+        });
+        debug!("taint summary of {:?}: {:?}", def_id, summary);
+        self.summaries.borrow_mut().insert(def_id, Some(summary.clone()));
+        Some(summary)
+    }
+
+    /// Whether `body` may return tainted data if only `tainted_arg` is tainted on entry.
+    fn returns_taint(&self, def_id: DefId, body: &Body<'tcx>, tainted_arg: Option<Local>) -> bool {
+        let mut analysis = MaybeTaintedLocals::new(self.tcx, body, self);
+        analysis.tainted_on_entry = tainted_arg;
+        let mut cursor = analysis
+            .into_engine(self.tcx, body, def_id)
+            .iterate_to_fixpoint()
+            .into_results_cursor(body);
+        body.basic_blocks().iter_enumerated().any(|(block, data)| {
+            if data.terminator().kind != TerminatorKind::Return {
+                return false;
+            }
+            cursor.seek_before(body.terminator_loc(block));
+            cursor.contains(mir::RETURN_PLACE)
+        })
+    }
+}
+
+/// A dataflow analysis whose state holds the locals which may hold tainted data.
+pub struct MaybeTaintedLocals<'a, 'mir, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'mir Body<'tcx>,
+    summaries: &'a TaintSummaries<'tcx>,
+    /// For every local holding a reference or raw pointer, the locals it may point to.
+    pointees: IndexVec<Local, Vec<Local>>,
+    /// The argument tainted on entry, when computing summaries.
+    tainted_on_entry: Option<Local>,
+}
+
+impl MaybeTaintedLocals<'a, 'mir, 'tcx> {
+    pub fn new(
+        tcx: TyCtxt<'tcx>,
+        body: &'mir Body<'tcx>,
+        summaries: &'a TaintSummaries<'tcx>,
+    ) -> Self {
+        let mut pointees = IndexVec::from_elem(vec![], &body.local_decls);
+        for (_, data) in traversal::reverse_postorder(body) {
+            for statement in &data.statements {
+                let (place, rvalue) = match &statement.kind {
+                    StatementKind::Assign(box (place, rvalue)) => (place, rvalue),
+                    _ => continue,
+                };
+                let dest = match place.as_local() {
+                    Some(dest) => dest,
+                    None => continue,
+                };
+                let targets = match rvalue {
+                    Rvalue::Ref(_, _, borrowed) | Rvalue::AddressOf(_, borrowed) => {
+                        if borrowed.is_indirect() {
+                            pointees[borrowed.local].clone()
+                        } else {
+                            vec![borrowed.local]
+                        }
+                    }
+                    Rvalue::Use(operand) | Rvalue::Cast(_, operand, _) => match operand.place() {
+                        Some(source) if source.as_local().is_some() => {
+                            pointees[source.local].clone()
+                        }
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                for target in targets {
+                    if target != dest && !pointees[dest].contains(&target) {
+                        pointees[dest].push(target);
+                    }
+                }
+            }
+        }
+        MaybeTaintedLocals { tcx, body, summaries, pointees, tainted_on_entry: None }
+    }
+
+    fn is_tainted(&self, state: &BitSet<Local>, local: Local) -> bool {
+        state.contains(local) || self.pointees[local].iter().any(|&pointee| state.contains(pointee))
+    }
+
+    pub fn operand_is_tainted(&self, state: &BitSet<Local>, operand: &Operand<'tcx>) -> bool {
+        let mut reads = TaintedReads { analysis: self, state, tainted: false };
+        reads.visit_operand(operand, Location::START);
+        reads.tainted
+    }
+
+    fn rvalue_is_tainted(&self, state: &BitSet<Local>, rvalue: &Rvalue<'tcx>) -> bool {
+        let mut reads = TaintedReads { analysis: self, state, tainted: false };
+        reads.visit_rvalue(rvalue, Location::START);
+        reads.tainted
+    }
+
+    fn write(&self, state: &mut BitSet<Local>, place: &Place<'tcx>, tainted: bool) {
+        if let Some(local) = place.as_local() {
+            // Overwriting a whole local removes its taint.
+            if tainted {
+                state.insert(local);
+            } else {
+                state.remove(local);
+            }
+        } else if tainted {
+            // Writing to part of a local, or through a pointer, only ever adds taint.
+            state.insert(place.local);
+            if place.is_indirect() {
+                self.taint_pointees(state, place.local);
+            }
+        }
+    }
+
+    fn taint_pointees(&self, state: &mut BitSet<Local>, local: Local) {
+        for &pointee in &self.pointees[local] {
+            state.insert(pointee);
+        }
+    }
+
+    fn callee_has_attr(&self, func: &Operand<'tcx>, attr: Symbol) -> bool {
+        callee(self.tcx, self.body, func).map_or(false, |def_id| self.tcx.has_attr(def_id, attr))
+    }
+}
+
+impl BottomValue for MaybeTaintedLocals<'_, '_, '_> {
+    // bottom = untainted
+    const BOTTOM_VALUE: bool = false;
+}
+
+impl AnalysisDomain<'tcx> for MaybeTaintedLocals<'_, '_, 'tcx> {
+    type Idx = Local;
+
+    const NAME: &'static str = "maybe_tainted_locals";
+
+    fn bits_per_block(&self, body: &Body<'tcx>) -> usize {
+        body.local_decls.len()
+    }
+
+    fn initialize_start_block(&self, _: &Body<'tcx>, state: &mut BitSet<Self::Idx>) {
+        if let Some(arg) = self.tainted_on_entry {
+            state.insert(arg);
+        }
+    }
+}
+
+impl Analysis<'tcx> for MaybeTaintedLocals<'_, '_, 'tcx> {
+    fn apply_statement_effect(
+        &self,
+        state: &mut BitSet<Local>,
+        statement: &mir::Statement<'tcx>,
+        _: Location,
+    ) {
+        match &statement.kind {
+            StatementKind::Assign(box (place, rvalue)) => {
+                let tainted = self.rvalue_is_tainted(state, rvalue);
+                self.write(state, place, tainted);
+            }
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                state.remove(*local);
+            }
+            StatementKind::InlineAsm(asm) => {
+                if asm.inputs.iter().any(|(_, input)| self.operand_is_tainted(state, input)) {
+                    for output in asm.outputs.iter() {
+                        self.write(state, output, true);
+                    }
+                }
+            }
+            StatementKind::FakeRead(..)
+            | StatementKind::SetDiscriminant { .. }
+            | StatementKind::Retag(..)
+            | StatementKind::AscribeUserType(..)
+            | StatementKind::Assume(..)
+            | StatementKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        state: &mut BitSet<Local>,
+        terminator: &mir::Terminator<'tcx>,
+        _: Location,
+    ) {
+        match &terminator.kind {
+            TerminatorKind::DropAndReplace { location, value, .. } => {
+                let tainted = self.operand_is_tainted(state, value);
+                self.write(state, location, tainted);
+            }
+            TerminatorKind::Call { func, args, .. } => {
+                // Sources fill the buffers passed to them. Other functions may store tainted
+                // arguments in memory the other arguments point to.
+                let taints_args = self.callee_has_attr(func, sym::rustc_taint_source)
+                    || (!self.callee_has_attr(func, sym::rustc_taint_sanitizer)
+                        && args.iter().any(|arg| self.operand_is_tainted(state, arg)));
+                if taints_args {
+                    for arg in args {
+                        if let Some(place) = arg.place() {
+                            self.taint_pointees(state, place.local);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_call_return_effect(
+        &self,
+        state: &mut BitSet<Local>,
+        _block: BasicBlock,
+        func: &Operand<'tcx>,
+        args: &[Operand<'tcx>],
+        return_place: &Place<'tcx>,
+    ) {
+        let tainted = if self.callee_has_attr(func, sym::rustc_taint_source) {
+            true
+        } else if self.callee_has_attr(func, sym::rustc_taint_sanitizer) {
+            false
+        } else {
+            let summary = callee(self.tcx, self.body, func)
+                .and_then(|def_id| self.summaries.summary(def_id));
+            match summary {
+                Some(summary) => {
+                    summary.returns_taint
+                        || args.iter().zip(&summary.arg_flows_to_return).any(|(arg, &flows)| {
+                            flows && self.operand_is_tainted(state, arg)
+                        })
+                }
+                None => args.iter().any(|arg| self.operand_is_tainted(state, arg)),
+            }
+        };
+        self.write(state, return_place, tainted);
+    }
+}
+
+/// Determines whether any of the locals visited is tainted.
+struct TaintedReads<'a, 'b, 'mir, 'tcx> {
+    analysis: &'a MaybeTaintedLocals<'b, 'mir, 'tcx>,
+    state: &'a BitSet<Local>,
+    tainted: bool,
+}
+
+impl Visitor<'tcx> for TaintedReads<'_, '_, '_, 'tcx> {
+    fn visit_local(&mut self, &local: &Local, _: PlaceContext, _: Location) {
+        self.tainted |= self.analysis.is_tainted(self.state, local);
+    }
+}
+
+/// A call passing tainted data to a `#[rustc_taint_sink]` function.
+#[derive(Copy, Clone, Debug)]
+pub struct TaintedSinkCall {
+    pub source_info: SourceInfo,
+    pub sink: DefId,
+    /// The index of the tainted argument.
+    pub arg: usize,
+}
+
+/// Finds the calls in `body` which pass tainted data to a sink. Lints checking that untrusted
+/// input is sanitized can be built on top of this.
+pub fn tainted_sink_calls<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    def_id: DefId,
+) -> Vec<TaintedSinkCall> {
+    let sink_calls: Vec<_> = body
+        .basic_blocks()
+        .iter_enumerated()
+        .filter_map(|(block, data)| {
+            let terminator = data.terminator();
+            match &terminator.kind {
+                TerminatorKind::Call { func, args, .. } => {
+                    let sink = callee(tcx, body, func)?;
+                    if !tcx.has_attr(sink, sym::rustc_taint_sink) {
+                        return None;
+                    }
+                    Some((block, terminator.source_info, sink, args))
+                }
+                _ => None,
+            }
+        })
+        .collect();
+    if sink_calls.is_empty() {
+        return vec![];
+    }
+
+    let summaries = TaintSummaries::new(tcx, def_id);
+    let mut cursor = MaybeTaintedLocals::new(tcx, body, &summaries)
+        .into_engine(tcx, body, def_id)
+        .iterate_to_fixpoint()
+        .into_results_cursor(body);
+    let mut tainted_calls = vec![];
+    for (block, source_info, sink, args) in sink_calls {
+        cursor.seek_before(body.terminator_loc(block));
+        for (arg, operand) in args.iter().enumerate() {
+            if cursor.analysis().operand_is_tainted(cursor.get(), operand) {
+                tainted_calls.push(TaintedSinkCall { source_info, sink, arg });
+            }
+        }
+    }
+    tainted_calls
+}
+
+fn callee<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, func: &Operand<'tcx>) -> Option<DefId> {
+    match func.ty(body, tcx).kind {
+        ty::FnDef(def_id, _) => Some(def_id),
+        _ => None,
+    }
+}
@@ -22,9 +22,13 @@ pub(crate) use self::drop_flag_effects::*;
 pub use self::impls::borrows::Borrows;
 pub use self::impls::DefinitelyInitializedPlaces;
 pub use self::impls::EverInitializedPlaces;
+pub use self::impls::KnownVariants;
 pub use self::impls::{MaybeBorrowedLocals, MaybeMutBorrowedLocals};
 pub use self::impls::{MaybeInitializedPlaces, MaybeUninitializedPlaces};
 pub use self::impls::{MaybeRequiresStorage, MaybeStorageLive};
+pub use self::impls::{
+    tainted_sink_calls, MaybeTaintedLocals, TaintSummaries, TaintSummary, TaintedSinkCall,
+};
 
 use self::move_paths::MoveData;
 
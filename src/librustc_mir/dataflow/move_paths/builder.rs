@@ -1,7 +1,8 @@
 use rustc::mir::tcx::RvalueInitializationState;
 use rustc::mir::*;
+use rustc::ty::subst::SubstsRef;
 use rustc::ty::{self, TyCtxt};
-use rustc_index::vec::IndexVec;
+use rustc_index::vec::{Idx, IndexVec};
 use smallvec::{smallvec, SmallVec};
 
 use std::convert::TryInto;
@@ -24,10 +25,6 @@ struct MoveDataBuilder<'a, 'tcx> {
 
 impl<'a, 'tcx> MoveDataBuilder<'a, 'tcx> {
     fn new(body: &'a Body<'tcx>, tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>) -> Self {
-        let mut move_paths = IndexVec::new();
-        let mut path_map = IndexVec::new();
-        let mut init_path_map = IndexVec::new();
-
         MoveDataBuilder {
             body,
             tcx,
@@ -37,30 +34,28 @@ impl<'a, 'tcx> MoveDataBuilder<'a, 'tcx> {
                 moves: IndexVec::new(),
                 loc_map: LocationMap::new(body),
                 rev_lookup: MovePathLookup {
-                    locals: body
-                        .local_decls
-                        .indices()
-                        .map(|i| {
-                            Self::new_move_path(
-                                &mut move_paths,
-                                &mut path_map,
-                                &mut init_path_map,
-                                None,
-                                Place::from(i),
-                            )
-                        })
-                        .collect(),
+                    // The move paths of locals are created on demand, when the local is first
+                    // initialized, moved out of or dropped.
+                    locals: IndexVec::from_elem(None, &body.local_decls),
                     projections: Default::default(),
                 },
-                move_paths,
-                path_map,
+                move_paths: IndexVec::new(),
+                path_map: IndexVec::new(),
                 inits: IndexVec::new(),
                 init_loc_map: LocationMap::new(body),
-                init_path_map,
+                init_path_map: IndexVec::new(),
             },
         }
     }
 
+    /// Returns the move path of `local`, creating it if needed.
+    fn local_path(&mut self, local: Local) -> MovePathIndex {
+        let MoveData { rev_lookup, move_paths, path_map, init_path_map, .. } = &mut self.data;
+        *rev_lookup.locals[local].get_or_insert_with(|| {
+            Self::new_move_path(move_paths, path_map, init_path_map, None, Place::from(local))
+        })
+    }
+
     fn new_move_path(
         move_paths: &mut IndexVec<MovePathIndex, MovePath<'tcx>>,
         path_map: &mut IndexVec<MovePathIndex, SmallVec<[MoveOutIndex; 4]>>,
@@ -96,13 +91,10 @@ impl<'b, 'a, 'tcx> Gatherer<'b, 'a, 'tcx> {
     /// Maybe we should have separate "borrowck" and "moveck" modes.
     fn move_path_for(&mut self, place: &Place<'tcx>) -> Result<MovePathIndex, MoveError<'tcx>> {
         debug!("lookup({:?})", place);
-        let mut base = self.builder.data.rev_lookup.locals[place.local];
+        let mut base = self.builder.local_path(place.local);
 
-        // The move path index of the first union that we find. Once this is
-        // some we stop creating child move paths, since moves from unions
-        // move the whole thing.
-        // We continue looking for other move errors though so that moving
-        // from `*(u.f: &_)` isn't allowed.
+        // The move path index of the union, if we are moving one of its fields. The fields
+        // of a union overlap, so moving one of them moves the whole union.
         let mut union_path = None;
 
         for (i, elem) in place.projection.iter().enumerate() {
@@ -129,8 +121,19 @@ impl<'b, 'a, 'tcx> Gatherer<'b, 'a, 'tcx> {
                         InteriorOfTypeWithDestructor { container_ty: place_ty },
                     ));
                 }
-                ty::Adt(adt, _) if adt.is_union() => {
-                    union_path.get_or_insert(base);
+                ty::Adt(adt, substs) if adt.is_union() => {
+                    if i + 1 == place.projection.len() {
+                        union_path = Some(base);
+                    } else {
+                        // Places inside of a union field are tracked separately, like those
+                        // inside of a struct. The other fields overlap them, so they get move
+                        // paths as well, to be marked as moved out of together with them.
+                        let union_place = Place {
+                            local: place.local,
+                            projection: tcx.intern_place_elems(proj_base),
+                        };
+                        self.add_union_field_paths(base, union_place, adt, substs);
+                    }
                 }
                 ty::Slice(_) => {
                     return Err(MoveError::cannot_move_out_of(
@@ -195,6 +198,20 @@ impl<'b, 'a, 'tcx> Gatherer<'b, 'a, 'tcx> {
         })
     }
 
+    /// Adds the move paths of all fields of the union `union_place`, whose move path is `base`.
+    fn add_union_field_paths(
+        &mut self,
+        base: MovePathIndex,
+        union_place: Place<'tcx>,
+        adt: &'tcx ty::AdtDef,
+        substs: SubstsRef<'tcx>,
+    ) {
+        for (index, field) in adt.non_enum_variant().fields.iter().enumerate() {
+            let elem = ProjectionElem::Field(Field::new(index), field.ty(self.builder.tcx, substs));
+            self.add_move_path(base, &elem, |tcx| tcx.mk_place_elem(union_place, elem));
+        }
+    }
+
     fn create_move_path(&mut self, place: &Place<'tcx>) {
         // This is an non-moving access (such as an overwrite or
         // drop), so this not being a valid move path is OK.
@@ -247,7 +264,7 @@ pub(super) fn gather_moves<'tcx>(
 impl<'a, 'tcx> MoveDataBuilder<'a, 'tcx> {
     fn gather_args(&mut self) {
         for arg in self.body.args_iter() {
-            let path = self.data.rev_lookup.locals[arg];
+            let path = self.local_path(arg);
 
             let init = self.data.inits.push(Init {
                 path,
@@ -306,6 +323,7 @@ impl<'b, 'a, 'tcx> Gatherer<'b, 'a, 'tcx> {
                     self.gather_operand(input);
                 }
             }
+            StatementKind::Assume(ref op) => self.gather_operand(op),
             StatementKind::StorageLive(_) => {}
             StatementKind::StorageDead(local) => {
                 self.gather_move(&Place::from(local));
@@ -383,7 +401,7 @@ impl<'b, 'a, 'tcx> Gatherer<'b, 'a, 'tcx> {
             TerminatorKind::Yield { ref value, resume_arg: ref place, .. } => {
                 self.gather_operand(value);
                 self.create_move_path(place);
-                self.gather_init(place.as_ref(), InitKind::Deep);
+                self.gather_init(place.as_ref(), InitKind::ResumePathOnly);
             }
 
             TerminatorKind::Drop { ref location, target: _, unwind: _ } => {
@@ -500,6 +518,8 @@ impl<'b, 'a, 'tcx> Gatherer<'b, 'a, 'tcx> {
             }
         }
 
+        // The local needs a move path even if this is the only thing ever done with it.
+        self.builder.local_path(place.local);
         if let LookupResult::Exact(path) = self.builder.data.rev_lookup.find(place) {
             let init = self.builder.data.inits.push(Init {
                 location: InitLocation::Statement(self.loc),
@@ -0,0 +1,185 @@
+//! The `-Z dump-move-paths` output: the move paths of a body, and the paths the initialization
+//! analyses generate and kill at each location, along with the moves and initializations causing
+//! it.
+
+use super::{InitKind, MoveData, MovePathIndex};
+use crate::dataflow::drop_flag_effects::{
+    drop_flag_effects_for_function_entry, drop_flag_effects_for_location,
+};
+use crate::dataflow::MoveDataParamEnv;
+use crate::transform::MirSource;
+use crate::util::elaborate_drops::DropFlagState;
+use crate::util::pretty::{self, create_dump_file};
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::fx::FxIndexMap;
+use std::io::{self, Write};
+
+/// Dumps the move paths of `body`, gathered for `pass_name`, if `-Z dump-move-paths` selects
+/// them. Writes a `.move_paths` text file, and a `.move_paths.dot` graph of the paths, to the
+/// `-Z dump-mir-dir` directory.
+pub fn dump_move_paths<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    pass_name: &str,
+    source: MirSource<'tcx>,
+    body: &Body<'tcx>,
+    env: &MoveDataParamEnv<'tcx>,
+) {
+    let filters = match &tcx.sess.opts.debugging_opts.dump_move_paths {
+        Some(filters) => filters,
+        None => return,
+    };
+    if !pretty::filter_matches(tcx, filters, pass_name, source) {
+        return;
+    }
+
+    let _: io::Result<()> = try {
+        let mut file = create_dump_file(tcx, "move_paths", None, pass_name, &0, source)?;
+        write_move_paths(tcx, source, body, env, &mut file)?;
+        let mut file = create_dump_file(tcx, "move_paths.dot", None, pass_name, &0, source)?;
+        write_move_paths_graphviz(tcx, source, &env.move_data, &mut file)?;
+    };
+}
+
+fn write_move_paths<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    source: MirSource<'tcx>,
+    body: &Body<'tcx>,
+    env: &MoveDataParamEnv<'tcx>,
+    w: &mut dyn Write,
+) -> io::Result<()> {
+    let move_data = &env.move_data;
+    writeln!(w, "// Move paths of `{}`", pretty::item_path(tcx, source))?;
+    for (_, path) in move_data.rev_lookup.iter_locals_enumerated() {
+        write_move_path_tree(move_data, path, 0, w)?;
+    }
+
+    writeln!(w)?;
+    writeln!(w, "// On entry")?;
+    let mut effects = FxIndexMap::default();
+    drop_flag_effects_for_function_entry(tcx, body, env, |path, state| {
+        effects.insert(path, state);
+    });
+    write_effects(move_data, &effects, "", w)?;
+
+    for (bb, bb_data) in body.basic_blocks().iter_enumerated() {
+        writeln!(w)?;
+        writeln!(w, "{:?}: {{", bb)?;
+        for statement_index in 0..=bb_data.statements.len() {
+            let location = Location { block: bb, statement_index };
+            match bb_data.statements.get(statement_index) {
+                Some(statement) => writeln!(w, "    {:?}", statement)?,
+                None => writeln!(w, "    {:?}", bb_data.terminator().kind)?,
+            }
+            for &move_out in &move_data.loc_map[location] {
+                let path = move_data.moves[move_out].path;
+                writeln!(w, "        // move out: {}", path_name(move_data, path))?;
+            }
+            for &init in &move_data.init_loc_map[location] {
+                let init = &move_data.inits[init];
+                writeln!(
+                    w,
+                    "        // init: {} ({:?})",
+                    path_name(move_data, init.path),
+                    init.kind,
+                )?;
+            }
+
+            // A path moved out of and then initialized again is generated.
+            let mut effects = FxIndexMap::default();
+            drop_flag_effects_for_location(tcx, body, env, location, |path, state| {
+                effects.insert(path, state);
+            });
+            write_effects(move_data, &effects, "        ", w)?;
+            // The destinations of calls are only initialized on return, see
+            // `MaybeInitializedPlaces::call_return_effect`.
+            for &init in &move_data.init_loc_map[location] {
+                let init = &move_data.inits[init];
+                if init.kind == InitKind::NonPanicPathOnly {
+                    writeln!(w, "        // gen on return: {}", path_name(move_data, init.path))?;
+                }
+            }
+        }
+        writeln!(w, "}}")?;
+    }
+    Ok(())
+}
+
+/// Writes the paths generated and killed by `effects`, as the initialization analyses see them.
+fn write_effects(
+    move_data: &MoveData<'_>,
+    effects: &FxIndexMap<MovePathIndex, DropFlagState>,
+    indent: &str,
+    w: &mut dyn Write,
+) -> io::Result<()> {
+    for (label, kind) in &[("gen", DropFlagState::Present), ("kill", DropFlagState::Absent)] {
+        let paths: Vec<_> = effects
+            .iter()
+            .filter(|&(_, state)| state == kind)
+            .map(|(&path, _)| path_name(move_data, path))
+            .collect();
+        if !paths.is_empty() {
+            writeln!(w, "{}// {}: {}", indent, label, paths.join(", "))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_move_path_tree(
+    move_data: &MoveData<'_>,
+    path: MovePathIndex,
+    depth: usize,
+    w: &mut dyn Write,
+) -> io::Result<()> {
+    let move_path = &move_data.move_paths[path];
+    writeln!(
+        w,
+        "{:indent$}{} ({} moves, {} inits)",
+        "",
+        path_name(move_data, path),
+        move_data.path_map[path].len(),
+        move_data.init_path_map[path].len(),
+        indent = depth * 4,
+    )?;
+    for (child, _) in move_path.children(&move_data.move_paths) {
+        write_move_path_tree(move_data, child, depth + 1, w)?;
+    }
+    Ok(())
+}
+
+fn write_move_paths_graphviz<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    source: MirSource<'tcx>,
+    move_data: &MoveData<'tcx>,
+    w: &mut dyn Write,
+) -> io::Result<()> {
+    writeln!(w, "digraph MovePaths {{")?;
+    writeln!(w, r#"    graph [fontname="monospace"];"#)?;
+    writeln!(w, r#"    node [fontname="monospace", shape="box"];"#)?;
+    writeln!(
+        w,
+        r#"    label=<move paths of {}>;"#,
+        dot::escape_html(&pretty::item_path(tcx, source))
+    )?;
+    for (path, move_path) in move_data.move_paths.iter_enumerated() {
+        writeln!(
+            w,
+            r#"    {:?} [label=<{:?}: {}<br/>{} moves, {} inits>];"#,
+            path,
+            path,
+            dot::escape_html(&format!("{:?}", move_path.place)),
+            move_data.path_map[path].len(),
+            move_data.init_path_map[path].len(),
+        )?;
+    }
+    for (path, move_path) in move_data.move_paths.iter_enumerated() {
+        if let Some(parent) = move_path.parent {
+            writeln!(w, "    {:?} -> {:?};", parent, path)?;
+        }
+    }
+    writeln!(w, "}}")
+}
+
+fn path_name(move_data: &MoveData<'_>, path: MovePathIndex) -> String {
+    format!("{:?}: {:?}", path, move_data.move_paths[path].place)
+}
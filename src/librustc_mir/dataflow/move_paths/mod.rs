@@ -1,8 +1,7 @@
-use core::slice::Iter;
 use rustc::mir::*;
 use rustc::ty::{ParamEnv, Ty, TyCtxt};
 use rustc_data_structures::fx::FxHashMap;
-use rustc_index::vec::{Enumerated, IndexVec};
+use rustc_index::vec::IndexVec;
 use rustc_span::Span;
 use smallvec::SmallVec;
 
@@ -10,8 +9,10 @@ use std::fmt;
 use std::ops::{Index, IndexMut};
 
 use self::abs_domain::{AbstractElem, Lift};
+pub use self::dump::dump_move_paths;
 
 mod abs_domain;
+mod dump;
 
 rustc_index::newtype_index! {
     pub struct MovePathIndex {
@@ -268,6 +269,9 @@ pub enum InitKind {
     Shallow,
     /// This doesn't initialize the variable on panic (and a panic is possible).
     NonPanicPathOnly,
+    /// The resume argument of a `Yield`, only initialized when the generator is resumed, not
+    /// when it is dropped while suspended.
+    ResumePathOnly,
 }
 
 impl fmt::Debug for Init {
@@ -288,7 +292,9 @@ impl Init {
 /// Tables mapping from a place to its MovePathIndex.
 #[derive(Debug)]
 pub struct MovePathLookup {
-    locals: IndexVec<Local, MovePathIndex>,
+    /// The move path of each local, or `None` if the local is never initialized, moved out of
+    /// or dropped, i.e. if it is always uninitialized.
+    locals: IndexVec<Local, Option<MovePathIndex>>,
 
     /// projections are made from a base-place and a projection
     /// elem. The base-place will have a unique MovePathIndex; we use
@@ -313,7 +319,10 @@ impl MovePathLookup {
     // unknown place, but will rather return the nearest available
     // parent.
     pub fn find(&self, place: PlaceRef<'_, '_>) -> LookupResult {
-        let mut result = self.locals[place.local];
+        let mut result = match self.locals[place.local] {
+            Some(path) => path,
+            None => return LookupResult::Parent(None),
+        };
 
         for elem in place.projection.iter() {
             if let Some(&subpath) = self.projections.get(&(result, elem.lift())) {
@@ -326,14 +335,15 @@ impl MovePathLookup {
         LookupResult::Exact(result)
     }
 
-    pub fn find_local(&self, local: Local) -> MovePathIndex {
+    /// Returns the move path of `local`, or `None` if it is always uninitialized.
+    pub fn find_local(&self, local: Local) -> Option<MovePathIndex> {
         self.locals[local]
     }
 
-    /// An enumerated iterator of `local`s and their associated
+    /// An enumerated iterator of the `local`s that have a move path, and their associated
     /// `MovePathIndex`es.
-    pub fn iter_locals_enumerated(&self) -> Enumerated<Local, Iter<'_, MovePathIndex>> {
-        self.locals.iter_enumerated()
+    pub fn iter_locals_enumerated(&self) -> impl Iterator<Item = (Local, MovePathIndex)> + '_ {
+        self.locals.iter_enumerated().filter_map(|(local, &path)| Some((local, path?)))
     }
 }
 
@@ -14,9 +14,11 @@ use rustc::ty::TyCtxt;
 use rustc_hir::def_id::DefId;
 use rustc_span::symbol::{sym, Symbol};
 use rustc_span::Span;
+use std::iter;
 
 use super::{ImmTy, InterpCx, Machine, OpTy, PlaceTy};
 
+mod atomic;
 mod caller_location;
 mod type_name;
 
@@ -320,6 +322,40 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
             sym::transmute => {
                 self.copy_op_transmute(args[0], dest)?;
             }
+            sym::copy | sym::copy_nonoverlapping => {
+                let elem_layout = self.layout_of(substs.type_at(0))?;
+                let count = self.read_scalar(args[2])?.to_machine_usize(self)?;
+                let size = elem_layout.size.checked_mul(count, self).ok_or_else(|| {
+                    err_ub_format!("overflow computing total size of `{}`", intrinsic_name)
+                })?;
+                let elem_align = elem_layout.align.abi;
+
+                // Both pointers must be aligned and in bounds, even if nothing is copied.
+                let src = self.read_scalar(args[0])?.not_undef()?;
+                let src = self.memory.check_ptr_access(src, size, elem_align)?;
+                let dest = self.read_scalar(args[1])?.not_undef()?;
+                let dest = self.memory.check_ptr_access(dest, size, elem_align)?;
+
+                // `copy` checks for overlap, and copies the definedness and relocations.
+                if let (Some(src), Some(dest)) = (src, dest) {
+                    let nonoverlapping = intrinsic_name == sym::copy_nonoverlapping;
+                    self.memory.copy(src, dest, size, nonoverlapping)?;
+                }
+            }
+            sym::write_bytes => {
+                let layout = self.layout_of(substs.type_at(0))?;
+                let ptr = self.read_scalar(args[0])?.not_undef()?;
+                let byte = self.read_scalar(args[1])?.to_u8()?;
+                let count = self.read_scalar(args[2])?.to_machine_usize(self)?;
+                let size = layout.size.checked_mul(count, self).ok_or_else(|| {
+                    err_ub_format!("overflow computing total size of `{}`", intrinsic_name)
+                })?;
+
+                // Like for `copy`, the pointer must be aligned and in bounds, even if nothing is
+                // written.
+                self.memory.check_ptr_access(ptr, size, layout.align.abi)?;
+                self.memory.write_bytes(ptr, iter::repeat(byte).take(size.bytes() as usize))?;
+            }
             sym::simd_insert => {
                 let index = u64::from(self.read_scalar(args[1])?.to_u32()?);
                 let elem = args[2];
@@ -366,6 +402,100 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
                 );
                 self.copy_op(self.operand_field(args[0], index)?, dest)?;
             }
+            sym::simd_add
+            | sym::simd_sub
+            | sym::simd_mul
+            | sym::simd_div
+            | sym::simd_rem
+            | sym::simd_shl
+            | sym::simd_shr
+            | sym::simd_and
+            | sym::simd_or
+            | sym::simd_xor
+            | sym::simd_eq
+            | sym::simd_ne
+            | sym::simd_lt
+            | sym::simd_le
+            | sym::simd_gt
+            | sym::simd_ge => {
+                let op = match intrinsic_name {
+                    sym::simd_add => BinOp::Add,
+                    sym::simd_sub => BinOp::Sub,
+                    sym::simd_mul => BinOp::Mul,
+                    sym::simd_div => BinOp::Div,
+                    sym::simd_rem => BinOp::Rem,
+                    sym::simd_shl => BinOp::Shl,
+                    sym::simd_shr => BinOp::Shr,
+                    sym::simd_and => BinOp::BitAnd,
+                    sym::simd_or => BinOp::BitOr,
+                    sym::simd_xor => BinOp::BitXor,
+                    sym::simd_eq => BinOp::Eq,
+                    sym::simd_ne => BinOp::Ne,
+                    sym::simd_lt => BinOp::Lt,
+                    sym::simd_le => BinOp::Le,
+                    sym::simd_gt => BinOp::Gt,
+                    sym::simd_ge => BinOp::Ge,
+                    _ => bug!(),
+                };
+                let (len, _) = args[0].layout.ty.simd_size_and_type(self.tcx.tcx);
+                let (dest_len, _) = dest.layout.ty.simd_size_and_type(self.tcx.tcx);
+                assert_eq!(
+                    len, dest_len,
+                    "Return type `{}` must have as many lanes as vector type `{}`",
+                    dest.layout.ty, args[0].layout.ty
+                );
+
+                for i in 0..len {
+                    let left = self.read_immediate(self.operand_field(args[0], i)?)?;
+                    let right = self.read_immediate(self.operand_field(args[1], i)?)?;
+                    let place = self.place_field(dest, i)?;
+                    let (val, overflowed, ty) = self.overflowing_binary_op(op, left, right)?;
+                    let val = if ty == self.tcx.types.bool {
+                        // Comparisons set all bits of a lane if they hold.
+                        Scalar::from_int(if val.to_bool()? { -1 } else { 0 }, place.layout.size)
+                    } else {
+                        // Arithmetic wraps, but overflowing divisions and shifts are UB like
+                        // their scalar counterparts.
+                        let is_ub = match op {
+                            BinOp::Div | BinOp::Rem | BinOp::Shl | BinOp::Shr => overflowed,
+                            _ => false,
+                        };
+                        if is_ub {
+                            throw_ub_format!("overflow in `{}` in lane {}", intrinsic_name, i);
+                        }
+                        val
+                    };
+                    self.write_scalar(val, place)?;
+                }
+            }
+            _ if intrinsic_name.as_str().starts_with("simd_shuffle") => {
+                let (len, _) = args[0].layout.ty.simd_size_and_type(self.tcx.tcx);
+                let (dest_len, _) = dest.layout.ty.simd_size_and_type(self.tcx.tcx);
+                let indices = args[2];
+                for i in 0..dest_len {
+                    let index = self.read_scalar(self.operand_field(indices, i)?)?.to_u32()?;
+                    let index = u64::from(index);
+                    let value = if index < len {
+                        self.operand_field(args[0], index)?
+                    } else if index < 2 * len {
+                        self.operand_field(args[1], index - len)?
+                    } else {
+                        throw_ub_format!(
+                            "`{}` index {} is out of bounds of two vectors with {} lanes",
+                            intrinsic_name,
+                            index,
+                            len
+                        );
+                    };
+                    let place = self.place_field(dest, i)?;
+                    self.copy_op(value, place)?;
+                }
+            }
+            _ if intrinsic_name.as_str().starts_with("atomic_") => {
+                if !self.emulate_atomic_intrinsic(intrinsic_name, args, dest)? {
+                    return Ok(false);
+                }
+            }
             _ => return Ok(false),
         }
 
@@ -0,0 +1,139 @@
+//! The `atomic_*` intrinsics. Their names follow the pattern `atomic_<operation>[_<ordering>]`,
+//! where no ordering means `SeqCst`, just like for codegen. The operations themselves are left to
+//! the `Machine`, so that it can model weak memory.
+
+use rustc::mir::{interpret::InterpResult, BinOp, UnOp};
+use rustc_span::symbol::Symbol;
+
+use crate::interpret::{
+    AtomicOrdering, AtomicRmwOp, ImmTy, InterpCx, Machine, OpTy, PlaceTy, Scalar,
+};
+
+/// Parses the orderings of an atomic intrinsic, returning the ordering for failed
+/// compare-exchanges as well.
+fn parse_orderings(
+    name: &str,
+    is_cxchg: bool,
+    suffix: &[&str],
+) -> (AtomicOrdering, AtomicOrdering) {
+    use crate::interpret::AtomicOrdering::*;
+    match suffix {
+        [] => (SeqCst, SeqCst),
+        ["unordered"] => (Unordered, Unordered),
+        ["relaxed"] => (Relaxed, Relaxed),
+        ["acq"] => (Acquire, Acquire),
+        ["rel"] => (Release, Relaxed),
+        ["acqrel"] => (AcqRel, Acquire),
+        ["failrelaxed"] if is_cxchg => (SeqCst, Relaxed),
+        ["failacq"] if is_cxchg => (SeqCst, Acquire),
+        ["acq", "failrelaxed"] if is_cxchg => (Acquire, Relaxed),
+        ["acqrel", "failrelaxed"] if is_cxchg => (AcqRel, Relaxed),
+        _ => bug!("unknown ordering in atomic intrinsic `{}`", name),
+    }
+}
+
+impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
+    /// Returns `true` if `intrinsic_name` was an atomic intrinsic, and got emulated.
+    pub(super) fn emulate_atomic_intrinsic(
+        &mut self,
+        intrinsic_name: Symbol,
+        args: &[OpTy<'tcx, M::PointerTag>],
+        dest: PlaceTy<'tcx, M::PointerTag>,
+    ) -> InterpResult<'tcx, bool> {
+        let name = intrinsic_name.as_str();
+        let split: Vec<&str> = name.split('_').collect();
+        let (operation, suffix) = match &split[..] {
+            ["atomic", operation, suffix @ ..] => (*operation, suffix),
+            _ => return Ok(false),
+        };
+        let is_cxchg = operation == "cxchg" || operation == "cxchgweak";
+        let (ordering, fail_ordering) = parse_orderings(&name, is_cxchg, suffix);
+
+        match operation {
+            "load" => {
+                let place = self.deref_operand(args[0])?;
+                let val = M::atomic_load(self, place, ordering)?;
+                self.write_immediate(*val, dest)?;
+            }
+            "store" => {
+                let place = self.deref_operand(args[0])?;
+                let val = self.read_immediate(args[1])?;
+                M::atomic_store(self, place, val, ordering)?;
+            }
+            "cxchg" | "cxchgweak" => {
+                let place = self.deref_operand(args[0])?;
+                let expected = self.read_immediate(args[1])?;
+                let new = self.read_immediate(args[2])?;
+                let weak = operation == "cxchgweak";
+                let (old, exchanged) = M::atomic_compare_exchange(
+                    self,
+                    place,
+                    expected,
+                    new,
+                    ordering,
+                    fail_ordering,
+                    weak,
+                )?;
+                let old_dest = self.place_field(dest, 0)?;
+                self.write_immediate(*old, old_dest)?;
+                let exchanged_dest = self.place_field(dest, 1)?;
+                self.write_scalar(Scalar::from_bool(exchanged), exchanged_dest)?;
+            }
+            "fence" | "singlethreadfence" => {
+                M::atomic_fence(self, ordering, operation == "singlethreadfence")?;
+            }
+            _ => {
+                let op = match operation {
+                    "xchg" => AtomicRmwOp::Xchg,
+                    "xadd" => AtomicRmwOp::Add,
+                    "xsub" => AtomicRmwOp::Sub,
+                    "and" => AtomicRmwOp::And,
+                    "nand" => AtomicRmwOp::Nand,
+                    "or" => AtomicRmwOp::Or,
+                    "xor" => AtomicRmwOp::Xor,
+                    "max" => AtomicRmwOp::Max,
+                    "min" => AtomicRmwOp::Min,
+                    "umax" => AtomicRmwOp::UMax,
+                    "umin" => AtomicRmwOp::UMin,
+                    _ => return Ok(false),
+                };
+                let place = self.deref_operand(args[0])?;
+                let rhs = self.read_immediate(args[1])?;
+                let old = M::atomic_rmw(self, place, op, rhs, ordering)?;
+                self.write_immediate(*old, dest)?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Computes the value a read-modify-write operation stores, given the `old` value and its
+    /// right-hand side. Additions and subtractions wrap around. `Max` and `Min` compare signed
+    /// or unsigned according to the type of the operands, which matches the unsigned types the
+    /// `UMax` and `UMin` intrinsics are used with.
+    pub fn atomic_rmw_result(
+        &self,
+        op: AtomicRmwOp,
+        old: ImmTy<'tcx, M::PointerTag>,
+        rhs: ImmTy<'tcx, M::PointerTag>,
+    ) -> InterpResult<'tcx, ImmTy<'tcx, M::PointerTag>> {
+        let bin_op = match op {
+            AtomicRmwOp::Xchg => return Ok(rhs),
+            AtomicRmwOp::Add => BinOp::Add,
+            AtomicRmwOp::Sub => BinOp::Sub,
+            AtomicRmwOp::And | AtomicRmwOp::Nand => BinOp::BitAnd,
+            AtomicRmwOp::Or => BinOp::BitOr,
+            AtomicRmwOp::Xor => BinOp::BitXor,
+            AtomicRmwOp::Max | AtomicRmwOp::UMax => {
+                let old_is_larger = self.binary_op(BinOp::Gt, old, rhs)?;
+                return Ok(if old_is_larger.to_scalar()?.to_bool()? { old } else { rhs });
+            }
+            AtomicRmwOp::Min | AtomicRmwOp::UMin => {
+                let old_is_smaller = self.binary_op(BinOp::Lt, old, rhs)?;
+                return Ok(if old_is_smaller.to_scalar()?.to_bool()? { old } else { rhs });
+            }
+        };
+        let (val, _overflowed, _ty) = self.overflowing_binary_op(bin_op, old, rhs)?;
+        let val = ImmTy::from_scalar(val, old.layout);
+        if op == AtomicRmwOp::Nand { self.unary_op(UnOp::Not, val) } else { Ok(val) }
+    }
+}
@@ -10,8 +10,8 @@ use rustc::ty::{self, Ty};
 use rustc_span::Span;
 
 use super::{
-    AllocId, Allocation, AllocationExtra, Frame, ImmTy, InterpCx, InterpResult, Memory, MemoryKind,
-    OpTy, Operand, PlaceTy, Pointer, Scalar,
+    AllocId, Allocation, AllocationExtra, Frame, ImmTy, InterpCx, InterpResult, MPlaceTy, Memory,
+    MemoryKind, OpTy, Operand, PlaceTy, Pointer, Scalar, ThreadId, WatchKind, WatchpointHit,
 };
 
 /// Data returned by Machine::stack_pop,
@@ -29,6 +29,34 @@ pub enum StackPopInfo {
     StopUnwinding,
 }
 
+/// The memory ordering of an atomic operation, as given by the suffix of the `atomic_*`
+/// intrinsics.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum AtomicOrdering {
+    Unordered,
+    Relaxed,
+    Acquire,
+    Release,
+    AcqRel,
+    SeqCst,
+}
+
+/// The read-modify-write operations of the `atomic_*` intrinsics.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum AtomicRmwOp {
+    Xchg,
+    Add,
+    Sub,
+    And,
+    Nand,
+    Or,
+    Xor,
+    Max,
+    Min,
+    UMax,
+    UMin,
+}
+
 /// Whether this kind of memory is allowed to leak
 pub trait MayLeak: Copy {
     fn may_leak(self) -> bool;
@@ -119,6 +147,10 @@ pub trait Machine<'mir, 'tcx>: Sized {
     /// Whether memory accesses should be alignment-checked.
     const CHECK_ALIGN: bool;
 
+    /// Whether the watchpoints of `-Z interp-watchpoints` apply to this machine. Machines that
+    /// evaluate statements without `InterpCx::step`, which reports the hits, should not use them.
+    const WATCHPOINTS: bool = true;
+
     /// Whether to enforce the validity invariant
     fn enforce_validity(ecx: &InterpCx<'mir, 'tcx, Self>) -> bool;
 
@@ -203,6 +235,108 @@ pub trait Machine<'mir, 'tcx>: Sized {
         Ok(())
     }
 
+    /// Called before every step once threads other than the main thread exist, to pick the
+    /// thread that runs the step. The thread must be enabled. Returning `None` keeps running the
+    /// active thread until it blocks or terminates, and then switches to the first enabled one.
+    #[inline]
+    fn schedule(_ecx: &InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx, Option<ThreadId>> {
+        Ok(None)
+    }
+
+    /// Called after a statement or terminator accessed memory covered by a watchpoint, see
+    /// `Memory::add_watchpoint`. Returning an error stops the evaluation right there.
+    ///
+    /// By default, the access is reported as a warning.
+    fn watchpoint_hit(ecx: &InterpCx<'mir, 'tcx, Self>, hit: &WatchpointHit) -> InterpResult<'tcx> {
+        let access = if hit.kind == WatchKind::Write { "write" } else { "read" };
+        let msg = format!(
+            "{} of {} bytes at offset {} of alloc{} hits a watchpoint",
+            access,
+            hit.size.bytes(),
+            hit.offset.bytes(),
+            hit.watchpoint.alloc_id,
+        );
+        let mut warn = ecx.tcx.sess.struct_span_warn(hit.spans[0], &msg);
+        for &call_site in &hit.spans[1..] {
+            warn.span_note(call_site, "inside the call here");
+        }
+        warn.emit();
+        Ok(())
+    }
+
+    /// Called for the atomic loads of the `atomic_load*` intrinsics.
+    ///
+    /// The atomic hooks let machines model weak memory. By default, all atomic operations are
+    /// sequentially consistent, which for a single thread makes them ordinary accesses.
+    #[inline]
+    fn atomic_load(
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        place: MPlaceTy<'tcx, Self::PointerTag>,
+        _ordering: AtomicOrdering,
+    ) -> InterpResult<'tcx, ImmTy<'tcx, Self::PointerTag>> {
+        ecx.read_immediate(place.into())
+    }
+
+    /// Called for the atomic stores of the `atomic_store*` intrinsics.
+    #[inline]
+    fn atomic_store(
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        place: MPlaceTy<'tcx, Self::PointerTag>,
+        val: ImmTy<'tcx, Self::PointerTag>,
+        _ordering: AtomicOrdering,
+    ) -> InterpResult<'tcx> {
+        ecx.write_immediate(*val, place.into())
+    }
+
+    /// Called for the atomic read-modify-write operations, like `atomic_xadd`. Returns the old
+    /// value.
+    #[inline]
+    fn atomic_rmw(
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        place: MPlaceTy<'tcx, Self::PointerTag>,
+        op: AtomicRmwOp,
+        rhs: ImmTy<'tcx, Self::PointerTag>,
+        _ordering: AtomicOrdering,
+    ) -> InterpResult<'tcx, ImmTy<'tcx, Self::PointerTag>> {
+        let old = ecx.read_immediate(place.into())?;
+        let new = ecx.atomic_rmw_result(op, old, rhs)?;
+        ecx.write_immediate(*new, place.into())?;
+        Ok(old)
+    }
+
+    /// Called for the atomic compare-exchanges of the `atomic_cxchg*` intrinsics. Returns the old
+    /// value, and whether it was replaced by `new`. Weak compare-exchanges may fail spuriously,
+    /// but the default implementation never does.
+    #[inline]
+    fn atomic_compare_exchange(
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        place: MPlaceTy<'tcx, Self::PointerTag>,
+        expected: ImmTy<'tcx, Self::PointerTag>,
+        new: ImmTy<'tcx, Self::PointerTag>,
+        _success: AtomicOrdering,
+        _failure: AtomicOrdering,
+        _weak: bool,
+    ) -> InterpResult<'tcx, (ImmTy<'tcx, Self::PointerTag>, bool)> {
+        let old = ecx.read_immediate(place.into())?;
+        let eq = ecx.binary_op(mir::BinOp::Eq, old, expected)?;
+        let exchanged = eq.to_scalar()?.to_bool()?;
+        if exchanged {
+            ecx.write_immediate(*new, place.into())?;
+        }
+        Ok((old, exchanged))
+    }
+
+    /// Called for the `atomic_fence*` and `atomic_singlethreadfence*` intrinsics, the latter
+    /// with `single_thread` set.
+    #[inline]
+    fn atomic_fence(
+        _ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        _ordering: AtomicOrdering,
+        _single_thread: bool,
+    ) -> InterpResult<'tcx> {
+        Ok(())
+    }
+
     /// Called before a `Static` value is accessed.
     #[inline]
     fn before_access_static(
@@ -7,18 +7,23 @@
 //! short-circuiting the empty case!
 
 use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
 use std::ptr;
 
+use rustc::session::config::InterpWatchpoint;
+pub use rustc::session::config::WatchKind;
 use rustc::ty::layout::{Align, HasDataLayout, Size, TargetDataLayout};
 use rustc::ty::{self, query::TyCtxtAt, Instance, ParamEnv};
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 
 use rustc_ast::ast::Mutability;
+use rustc_span::Span;
 
 use super::{
-    AllocId, AllocMap, Allocation, AllocationExtra, CheckInAllocMsg, ErrorHandled, GlobalAlloc,
-    GlobalId, InterpResult, Machine, MayLeak, Pointer, PointerArithmetic, Scalar,
+    AllocEvent, AllocEventKind, AllocId, AllocMap, Allocation, AllocationExtra, CheckInAllocMsg,
+    ErrorHandled, GlobalAlloc, GlobalId, InterpResult, Machine, MayLeak, Pointer,
+    PointerArithmetic, Scalar,
 };
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -74,6 +79,80 @@ impl<'tcx, Other> FnVal<'tcx, Other> {
     }
 }
 
+/// The maximal number of events kept per allocation. The creation of an allocation is always
+/// kept, the oldest other events are dropped first.
+const MAX_ALLOC_EVENTS: usize = 8;
+
+/// The histories of the allocations, recorded with `-Z track-alloc-history`.
+#[derive(Clone, Default)]
+struct AllocHistories {
+    events: FxHashMap<AllocId, Vec<AllocEvent>>,
+    /// The allocation accessed last. If an error happens during a memory access, it is the
+    /// allocation the error is about.
+    last_accessed: Cell<Option<AllocId>>,
+}
+
+impl AllocHistories {
+    fn record(&mut self, id: AllocId, kind: AllocEventKind, spans: Vec<Span>) {
+        let events = self.events.entry(id).or_default();
+        // A write right where the allocation was created or last written tells nothing new, and
+        // writes in a loop would otherwise push out all other events.
+        if kind == AllocEventKind::Written
+            && events.last().map_or(false, |last| {
+                last.kind != AllocEventKind::Deallocated && last.spans == spans
+            })
+        {
+            return;
+        }
+        if events.len() == MAX_ALLOC_EVENTS {
+            events.remove(1);
+        }
+        events.push(AllocEvent { kind, spans });
+    }
+}
+
+/// A range of an allocation whose accesses are reported to `Machine::watchpoint_hit`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Watchpoint {
+    pub alloc_id: AllocId,
+    /// The first byte watched.
+    pub start: Size,
+    /// The first byte after the watched range.
+    pub end: Size,
+    pub kind: WatchKind,
+}
+
+impl Watchpoint {
+    /// The watchpoint given with `-Z interp-watchpoints`.
+    fn from_option(watchpoint: &InterpWatchpoint) -> Watchpoint {
+        Watchpoint {
+            alloc_id: AllocId(watchpoint.alloc_id),
+            start: Size::from_bytes(watchpoint.start),
+            end: Size::from_bytes(watchpoint.end),
+            kind: watchpoint.kind,
+        }
+    }
+
+    fn overlaps<Tag>(&self, ptr: Pointer<Tag>, size: Size) -> bool {
+        ptr.alloc_id == self.alloc_id && ptr.offset < self.end && self.start < ptr.offset + size
+    }
+}
+
+/// An access that hit a watchpoint.
+#[derive(Clone, Debug)]
+pub struct WatchpointHit {
+    /// The watchpoint that was hit.
+    pub watchpoint: Watchpoint,
+    /// `Read` or `Write`.
+    pub kind: WatchKind,
+    /// The offset of the access in the allocation.
+    pub offset: Size,
+    pub size: Size,
+    /// The span of the statement or terminator doing the access, followed by the call sites of
+    /// the frames on the stack, innermost first.
+    pub spans: Vec<Span>,
+}
+
 // `Memory` has to depend on the `Machine` because some of its operations
 // (e.g., `get`) call a `Machine` hook.
 pub struct Memory<'mir, 'tcx, M: Machine<'mir, 'tcx>> {
@@ -103,6 +182,18 @@ pub struct Memory<'mir, 'tcx, M: Machine<'mir, 'tcx>> {
 
     /// Lets us implement `HasDataLayout`, which is awfully convenient.
     pub tcx: TyCtxtAt<'tcx>,
+
+    /// The history of each allocation, if `-Z track-alloc-history` is set.
+    history: Option<AllocHistories>,
+
+    /// The watched ranges, see `add_watchpoint`.
+    watchpoints: Vec<Watchpoint>,
+    /// The hits since the last call to `take_watchpoint_hits`.
+    watchpoint_hits: RefCell<Vec<WatchpointHit>>,
+
+    /// The call sites of the frames on the stack, innermost first, for the allocation history and
+    /// the watchpoint hits. Only kept up to date by the `InterpCx` if one of them needs it.
+    call_sites: Vec<Span>,
 }
 
 impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> HasDataLayout for Memory<'mir, 'tcx, M> {
@@ -127,18 +218,117 @@ where
             dead_alloc_map: self.dead_alloc_map.clone(),
             extra: self.extra,
             tcx: self.tcx,
+            history: self.history.clone(),
+            watchpoints: self.watchpoints.clone(),
+            watchpoint_hits: self.watchpoint_hits.clone(),
+            call_sites: self.call_sites.clone(),
         }
     }
 }
 
 impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'mir, 'tcx, M> {
     pub fn new(tcx: TyCtxtAt<'tcx>, extra: M::MemoryExtra) -> Self {
+        let watchpoints = if M::WATCHPOINTS {
+            let options = &tcx.sess.opts.debugging_opts.interp_watchpoints;
+            options.iter().map(Watchpoint::from_option).collect()
+        } else {
+            vec![]
+        };
         Memory {
             alloc_map: M::MemoryMap::default(),
             extra_fn_ptr_map: FxHashMap::default(),
             dead_alloc_map: FxHashMap::default(),
             extra,
             tcx,
+            history: if tcx.sess.opts.debugging_opts.track_alloc_history {
+                Some(AllocHistories::default())
+            } else {
+                None
+            },
+            watchpoints,
+            watchpoint_hits: RefCell::new(vec![]),
+            call_sites: vec![],
+        }
+    }
+
+    /// Whether the call sites of the frames on the stack need to be passed to
+    /// `set_call_sites`, to record them in the allocation history or in watchpoint hits.
+    pub fn needs_call_sites(&self) -> bool {
+        self.history.is_some() || !self.watchpoints.is_empty()
+    }
+
+    /// Sets the call sites of the frames on the stack, innermost first. The span of the current
+    /// statement comes from `self.tcx.span`.
+    pub fn set_call_sites(&mut self, call_sites: Vec<Span>) {
+        self.call_sites = call_sites;
+    }
+
+    /// The span of the current statement, followed by the call sites.
+    fn current_spans(&self) -> Vec<Span> {
+        Some(self.tcx.span).into_iter().chain(self.call_sites.iter().copied()).collect()
+    }
+
+    /// Starts watching accesses to the given range, which are reported to
+    /// `Machine::watchpoint_hit` after the statement or terminator doing them.
+    pub fn add_watchpoint(&mut self, watchpoint: Watchpoint) {
+        self.watchpoints.push(watchpoint);
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    /// Returns the watchpoint hits since the last call, oldest first.
+    pub fn take_watchpoint_hits(&self) -> Vec<WatchpointHit> {
+        self.watchpoint_hits.replace(vec![])
+    }
+
+    /// Records a hit for each watchpoint overlapping the access. `kind` is `Read` or `Write`.
+    pub(super) fn check_watchpoints(
+        &self,
+        ptr: Pointer<M::PointerTag>,
+        size: Size,
+        kind: WatchKind,
+    ) {
+        if self.watchpoints.is_empty() || size.bytes() == 0 {
+            return;
+        }
+        for watchpoint in &self.watchpoints {
+            if (watchpoint.kind == kind || watchpoint.kind == WatchKind::Access)
+                && watchpoint.overlaps(ptr, size)
+            {
+                self.watchpoint_hits.borrow_mut().push(WatchpointHit {
+                    watchpoint: *watchpoint,
+                    kind,
+                    offset: ptr.offset,
+                    size,
+                    spans: self.current_spans(),
+                });
+            }
+        }
+    }
+
+    /// Returns what happened to the allocation `id` so far, oldest first. This is empty if
+    /// the history is not recorded.
+    pub fn alloc_history(&self, id: AllocId) -> &[AllocEvent] {
+        self.history.as_ref().and_then(|history| history.events.get(&id)).map_or(&[], |e| &e[..])
+    }
+
+    /// Returns the allocation accessed last, if the history is recorded.
+    pub fn last_accessed_alloc(&self) -> Option<AllocId> {
+        self.history.as_ref().and_then(|history| history.last_accessed.get())
+    }
+
+    fn record_alloc_event(&mut self, id: AllocId, kind: AllocEventKind) {
+        if self.history.is_some() {
+            let spans = self.current_spans();
+            self.history.as_mut().unwrap().record(id, kind, spans);
+        }
+    }
+
+    fn record_alloc_access(&self, id: AllocId) {
+        if let Some(history) = &self.history {
+            history.last_accessed.set(Some(id));
         }
     }
 
@@ -203,6 +393,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'mir, 'tcx, M> {
         );
         let (alloc, tag) = M::init_allocation_extra(&self.extra, id, Cow::Owned(alloc), Some(kind));
         self.alloc_map.insert(id, (kind, alloc.into_owned()));
+        self.record_alloc_event(id, AllocEventKind::Created);
         Pointer::from(id).with_tag(tag)
     }
 
@@ -249,6 +440,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'mir, 'tcx, M> {
         kind: MemoryKind<M::MemoryKinds>,
     ) -> InterpResult<'tcx> {
         trace!("deallocating: {}", ptr.alloc_id);
+        self.record_alloc_access(ptr.alloc_id);
 
         if ptr.offset.bytes() != 0 {
             throw_unsup!(DeallocateNonBasePtr)
@@ -294,6 +486,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'mir, 'tcx, M> {
         if old.is_some() {
             bug!("Nothing can be deallocated twice");
         }
+        self.record_alloc_event(ptr.alloc_id, AllocEventKind::Deallocated);
 
         Ok(())
     }
@@ -481,6 +674,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'mir, 'tcx, M> {
         id: AllocId,
     ) -> InterpResult<'tcx, &Allocation<M::PointerTag, M::AllocExtra>> {
         let id = M::canonical_alloc_id(self, id);
+        self.record_alloc_access(id);
         // The error type of the inner closure here is somewhat funny.  We have two
         // ways of "erroring": An actual error, or because we got a reference from
         // `get_static_alloc` that we can actually use directly without inserting anything anywhere.
@@ -517,6 +711,8 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'mir, 'tcx, M> {
         id: AllocId,
     ) -> InterpResult<'tcx, &mut Allocation<M::PointerTag, M::AllocExtra>> {
         let id = M::canonical_alloc_id(self, id);
+        self.record_alloc_access(id);
+        self.record_alloc_event(id, AllocEventKind::Written);
         let tcx = self.tcx;
         let memory_extra = &self.extra;
         let a = self.alloc_map.get_mut_or(id, || {
@@ -555,6 +751,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'mir, 'tcx, M> {
         liveness: AllocCheck,
     ) -> InterpResult<'static, (Size, Align)> {
         let id = M::canonical_alloc_id(self, id);
+        self.record_alloc_access(id);
         // # Regular allocations
         // Don't use `self.get_raw` here as that will
         // a) cause cycles in case `id` refers to a static
@@ -787,6 +984,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'mir, 'tcx, M> {
             Some(ptr) => ptr,
             None => return Ok(&[]), // zero-sized access
         };
+        self.check_watchpoints(ptr, size, WatchKind::Read);
         self.get_raw(ptr.alloc_id)?.get_bytes(self, ptr, size)
     }
 
@@ -813,6 +1011,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'mir, 'tcx, M> {
             Some(ptr) => ptr,
             None => return Ok(()), // zero-sized access
         };
+        self.check_watchpoints(ptr, size, WatchKind::Write);
         let tcx = self.tcx.tcx;
         self.get_raw_mut(ptr.alloc_id)?.write_bytes(&tcx, ptr, src)
     }
@@ -844,6 +1043,8 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'mir, 'tcx, M> {
         // relocations overlapping the edges; those would not be handled correctly).
         let relocations =
             self.get_raw(src.alloc_id)?.prepare_relocation_copy(self, src, size, dest, length);
+        self.check_watchpoints(src, size, WatchKind::Read);
+        self.check_watchpoints(dest, size * length, WatchKind::Write);
 
         let tcx = self.tcx.tcx;
 
@@ -1,4 +1,19 @@
 //! An interpreter for MIR used in CTFE and by miri
+//!
+//! The interpreter is split by concern, with every module adding methods to `InterpCx`:
+//!
+//! - `eval_context`: the `InterpCx` itself, and layout and type computations.
+//! - `stack`: stack frames and their locals, pushing and popping frames.
+//! - `thread`: the threads of execution, each with its own stack.
+//! - `step` and `terminator`: executing statements and terminators.
+//! - `operand` and `place`: reading and writing values; the projections of places are in
+//!   `place::projection`.
+//! - `memory`: the allocations, and accessing them.
+//!
+//! Machines other than CTFE, like Miri, drive the interpreter through the `pub` methods of
+//! `InterpCx` and the hooks of the `Machine` trait, and add their own functionality through
+//! extension traits implemented for `InterpCx<'mir, 'tcx, TheirMachine>`. The `pub(super)` and
+//! `pub(crate)` items are internal to the engine and may change at any time.
 
 mod cast;
 mod eval_context;
@@ -10,21 +25,29 @@ mod operand;
 mod operator;
 mod place;
 pub(crate) mod snapshot; // for const_eval
+mod stack;
 mod step;
 mod terminator;
+mod thread;
 mod traits;
 mod validity;
 mod visitor;
 
 pub use rustc::mir::interpret::*; // have all the `interpret` symbols in one place: here
 
-pub use self::eval_context::{Frame, InterpCx, LocalState, LocalValue, StackPopCleanup};
+pub use self::eval_context::InterpCx;
+
+pub use self::stack::{Frame, LocalState, LocalValue, StackPopCleanup};
 
 pub use self::place::{MPlaceTy, MemPlace, MemPlaceMeta, Place, PlaceTy};
 
-pub use self::memory::{AllocCheck, FnVal, Memory, MemoryKind};
+pub use self::memory::{
+    AllocCheck, FnVal, Memory, MemoryKind, WatchKind, Watchpoint, WatchpointHit,
+};
+
+pub use self::machine::{AllocMap, AtomicOrdering, AtomicRmwOp, Machine, MayLeak, StackPopInfo};
 
-pub use self::machine::{AllocMap, Machine, MayLeak, StackPopInfo};
+pub use self::thread::{ThreadId, ThreadState, Threads, MAIN_THREAD};
 
 pub use self::operand::{ImmTy, Immediate, OpTy, Operand, ScalarMaybeUndef};
 
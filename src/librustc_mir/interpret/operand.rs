@@ -8,7 +8,7 @@ use rustc::ty::layout::{
 };
 use rustc::{mir, ty};
 
-use super::{InterpCx, MPlaceTy, Machine, MemPlace, Place, PlaceTy};
+use super::{InterpCx, MPlaceTy, Machine, MemPlace, Place, PlaceTy, WatchKind};
 pub use rustc::mir::interpret::ScalarMaybeUndef;
 use rustc::mir::interpret::{
     sign_extend, truncate, AllocId, ConstValue, GlobalId, InterpResult, Pointer, Scalar,
@@ -271,6 +271,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
 
         match mplace.layout.abi {
             layout::Abi::Scalar(..) => {
+                self.memory.check_watchpoints(ptr, mplace.layout.size, WatchKind::Read);
                 let scalar = self.memory.get_raw(ptr.alloc_id)?.read_scalar(
                     self,
                     ptr,
@@ -288,6 +289,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
                 let b_offset = a_size.align_to(b.align(self).abi);
                 assert!(b_offset.bytes() > 0); // we later use the offset to tell apart the fields
                 let b_ptr = ptr.offset(b_offset, self)?;
+                self.memory.check_watchpoints(ptr, mplace.layout.size, WatchKind::Read);
                 let a_val = self.memory.get_raw(ptr.alloc_id)?.read_scalar(self, a_ptr, a_size)?;
                 let b_val = self.memory.get_raw(ptr.alloc_id)?.read_scalar(self, b_ptr, b_size)?;
                 Ok(Some(ImmTy { imm: Immediate::ScalarPair(a_val, b_val), layout: mplace.layout }))
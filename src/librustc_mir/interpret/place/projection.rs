@@ -0,0 +1,256 @@
+//! Projections of places: fields, downcasts, dereferences and indexing. Projecting a place
+//! stored in a local may need to move that local to memory first, see `place_field`.
+
+use std::convert::TryFrom;
+use std::hash::Hash;
+
+use rustc::mir;
+use rustc::ty::layout::{self, LayoutOf, Size, VariantIdx};
+use rustc::ty::{self, Ty};
+
+use super::{MPlaceTy, MemPlaceMeta, Place, PlaceTy};
+use crate::interpret::{
+    AllocId, AllocMap, Allocation, AllocationExtra, InterpCx, InterpResult, Machine, MemoryKind,
+    PointerArithmetic, Scalar,
+};
+
+// separating the pointer tag for `impl Trait`, see https://github.com/rust-lang/rust/issues/54385
+impl<'mir, 'tcx, Tag, M> InterpCx<'mir, 'tcx, M>
+where
+    // FIXME: Working around https://github.com/rust-lang/rust/issues/54385
+    Tag: ::std::fmt::Debug + Copy + Eq + Hash + 'static,
+    M: Machine<'mir, 'tcx, PointerTag = Tag>,
+    // FIXME: Working around https://github.com/rust-lang/rust/issues/24159
+    M::MemoryMap: AllocMap<AllocId, (MemoryKind<M::MemoryKinds>, Allocation<Tag, M::AllocExtra>)>,
+    M::AllocExtra: AllocationExtra<Tag>,
+{
+    /// Offset a pointer to project to a field. Unlike `place_field`, this is always
+    /// possible without allocating, so it can take `&self`. Also return the field's layout.
+    /// This supports both struct and array fields.
+    #[inline(always)]
+    pub fn mplace_field(
+        &self,
+        base: MPlaceTy<'tcx, M::PointerTag>,
+        field: u64,
+    ) -> InterpResult<'tcx, MPlaceTy<'tcx, M::PointerTag>> {
+        // Not using the layout method because we want to compute on u64
+        let offset = match base.layout.fields {
+            layout::FieldPlacement::Arbitrary { ref offsets, .. } => {
+                offsets[usize::try_from(field).unwrap()]
+            }
+            layout::FieldPlacement::Array { stride, .. } => {
+                let len = base.len(self)?;
+                if field >= len {
+                    // This can only be reached in ConstProp and non-rustc-MIR.
+                    throw_ub!(BoundsCheckFailed { len, index: field });
+                }
+                stride * field
+            }
+            layout::FieldPlacement::Union(count) => {
+                assert!(
+                    field < count as u64,
+                    "Tried to access field {} of union {:#?} with {} fields",
+                    field,
+                    base.layout,
+                    count
+                );
+                // Offset is always 0
+                Size::from_bytes(0)
+            }
+        };
+        // the only way conversion can fail if is this is an array (otherwise we already panicked
+        // above). In that case, all fields are equal.
+        let field_layout = base.layout.field(self, usize::try_from(field).unwrap_or(0))?;
+
+        // Offset may need adjustment for unsized fields.
+        let (meta, offset) = if field_layout.is_unsized() {
+            // Re-use parent metadata to determine dynamic field layout.
+            // With custom DSTS, this *will* execute user-defined code, but the same
+            // happens at run-time so that's okay.
+            let align = match self.size_and_align_of(base.meta, field_layout)? {
+                Some((_, align)) => align,
+                None if offset == Size::ZERO => {
+                    // An extern type at offset 0, we fall back to its static alignment.
+                    // FIXME: Once we have made decisions for how to handle size and alignment
+                    // of `extern type`, this should be adapted.  It is just a temporary hack
+                    // to get some code to work that probably ought to work.
+                    field_layout.align.abi
+                }
+                None => bug!("Cannot compute offset for extern type field at non-0 offset"),
+            };
+            (base.meta, offset.align_to(align))
+        } else {
+            // base.meta could be present; we might be accessing a sized field of an unsized
+            // struct.
+            (MemPlaceMeta::None, offset)
+        };
+
+        // We do not look at `base.layout.align` nor `field_layout.align`, unlike
+        // codegen -- mostly to see if we can get away with that
+        base.offset(offset, meta, field_layout, self)
+    }
+
+    // Iterates over all fields of an array. Much more efficient than doing the
+    // same by repeatedly calling `mplace_array`.
+    pub(in crate::interpret) fn mplace_array_fields(
+        &self,
+        base: MPlaceTy<'tcx, Tag>,
+    ) -> InterpResult<'tcx, impl Iterator<Item = InterpResult<'tcx, MPlaceTy<'tcx, Tag>>> + 'tcx>
+    {
+        let len = base.len(self)?; // also asserts that we have a type where this makes sense
+        let stride = match base.layout.fields {
+            layout::FieldPlacement::Array { stride, .. } => stride,
+            _ => bug!("mplace_array_fields: expected an array layout"),
+        };
+        let layout = base.layout.field(self, 0)?;
+        let dl = &self.tcx.data_layout;
+        Ok((0..len).map(move |i| base.offset(i * stride, MemPlaceMeta::None, layout, dl)))
+    }
+
+    fn mplace_subslice(
+        &self,
+        base: MPlaceTy<'tcx, M::PointerTag>,
+        from: u64,
+        to: u64,
+        from_end: bool,
+    ) -> InterpResult<'tcx, MPlaceTy<'tcx, M::PointerTag>> {
+        let len = base.len(self)?; // also asserts that we have a type where this makes sense
+        let actual_to = if from_end {
+            if from + to > len {
+                // This can only be reached in ConstProp and non-rustc-MIR.
+                throw_ub!(BoundsCheckFailed { len: len as u64, index: from as u64 + to as u64 });
+            }
+            len - to
+        } else {
+            to
+        };
+
+        // Not using layout method because that works with usize, and does not work with slices
+        // (that have count 0 in their layout).
+        let from_offset = match base.layout.fields {
+            layout::FieldPlacement::Array { stride, .. } => stride * from,
+            _ => bug!("Unexpected layout of index access: {:#?}", base.layout),
+        };
+
+        // Compute meta and new layout
+        let inner_len = actual_to - from;
+        let (meta, ty) = match base.layout.ty.kind {
+            // It is not nice to match on the type, but that seems to be the only way to
+            // implement this.
+            ty::Array(inner, _) => (MemPlaceMeta::None, self.tcx.mk_array(inner, inner_len)),
+            ty::Slice(..) => {
+                let len = Scalar::from_uint(inner_len, self.pointer_size());
+                (MemPlaceMeta::Meta(len), base.layout.ty)
+            }
+            _ => bug!("cannot subslice non-array type: `{:?}`", base.layout.ty),
+        };
+        let layout = self.layout_of(ty)?;
+        base.offset(from_offset, meta, layout, self)
+    }
+
+    pub(in crate::interpret) fn mplace_downcast(
+        &self,
+        base: MPlaceTy<'tcx, M::PointerTag>,
+        variant: VariantIdx,
+    ) -> InterpResult<'tcx, MPlaceTy<'tcx, M::PointerTag>> {
+        // Downcasts only change the layout
+        assert!(!base.meta.has_meta());
+        Ok(MPlaceTy { layout: base.layout.for_variant(self, variant), ..base })
+    }
+
+    /// Project into an mplace
+    pub(in crate::interpret) fn mplace_projection(
+        &self,
+        base: MPlaceTy<'tcx, M::PointerTag>,
+        proj_elem: &mir::PlaceElem<'tcx>,
+    ) -> InterpResult<'tcx, MPlaceTy<'tcx, M::PointerTag>> {
+        use rustc::mir::ProjectionElem::*;
+        Ok(match *proj_elem {
+            Field(field, _) => self.mplace_field(base, field.index() as u64)?,
+            Downcast(_, variant) => self.mplace_downcast(base, variant)?,
+            Deref => self.deref_operand(base.into())?,
+
+            Index(local) => {
+                let layout = self.layout_of(self.tcx.types.usize)?;
+                let n = self.access_local(self.frame(), local, Some(layout))?;
+                let n = self.read_scalar(n)?;
+                let n = self.force_bits(n.not_undef()?, self.tcx.data_layout.pointer_size)?;
+                self.mplace_field(base, u64::try_from(n).unwrap())?
+            }
+
+            ConstantIndex { offset, min_length, from_end } => {
+                let n = base.len(self)?;
+                if n < min_length as u64 {
+                    // This can only be reached in ConstProp and non-rustc-MIR.
+                    throw_ub!(BoundsCheckFailed { len: min_length as u64, index: n as u64 });
+                }
+
+                let index = if from_end {
+                    assert!(0 < offset && offset - 1 < min_length);
+                    n - u64::from(offset)
+                } else {
+                    assert!(offset < min_length);
+                    u64::from(offset)
+                };
+
+                self.mplace_field(base, index)?
+            }
+
+            Subslice { from, to, from_end } => {
+                self.mplace_subslice(base, u64::from(from), u64::from(to), from_end)?
+            }
+        })
+    }
+
+    /// Gets the place of a field inside the place, and also the field's type.
+    /// Just a convenience function, but used quite a bit.
+    /// This is the only projection that might have a side-effect: We cannot project
+    /// into the field of a local `ScalarPair`, we have to first allocate it.
+    pub fn place_field(
+        &mut self,
+        base: PlaceTy<'tcx, M::PointerTag>,
+        field: u64,
+    ) -> InterpResult<'tcx, PlaceTy<'tcx, M::PointerTag>> {
+        // FIXME: We could try to be smarter and avoid allocation for fields that span the
+        // entire place.
+        let mplace = self.force_allocation(base)?;
+        Ok(self.mplace_field(mplace, field)?.into())
+    }
+
+    pub fn place_downcast(
+        &self,
+        base: PlaceTy<'tcx, M::PointerTag>,
+        variant: VariantIdx,
+    ) -> InterpResult<'tcx, PlaceTy<'tcx, M::PointerTag>> {
+        // Downcast just changes the layout
+        Ok(match base.place {
+            Place::Ptr(mplace) => {
+                self.mplace_downcast(MPlaceTy { mplace, layout: base.layout }, variant)?.into()
+            }
+            Place::Local { .. } => {
+                let layout = base.layout.for_variant(self, variant);
+                PlaceTy { layout, ..base }
+            }
+        })
+    }
+
+    /// Projects into a place.
+    pub fn place_projection(
+        &mut self,
+        base: PlaceTy<'tcx, M::PointerTag>,
+        proj_elem: &mir::ProjectionElem<mir::Local, Ty<'tcx>>,
+    ) -> InterpResult<'tcx, PlaceTy<'tcx, M::PointerTag>> {
+        use rustc::mir::ProjectionElem::*;
+        Ok(match *proj_elem {
+            Field(field, _) => self.place_field(base, field.index() as u64)?,
+            Downcast(_, variant) => self.place_downcast(base, variant)?,
+            Deref => self.deref_operand(self.place_to_op(base)?)?.into(),
+            // For the other variants, we have to force an allocation.
+            // This matches `operand_projection`.
+            Subslice { .. } | ConstantIndex { .. } | Index(_) => {
+                let mplace = self.force_allocation(base)?;
+                self.mplace_projection(mplace, proj_elem)?.into()
+            }
+        })
+    }
+}
@@ -22,7 +22,7 @@ use rustc_index::vec::IndexVec;
 use rustc_macros::HashStable;
 use rustc_span::source_map::Span;
 
-use super::eval_context::{LocalState, StackPopCleanup};
+use super::stack::{LocalState, StackPopCleanup};
 use super::{
     Frame, Immediate, LocalValue, MemPlace, MemPlaceMeta, Memory, Operand, Place, ScalarMaybeUndef,
 };
@@ -42,7 +42,12 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
     /// This is marked `#inline(always)` to work around adverserial codegen when `opt-level = 3`
     #[inline(always)]
     pub fn step(&mut self) -> InterpResult<'tcx, bool> {
-        if self.stack.is_empty() {
+        // CTFE never creates threads, so it does not pay for scheduling them.
+        if self.has_multiple_threads() {
+            if !self.schedule_thread()? {
+                return Ok(false);
+            }
+        } else if self.stack.is_empty() {
             return Ok(false);
         }
 
@@ -64,7 +69,10 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
 
         if let Some(stmt) = basic_block.statements.get(stmt_id) {
             assert_eq!(old_frames, self.cur_frame());
-            self.statement(stmt)?;
+            // The accesses done before an error are reported too.
+            let result = self.statement(stmt);
+            self.report_watchpoint_hits()?;
+            result?;
             return Ok(true);
         }
 
@@ -72,7 +80,9 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
 
         let terminator = basic_block.terminator();
         assert_eq!(old_frames, self.cur_frame());
-        self.terminator(terminator)?;
+        let result = self.terminator(terminator);
+        self.report_watchpoint_hits()?;
+        result?;
         Ok(true)
     }
 
@@ -86,6 +96,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
         let frame_idx = self.cur_frame();
         self.tcx.span = stmt.source_info.span;
         self.memory.tcx.span = stmt.source_info.span;
+        self.update_call_sites();
 
         match stmt.kind {
             Assign(box (ref place, ref rvalue)) => self.eval_rvalue_into_place(rvalue, place)?,
@@ -120,6 +131,13 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
             // Statements we do not track.
             AscribeUserType(..) => {}
 
+            Assume(box ref op) => {
+                let cond = self.read_immediate(self.eval_operand(op, None)?)?.to_scalar()?;
+                if !cond.to_bool()? {
+                    throw_ub_format!("`assume` called with `false`");
+                }
+            }
+
             // Defined to do nothing. These are added by optimization passes, to avoid changing the
             // size of MIR constantly.
             Nop => {}
@@ -280,6 +298,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
         info!("{:?}", terminator.kind);
         self.tcx.span = terminator.source_info.span;
         self.memory.tcx.span = terminator.source_info.span;
+        self.update_call_sites();
 
         let old_stack = self.cur_frame();
         let old_bb = self.frame().block;
@@ -294,4 +313,22 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
         }
         Ok(())
     }
+
+    /// Tells the memory where the current frames were called from, for the allocation history
+    /// and the watchpoint hits.
+    fn update_call_sites(&mut self) {
+        if self.memory.needs_call_sites() {
+            // The span of the innermost frame is where it was called from.
+            let call_sites = self.stack.iter().rev().map(|frame| frame.span).collect();
+            self.memory.set_call_sites(call_sites);
+        }
+    }
+
+    /// Passes the watchpoint hits of the last statement or terminator to the machine.
+    fn report_watchpoint_hits(&mut self) -> InterpResult<'tcx> {
+        for hit in self.memory.take_watchpoint_hits() {
+            M::watchpoint_hit(self, &hit)?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,161 @@
+//! Support for interpreting several threads of execution, each with its own call stack.
+//!
+//! The interpreter itself never creates threads; CTFE only ever runs the main thread. Machines
+//! like Miri create threads when emulating the platform's thread API, and pick the thread to run
+//! next with `Machine::schedule`. All threads share the same `Memory`.
+//!
+//! Only the stack of the active thread lives in `InterpCx::stack`, so that the rest of the
+//! interpreter does not have to care about threads. The stacks of the other threads are swapped
+//! in when switching to them. As `Place::Local` refers to a frame of the active stack, the locals
+//! of other threads can only be reached through memory.
+
+use rustc_index::vec::IndexVec;
+
+use super::{Frame, InterpCx, InterpResult, Machine};
+
+rustc_index::newtype_index! {
+    pub struct ThreadId {
+        DEBUG_FORMAT = "thread{}",
+        const MAIN_THREAD = 0,
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ThreadState {
+    /// The thread can run.
+    Enabled,
+    /// The thread waits for the given thread to terminate.
+    BlockedOnJoin(ThreadId),
+    /// The call stack of the thread is empty.
+    Terminated,
+}
+
+struct Thread<'mir, 'tcx, Tag, Extra> {
+    state: ThreadState,
+    /// The call stack of the thread while it is not active.
+    stack: Vec<Frame<'mir, 'tcx, Tag, Extra>>,
+}
+
+/// The threads of an `InterpCx`.
+pub struct Threads<'mir, 'tcx, Tag, Extra> {
+    active: ThreadId,
+    threads: IndexVec<ThreadId, Thread<'mir, 'tcx, Tag, Extra>>,
+}
+
+impl<Tag, Extra> Default for Threads<'_, '_, Tag, Extra> {
+    fn default() -> Self {
+        let mut threads = IndexVec::new();
+        threads.push(Thread { state: ThreadState::Enabled, stack: Vec::new() });
+        Threads { active: MAIN_THREAD, threads }
+    }
+}
+
+impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
+    #[inline(always)]
+    pub fn active_thread(&self) -> ThreadId {
+        self.threads.active
+    }
+
+    /// All the threads created so far, including the terminated ones.
+    pub fn thread_ids(&self) -> impl Iterator<Item = ThreadId> {
+        self.threads.threads.indices()
+    }
+
+    pub fn thread_state(&self, thread: ThreadId) -> InterpResult<'tcx, ThreadState> {
+        match self.threads.threads.get(thread) {
+            Some(thread) => Ok(thread.state),
+            None => throw_ub_format!("{:?} does not exist", thread),
+        }
+    }
+
+    /// Creates a new thread with an empty call stack. To start it, switch to it with
+    /// `set_active_thread` and push a frame for the function it runs. A thread whose stack is
+    /// still empty when it is scheduled terminates right away.
+    pub fn create_thread(&mut self) -> ThreadId {
+        self.threads.threads.push(Thread { state: ThreadState::Enabled, stack: Vec::new() })
+    }
+
+    /// Makes `thread` the active thread, returning the previously active one. Terminated
+    /// threads cannot be made active again.
+    pub fn set_active_thread(&mut self, thread: ThreadId) -> InterpResult<'tcx, ThreadId> {
+        if self.thread_state(thread)? == ThreadState::Terminated {
+            throw_ub_format!("{:?} terminated, and cannot run again", thread);
+        }
+        let previous = self.threads.active;
+        if thread != previous {
+            trace!("switching from {:?} to {:?}", previous, thread);
+            std::mem::swap(&mut self.stack, &mut self.threads.threads[previous].stack);
+            std::mem::swap(&mut self.stack, &mut self.threads.threads[thread].stack);
+            self.threads.active = thread;
+        }
+        Ok(previous)
+    }
+
+    /// Blocks the active thread until `thread` terminated.
+    pub fn join_thread(&mut self, thread: ThreadId) -> InterpResult<'tcx> {
+        let active = self.threads.active;
+        if thread == active {
+            throw_ub_format!("{:?} tried to join itself", thread);
+        }
+        if self.thread_state(thread)? != ThreadState::Terminated {
+            self.threads.threads[active].state = ThreadState::BlockedOnJoin(thread);
+        }
+        Ok(())
+    }
+
+    /// Whether threads other than the main thread were created.
+    #[inline(always)]
+    pub(super) fn has_multiple_threads(&self) -> bool {
+        self.threads.threads.len() > 1
+    }
+
+    /// Marks the active thread as terminated, and unblocks the threads joining it.
+    fn terminate_active_thread(&mut self) {
+        let active = self.threads.active;
+        trace!("{:?} terminated", active);
+        self.threads.threads[active].state = ThreadState::Terminated;
+        for thread in self.threads.threads.iter_mut() {
+            if thread.state == ThreadState::BlockedOnJoin(active) {
+                thread.state = ThreadState::Enabled;
+            }
+        }
+    }
+
+    /// Lets the machine pick the thread to run the next step, and switches to another thread if
+    /// the active one cannot run. Threads with nothing left to run terminate and are skipped.
+    /// Returns `false` if all threads terminated.
+    pub(super) fn schedule_thread(&mut self) -> InterpResult<'tcx, bool> {
+        loop {
+            let active = self.threads.active;
+            if self.stack.is_empty()
+                && self.threads.threads[active].state != ThreadState::Terminated
+            {
+                self.terminate_active_thread();
+            }
+
+            let next = match M::schedule(self)? {
+                Some(next) => next,
+                // By default, keep running the active thread as long as it can.
+                None if self.threads.threads[active].state == ThreadState::Enabled => active,
+                None => {
+                    let threads = &self.threads.threads;
+                    match threads.indices().find(|&id| threads[id].state == ThreadState::Enabled) {
+                        Some(next) => next,
+                        None if threads.iter().all(|t| t.state == ThreadState::Terminated) => {
+                            return Ok(false);
+                        }
+                        None => throw_ub_format!("deadlock: all threads are blocked"),
+                    }
+                }
+            };
+            let state = self.thread_state(next)?;
+            if state != ThreadState::Enabled {
+                throw_unsup_format!("the machine scheduled {:?}, which is {:?}", next, state);
+            }
+            self.set_active_thread(next)?;
+            if !self.stack.is_empty() {
+                return Ok(true);
+            }
+        }
+    }
+}
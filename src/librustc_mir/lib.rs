@@ -28,6 +28,14 @@ extern crate log;
 #[macro_use]
 extern crate rustc;
 
+/// Logs a decision of a MIR pass, like `debug!`, and prints it when the pass is selected by
+/// `-Z trace-mir-pass`. See `util::pass_trace`.
+macro_rules! mir_trace {
+    ($($arg:tt)*) => {
+        $crate::util::pass_trace::event(format_args!($($arg)*))
+    };
+}
+
 mod borrow_check;
 pub mod const_eval;
 pub mod dataflow;
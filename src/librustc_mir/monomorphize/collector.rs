@@ -293,7 +293,15 @@ pub fn collect_crate_mono_items(
         tcx.sess.time("monomorphization_collector_graph_walk", || {
             par_iter(roots).for_each(|root| {
                 let mut recursion_depths = DefIdMap::default();
-                collect_items_rec(tcx, root, visited, &mut recursion_depths, inlining_map);
+                let mut instantiation_stack = Vec::new();
+                collect_items_rec(
+                    tcx,
+                    root,
+                    visited,
+                    &mut recursion_depths,
+                    &mut instantiation_stack,
+                    inlining_map,
+                );
             });
         });
     }
@@ -333,6 +341,7 @@ fn collect_items_rec<'tcx>(
     starting_point: MonoItem<'tcx>,
     visited: MTRef<'_, MTLock<FxHashSet<MonoItem<'tcx>>>>,
     recursion_depths: &mut DefIdMap<usize>,
+    instantiation_stack: &mut Vec<Instance<'tcx>>,
     inlining_map: MTRef<'_, MTLock<InliningMap<'tcx>>>,
 ) {
     if !visited.lock_mut().insert(starting_point.clone()) {
@@ -369,6 +378,8 @@ fn collect_items_rec<'tcx>(
             check_type_length_limit(tcx, instance);
 
             collect_neighbours(tcx, instance, &mut neighbors);
+            instantiation_stack.push(instance);
+            check_required_consts(tcx, instance, instantiation_stack);
         }
         MonoItem::GlobalAsm(..) => {
             recursion_depth_reset = None;
@@ -378,7 +389,18 @@ fn collect_items_rec<'tcx>(
     record_accesses(tcx, starting_point, &neighbors[..], inlining_map);
 
     for neighbour in neighbors {
-        collect_items_rec(tcx, neighbour, visited, recursion_depths, inlining_map);
+        collect_items_rec(
+            tcx,
+            neighbour,
+            visited,
+            recursion_depths,
+            instantiation_stack,
+            inlining_map,
+        );
+    }
+
+    if let MonoItem::Fn(_) = starting_point {
+        instantiation_stack.pop();
     }
 
     if let Some((def_id, depth)) = recursion_depth_reset {
@@ -1160,6 +1182,57 @@ fn collect_neighbours<'tcx>(
         .visit_body(body);
 }
 
+/// Evaluates the constants required by the MIR of `instance`, see `mir_required_consts`.
+///
+/// Whether such a constant fails to evaluate usually depends on the generic arguments, so
+/// errors explain the chain of instantiations, ending in `instantiation_stack`, that led here.
+fn check_required_consts<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: Instance<'tcx>,
+    instantiation_stack: &[Instance<'tcx>],
+) {
+    let def_id = match instance.def {
+        ty::InstanceDef::Item(def_id)
+            if def_id.is_local() && !tcx.is_constructor(def_id) && tcx.is_mir_available(def_id) =>
+        {
+            def_id
+        }
+        _ => return,
+    };
+
+    let param_env = ty::ParamEnv::reveal_all();
+    for required_const in tcx.mir_required_consts(def_id) {
+        let literal = tcx.subst_and_normalize_erasing_regions(
+            instance.substs,
+            param_env,
+            &required_const.literal,
+        );
+        let (def_id, substs, promoted) = match literal.val {
+            ty::ConstKind::Unevaluated(def_id, substs, promoted) => (def_id, substs, promoted),
+            _ => continue,
+        };
+        match tcx.const_eval_resolve(param_env, def_id, substs, promoted, None) {
+            Ok(_) => {}
+            Err(ErrorHandled::Reported) => {
+                let mut err = tcx
+                    .sess
+                    .struct_span_err(required_const.span, "erroneous constant encountered");
+                let mut users = instantiation_stack.iter().rev();
+                if let Some(user) = users.next() {
+                    err.note(&format!("required by `{}`", user));
+                }
+                for user in users {
+                    err.note(&format!("which is instantiated by `{}`", user));
+                }
+                err.emit();
+            }
+            Err(ErrorHandled::TooGeneric) => {
+                span_bug!(required_const.span, "collection encountered polymorphic constant")
+            }
+        }
+    }
+}
+
 fn def_id_to_string(tcx: TyCtxt<'_>, def_id: DefId) -> String {
     let mut output = String::new();
     let printer = DefPathBasedNames::new(tcx, false, false);
@@ -31,6 +31,11 @@ pub use self::AddCallGuards::*;
  */
 
 impl<'tcx> MirPass<'tcx> for AddCallGuards {
+    fn is_required(&self) -> bool {
+        // Codegen stores the results of the calls it turns into `invoke`s in their successors.
+        true
+    }
+
     fn run_pass(&self, _tcx: TyCtxt<'tcx>, _src: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
         self.add_call_guards(body);
     }
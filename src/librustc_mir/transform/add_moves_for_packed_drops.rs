@@ -1,5 +1,5 @@
 use rustc::mir::*;
-use rustc::ty::TyCtxt;
+use rustc::ty::{self, TyCtxt};
 use rustc_hir::def_id::DefId;
 
 use crate::transform::{MirPass, MirSource};
@@ -36,6 +36,18 @@ use crate::util::patch::MirPatch;
 //
 // The storage instructions are required to avoid stack space
 // blowup.
+//
+// Drops may also go through pointers stored in a packed struct, e.g.
+// when the rest of a `Box` field is dropped after moving out of its
+// contents. The pointee is aligned, but the pointer itself is not, so
+// we copy it to an aligned local first and drop through that:
+// ```
+//     storage.live(ptr_temp)
+//     ptr_temp = foo.boxed;
+//     drop((*ptr_temp).1) -> next
+// next:
+//     storage.dead(ptr_temp)
+// ```
 
 pub struct AddMovesForPackedDrops;
 
@@ -68,10 +80,16 @@ fn add_moves_for_packed_drops_patch<'tcx>(
         let terminator = data.terminator();
 
         match terminator.kind {
-            TerminatorKind::Drop { ref location, .. }
-                if util::is_disaligned(tcx, body, param_env, location) =>
-            {
-                add_move_for_packed_drop(tcx, body, &mut patch, terminator, loc, data.is_cleanup);
+            TerminatorKind::Drop { .. } => {
+                add_move_for_packed_drop(
+                    tcx,
+                    body,
+                    param_env,
+                    &mut patch,
+                    terminator,
+                    loc,
+                    data.is_cleanup,
+                );
             }
             TerminatorKind::DropAndReplace { .. } => {
                 span_bug!(terminator.source_info.span, "replace in AddMovesForPackedDrops");
@@ -86,31 +104,66 @@ fn add_moves_for_packed_drops_patch<'tcx>(
 fn add_move_for_packed_drop<'tcx>(
     tcx: TyCtxt<'tcx>,
     body: &Body<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
     patch: &mut MirPatch<'tcx>,
     terminator: &Terminator<'tcx>,
     loc: Location,
     is_cleanup: bool,
 ) {
-    debug!("add_move_for_packed_drop({:?} @ {:?})", terminator, loc);
     let (location, target, unwind) = match terminator.kind {
         TerminatorKind::Drop { ref location, target, unwind } => (location, target, unwind),
         _ => unreachable!(),
     };
+    let disaligned_derefs = util::disaligned_derefs(tcx, body, param_env, location);
+    let is_disaligned = util::is_disaligned(tcx, body, param_env, location);
+    if disaligned_derefs.is_empty() && !is_disaligned {
+        return;
+    }
+    debug!("add_move_for_packed_drop({:?} @ {:?})", terminator, loc);
 
     let source_info = terminator.source_info;
-    let ty = location.ty(body, tcx).ty;
-    let temp = patch.new_temp(ty, terminator.source_info.span);
+    let mut temps = vec![];
+
+    // Go through aligned copies of the misaligned pointers. `start` is the index of the
+    // projection element of `location` the projections of `local` start at.
+    let mut local = location.local;
+    let mut start = 0;
+    for index in disaligned_derefs {
+        let ty = Place::ty_from(location.local, &location.projection[..index], body, tcx).ty;
+        let pointer = Place {
+            local,
+            projection: tcx.intern_place_elems(&location.projection[start..index]),
+        };
+        let temp = patch.new_temp(ty, source_info.span);
+        patch.add_statement(loc, StatementKind::StorageLive(temp));
+        patch.add_assign(loc, Place::from(temp), Rvalue::Use(Operand::Copy(pointer)));
+        temps.push(temp);
+        local = temp;
+        start = index;
+    }
+    let mut place =
+        Place { local, projection: tcx.intern_place_elems(&location.projection[start..]) };
+
+    if is_disaligned {
+        let ty = location.ty(body, tcx).ty;
+        let temp = patch.new_temp(ty, source_info.span);
+        patch.add_statement(loc, StatementKind::StorageLive(temp));
+        patch.add_assign(loc, Place::from(temp), Rvalue::Use(Operand::Move(place)));
+        temps.push(temp);
+        place = Place::from(temp);
+    }
 
     let storage_dead_block = patch.new_block(BasicBlockData {
-        statements: vec![Statement { source_info, kind: StatementKind::StorageDead(temp) }],
+        statements: temps
+            .into_iter()
+            .rev()
+            .map(|temp| Statement { source_info, kind: StatementKind::StorageDead(temp) })
+            .collect(),
         terminator: Some(Terminator { source_info, kind: TerminatorKind::Goto { target } }),
         is_cleanup,
     });
-
-    patch.add_statement(loc, StatementKind::StorageLive(temp));
-    patch.add_assign(loc, Place::from(temp), Rvalue::Use(Operand::Move(*location)));
     patch.patch_terminator(
         loc.block,
-        TerminatorKind::Drop { location: Place::from(temp), target: storage_dead_block, unwind },
+        TerminatorKind::Drop { location: place, target: storage_dead_block, unwind },
     );
 }
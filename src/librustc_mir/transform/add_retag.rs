@@ -3,9 +3,13 @@
 //! introducing these calls *adds* UB -- so, conceptually, this pass is actually part
 //! of MIR building, and only after this pass we think of the program has having the
 //! normal MIR semantics.
+//!
+//! Which retags get emitted is controlled by `-Z mir-emit-retag=<mode>`, so that aliasing models
+//! can experiment with retagging less often; see `MirEmitRetag`.
 
 use crate::transform::{MirPass, MirSource};
 use rustc::mir::*;
+use rustc::session::config::MirEmitRetag;
 use rustc::ty::{self, Ty, TyCtxt};
 
 pub struct AddRetag;
@@ -59,9 +63,10 @@ fn may_be_reference<'tcx>(ty: Ty<'tcx>) -> bool {
 
 impl<'tcx> MirPass<'tcx> for AddRetag {
     fn run_pass(&self, tcx: TyCtxt<'tcx>, _src: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
-        if !tcx.sess.opts.debugging_opts.mir_emit_retag {
-            return;
-        }
+        let mode = match tcx.sess.opts.debugging_opts.mir_emit_retag {
+            Some(mode) => mode,
+            None => return,
+        };
         let (span, arg_count) = (body.span, body.arg_count);
         let (basic_blocks, local_decls) = body.basic_blocks_and_local_decls_mut();
         let needs_retag = |place: &Place<'tcx>| {
@@ -96,6 +101,10 @@ impl<'tcx> MirPass<'tcx> for AddRetag {
             );
         }
 
+        if mode == MirEmitRetag::FnEntry {
+            return;
+        }
+
         // PART 2
         // Retag return values of functions.  Also escape-to-raw the argument of `drop`.
         // We collect the return destinations because we cannot mutate while iterating.
@@ -128,7 +137,7 @@ impl<'tcx> MirPass<'tcx> for AddRetag {
                 0,
                 Statement {
                     source_info,
-                    kind: StatementKind::Retag(RetagKind::Default, box (dest_place)),
+                    kind: StatementKind::Retag(RetagKind::FnReturn, box (dest_place)),
                 },
             );
         }
@@ -150,7 +159,8 @@ impl<'tcx> MirPass<'tcx> for AddRetag {
                     StatementKind::Assign(box (ref place, ref rvalue)) if needs_retag(place) => {
                         let kind = match rvalue {
                             Rvalue::Ref(_, borrow_kind, _)
-                                if borrow_kind.allows_two_phase_borrow() =>
+                                if mode == MirEmitRetag::TwoPhaseAware
+                                    && borrow_kind.allows_two_phase_borrow() =>
                             {
                                 RetagKind::TwoPhase
                             }
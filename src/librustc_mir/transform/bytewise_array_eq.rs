@@ -0,0 +1,96 @@
+//! Compares arrays of integers, `char`s and `bool`s bytewise.
+//!
+//! `<[T; N] as PartialEq>::eq` compares the arrays as slices, through several layers of generic
+//! calls which are only cheap once inlined, and the element-wise loop only turns into a
+//! `memcmp` deep inside of them. For such element types, arrays are equal exactly when their
+//! bytes are, so we call the `bytewise_eq` lang item instead, which compares both arrays with a
+//! single `memcmp`:
+//!
+//! ```rust
+//! _0 = const <[u8; 4] as std::cmp::PartialEq>::eq(move _3, move _4) -> bb1;
+//! ```
+//!
+//! becomes
+//!
+//! ```rust
+//! _0 = const core::slice::bytewise_eq::<[u8; 4]>(move _3, move _4) -> bb1;
+//! ```
+
+use crate::transform::{MirPass, MirSource};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_hir::def_id::DefId;
+use rustc_span::symbol::sym;
+
+pub struct BytewiseArrayEq;
+
+impl<'tcx> MirPass<'tcx> for BytewiseArrayEq {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let lang_items = tcx.lang_items();
+        let (eq_trait, bytewise_eq_fn) = match (lang_items.eq_trait(), lang_items.bytewise_eq_fn())
+        {
+            (Some(eq_trait), Some(bytewise_eq_fn)) => (eq_trait, bytewise_eq_fn),
+            _ => return,
+        };
+
+        for bb_data in body.basic_blocks_mut() {
+            let terminator = bb_data.terminator_mut();
+            let span = terminator.source_info.span;
+            let func = match &mut terminator.kind {
+                TerminatorKind::Call { func, .. } => func,
+                _ => continue,
+            };
+            let func_ty = match func {
+                Operand::Constant(constant) => constant.literal.ty,
+                _ => continue,
+            };
+            let array_ty = match bytewise_comparable_array(tcx, eq_trait, func_ty) {
+                Some(array_ty) => array_ty,
+                None => continue,
+            };
+            if !tcx.consider_optimizing(|| {
+                format!("BytewiseArrayEq {:?} {:?}", source.def_id(), span)
+            }) {
+                return;
+            }
+
+            debug!("comparing `{:?}` bytewise at {:?}", array_ty, span);
+            let substs = tcx.intern_substs(&[array_ty.into()]);
+            *func = Operand::function_handle(tcx, bytewise_eq_fn, substs, span);
+        }
+    }
+}
+
+/// If `func_ty` is `<[T; N] as PartialEq<[T; N]>>::eq`, where `T` can be compared bytewise,
+/// returns `[T; N]`.
+fn bytewise_comparable_array<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    eq_trait: DefId,
+    func_ty: Ty<'tcx>,
+) -> Option<Ty<'tcx>> {
+    let (def_id, substs) = match func_ty.kind {
+        ty::FnDef(def_id, substs) => (def_id, substs),
+        _ => return None,
+    };
+    if tcx.trait_of_item(def_id) != Some(eq_trait) || tcx.item_name(def_id) != sym::eq {
+        return None;
+    }
+
+    let (lhs, rhs) = (substs.type_at(0), substs.type_at(1));
+    if lhs != rhs {
+        return None;
+    }
+    match lhs.kind {
+        ty::Array(elem_ty, _) => match elem_ty.kind {
+            // Mirrors the `BytewiseEquality` types of `core::slice`.
+            ty::Int(_) | ty::Uint(_) | ty::Char | ty::Bool => Some(lhs),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,255 @@
+//! Builds the graph of the calls between the bodies of the crate.
+//!
+//! The calls of each body are collected from `mir_validated`, with the trait methods resolved in
+//! the generic body, so that the graph doesn't depend on the optimizations, which may then use
+//! it. The calls that can't be resolved are kept as `Callee::Unknown`, for the users that need to
+//! be conservative about what may be called.
+//!
+//! As the callees have to be collected before the optimizations steal `mir_validated`, they are
+//! only collected if the graph is used, see `is_callgraph_used`.
+
+use rustc::lint::builtin::UNCONDITIONAL_MUTUAL_RECURSION;
+use rustc::lint::Level;
+use rustc::mir::{BasicBlock, Body, CallGraph, CallGraphScc, CallSite, Callee};
+use rustc::mir::{TerminatorKind, START_BLOCK};
+use rustc::ty::{self, Instance, InstanceDef, TyCtxt};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
+use rustc_data_structures::graph::scc::Sccs;
+use rustc_data_structures::graph::vec_graph::VecGraph;
+use rustc_hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
+use rustc_hir::CRATE_HIR_ID;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_span::symbol::sym;
+use rustc_span::Span;
+
+/// Whether the call graph is used in this crate, so that `optimized_mir` has to collect the
+/// callees before stealing the MIR they are collected from. Its only user is the
+/// `unconditional_mutual_recursion` lint, which isn't checked if it is allowed for the whole
+/// crate.
+pub fn is_callgraph_used(tcx: TyCtxt<'_>) -> bool {
+    tcx.lint_level_at_node(UNCONDITIONAL_MUTUAL_RECURSION, CRATE_HIR_ID).0 != Level::Allow
+}
+
+pub(super) fn mir_callees(tcx: TyCtxt<'_>, def_id: DefId) -> &[CallSite] {
+    // Constructors don't call anything, and don't go through `mir_validated`.
+    if tcx.is_constructor(def_id) {
+        return &[];
+    }
+
+    let param_env = tcx.param_env(def_id);
+    let (body, promoted) = tcx.mir_validated(def_id);
+    let mut callees = Vec::new();
+    collect_callees(tcx, param_env, &body.borrow(), true, &mut callees);
+    for promoted in promoted.borrow().iter() {
+        collect_callees(tcx, param_env, promoted, false, &mut callees);
+    }
+    tcx.arena.alloc_from_iter(callees)
+}
+
+fn collect_callees<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+    check_paths: bool,
+    callees: &mut Vec<CallSite>,
+) {
+    let mut call_blocks = Vec::new();
+    let mut callbacks = Vec::new();
+    for (bb, bb_data) in body.basic_blocks().iter_enumerated() {
+        let terminator = bb_data.terminator();
+        let func = match &terminator.kind {
+            TerminatorKind::Call { func, .. } => func,
+            _ => continue,
+        };
+        let span = terminator.source_info.span;
+        let callee = match func.ty(body, tcx).kind {
+            ty::FnDef(def_id, substs) => {
+                let substs = tcx.erase_regions(&substs);
+                let callee = match Instance::resolve(tcx, param_env, def_id, substs) {
+                    Some(instance) => resolved_callee(tcx, instance),
+                    None => Callee::Unknown,
+                };
+                if let Callee::Extern(_) = callee {
+                    collect_callbacks(tcx, substs, span, &mut callbacks);
+                }
+                callee
+            }
+            _ => Callee::Unknown,
+        };
+        call_blocks.push((bb, callee, span));
+    }
+
+    // The blocks calling each local callee, to find the callees called on every path.
+    let mut local_calls = FxIndexMap::<DefId, BitSet<BasicBlock>>::default();
+    if check_paths {
+        for &(bb, callee, _) in &call_blocks {
+            if let Callee::Local(def_id) = callee {
+                local_calls
+                    .entry(def_id)
+                    .or_insert_with(|| BitSet::new_empty(body.basic_blocks().len()))
+                    .insert(bb);
+            }
+        }
+    }
+    let unconditional_callees: FxHashSet<DefId> = local_calls
+        .into_iter()
+        .filter(|(_, calls)| always_calls(body, calls))
+        .map(|(def_id, _)| def_id)
+        .collect();
+
+    callees.extend(call_blocks.into_iter().map(|(_, callee, span)| {
+        let unconditional = match callee {
+            Callee::Local(def_id) => unconditional_callees.contains(&def_id),
+            Callee::Extern(_) | Callee::Unknown => false,
+        };
+        CallSite { callee, span, unconditional }
+    }));
+    callees.extend(callbacks);
+}
+
+/// Records the local functions and closures a generic function of another crate is
+/// instantiated with, which it may call back, as called at `span`. Whether they are called, even
+/// if the function always returns, is not known.
+fn collect_callbacks<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    substs: ty::subst::SubstsRef<'tcx>,
+    span: Span,
+    callbacks: &mut Vec<CallSite>,
+) {
+    let mut seen = FxHashSet::default();
+    for ty in substs.types().flat_map(|ty| ty.walk()) {
+        let def_id = match ty.kind {
+            ty::FnDef(def_id, _) | ty::Closure(def_id, _) => def_id,
+            _ => continue,
+        };
+        if let Callee::Local(def_id) = local_or_extern(tcx, def_id) {
+            if seen.insert(def_id) {
+                let callee = Callee::Local(def_id);
+                callbacks.push(CallSite { callee, span, unconditional: false });
+            }
+        }
+    }
+}
+
+/// Returns whether every path from the entry of `body` to a return goes through one of the
+/// `calls`, by looking for a path that doesn't, treating the calls as sinks. Like for the
+/// `unconditional_recursion` lint, a body that always diverges only counts as calling if one of
+/// the calls can be reached.
+fn always_calls(body: &Body<'_>, calls: &BitSet<BasicBlock>) -> bool {
+    let mut visited = BitSet::new_empty(body.basic_blocks().len());
+    let mut queue = vec![START_BLOCK];
+    let mut reached_call = false;
+    while let Some(bb) = queue.pop() {
+        if !visited.insert(bb) {
+            continue;
+        }
+        if calls.contains(bb) {
+            reached_call = true;
+            continue;
+        }
+        let terminator = body.basic_blocks()[bb].terminator();
+        match terminator.kind {
+            TerminatorKind::Abort | TerminatorKind::Return => return false,
+            _ => {}
+        }
+        queue.extend(terminator.successors().copied());
+    }
+    reached_call
+}
+
+fn local_or_extern(tcx: TyCtxt<'_>, def_id: DefId) -> Callee {
+    if def_id.is_local() && tcx.is_mir_available(def_id) {
+        Callee::Local(def_id)
+    } else {
+        Callee::Extern(def_id)
+    }
+}
+
+fn resolved_callee<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> Callee {
+    match instance.def {
+        InstanceDef::Item(def_id)
+        | InstanceDef::VtableShim(def_id)
+        | InstanceDef::ReifyShim(def_id) => local_or_extern(tcx, def_id),
+        // The `FnOnce` shim calls the `FnMut` method of the closure, which is its body.
+        InstanceDef::ClosureOnceShim { .. } => match instance.substs.type_at(0).kind {
+            ty::Closure(def_id, _) => local_or_extern(tcx, def_id),
+            _ => Callee::Unknown,
+        },
+        InstanceDef::Intrinsic(def_id)
+        | InstanceDef::DropGlue(def_id, _)
+        | InstanceDef::CloneShim(def_id, _) => Callee::Extern(def_id),
+        InstanceDef::Virtual(..) | InstanceDef::FnPtrShim(..) => Callee::Unknown,
+    }
+}
+
+pub(super) fn mir_callgraph(tcx: TyCtxt<'_>, krate: CrateNum) -> &CallGraph {
+    assert_eq!(krate, LOCAL_CRATE);
+
+    // Number the bodies in a deterministic order.
+    #[allow(rustc::unordered_iteration)]
+    let mut nodes: Vec<DefId> = tcx.mir_keys(LOCAL_CRATE).iter().copied().collect();
+    nodes.sort();
+    let node_indices: FxHashMap<DefId, usize> =
+        nodes.iter().enumerate().map(|(index, &def_id)| (def_id, index)).collect();
+
+    let mut edges = Vec::new();
+    let mut calls_itself = BitSet::new_empty(nodes.len());
+    for (caller, &def_id) in nodes.iter().enumerate() {
+        for call_site in tcx.mir_callees(def_id) {
+            let callee = match call_site.callee {
+                Callee::Local(callee) => node_indices[&callee],
+                Callee::Extern(_) | Callee::Unknown => continue,
+            };
+            if callee == caller {
+                calls_itself.insert(caller);
+            }
+            edges.push((caller, callee));
+        }
+    }
+    let graph = VecGraph::new(nodes.len(), edges);
+    let sccs: Sccs<usize, CallGraphScc> = Sccs::new(&graph);
+
+    let mut scc_indices = FxHashMap::default();
+    let mut scc_members: IndexVec<CallGraphScc, Vec<DefId>> =
+        IndexVec::from_elem_n(Vec::new(), sccs.num_sccs());
+    for (node, &def_id) in nodes.iter().enumerate() {
+        let scc = sccs.scc(node);
+        scc_indices.insert(def_id, scc);
+        scc_members[scc].push(def_id);
+    }
+    let mut recursive_sccs = BitSet::new_empty(sccs.num_sccs());
+    for scc in sccs.all_sccs() {
+        let members = &scc_members[scc];
+        if members.len() > 1 || calls_itself.contains(node_indices[&members[0]]) {
+            recursive_sccs.insert(scc);
+        }
+    }
+
+    tcx.arena.alloc(CallGraph::new(scc_indices, scc_members, recursive_sccs))
+}
+
+/// Reports the callees of the bodies marked with `#[rustc_dump_callees]` as errors, for testing.
+pub fn dump_callees(tcx: TyCtxt<'_>) {
+    if !tcx.features().rustc_attrs || !is_callgraph_used(tcx) {
+        return;
+    }
+
+    #[allow(rustc::unordered_iteration)]
+    let mut def_ids: Vec<DefId> = tcx.mir_keys(LOCAL_CRATE).iter().copied().collect();
+    def_ids.sort();
+    for def_id in def_ids {
+        if !tcx.has_attr(def_id, sym::rustc_dump_callees) {
+            continue;
+        }
+        for call_site in tcx.mir_callees(def_id) {
+            let callee = match call_site.callee {
+                Callee::Local(def_id) => format!("`{}`", tcx.def_path_str(def_id)),
+                Callee::Extern(def_id) => format!("extern `{}`", tcx.def_path_str(def_id)),
+                Callee::Unknown => "unknown".to_string(),
+            };
+            let always = if call_site.unconditional { ", always" } else { "" };
+            tcx.sess.span_err(call_site.span, &format!("callee: {}{}", callee, always));
+        }
+    }
+}
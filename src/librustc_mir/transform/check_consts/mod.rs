@@ -109,5 +109,9 @@ impl fmt::Display for ConstKind {
 
 /// Returns `true` if this `DefId` points to one of the official `panic` lang items.
 pub fn is_lang_panic_fn(tcx: TyCtxt<'tcx>, def_id: DefId) -> bool {
-    Some(def_id) == tcx.lang_items().panic_fn() || Some(def_id) == tcx.lang_items().begin_panic_fn()
+    let lang_items = tcx.lang_items();
+    Some(def_id) == lang_items.panic_fn()
+        || Some(def_id) == lang_items.begin_panic_fn()
+        || Some(def_id) == lang_items.panic_fmt_fn()
+        || Some(def_id) == lang_items.begin_panic_fmt_fn()
 }
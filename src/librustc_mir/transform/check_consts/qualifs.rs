@@ -6,11 +6,35 @@ use rustc_span::DUMMY_SP;
 
 use super::Item as ConstCx;
 
+/// Something that is done for each `Qualif`, see `for_each_qualif`.
+pub trait QualifVisitor {
+    fn visit_qualif<Q: Qualif + 'static>(&mut self, qualif: Q);
+}
+
+/// Calls `visitor` with every `Qualif`, in the order of the variants of `QualifKind`. This is the
+/// only place listing all the qualifs; everything that needs to handle each of them goes
+/// through here.
+pub fn for_each_qualif(visitor: &mut impl QualifVisitor) {
+    visitor.visit_qualif(HasMutInterior);
+    visitor.visit_qualif(NeedsDrop);
+}
+
 pub fn in_any_value_of_ty(cx: &ConstCx<'_, 'tcx>, ty: Ty<'tcx>) -> ConstQualifs {
-    ConstQualifs {
-        has_mut_interior: HasMutInterior::in_any_value_of_ty(cx, ty),
-        needs_drop: NeedsDrop::in_any_value_of_ty(cx, ty),
+    struct InAnyValueOfTy<'a, 'mir, 'tcx> {
+        cx: &'a ConstCx<'mir, 'tcx>,
+        ty: Ty<'tcx>,
+        qualifs: ConstQualifs,
     }
+
+    impl QualifVisitor for InAnyValueOfTy<'_, '_, '_> {
+        fn visit_qualif<Q: Qualif + 'static>(&mut self, _: Q) {
+            self.qualifs.set(Q::KIND, Q::in_any_value_of_ty(self.cx, self.ty));
+        }
+    }
+
+    let mut visitor = InAnyValueOfTy { cx, ty, qualifs: ConstQualifs::default() };
+    for_each_qualif(&mut visitor);
+    visitor.qualifs
 }
 
 /// A "qualif"(-ication) is a way to look for something "bad" in the MIR that would disqualify some
@@ -19,14 +43,23 @@ pub fn in_any_value_of_ty(cx: &ConstCx<'_, 'tcx>, ty: Ty<'tcx>) -> ConstQualifs
 /// definitely cannot find anything bad anywhere.
 ///
 /// The default implementations proceed structurally.
+///
+/// To add a new qualif, add a variant to `QualifKind`, implement this trait and register the
+/// implementation in `for_each_qualif`. The propagation of the qualif through the MIR, its
+/// dataflow analysis and its result in `ConstQualifs` then come for free.
 pub trait Qualif {
+    /// The `QualifKind` identifying this qualif in `ConstQualifs`.
+    const KIND: QualifKind;
+
     /// The name of the file used to debug the dataflow analysis that computes this qualif.
     const ANALYSIS_NAME: &'static str;
 
     /// Whether this `Qualif` is cleared when a local is moved from.
     const IS_CLEARED_ON_MOVE: bool = false;
 
-    fn in_qualifs(qualifs: &ConstQualifs) -> bool;
+    fn in_qualifs(qualifs: &ConstQualifs) -> bool {
+        qualifs.get(Self::KIND)
+    }
 
     /// Return the qualification that is (conservatively) correct for any value
     /// of the type.
@@ -187,12 +220,9 @@ pub trait Qualif {
 pub struct HasMutInterior;
 
 impl Qualif for HasMutInterior {
+    const KIND: QualifKind = QualifKind::HasMutInterior;
     const ANALYSIS_NAME: &'static str = "flow_has_mut_interior";
 
-    fn in_qualifs(qualifs: &ConstQualifs) -> bool {
-        qualifs.has_mut_interior
-    }
-
     fn in_any_value_of_ty(cx: &ConstCx<'_, 'tcx>, ty: Ty<'tcx>) -> bool {
         !ty.is_freeze(cx.tcx, cx.param_env, DUMMY_SP)
     }
@@ -227,13 +257,10 @@ impl Qualif for HasMutInterior {
 pub struct NeedsDrop;
 
 impl Qualif for NeedsDrop {
+    const KIND: QualifKind = QualifKind::NeedsDrop;
     const ANALYSIS_NAME: &'static str = "flow_needs_drop";
     const IS_CLEARED_ON_MOVE: bool = true;
 
-    fn in_qualifs(qualifs: &ConstQualifs) -> bool {
-        qualifs.needs_drop
-    }
-
     fn in_any_value_of_ty(cx: &ConstCx<'_, 'tcx>, ty: Ty<'tcx>) -> bool {
         ty.needs_drop(cx.tcx, cx.param_env)
     }
@@ -17,7 +17,7 @@ use std::borrow::Cow;
 use std::ops::Deref;
 
 use super::ops::{self, NonConstOp};
-use super::qualifs::{self, HasMutInterior, NeedsDrop};
+use super::qualifs::{self, HasMutInterior, NeedsDrop, QualifVisitor};
 use super::resolver::FlowSensitiveAnalysis;
 use super::{is_lang_panic_fn, ConstKind, Item, Qualif};
 use crate::const_eval::{is_const_fn, is_unstable_const_fn};
@@ -53,41 +53,74 @@ impl<Q: Qualif> QualifCursor<'a, 'mir, 'tcx, Q> {
     }
 }
 
+/// The results of the dataflow analysis of some `Qualif`, independently of its type.
+trait QualifResults {
+    fn kind(&self) -> QualifKind;
+
+    /// Returns `true` if `local` is qualified at the given `Location`, ignoring indirect
+    /// assignments.
+    ///
+    /// Only updates the cursor if absolutely necessary.
+    fn contains(&mut self, local: Local, location: Location) -> bool;
+}
+
+impl<Q: Qualif> QualifResults for QualifCursor<'_, '_, '_, Q> {
+    fn kind(&self) -> QualifKind {
+        Q::KIND
+    }
+
+    fn contains(&mut self, local: Local, location: Location) -> bool {
+        if !self.in_any_value_of_ty.contains(local) {
+            return false;
+        }
+
+        self.cursor.seek_before(location);
+        self.cursor.get().contains(local)
+    }
+}
+
 pub struct Qualifs<'a, 'mir, 'tcx> {
-    has_mut_interior: QualifCursor<'a, 'mir, 'tcx, HasMutInterior>,
-    needs_drop: QualifCursor<'a, 'mir, 'tcx, NeedsDrop>,
+    /// The results for each `Qualif`, in the order of `QualifKind`.
+    per_qualif: Vec<Box<dyn QualifResults + 'a>>,
     indirectly_mutable: IndirectlyMutableResults<'mir, 'tcx>,
 }
 
 impl Qualifs<'a, 'mir, 'tcx> {
+    fn new(
+        item: &'a Item<'mir, 'tcx>,
+        indirectly_mutable: IndirectlyMutableResults<'mir, 'tcx>,
+    ) -> Self {
+        struct Analyze<'a, 'mir, 'tcx> {
+            item: &'a Item<'mir, 'tcx>,
+            per_qualif: Vec<Box<dyn QualifResults + 'a>>,
+        }
+
+        impl QualifVisitor for Analyze<'a, 'mir, 'tcx> {
+            fn visit_qualif<Q: Qualif + 'static>(&mut self, qualif: Q) {
+                assert_eq!(Q::KIND as usize, self.per_qualif.len());
+                self.per_qualif.push(Box::new(QualifCursor::new(qualif, self.item)));
+            }
+        }
+
+        let mut analyze = Analyze { item, per_qualif: vec![] };
+        qualifs::for_each_qualif(&mut analyze);
+        Qualifs { per_qualif: analyze.per_qualif, indirectly_mutable }
+    }
+
     fn indirectly_mutable(&mut self, local: Local, location: Location) -> bool {
         self.indirectly_mutable.seek_before(location);
         self.indirectly_mutable.get().contains(local)
     }
 
-    /// Returns `true` if `local` is `NeedsDrop` at the given `Location`.
-    ///
-    /// Only updates the cursor if absolutely necessary
-    fn needs_drop(&mut self, local: Local, location: Location) -> bool {
-        if !self.needs_drop.in_any_value_of_ty.contains(local) {
-            return false;
-        }
-
-        self.needs_drop.cursor.seek_before(location);
-        self.needs_drop.cursor.get().contains(local) || self.indirectly_mutable(local, location)
+    /// Returns `true` if `local` is `Q` at the given `Location`.
+    fn in_local<Q: Qualif>(&mut self, local: Local, location: Location) -> bool {
+        self.in_local_of_kind(Q::KIND, local, location)
     }
 
-    /// Returns `true` if `local` is `HasMutInterior` at the given `Location`.
-    ///
-    /// Only updates the cursor if absolutely necessary.
-    fn has_mut_interior(&mut self, local: Local, location: Location) -> bool {
-        if !self.has_mut_interior.in_any_value_of_ty.contains(local) {
-            return false;
-        }
-
-        self.has_mut_interior.cursor.seek_before(location);
-        self.has_mut_interior.cursor.get().contains(local)
-            || self.indirectly_mutable(local, location)
+    fn in_local_of_kind(&mut self, kind: QualifKind, local: Local, location: Location) -> bool {
+        let results = &mut self.per_qualif[kind as usize];
+        debug_assert_eq!(results.kind(), kind);
+        results.contains(local, location) || self.indirectly_mutable(local, location)
     }
 
     fn in_return_place(&mut self, item: &Item<'_, 'tcx>) -> ConstQualifs {
@@ -112,10 +145,12 @@ impl Qualifs<'a, 'mir, 'tcx> {
 
         let return_loc = item.body.terminator_loc(return_block);
 
-        ConstQualifs {
-            needs_drop: self.needs_drop(RETURN_PLACE, return_loc),
-            has_mut_interior: self.has_mut_interior(RETURN_PLACE, return_loc),
+        let mut qualifs = ConstQualifs::default();
+        for i in 0..self.per_qualif.len() {
+            let kind = self.per_qualif[i].kind();
+            qualifs.set(kind, self.in_local_of_kind(kind, RETURN_PLACE, return_loc));
         }
+        qualifs
     }
 }
 
@@ -139,9 +174,6 @@ impl Validator<'a, 'mir, 'tcx> {
     pub fn new(item: &'a Item<'mir, 'tcx>) -> Self {
         let Item { tcx, body, def_id, param_env, .. } = *item;
 
-        let needs_drop = QualifCursor::new(NeedsDrop, item);
-        let has_mut_interior = QualifCursor::new(HasMutInterior, item);
-
         // We can use `unsound_ignore_borrow_on_drop` here because custom drop impls are not
         // allowed in a const.
         //
@@ -153,7 +185,7 @@ impl Validator<'a, 'mir, 'tcx> {
             .iterate_to_fixpoint()
             .into_results_cursor(*body);
 
-        let qualifs = Qualifs { needs_drop, has_mut_interior, indirectly_mutable };
+        let qualifs = Qualifs::new(item, indirectly_mutable);
 
         Validator { span: item.body.span, item, qualifs }
     }
@@ -212,7 +244,7 @@ impl Validator<'a, 'mir, 'tcx> {
 
         // If an operation is supported in miri (and is not already controlled by a feature gate) it
         // can be turned on with `-Zunleash-the-miri-inside-of-you`.
-        let is_unleashable = O::IS_SUPPORTED_IN_MIRI && O::feature_gate(self.tcx).is_none();
+        let is_unleashable = O::IS_SUPPORTED_IN_MIRI && O::feature_gate().is_none();
 
         if is_unleashable && self.tcx.sess.opts.debugging_opts.unleash_the_miri_inside_of_you {
             self.tcx.sess.span_warn(span, "skipping const checks");
@@ -345,7 +377,7 @@ impl Visitor<'tcx> for Validator<'_, 'mir, 'tcx> {
             | Rvalue::AddressOf(Mutability::Not, ref place) => {
                 let borrowed_place_has_mut_interior = HasMutInterior::in_place(
                     &self.item,
-                    &mut |local| self.qualifs.has_mut_interior(local, location),
+                    &mut |local| self.qualifs.in_local::<HasMutInterior>(local, location),
                     place.as_ref(),
                 );
 
@@ -444,7 +476,11 @@ impl Visitor<'tcx> for Validator<'_, 'mir, 'tcx> {
                 }
 
                 if context.is_mutating_use() {
-                    self.check_op(ops::MutDeref);
+                    if base_ty.is_box() {
+                        self.check_op(ops::MutBoxDeref);
+                    } else {
+                        self.check_op(ops::MutDeref);
+                    }
                 }
             }
 
@@ -490,6 +526,7 @@ impl Visitor<'tcx> for Validator<'_, 'mir, 'tcx> {
             | StatementKind::InlineAsm { .. }
             | StatementKind::Retag { .. }
             | StatementKind::AscribeUserType(..)
+            | StatementKind::Assume(..)
             | StatementKind::Nop => {}
         }
     }
@@ -534,6 +571,15 @@ impl Visitor<'tcx> for Validator<'_, 'mir, 'tcx> {
                     }
                 }
 
+                // Calls of the methods of trait objects are checked during evaluation, as the impl
+                // they call is only known from the vtable.
+                if let Some(Instance { def: InstanceDef::Virtual(..), .. }) =
+                    Instance::resolve(self.tcx, self.param_env, def_id, substs)
+                {
+                    self.check_op(ops::FnCallTraitObject);
+                    return;
+                }
+
                 if is_lang_panic_fn(self.tcx, def_id) {
                     self.check_op(ops::Panic);
                 } else if let Some(feature) = is_unstable_const_fn(self.tcx, def_id) {
@@ -565,7 +611,7 @@ impl Visitor<'tcx> for Validator<'_, 'mir, 'tcx> {
                 let needs_drop = if let Some(local) = dropped_place.as_local() {
                     // Use the span where the local was declared as the span of the drop error.
                     err_span = self.body.local_decls[local].source_info.span;
-                    self.qualifs.needs_drop(local, location)
+                    self.qualifs.in_local::<NeedsDrop>(local, location)
                 } else {
                     true
                 };
@@ -0,0 +1,249 @@
+//! Implements the `dead_assignments` lint, which detects assignments to user variables whose
+//! value is never read on any path:
+//!
+//! ```rust
+//! let mut x = 0;
+//! if c { x = 1; } else { x = 2; }
+//! use(x);
+//! ```
+//!
+//! Unlike `unused_assignments`, which works on the HIR, this uses a liveness analysis of the MIR,
+//! so it sees through the control flow, and knows when the value assigned in one iteration of a
+//! loop is read by the next one.
+//!
+//! Dropping a value does not count as reading it. Variables that are mutably borrowed or whose
+//! address is taken are not linted, as they could be read through pointers. Variables that are
+//! never read at all are left to `unused_variables`.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::lint::builtin::DEAD_ASSIGNMENTS;
+use rustc::lint::Level;
+use rustc::mir::visit::{
+    MutatingUseContext, NonMutatingUseContext, NonUseContext, PlaceContext, Visitor,
+};
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_span::source_map::original_sp;
+use rustc_span::Symbol;
+
+pub struct CheckDeadAssignments;
+
+impl<'tcx> MirPass<'tcx> for CheckDeadAssignments {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        if source.promoted.is_some() {
+            return;
+        }
+        // Don't bother computing liveness if the lint is allowed in every scope of the body.
+        let linted = body.source_scopes.iter().any(|scope| match &scope.local_data {
+            ClearCrossCrate::Set(data) => {
+                tcx.lint_level_at_node(DEAD_ASSIGNMENTS, data.lint_root).0 != Level::Allow
+            }
+            ClearCrossCrate::Clear => false,
+        });
+        if !linted {
+            return;
+        }
+        let body = read_only!(body);
+        let variables = linted_variables(body);
+        if variables.is_empty() {
+            return;
+        }
+
+        let live_out = live_out_of_blocks(body);
+        for (block, data) in traversal::reverse_postorder(&body) {
+            let mut live = live_out[block].clone();
+            let mut dead_assignments = vec![];
+            let terminator_location = body.terminator_loc(block);
+            let effect = Effect::of_terminator(data.terminator(), terminator_location);
+            effect.apply(&mut live, |local| dead_assignments.push((local, terminator_location)));
+            for (statement_index, statement) in data.statements.iter().enumerate().rev() {
+                let location = Location { block, statement_index };
+                let effect = Effect::of_statement(statement, location);
+                effect.apply(&mut live, |local| dead_assignments.push((local, location)));
+            }
+
+            for (local, location) in dead_assignments {
+                let name = match variables.get(&local) {
+                    Some(&name) => name,
+                    None => continue,
+                };
+                let source_info = body.source_info(location);
+                let lint_root = match &body.source_scopes[source_info.scope].local_data {
+                    ClearCrossCrate::Set(data) => data.lint_root,
+                    ClearCrossCrate::Clear => continue,
+                };
+                // Point at the macro invocation if the assignment comes from a macro the variable
+                // was passed to.
+                let span = original_sp(source_info.span, body.local_decls[local].source_info.span);
+                tcx.struct_span_lint_hir(DEAD_ASSIGNMENTS, lint_root, span, |lint| {
+                    lint.build(&format!("value assigned to `{}` is never read", name))
+                        .help("maybe it is overwritten before being read?")
+                        .emit()
+                });
+            }
+        }
+    }
+}
+
+/// Returns the names of the user variables to lint.
+fn linted_variables(body: ReadOnlyBodyAndCache<'_, '_>) -> FxHashMap<Local, Symbol> {
+    let mut uses = UseCollector {
+        read: BitSet::new_empty(body.local_decls.len()),
+        escaping: BitSet::new_empty(body.local_decls.len()),
+    };
+    uses.visit_body(body);
+
+    body.var_debug_info
+        .iter()
+        .filter_map(|info| Some((info.value.place()?.as_local()?, info.name)))
+        .filter(|&(local, name)| {
+            let decl = &body.local_decls[local];
+            decl.is_user_variable()
+                && !decl.from_compiler_desugaring()
+                && !name.as_str().starts_with('_')
+                && uses.read.contains(local)
+                && !uses.escaping.contains(local)
+        })
+        .collect()
+}
+
+/// Computes the locals whose value may be read after each block.
+fn live_out_of_blocks(body: ReadOnlyBodyAndCache<'_, '_>) -> IndexVec<BasicBlock, BitSet<Local>> {
+    let effects: IndexVec<BasicBlock, Vec<Effect>> = body
+        .basic_blocks()
+        .iter_enumerated()
+        .map(|(block, data)| {
+            let terminator_location = body.terminator_loc(block);
+            let mut effects = vec![Effect::of_terminator(data.terminator(), terminator_location)];
+            for (statement_index, statement) in data.statements.iter().enumerate().rev() {
+                let location = Location { block, statement_index };
+                effects.push(Effect::of_statement(statement, location));
+            }
+            effects
+        })
+        .collect();
+
+    let mut live_out: IndexVec<BasicBlock, _> = body
+        .basic_blocks()
+        .indices()
+        .map(|_| BitSet::new_empty(body.local_decls.len()))
+        .collect();
+    let mut live = BitSet::new_empty(body.local_decls.len());
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (block, _) in traversal::postorder(&body) {
+            live.overwrite(&live_out[block]);
+            for effect in &effects[block] {
+                effect.apply(&mut live, |_| {});
+            }
+            for &pred in body.predecessors_for(block) {
+                changed |= live_out[pred].union(&live);
+            }
+        }
+    }
+    live_out
+}
+
+/// What a statement or terminator does to the locals, as seen by liveness.
+#[derive(Default)]
+struct Effect {
+    /// The locals overwritten as a whole, and whether that is an assignment to be linted.
+    defs: Vec<(Local, bool)>,
+    /// The locals read.
+    uses: Vec<Local>,
+}
+
+impl Effect {
+    fn of_statement(statement: &Statement<'_>, location: Location) -> Self {
+        let mut effect = Effect::default();
+        match statement.kind {
+            // These only exist for borrowck, and don't read anything.
+            StatementKind::FakeRead(..) | StatementKind::AscribeUserType(..) => {}
+            _ => effect.visit_statement(statement, location),
+        }
+        effect
+    }
+
+    fn of_terminator(terminator: &Terminator<'_>, location: Location) -> Self {
+        let mut effect = Effect::default();
+        match &terminator.kind {
+            TerminatorKind::DropAndReplace { location: place, value, .. } => {
+                match place.as_local() {
+                    Some(local) => effect.defs.push((local, true)),
+                    None => {
+                        let context = PlaceContext::MutatingUse(MutatingUseContext::Drop);
+                        effect.visit_place(place, context, location);
+                    }
+                }
+                effect.visit_operand(value, location);
+            }
+            _ => effect.visit_terminator(terminator, location),
+        }
+        effect
+    }
+
+    /// Applies the effect backwards to `live`, the locals live after it. Calls
+    /// `on_dead_assignment` for the linted assignments of values that are not live.
+    fn apply(&self, live: &mut BitSet<Local>, mut on_dead_assignment: impl FnMut(Local)) {
+        for &(local, linted) in &self.defs {
+            if linted && !live.contains(local) {
+                on_dead_assignment(local);
+            }
+            live.remove(local);
+        }
+        for &local in &self.uses {
+            live.insert(local);
+        }
+    }
+}
+
+impl Visitor<'_> for Effect {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        match context {
+            PlaceContext::MutatingUse(MutatingUseContext::Store)
+            | PlaceContext::MutatingUse(MutatingUseContext::Call)
+            | PlaceContext::MutatingUse(MutatingUseContext::AsmOutput) => {
+                self.defs.push((local, true))
+            }
+            PlaceContext::MutatingUse(MutatingUseContext::Drop)
+            | PlaceContext::NonUse(NonUseContext::StorageLive)
+            | PlaceContext::NonUse(NonUseContext::StorageDead) => self.defs.push((local, false)),
+            PlaceContext::NonUse(NonUseContext::VarDebugInfo)
+            | PlaceContext::NonUse(NonUseContext::AscribeUserTy) => {}
+            _ => self.uses.push(local),
+        }
+    }
+}
+
+/// Finds the locals that are read, and those that could be accessed through pointers.
+struct UseCollector {
+    read: BitSet<Local>,
+    escaping: BitSet<Local>,
+}
+
+impl Visitor<'_> for UseCollector {
+    fn visit_statement(&mut self, statement: &Statement<'_>, location: Location) {
+        match statement.kind {
+            StatementKind::FakeRead(..) | StatementKind::AscribeUserType(..) => {}
+            _ => self.super_statement(statement, location),
+        }
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        match context {
+            PlaceContext::MutatingUse(MutatingUseContext::Borrow)
+            | PlaceContext::MutatingUse(MutatingUseContext::AddressOf)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::AddressOf) => {
+                self.escaping.insert(local);
+            }
+            PlaceContext::NonMutatingUse(_) => {
+                self.read.insert(local);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Implements the `huge_function_body` lint, which detects functions whose MIR has more
+//! statements than `#![huge_function_body_limit]`, e.g. because of what some derive or
+//! procedural macro expands to. The lint points out the macro invocations in the function that
+//! most of the statements come from.
+//!
+//! The MIR is checked before optimizations, so that the lint also fires in check builds and for
+//! generic functions, which are only optimized when they are used.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::lint::builtin::HUGE_FUNCTION_BODY;
+use rustc::lint::Level;
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_span::hygiene::ExpnKind;
+use rustc_span::Span;
+use std::cmp::Reverse;
+
+/// The number of macro invocations pointed out by the lint.
+const MAX_NOTED_EXPANSIONS: usize = 3;
+
+pub struct CheckHugeFunctionBody;
+
+impl<'tcx> MirPass<'tcx> for CheckHugeFunctionBody {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let id = tcx.hir().as_local_hir_id(source.def_id()).unwrap();
+        if !tcx.hir().body_owner_kind(id).is_fn_or_closure() || source.promoted.is_some() {
+            return;
+        }
+        let lint_root = match &body.source_scopes[OUTERMOST_SOURCE_SCOPE].local_data {
+            ClearCrossCrate::Set(data) => data.lint_root,
+            ClearCrossCrate::Clear => return,
+        };
+        // Don't bother counting if nobody will hear about it.
+        if tcx.lint_level_at_node(HUGE_FUNCTION_BODY, lint_root).0 == Level::Allow {
+            return;
+        }
+
+        let limit = *tcx.sess.huge_function_body_limit.get();
+        let mut size = 0;
+        // The number of statements per outermost macro invocation, with the macro's name.
+        let mut expansions = FxIndexMap::<Span, (usize, String)>::default();
+        let mut count = |source_info: &SourceInfo| {
+            size += 1;
+            if let Some((call_site, name)) = outermost_macro_call(source_info.span) {
+                expansions.entry(call_site).or_insert((0, name)).0 += 1;
+            }
+        };
+        for bb_data in body.basic_blocks() {
+            for statement in &bb_data.statements {
+                match statement.kind {
+                    StatementKind::StorageLive(_)
+                    | StatementKind::StorageDead(_)
+                    | StatementKind::Nop => {}
+                    _ => count(&statement.source_info),
+                }
+            }
+            count(&bb_data.terminator().source_info);
+        }
+        if size <= limit {
+            return;
+        }
+
+        let mut expansions: Vec<_> = expansions.into_iter().collect();
+        expansions.sort_by_key(|&(call_site, (count, _))| (Reverse(count), call_site));
+        tcx.struct_span_lint_hir(HUGE_FUNCTION_BODY, lint_root, body.span, |lint| {
+            let mut err = lint.build(&format!(
+                "this function has {} MIR statements, more than the limit of {}",
+                size, limit
+            ));
+            for (call_site, (count, name)) in expansions.into_iter().take(MAX_NOTED_EXPANSIONS) {
+                err.span_note(call_site, &format!("{} of them come from this `{}`", count, name));
+            }
+            err.help(&format!(
+                "split up the function, or raise the limit with \
+                `#![huge_function_body_limit = \"{}\"]`",
+                size
+            ));
+            err.emit()
+        });
+    }
+}
+
+/// Returns the call site and the name of the outermost macro `span` was expanded from, if any.
+fn outermost_macro_call(span: Span) -> Option<(Span, String)> {
+    let mut macro_call = None;
+    let mut span = span;
+    while span.from_expansion() {
+        let expn_data = span.ctxt().outer_expn_data();
+        if let ExpnKind::Macro(..) = expn_data.kind {
+            macro_call = Some((expn_data.call_site, expn_data.kind.descr()));
+        }
+        span = expn_data.call_site;
+    }
+    macro_call
+}
@@ -0,0 +1,48 @@
+//! Implements the `infinite_loop_without_side_effects` lint, which points out the loops that
+//! never terminate and have no side effects, like `loop {}`.
+//!
+//! This runs on the MIR borrowck checks, so the lint fires in check builds and in generic
+//! functions too. `MarkSideEffectFreeLoops` finds the same loops in the optimized MIR for codegen.
+
+use crate::transform::mark_side_effect_free_loops::side_effect_free_loops;
+use crate::transform::{MirPass, MirSource};
+use rustc::lint::builtin::INFINITE_LOOP_WITHOUT_SIDE_EFFECTS;
+use rustc::lint::Level;
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+
+pub struct CheckInfiniteLoops;
+
+impl<'tcx> MirPass<'tcx> for CheckInfiniteLoops {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        if source.promoted.is_some() || !body.is_cfg_cyclic() {
+            return;
+        }
+        // Don't bother looking for the loops if the lint is allowed in every scope of the body.
+        let linted = body.source_scopes.iter().any(|scope| match &scope.local_data {
+            ClearCrossCrate::Set(data) => {
+                tcx.lint_level_at_node(INFINITE_LOOP_WITHOUT_SIDE_EFFECTS, data.lint_root).0
+                    != Level::Allow
+            }
+            ClearCrossCrate::Clear => false,
+        });
+        if !linted {
+            return;
+        }
+
+        let body = read_only!(body);
+        for header in side_effect_free_loops(body) {
+            let source_info = body[header].terminator().source_info;
+            let lint_root = match &body.source_scopes[source_info.scope].local_data {
+                ClearCrossCrate::Set(data) => data.lint_root,
+                ClearCrossCrate::Clear => continue,
+            };
+            tcx.struct_span_lint_hir(
+                INFINITE_LOOP_WITHOUT_SIDE_EFFECTS,
+                lint_root,
+                source_info.span,
+                |lint| lint.build("this loop never terminates and has no side effects").emit(),
+            );
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Implements `-Z lint-int2ptr-casts`, which warns about every cast of an integer to a pointer, to
+//! help auditing code for strict provenance.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::mir::visit::Visitor;
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+
+pub struct CheckIntToPtrCasts;
+
+impl<'tcx> MirPass<'tcx> for CheckIntToPtrCasts {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        if !tcx.sess.opts.debugging_opts.lint_int2ptr_casts || source.promoted.is_some() {
+            return;
+        }
+
+        let mut checker = IntToPtrCastChecker { tcx, source, body };
+        for (bb, data) in body.basic_blocks().iter_enumerated() {
+            checker.visit_basic_block_data(bb, data);
+        }
+    }
+}
+
+struct IntToPtrCastChecker<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    source: MirSource<'tcx>,
+    body: &'a Body<'tcx>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for IntToPtrCastChecker<'a, 'tcx> {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Cast(CastKind::Misc, operand, cast_ty) = rvalue {
+            let operand_ty = operand.ty(self.body, self.tcx);
+            if operand_ty.is_integral() && cast_ty.is_unsafe_ptr() {
+                let span = self.body.source_info(location).span;
+                let message = format!("cast of integer `{}` to pointer `{}`", operand_ty, cast_ty);
+                let note = format!("in `{}`", self.tcx.def_path_str(self.source.def_id()));
+                self.tcx
+                    .sess
+                    .struct_span_warn(span, &message)
+                    .note(&note)
+                    .help("the resulting pointer has no provenance")
+                    .emit();
+            }
+        }
+        self.super_rvalue(rvalue, location);
+    }
+}
@@ -0,0 +1,143 @@
+//! Implements the `redundant_match_arms` lint, which detects matches like
+//!
+//! ```rust
+//! match x {
+//!     Ok(v) => Ok(v),
+//!     Err(e) => Err(e),
+//! }
+//! ```
+//!
+//! that can be replaced by just `x`. The arms are recognized by `util::match_identity`, the same
+//! way `SimplifyArmIdentity` recognizes the arms it optimizes away.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::match_identity::match_arm_identity;
+use rustc::lint::builtin::REDUNDANT_MATCH_ARMS;
+use rustc::mir::*;
+use rustc::ty::{self, TyCtxt};
+
+pub struct CheckMatchArms;
+
+impl<'tcx> MirPass<'tcx> for CheckMatchArms {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        if source.promoted.is_some() {
+            return;
+        }
+
+        for bb_data in body.basic_blocks() {
+            if let Some(source_info) = redundant_match(tcx, body, bb_data) {
+                let lint_root = match &body.source_scopes[source_info.scope].local_data {
+                    ClearCrossCrate::Set(data) => data.lint_root,
+                    ClearCrossCrate::Clear => continue,
+                };
+                let span = source_info.span;
+                tcx.struct_span_lint_hir(REDUNDANT_MATCH_ARMS, lint_root, span, |lint| {
+                    lint.build("every arm of this match rebuilds the matched value")
+                        .span_label(span, "this value is returned unchanged")
+                        .help("the match can be replaced by the matched expression")
+                        .emit()
+                });
+            }
+        }
+    }
+}
+
+/// If `bb_data` switches on the discriminant of a place and every arm rebuilds that place,
+/// returns the source info of the matched place.
+fn redundant_match<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    bb_data: &BasicBlockData<'tcx>,
+) -> Option<SourceInfo> {
+    let (discr, values, targets) = match &bb_data.terminator().kind {
+        TerminatorKind::SwitchInt { discr, values, targets, .. } => {
+            (discr.place()?, values, targets)
+        }
+        _ => return None,
+    };
+
+    // Only look at `match` expressions, which read the matched place first.
+    let (source_info, matched) = bb_data.statements.iter().find_map(|statement| {
+        match &statement.kind {
+            StatementKind::FakeRead(FakeReadCause::ForMatchedPlace, place) => {
+                Some((statement.source_info, place.as_local()?))
+            }
+            _ => None,
+        }
+    })?;
+    if source_info.span.from_expansion() {
+        return None;
+    }
+    let switches_on_matched = bb_data.statements.iter().any(|statement| match &statement.kind {
+        StatementKind::Assign(box (place, Rvalue::Discriminant(read))) => {
+            place == discr && read.as_local() == Some(matched)
+        }
+        _ => false,
+    });
+    if !switches_on_matched {
+        return None;
+    }
+    let adt_def = match body.local_decls[matched].ty.kind {
+        ty::Adt(adt_def, _) if adt_def.is_enum() => adt_def,
+        _ => return None,
+    };
+
+    // The local all arms write to, and the block they all continue in.
+    let mut dest = None;
+    let mut join = None;
+    let mut check_arm = |target: BasicBlock, variant| -> Option<()> {
+        let block = &body[arm_block(body, target)];
+        let arm = match_arm_identity(&body.local_decls, block).ok()?;
+        let next = match block.terminator().kind {
+            TerminatorKind::Goto { target } => target,
+            _ => return None,
+        };
+        // Removing an arm doing other things as well would change what the code does.
+        if arm.variant != variant
+            || !arm.unrelated.is_empty()
+            || arm.source.map_or(false, |source| source != matched)
+            || *dest.get_or_insert(arm.dest) != arm.dest
+            || *join.get_or_insert(next) != next
+        {
+            return None;
+        }
+        Some(())
+    };
+
+    let (&otherwise, targets) = targets.split_last()?;
+    let mut covered = vec![];
+    for (&value, &target) in values.iter().zip(targets) {
+        let (variant, _) = adt_def.discriminants(tcx).find(|(_, discr)| discr.val == value)?;
+        check_arm(target, variant)?;
+        covered.push(variant);
+    }
+
+    // The `otherwise` arm must be unreachable, or cover exactly the one remaining variant.
+    let otherwise_block = &body[arm_block(body, otherwise)];
+    if otherwise_block.terminator().kind != TerminatorKind::Unreachable {
+        let mut remaining = adt_def.variants.indices().filter(|variant| !covered.contains(variant));
+        match (remaining.next(), remaining.next()) {
+            (Some(variant), None) => check_arm(otherwise, variant)?,
+            _ => return None,
+        }
+    }
+
+    Some(source_info)
+}
+
+/// Skips the blocks without statements that match lowering puts in front of the arms.
+fn arm_block(body: &Body<'_>, mut block: BasicBlock) -> BasicBlock {
+    // Bounded, as such blocks may form a cycle.
+    for _ in body.basic_blocks().indices() {
+        let bb_data = &body[block];
+        if !bb_data.statements.is_empty() {
+            break;
+        }
+        block = match bb_data.terminator().kind {
+            TerminatorKind::Goto { target }
+            | TerminatorKind::FalseEdges { real_target: target, .. } => target,
+            _ => break,
+        };
+    }
+    block
+}
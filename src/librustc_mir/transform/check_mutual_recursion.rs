@@ -0,0 +1,76 @@
+//! Implements the `unconditional_mutual_recursion` lint, the counterpart of
+//! `unconditional_recursion` for the cycles of more than one function: it detects the strongly
+//! connected components of the `mir_callgraph` in which no function can return without calling
+//! another function of the component, e.g.
+//!
+//! ```rust
+//! fn is_even(n: u32) -> bool { !is_odd(n) }
+//! fn is_odd(n: u32) -> bool { !is_even(n) }
+//! ```
+//!
+//! Only the calls made on every path to a return are considered, see `CallSite::unconditional`,
+//! so a function can't return without calling some function of the component only if it
+//! unconditionally calls one of them in particular.
+
+use crate::transform::callgraph::is_callgraph_used;
+use rustc::lint::builtin::UNCONDITIONAL_MUTUAL_RECURSION;
+use rustc::mir::{CallSite, Callee};
+use rustc::ty::TyCtxt;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+
+pub fn check_mutual_recursion(tcx: TyCtxt<'_>) {
+    if !is_callgraph_used(tcx) {
+        return;
+    }
+    let call_graph = tcx.mir_callgraph(LOCAL_CRATE);
+    'sccs: for scc in call_graph.sccs() {
+        let members = call_graph.scc_members(scc);
+        // Single functions are handled by `unconditional_recursion`, and generators are resumed
+        // rather than called.
+        if members.len() < 2 || members.iter().any(|&def_id| tcx.generator_kind(def_id).is_some())
+        {
+            continue;
+        }
+
+        let mut recursive_calls: Vec<(DefId, Vec<&CallSite>)> = Vec::new();
+        for &def_id in members {
+            let calls: Vec<_> = tcx
+                .mir_callees(def_id)
+                .iter()
+                .filter(|call| match call.callee {
+                    Callee::Local(callee) => call.unconditional && members.contains(&callee),
+                    Callee::Extern(_) | Callee::Unknown => false,
+                })
+                .collect();
+            if calls.is_empty() {
+                continue 'sccs;
+            }
+            recursive_calls.push((def_id, calls));
+        }
+
+        // The lint is controlled at the first function of the cycle.
+        let def_span = |def_id| {
+            let hir_id = tcx.hir().as_local_hir_id(def_id).unwrap();
+            (hir_id, tcx.sess.source_map().def_span(tcx.hir().span(hir_id)))
+        };
+        let (hir_id, sp) = def_span(members[0]);
+        tcx.struct_span_lint_hir(UNCONDITIONAL_MUTUAL_RECURSION, hir_id, sp, |lint| {
+            let mut db = lint.build("functions cannot return without calling each other");
+            for (def_id, calls) in &recursive_calls {
+                let callee = match calls[0].callee {
+                    Callee::Local(callee) => callee,
+                    Callee::Extern(_) | Callee::Unknown => unreachable!(),
+                };
+                db.span_label(
+                    def_span(*def_id).1,
+                    format!("cannot return without calling `{}`", tcx.def_path_str(callee)),
+                );
+                for call in calls {
+                    db.span_label(call.span, "recursive call site");
+                }
+            }
+            db.help("a `loop` may express intention better if this is on purpose");
+            db.emit();
+        });
+    }
+}
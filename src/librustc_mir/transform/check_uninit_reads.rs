@@ -0,0 +1,291 @@
+//! Implements the `uninitialized_reads` lint, which detects values left uninitialized by
+//! `MaybeUninit::uninit()` or `mem::uninitialized()` that are used before anything could have
+//! written to them:
+//!
+//! ```rust
+//! let x = MaybeUninit::<u32>::uninit();
+//! let y = unsafe { x.assume_init() };
+//! ```
+//!
+//! A forward dataflow analysis tracks, for every local, whether its contents may have been
+//! written since it was set by one of these functions. Assigning to the local or to part of it,
+//! borrowing it or passing it to inline assembly counts as a write, so the lint only fires for
+//! the obvious cases, where there is no way the value got initialized.
+//!
+//! Only types which certainly have no valid uninitialized values are linted, e.g. integers,
+//! references or structs containing one. `MaybeUninit`s, unions and type parameters could be
+//! fine, as could the arrays of `MaybeUninit` commonly created with
+//! `MaybeUninit::uninit().assume_init()`.
+
+use crate::dataflow::generic::{Analysis, AnalysisDomain, ResultsCursor};
+use crate::dataflow::BottomValue;
+use crate::transform::{MirPass, MirSource};
+use rustc::lint::builtin::UNINITIALIZED_READS;
+use rustc::lint::LintDiagnosticBuilder;
+use rustc::mir::visit::{NonMutatingUseContext, PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_index::bit_set::BitSet;
+use rustc_span::symbol::{sym, Symbol};
+
+pub struct CheckUninitReads;
+
+impl<'tcx> MirPass<'tcx> for CheckUninitReads {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        if source.promoted.is_some() {
+            return;
+        }
+        let body: &Body<'tcx> = body;
+        let analysis = MaybeWrittenLocals::new(tcx, body);
+        // Nothing to check if nothing is left uninitialized.
+        if !body.basic_blocks().iter().any(|data| analysis.uninit_source(data.terminator())) {
+            return;
+        }
+
+        let mut cursor = analysis
+            .into_engine(tcx, body, source.def_id())
+            .iterate_to_fixpoint()
+            .into_results_cursor(body);
+        for (block, data) in body.basic_blocks().iter_enumerated() {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let location = Location { block, statement_index };
+                cursor.seek_before(location);
+                let mut checker = ReadChecker { tcx, body, cursor: &cursor };
+                checker.visit_statement(statement, location);
+            }
+
+            let location = body.terminator_loc(block);
+            let terminator = data.terminator();
+            cursor.seek_before(location);
+            if let TerminatorKind::Call { func, args, .. } = &terminator.kind {
+                if is_fn(tcx, body, func, sym::assume_init) {
+                    check_assume_init(tcx, body, &cursor, args, terminator.source_info);
+                }
+            }
+            let mut checker = ReadChecker { tcx, body, cursor: &cursor };
+            checker.visit_terminator(terminator, location);
+        }
+    }
+}
+
+/// Lints a call to `MaybeUninit::assume_init` on a local that was never written to.
+fn check_assume_init<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    cursor: &ResultsCursor<'_, 'tcx, MaybeWrittenLocals<'_, 'tcx>>,
+    args: &[Operand<'tcx>],
+    source_info: SourceInfo,
+) {
+    let local = match args.first().and_then(|arg| arg.place()).and_then(|arg| arg.as_local()) {
+        Some(local) => local,
+        None => return,
+    };
+    if cursor.contains(local) {
+        return;
+    }
+    let ty = match body.local_decls[local].ty.kind {
+        ty::Adt(_, substs) => substs.type_at(0),
+        _ => return,
+    };
+    if !requires_init(tcx, ty) {
+        return;
+    }
+    lint(tcx, body, source_info, |lint| {
+        lint.build("`assume_init` called on a `MaybeUninit` that was never written to")
+            .span_label(source_info.span, format!("`{}` cannot be left uninitialized", ty))
+            .help("write to the `MaybeUninit` or use `MaybeUninit::new` first")
+            .emit()
+    });
+}
+
+fn lint<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    source_info: SourceInfo,
+    decorate: impl for<'a> FnOnce(LintDiagnosticBuilder<'a>),
+) {
+    let lint_root = match &body.source_scopes[source_info.scope].local_data {
+        ClearCrossCrate::Set(data) => data.lint_root,
+        ClearCrossCrate::Clear => return,
+    };
+    tcx.struct_span_lint_hir(UNINITIALIZED_READS, lint_root, source_info.span, decorate);
+}
+
+/// Lints reads of values of types that must be initialized, which were never written to.
+struct ReadChecker<'a, 'mir, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'mir Body<'tcx>,
+    cursor: &'a ResultsCursor<'mir, 'tcx, MaybeWrittenLocals<'mir, 'tcx>>,
+}
+
+impl<'tcx> Visitor<'tcx> for ReadChecker<'_, '_, 'tcx> {
+    fn visit_operand(&mut self, operand: &Operand<'tcx>, location: Location) {
+        let local = match operand.place() {
+            Some(place) => place.local,
+            None => return,
+        };
+        if self.cursor.contains(local) || self.cursor.analysis().copies_uninit.contains(local) {
+            return;
+        }
+        let ty = self.body.local_decls[local].ty;
+        let source_info = *self.body.source_info(location);
+        lint(self.tcx, self.body, source_info, |lint| {
+            lint.build("use of a value that was never initialized")
+                .span_label(source_info.span, format!("`{}` cannot be left uninitialized", ty))
+                .note("the value was created by `mem::uninitialized`")
+                .emit()
+        });
+    }
+}
+
+/// A dataflow analysis whose state holds the locals whose contents may have been written to. The
+/// locals left uninitialized by a call to `MaybeUninit::uninit` or `mem::uninitialized` are
+/// removed from it, everything else is assumed to be written.
+struct MaybeWrittenLocals<'mir, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'mir Body<'tcx>,
+    /// The locals which can be copied while uninitialized. Copying them copies their state.
+    copies_uninit: BitSet<Local>,
+}
+
+impl MaybeWrittenLocals<'mir, 'tcx> {
+    fn new(tcx: TyCtxt<'tcx>, body: &'mir Body<'tcx>) -> Self {
+        let mut copies_uninit = BitSet::new_empty(body.local_decls.len());
+        for (local, decl) in body.local_decls.iter_enumerated() {
+            if !requires_init(tcx, decl.ty) {
+                copies_uninit.insert(local);
+            }
+        }
+        MaybeWrittenLocals { tcx, body, copies_uninit }
+    }
+
+    /// Whether `terminator` calls a function returning an uninitialized value.
+    fn uninit_source(&self, terminator: &Terminator<'tcx>) -> bool {
+        match &terminator.kind {
+            TerminatorKind::Call { func, .. } => {
+                is_fn(self.tcx, self.body, func, sym::maybe_uninit_uninit)
+                    || is_fn(self.tcx, self.body, func, sym::mem_uninitialized)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl BottomValue for MaybeWrittenLocals<'_, '_> {
+    // bottom = never written
+    const BOTTOM_VALUE: bool = false;
+}
+
+impl AnalysisDomain<'tcx> for MaybeWrittenLocals<'_, 'tcx> {
+    type Idx = Local;
+
+    const NAME: &'static str = "maybe_written_locals";
+
+    fn bits_per_block(&self, body: &Body<'tcx>) -> usize {
+        body.local_decls.len()
+    }
+
+    fn initialize_start_block(&self, _: &Body<'tcx>, state: &mut BitSet<Self::Idx>) {
+        state.insert_all();
+    }
+}
+
+impl Analysis<'tcx> for MaybeWrittenLocals<'_, 'tcx> {
+    fn apply_statement_effect(
+        &self,
+        state: &mut BitSet<Local>,
+        statement: &Statement<'tcx>,
+        location: Location,
+    ) {
+        if let StatementKind::Assign(box (place, Rvalue::Use(operand))) = &statement.kind {
+            let copy = (place.as_local(), operand.place().and_then(|from| from.as_local()));
+            if let (Some(into), Some(from)) = copy {
+                if self.copies_uninit.contains(from) && !state.contains(from) {
+                    state.remove(into);
+                } else {
+                    state.insert(into);
+                }
+                return;
+            }
+        }
+        WriteCollector { state }.visit_statement(statement, location);
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        state: &mut BitSet<Local>,
+        terminator: &Terminator<'tcx>,
+        location: Location,
+    ) {
+        WriteCollector { state }.visit_terminator(terminator, location);
+    }
+
+    fn apply_call_return_effect(
+        &self,
+        state: &mut BitSet<Local>,
+        block: BasicBlock,
+        _func: &Operand<'tcx>,
+        _args: &[Operand<'tcx>],
+        return_place: &Place<'tcx>,
+    ) {
+        match return_place.as_local() {
+            Some(local) if self.uninit_source(self.body[block].terminator()) => {
+                state.remove(local);
+            }
+            _ => {
+                state.insert(return_place.local);
+            }
+        }
+    }
+}
+
+/// Adds the locals written to or borrowed to the state.
+struct WriteCollector<'a> {
+    state: &'a mut BitSet<Local>,
+}
+
+impl Visitor<'_> for WriteCollector<'_> {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        let is_borrow = match context {
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::SharedBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::ShallowBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::UniqueBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::AddressOf) => true,
+            _ => false,
+        };
+        if is_borrow || context.is_mutating_use() {
+            self.state.insert(local);
+        }
+    }
+}
+
+/// Whether `func` is the function with the diagnostic item `name`.
+fn is_fn<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, func: &Operand<'tcx>, name: Symbol) -> bool {
+    match func.ty(body, tcx).kind {
+        ty::FnDef(def_id, _) => tcx.is_diagnostic_item(name, def_id),
+        _ => false,
+    }
+}
+
+/// Whether leaving a value of type `ty` uninitialized is certainly undefined behavior.
+fn requires_init<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    match ty.kind {
+        ty::Bool
+        | ty::Char
+        | ty::Int(_)
+        | ty::Uint(_)
+        | ty::Float(_)
+        | ty::Ref(..)
+        | ty::RawPtr(_)
+        | ty::FnPtr(_)
+        | ty::Never => true,
+        ty::Adt(adt_def, _) if adt_def.is_union() => false,
+        ty::Adt(adt_def, substs) => {
+            (adt_def.is_enum() && adt_def.variants.len() != 1)
+                || adt_def.all_fields().any(|field| requires_init(tcx, field.ty(tcx, substs)))
+        }
+        ty::Array(elem, _) => requires_init(tcx, elem),
+        ty::Tuple(..) => ty.tuple_fields().any(|ty| requires_init(tcx, ty)),
+        _ => false,
+    }
+}
@@ -112,6 +112,11 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                 "inline assembly is entirely unchecked and can cause undefined behavior",
                 UnsafetyViolationKind::General,
             ),
+            StatementKind::Assume(..) => self.require_unsafe(
+                "assumption",
+                "an assumption that doesn't hold is undefined behavior",
+                UnsafetyViolationKind::General,
+            ),
         }
         self.super_statement(statement, location);
     }
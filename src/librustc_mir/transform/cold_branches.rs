@@ -0,0 +1,93 @@
+//! Sets the `target_weights` of `SwitchInt`s, where some of the targets inevitably end up in a
+//! call to a `#[cold]` function, or in a call that never returns, like a panic. Those targets
+//! are weighted as unlikely, which makes codegen move them out of the hot path.
+
+use crate::transform::{mir_opt_level, optimize_for_size, MirPass, MirSource};
+use rustc::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::{self, TyCtxt};
+use rustc_index::bit_set::BitSet;
+
+/// The weights of likely and unlikely targets, the same as LLVM uses for `llvm.expect`.
+const LIKELY_WEIGHT: u32 = 2000;
+const UNLIKELY_WEIGHT: u32 = 1;
+
+pub struct ColdBranchWeights;
+
+impl<'tcx> MirPass<'tcx> for ColdBranchWeights {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Stable
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        // We only run when optimizing MIR (at any level), or for size, as moving the cold paths
+        // out of line keeps them from being duplicated.
+        if mir_opt_level(tcx, source) == 0 && !optimize_for_size(tcx, source) {
+            return;
+        }
+
+        let cold = cold_blocks(tcx, body);
+        if cold.is_empty() {
+            return;
+        }
+
+        for bb_data in body.basic_blocks_mut() {
+            if let TerminatorKind::SwitchInt { targets, target_weights, .. } =
+                &mut bb_data.terminator_mut().kind
+            {
+                let cold_targets = targets.iter().filter(|&&target| cold.contains(target)).count();
+                if target_weights.is_some() || cold_targets == 0 || cold_targets == targets.len() {
+                    continue;
+                }
+                *target_weights = Some(
+                    targets
+                        .iter()
+                        .map(|&target| {
+                            if cold.contains(target) { UNLIKELY_WEIGHT } else { LIKELY_WEIGHT }
+                        })
+                        .collect(),
+                );
+            }
+        }
+    }
+}
+
+/// Returns the blocks from which every path leads to a cold call. Unwinding is not considered.
+fn cold_blocks<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> BitSet<BasicBlock> {
+    let mut cold = BitSet::new_empty(body.basic_blocks().len());
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (bb, bb_data) in traversal::postorder(body) {
+            if bb_data.is_cleanup || cold.contains(bb) {
+                continue;
+            }
+            let terminator = bb_data.terminator();
+            let is_cold = is_cold_call(tcx, body, &terminator.kind) || {
+                let mut successors =
+                    terminator.successors().filter(|&&succ| !body[succ].is_cleanup).peekable();
+                successors.peek().is_some() && successors.all(|&succ| cold.contains(succ))
+            };
+            if is_cold {
+                cold.insert(bb);
+                changed = true;
+            }
+        }
+    }
+    cold
+}
+
+fn is_cold_call<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, kind: &TerminatorKind<'tcx>) -> bool {
+    match kind {
+        // Diverging calls, e.g. to `panic!`.
+        TerminatorKind::Call { destination: None, .. } => true,
+        TerminatorKind::Call { func, .. } => match func.ty(body, tcx).kind {
+            ty::FnDef(def_id, _) => {
+                tcx.codegen_fn_attrs(def_id).flags.contains(CodegenFnAttrFlags::COLD)
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
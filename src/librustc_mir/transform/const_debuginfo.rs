@@ -0,0 +1,88 @@
+//! Describes the user variables that are only ever assigned a constant by that constant in their
+//! debuginfo, like the `x` of
+//!
+//! ```rust
+//! let x = 42;
+//! f(x);
+//! ```
+//!
+//! once `ConstProp` replaced its use with `const 42i32`. The debuginfo then no longer refers to the
+//! local, which leaves it unused, and `SimplifyLocals` removes it along with its assignment while
+//! the debugger still shows the value of `x`.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::def_use::{DefUseAnalysis, Info};
+use rustc::mir::visit::{NonMutatingUseContext, NonUseContext, PlaceContext};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::TyCtxt;
+
+pub struct ConstDebugInfo;
+
+impl<'tcx> MirPass<'tcx> for ConstDebugInfo {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let mut def_use_analysis = DefUseAnalysis::new(body);
+        def_use_analysis.analyze(read_only!(body));
+
+        let def_id = source.def_id();
+        for index in 0..body.var_debug_info.len() {
+            let local = match body.var_debug_info[index].value.place().and_then(Place::as_local) {
+                Some(local) => local,
+                None => continue,
+            };
+            let info = def_use_analysis.local_info(local);
+            let constant = match only_assigned_constant(body, local, info) {
+                Some(constant) => constant.clone(),
+                None => continue,
+            };
+            if !tcx.consider_optimizing(|| format!("ConstDebugInfo {:?} {:?}", def_id, local)) {
+                return;
+            }
+            body.var_debug_info[index].value = VarDebugInfoContents::Const(constant);
+        }
+    }
+}
+
+/// The constant assigned to `local`, if that assignment is the only def of the local, and the
+/// local is otherwise only read directly, not borrowed or projected out of.
+fn only_assigned_constant<'a, 'tcx>(
+    body: &'a Body<'tcx>,
+    local: Local,
+    info: &Info,
+) -> Option<&'a Constant<'tcx>> {
+    match body.local_kind(local) {
+        LocalKind::Var | LocalKind::Temp => {}
+        LocalKind::Arg | LocalKind::ReturnPointer => return None,
+    }
+
+    let mut constant = None;
+    for place_use in &info.defs_and_uses {
+        match place_use.context {
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::Move)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::Inspect)
+            | PlaceContext::NonUse(NonUseContext::StorageLive)
+            | PlaceContext::NonUse(NonUseContext::StorageDead)
+            | PlaceContext::NonUse(NonUseContext::VarDebugInfo) => {}
+            context if context.is_place_assignment() && constant.is_none() => {
+                let statement = body.basic_blocks()[place_use.location.block]
+                    .statements
+                    .get(place_use.location.statement_index)?;
+                match &statement.kind {
+                    StatementKind::Assign(box (place, Rvalue::Use(Operand::Constant(c))))
+                        if place.as_local() == Some(local) =>
+                    {
+                        constant = Some(&**c);
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        }
+    }
+    constant
+}
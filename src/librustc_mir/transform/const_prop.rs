@@ -3,9 +3,11 @@
 
 use std::borrow::Cow;
 use std::cell::Cell;
+use std::mem;
 
 use rustc::lint;
 use rustc::mir::interpret::{InterpResult, Scalar};
+use rustc::mir::type_intrinsics::TypeIntrinsic;
 use rustc::mir::visit::{
     MutVisitor, MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor,
 };
@@ -34,7 +36,7 @@ use crate::interpret::{
     InterpCx, LocalState, LocalValue, Memory, MemoryKind, OpTy, Operand as InterpOperand, PlaceTy,
     Pointer, ScalarMaybeUndef, StackPopCleanup,
 };
-use crate::transform::{MirPass, MirSource};
+use crate::transform::{mir_opt_level, MirPass, MirSource};
 
 /// The maximum number of bytes that we'll allocate space for a return value.
 const MAX_ALLOC_LIMIT: u64 = 1024;
@@ -117,6 +119,10 @@ impl<'tcx> MirPass<'tcx> for ConstProp {
 
         trace!("ConstProp starting for {:?}", source.def_id());
 
+        if mir_opt_level(tcx, source) > 0 {
+            fold_type_intrinsic_calls(tcx, source, body);
+        }
+
         let dummy_body = &Body::new(
             body.basic_blocks().clone(),
             body.source_scopes.clone(),
@@ -158,6 +164,8 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for ConstPropMachine {
 
     const CHECK_ALIGN: bool = false;
 
+    const WATCHPOINTS: bool = false;
+
     #[inline(always)]
     fn enforce_validity(_ecx: &InterpCx<'mir, 'tcx, Self>) -> bool {
         false
@@ -277,6 +285,53 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for ConstPropMachine {
     }
 }
 
+/// Replaces the calls to the type intrinsics instantiated with a concrete type, like
+/// `mem::size_of::<u32>()`, by the assignment of their value, which then propagates into the
+/// comparisons and branches on it. The generic calls are left to codegen.
+fn fold_type_intrinsic_calls<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    source: MirSource<'tcx>,
+    body: &mut BodyAndCache<'tcx>,
+) {
+    for (block, bb_data) in body.basic_blocks_mut().iter_enumerated_mut() {
+        let terminator = bb_data.terminator_mut();
+        let (value, dest, target) = match &terminator.kind {
+            TerminatorKind::Call {
+                func: Operand::Constant(func),
+                destination: Some((dest, target)),
+                ..
+            } => {
+                let value = match func.literal.ty.kind {
+                    ty::FnDef(def_id, substs) => TypeIntrinsic::of_fn(tcx, def_id)
+                        .and_then(|intrinsic| intrinsic.eval(tcx, substs.type_at(0))),
+                    _ => None,
+                };
+                match value {
+                    Some(value) => (value, dest.clone(), *target),
+                    None => continue,
+                }
+            }
+            _ => continue,
+        };
+        if !tcx.consider_optimizing(|| {
+            format!("ConstProp {:?} type intrinsic call in {:?}", source.def_id(), block)
+        }) {
+            return;
+        }
+        trace!("replacing the call in {:?} with {:?}", block, value);
+        let source_info = terminator.source_info;
+        terminator.kind = TerminatorKind::Goto { target };
+        let constant = Constant { span: source_info.span, user_ty: None, literal: value };
+        bb_data.statements.push(Statement {
+            source_info,
+            kind: StatementKind::Assign(Box::new((
+                dest,
+                Rvalue::Use(Operand::Constant(Box::new(constant))),
+            ))),
+        });
+    }
+}
+
 /// Finds optimization opportunities on the MIR.
 struct ConstPropagator<'mir, 'tcx> {
     ecx: InterpCx<'mir, 'tcx, ConstPropMachine>,
@@ -291,6 +346,10 @@ struct ConstPropagator<'mir, 'tcx> {
     // Because we have `MutVisitor` we can't obtain the `SourceInfo` from a `Location`. So we store
     // the last known `SourceInfo` here and just keep revisiting it.
     source_info: Option<SourceInfo>,
+    mir_opt_level: usize,
+    // The locals of the current block that are only known to be `true` from an `Assume`. They
+    // may be `false` in the blocks not dominated by it, so they are forgotten at the terminator.
+    assumed_locals: Vec<Local>,
 }
 
 impl<'mir, 'tcx> LayoutOf for ConstPropagator<'mir, 'tcx> {
@@ -369,6 +428,8 @@ impl<'mir, 'tcx> ConstPropagator<'mir, 'tcx> {
             local_decls: body.local_decls.clone(),
             ret: ret.map(Into::into),
             source_info: None,
+            mir_opt_level: mir_opt_level(tcx, source),
+            assumed_locals: Vec::new(),
         }
     }
 
@@ -720,7 +781,7 @@ impl<'mir, 'tcx> ConstPropagator<'mir, 'tcx> {
     }
 
     fn should_const_prop(&mut self, op: OpTy<'tcx>) -> bool {
-        let mir_opt_level = self.tcx.sess.opts.debugging_opts.mir_opt_level;
+        let mir_opt_level = self.mir_opt_level;
 
         if mir_opt_level == 0 {
             return false;
@@ -872,6 +933,22 @@ impl<'mir, 'tcx> MutVisitor<'tcx> for ConstPropagator<'mir, 'tcx> {
                             LocalValue::Dead
                         };
                 }
+                StatementKind::Assume(box Operand::Copy(ref place))
+                | StatementKind::Assume(box Operand::Move(ref place)) => {
+                    // The assumed condition is `true` for the rest of the block.
+                    if let Some(local) = place.as_local() {
+                        if self.can_const_prop[local] == ConstPropMode::FullConstProp
+                            && self.get_const(local).is_none()
+                        {
+                            trace!("assuming {:?} is true", local);
+                            self.use_ecx(|this| {
+                                let dest = this.ecx.eval_place(place)?;
+                                this.ecx.write_scalar(Scalar::from_bool(true), dest)
+                            });
+                            self.assumed_locals.push(local);
+                        }
+                    }
+                }
                 _ => {}
             }
         }
@@ -883,6 +960,19 @@ impl<'mir, 'tcx> MutVisitor<'tcx> for ConstPropagator<'mir, 'tcx> {
         let source_info = terminator.source_info;
         self.source_info = Some(source_info);
         self.super_terminator(terminator, location);
+        self.propagate_into_terminator(terminator, source_info);
+        for local in mem::take(&mut self.assumed_locals) {
+            self.remove_const(local);
+        }
+    }
+}
+
+impl<'mir, 'tcx> ConstPropagator<'mir, 'tcx> {
+    fn propagate_into_terminator(
+        &mut self,
+        terminator: &mut Terminator<'tcx>,
+        source_info: SourceInfo,
+    ) {
         match &mut terminator.kind {
             TerminatorKind::Assert { expected, ref msg, ref mut cond, .. } => {
                 if let Some(value) = self.eval_operand(&cond, source_info) {
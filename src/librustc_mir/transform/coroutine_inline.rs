@@ -0,0 +1,252 @@
+//! Replaces the polls of the futures awaited in `async` code by the resumption of their generator,
+//! when they are the futures of `async` blocks and functions, so that `Inline` can inline the
+//! resume function of the awaited generator into the state machine of the caller.
+//!
+//! `b().await` polls the future of `b` with `poll_with_tls_context`, which takes the task context
+//! out of the thread local storage, and calls `GenFuture::poll`, which puts it back before
+//! resuming the generator of `b`:
+//!
+//! ```rust
+//! _5 = const std::future::poll_with_tls_context::<GenFuture<[generator@b]>>(move _6) -> bb3;
+//! ```
+//!
+//! The caller is itself resumed by a `GenFuture::poll` with the same task context, which is
+//! already in the thread local storage, so this becomes:
+//!
+//! ```rust
+//! _7 = &mut ((*(_6.0: &mut GenFuture<[generator@b]>)).0: [generator@b]);
+//! _8 = std::pin::Pin::<&mut [generator@b]> { pointer: move _7 };
+//! _9 = const <[generator@b] as Generator>::resume(move _8, const ()) -> bb4;
+//! ```
+//!
+//! followed by a switch on `_9`, setting `_5` to `Poll::Pending` for `GeneratorState::Yielded`
+//! and to `Poll::Ready` with the returned value for `GeneratorState::Complete`.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::patch::MirPatch;
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::layout::VariantIdx;
+use rustc::ty::subst::InternalSubsts;
+use rustc::ty::util::IntTypeExt;
+use rustc::ty::{self, Ty, TyCtxt, TypeFoldable};
+use rustc_hir::GeneratorKind;
+use rustc_index::vec::Idx;
+use rustc_span::symbol::sym;
+use std::borrow::Cow;
+
+pub struct CoroutineInline;
+
+impl<'tcx> MirPass<'tcx> for CoroutineInline {
+    // Only useful along with `Inline`.
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        // Only `async` code is resumed with the task context in the thread local storage.
+        match body.generator_kind {
+            Some(GeneratorKind::Async(_)) if source.promoted.is_none() => {}
+            _ => return,
+        }
+        // The awaited futures are opaque types, which can only be revealed in monomorphic code.
+        let def_id = source.def_id();
+        if InternalSubsts::identity_for_item(tcx, def_id).needs_subst() {
+            return;
+        }
+        let param_env = tcx.param_env(def_id).with_reveal_all();
+
+        let mut patch = MirPatch::new(body);
+        for (bb, bb_data) in body.basic_blocks().iter_enumerated() {
+            let poll = match awaited_generator_poll(tcx, param_env, body, bb_data) {
+                Some(poll) => poll,
+                None => continue,
+            };
+            if !tcx.consider_optimizing(|| format!("CoroutineInline {:?} {:?}", def_id, bb)) {
+                break;
+            }
+            debug!("resuming {:?} directly in {:?}", poll.generator_ty, bb);
+            resume_directly(tcx, &mut patch, body, bb, poll);
+        }
+        patch.apply(body);
+    }
+}
+
+/// A call polling the future of an `async` block or function with `poll_with_tls_context`.
+struct AwaitedGeneratorPoll<'tcx> {
+    /// The `Pin<&mut GenFuture<G>>` polled.
+    pinned_future: Place<'tcx>,
+    future_ty: Ty<'tcx>,
+    /// The generator `G` of the future.
+    generator_ty: Ty<'tcx>,
+    /// The `Poll<G::Return>` returned.
+    destination: Place<'tcx>,
+    target: BasicBlock,
+    cleanup: Option<BasicBlock>,
+}
+
+fn awaited_generator_poll<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+    bb_data: &BasicBlockData<'tcx>,
+) -> Option<AwaitedGeneratorPoll<'tcx>> {
+    let (func, args, destination, target, cleanup) = match &bb_data.terminator().kind {
+        TerminatorKind::Call { func, args, destination: Some((destination, target)), cleanup, .. }
+            if args.len() == 1 =>
+        {
+            (func, args, destination, *target, *cleanup)
+        }
+        _ => return None,
+    };
+    let substs = match func.ty(body, tcx).kind {
+        ty::FnDef(def_id, substs) if tcx.is_diagnostic_item(sym::poll_with_tls_context, def_id) => {
+            substs
+        }
+        _ => return None,
+    };
+
+    let future_ty = tcx.normalize_erasing_regions(param_env, substs.type_at(0));
+    let generator_ty = match future_ty.kind {
+        ty::Adt(adt_def, future_substs)
+            if tcx.is_diagnostic_item(sym::gen_future_type, adt_def.did) =>
+        {
+            future_substs.type_at(0)
+        }
+        _ => return None,
+    };
+    match generator_ty.kind {
+        ty::Generator(..) => {}
+        _ => return None,
+    }
+
+    Some(AwaitedGeneratorPoll {
+        pinned_future: args[0].place()?.clone(),
+        future_ty,
+        generator_ty,
+        destination: destination.clone(),
+        target,
+        cleanup,
+    })
+}
+
+fn resume_directly<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    patch: &mut MirPatch<'tcx>,
+    body: &Body<'tcx>,
+    bb: BasicBlock,
+    poll: AwaitedGeneratorPoll<'tcx>,
+) {
+    let AwaitedGeneratorPoll {
+        pinned_future,
+        future_ty,
+        generator_ty,
+        destination,
+        target,
+        cleanup,
+    } = poll;
+    let source_info = body.basic_blocks()[bb].terminator().source_info;
+    let span = source_info.span;
+    let (poll_def, poll_substs) = match destination.ty(body, tcx).ty.kind {
+        ty::Adt(adt_def, substs) => (adt_def, substs),
+        _ => bug!("`poll_with_tls_context` does not return a `Poll`"),
+    };
+    // `Pending` is the variant without fields.
+    let poll_variant = |has_fields: bool| {
+        poll_def.variants.indices().find(|&v| poll_def.variants[v].fields.is_empty() != has_fields)
+    };
+    let (ready, pending) = (poll_variant(true).unwrap(), poll_variant(false).unwrap());
+    let return_ty = poll_substs.type_at(0);
+
+    // Project the `Pin<&mut GenFuture<G>>` to a `Pin<&mut G>`.
+    let re_erased = tcx.lifetimes.re_erased;
+    let future_ref_ty = tcx.mk_mut_ref(re_erased, future_ty);
+    let future_place =
+        tcx.mk_place_deref(tcx.mk_place_field(pinned_future, Field::new(0), future_ref_ty));
+    let generator_place = tcx.mk_place_field(future_place, Field::new(0), generator_ty);
+    let generator_ref_ty = tcx.mk_mut_ref(re_erased, generator_ty);
+    let generator_ref = patch.new_temp(generator_ref_ty, span);
+    let location = patch.terminator_loc(body, bb);
+    patch.add_assign(
+        location,
+        Place::from(generator_ref),
+        Rvalue::Ref(re_erased, BorrowKind::Mut { allow_two_phase_borrow: false }, generator_place),
+    );
+    let pin_def = tcx.adt_def(tcx.lang_items().pin_type().unwrap());
+    let pin_substs = tcx.intern_substs(&[generator_ref_ty.into()]);
+    let pinned_generator = patch.new_temp(tcx.mk_adt(pin_def, pin_substs), span);
+    patch.add_assign(
+        location,
+        Place::from(pinned_generator),
+        Rvalue::Aggregate(
+            box AggregateKind::Adt(pin_def, VariantIdx::new(0), pin_substs, None, None),
+            vec![Operand::Move(Place::from(generator_ref))],
+        ),
+    );
+
+    // Turn the `GeneratorState<(), G::Return>` returned by `resume` into a `Poll`.
+    let unit = tcx.mk_unit();
+    let state_def = tcx.adt_def(tcx.lang_items().gen_state().unwrap());
+    let state_substs = tcx.intern_substs(&[unit.into(), return_ty.into()]);
+    let state = Place::from(patch.new_temp(tcx.mk_adt(state_def, state_substs), span));
+    let mut assign_poll = |variant, operands| {
+        let kind = AggregateKind::Adt(poll_def, variant, poll_substs, None, None);
+        let rvalue = Rvalue::Aggregate(box kind, operands);
+        patch.new_block(BasicBlockData {
+            statements: vec![Statement {
+                source_info,
+                kind: StatementKind::Assign(box (destination.clone(), rvalue)),
+            }],
+            terminator: Some(Terminator { source_info, kind: TerminatorKind::Goto { target } }),
+            is_cleanup: false,
+        })
+    };
+    let yielded = assign_poll(pending, vec![]);
+    let complete_place = tcx.mk_place_downcast(state.clone(), state_def, VariantIdx::new(1));
+    let returned = tcx.mk_place_field(complete_place, Field::new(0), return_ty);
+    let complete = assign_poll(ready, vec![Operand::Move(returned)]);
+    let discr_ty = state_def.repr.discr_type().to_ty(tcx);
+    let discr = Place::from(patch.new_temp(discr_ty, span));
+    let dispatch = patch.new_block(BasicBlockData {
+        statements: vec![Statement {
+            source_info,
+            kind: StatementKind::Assign(box (discr.clone(), Rvalue::Discriminant(state.clone()))),
+        }],
+        terminator: Some(Terminator {
+            source_info,
+            kind: TerminatorKind::SwitchInt {
+                discr: Operand::Move(discr),
+                switch_ty: discr_ty,
+                // `Yielded` is the first variant.
+                values: Cow::from(vec![0]),
+                targets: vec![yielded, complete],
+                target_weights: None,
+            },
+        }),
+        is_cleanup: false,
+    });
+
+    // Resume the generator.
+    let gen_trait = tcx.lang_items().gen_trait().unwrap();
+    let resume = tcx
+        .associated_items(gen_trait)
+        .in_definition_order()
+        .find(|item| item.kind == ty::AssocKind::Method)
+        .unwrap()
+        .def_id;
+    let resume_substs = tcx.mk_substs_trait(generator_ty, &[unit.into()]);
+    let resume_arg = Constant { span, user_ty: None, literal: ty::Const::zero_sized(tcx, unit) };
+    patch.patch_terminator(
+        bb,
+        TerminatorKind::Call {
+            func: Operand::function_handle(tcx, resume, resume_substs, span),
+            args: vec![
+                Operand::Move(Place::from(pinned_generator)),
+                Operand::Constant(box resume_arg),
+            ],
+            destination: Some((state, dispatch)),
+            cleanup,
+            from_hir_call: false,
+        },
+    );
+}
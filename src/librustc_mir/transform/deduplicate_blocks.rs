@@ -0,0 +1,179 @@
+//! Merges blocks doing the same thing, so that their predecessors share a single copy.
+//!
+//! The lowering of `if let` and `match` lowers the remaining arms separately after every test
+//! that can fail, so patterns testing several things, like `Some(1)`, get a copy of the `else`
+//! block for each of these tests:
+//!
+//! ```rust
+//! bb0: {
+//!     _2 = discriminant(_1);
+//!     switchInt(move _2) -> [1isize: bb2, otherwise: bb1];
+//! }
+//! bb1: {
+//!     _0 = const 0u8;
+//!     goto -> bb5;
+//! }
+//! bb2: {
+//!     switchInt(((_1 as Some).0: u8)) -> [1u8: bb3, otherwise: bb4];
+//! }
+//! bb4: {
+//!     _0 = const 0u8;
+//!     goto -> bb5;
+//! }
+//! ```
+//!
+//! Here, `bb4` is replaced by `bb1`. Blocks are only merged if their statements and terminators
+//! have the same source info as well, so that debuginfo is unaffected, except in functions
+//! optimized for size. Blocks are looked up by a fingerprint of what they do, so this stays
+//! linear in the size of the body.
+//!
+//! The copies often start and end the storage of the locals of the `else` block without using
+//! them, e.g. for `let x;`, so these storage statements are removed first.
+
+use crate::transform::{optimize_for_size, simplify, MirPass, MirSource};
+use rustc::ich::StableHashingContext;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
+use rustc_index::vec::IndexVec;
+use std::hash::Hash;
+
+pub struct DeduplicateBlocks;
+
+impl<'tcx> MirPass<'tcx> for DeduplicateBlocks {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        for bb_data in body.basic_blocks_mut() {
+            remove_unused_storage(bb_data);
+        }
+
+        let compare_source_info = !optimize_for_size(tcx, source);
+        // Merging blocks can make their predecessors equal as well.
+        loop {
+            let replacements = find_duplicates(tcx, body, compare_source_info);
+            if replacements.iter_enumerated().all(|(bb, &replacement)| bb == replacement) {
+                break;
+            }
+            debug!("deduplicating blocks in {:?}: {:?}", source.def_id(), replacements);
+            for bb_data in body.basic_blocks_mut() {
+                for target in bb_data.terminator_mut().successors_mut() {
+                    *target = replacements[*target];
+                }
+            }
+            // Removes the duplicates.
+            simplify::remove_dead_blocks(body);
+        }
+    }
+}
+
+/// Removes the `StorageLive(x)` followed by a `StorageDead(x)` in the same block with no use of
+/// `x` in between.
+fn remove_unused_storage(bb_data: &mut BasicBlockData<'_>) {
+    // The index of the `StorageLive` of each local not used since.
+    let mut unused_since: FxHashMap<Local, usize> = FxHashMap::default();
+    let mut unused = vec![];
+    for (index, statement) in bb_data.statements.iter().enumerate() {
+        match statement.kind {
+            StatementKind::StorageLive(local) => {
+                unused_since.insert(local, index);
+            }
+            StatementKind::StorageDead(local) => {
+                if let Some(live) = unused_since.remove(&local) {
+                    unused.push(live);
+                    unused.push(index);
+                }
+            }
+            _ => StorageUses { unused_since: &mut unused_since }
+                .visit_statement(statement, Location::START),
+        }
+    }
+    if unused.is_empty() {
+        return;
+    }
+    for index in unused {
+        bb_data.statements[index].make_nop();
+    }
+    bb_data.statements.retain(|statement| statement.kind != StatementKind::Nop);
+}
+
+struct StorageUses<'a> {
+    unused_since: &'a mut FxHashMap<Local, usize>,
+}
+
+impl Visitor<'_> for StorageUses<'_> {
+    fn visit_local(&mut self, local: &Local, context: PlaceContext, _: Location) {
+        if !context.is_storage_marker() {
+            self.unused_since.remove(local);
+        }
+    }
+}
+
+/// Maps every block to the first block doing the same thing. The start block is left alone.
+fn find_duplicates<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    compare_source_info: bool,
+) -> IndexVec<BasicBlock, BasicBlock> {
+    let basic_blocks = body.basic_blocks();
+    let mut replacements: IndexVec<BasicBlock, BasicBlock> = basic_blocks.indices().collect();
+
+    let mut hcx = tcx.create_stable_hashing_context();
+    let mut first_with_fingerprint: FxHashMap<Fingerprint, BasicBlock> = FxHashMap::default();
+    for (bb, bb_data) in basic_blocks.iter_enumerated() {
+        if bb == START_BLOCK {
+            continue;
+        }
+        let fingerprint = block_fingerprint(&mut hcx, bb_data, compare_source_info);
+        let other = *first_with_fingerprint.entry(fingerprint).or_insert(bb);
+        // Blocks are still compared, in case two of them have the same fingerprint by chance.
+        if other != bb && same_block(&basic_blocks[other], bb_data, compare_source_info) {
+            replacements[bb] = other;
+        }
+    }
+    replacements
+}
+
+/// A fingerprint of what `bb_data` does, equal for the blocks `same_block` considers the same.
+fn block_fingerprint(
+    hcx: &mut StableHashingContext<'_>,
+    bb_data: &BasicBlockData<'_>,
+    compare_source_info: bool,
+) -> Fingerprint {
+    let mut hasher = StableHasher::new();
+    bb_data.is_cleanup.hash(&mut hasher);
+    bb_data.statements.len().hash(&mut hasher);
+    for statement in &bb_data.statements {
+        statement.kind.hash_stable(hcx, &mut hasher);
+        if compare_source_info {
+            statement.source_info.hash(&mut hasher);
+        }
+    }
+    let terminator = bb_data.terminator();
+    terminator.kind.hash_stable(hcx, &mut hasher);
+    if compare_source_info {
+        terminator.source_info.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn same_block(
+    bb_l: &BasicBlockData<'_>,
+    bb_r: &BasicBlockData<'_>,
+    compare_source_info: bool,
+) -> bool {
+    let (term_l, term_r) = (bb_l.terminator(), bb_r.terminator());
+    bb_l.is_cleanup == bb_r.is_cleanup
+        && bb_l.statements.len() == bb_r.statements.len()
+        && term_l.kind == term_r.kind
+        && (!compare_source_info || term_l.source_info == term_r.source_info)
+        && bb_l.statements.iter().zip(&bb_r.statements).all(|(l, r)| {
+            l.kind == r.kind && (!compare_source_info || l.source_info == r.source_info)
+        })
+}
@@ -0,0 +1,235 @@
+//! Devirtualizes calls through trait objects whose concrete type is known within the body.
+//!
+//! We look for virtual calls of the form:
+//!
+//! ```rust
+//! _2 = move _3 as &dyn Trait (Pointer(Unsize));
+//! ...
+//! _4 = &(*_2);
+//! _0 = const <dyn Trait as Trait>::method(move _4) -> bb1;
+//! ```
+//!
+//! where every local on the way from the unsizing cast to the receiver is assigned exactly
+//! once. In that case the type erased by the cast is the concrete type of the receiver, so
+//! we can resolve the method statically and call it on the original thin pointer:
+//!
+//! ```rust
+//! _5 = _3;
+//! _2 = move _3 as &dyn Trait (Pointer(Unsize));
+//! ...
+//! _0 = const <Concrete as Trait>::method(move _5) -> bb1;
+//! ```
+//!
+//! This removes the vtable load and, more importantly, makes the callee visible to the
+//! inliner.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::def_use::DefUseAnalysis;
+use crate::util::patch::MirPatch;
+use rustc::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::adjustment::PointerCast;
+use rustc::ty::subst::{InternalSubsts, Subst};
+use rustc::ty::{self, Instance, InstanceDef, ParamEnv, Ty, TyCtxt, TypeFoldable};
+use rustc_data_structures::graph::dominators::Dominators;
+use std::iter;
+
+pub struct Devirtualize;
+
+impl<'tcx> MirPass<'tcx> for Devirtualize {
+    // Like inlining, which this pass mostly exists to feed, only run at higher opt levels.
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let mut param_env = tcx.param_env(source.def_id());
+        let substs = &InternalSubsts::identity_for_item(tcx, source.def_id());
+
+        // For monomorphic functions, we can use `Reveal::All` to resolve specialized instances.
+        if !substs.needs_subst() {
+            param_env = param_env.with_reveal_all();
+        }
+
+        let mut def_use_analysis = DefUseAnalysis::new(body);
+        def_use_analysis.analyze(read_only!(body));
+        let dominators = read_only!(body).dominators();
+
+        let mut patch = MirPatch::new(body);
+        let mut changed = false;
+        let finder = CallFinder { tcx, param_env, body, def_use_analysis: &def_use_analysis };
+
+        for (bb, bb_data) in body.basic_blocks().iter_enumerated() {
+            let call_location = body.terminator_loc(bb);
+            let devirtualized = match finder.devirtualize_call(
+                bb_data.terminator(),
+                call_location,
+                &dominators,
+            ) {
+                Some(devirtualized) => devirtualized,
+                None => continue,
+            };
+
+            if !tcx.consider_optimizing(|| {
+                format!("Devirtualize {:?} into {:?}", call_location, devirtualized.instance)
+            }) {
+                continue;
+            }
+
+            debug!("devirtualizing call at {:?} to {:?}", call_location, devirtualized.instance);
+
+            let span = bb_data.terminator().source_info.span;
+            let thin = patch.new_temp(devirtualized.thin_ty, span);
+            patch.add_assign(
+                devirtualized.cast_location,
+                Place::from(thin),
+                Rvalue::Use(devirtualized.thin_operand.to_copy()),
+            );
+
+            let mut kind = bb_data.terminator().kind.clone();
+            if let TerminatorKind::Call { func, args, .. } = &mut kind {
+                *func = Operand::function_handle(
+                    tcx,
+                    devirtualized.instance.def_id(),
+                    devirtualized.instance.substs,
+                    span,
+                );
+                args[0] = Operand::Move(Place::from(thin));
+            }
+            patch.patch_terminator(bb, kind);
+            changed = true;
+        }
+
+        if changed {
+            patch.apply(body);
+        }
+    }
+}
+
+struct CallFinder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    def_use_analysis: &'a DefUseAnalysis,
+}
+
+/// A virtual call that can be replaced with a direct call to `instance`.
+struct Devirtualized<'tcx> {
+    instance: Instance<'tcx>,
+    /// Where the unsizing cast producing the trait object happens.
+    cast_location: Location,
+    /// The thin pointer operand of the unsizing cast.
+    thin_operand: Operand<'tcx>,
+    thin_ty: Ty<'tcx>,
+}
+
+impl CallFinder<'a, 'tcx> {
+    fn devirtualize_call(
+        &self,
+        terminator: &Terminator<'tcx>,
+        call_location: Location,
+        dominators: &Dominators<BasicBlock>,
+    ) -> Option<Devirtualized<'tcx>> {
+        let (func, args) = match &terminator.kind {
+            TerminatorKind::Call { func, args, .. } => (func, args),
+            _ => return None,
+        };
+
+        let (callee, substs) = match func.ty(self.body, self.tcx).kind {
+            ty::FnDef(callee, substs) => (callee, substs),
+            _ => return None,
+        };
+        match Instance::resolve(self.tcx, self.param_env, callee, substs)?.def {
+            InstanceDef::Virtual(..) => {}
+            _ => return None,
+        }
+
+        // The receiver is always the first argument of a virtual call.
+        let receiver = args.first()?.place()?.as_local()?;
+        let (cast_location, thin_operand) = self.find_unsize_cast(receiver)?;
+
+        if !cast_location.dominates(call_location, dominators) {
+            return None;
+        }
+
+        let thin_ty = thin_operand.ty(self.body, self.tcx);
+        let concrete_ty = match thin_ty.kind {
+            ty::Ref(_, pointee, _) => pointee,
+            _ => return None,
+        };
+        if let ty::Dynamic(..) = concrete_ty.kind {
+            return None;
+        }
+
+        let dyn_ty = substs.type_at(0);
+        debug!("devirtualize_call: {:?} on {:?} erased from {:?}", callee, dyn_ty, concrete_ty);
+
+        let concrete_substs = self
+            .tcx
+            .mk_substs(iter::once(concrete_ty.into()).chain(substs.iter().skip(1).cloned()));
+        let instance = Instance::resolve(self.tcx, self.param_env, callee, concrete_substs)?;
+        match instance.def {
+            InstanceDef::Item(def_id) => {
+                let attrs = self.tcx.codegen_fn_attrs(def_id);
+                // Going through the vtable gives `#[track_caller]` functions a shim, keep it.
+                if attrs.flags.contains(CodegenFnAttrFlags::TRACK_CALLER) {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+
+        // Make sure the resolved method takes the thin pointer we are going to pass it.
+        let sig = self.tcx.fn_sig(instance.def_id()).subst(self.tcx, instance.substs);
+        let sig = self.tcx.erase_late_bound_regions(&sig);
+        let expected_ty = self.tcx.erase_regions(sig.inputs().first()?);
+        if expected_ty != self.tcx.erase_regions(&thin_ty) {
+            debug!("devirtualize_call: receiver type mismatch {:?} != {:?}", expected_ty, thin_ty);
+            return None;
+        }
+
+        Some(Devirtualized { instance, cast_location, thin_operand: thin_operand.clone(), thin_ty })
+    }
+
+    /// Follows the single definitions of `local` through copies and reborrows until reaching
+    /// the unsizing cast that created the trait object.
+    fn find_unsize_cast(&self, mut local: Local) -> Option<(Location, &'a Operand<'tcx>)> {
+        loop {
+            if self.body.local_kind(local) != LocalKind::Temp
+                && self.body.local_kind(local) != LocalKind::Var
+            {
+                return None;
+            }
+
+            let info = self.def_use_analysis.local_info(local);
+            if info.def_count() != 1 {
+                return None;
+            }
+            let location = info.defs_not_including_drop().next()?.location;
+            let statement = self.body[location.block].statements.get(location.statement_index)?;
+
+            let rvalue = match &statement.kind {
+                StatementKind::Assign(box (place, rvalue)) if place.as_local() == Some(local) => {
+                    rvalue
+                }
+                _ => return None,
+            };
+
+            local = match rvalue {
+                Rvalue::Cast(CastKind::Pointer(PointerCast::Unsize), operand, _) => {
+                    return Some((location, operand));
+                }
+                Rvalue::Use(Operand::Copy(place)) | Rvalue::Use(Operand::Move(place)) => {
+                    place.as_local()?
+                }
+                // A reborrow points to the same data, and keeps the same concrete type.
+                Rvalue::Ref(_, _, place) => match place.as_ref() {
+                    PlaceRef { local, projection: &[ProjectionElem::Deref] } => local,
+                    _ => return None,
+                },
+                _ => return None,
+            };
+        }
+    }
+}
@@ -7,9 +7,14 @@ use std::io;
 
 use crate::transform::{MirPass, MirSource};
 use crate::util as mir_util;
+use rustc::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc::mir::mono::{Linkage, MonoItem, Visibility};
 use rustc::mir::{Body, BodyAndCache};
 use rustc::session::config::{OutputFilenames, OutputType};
-use rustc::ty::TyCtxt;
+use rustc::ty::{self, Instance, InstanceDef, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_target::spec::abi::Abi;
 
 pub struct Marker(pub &'static str);
 
@@ -59,9 +64,115 @@ pub fn on_mir_pass<'tcx>(
     }
 }
 
+/// Writes the final MIR of the items codegen instantiates for `--emit mir`: each function instance
+/// with its generic parameters substituted, and each static. The file starts with an index of the
+/// items, by symbol name, which tells the ones exported with `#[no_mangle]` or `#[export_name]`
+/// and the ones with a foreign ABI, so that the tools analyzing the whole program can find its
+/// entry points.
 pub fn emit_mir(tcx: TyCtxt<'_>, outputs: &OutputFilenames) -> io::Result<()> {
     let path = outputs.path(OutputType::Mir);
     let mut f = io::BufWriter::new(File::create(&path)?);
-    mir_util::write_mir_pretty(tcx, None, &mut f)?;
+    write_mono_items_mir(tcx, &mut f)
+}
+
+// The items are sorted by symbol name before being written.
+#[allow(rustc::unordered_iteration)]
+fn write_mono_items_mir(tcx: TyCtxt<'tcx>, w: &mut dyn io::Write) -> io::Result<()> {
+    // The same item may be in several codegen units, as a local copy of an inline function.
+    let mut items: FxHashMap<MonoItem<'tcx>, (Linkage, Visibility)> = Default::default();
+    for cgu in tcx.collect_and_partition_mono_items(LOCAL_CRATE).1.iter() {
+        for (&item, &linkage) in cgu.items() {
+            match item {
+                MonoItem::Fn(Instance { def: InstanceDef::Intrinsic(..), .. })
+                | MonoItem::Fn(Instance { def: InstanceDef::Virtual(..), .. })
+                | MonoItem::GlobalAsm(..) => {}
+                _ => {
+                    items.insert(item, linkage);
+                }
+            }
+        }
+    }
+    let mut items: Vec<_> = items
+        .into_iter()
+        .map(|(item, linkage)| (item.symbol_name(tcx).name.as_str().to_string(), item, linkage))
+        .collect();
+    items.sort_by(|a, b| a.0.cmp(&b.0));
+
+    writeln!(w, "// WARNING: This output format is intended for human consumers only")?;
+    writeln!(w, "// and is subject to change without notice. Knock yourself out.")?;
+    writeln!(w, "//")?;
+    writeln!(w, "// Items:")?;
+    for (index, (symbol, item, (linkage, visibility))) in items.iter().enumerate() {
+        let mut notes = String::new();
+        let def_id = match *item {
+            MonoItem::Fn(instance) => Some(instance.def_id()),
+            MonoItem::Static(def_id) => Some(def_id),
+            MonoItem::GlobalAsm(..) => None,
+        };
+        if let Some(def_id) = def_id {
+            let attrs = tcx.codegen_fn_attrs(def_id);
+            if attrs.flags.contains(CodegenFnAttrFlags::NO_MANGLE) {
+                notes.push_str(" #[no_mangle]");
+            }
+            if attrs.export_name.is_some() {
+                notes.push_str(" #[export_name]");
+            }
+        }
+        if let MonoItem::Fn(instance) = item {
+            // Closures have no signature of their own.
+            let ty = instance.monomorphic_ty(tcx);
+            if let ty::FnDef(..) = ty.kind {
+                let abi = ty.fn_sig(tcx).abi();
+                if abi != Abi::Rust && abi != Abi::RustCall {
+                    notes.push_str(&format!(" extern {:?}", abi));
+                }
+            }
+        }
+        writeln!(
+            w,
+            "// {:4}: {} ({:?}, {:?}){} = {}",
+            index,
+            symbol,
+            linkage,
+            visibility,
+            notes,
+            item.to_string(tcx, false),
+        )?;
+    }
+
+    for (index, (symbol, item, _)) in items.iter().enumerate() {
+        writeln!(w)?;
+        writeln!(w, "// {:4}: {}", index, symbol)?;
+        match *item {
+            MonoItem::Fn(instance) => {
+                let body = tcx.instance_mir(instance.def);
+                let body = tcx.subst_and_normalize_erasing_regions(
+                    instance.substs,
+                    ty::ParamEnv::reveal_all(),
+                    *body,
+                );
+                let source = MirSource { instance: instance.def, promoted: None };
+                mir_util::pretty::write_mir_fn(tcx, source, &body, &mut |_, _| Ok(()), w)?;
+                if let InstanceDef::Item(def_id) = instance.def {
+                    for (promoted, body) in tcx.promoted_mir(def_id).iter_enumerated() {
+                        let body = tcx.subst_and_normalize_erasing_regions(
+                            instance.substs,
+                            ty::ParamEnv::reveal_all(),
+                            &**body,
+                        );
+                        writeln!(w)?;
+                        let source = MirSource { instance: instance.def, promoted: Some(promoted) };
+                        mir_util::pretty::write_mir_fn(tcx, source, &body, &mut |_, _| Ok(()), w)?;
+                    }
+                }
+            }
+            MonoItem::Static(def_id) => {
+                let source = MirSource::item(def_id);
+                let body = tcx.optimized_mir(def_id);
+                mir_util::pretty::write_mir_fn(tcx, source, body, &mut |_, _| Ok(()), w)?;
+            }
+            MonoItem::GlobalAsm(..) => {}
+        }
+    }
     Ok(())
 }
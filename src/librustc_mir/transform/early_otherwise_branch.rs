@@ -0,0 +1,207 @@
+//! Checks up front whether two nested switches on the discriminants of independent places can
+//! both take their value target, rather than testing each place in turn. Matching a pair of
+//! `Option`s, like `match (a, b) { (Some(x), Some(y)) => .., _ => .. }`, gives
+//!
+//! ```rust
+//! bb0: {
+//!     _3 = discriminant((_1.0: std::option::Option<u32>));
+//!     switchInt(move _3) -> [1isize: bb2, otherwise: bb1];
+//! }
+//! bb2: {
+//!     _4 = discriminant((_1.1: std::option::Option<u32>));
+//!     switchInt(move _4) -> [1isize: bb3, otherwise: bb1];
+//! }
+//! ```
+//!
+//! which becomes
+//!
+//! ```rust
+//! bb0: {
+//!     _3 = discriminant((_1.0: std::option::Option<u32>));
+//!     _4 = discriminant((_1.1: std::option::Option<u32>));
+//!     _5 = Ne(_3, _4);
+//!     switchInt(move _5) -> [false: bb4, otherwise: bb1];
+//! }
+//! bb4: {
+//!     switchInt(_3) -> [1isize: bb3, otherwise: bb1];
+//! }
+//! ```
+//!
+//! The blocks of the inner switches are left unreachable, and removed by `SimplifyCfg`. Each value
+//! target of the outer switch must switch on the same inner place, for the same value, and share
+//! the `otherwise` target of the outer switch.
+//!
+//! The inner discriminant is read even when the outer switch takes its `otherwise` target, so the
+//! read must be hoistable by `SideEffects::can_hoist`: the inner place may only project fields,
+//! not dereference a pointer or a variant that may not be the active one, and no field of a union,
+//! which may not be the one last written.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::patch::MirPatch;
+use crate::util::side_effects::place_side_effects;
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::{self, Ty, TyCtxt};
+use std::borrow::Cow;
+
+pub struct EarlyOtherwiseBranch;
+
+impl<'tcx> MirPass<'tcx> for EarlyOtherwiseBranch {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        body.ensure_predecessors();
+        let opportunities = {
+            let body = body.unwrap_read_only();
+            body.basic_blocks()
+                .indices()
+                .filter_map(|bb| find_opportunity(tcx, body, bb))
+                .collect::<Vec<_>>()
+        };
+        if opportunities.is_empty() {
+            return;
+        }
+
+        let mut patch = MirPatch::new(body);
+        for opp in opportunities {
+            if !tcx.consider_optimizing(|| {
+                format!("EarlyOtherwiseBranch {:?} {:?}", source.def_id(), opp.parent)
+            }) {
+                break;
+            }
+            mir_trace!("checking the discriminants of {:?} up front", opp.parent);
+            let source_info = body[opp.parent].terminator().source_info;
+            let span = source_info.span;
+
+            let switch_on_parent = patch.new_block(BasicBlockData::new(Some(Terminator {
+                source_info,
+                kind: TerminatorKind::SwitchInt {
+                    discr: Operand::Copy(opp.parent_discr.clone()),
+                    switch_ty: opp.discr_ty,
+                    values: Cow::from(opp.values),
+                    targets: opp.targets,
+                    target_weights: None,
+                },
+            })));
+
+            let location = body.terminator_loc(opp.parent);
+            let child_discr = Place::from(opp.child_discr);
+            patch.add_assign(location, child_discr.clone(), Rvalue::Discriminant(opp.child_place));
+            let not_equal = Place::from(patch.new_temp(tcx.types.bool, span));
+            patch.add_assign(
+                location,
+                not_equal.clone(),
+                Rvalue::BinaryOp(
+                    BinOp::Ne,
+                    Operand::Copy(opp.parent_discr),
+                    Operand::Copy(child_discr),
+                ),
+            );
+            patch.patch_terminator(
+                opp.parent,
+                TerminatorKind::SwitchInt {
+                    discr: Operand::Move(not_equal),
+                    switch_ty: tcx.types.bool,
+                    values: Cow::from(vec![0]),
+                    targets: vec![switch_on_parent, opp.otherwise],
+                    target_weights: None,
+                },
+            );
+        }
+        patch.apply(body);
+    }
+}
+
+/// An outer switch on `parent_discr`, whose value targets switch on the discriminant of
+/// `child_place`.
+struct Opportunity<'tcx> {
+    parent: BasicBlock,
+    parent_discr: Place<'tcx>,
+    discr_ty: Ty<'tcx>,
+    child_discr: Local,
+    child_place: Place<'tcx>,
+    /// The values of the outer switch, and the targets of the inner switches for them, followed
+    /// by the common `otherwise` target.
+    values: Vec<u128>,
+    targets: Vec<BasicBlock>,
+    otherwise: BasicBlock,
+}
+
+fn find_opportunity<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: ReadOnlyBodyAndCache<'_, 'tcx>,
+    parent: BasicBlock,
+) -> Option<Opportunity<'tcx>> {
+    let (parent_discr, discr_ty, values, parent_targets) = match &body[parent].terminator().kind {
+        TerminatorKind::SwitchInt { discr, switch_ty, values, targets, .. } => {
+            (discr.place()?, *switch_ty, values, targets)
+        }
+        _ => return None,
+    };
+    let parent_discr_local = parent_discr.as_local()?;
+    let (&otherwise, parent_targets) = parent_targets.split_last()?;
+
+    let mut child: Option<(Local, &Place<'tcx>)> = None;
+    let mut targets = Vec::with_capacity(values.len() + 1);
+    for (&value, &child_bb) in values.iter().zip(parent_targets) {
+        if body.predecessors_for(child_bb) != [parent] {
+            return None;
+        }
+        let child_data = &body[child_bb];
+        let (child_discr, child_place) = match &child_data.statements[..] {
+            [Statement {
+                kind: StatementKind::Assign(box (dest, Rvalue::Discriminant(place))), ..
+            }] => (dest.as_local()?, place),
+            _ => return None,
+        };
+        // The inner discriminant is assigned before the outer one is compared to it.
+        if child_discr == parent_discr_local {
+            return None;
+        }
+        match child {
+            Some(first) if first != (child_discr, child_place) => return None,
+            Some(_) => {}
+            None => child = Some((child_discr, child_place)),
+        }
+        match &child_data.terminator().kind {
+            TerminatorKind::SwitchInt { discr, switch_ty, values: child_values, targets: t, .. }
+                if discr.place().and_then(|place| place.as_local()) == Some(child_discr)
+                    && *switch_ty == discr_ty
+                    && child_values[..] == [value]
+                    && t[1] == otherwise =>
+            {
+                targets.push(t[0]);
+            }
+            _ => return None,
+        }
+    }
+    let (child_discr, child_place) = child?;
+    // The classification of the side effects doesn't know about unions.
+    let reads_union_field = child_place.projection.iter().enumerate().any(|(i, elem)| match elem {
+        ProjectionElem::Field(..) => {
+            let base = &child_place.projection[..i];
+            match Place::ty_from(child_place.local, base, &**body, tcx).ty.kind {
+                ty::Adt(adt_def, _) => adt_def.is_union(),
+                _ => false,
+            }
+        }
+        _ => false,
+    });
+    if !place_side_effects(child_place).can_hoist() || reads_union_field {
+        return None;
+    }
+    targets.push(otherwise);
+
+    Some(Opportunity {
+        parent,
+        parent_discr: parent_discr.clone(),
+        discr_ty,
+        child_discr,
+        child_place: child_place.clone(),
+        values: values.to_vec(),
+        targets,
+        otherwise,
+    })
+}
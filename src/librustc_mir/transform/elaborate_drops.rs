@@ -1,6 +1,6 @@
 use crate::dataflow;
 use crate::dataflow::generic::{Analysis, ResultsCursor};
-use crate::dataflow::move_paths::{LookupResult, MoveData, MovePathIndex};
+use crate::dataflow::move_paths::{dump_move_paths, LookupResult, MoveData, MovePathIndex};
 use crate::dataflow::on_lookup_result_bits;
 use crate::dataflow::MoveDataParamEnv;
 use crate::dataflow::{on_all_children_bits, on_all_drop_children_bits};
@@ -12,9 +12,9 @@ use crate::util::patch::MirPatch;
 use rustc::mir::*;
 use rustc::ty::layout::VariantIdx;
 use rustc::ty::{self, TyCtxt};
-use rustc_data_structures::fx::FxHashMap;
 use rustc_hir as hir;
 use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
 use rustc_span::Span;
 use std::fmt;
 
@@ -39,6 +39,7 @@ impl<'tcx> MirPass<'tcx> for ElaborateDrops {
         let elaborate_patch = {
             let body = &*body;
             let env = MoveDataParamEnv { move_data, param_env };
+            dump_move_paths(tcx, "ElaborateDrops", src, body, &env);
             let dead_unwinds = find_dead_unwinds(tcx, body, def_id, &env);
 
             let inits = MaybeInitializedPlaces::new(tcx, body, &env)
@@ -58,7 +59,7 @@ impl<'tcx> MirPass<'tcx> for ElaborateDrops {
                 body,
                 env: &env,
                 init_data: InitializationData { inits, uninits },
-                drop_flags: Default::default(),
+                drop_flags: IndexVec::from_elem(None, &env.move_data.move_paths),
                 patch: MirPatch::new(body),
             }
             .elaborate()
@@ -252,7 +253,7 @@ struct ElaborateDropsCtxt<'a, 'tcx> {
     body: &'a Body<'tcx>,
     env: &'a MoveDataParamEnv<'tcx>,
     init_data: InitializationData<'a, 'tcx>,
-    drop_flags: FxHashMap<MovePathIndex, Local>,
+    drop_flags: IndexVec<MovePathIndex, Option<Local>>,
     patch: MirPatch<'tcx>,
 }
 
@@ -269,11 +270,11 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
         let tcx = self.tcx;
         let patch = &mut self.patch;
         debug!("create_drop_flag({:?})", self.body.span);
-        self.drop_flags.entry(index).or_insert_with(|| patch.new_internal(tcx.types.bool, span));
+        self.drop_flags[index].get_or_insert_with(|| patch.new_internal(tcx.types.bool, span));
     }
 
     fn drop_flag(&mut self, index: MovePathIndex) -> Option<Place<'tcx>> {
-        self.drop_flags.get(&index).map(|t| Place::from(*t))
+        self.drop_flags[index].map(Place::from)
     }
 
     /// create a patch that elaborates all drops in the input
@@ -474,7 +475,7 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
     }
 
     fn set_drop_flag(&mut self, loc: Location, path: MovePathIndex, val: DropFlagState) {
-        if let Some(&flag) = self.drop_flags.get(&path) {
+        if let Some(flag) = self.drop_flags[path] {
             let span = self.patch.source_info_for_location(self.body, loc).span;
             let val = self.constant_bool(span, val.value());
             self.patch.add_assign(loc, Place::from(flag), val);
@@ -485,30 +486,69 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
         let loc = Location::START;
         let span = self.patch.source_info_for_location(self.body, loc).span;
         let false_ = self.constant_bool(span, false);
-        for flag in self.drop_flags.values() {
+        for flag in self.drop_flags.iter().flatten() {
             self.patch.add_assign(loc, Place::from(*flag), false_.clone());
         }
     }
 
     fn drop_flags_for_fn_rets(&mut self) {
         for (bb, data) in self.body.basic_blocks().iter_enumerated() {
-            if let TerminatorKind::Call {
-                destination: Some((ref place, tgt)),
-                cleanup: Some(_),
-                ..
-            } = data.terminator().kind
-            {
-                assert!(!self.patch.is_patched(bb));
+            let (place, tgt) = match data.terminator().kind {
+                TerminatorKind::Call {
+                    destination: Some((ref place, tgt)), cleanup: Some(_), ..
+                } => (place, tgt),
+                TerminatorKind::Yield { ref resume_arg, resume, drop: Some(_), .. } => {
+                    (resume_arg, resume)
+                }
+                _ => continue,
+            };
+            assert!(!self.patch.is_patched(bb));
+            // The resume argument is only written when resuming, not when the generator is
+            // dropped.
+            let tgt = match data.terminator().kind {
+                TerminatorKind::Yield { .. } => self.resume_edge(bb, tgt),
+                _ => tgt,
+            };
 
-                let loc = Location { block: tgt, statement_index: 0 };
-                let path = self.move_data().rev_lookup.find(place.as_ref());
-                on_lookup_result_bits(self.tcx, self.body, self.move_data(), path, |child| {
-                    self.set_drop_flag(loc, child, DropFlagState::Present)
-                });
-            }
+            let loc = Location { block: tgt, statement_index: 0 };
+            let path = self.move_data().rev_lookup.find(place.as_ref());
+            on_lookup_result_bits(self.tcx, self.body, self.move_data(), path, |child| {
+                self.set_drop_flag(loc, child, DropFlagState::Present)
+            });
         }
     }
 
+    /// Returns a block only reached from the `Yield` terminator of `bb` through its `resume`
+    /// edge, splitting the edge if `resume` has other predecessors.
+    fn resume_edge(&mut self, bb: BasicBlock, resume: BasicBlock) -> BasicBlock {
+        let predecessors = self
+            .body
+            .basic_blocks()
+            .iter()
+            .flat_map(|data| data.terminator().successors())
+            .filter(|&&target| target == resume)
+            .count();
+        if predecessors == 1 {
+            return resume;
+        }
+
+        let terminator = self.body[bb].terminator();
+        let edge = self.patch.new_block(BasicBlockData {
+            statements: vec![],
+            terminator: Some(Terminator {
+                source_info: terminator.source_info,
+                kind: TerminatorKind::Goto { target: resume },
+            }),
+            is_cleanup: false,
+        });
+        let mut kind = terminator.kind.clone();
+        if let TerminatorKind::Yield { ref mut resume, .. } = kind {
+            *resume = edge;
+        }
+        self.patch.patch_terminator(bb, kind);
+        edge
+    }
+
     fn drop_flags_for_args(&mut self) {
         let loc = Location::START;
         dataflow::drop_flag_effects_for_function_entry(self.tcx, self.body, self.env, |path, ds| {
@@ -569,11 +609,13 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
 
             // There may be a critical edge after this call,
             // so mark the return as initialized *before* the
-            // call.
-            if let TerminatorKind::Call {
-                destination: Some((ref place, _)), cleanup: None, ..
-            } = data.terminator().kind
-            {
+            // call. The same goes for a yield without drop edge.
+            let place = match data.terminator().kind {
+                TerminatorKind::Call { destination: Some((ref place, _)), cleanup: None, .. }
+                | TerminatorKind::Yield { resume_arg: ref place, drop: None, .. } => Some(place),
+                _ => None,
+            };
+            if let Some(place) = place {
                 assert!(!self.patch.is_patched(bb));
 
                 let loc = Location { block: bb, statement_index: data.statements.len() };
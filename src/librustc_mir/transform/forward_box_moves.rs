@@ -0,0 +1,193 @@
+//! Moves the fields of the contents of a box directly out of the box, rather than out of a copy
+//! of the whole contents:
+//!
+//! ```rust
+//! _3 = move (*_1);            // `_1: Box<Foo>`
+//! _4 = move (_3.0: A);
+//! _5 = move (_3.1: B);
+//! ```
+//!
+//! becomes
+//!
+//! ```rust
+//! _4 = move ((*_1).0: A);
+//! _5 = move ((*_1).1: B);
+//! ```
+//!
+//! MIR building moves the fields of a destructured `*boxed` out of the box already, but the
+//! contents go through a temporary when they are a value first, e.g. when they are the value of a
+//! block, or when they are moved to a variable that is then destructured. Large contents then
+//! need a stack slot of their own.
+//!
+//! Drop elaboration considers the contents moved out of the box by the first assignment, so only
+//! the temporaries whose fields are not dropped are forwarded: the fields left in the box are then
+//! the ones that need no drop. The box itself must be untouched until the last field is moved.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::mir::visit::{MutVisitor, MutatingUseContext, PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::TyCtxt;
+
+pub struct ForwardBoxMoves;
+
+impl<'tcx> MirPass<'tcx> for ForwardBoxMoves {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let forwards = find_forwards(body);
+        for forward in forwards {
+            if !tcx.consider_optimizing(|| {
+                format!("ForwardBoxMoves {:?} {:?}", source.def_id(), forward.location)
+            }) {
+                return;
+            }
+            mir_trace!("moving the fields of {:?} out of {:?}", forward.temp, forward.boxed);
+            body[forward.location.block].statements[forward.location.statement_index].make_nop();
+            ReplaceTemp { tcx, temp: forward.temp, boxed: forward.boxed }.visit_body(body);
+        }
+    }
+}
+
+/// The move of the contents of the box `boxed` to `temp` at `location`, whose fields can be moved
+/// out of the box instead.
+struct Forward {
+    location: Location,
+    temp: Local,
+    boxed: Local,
+}
+
+fn find_forwards(body: &Body<'tcx>) -> Vec<Forward> {
+    let mut forwards: Vec<Forward> = vec![];
+    for (block, bb_data) in body.basic_blocks().iter_enumerated() {
+        for (statement_index, statement) in bb_data.statements.iter().enumerate() {
+            let (temp, boxed) = match &statement.kind {
+                StatementKind::Assign(box (dest, Rvalue::Use(Operand::Move(src)))) => {
+                    match (dest.as_local(), &src.projection[..]) {
+                        (Some(temp), [ProjectionElem::Deref])
+                            if body.local_decls[src.local].ty.is_box()
+                                && (body.local_kind(temp) == LocalKind::Temp
+                                    || body.local_kind(temp) == LocalKind::Var) =>
+                        {
+                            (temp, src.local)
+                        }
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+            // Forwarding to a box that is itself forwarded would stack the rewrites.
+            if forwards.iter().any(|forward| forward.temp == boxed || forward.boxed == temp) {
+                continue;
+            }
+            let location = Location { block, statement_index };
+            if can_forward(body, location, temp, boxed) {
+                forwards.push(Forward { location, temp, boxed });
+            }
+        }
+    }
+    forwards
+}
+
+/// Returns whether `temp`, assigned the contents of `boxed` at `location`, is only used by moves
+/// and copies of its fields later in the same block, while `boxed` is not used.
+fn can_forward(body: &Body<'tcx>, location: Location, temp: Local, boxed: Local) -> bool {
+    let mut uses = Uses { temp, boxed, location, valid: true, last_use: None, boxed_uses: vec![] };
+    for (bb, data) in body.basic_blocks().iter_enumerated() {
+        uses.visit_basic_block_data(bb, data);
+    }
+    let last_use = match uses.last_use {
+        Some(last_use) if uses.valid => last_use,
+        _ => return false,
+    };
+    // The box must hold on to the contents until the last field is moved.
+    uses.boxed_uses.iter().all(|&boxed_use| {
+        boxed_use == location
+            || boxed_use.block != location.block
+            || boxed_use.statement_index > last_use.statement_index
+    })
+}
+
+struct Uses {
+    temp: Local,
+    boxed: Local,
+    /// The location of the assignment of the contents to `temp`.
+    location: Location,
+    /// Whether `temp` is only used by moves and copies of its fields after `location`.
+    valid: bool,
+    last_use: Option<Location>,
+    boxed_uses: Vec<Location>,
+}
+
+impl Visitor<'tcx> for Uses {
+    fn visit_operand(&mut self, operand: &Operand<'tcx>, location: Location) {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) if place.local == self.temp => {
+                let projects_field = match place.projection.first() {
+                    Some(ProjectionElem::Field(..)) => true,
+                    _ => false,
+                };
+                if projects_field
+                    && location.block == self.location.block
+                    && location.statement_index > self.location.statement_index
+                {
+                    self.last_use = Some(location);
+                } else {
+                    self.valid = false;
+                }
+                // The indices of the projection can't be `temp`, which is not an integer.
+            }
+            _ => self.super_operand(operand, location),
+        }
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, location: Location) {
+        if local == self.boxed {
+            self.boxed_uses.push(location);
+        } else if local == self.temp {
+            match context {
+                PlaceContext::NonUse(_) => {}
+                PlaceContext::MutatingUse(MutatingUseContext::Store)
+                    if location == self.location => {}
+                _ => self.valid = false,
+            }
+        }
+    }
+}
+
+/// Replaces the places based on `temp` by the same places in the contents of `boxed`, and removes
+/// the storage statements of `temp`.
+struct ReplaceTemp<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    temp: Local,
+    boxed: Local,
+}
+
+impl<'tcx> MutVisitor<'tcx> for ReplaceTemp<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_place(&mut self, place: &mut Place<'tcx>, context: PlaceContext, location: Location) {
+        if place.local == self.temp {
+            let mut projection = vec![ProjectionElem::Deref];
+            projection.extend(place.projection.iter().cloned());
+            let projection = self.tcx.intern_place_elems(&projection);
+            *place = Place { local: self.boxed, projection };
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
+        match statement.kind {
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local)
+                if local == self.temp =>
+            {
+                statement.make_nop();
+            }
+            _ => self.super_statement(statement, location),
+        }
+    }
+}
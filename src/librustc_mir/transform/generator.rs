@@ -542,6 +542,14 @@ fn locals_live_across_suspend_points(
             // The generator argument is ignored
             live_locals_here.remove(self_arg());
 
+            // The resume argument is written when resuming, so the value its destination holds
+            // during the suspension is never read.
+            if let TerminatorKind::Yield { resume_arg, .. } = &data.terminator().kind {
+                if let Some(local) = resume_arg.as_local() {
+                    live_locals_here.remove(local);
+                }
+            }
+
             debug!("loc = {:?}, live_locals_here = {:?}", loc, live_locals_here);
 
             // Add the locals live at this suspension point to the set of locals which live across
@@ -804,6 +812,7 @@ fn insert_switch<'tcx>(
         switch_ty: transform.discr_ty,
         values: Cow::from(cases.iter().map(|&(i, _)| i as u128).collect::<Vec<_>>()),
         targets: cases.iter().map(|&(_, d)| d).chain(iter::once(default_block)).collect(),
+        target_weights: None,
     };
 
     let source_info = source_info(body);
@@ -882,20 +891,34 @@ fn create_generator_drop_shim<'tcx>(
     gen_ty: Ty<'tcx>,
     body: &mut BodyAndCache<'tcx>,
     drop_clean: BasicBlock,
+    needs_drop: bool,
 ) -> BodyAndCache<'tcx> {
     let mut body = body.clone();
     body.arg_count = 1; // make sure the resume argument is not included here
 
     let source_info = source_info(&body);
 
-    let mut cases = create_cases(&mut body, transform, Operation::Drop);
+    if needs_drop {
+        let mut cases = create_cases(&mut body, transform, Operation::Drop);
 
-    cases.insert(0, (UNRESUMED, drop_clean));
+        cases.insert(0, (UNRESUMED, drop_clean));
 
-    // The returned state and the poisoned state fall through to the default
-    // case which is just to return
+        // The returned state and the poisoned state fall through to the default
+        // case which is just to return
 
-    insert_switch(&mut body, cases, &transform, TerminatorKind::Return);
+        insert_switch(&mut body, cases, &transform, TerminatorKind::Return);
+    } else {
+        // Dropping the generator does nothing in any state, so return right away instead of
+        // switching on the state. The rest of the body is removed with the dead blocks below.
+        body.basic_blocks_mut().raw.insert(
+            0,
+            BasicBlockData {
+                statements: Vec::new(),
+                terminator: Some(Terminator { source_info, kind: TerminatorKind::Return }),
+                is_cleanup: false,
+            },
+        );
+    }
 
     for block in body.basic_blocks_mut() {
         let kind = &mut block.terminator_mut().kind;
@@ -927,7 +950,7 @@ fn create_generator_drop_shim<'tcx>(
         is_block_tail: None,
         local_info: LocalInfo::Other,
     };
-    if tcx.sess.opts.debugging_opts.mir_emit_retag {
+    if tcx.sess.opts.debugging_opts.mir_emit_retag.is_some() {
         // Alias tracking must know we changed the type
         body.basic_blocks_mut()[START_BLOCK].statements.insert(
             0,
@@ -1133,6 +1156,10 @@ fn create_cases<'tcx>(
 }
 
 impl<'tcx> MirPass<'tcx> for StateTransform {
+    fn is_required(&self) -> bool {
+        true
+    }
+
     fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
         let yield_ty = if let Some(yield_ty) = body.yield_ty {
             yield_ty
@@ -1200,6 +1227,12 @@ impl<'tcx> MirPass<'tcx> for StateTransform {
         let (remap, layout, storage_liveness) =
             compute_layout(tcx, source, &upvars, interior, movable, body);
 
+        // Dropping the generator only has an effect if one of its upvars or saved locals needs
+        // to be dropped.
+        let param_env = tcx.param_env(def_id);
+        let needs_drop =
+            upvars.iter().chain(&layout.field_tys).any(|ty| ty.needs_drop(tcx, param_env));
+
         // Run the transformation which converts Places from Local to generator struct
         // accesses for locals in `remap`.
         // It also rewrites `return x` and `yield y` as writing a new generator state and returning
@@ -1237,8 +1270,16 @@ impl<'tcx> MirPass<'tcx> for StateTransform {
         dump_mir(tcx, None, "generator_post-transform", &0, source, body, |_, _| Ok(()));
 
         // Create a copy of our MIR and use it to create the drop shim for the generator
-        let drop_shim =
-            create_generator_drop_shim(tcx, &transform, def_id, source, gen_ty, body, drop_clean);
+        let drop_shim = create_generator_drop_shim(
+            tcx,
+            &transform,
+            def_id,
+            source,
+            gen_ty,
+            body,
+            drop_clean,
+            needs_drop,
+        );
 
         body.generator_drop = Some(box drop_shim);
 
@@ -1,5 +1,6 @@
 //! Inlining pass for MIR functions
 
+use rustc_data_structures::fx::FxHashSet;
 use rustc_hir::def_id::DefId;
 
 use rustc_index::bit_set::BitSet;
@@ -8,20 +9,26 @@ use rustc_index::vec::{Idx, IndexVec};
 use rustc::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use rustc::mir::visit::*;
 use rustc::mir::*;
-use rustc::session::config::Sanitizer;
+use rustc::session::config::{MirPassRisk, Sanitizer};
 use rustc::ty::subst::{InternalSubsts, Subst, SubstsRef};
 use rustc::ty::{self, Instance, InstanceDef, ParamEnv, Ty, TyCtxt, TypeFoldable};
 
 use super::simplify::{remove_dead_blocks, CfgSimplifier};
-use crate::transform::{MirPass, MirSource};
+use crate::transform::{mir_opt_level, MirPass, MirSource};
+use crate::util::remarks::{emit_remark, RemarkKind};
 use std::collections::VecDeque;
 use std::iter;
 
 use rustc_attr as attr;
+use rustc_span::symbol::sym;
+use rustc_span::Span;
 use rustc_target::spec::abi::Abi;
 
 const DEFAULT_THRESHOLD: usize = 50;
 const HINT_THRESHOLD: usize = 100;
+/// The threshold for calls in iterator adapter chains over local closures, see
+/// `Inliner::is_closure_chain_link`.
+const CLOSURE_CHAIN_THRESHOLD: usize = 200;
 
 const INSTR_COST: usize = 5;
 const CALL_PENALTY: usize = 25;
@@ -30,25 +37,118 @@ const UNKNOWN_SIZE_COST: usize = 10;
 
 pub struct Inline;
 
+/// Only inlines the iterator adapter chains over closures of this crate, like
+/// `iter.map(|x| ..).filter(|x| ..)`, see `Inliner::is_closure_chain_link`. Unlike `Inline`, this
+/// runs in unoptimized builds too, where the calls of such chains are most of the cost of the
+/// code using them.
+pub struct InlineClosureChains;
+
 #[derive(Copy, Clone, Debug)]
 struct CallSite<'tcx> {
     callee: DefId,
     substs: SubstsRef<'tcx>,
+    /// `InstanceDef::Item(callee)`, or the shim providing the callee, see `callee_mir`.
+    instance: InstanceDef<'tcx>,
     bb: BasicBlock,
     location: SourceInfo,
 }
 
+/// The MIR of a callee, see `Inliner::callee_mir`. Deciding whether to inline a callee of
+/// another crate only decodes the blocks of its MIR that `should_inline` visits; the whole MIR
+/// is only decoded to be inlined.
+#[derive(Copy, Clone)]
+enum CalleeMir<'tcx> {
+    /// The MIR of a callee of this crate, or of a shim.
+    Body(&'tcx BodyAndCache<'tcx>),
+    /// The MIR of a callee of another crate, without its blocks.
+    Extern(DefId, &'tcx BodyOutline<'tcx>),
+}
+
+impl<'tcx> CalleeMir<'tcx> {
+    fn span(self) -> Span {
+        match self {
+            CalleeMir::Body(body) => body.span,
+            CalleeMir::Extern(_, outline) => outline.span,
+        }
+    }
+
+    fn yield_ty(self) -> Option<Ty<'tcx>> {
+        match self {
+            CalleeMir::Body(body) => body.yield_ty,
+            CalleeMir::Extern(_, outline) => outline.yield_ty,
+        }
+    }
+
+    fn arg_count(self) -> usize {
+        match self {
+            CalleeMir::Body(body) => body.arg_count,
+            CalleeMir::Extern(_, outline) => outline.arg_count,
+        }
+    }
+
+    fn block_count(self) -> usize {
+        match self {
+            CalleeMir::Body(body) => body.basic_blocks().len(),
+            CalleeMir::Extern(_, outline) => outline.block_count,
+        }
+    }
+
+    fn block(self, tcx: TyCtxt<'tcx>, bb: BasicBlock) -> &'tcx BasicBlockData<'tcx> {
+        match self {
+            CalleeMir::Body(body) => &body[bb],
+            CalleeMir::Extern(def_id, _) => tcx.optimized_mir_block((def_id, bb)),
+        }
+    }
+
+    fn body(self, tcx: TyCtxt<'tcx>) -> &'tcx BodyAndCache<'tcx> {
+        match self {
+            CalleeMir::Body(body) => body,
+            CalleeMir::Extern(def_id, _) => tcx.optimized_mir(def_id),
+        }
+    }
+}
+
+impl<'tcx> HasLocalDecls<'tcx> for CalleeMir<'tcx> {
+    fn local_decls(&self) -> &IndexVec<Local, LocalDecl<'tcx>> {
+        match *self {
+            CalleeMir::Body(body) => &body.local_decls,
+            CalleeMir::Extern(_, outline) => &outline.local_decls,
+        }
+    }
+}
+
 impl<'tcx> MirPass<'tcx> for Inline {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn duplicates_code(&self) -> bool {
+        true
+    }
+
     fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
-        if tcx.sess.opts.debugging_opts.mir_opt_level >= 2 {
-            Inliner { tcx, source }.run_pass(body);
+        Inliner { tcx, source, closure_chains_only: false }.run_pass(body);
+    }
+}
+
+impl<'tcx> MirPass<'tcx> for InlineClosureChains {
+    fn duplicates_code(&self) -> bool {
+        true
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        if mir_opt_level(tcx, source) == 0 {
+            return;
         }
+        Inliner { tcx, source, closure_chains_only: true }.run_pass(body);
     }
 }
 
 struct Inliner<'tcx> {
     tcx: TyCtxt<'tcx>,
     source: MirSource<'tcx>,
+    /// Whether to only inline the calls of iterator adapter chains over local closures.
+    closure_chains_only: bool,
 }
 
 impl Inliner<'tcx> {
@@ -90,6 +190,8 @@ impl Inliner<'tcx> {
             return;
         }
 
+        // The closures passed to the iterator adapters inlined so far.
+        let mut chain_closures = FxHashSet::default();
         let mut local_change;
         let mut changed = false;
 
@@ -97,37 +199,20 @@ impl Inliner<'tcx> {
             local_change = false;
             while let Some(callsite) = callsites.pop_front() {
                 debug!("checking whether to inline callsite {:?}", callsite);
-                if !self.tcx.is_mir_available(callsite.callee) {
-                    debug!("checking whether to inline callsite {:?} - MIR unavailable", callsite);
+                let closure_chain = self.is_closure_chain_link(callsite, &chain_closures);
+                if self.closure_chains_only && !closure_chain {
                     continue;
                 }
-
-                let self_node_id = self.tcx.hir().as_local_node_id(self.source.def_id()).unwrap();
-                let callee_node_id = self.tcx.hir().as_local_node_id(callsite.callee);
-
-                let callee_body = if let Some(callee_node_id) = callee_node_id {
-                    // Avoid a cycle here by only using `optimized_mir` only if we have
-                    // a lower node id than the callee. This ensures that the callee will
-                    // not inline us. This trick only works without incremental compilation.
-                    // So don't do it if that is enabled.
-                    if !self.tcx.dep_graph.is_fully_enabled()
-                        && self_node_id.as_u32() < callee_node_id.as_u32()
-                    {
-                        self.tcx.optimized_mir(callsite.callee)
-                    } else {
-                        continue;
-                    }
-                } else {
-                    // This cannot result in a cycle since the callee MIR is from another crate
-                    // and is already optimized.
-                    self.tcx.optimized_mir(callsite.callee)
+                let callee_mir = match self.callee_mir(callsite) {
+                    Some(callee_mir) => callee_mir,
+                    None => continue,
                 };
 
-                let callee_body = if self.consider_optimizing(callsite, callee_body) {
+                let callee_body = if self.consider_optimizing(callsite, callee_mir, closure_chain) {
                     self.tcx.subst_and_normalize_erasing_regions(
                         &callsite.substs,
                         param_env,
-                        callee_body,
+                        callee_mir.body(self.tcx),
                     )
                 } else {
                     continue;
@@ -135,19 +220,27 @@ impl Inliner<'tcx> {
 
                 let start = caller_body.basic_blocks().len();
                 debug!("attempting to inline callsite {:?} - body={:?}", callsite, callee_body);
-                if !self.inline_call(callsite, caller_body, callee_body) {
+                if !self.inline_call(callsite, caller_body, callee_body, param_env) {
                     debug!("attempting to inline callsite {:?} - failure", callsite);
                     continue;
                 }
                 debug!("attempting to inline callsite {:?} - success", callsite);
+                if closure_chain {
+                    chain_closures.extend(self.local_closures(callsite));
+                }
 
                 // Add callsites from inlined function
                 for (bb, bb_data) in caller_body.basic_blocks().iter_enumerated().skip(start) {
                     if let Some(new_callsite) =
                         self.get_valid_function_call(bb, bb_data, caller_body, param_env)
                     {
-                        // Don't inline the same function multiple times.
-                        if callsite.callee != new_callsite.callee {
+                        // Don't inline the same function multiple times. Shims are tiny and
+                        // call other shims for the same types, e.g. for recursive drop glue, so
+                        // don't follow them any further either.
+                        let from_shim = !is_item(callsite.instance);
+                        if callsite.callee != new_callsite.callee
+                            && !(from_shim && !is_item(new_callsite.instance))
+                        {
                             callsites.push_back(new_callsite);
                         }
                     }
@@ -188,13 +281,26 @@ impl Inliner<'tcx> {
             if let ty::FnDef(callee_def_id, substs) = op.ty(caller_body, self.tcx).kind {
                 let instance = Instance::resolve(self.tcx, param_env, callee_def_id, substs)?;
 
-                if let InstanceDef::Virtual(..) = instance.def {
-                    return None;
+                match instance.def {
+                    InstanceDef::Item(_) => {}
+                    // The `FnOnce` shim of a closure only depends on `substs` through its
+                    // signature, so it can be inlined even where the closure is generic. It calls
+                    // the `FnMut` method of the closure, which is its body, so this lets calls
+                    // through `impl FnOnce` parameters of inlined functions reach the closure.
+                    InstanceDef::ClosureOnceShim { .. } => {}
+                    // The other shims are built for the types in `substs`, so they can only be
+                    // inlined where those are known.
+                    InstanceDef::DropGlue(_, Some(_))
+                    | InstanceDef::CloneShim(..)
+                    | InstanceDef::FnPtrShim(..)
+                        if !instance.substs.needs_subst() => {}
+                    _ => return None,
                 }
 
                 return Some(CallSite {
                     callee: instance.def_id(),
                     substs: instance.substs,
+                    instance: instance.def,
                     bb,
                     location: terminator.source_info,
                 });
@@ -204,31 +310,115 @@ impl Inliner<'tcx> {
         None
     }
 
-    fn consider_optimizing(&self, callsite: CallSite<'tcx>, callee_body: &Body<'tcx>) -> bool {
+    /// Returns the MIR of the callee, if it can be used without causing a query cycle.
+    fn callee_mir(&self, callsite: CallSite<'tcx>) -> Option<CalleeMir<'tcx>> {
+        if !is_item(callsite.instance) {
+            // Shims are built on demand and never inline anything, so they cannot cycle back
+            // to us.
+            return Some(CalleeMir::Body(self.tcx.mir_shims(callsite.instance)));
+        }
+
+        if !self.tcx.is_mir_available(callsite.callee) {
+            debug!("checking whether to inline callsite {:?} - MIR unavailable", callsite);
+            return None;
+        }
+
+        let self_node_id = self.tcx.hir().as_local_node_id(self.source.def_id()).unwrap();
+        let callee_node_id = self.tcx.hir().as_local_node_id(callsite.callee);
+
+        if let Some(callee_node_id) = callee_node_id {
+            // Avoid a cycle here by only using `optimized_mir` only if we have
+            // a lower node id than the callee. This ensures that the callee will
+            // not inline us. This trick only works without incremental compilation.
+            // So don't do it if that is enabled.
+            if !self.tcx.dep_graph.is_fully_enabled()
+                && self_node_id.as_u32() < callee_node_id.as_u32()
+            {
+                Some(CalleeMir::Body(self.tcx.optimized_mir(callsite.callee)))
+            } else {
+                None
+            }
+        } else {
+            // This cannot result in a cycle since the callee MIR is from another crate
+            // and is already optimized.
+            let outline = self.tcx.optimized_mir_outline(callsite.callee);
+            Some(CalleeMir::Extern(callsite.callee, outline))
+        }
+    }
+
+    fn consider_optimizing(
+        &self,
+        callsite: CallSite<'tcx>,
+        callee_mir: CalleeMir<'tcx>,
+        closure_chain: bool,
+    ) -> bool {
         debug!("consider_optimizing({:?})", callsite);
-        self.should_inline(callsite, callee_body)
+        self.should_inline(callsite, callee_mir, closure_chain)
             && self.tcx.consider_optimizing(|| {
-                format!("Inline {:?} into {:?}", callee_body.span, callsite)
+                format!("Inline {:?} into {:?}", callee_mir.span(), callsite)
             })
     }
 
-    fn should_inline(&self, callsite: CallSite<'tcx>, callee_body: &Body<'tcx>) -> bool {
+    /// Whether the call is a link of an iterator adapter chain over closures of this crate, like
+    /// `iter.map(|x| ..).filter(|x| ..)`. The chain starts with the calls to the methods of
+    /// `Iterator`, like `Iterator::map` or `<Map<I, F> as Iterator>::next`, instantiated with such
+    /// a closure. It goes on with the calls to the closures passed to the adapters inlined so far,
+    /// `chain_closures`, and to the functions instantiated with them, like `Option::map` in
+    /// `Map::next`. Inlining all of them collapses the chain into a plain loop, so they get a much
+    /// higher threshold. Calls to other closures are left alone.
+    fn is_closure_chain_link(
+        &self,
+        callsite: CallSite<'tcx>,
+        chain_closures: &FxHashSet<DefId>,
+    ) -> bool {
+        if self.is_iterator_method(callsite.callee) {
+            self.local_closures(callsite).next().is_some()
+        } else {
+            self.local_closures(callsite).any(|closure| chain_closures.contains(&closure))
+        }
+    }
+
+    /// Whether `def_id` is a method of `Iterator`, or of one of its impls.
+    fn is_iterator_method(&self, def_id: DefId) -> bool {
+        let tcx = self.tcx;
+        let trait_def_id = tcx.trait_of_item(def_id).or_else(|| {
+            tcx.impl_of_method(def_id).and_then(|impl_def_id| tcx.trait_id_of_impl(impl_def_id))
+        });
+        match (trait_def_id, tcx.get_diagnostic_item(sym::Iterator)) {
+            (Some(trait_def_id), Some(iterator)) => trait_def_id == iterator,
+            _ => false,
+        }
+    }
+
+    /// The closures of this crate the call is to, or that its callee is instantiated with.
+    fn local_closures(&self, callsite: CallSite<'tcx>) -> impl Iterator<Item = DefId> + 'tcx {
+        let callee = Some(callsite.callee).filter(|&callee| self.tcx.is_closure(callee));
+        let instantiated_with = callsite.substs.types().flat_map(|ty| {
+            ty.walk().filter_map(|ty| match ty.kind {
+                ty::Closure(def_id, _) => Some(def_id),
+                _ => None,
+            })
+        });
+        callee.into_iter().chain(instantiated_with).filter(|def_id| def_id.is_local())
+    }
+
+    fn should_inline(
+        &self,
+        callsite: CallSite<'tcx>,
+        callee_mir: CalleeMir<'tcx>,
+        closure_chain: bool,
+    ) -> bool {
         debug!("should_inline({:?})", callsite);
         let tcx = self.tcx;
 
         // Cannot inline generators which haven't been transformed yet
-        if callee_body.yield_ty.is_some() {
+        if callee_mir.yield_ty().is_some() {
             debug!("    yield ty present - not inlining");
             return false;
         }
 
         let codegen_fn_attrs = tcx.codegen_fn_attrs(callsite.callee);
 
-        if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::TRACK_CALLER) {
-            debug!("`#[track_caller]` present - not inlining");
-            return false;
-        }
-
         // Avoid inlining functions marked as no_sanitize if sanitizer is enabled,
         // since instrumentation might be enabled and performed on the caller.
         match self.tcx.sess.opts.debugging_opts.sanitizer {
@@ -251,13 +441,31 @@ impl Inliner<'tcx> {
             None => {}
         }
 
+        let callee = || tcx.def_path_str(callsite.callee);
+        let span = callsite.location.span;
+
+        // `#[rustc_mir_inline]` and `#[rustc_no_mir_inline]` are only for the MIR inliner, and take
+        // precedence over `#[inline]`, which is also a hint for LLVM.
+        if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NO_MIR_INLINE) {
+            debug!("`#[rustc_no_mir_inline]` present - not inlining");
+            emit_remark(tcx, "Inline", RemarkKind::Missed, span, || {
+                format!("`{}` is `#[rustc_no_mir_inline]`", callee())
+            });
+            return false;
+        }
+        let mir_inline = codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::MIR_INLINE);
+
         let hinted = match codegen_fn_attrs.inline {
+            _ if mir_inline => true,
             // Just treat inline(always) as a hint for now,
             // there are cases that prevent inlining that we
             // need to check for first.
             attr::InlineAttr::Always => true,
             attr::InlineAttr::Never => {
                 debug!("`#[inline(never)]` present - not inlining");
+                emit_remark(tcx, "Inline", RemarkKind::Missed, span, || {
+                    format!("`{}` is `#[inline(never)]`", callee())
+                });
                 return false;
             }
             attr::InlineAttr::Hint => true,
@@ -274,7 +482,13 @@ impl Inliner<'tcx> {
             }
         }
 
-        let mut threshold = if hinted { HINT_THRESHOLD } else { DEFAULT_THRESHOLD };
+        let mut threshold = if closure_chain {
+            CLOSURE_CHAIN_THRESHOLD
+        } else if hinted {
+            HINT_THRESHOLD
+        } else {
+            DEFAULT_THRESHOLD
+        };
 
         // Significantly lower the threshold for inlining cold functions
         if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::COLD) {
@@ -284,7 +498,7 @@ impl Inliner<'tcx> {
         // Give a bonus functions with a small number of blocks,
         // We normally have two or three blocks for even
         // very small functions.
-        if callee_body.basic_blocks().len() <= 3 {
+        if callee_mir.block_count() <= 3 {
             threshold += threshold / 4;
         }
         debug!("    final inline threshold = {}", threshold);
@@ -299,12 +513,12 @@ impl Inliner<'tcx> {
         // Traverse the MIR manually so we can account for the effects of
         // inlining on the CFG.
         let mut work_list = vec![START_BLOCK];
-        let mut visited = BitSet::new_empty(callee_body.basic_blocks().len());
+        let mut visited = BitSet::new_empty(callee_mir.block_count());
         while let Some(bb) = work_list.pop() {
             if !visited.insert(bb.index()) {
                 continue;
             }
-            let blk = &callee_body.basic_blocks()[bb];
+            let blk = callee_mir.block(tcx, bb);
 
             for stmt in &blk.statements {
                 // Don't count StorageLive/StorageDead in the inlining cost.
@@ -324,7 +538,7 @@ impl Inliner<'tcx> {
                     work_list.push(target);
                     // If the location doesn't actually need dropping, treat it like
                     // a regular goto.
-                    let ty = location.ty(callee_body, tcx).subst(tcx, callsite.substs).ty;
+                    let ty = location.ty(&callee_mir, tcx).subst(tcx, callsite.substs).ty;
                     if ty.needs_drop(tcx, param_env) {
                         cost += CALL_PENALTY;
                         if let Some(unwind) = unwind {
@@ -372,8 +586,8 @@ impl Inliner<'tcx> {
 
         let ptr_size = tcx.data_layout.pointer_size.bytes();
 
-        for v in callee_body.vars_and_temps_iter() {
-            let v = &callee_body.local_decls[v];
+        // Skip the return place and the arguments.
+        for v in callee_mir.local_decls().iter().skip(callee_mir.arg_count() + 1) {
             let ty = v.ty.subst(tcx, callsite.substs);
             // Cost of the var is the size in machine-words, if we know
             // it.
@@ -384,15 +598,35 @@ impl Inliner<'tcx> {
             }
         }
 
-        if let attr::InlineAttr::Always = codegen_fn_attrs.inline {
+        if mir_inline {
+            debug!("INLINING {:?} because rustc_mir_inline [cost={}]", callsite, cost);
+            emit_remark(tcx, "Inline", RemarkKind::Applied, span, || {
+                format!("inlined `{}`, which is `#[rustc_mir_inline]` (cost {})", callee(), cost)
+            });
+            true
+        } else if let attr::InlineAttr::Always = codegen_fn_attrs.inline {
             debug!("INLINING {:?} because inline(always) [cost={}]", callsite, cost);
+            emit_remark(tcx, "Inline", RemarkKind::Applied, span, || {
+                format!("inlined `{}`, which is `#[inline(always)]` (cost {})", callee(), cost)
+            });
             true
         } else {
             if cost <= threshold {
                 debug!("INLINING {:?} [cost={} <= threshold={}]", callsite, cost, threshold);
+                emit_remark(tcx, "Inline", RemarkKind::Applied, span, || {
+                    format!("inlined `{}` (cost {} <= threshold {})", callee(), cost, threshold)
+                });
                 true
             } else {
                 debug!("NOT inlining {:?} [cost={} > threshold={}]", callsite, cost, threshold);
+                emit_remark(tcx, "Inline", RemarkKind::Missed, span, || {
+                    format!(
+                        "`{}` is too big to inline (cost {} > threshold {})",
+                        callee(),
+                        cost,
+                        threshold
+                    )
+                });
                 false
             }
         }
@@ -403,7 +637,15 @@ impl Inliner<'tcx> {
         callsite: CallSite<'tcx>,
         caller_body: &mut BodyAndCache<'tcx>,
         mut callee_body: BodyAndCache<'tcx>,
+        param_env: ParamEnv<'tcx>,
     ) -> bool {
+        if callsite.instance.requires_caller_location(self.tcx)
+            && !self.forward_caller_location(callsite, &mut callee_body, param_env)
+        {
+            debug!("not inlining {:?}: its calls may need its caller location", callsite.callee);
+            return false;
+        }
+
         let terminator = caller_body[callsite.bb].terminator.take().unwrap();
         match terminator.kind {
             // FIXME: Handle inlining of diverging calls
@@ -526,6 +768,83 @@ impl Inliner<'tcx> {
         }
     }
 
+    /// Makes the inlined body of a `#[track_caller]` callee observe the location of `callsite` as
+    /// its caller location.
+    ///
+    /// The callee gets its caller location from an implicit argument, which is used by the panics
+    /// of its assertions, its calls to other `#[track_caller]` functions, and the
+    /// `caller_location` intrinsic. Once inlined, these instead use the caller location of the
+    /// caller: the implicit argument of the caller if it is itself `#[track_caller]`, and else
+    /// the location of their own span. So they are given the span of `callsite`, and when that
+    /// location is constant, the `caller_location` intrinsic is replaced by the constant.
+    ///
+    /// Returns `false` if a call of the callee may or may not be to a `#[track_caller]` function
+    /// depending on how it is monomorphized. The callee must then not be inlined, as the location
+    /// that call observes would depend on it.
+    fn forward_caller_location(
+        &self,
+        callsite: CallSite<'tcx>,
+        callee_body: &mut BodyAndCache<'tcx>,
+        param_env: ParamEnv<'tcx>,
+    ) -> bool {
+        let tcx = self.tcx;
+        let caller_tracks_caller = self.source.instance.requires_caller_location(tcx);
+        let callsite_span = callsite.location.span;
+        let (basic_blocks, local_decls) = callee_body.basic_blocks_and_local_decls_mut();
+        for bb_data in basic_blocks {
+            let terminator = bb_data.terminator_mut();
+            let (def_id, substs, destination) = match &terminator.kind {
+                TerminatorKind::Assert { .. } => {
+                    terminator.source_info.span = callsite_span;
+                    continue;
+                }
+                TerminatorKind::Call { func, destination, .. } => {
+                    match func.ty(&*local_decls, tcx).kind {
+                        ty::FnDef(def_id, substs) => (def_id, substs, destination.clone()),
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+
+            let is_caller_location_intrinsic = tcx.fn_sig(def_id).abi() == Abi::RustIntrinsic
+                && tcx.item_name(def_id) == sym::caller_location;
+            if is_caller_location_intrinsic && !caller_tracks_caller {
+                if let Some((place, target)) = destination {
+                    debug!("replacing `caller_location` in {:?} by a constant", callsite.callee);
+                    let source_info = terminator.source_info;
+                    terminator.kind = TerminatorKind::Goto { target };
+                    let location = Constant {
+                        span: callsite_span,
+                        user_ty: None,
+                        literal: ty::Const::from_value(
+                            tcx,
+                            tcx.span_as_caller_location(callsite_span),
+                            tcx.caller_location_ty(),
+                        ),
+                    };
+                    bb_data.statements.push(Statement {
+                        source_info,
+                        kind: StatementKind::Assign(box (
+                            place,
+                            Rvalue::Use(Operand::Constant(box location)),
+                        )),
+                    });
+                    continue;
+                }
+            }
+            let tracks_caller = is_caller_location_intrinsic
+                || match Instance::resolve(tcx, param_env, def_id, substs) {
+                    Some(instance) => instance.def.requires_caller_location(tcx),
+                    None => return false,
+                };
+            if tracks_caller {
+                terminator.source_info.span = callsite_span;
+            }
+        }
+        true
+    }
+
     fn make_call_args(
         &self,
         args: Vec<Operand<'tcx>>,
@@ -801,3 +1120,10 @@ impl<'a, 'tcx> MutVisitor<'tcx> for Integrator<'a, 'tcx> {
         *scope = self.scope_map[*scope];
     }
 }
+
+fn is_item(instance: InstanceDef<'_>) -> bool {
+    match instance {
+        InstanceDef::Item(_) => true,
+        _ => false,
+    }
+}
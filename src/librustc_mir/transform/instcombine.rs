@@ -1,37 +1,58 @@
 //! Performs various peephole optimizations.
+//!
+//! The peepholes are listed in `PEEPHOLES`. Each one looks at a single rvalue, and possibly at
+//! the definitions of the temporaries it uses, and proposes replacements for them.
 
-use crate::transform::{MirPass, MirSource};
+use crate::transform::{mir_opt_level, MirPass, MirSource};
+use crate::util::def_use::DefUseAnalysis;
+use crate::util::ssa::{SsaDef, SsaRenaming};
 use rustc::mir::visit::{MutVisitor, Visitor};
 use rustc::mir::{
-    read_only, Body, BodyAndCache, Constant, Local, Location, Operand, Place, PlaceRef,
-    ProjectionElem, Rvalue,
+    read_only, AggregateKind, BinOp, Body, BodyAndCache, Constant, Local, LocalDecl, LocalKind,
+    Location, Operand, Place, PlaceRef, ProjectionElem, Rvalue, Statement, StatementKind, UnOp,
 };
-use rustc::ty::{self, TyCtxt};
-use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc::ty::{self, ParamEnv, Ty, TyCtxt};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
 use rustc_index::vec::Idx;
+use rustc_span::Span;
 use std::mem;
 
 pub struct InstCombine;
 
 impl<'tcx> MirPass<'tcx> for InstCombine {
-    fn run_pass(&self, tcx: TyCtxt<'tcx>, _: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
         // We only run when optimizing MIR (at any level).
-        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+        if mir_opt_level(tcx, source) == 0 {
             return;
         }
 
         // First, find optimization opportunities. This is done in a pre-pass to keep the MIR
         // read-only so that we can do global analyses on the MIR in the process (e.g.
         // `Place::ty()`).
-        let optimizations = {
+        let mut optimizations = {
             let read_only_cache = read_only!(body);
-            let mut optimization_finder = OptimizationFinder::new(body, tcx);
+            let mut def_use_analysis = DefUseAnalysis::new(body);
+            def_use_analysis.analyze(read_only_cache);
+            let ssa = SsaRenaming::new(read_only_cache);
+            let param_env = tcx.param_env(source.def_id());
+            let mut optimization_finder =
+                OptimizationFinder::new(body, tcx, param_env, &def_use_analysis, &ssa);
             optimization_finder.visit_body(read_only_cache);
             optimization_finder.optimizations
         };
 
         // Then carry out those optimizations.
+        body.local_decls.extend(mem::take(&mut optimizations.new_temps));
+        let new_statements = mem::take(&mut optimizations.new_statements);
         MutVisitor::visit_body(&mut InstCombineVisitor { optimizations, tcx }, body);
+
+        // The statements were found in order, so inserting the last ones first keeps the
+        // locations of the others valid.
+        let basic_blocks = body.basic_blocks_mut();
+        for (location, statements) in new_statements.into_iter().rev() {
+            let index = location.statement_index;
+            basic_blocks[location.block].statements.splice(index..index, statements);
+        }
     }
 }
 
@@ -46,76 +67,479 @@ impl<'tcx> MutVisitor<'tcx> for InstCombineVisitor<'tcx> {
     }
 
     fn visit_rvalue(&mut self, rvalue: &mut Rvalue<'tcx>, location: Location) {
-        if self.optimizations.and_stars.remove(&location) {
-            debug!("replacing `&*`: {:?}", rvalue);
-            let new_place = match rvalue {
-                Rvalue::Ref(_, _, place) => {
-                    if let &[ref proj_l @ .., proj_r] = place.projection.as_ref() {
-                        place.projection = self.tcx().intern_place_elems(&[proj_r]);
-
-                        Place {
-                            // Replace with dummy
-                            local: mem::replace(&mut place.local, Local::new(0)),
-                            projection: self.tcx().intern_place_elems(proj_l),
-                        }
-                    } else {
-                        unreachable!();
-                    }
-                }
-                _ => bug!("Detected `&*` but didn't find `&*`!"),
-            };
-            *rvalue = Rvalue::Use(Operand::Copy(new_place))
-        }
-
-        if let Some(constant) = self.optimizations.arrays_lengths.remove(&location) {
-            debug!("replacing `Len([_; N])`: {:?}", rvalue);
-            *rvalue = Rvalue::Use(Operand::Constant(box constant));
+        if let Some(new_rvalue) = self.optimizations.rvalues.remove(&location) {
+            mir_trace!("replacing {:?} with {:?}", rvalue, new_rvalue);
+            *rvalue = new_rvalue;
         }
 
         self.super_rvalue(rvalue, location)
     }
 }
 
+/// A peephole optimization. `apply` returns the replacement for an rvalue if the peephole
+/// applies to it. Peepholes that also need to change the definition of a temporary used by the
+/// rvalue do so through `OptimizationFinder::replace_def`.
+struct Peephole {
+    name: &'static str,
+    apply: for<'a, 'b, 'tcx> fn(
+        &'a mut OptimizationFinder<'b, 'tcx>,
+        &'a Rvalue<'tcx>,
+        Location,
+    ) -> Option<Rvalue<'tcx>>,
+}
+
+const PEEPHOLES: &[Peephole] = &[
+    Peephole { name: "and_star", apply: combine_and_star },
+    Peephole { name: "array_len", apply: combine_array_len },
+    Peephole { name: "mul_one", apply: combine_mul_one },
+    Peephole { name: "add_zero", apply: combine_add_zero },
+    Peephole { name: "mul_pow2", apply: combine_mul_pow2 },
+    Peephole { name: "checked_mul_pow2", apply: combine_checked_mul_pow2 },
+    Peephole { name: "div_rem_pow2", apply: combine_div_rem_pow2 },
+    Peephole { name: "double_not", apply: combine_double_not },
+    Peephole { name: "bool_eq", apply: combine_bool_eq },
+    Peephole { name: "discriminant_eq", apply: combine_discriminant_eq },
+];
+
 /// Finds optimization opportunities on the MIR.
 struct OptimizationFinder<'b, 'tcx> {
     body: &'b Body<'tcx>,
     tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    def_use_analysis: &'b DefUseAnalysis,
+    ssa: &'b SsaRenaming,
     optimizations: OptimizationList<'tcx>,
 }
 
 impl OptimizationFinder<'b, 'tcx> {
-    fn new(body: &'b Body<'tcx>, tcx: TyCtxt<'tcx>) -> OptimizationFinder<'b, 'tcx> {
-        OptimizationFinder { body, tcx, optimizations: OptimizationList::default() }
+    fn new(
+        body: &'b Body<'tcx>,
+        tcx: TyCtxt<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        def_use_analysis: &'b DefUseAnalysis,
+        ssa: &'b SsaRenaming,
+    ) -> OptimizationFinder<'b, 'tcx> {
+        OptimizationFinder {
+            body,
+            tcx,
+            param_env,
+            def_use_analysis,
+            ssa,
+            optimizations: OptimizationList::default(),
+        }
+    }
+
+    fn operand_ty(&self, operand: &Operand<'tcx>) -> Ty<'tcx> {
+        operand.ty(self.body, self.tcx)
+    }
+
+    /// Returns the bits of `operand` if it is an integer or `bool` constant.
+    fn constant_bits(&self, operand: &Operand<'tcx>) -> Option<u128> {
+        match operand {
+            Operand::Constant(constant) => {
+                let ty = constant.literal.ty;
+                if !ty.is_integral() && !ty.is_bool() {
+                    return None;
+                }
+                constant.literal.try_eval_bits(self.tcx, self.param_env, ty)
+            }
+            _ => None,
+        }
+    }
+
+    fn int_constant(&self, bits: u128, ty: Ty<'tcx>, location: Location) -> Operand<'tcx> {
+        Operand::Constant(box Constant {
+            span: self.body.source_info(location).span,
+            user_ty: None,
+            literal: ty::Const::from_bits(self.tcx, bits, self.param_env.and(ty)),
+        })
+    }
+
+    fn bool_constant(&self, value: bool, location: Location) -> Rvalue<'tcx> {
+        Rvalue::Use(Operand::Constant(box Constant {
+            span: self.body.source_info(location).span,
+            user_ty: None,
+            literal: ty::Const::from_bool(self.tcx, value),
+        }))
+    }
+
+    /// If `operand` reads a temporary, returns that temporary and the location and rvalue of the
+    /// assignment whose value it reads at `location`.
+    fn operand_def(
+        &self,
+        operand: &Operand<'tcx>,
+        location: Location,
+    ) -> Option<(Local, Location, &'b Rvalue<'tcx>)> {
+        let local = match operand {
+            Operand::Copy(place) | Operand::Move(place) => place.as_local()?,
+            Operand::Constant(_) => return None,
+        };
+        if self.body.local_kind(local) != LocalKind::Temp {
+            return None;
+        }
+
+        let value = self.ssa.value_used_at(location, local)?;
+        let def_location = match self.ssa.value_data(value).def {
+            SsaDef::Location(def_location) => def_location,
+            SsaDef::Arg | SsaDef::Phi(_) => return None,
+        };
+        let block_data = &self.body[def_location.block];
+        let statement = block_data.statements.get(def_location.statement_index)?;
+        match &statement.kind {
+            StatementKind::Assign(box (place, rvalue)) if place.as_local() == Some(local) => {
+                Some((local, def_location, rvalue))
+            }
+            _ => None,
+        }
+    }
+
+    /// Like `operand_def`, but additionally requires the temporary to be assigned only there and
+    /// `operand` to be its only use, so that its definition can be changed.
+    fn single_use_def(
+        &self,
+        operand: &Operand<'tcx>,
+        location: Location,
+    ) -> Option<(Local, Location, &'b Rvalue<'tcx>)> {
+        let (local, def_location, rvalue) = self.operand_def(operand, location)?;
+        let info = self.def_use_analysis.local_info(local);
+        if info.def_count() != 1
+            || info.use_count() != 1
+            || self.optimizations.rvalues.contains_key(&def_location)
+        {
+            return None;
+        }
+        Some((local, def_location, rvalue))
+    }
+
+    fn replace_def(&mut self, location: Location, rvalue: Rvalue<'tcx>) {
+        self.optimizations.rvalues.insert(location, rvalue);
+    }
+
+    fn new_temp(&mut self, ty: Ty<'tcx>, span: Span) -> Local {
+        let new_temps = &mut self.optimizations.new_temps;
+        new_temps.push(LocalDecl::new_temp(ty, span));
+        Local::new(self.body.local_decls.len() + new_temps.len() - 1)
+    }
+
+    /// Assigns `rvalue` to a new temporary right before the statement at `location`.
+    fn insert_temp(&mut self, location: Location, ty: Ty<'tcx>, rvalue: Rvalue<'tcx>) -> Local {
+        let source_info = *self.body.source_info(location);
+        let temp = self.new_temp(ty, source_info.span);
+        let kind = StatementKind::Assign(box (Place::from(temp), rvalue));
+        let statements = self.optimizations.new_statements.entry(location).or_default();
+        statements.push(Statement { source_info, kind });
+        temp
     }
 }
 
 impl Visitor<'tcx> for OptimizationFinder<'b, 'tcx> {
     fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
-        if let Rvalue::Ref(_, _, place) = rvalue {
-            if let PlaceRef { local, projection: &[ref proj_base @ .., ProjectionElem::Deref] } =
-                place.as_ref()
-            {
-                if Place::ty_from(local, proj_base, self.body, self.tcx).ty.is_region_ptr() {
-                    self.optimizations.and_stars.insert(location);
+        // A peephole looking through the definition of a temporary may already have replaced
+        // this rvalue.
+        if !self.optimizations.rvalues.contains_key(&location) {
+            for peephole in PEEPHOLES {
+                if let Some(new_rvalue) = (peephole.apply)(self, rvalue, location) {
+                    mir_trace!("peephole `{}` applies at {:?}", peephole.name, location);
+                    self.optimizations.rvalues.insert(location, new_rvalue);
+                    break;
                 }
             }
         }
 
-        if let Rvalue::Len(ref place) = *rvalue {
-            let place_ty = place.ty(&self.body.local_decls, self.tcx).ty;
-            if let ty::Array(_, len) = place_ty.kind {
-                let span = self.body.source_info(location).span;
-                let constant = Constant { span, literal: len, user_ty: None };
-                self.optimizations.arrays_lengths.insert(location, constant);
+        self.super_rvalue(rvalue, location)
+    }
+}
+
+/// `&*x` where `x: &T` becomes `x`.
+fn combine_and_star<'tcx>(
+    finder: &mut OptimizationFinder<'_, 'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    _: Location,
+) -> Option<Rvalue<'tcx>> {
+    if let Rvalue::Ref(_, _, place) = rvalue {
+        if let PlaceRef { local, projection: &[ref proj_base @ .., ProjectionElem::Deref] } =
+            place.as_ref()
+        {
+            if Place::ty_from(local, proj_base, finder.body, finder.tcx).ty.is_region_ptr() {
+                let projection = finder.tcx.intern_place_elems(proj_base);
+                return Some(Rvalue::Use(Operand::Copy(Place { local, projection })));
             }
         }
+    }
+    None
+}
 
-        self.super_rvalue(rvalue, location)
+/// `Len(x)` where `x: [T; N]` becomes `N`.
+fn combine_array_len<'tcx>(
+    finder: &mut OptimizationFinder<'_, 'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    location: Location,
+) -> Option<Rvalue<'tcx>> {
+    if let Rvalue::Len(ref place) = *rvalue {
+        let place_ty = place.ty(&finder.body.local_decls, finder.tcx).ty;
+        if let ty::Array(_, len) = place_ty.kind {
+            let span = finder.body.source_info(location).span;
+            return Some(Rvalue::Use(Operand::Constant(box Constant {
+                span,
+                literal: len,
+                user_ty: None,
+            })));
+        }
+    }
+    None
+}
+
+/// `x * 1` and `1 * x` on integers become `x`.
+fn combine_mul_one<'tcx>(
+    finder: &mut OptimizationFinder<'_, 'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    _: Location,
+) -> Option<Rvalue<'tcx>> {
+    match rvalue {
+        Rvalue::BinaryOp(BinOp::Mul, lhs, rhs) if finder.operand_ty(lhs).is_integral() => {
+            if finder.constant_bits(rhs) == Some(1) {
+                Some(Rvalue::Use(lhs.clone()))
+            } else if finder.constant_bits(lhs) == Some(1) {
+                Some(Rvalue::Use(rhs.clone()))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `x + 0`, `0 + x` and `x - 0` on integers become `x`.
+fn combine_add_zero<'tcx>(
+    finder: &mut OptimizationFinder<'_, 'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    _: Location,
+) -> Option<Rvalue<'tcx>> {
+    match rvalue {
+        Rvalue::BinaryOp(op @ BinOp::Add, lhs, rhs)
+        | Rvalue::BinaryOp(op @ BinOp::Sub, lhs, rhs)
+            if finder.operand_ty(lhs).is_integral() =>
+        {
+            if finder.constant_bits(rhs) == Some(0) {
+                Some(Rvalue::Use(lhs.clone()))
+            } else if *op == BinOp::Add && finder.constant_bits(lhs) == Some(0) {
+                Some(Rvalue::Use(rhs.clone()))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `x * 2^k` and `2^k * x` on integers become `x << k`, which is the same with wrapping
+/// arithmetic, for signed integers too.
+fn combine_mul_pow2<'tcx>(
+    finder: &mut OptimizationFinder<'_, 'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    location: Location,
+) -> Option<Rvalue<'tcx>> {
+    let (lhs, rhs) = match rvalue {
+        Rvalue::BinaryOp(BinOp::Mul, lhs, rhs) if finder.operand_ty(lhs).is_integral() => {
+            (lhs, rhs)
+        }
+        _ => return None,
+    };
+    let (value, constant) = match (finder.constant_bits(lhs), finder.constant_bits(rhs)) {
+        (_, Some(constant)) if constant.is_power_of_two() => (lhs, constant),
+        (Some(constant), _) if constant.is_power_of_two() => (rhs, constant),
+        _ => return None,
+    };
+    let shift = u128::from(constant.trailing_zeros());
+    let shift = finder.int_constant(shift, finder.tcx.types.u32, location);
+    Some(Rvalue::BinaryOp(BinOp::Shl, value.clone(), shift))
+}
+
+/// `CheckedMul(x, 2^k)` and `CheckedMul(2^k, x)` on integers become `x << k`, which overflowed if
+/// shifting it back right by `k` doesn't give `x`. The overflow flag of `CheckedShl` can't be
+/// used, as it only tells whether `k` is too large. The shift back is arithmetic for signed
+/// integers, so that the results changing sign overflowed as well. `2^k` must then be positive.
+fn combine_checked_mul_pow2<'tcx>(
+    finder: &mut OptimizationFinder<'_, 'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    location: Location,
+) -> Option<Rvalue<'tcx>> {
+    let (lhs, rhs) = match rvalue {
+        Rvalue::CheckedBinaryOp(BinOp::Mul, lhs, rhs) if finder.operand_ty(lhs).is_integral() => {
+            (lhs, rhs)
+        }
+        _ => return None,
+    };
+    let (value, constant) = match (finder.constant_bits(lhs), finder.constant_bits(rhs)) {
+        (_, Some(constant)) if constant.is_power_of_two() => (lhs, constant),
+        (Some(constant), _) if constant.is_power_of_two() => (rhs, constant),
+        _ => return None,
+    };
+    let ty = finder.operand_ty(value);
+    let bits = finder.tcx.layout_of(finder.param_env.and(ty)).ok()?.size.bits();
+    let shift = constant.trailing_zeros();
+    if ty.is_signed() && u64::from(shift) + 1 >= bits {
+        return None;
+    }
+
+    // `value` is used twice, and integers are `Copy`.
+    let value = match value {
+        Operand::Move(place) => Operand::Copy(place.clone()),
+        _ => value.clone(),
+    };
+    let shift = finder.int_constant(u128::from(shift), finder.tcx.types.u32, location);
+    let shifted = finder.insert_temp(
+        location,
+        ty,
+        Rvalue::BinaryOp(BinOp::Shl, value.clone(), shift.clone()),
+    );
+    let shifted_back = finder.insert_temp(
+        location,
+        ty,
+        Rvalue::BinaryOp(BinOp::Shr, Operand::Copy(shifted.into()), shift),
+    );
+    let overflow = finder.insert_temp(
+        location,
+        finder.tcx.types.bool,
+        Rvalue::BinaryOp(BinOp::Ne, Operand::Move(shifted_back.into()), value),
+    );
+    Some(Rvalue::Aggregate(
+        box AggregateKind::Tuple,
+        vec![Operand::Move(shifted.into()), Operand::Move(overflow.into())],
+    ))
+}
+
+/// `x / 2^k` and `x % 2^k` on unsigned integers become `x >> k` and `x & (2^k - 1)`. Signed
+/// integers are left alone, as their division rounds towards zero.
+fn combine_div_rem_pow2<'tcx>(
+    finder: &mut OptimizationFinder<'_, 'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    location: Location,
+) -> Option<Rvalue<'tcx>> {
+    let (op, lhs, rhs) = match rvalue {
+        Rvalue::BinaryOp(op @ BinOp::Div, lhs, rhs) | Rvalue::BinaryOp(op @ BinOp::Rem, lhs, rhs)
+            if finder.operand_ty(lhs).is_integral() && !finder.operand_ty(lhs).is_signed() =>
+        {
+            (*op, lhs, rhs)
+        }
+        _ => return None,
+    };
+    let constant = finder.constant_bits(rhs).filter(|constant| constant.is_power_of_two())?;
+    if op == BinOp::Div {
+        let shift = u128::from(constant.trailing_zeros());
+        let shift = finder.int_constant(shift, finder.tcx.types.u32, location);
+        Some(Rvalue::BinaryOp(BinOp::Shr, lhs.clone(), shift))
+    } else {
+        let mask = finder.int_constant(constant - 1, finder.operand_ty(lhs), location);
+        Some(Rvalue::BinaryOp(BinOp::BitAnd, lhs.clone(), mask))
+    }
+}
+
+/// `_1 = Not(x); _2 = Not(move _1)` becomes `_1 = x; _2 = move _1`.
+fn combine_double_not<'tcx>(
+    finder: &mut OptimizationFinder<'_, 'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    location: Location,
+) -> Option<Rvalue<'tcx>> {
+    let operand = match rvalue {
+        Rvalue::UnaryOp(UnOp::Not, operand) => operand,
+        _ => return None,
+    };
+    match finder.single_use_def(operand, location)? {
+        (_, def_location, Rvalue::UnaryOp(UnOp::Not, inner)) => {
+            finder.replace_def(def_location, Rvalue::Use(inner.clone()));
+            Some(Rvalue::Use(operand.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// `x == true` and `x != false` become `x`, `x == false` and `x != true` become `!x`.
+fn combine_bool_eq<'tcx>(
+    finder: &mut OptimizationFinder<'_, 'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    _: Location,
+) -> Option<Rvalue<'tcx>> {
+    let (op, lhs, rhs) = match rvalue {
+        Rvalue::BinaryOp(op @ BinOp::Eq, lhs, rhs) | Rvalue::BinaryOp(op @ BinOp::Ne, lhs, rhs)
+            if finder.operand_ty(lhs).is_bool() =>
+        {
+            (*op, lhs, rhs)
+        }
+        _ => return None,
+    };
+    let (value, constant) = match (finder.constant_bits(lhs), finder.constant_bits(rhs)) {
+        (_, Some(constant)) => (lhs, constant),
+        (Some(constant), None) => (rhs, constant),
+        (None, None) => return None,
+    };
+    if (constant == 1) == (op == BinOp::Eq) {
+        Some(Rvalue::Use(value.clone()))
+    } else {
+        Some(Rvalue::UnaryOp(UnOp::Not, value.clone()))
+    }
+}
+
+/// `_1 = discriminant(x); _2 = Eq(move _1, const K)` becomes `_2 = const false` if no variant
+/// of `x` has discriminant `K`, and `_2 = const true` if all of them have. `Ne` is handled
+/// accordingly. The discriminant may also be copied to other temporaries in between, as in the
+/// output of `SimplifyEnumCasts`.
+fn combine_discriminant_eq<'tcx>(
+    finder: &mut OptimizationFinder<'_, 'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    location: Location,
+) -> Option<Rvalue<'tcx>> {
+    let (op, lhs, rhs) = match rvalue {
+        Rvalue::BinaryOp(op @ BinOp::Eq, lhs, rhs) | Rvalue::BinaryOp(op @ BinOp::Ne, lhs, rhs) => {
+            (*op, lhs, rhs)
+        }
+        _ => return None,
+    };
+    let (discr, constant) = match (finder.constant_bits(lhs), finder.constant_bits(rhs)) {
+        (None, Some(constant)) => (lhs, constant),
+        (Some(constant), None) => (rhs, constant),
+        _ => return None,
+    };
+    // The result doesn't depend on the value of the discriminant, so it doesn't matter whether
+    // `x` changes between the copies.
+    let (_, mut def_location, mut def) = finder.operand_def(discr, location)?;
+    while let Rvalue::Use(operand) = def {
+        let (_, operand_def_location, operand_def) = finder.operand_def(operand, def_location)?;
+        def_location = operand_def_location;
+        def = operand_def;
+    }
+    let place = match def {
+        Rvalue::Discriminant(place) => place,
+        _ => return None,
+    };
+    let adt_def = match place.ty(finder.body, finder.tcx).ty.kind {
+        ty::Adt(adt_def, _) if adt_def.is_enum() => adt_def,
+        _ => return None,
+    };
+
+    let mut any_equal = false;
+    let mut all_equal = true;
+    for (_, variant_discr) in adt_def.discriminants(finder.tcx) {
+        any_equal |= variant_discr.val == constant;
+        all_equal &= variant_discr.val == constant;
     }
+    let equal = if !any_equal {
+        false
+    } else if all_equal {
+        true
+    } else {
+        return None;
+    };
+    Some(finder.bool_constant(equal == (op == BinOp::Eq), location))
 }
 
 #[derive(Default)]
 struct OptimizationList<'tcx> {
-    and_stars: FxHashSet<Location>,
-    arrays_lengths: FxHashMap<Location, Constant<'tcx>>,
+    /// The replacements for the rvalues at the given locations.
+    rvalues: FxHashMap<Location, Rvalue<'tcx>>,
+    /// The declarations of the temporaries added by the peepholes, following the existing locals.
+    new_temps: Vec<LocalDecl<'tcx>>,
+    /// The statements to insert before the statements at the given locations, in the order the
+    /// locations were visited.
+    new_statements: FxIndexMap<Location, Vec<Statement<'tcx>>>,
 }
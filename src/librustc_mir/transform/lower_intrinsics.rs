@@ -0,0 +1,187 @@
+//! Lowers the calls to the intrinsics that the optimizations understand better as MIR
+//! statements. A call to `assume`:
+//!
+//! ```rust
+//! _2 = const std::intrinsics::assume(move _3) -> bb1;
+//! ```
+//!
+//! becomes
+//!
+//! ```rust
+//! assume(move _3);
+//! goto -> bb1;
+//! ```
+//!
+//! which `ConstProp` and the `IntRanges` analysis take as a fact about the condition.
+//!
+//! The branches to `unreachable_unchecked`, which `NormalizeUnreachable` turns into `unreachable`
+//! blocks, state the same kind of fact. The branch of `if !(i < 4) { unreachable_unchecked() }`:
+//!
+//! ```rust
+//! _3 = Lt(move _4, const 4usize);
+//! _2 = Not(move _3);
+//! switchInt(move _2) -> [false: bb1, otherwise: bb2]; // bb2 is `unreachable`
+//! ```
+//!
+//! becomes
+//!
+//! ```rust
+//! _3 = Lt(move _4, const 4usize);
+//! assume(move _3);
+//! goto -> bb1;
+//! ```
+
+use crate::transform::{mir_opt_level, MirPass, MirSource};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_span::symbol::{sym, Symbol};
+use rustc_target::spec::abi::Abi;
+
+pub struct LowerIntrinsics;
+
+impl<'tcx> MirPass<'tcx> for LowerIntrinsics {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Stable
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        // We only run when optimizing MIR (at any level).
+        if mir_opt_level(tcx, source) == 0 {
+            return;
+        }
+
+        let bool_ty = tcx.types.bool;
+        for bb in body.basic_blocks().indices() {
+            if let Some((target, negated)) = branch_to_unreachable(body, bb, bool_ty) {
+                if !tcx.consider_optimizing(|| {
+                    format!("LowerIntrinsics {:?} {:?}", source.def_id(), bb)
+                }) {
+                    break;
+                }
+                mir_trace!("lowering the branch to unreachable in {:?}", bb);
+                lower_branch_to_unreachable(body, bb, target, negated, bool_ty);
+            }
+        }
+
+        for (bb, bb_data) in body.basic_blocks_mut().iter_enumerated_mut() {
+            let (cond, target) = match &bb_data.terminator().kind {
+                TerminatorKind::Call { func, args, destination: Some((_, target)), .. }
+                    if intrinsic_name(tcx, func) == Some(sym::assume) =>
+                {
+                    (args[0].clone(), *target)
+                }
+                _ => continue,
+            };
+            if !tcx.consider_optimizing(|| {
+                format!("LowerIntrinsics {:?} {:?}", source.def_id(), bb)
+            }) {
+                break;
+            }
+            mir_trace!("lowering the assume in {:?}", bb);
+
+            // The destination is `()`, so it needs no assignment.
+            let terminator = bb_data.terminator_mut();
+            terminator.kind = TerminatorKind::Goto { target };
+            let source_info = terminator.source_info;
+            bb_data
+                .statements
+                .push(Statement { source_info, kind: StatementKind::Assume(box cond) });
+        }
+    }
+}
+
+/// Returns the only reachable target of the `switchInt` on a `bool` ending `bb`, if its other
+/// target is an `unreachable` block, and whether the switch goes there when the condition is
+/// false.
+fn branch_to_unreachable(
+    body: &Body<'tcx>,
+    bb: BasicBlock,
+    bool_ty: Ty<'tcx>,
+) -> Option<(BasicBlock, bool)> {
+    match &body[bb].terminator().kind {
+        TerminatorKind::SwitchInt { switch_ty, values, targets, .. }
+            if *switch_ty == bool_ty && values[..] == [0] =>
+        {
+            let (if_false, if_true) = (targets[0], targets[1]);
+            if body[if_false].is_empty_unreachable() && !body[if_true].is_empty_unreachable() {
+                Some((if_true, false))
+            } else if body[if_true].is_empty_unreachable() && !body[if_false].is_empty_unreachable()
+            {
+                Some((if_false, true))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Replaces the `switchInt` ending `bb` by a `goto` to `target`, and assumes the condition of
+/// the switch, or its negation if `negated` is set.
+fn lower_branch_to_unreachable(
+    body: &mut BodyAndCache<'tcx>,
+    bb: BasicBlock,
+    target: BasicBlock,
+    negated: bool,
+    bool_ty: Ty<'tcx>,
+) {
+    let terminator = body[bb].terminator();
+    let source_info = terminator.source_info;
+    let cond = match &terminator.kind {
+        TerminatorKind::SwitchInt { discr, .. } => discr.clone(),
+        _ => bug!("not a switch: {:?}", terminator),
+    };
+
+    // The condition of `if !cond` is usually computed in the same block, so assume `cond` right
+    // where its negation was computed, rather than negating it again.
+    let negation = match &cond {
+        Operand::Move(place) if negated => {
+            body[bb].statements.iter().rposition(|statement| match &statement.kind {
+                StatementKind::Assign(box (dest, Rvalue::UnaryOp(UnOp::Not, _))) => dest == place,
+                _ => false,
+            })
+        }
+        _ => None,
+    };
+    let negated_cond = if negated && negation.is_none() {
+        Some(body.local_decls.push(LocalDecl::new_temp(bool_ty, source_info.span)))
+    } else {
+        None
+    };
+
+    let bb_data = &mut body.basic_blocks_mut()[bb];
+    if let Some(index) = negation {
+        let statement = &mut bb_data.statements[index];
+        if let StatementKind::Assign(box (_, Rvalue::UnaryOp(UnOp::Not, operand))) =
+            &statement.kind
+        {
+            statement.kind = StatementKind::Assume(box operand.clone());
+        }
+    } else if let Some(temp) = negated_cond {
+        let not = Rvalue::UnaryOp(UnOp::Not, cond);
+        let kind = StatementKind::Assign(box (temp.into(), not));
+        bb_data.statements.push(Statement { source_info, kind });
+        let kind = StatementKind::Assume(box Operand::Move(temp.into()));
+        bb_data.statements.push(Statement { source_info, kind });
+    } else {
+        bb_data.statements.push(Statement { source_info, kind: StatementKind::Assume(box cond) });
+    }
+    bb_data.terminator_mut().kind = TerminatorKind::Goto { target };
+}
+
+/// Returns the name of the intrinsic called by `func`, if it calls one.
+pub fn intrinsic_name(tcx: TyCtxt<'_>, func: &Operand<'_>) -> Option<Symbol> {
+    let def_id = match func {
+        Operand::Constant(constant) => match constant.literal.ty.kind {
+            ty::FnDef(def_id, _) => def_id,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if tcx.fn_sig(def_id).abi() == Abi::RustIntrinsic {
+        Some(tcx.item_name(def_id))
+    } else {
+        None
+    }
+}
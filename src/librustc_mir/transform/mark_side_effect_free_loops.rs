@@ -0,0 +1,95 @@
+//! Records the loops that never terminate and have no side effects, like `loop {}`, in
+//! `Body::side_effect_free_loops`. LLVM assumes that such loops terminate, as C++ does, and
+//! removes them, so codegen inserts a side effect in each recorded loop to preserve it. Loops
+//! that can exit are left alone, as the side effect would keep LLVM from optimizing them.
+//!
+//! The `infinite_loop_without_side_effects` lint is emitted by `CheckInfiniteLoops`, which looks
+//! for the same loops before optimizations.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::side_effects::SideEffects::Divergent;
+use crate::util::side_effects::{statement_side_effects, terminator_side_effects};
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc_index::bit_set::BitSet;
+
+pub struct MarkSideEffectFreeLoops;
+
+impl<'tcx> MirPass<'tcx> for MarkSideEffectFreeLoops {
+    fn run_pass(&self, _: TyCtxt<'tcx>, _: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        if !body.is_cfg_cyclic() {
+            return;
+        }
+
+        body.ensure_predecessors();
+        let headers = side_effect_free_loops(body.unwrap_read_only());
+        for &header in &headers {
+            mir_trace!("the loop at {:?} has no side effects", header);
+        }
+        body.side_effect_free_loops = headers;
+    }
+}
+
+/// Returns the headers of the natural loops that have no exit and no side effects.
+pub fn side_effect_free_loops(body: ReadOnlyBodyAndCache<'_, '_>) -> Vec<BasicBlock> {
+    let dominators = body.dominators();
+    let mut headers = vec![];
+    for (bb, bb_data) in body.basic_blocks().iter_enumerated() {
+        if !dominators.is_reachable(bb) {
+            continue;
+        }
+        for &header in bb_data.terminator().successors() {
+            // Only the edges back to a dominator of `bb` close a natural loop.
+            if !dominators.is_dominated_by(bb, header) || headers.contains(&header) {
+                continue;
+            }
+            let blocks = loop_blocks(body, header, bb);
+            let side_effect_free = blocks.iter().all(|block| {
+                let block_data = &body[block];
+                real_successors(block_data.terminator()).all(|target| blocks.contains(target))
+                    && has_no_side_effects(block_data)
+            });
+            if side_effect_free {
+                headers.push(header);
+            }
+        }
+    }
+    headers
+}
+
+/// Returns the successors of `terminator` that execution can go on to. The imaginary edges of
+/// `FalseEdges` and `FalseUnwind`, which are only there for borrowck, don't leave the loop.
+fn real_successors<'a>(terminator: &'a Terminator<'_>) -> impl Iterator<Item = BasicBlock> + 'a {
+    let real_target = match terminator.kind {
+        TerminatorKind::FalseEdges { real_target, .. }
+        | TerminatorKind::FalseUnwind { real_target, .. } => Some(real_target),
+        _ => None,
+    };
+    let successors = if real_target.is_some() { None } else { Some(terminator.successors()) };
+    real_target.into_iter().chain(successors.into_iter().flatten().copied())
+}
+
+/// Returns the blocks of the natural loop closed by the edge from `latch` to `header`: the
+/// blocks reaching `latch` without going through `header`.
+fn loop_blocks(
+    body: ReadOnlyBodyAndCache<'_, '_>,
+    header: BasicBlock,
+    latch: BasicBlock,
+) -> BitSet<BasicBlock> {
+    let mut blocks = BitSet::new_empty(body.basic_blocks().len());
+    blocks.insert(header);
+    let mut stack = vec![latch];
+    while let Some(block) = stack.pop() {
+        if blocks.insert(block) {
+            stack.extend(body.predecessors_for(block).iter().copied());
+        }
+    }
+    blocks
+}
+
+/// Returns whether `bb_data` only computes values, accesses memory and branches. Calls are side
+/// effects, as they may access volatile or atomic memory, or do I/O.
+fn has_no_side_effects(bb_data: &BasicBlockData<'_>) -> bool {
+    bb_data.statements.iter().all(|statement| statement_side_effects(statement) < Divergent)
+        && terminator_side_effects(bb_data.terminator()) < Divergent
+}
@@ -1,42 +1,94 @@
+// The result of a pass must not depend on the iteration order of a hash map, which varies with
+// the addresses of the keys hashed by address, like types, between compilations.
+#![deny(rustc::unordered_iteration)]
+
 use crate::{shim, util};
 use rustc::hir::map::Map;
-use rustc::mir::{BodyAndCache, ConstQualifs, MirPhase, Promoted};
-use rustc::ty::query::Providers;
+use rustc::mir::{BasicBlock, BasicBlockData, Body, BodyAndCache, BodyOutline, ConstQualifs};
+use rustc::mir::{MirPhase, Promoted};
+use rustc::session::config::MirPassRisk;
+use rustc::ty::query::{poisoned_query_count, Providers};
 use rustc::ty::steal::Steal;
 use rustc::ty::{InstanceDef, TyCtxt, TypeFoldable};
 use rustc_ast::ast;
+use rustc_attr::OptimizeAttr;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::profiling;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 use rustc_hir as hir;
 use rustc_hir::def_id::{CrateNum, DefId, DefIdSet, LOCAL_CRATE};
 use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc_index::vec::IndexVec;
+use rustc_span::fatal_error::FatalErrorMarker;
 use rustc_span::Span;
+use std::any::Any;
 use std::borrow::Cow;
+use std::iter;
+use std::panic;
+use std::time::Instant;
 
 pub mod add_call_guards;
 pub mod add_moves_for_packed_drops;
 pub mod add_retag;
+pub mod bytewise_array_eq;
+pub mod callgraph;
 pub mod check_consts;
+pub mod check_dead_assignments;
+pub mod check_huge_function_body;
+pub mod check_infinite_loops;
+pub mod check_int_to_ptr_casts;
+pub mod check_match_arms;
+pub mod check_mutual_recursion;
+pub mod check_uninit_reads;
 pub mod check_unsafety;
 pub mod cleanup_post_borrowck;
+pub mod cold_branches;
+pub mod const_debuginfo;
 pub mod const_prop;
 pub mod copy_prop;
+pub mod coroutine_inline;
 pub mod deaggregator;
+pub mod deduplicate_blocks;
+pub mod devirtualize;
 pub mod dump_mir;
+pub mod early_otherwise_branch;
 pub mod elaborate_drops;
 pub mod erase_regions;
+pub mod forward_box_moves;
 pub mod generator;
 pub mod inline;
 pub mod instcombine;
+pub mod lower_intrinsics;
+pub mod mark_side_effect_free_loops;
+pub mod narrow_switches;
 pub mod no_landing_pads;
+pub mod normalize_unreachable;
 pub mod promote_consts;
 pub mod qualify_min_const_fn;
+pub mod reaggregate;
+pub mod remove_bounds_checks;
+pub mod remove_identity_calls;
 pub mod remove_noop_landing_pads;
+pub mod remove_overflow_checks;
+pub mod remove_transparent_wrappers;
+pub mod required_consts;
 pub mod rustc_peek;
 pub mod simplify;
 pub mod simplify_branches;
+pub mod simplify_casts;
+pub mod simplify_comparison_integral;
+pub mod simplify_drop_flags;
+pub mod simplify_enum_casts;
+pub mod simplify_known_variants;
+pub mod simplify_short_circuit;
+pub mod simplify_slice_patterns;
 pub mod simplify_try;
+pub mod sink_statements;
 pub mod uninhabited_enum_branching;
 pub mod unreachable_prop;
+pub mod upgrade_to_moves;
+pub mod validate;
+pub mod verify_debuginfo;
 
 pub(crate) fn provide(providers: &mut Providers<'_>) {
     self::check_unsafety::provide(providers);
@@ -45,7 +97,13 @@ pub(crate) fn provide(providers: &mut Providers<'_>) {
         mir_const,
         mir_const_qualif,
         mir_validated,
+        mir_required_consts: required_consts::mir_required_consts,
+        mir_callees: callgraph::mir_callees,
+        mir_callgraph: callgraph::mir_callgraph,
         optimized_mir,
+        optimized_mir_block,
+        optimized_mir_outline,
+        optimized_mir_hash,
         is_mir_available,
         promoted_mir,
         ..*providers
@@ -133,7 +191,253 @@ pub trait MirPass<'tcx> {
         default_name::<Self>()
     }
 
+    /// How far the pass can be trusted, which decides at which `-Z mir-opt-level` it runs.
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Stable
+    }
+
+    /// Whether the pass copies code around, like inlining does. Such passes are skipped in
+    /// functions marked `#[optimize(size)]`.
+    fn duplicates_code(&self) -> bool {
+        false
+    }
+
+    /// Whether the MIR cannot be codegened without the pass, like generators without
+    /// `StateTransform`. The other passes run while optimizing are optional: the pass manager
+    /// recovers from a panic in them by going on with the MIR they started from.
+    fn is_required(&self) -> bool {
+        false
+    }
+
     fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>);
+
+    /// The passes `run_passes` runs repeatedly instead of this one, see `FixpointGroup`.
+    fn fixpoint_group(&self) -> Option<&[&dyn MirPass<'tcx>]> {
+        None
+    }
+}
+
+/// A group of passes that enable each other, e.g. inlining exposes constant arguments to
+/// `ConstProp`, which can make more calls direct. `run_passes` runs the group until the MIR
+/// stops changing, at most `-Z mir-opt-fixpoint-limit` times.
+pub struct FixpointGroup<'a, 'tcx> {
+    label: String,
+    passes: &'a [&'a dyn MirPass<'tcx>],
+}
+
+impl<'a, 'tcx> FixpointGroup<'a, 'tcx> {
+    pub fn new(label: &str, passes: &'a [&'a dyn MirPass<'tcx>]) -> Self {
+        FixpointGroup { label: format!("FixpointGroup-{}", label), passes }
+    }
+}
+
+impl<'tcx> MirPass<'tcx> for FixpointGroup<'_, 'tcx> {
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.label)
+    }
+
+    /// Runs each pass once. Only used when the group is run outside of `run_passes`.
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        for pass in self.passes {
+            if is_pass_enabled(tcx, source, *pass) {
+                pass.run_pass(tcx, source, body);
+            }
+        }
+    }
+
+    fn fixpoint_group(&self) -> Option<&[&dyn MirPass<'tcx>]> {
+        Some(self.passes)
+    }
+}
+
+/// A fingerprint of `body`, to tell whether passes changed it.
+fn body_fingerprint(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> Fingerprint {
+    let mut hcx = tcx.create_stable_hashing_context();
+    let mut hasher = StableHasher::new();
+    body.hash_stable(&mut hcx, &mut hasher);
+    hasher.finish()
+}
+
+/// The points in the MIR pipeline at which drivers can add their own passes, see
+/// `CustomMirPasses`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MirPassInsertionPoint {
+    /// Right after borrowck, before drops are elaborated. The MIR still has regions, borrowck-only
+    /// statements, and unwinding edges even with `-C panic=abort`.
+    PostBorrowck,
+    /// After drop elaboration and erasing the regions, before any optimization.
+    PreOptimization,
+    /// After all optimizations, right before the MIR is handed to codegen.
+    PreCodegen,
+}
+
+/// MIR passes registered by a driver through `rustc_interface::Config::register_mir_passes`.
+/// They run on the MIR of every body and promoted of the local crate that gets optimized, in the
+/// order they were registered, and honor `MirPass::risk` like any other pass.
+#[derive(Default)]
+pub struct CustomMirPasses {
+    passes: Vec<(MirPassInsertionPoint, Box<dyn for<'tcx> MirPass<'tcx> + Send + Sync>)>,
+}
+
+impl CustomMirPasses {
+    pub fn register(
+        &mut self,
+        point: MirPassInsertionPoint,
+        pass: impl for<'tcx> MirPass<'tcx> + Send + Sync + 'static,
+    ) {
+        self.passes.push((point, Box::new(pass)));
+    }
+
+    /// Extracts the passes from the query context, where they are erased as `dyn Any`.
+    fn of(tcx: TyCtxt<'_>) -> &CustomMirPasses {
+        let passes: &dyn Any = &*tcx.mir_passes;
+        passes.downcast_ref().unwrap()
+    }
+
+    fn at<'a, 'tcx: 'a>(
+        &'a self,
+        point: MirPassInsertionPoint,
+    ) -> impl Iterator<Item = &'a dyn MirPass<'tcx>> + 'a {
+        self.passes.iter().filter(move |(at, _)| *at == point).map(|(_, pass)| {
+            let pass: &dyn MirPass<'tcx> = pass;
+            pass
+        })
+    }
+}
+
+/// Lets a registered pass, which works for any `'tcx`, run where a pass of one `'tcx` is expected.
+impl<'tcx> MirPass<'tcx> for Box<dyn for<'a> MirPass<'a> + Send + Sync> {
+    fn name(&self) -> Cow<'_, str> {
+        (**self).name()
+    }
+
+    fn risk(&self) -> MirPassRisk {
+        (**self).risk()
+    }
+
+    fn duplicates_code(&self) -> bool {
+        (**self).duplicates_code()
+    }
+
+    fn is_required(&self) -> bool {
+        (**self).is_required()
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        (**self).run_pass(tcx, source, body)
+    }
+
+    fn fixpoint_group(&self) -> Option<&[&dyn MirPass<'tcx>]> {
+        (**self).fixpoint_group()
+    }
+}
+
+/// The `#[optimize]` attribute of `source`. Shims have no attributes of their own.
+fn optimize_attr(tcx: TyCtxt<'_>, source: MirSource<'_>) -> OptimizeAttr {
+    match source.instance {
+        InstanceDef::Item(def_id) => tcx.codegen_fn_attrs(def_id).optimize,
+        _ => OptimizeAttr::None,
+    }
+}
+
+/// The MIR optimization level `source` is optimized at. This is the `-Z mir-opt-level` of the
+/// crate, or its `#![mir_opt_level]`, raised for functions marked `#[optimize(speed)]`, which
+/// get all passes not known to be unsound even in unoptimized builds.
+///
+/// Passes must use this instead of the session options to decide what to do.
+pub fn mir_opt_level(tcx: TyCtxt<'_>, source: MirSource<'_>) -> usize {
+    let crate_level = *tcx.sess.mir_opt_level.get();
+    match optimize_attr(tcx, source) {
+        OptimizeAttr::Speed => {
+            crate_level.max(MirPassRisk::Experimental.default_mir_opt_level())
+        }
+        OptimizeAttr::None | OptimizeAttr::Size => crate_level,
+    }
+}
+
+/// Whether `source` is marked `#[optimize(size)]`. Passes then favor smaller code over faster
+/// code, and passes that duplicate code don't run at all.
+pub fn optimize_for_size(tcx: TyCtxt<'_>, source: MirSource<'_>) -> bool {
+    match optimize_attr(tcx, source) {
+        OptimizeAttr::Size => true,
+        OptimizeAttr::None | OptimizeAttr::Speed => false,
+    }
+}
+
+/// Whether passes of the given risk are enabled on `source`, either by its `mir_opt_level` or by
+/// `-Z mir-enable-experimental-passes`.
+pub fn is_pass_risk_enabled(tcx: TyCtxt<'_>, source: MirSource<'_>, risk: MirPassRisk) -> bool {
+    let mir_opt_level = mir_opt_level(tcx, source);
+    mir_opt_level >= risk.default_mir_opt_level()
+        || (mir_opt_level > 0
+            && tcx.sess.opts.debugging_opts.mir_enable_experimental_passes.contains(&risk))
+}
+
+/// Whether `pass` runs on `source`, given its risk and whether it duplicates code.
+fn is_pass_enabled(tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, pass: &dyn MirPass<'tcx>) -> bool {
+    is_pass_risk_enabled(tcx, source, pass.risk())
+        && !(pass.duplicates_code() && optimize_for_size(tcx, source))
+}
+
+/// Records the time `pass_name` took on the body of `source` since `start`, and the growth of the
+/// resident memory since it was `resident_before`, for `-Z mir-pass-stats`. There is no telling
+/// the memory allocated and freed while the pass ran, but the blowups show as growth.
+fn record_pass_stats(
+    tcx: TyCtxt<'tcx>,
+    pass_name: &str,
+    source: MirSource<'tcx>,
+    start: Instant,
+    resident_before: Option<usize>,
+) {
+    let time = start.elapsed();
+    let resident_growth = match (resident_before, profiling::get_resident()) {
+        (Some(before), Some(after)) => after.saturating_sub(before),
+        _ => 0,
+    };
+    let body_description = || util::pretty::item_path(tcx, source);
+    tcx.sess.code_stats.record_mir_pass_run(pass_name, body_description, time, resident_growth);
+}
+
+/// Runs the optional pass `pass` with `-Z recover-mir-pass-panics`, going on with the MIR from
+/// before it if it panics: a bug in an optimization should not fail a compilation the unoptimized
+/// MIR is fine for. The panic is still reported, with a warning naming the pass. With
+/// `-Z treat-err-as-bug`, or if the panic unwound out of a query the pass used, the panic goes on
+/// unwinding instead.
+fn run_optional_pass(
+    tcx: TyCtxt<'tcx>,
+    source: MirSource<'tcx>,
+    pass: &dyn MirPass<'tcx>,
+    body: &mut BodyAndCache<'tcx>,
+) {
+    if tcx.sess.opts.debugging_opts.treat_err_as_bug.is_some() {
+        return pass.run_pass(tcx, source, body);
+    }
+
+    let before = body.clone();
+    let poisoned_queries = poisoned_query_count();
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        tcx.sess.diagnostic().with_recoverable_bugs(|| pass.run_pass(tcx, source, body))
+    }));
+    if let Err(payload) = result {
+        // Fatal errors were reported already, and must stop the compilation. The queries the
+        // panic unwound out of are poisoned, and would panic again when used.
+        if payload.is::<FatalErrorMarker>() || poisoned_query_count() != poisoned_queries {
+            panic::resume_unwind(payload);
+        }
+        *body = before;
+        tcx.sess
+            .struct_span_warn(
+                body.span,
+                &format!(
+                    "the MIR optimization pass `{}` panicked on `{}`, which was left unoptimized \
+                     by it",
+                    pass.name(),
+                    util::pretty::item_path(tcx, source),
+                ),
+            )
+            .note("this is a bug in the compiler, please report the panic above")
+            .emit();
+    }
 }
 
 pub fn run_passes(
@@ -151,8 +455,13 @@ pub fn run_passes(
     }
 
     let source = MirSource { instance, promoted };
+    let mut debuginfo_verifier = if tcx.sess.opts.debugging_opts.verify_debuginfo_mir {
+        Some(verify_debuginfo::DebugInfoVerifier::new(tcx, source, body))
+    } else {
+        None
+    };
     let mut index = 0;
-    let mut run_pass = |pass: &dyn MirPass<'tcx>| {
+    let mut run_pass = |pass: &dyn MirPass<'tcx>, body: &mut BodyAndCache<'tcx>| {
         let run_hooks = |body: &_, index, is_after| {
             dump_mir::on_mir_pass(
                 tcx,
@@ -164,17 +473,69 @@ pub fn run_passes(
             );
         };
         run_hooks(body, index, false);
-        pass.run_pass(tcx, source, body);
+        if is_pass_enabled(tcx, source, pass) {
+            let stats_start = if tcx.sess.mir_pass_stats() {
+                Some((Instant::now(), profiling::get_resident()))
+            } else {
+                None
+            };
+            util::pass_trace::in_pass(tcx, &pass.name(), source, || {
+                if mir_phase == MirPhase::Optimized
+                    && !pass.is_required()
+                    && tcx.sess.opts.debugging_opts.recover_mir_pass_panics
+                {
+                    run_optional_pass(tcx, source, pass, body)
+                } else {
+                    pass.run_pass(tcx, source, body)
+                }
+            });
+            if let Some((start, resident_before)) = stats_start {
+                record_pass_stats(tcx, &pass.name(), source, start, resident_before);
+            }
+        } else {
+            debug!(
+                "skipping {} ({:?}, duplicates code: {})",
+                pass.name(),
+                pass.risk(),
+                pass.duplicates_code()
+            );
+        }
         run_hooks(body, index, true);
+        if cfg!(debug_assertions) {
+            // The body is still in the phase before `mir_phase` until all the passes ran.
+            validate::validate_phase(tcx, source, &pass.name(), body, body.phase);
+        }
+        if let Some(verifier) = &mut debuginfo_verifier {
+            verifier.check_after_pass(tcx, source, &pass.name(), body);
+        }
 
         index += 1;
     };
 
     for pass in passes {
-        run_pass(*pass);
+        match pass.fixpoint_group() {
+            Some(group) => {
+                let mut before = None;
+                for _ in 0..tcx.sess.opts.debugging_opts.mir_opt_fixpoint_limit {
+                    let fingerprint = before.unwrap_or_else(|| body_fingerprint(tcx, body));
+                    for pass in group {
+                        run_pass(*pass, body);
+                    }
+                    let after = body_fingerprint(tcx, body);
+                    if after == fingerprint {
+                        break;
+                    }
+                    before = Some(after);
+                }
+            }
+            None => run_pass(*pass, body),
+        }
     }
 
     body.phase = mir_phase;
+    if cfg!(debug_assertions) {
+        validate::validate_phase(tcx, source, "the last pass", body, mir_phase);
+    }
 }
 
 fn mir_const_qualif(tcx: TyCtxt<'_>, def_id: DefId) -> ConstQualifs {
@@ -230,6 +591,10 @@ fn mir_const(tcx: TyCtxt<'_>, def_id: DefId) -> &Steal<BodyAndCache<'_>> {
             // What we need to do constant evaluation.
             &simplify::SimplifyCfg::new("initial"),
             &rustc_peek::SanityCheck,
+            &check_match_arms::CheckMatchArms,
+            &check_int_to_ptr_casts::CheckIntToPtrCasts,
+            &check_uninit_reads::CheckUninitReads,
+            &check_dead_assignments::CheckDeadAssignments,
         ],
     );
     body.ensure_predecessors();
@@ -256,6 +621,8 @@ fn mir_validated(
             // What we need to run borrowck etc.
             &promote_pass,
             &simplify::SimplifyCfg::new("qualify-consts"),
+            &check_infinite_loops::CheckInfiniteLoops,
+            &check_huge_function_body::CheckHugeFunctionBody,
         ],
     );
 
@@ -269,60 +636,128 @@ fn run_optimization_passes<'tcx>(
     def_id: DefId,
     promoted: Option<Promoted>,
 ) {
-    run_passes(
-        tcx,
-        body,
-        InstanceDef::Item(def_id),
-        promoted,
-        MirPhase::Optimized,
-        &[
-            // Remove all things only needed by analysis
-            &no_landing_pads::NoLandingPads::new(tcx),
-            &simplify_branches::SimplifyBranches::new("initial"),
-            &remove_noop_landing_pads::RemoveNoopLandingPads,
-            &cleanup_post_borrowck::CleanupNonCodegenStatements,
-            &simplify::SimplifyCfg::new("early-opt"),
-            // These next passes must be executed together
-            &add_call_guards::CriticalCallEdges,
-            &elaborate_drops::ElaborateDrops,
-            &no_landing_pads::NoLandingPads::new(tcx),
-            // AddMovesForPackedDrops needs to run after drop
-            // elaboration.
-            &add_moves_for_packed_drops::AddMovesForPackedDrops,
-            // AddRetag needs to run after ElaborateDrops, and it needs
-            // an AllCallEdges pass right before it.  Otherwise it should
-            // run fairly late, but before optimizations begin.
-            &add_call_guards::AllCallEdges,
-            &add_retag::AddRetag,
-            &simplify::SimplifyCfg::new("elaborate-drops"),
-            // No lifetime analysis based on borrowing can be done from here on out.
-
-            // From here on out, regions are gone.
-            &erase_regions::EraseRegions,
-            // Optimizations begin.
-            &unreachable_prop::UnreachablePropagation,
-            &uninhabited_enum_branching::UninhabitedEnumBranching,
-            &simplify::SimplifyCfg::new("after-uninhabited-enum-branching"),
-            &inline::Inline,
-            // Lowering generator control-flow and variables
-            // has to happen before we do anything else to them.
-            &generator::StateTransform,
-            &instcombine::InstCombine,
-            &const_prop::ConstProp,
-            &simplify_branches::SimplifyBranches::new("after-const-prop"),
-            &deaggregator::Deaggregator,
-            &copy_prop::CopyPropagation,
-            &simplify_branches::SimplifyBranches::new("after-copy-prop"),
-            &remove_noop_landing_pads::RemoveNoopLandingPads,
-            &simplify::SimplifyCfg::new("after-remove-noop-landing-pads"),
-            &simplify_try::SimplifyArmIdentity,
-            &simplify_try::SimplifyBranchSame,
-            &simplify::SimplifyCfg::new("final"),
-            &simplify::SimplifyLocals,
-            &add_call_guards::CriticalCallEdges,
-            &dump_mir::Marker("PreCodegen"),
-        ],
-    );
+    let lowering: &[&dyn MirPass<'tcx>] = &[
+        // Remove all things only needed by analysis
+        &no_landing_pads::NoLandingPads::new(tcx),
+        &simplify_branches::SimplifyBranches::new("initial"),
+        &remove_noop_landing_pads::RemoveNoopLandingPads,
+        &cleanup_post_borrowck::CleanupNonCodegenStatements,
+        &simplify::SimplifyCfg::new("early-opt"),
+        // These next passes must be executed together
+        &add_call_guards::CriticalCallEdges,
+        &elaborate_drops::ElaborateDrops,
+        &no_landing_pads::NoLandingPads::new(tcx),
+        // AddMovesForPackedDrops needs to run after drop
+        // elaboration.
+        &add_moves_for_packed_drops::AddMovesForPackedDrops,
+        // AddRetag needs to run after ElaborateDrops, and it needs
+        // an AllCallEdges pass right before it.  Otherwise it should
+        // run fairly late, but before optimizations begin.
+        &add_call_guards::AllCallEdges,
+        &add_retag::AddRetag,
+        &simplify::SimplifyCfg::new("elaborate-drops"),
+        // No lifetime analysis based on borrowing can be done from here on out.
+
+        // From here on out, regions are gone.
+        &erase_regions::EraseRegions,
+    ];
+    let simplify_branches_fixpoint = simplify_branches::SimplifyBranches::new("fixpoint");
+    let simplify_cfg_fixpoint = simplify::SimplifyCfg::new("fixpoint");
+    let inline_const_prop: &[&dyn MirPass<'tcx>] = &[
+        &inline::Inline,
+        &const_prop::ConstProp,
+        &simplify_branches_fixpoint,
+        &simplify_cfg_fixpoint,
+    ];
+    let inline_const_prop_fixpoint = FixpointGroup::new("inline-const-prop", inline_const_prop);
+    let optimizations: &[&dyn MirPass<'tcx>] = &[
+        // Optimizations begin.
+        &normalize_unreachable::NormalizeUnreachable,
+        // After `NormalizeUnreachable`, which turns the calls to `unreachable_unchecked` into
+        // `unreachable` blocks, and before `UnreachablePropagation`, which would remove the
+        // branches to them along with the facts they state.
+        &lower_intrinsics::LowerIntrinsics,
+        &unreachable_prop::UnreachablePropagation,
+        &uninhabited_enum_branching::UninhabitedEnumBranching,
+        &simplify::SimplifyCfg::new("after-uninhabited-enum-branching"),
+        // Devirtualize before inlining, so that the now-direct callees can be inlined.
+        &devirtualize::Devirtualize,
+        // Before inlining, and before the awaiting generators are transformed.
+        &coroutine_inline::CoroutineInline,
+        // Before inlining, which would otherwise inline the slice comparison.
+        &bytewise_array_eq::BytewiseArrayEq,
+        &inline::InlineClosureChains,
+        &inline::Inline,
+        // Lowering generator control-flow and variables
+        // has to happen before we do anything else to them.
+        &generator::StateTransform,
+        &simplify_slice_patterns::SimplifySlicePatterns,
+        &forward_box_moves::ForwardBoxMoves,
+        &remove_transparent_wrappers::RemoveTransparentWrappers,
+        // Before InstCombine, which folds the comparisons of the discriminants it exposes.
+        &simplify_enum_casts::SimplifyEnumCasts,
+        &instcombine::InstCombine,
+        &remove_identity_calls::RemoveIdentityCalls,
+        &simplify_casts::SimplifyCasts,
+        &const_prop::ConstProp,
+        &simplify_branches::SimplifyBranches::new("after-const-prop"),
+        // After the branches on constants are taken, which leaves fewer values for the drop flags.
+        &simplify_drop_flags::SimplifyDropFlags,
+        // After the branches on constants are taken, which leaves the results of `&&` and `||`
+        // known in the blocks joining their operands.
+        &simplify_short_circuit::SimplifyShortCircuit,
+        // Only runs with `-Z mir-opt-fixpoint-limit`.
+        &inline_const_prop_fixpoint,
+        &copy_prop::CopyPropagation,
+        // After inlining and copy propagation, which expose the switches on the discriminant of
+        // values that were matched on before, e.g. in `unwrap`.
+        &simplify_known_variants::SimplifyKnownVariants,
+        // After copy propagation, which leaves the compared locals rather than their copies.
+        &simplify_comparison_integral::SimplifyComparisonIntegral,
+        // After the passes above, which leave fewer values for the switched-on locals.
+        &narrow_switches::NarrowSwitches,
+        &remove_overflow_checks::RemoveOverflowChecks,
+        &remove_bounds_checks::RemoveBoundsChecks,
+        &simplify_branches::SimplifyBranches::new("after-copy-prop"),
+        &remove_noop_landing_pads::RemoveNoopLandingPads,
+        &simplify::SimplifyCfg::new("after-remove-noop-landing-pads"),
+        // Rebuild the aggregates of inlined MIR, which was deaggregated already.
+        &reaggregate::Reaggregate,
+        &simplify_try::SimplifyArmIdentity,
+        &simplify_try::SimplifyBranchSame,
+        // After the passes matching whole values, which are easier to recognize while they are
+        // still built by a single aggregate.
+        &deaggregator::Deaggregator,
+        &sink_statements::SinkStatements,
+        &deduplicate_blocks::DeduplicateBlocks,
+        // After the identical `otherwise` targets are deduplicated, and before the inner switches
+        // it leaves unreachable are removed.
+        &early_otherwise_branch::EarlyOtherwiseBranch,
+        &simplify::SimplifyCfg::new("final"),
+        // Before `SimplifyLocals`, which removes the variables no longer needed by the debuginfo.
+        &const_debuginfo::ConstDebugInfo,
+        &simplify::SimplifyLocals,
+        // Last, so that no pass reads a local after its copy was moved.
+        &upgrade_to_moves::UpgradeToMoves,
+        &add_call_guards::CriticalCallEdges,
+        &cold_branches::ColdBranchWeights,
+        &mark_side_effect_free_loops::MarkSideEffectFreeLoops,
+    ];
+
+    let custom_passes = CustomMirPasses::of(tcx);
+    let lowering: Vec<&dyn MirPass<'tcx>> = custom_passes
+        .at(MirPassInsertionPoint::PostBorrowck)
+        .chain(lowering.iter().copied())
+        .collect();
+    let optimizations: Vec<&dyn MirPass<'tcx>> = custom_passes
+        .at(MirPassInsertionPoint::PreOptimization)
+        .chain(optimizations.iter().copied())
+        .chain(custom_passes.at(MirPassInsertionPoint::PreCodegen))
+        .chain(iter::once(&dump_mir::Marker("PreCodegen") as &dyn MirPass<'tcx>))
+        .collect();
+    let instance = InstanceDef::Item(def_id);
+    run_passes(tcx, body, instance, promoted, MirPhase::DropsLowered, &lowering);
+    run_passes(tcx, body, instance, promoted, MirPhase::Optimized, &optimizations);
 }
 
 fn optimized_mir(tcx: TyCtxt<'_>, def_id: DefId) -> &BodyAndCache<'_> {
@@ -334,9 +769,13 @@ fn optimized_mir(tcx: TyCtxt<'_>, def_id: DefId) -> &BodyAndCache<'_> {
         return shim::build_adt_ctor(tcx, def_id);
     }
 
-    // (Mir-)Borrowck uses `mir_validated`, so we have to force it to
-    // execute before we can steal.
+    // (Mir-)Borrowck, the required consts and the callees use `mir_validated`, so we have to
+    // force them to execute before we can steal.
     tcx.ensure().mir_borrowck(def_id);
+    tcx.ensure().mir_required_consts(def_id);
+    if callgraph::is_callgraph_used(tcx) {
+        tcx.ensure().mir_callees(def_id);
+    }
 
     let (body, _) = tcx.mir_validated(def_id);
     let mut body = body.steal();
@@ -345,12 +784,28 @@ fn optimized_mir(tcx: TyCtxt<'_>, def_id: DefId) -> &BodyAndCache<'_> {
     tcx.arena.alloc(body)
 }
 
+// The local MIR is built whole, so these just look into it. Only the MIR of other crates is
+// decoded block by block.
+fn optimized_mir_block(
+    tcx: TyCtxt<'tcx>,
+    (def_id, bb): (DefId, BasicBlock),
+) -> &'tcx BasicBlockData<'tcx> {
+    &tcx.optimized_mir(def_id)[bb]
+}
+
+fn optimized_mir_outline(tcx: TyCtxt<'_>, def_id: DefId) -> &BodyOutline<'_> {
+    tcx.arena.alloc(BodyOutline::new(tcx.optimized_mir(def_id)))
+}
+
 fn promoted_mir(tcx: TyCtxt<'_>, def_id: DefId) -> &IndexVec<Promoted, BodyAndCache<'_>> {
     if tcx.is_constructor(def_id) {
         return tcx.intern_promoted(IndexVec::new());
     }
 
     tcx.ensure().mir_borrowck(def_id);
+    if callgraph::is_callgraph_used(tcx) {
+        tcx.ensure().mir_callees(def_id);
+    }
     let (_, promoted) = tcx.mir_validated(def_id);
     let mut promoted = promoted.steal();
 
@@ -361,3 +816,18 @@ fn promoted_mir(tcx: TyCtxt<'_>, def_id: DefId) -> &IndexVec<Promoted, BodyAndCa
 
     tcx.intern_promoted(promoted)
 }
+
+/// A hash of the optimized MIR of `def_id` and of its promoteds, telling tools whether a change
+/// to the source only touched comments or formatting. Spans are not hashed, so that the hash only
+/// changes with the code itself. The artifacts built from the MIR still depend on the spans,
+/// through the source locations in panic messages and debuginfo, so the hash is not a key under
+/// which to cache them.
+fn optimized_mir_hash(tcx: TyCtxt<'_>, def_id: DefId) -> Option<Fingerprint> {
+    let mut hcx = tcx.create_stable_hashing_context();
+    let mut hasher = StableHasher::new();
+    hcx.while_hashing_spans(false, |hcx| {
+        tcx.optimized_mir(def_id).hash_stable(hcx, &mut hasher);
+        tcx.promoted_mir(def_id).hash_stable(hcx, &mut hasher);
+    });
+    Some(hasher.finish())
+}
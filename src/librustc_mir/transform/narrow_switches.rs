@@ -0,0 +1,115 @@
+//! Removes the values of `switchInt` terminators that the switched-on local can't hold, according
+//! to the `IntRanges` analysis, and the `otherwise` target when the remaining values cover every
+//! value it can hold. For example, after `_1 = Lt(_2, const 3u8)` is checked:
+//!
+//! ```rust
+//! switchInt(_2) -> [0u8: bb1, 1u8: bb2, 2u8: bb3, 7u8: bb4, otherwise: bb5];
+//! ```
+//!
+//! becomes
+//!
+//! ```rust
+//! switchInt(_2) -> [0u8: bb1, 1u8: bb2, otherwise: bb3];
+//! ```
+//!
+//! This also removes the `otherwise` target of the switches on the discriminant of enums matching
+//! all the variants. The switches left with two targets going to identical blocks are collapsed
+//! by `SimplifyBranchSame`, and the ones left with a single target become a `goto`.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::int_ranges::IntRanges;
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::TyCtxt;
+use std::borrow::Cow;
+
+pub struct NarrowSwitches;
+
+impl<'tcx> MirPass<'tcx> for NarrowSwitches {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let def_id = source.def_id();
+        let narrowed = {
+            let body: &Body<'tcx> = body;
+            let ranges = IntRanges::new(tcx, body, tcx.param_env(def_id));
+            body.basic_blocks()
+                .iter_enumerated()
+                .filter_map(|(bb, bb_data)| {
+                    narrowed_switch(&ranges, body, bb, &bb_data.terminator().kind)
+                        .map(|kind| (bb, kind))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for (bb, kind) in narrowed {
+            if !tcx.consider_optimizing(|| format!("NarrowSwitches {:?} {:?}", def_id, bb)) {
+                return;
+            }
+            mir_trace!("narrowing the switch of {:?} to {:?}", bb, kind);
+            body[bb].terminator_mut().kind = kind;
+        }
+    }
+}
+
+/// Returns the switch `kind` without the values and targets that can't be taken, if there are
+/// any.
+fn narrowed_switch<'tcx>(
+    ranges: &IntRanges<'_, 'tcx>,
+    body: &Body<'tcx>,
+    bb: BasicBlock,
+    kind: &TerminatorKind<'tcx>,
+) -> Option<TerminatorKind<'tcx>> {
+    let (discr, switch_ty, values, targets, target_weights) = match kind {
+        TerminatorKind::SwitchInt { discr, switch_ty, values, targets, target_weights } => {
+            (discr, *switch_ty, values, targets, target_weights)
+        }
+        _ => return None,
+    };
+    let state = ranges.state_before(body.terminator_loc(bb))?;
+    let range = ranges.place_range(&state, discr.place()?)?;
+
+    let kept: Vec<usize> = (0..values.len())
+        .filter(|&index| match ranges.bits_to_i128(values[index], switch_ty) {
+            Some(value) => range.contains(value),
+            None => true,
+        })
+        .collect();
+    // The values of a switch are distinct, so `otherwise` can only be taken if there are more
+    // values in the range than kept values.
+    let otherwise_reachable = range
+        .hi
+        .checked_sub(range.lo)
+        .map_or(true, |width| width >= kept.len() as i128);
+    if kept.len() == values.len() && otherwise_reachable {
+        return None;
+    }
+
+    let mut new_values: Vec<u128> = kept.iter().map(|&index| values[index]).collect();
+    let mut new_targets: Vec<BasicBlock> = kept.iter().map(|&index| targets[index]).collect();
+    let mut new_weights: Option<Vec<u32>> = target_weights
+        .as_ref()
+        .map(|weights| kept.iter().map(|&index| weights[index]).collect());
+    if otherwise_reachable {
+        new_targets.push(*targets.last().unwrap());
+        if let (Some(new_weights), Some(weights)) = (&mut new_weights, target_weights) {
+            new_weights.push(*weights.last().unwrap());
+        }
+    } else {
+        // The target of the last value becomes the `otherwise` target.
+        new_values.pop();
+    }
+
+    if new_targets.len() == 1 {
+        return Some(TerminatorKind::Goto { target: new_targets[0] });
+    }
+    Some(TerminatorKind::SwitchInt {
+        discr: discr.clone(),
+        switch_ty,
+        values: Cow::from(new_values),
+        targets: new_targets,
+        target_weights: new_weights,
+    })
+}
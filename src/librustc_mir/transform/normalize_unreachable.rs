@@ -0,0 +1,87 @@
+//! Gives the code that is undefined behavior to reach a single representation: a block made of
+//! an `unreachable` terminator alone, which the other passes test with
+//! `BasicBlockData::is_empty_unreachable`.
+//!
+//! The calls to `unreachable_unchecked` and to the `unreachable` intrinsic become `unreachable`
+//! terminators, and the calls to the `abort` intrinsic become `abort` terminators. The statements
+//! of the blocks ending with `unreachable` are removed, unless they may diverge, which only
+//! `asm!` with side effects can do.
+
+use crate::transform::lower_intrinsics::intrinsic_name;
+use crate::transform::{mir_opt_level, simplify, MirPass, MirSource};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::{self, TyCtxt};
+use rustc_span::symbol::sym;
+
+pub struct NormalizeUnreachable;
+
+impl<'tcx> MirPass<'tcx> for NormalizeUnreachable {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Stable
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        // We only run when optimizing MIR (at any level).
+        if mir_opt_level(tcx, source) == 0 {
+            return;
+        }
+
+        let mut replaced_calls = false;
+        for (bb, bb_data) in body.basic_blocks_mut().iter_enumerated_mut() {
+            let kind = match &bb_data.terminator().kind {
+                TerminatorKind::Call { func, .. } => diverging_call(tcx, func),
+                _ => None,
+            };
+            let strip = match kind.as_ref().unwrap_or(&bb_data.terminator().kind) {
+                TerminatorKind::Unreachable => {
+                    !bb_data.statements.is_empty() && !bb_data.statements_may_diverge()
+                }
+                _ => false,
+            };
+            if kind.is_none() && !strip {
+                continue;
+            }
+            if !tcx.consider_optimizing(|| {
+                format!("NormalizeUnreachable {:?} {:?}", source.def_id(), bb)
+            }) {
+                break;
+            }
+
+            if let Some(kind) = kind {
+                mir_trace!("replacing the call in {:?} with {:?}", bb, kind);
+                bb_data.terminator_mut().kind = kind;
+                replaced_calls = true;
+            }
+            if strip {
+                mir_trace!("removing the statements of {:?}", bb);
+                bb_data.statements.clear();
+            }
+        }
+
+        if replaced_calls {
+            // The unwinding and return blocks of the calls may be dead now.
+            simplify::remove_dead_blocks(body);
+        }
+    }
+}
+
+/// Returns the terminator that does what the call to `func` does, if it never returns.
+fn diverging_call(tcx: TyCtxt<'tcx>, func: &Operand<'tcx>) -> Option<TerminatorKind<'tcx>> {
+    if let Some(name) = intrinsic_name(tcx, func) {
+        return match name {
+            sym::unreachable => Some(TerminatorKind::Unreachable),
+            sym::abort => Some(TerminatorKind::Abort),
+            _ => None,
+        };
+    }
+    match func {
+        Operand::Constant(constant) => match constant.literal.ty.kind {
+            ty::FnDef(def_id, _) if tcx.is_diagnostic_item(sym::unreachable_unchecked, def_id) => {
+                Some(TerminatorKind::Unreachable)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -12,6 +12,7 @@
 //! initialization and can otherwise silence errors, if
 //! move analysis runs after promotion on broken MIR.
 
+use rustc::ich::StableHashingContext;
 use rustc::mir::traversal::ReversePostorder;
 use rustc::mir::visit::{MutVisitor, MutatingUseContext, PlaceContext, Visitor};
 use rustc::mir::*;
@@ -19,6 +20,9 @@ use rustc::ty::cast::CastTy;
 use rustc::ty::subst::InternalSubsts;
 use rustc::ty::{self, List, TyCtxt, TypeFoldable};
 use rustc_ast::ast::LitKind;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 use rustc_hir::def_id::DefId;
 use rustc_span::symbol::sym;
 use rustc_span::{Span, DUMMY_SP};
@@ -1141,7 +1145,102 @@ pub fn promote_candidates<'tcx>(
         }
     }
 
-    promotions
+    dedup_promoteds(tcx, def_id, body, promotions)
+}
+
+/// Merges the promoteds of `body` that only differ in their spans, or in scopes with the same
+/// lint levels, so that a constant used in several places, like `&[1, 2, 3]`, is only evaluated
+/// and emitted once. The uses in `body` are updated to refer to the promoteds that are kept.
+fn dedup_promoteds<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    body: &mut BodyAndCache<'tcx>,
+    promotions: IndexVec<Promoted, BodyAndCache<'tcx>>,
+) -> IndexVec<Promoted, BodyAndCache<'tcx>> {
+    if promotions.len() < 2 {
+        return promotions;
+    }
+
+    // Each scope is replaced by the first scope with the same lint levels before hashing.
+    let mut lint_root_scopes = FxHashMap::default();
+    let canonical_scopes: IndexVec<SourceScope, SourceScope> = body
+        .source_scopes
+        .iter_enumerated()
+        .map(|(scope, data)| match &data.local_data {
+            ClearCrossCrate::Set(data) => *lint_root_scopes.entry(data.lint_root).or_insert(scope),
+            ClearCrossCrate::Clear => scope,
+        })
+        .collect();
+
+    let mut hcx = tcx.create_stable_hashing_context();
+    let mut kept = IndexVec::new();
+    let mut seen = FxHashMap::default();
+    let remap: IndexVec<Promoted, Promoted> = promotions
+        .into_iter()
+        .map(|promoted| {
+            let fingerprint = promoted_fingerprint(tcx, &mut hcx, &canonical_scopes, &promoted);
+            *seen.entry(fingerprint).or_insert_with(|| kept.push(promoted))
+        })
+        .collect();
+
+    if kept.len() < remap.len() {
+        debug!("dedup_promoteds({:?}): {} promoteds merged", def_id, remap.len() - kept.len());
+        PromotedRenumberer { tcx, def_id, remap }.visit_body(body);
+    }
+    kept
+}
+
+fn promoted_fingerprint<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    hcx: &mut StableHashingContext<'_>,
+    canonical_scopes: &IndexVec<SourceScope, SourceScope>,
+    promoted: &BodyAndCache<'tcx>,
+) -> Fingerprint {
+    let mut promoted = promoted.clone();
+    ScopeCanonicalizer { tcx, canonical_scopes }.visit_body(&mut promoted);
+    let promoted: &Body<'_> = &promoted;
+    let mut hasher = StableHasher::new();
+    hcx.while_hashing_spans(false, |hcx| promoted.hash_stable(hcx, &mut hasher));
+    hasher.finish()
+}
+
+struct ScopeCanonicalizer<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    canonical_scopes: &'a IndexVec<SourceScope, SourceScope>,
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for ScopeCanonicalizer<'a, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_source_scope(&mut self, scope: &mut SourceScope) {
+        *scope = self.canonical_scopes[*scope];
+    }
+}
+
+/// Makes the uses of the promoteds of `def_id` refer to their new index.
+struct PromotedRenumberer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    remap: IndexVec<Promoted, Promoted>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for PromotedRenumberer<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_constant(&mut self, constant: &mut Constant<'tcx>, _: Location) {
+        if let ty::ConstKind::Unevaluated(def_id, substs, Some(promoted)) = constant.literal.val {
+            if def_id == self.def_id {
+                constant.literal = self.tcx.mk_const(ty::Const {
+                    ty: constant.literal.ty,
+                    val: ty::ConstKind::Unevaluated(def_id, substs, Some(self.remap[promoted])),
+                });
+            }
+        }
+    }
 }
 
 /// This function returns `true` if the `const_in_array_repeat_expressions` feature attribute should
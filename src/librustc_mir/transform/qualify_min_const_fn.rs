@@ -234,6 +234,8 @@ fn check_statement(
             Err((span, "cannot use inline assembly in const fn".into()))
         }
 
+        StatementKind::Assume(op) => check_operand(tcx, op, span, def_id, body),
+
         // These are all NOPs
         StatementKind::StorageLive(_)
         | StatementKind::StorageDead(_)
@@ -340,7 +342,13 @@ fn check_terminator(
             Err((span, "loops and conditional expressions are not stable in const fn".into()))
         }
 
-        TerminatorKind::SwitchInt { discr, switch_ty: _, values: _, targets: _ } => {
+        TerminatorKind::SwitchInt {
+            discr,
+            switch_ty: _,
+            values: _,
+            targets: _,
+            target_weights: _,
+        } => {
             check_operand(tcx, discr, span, def_id, body)
         }
 
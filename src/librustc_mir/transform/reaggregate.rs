@@ -0,0 +1,180 @@
+//! Rebuilds the aggregates taken apart by the `Deaggregator`, e.g. in MIR inlined from other
+//! functions. The consecutive field writes
+//!
+//! ```rust
+//! ((_0 as Variant).0: TY0) = move _1;
+//! ((_0 as Variant).1: TY1) = const 0u8;
+//! discriminant(_0) = VAR_IDX;
+//! ```
+//!
+//! covering all fields of the variant become the single assignment
+//!
+//! ```rust
+//! _0 = Enum::Variant(move _1, const 0u8);
+//! ```
+//!
+//! and likewise for the fields of structs and tuples, which have no discriminant to set. Passes
+//! looking for whole values being built, like `SimplifyArmIdentity`, then only have one form to
+//! recognize.
+//!
+//! The writes are only merged if the value is not borrowed, and if the operands don't read the
+//! value itself, so that nothing can observe the fields being written in a different order.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::mir::visit::Visitor;
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::{self, TyCtxt};
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::{Idx, IndexVec};
+use rustc_target::abi::VariantIdx;
+
+pub struct Reaggregate;
+
+impl<'tcx> MirPass<'tcx> for Reaggregate {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let mut borrowed = BorrowedLocals { locals: BitSet::new_empty(body.local_decls.len()) };
+        borrowed.visit_body(read_only!(body));
+        let borrowed = borrowed.locals;
+
+        let (basic_blocks, local_decls) = body.basic_blocks_and_local_decls_mut();
+        for (bb, bb_data) in basic_blocks.iter_enumerated_mut() {
+            let mut start = 0;
+            while start < bb_data.statements.len() {
+                let group = find_group(local_decls, &borrowed, &bb_data.statements[start..]);
+                let group = match group {
+                    Some(group) => group,
+                    None => {
+                        start += 1;
+                        continue;
+                    }
+                };
+                if !tcx.consider_optimizing(|| {
+                    format!("Reaggregate {:?} {:?}[{}]", source.def_id(), bb, start)
+                }) {
+                    return;
+                }
+                debug!("reaggregating {:?} at {:?}[{}]", group.local, bb, start);
+
+                let Group { local, kind, operands, len } = group;
+                let statements = &mut bb_data.statements[start..start + len];
+                for statement in &mut statements[1..] {
+                    statement.make_nop();
+                }
+                let aggregate = Rvalue::Aggregate(box kind, operands);
+                statements[0].kind = StatementKind::Assign(box (local.into(), aggregate));
+                start += len;
+            }
+        }
+    }
+}
+
+/// Consecutive statements writing all the fields of `local`.
+struct Group<'tcx> {
+    local: Local,
+    kind: AggregateKind<'tcx>,
+    /// The values of the fields, in order.
+    operands: Vec<Operand<'tcx>>,
+    /// The number of statements making up the group.
+    len: usize,
+}
+
+/// Matches a group of field writes at the start of `statements`.
+fn find_group<'tcx>(
+    local_decls: &IndexVec<Local, LocalDecl<'tcx>>,
+    borrowed: &BitSet<Local>,
+    statements: &[Statement<'tcx>],
+) -> Option<Group<'tcx>> {
+    // The local written to, and the variant for enums.
+    let mut target: Option<(Local, Option<VariantIdx>)> = None;
+    let mut fields: Vec<Option<Operand<'tcx>>> = vec![];
+    let mut len = 0;
+    for statement in statements {
+        let (place, operand) = match &statement.kind {
+            StatementKind::Assign(box (place, Rvalue::Use(operand))) => (place, operand),
+            _ => break,
+        };
+        let (local, variant, field) = match place.as_ref() {
+            PlaceRef { local, projection: &[ProjectionElem::Field(field, _)] } => {
+                (local, None, field)
+            }
+            PlaceRef {
+                local,
+                projection:
+                    &[ProjectionElem::Downcast(_, variant), ProjectionElem::Field(field, _)],
+            } => (local, Some(variant), field),
+            _ => break,
+        };
+        if *target.get_or_insert((local, variant)) != (local, variant) {
+            break;
+        }
+        if operand.place().map_or(false, |place| place.local == local) {
+            return None;
+        }
+        if fields.len() <= field.index() {
+            fields.resize(field.index() + 1, None);
+        }
+        if fields[field.index()].replace(operand.clone()).is_some() {
+            return None;
+        }
+        len += 1;
+    }
+
+    let (local, variant) = match statements.get(len).map(|statement| &statement.kind) {
+        // Enum variants end by setting the discriminant, which may be all there is to write.
+        Some(StatementKind::SetDiscriminant { place, variant_index }) => {
+            let local = place.as_local()?;
+            if target.map_or(false, |target| target != (local, Some(*variant_index))) {
+                return None;
+            }
+            len += 1;
+            (local, Some(*variant_index))
+        }
+        _ => match target {
+            Some((local, None)) => (local, None),
+            _ => return None,
+        },
+    };
+    if borrowed.contains(local) {
+        return None;
+    }
+
+    let kind = match (&local_decls[local].ty.kind, variant) {
+        (ty::Adt(adt_def, substs), Some(variant)) if adt_def.is_enum() => {
+            if adt_def.variants[variant].fields.len() != fields.len() {
+                return None;
+            }
+            AggregateKind::Adt(adt_def, variant, substs, None, None)
+        }
+        (ty::Adt(adt_def, substs), None) if adt_def.is_struct() => {
+            if adt_def.non_enum_variant().fields.len() != fields.len() {
+                return None;
+            }
+            AggregateKind::Adt(adt_def, VariantIdx::new(0), substs, None, None)
+        }
+        (ty::Tuple(tys), None) if tys.len() == fields.len() => AggregateKind::Tuple,
+        _ => return None,
+    };
+    let operands = fields.into_iter().collect::<Option<Vec<_>>>()?;
+    Some(Group { local, kind, operands, len })
+}
+
+struct BorrowedLocals {
+    locals: BitSet<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        match rvalue {
+            Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) => {
+                self.locals.insert(place.local);
+            }
+            _ => {}
+        }
+        self.super_rvalue(rvalue, location);
+    }
+}
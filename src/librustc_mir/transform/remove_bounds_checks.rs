@@ -0,0 +1,60 @@
+//! Removes the bounds checks of the indexing that the `IntRanges` analysis proves in bounds, like
+//! the indexing of an array by an index tested, or assumed, to be smaller than its length:
+//!
+//! ```rust
+//! _5 = Len((*_1));  // `_2` is in `0..=3`, and `(*_1)` is a `[u8; 4]`
+//! _6 = Lt(_2, _5);
+//! assert(move _6, "index out of bounds: the len is {} but the index is {}", move _5, _2) -> bb1;
+//! ```
+//!
+//! The assertion becomes a `goto`.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::int_ranges::IntRanges;
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::TyCtxt;
+
+pub struct RemoveBoundsChecks;
+
+impl<'tcx> MirPass<'tcx> for RemoveBoundsChecks {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let def_id = source.def_id();
+        let unneeded_checks = {
+            let body: &Body<'tcx> = body;
+            let ranges = IntRanges::new(tcx, body, tcx.param_env(def_id));
+            body.basic_blocks()
+                .iter_enumerated()
+                .filter_map(|(bb, bb_data)| match &bb_data.terminator().kind {
+                    TerminatorKind::Assert {
+                        cond,
+                        expected,
+                        msg: AssertKind::BoundsCheck { .. },
+                        target,
+                        ..
+                    } => {
+                        let state = ranges.state_before(body.terminator_loc(bb))?;
+                        if ranges.condition_is(&state, cond, *expected) {
+                            Some((bb, *target))
+                        } else {
+                            None
+                        }
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for (bb, target) in unneeded_checks {
+            if !tcx.consider_optimizing(|| format!("RemoveBoundsChecks {:?} {:?}", def_id, bb)) {
+                return;
+            }
+            mir_trace!("removing the bounds check of {:?}", bb);
+            body[bb].terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Replaces the calls returning their argument unchanged by a move of the argument:
+//!
+//! ```rust
+//! _3 = const <u8 as std::convert::Into<u8>>::into(move _4) -> [return: bb2, unwind: bb1];
+//! ```
+//!
+//! becomes
+//!
+//! ```rust
+//! _3 = move _4;
+//! goto -> bb2;
+//! ```
+//!
+//! The calls are recognized by `util::match_identity::is_identity_call`. This is what lets
+//! `SimplifyArmIdentity` remove arms like `Some(x) => Some(x.into())`, once the blocks are merged.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::match_identity::is_identity_call;
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::TyCtxt;
+
+pub struct RemoveIdentityCalls;
+
+impl<'tcx> MirPass<'tcx> for RemoveIdentityCalls {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        for (bb, bb_data) in body.basic_blocks_mut().iter_enumerated_mut() {
+            let (arg, dest, target) = match &bb_data.terminator().kind {
+                TerminatorKind::Call { func, args, destination: Some((dest, target)), .. }
+                    if args.len() == 1 && is_identity_call(tcx, func) =>
+                {
+                    (args[0].clone(), dest.clone(), *target)
+                }
+                _ => continue,
+            };
+            if !tcx.consider_optimizing(|| {
+                format!("RemoveIdentityCalls {:?} {:?}", source.def_id(), bb)
+            }) {
+                break;
+            }
+            debug!("removing identity call in {:?}", bb);
+
+            let terminator = bb_data.terminator_mut();
+            terminator.kind = TerminatorKind::Goto { target };
+            let source_info = terminator.source_info;
+            bb_data.statements.push(Statement {
+                source_info,
+                kind: StatementKind::Assign(box (dest, Rvalue::Use(arg))),
+            });
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::transform::{MirPass, MirSource};
 use crate::util::patch::MirPatch;
+use crate::util::side_effects::statement_side_effects;
 use rustc::mir::*;
 use rustc::ty::TyCtxt;
 use rustc_index::bit_set::BitSet;
@@ -31,32 +32,10 @@ impl RemoveNoopLandingPads {
         body: &Body<'_>,
         nop_landing_pads: &BitSet<BasicBlock>,
     ) -> bool {
-        for stmt in &body[bb].statements {
-            match &stmt.kind {
-                StatementKind::FakeRead(..)
-                | StatementKind::StorageLive(_)
-                | StatementKind::StorageDead(_)
-                | StatementKind::AscribeUserType(..)
-                | StatementKind::Nop => {
-                    // These are all nops in a landing pad
-                }
-
-                StatementKind::Assign(box (place, Rvalue::Use(_))) => {
-                    if place.as_local().is_some() {
-                        // Writing to a local (e.g., a drop flag) does not
-                        // turn a landing pad to a non-nop
-                    } else {
-                        return false;
-                    }
-                }
-
-                StatementKind::Assign { .. }
-                | StatementKind::SetDiscriminant { .. }
-                | StatementKind::InlineAsm { .. }
-                | StatementKind::Retag { .. } => {
-                    return false;
-                }
-            }
+        // Writing to locals (e.g., drop flags) does not turn a landing pad into a non-nop, as
+        // long as nothing else happens.
+        if !body[bb].statements.iter().all(|stmt| statement_side_effects(stmt).is_removable()) {
+            return false;
         }
 
         let terminator = body[bb].terminator();
@@ -0,0 +1,132 @@
+//! Removes the overflow checks of the arithmetic that the `IntRanges` analysis proves can't
+//! overflow, like the increment of a loop counter bounded by a constant:
+//!
+//! ```rust
+//! _4 = CheckedAdd(_1, const 1u8);  // `_1` is in `0..=9`
+//! assert(!move (_4.1: bool), "attempt to add with overflow") -> bb3;
+//! ```
+//!
+//! The assertion becomes a `goto`, and the unused overflow flag is then removed by the later
+//! simplifications.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::int_ranges::{IntRange, IntRanges};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::{ParamEnv, TyCtxt};
+
+pub struct RemoveOverflowChecks;
+
+impl<'tcx> MirPass<'tcx> for RemoveOverflowChecks {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let def_id = source.def_id();
+        let param_env = tcx.param_env(def_id);
+        let unneeded_checks = {
+            let body: &Body<'tcx> = body;
+            let ranges = IntRanges::new(tcx, body, param_env);
+            body.basic_blocks()
+                .iter_enumerated()
+                .filter_map(|(bb, bb_data)| {
+                    let (cond, target) = match &bb_data.terminator().kind {
+                        TerminatorKind::Assert {
+                            cond,
+                            expected: false,
+                            msg: AssertKind::Overflow(_),
+                            target,
+                            ..
+                        } => (cond.place()?, *target),
+                        _ => return None,
+                    };
+                    let location = checked_op_location(body, bb, cond)?;
+                    if cannot_overflow(tcx, param_env, &ranges, body, location) {
+                        Some((bb, target))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for (bb, target) in unneeded_checks {
+            if !tcx.consider_optimizing(|| format!("RemoveOverflowChecks {:?} {:?}", def_id, bb)) {
+                return;
+            }
+            mir_trace!("removing the overflow check of {:?}", bb);
+            body[bb].terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+    }
+}
+
+/// If `cond` is the overflow flag of a checked operation assigned in `bb`, returns the location of
+/// that assignment.
+fn checked_op_location(body: &Body<'_>, bb: BasicBlock, cond: &Place<'_>) -> Option<Location> {
+    let result = match cond.as_ref() {
+        PlaceRef { local, projection: &[ProjectionElem::Field(field, _)] }
+            if field.index() == 1 =>
+        {
+            local
+        }
+        _ => return None,
+    };
+    // The last assignment to the result in the block must be the checked operation.
+    for (statement_index, statement) in body[bb].statements.iter().enumerate().rev() {
+        match &statement.kind {
+            StatementKind::Assign(box (place, rvalue)) if place.local == result => {
+                return match rvalue {
+                    Rvalue::CheckedBinaryOp(..) if place.as_local().is_some() => {
+                        Some(Location { block: bb, statement_index })
+                    }
+                    _ => None,
+                };
+            }
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local)
+                if *local == result =>
+            {
+                return None;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether the checked operation assigned at `location` can't overflow, given the ranges of its
+/// operands.
+fn cannot_overflow<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    ranges: &IntRanges<'_, 'tcx>,
+    body: &Body<'tcx>,
+    location: Location,
+) -> bool {
+    let (op, lhs, rhs) = match &body[location.block].statements[location.statement_index].kind {
+        StatementKind::Assign(box (_, Rvalue::CheckedBinaryOp(op, lhs, rhs))) => (*op, lhs, rhs),
+        _ => return false,
+    };
+    let state = match ranges.state_before(location) {
+        Some(state) => state,
+        // The assignment is unreachable, which is left to the other passes.
+        None => return false,
+    };
+    let (l, r) = match (ranges.operand_range(&state, lhs), ranges.operand_range(&state, rhs)) {
+        (Some(l), Some(r)) => (l, r),
+        _ => return false,
+    };
+    let ty = lhs.ty(body, tcx);
+    let bits = match tcx.layout_of(param_env.and(ty)) {
+        Ok(layout) => layout.size.bits(),
+        Err(_) => return false,
+    };
+    match op {
+        // Shifts overflow if the shift amount is at least the number of bits of the value.
+        BinOp::Shl | BinOp::Shr => r.lo >= 0 && r.hi < bits as i128,
+        _ => match (IntRange::binary_op(op, l, r, bits), IntRange::of_type(tcx, ty)) {
+            (Some(result), Some(full)) => full.contains_range(result),
+            _ => false,
+        },
+    }
+}
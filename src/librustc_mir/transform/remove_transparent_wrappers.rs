@@ -0,0 +1,322 @@
+//! Removes the wrapping of a value in a `#[repr(transparent)]` struct that is only unwrapped
+//! again, which has the layout of the value. A newtype built to be projected:
+//!
+//! ```rust
+//! _2 = Wrapper(move _1);
+//! _3 = move (_2.0: T);
+//! ```
+//!
+//! becomes `_3 = move _1`, and through the projections after the wrapped field, `(_2.0: T).1`
+//! becomes `_1.1`. A newtype rebuilt from the value of another:
+//!
+//! ```rust
+//! _2 = move (_1.0: T);                // `_1: Wrapper`
+//! _3 = Wrapper(move _2);
+//! ```
+//!
+//! becomes `_3 = move _1`. Each of those moves is one copy of the value less in codegen. The
+//! temporary must only be used by the unwrapping, later in the same block, and the wrapped value
+//! must not be used in between. Nested wrappers are removed from the outside in.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::{self, AdtDef, ParamEnv, TyCtxt};
+use rustc_index::vec::IndexVec;
+
+pub struct RemoveTransparentWrappers;
+
+impl<'tcx> MirPass<'tcx> for RemoveTransparentWrappers {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let param_env = tcx.param_env(source.def_id());
+        let mut use_counts = UseCounts { counts: IndexVec::from_elem(0, &body.local_decls) };
+        for (bb, data) in body.basic_blocks().iter_enumerated() {
+            use_counts.visit_basic_block_data(bb, data);
+        }
+
+        for bb in body.basic_blocks().indices() {
+            // Backwards, so that the outer wrapper of nested ones is removed first, leaving the
+            // inner one unwrapped by a projection.
+            for index in (0..body[bb].statements.len()).rev() {
+                let location = Location { block: bb, statement_index: index };
+                let rewrite = find_rewrite(tcx, param_env, body, &use_counts.counts, location);
+                let rewrite = match rewrite {
+                    Some(rewrite) => rewrite,
+                    None => continue,
+                };
+                if !tcx.consider_optimizing(|| {
+                    format!("RemoveTransparentWrappers {:?} {:?}", source.def_id(), location)
+                }) {
+                    return;
+                }
+                mir_trace!("removing the wrapper assigned at {:?}", location);
+                apply_rewrite(body, location, rewrite);
+            }
+        }
+    }
+}
+
+/// The removal of the temporary wrapper or unwrapped value assigned at a location.
+struct Rewrite<'tcx> {
+    temp: Local,
+    /// Where `temp` is unwrapped, or wrapped again.
+    use_location: Location,
+    kind: RewriteKind<'tcx>,
+}
+
+enum RewriteKind<'tcx> {
+    /// The operand unwrapping the wrapper becomes this one.
+    Unwrap(Operand<'tcx>),
+    /// The aggregate wrapping the unwrapped value becomes a use of this operand.
+    Rewrap(Operand<'tcx>),
+}
+
+fn find_rewrite<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+    use_counts: &IndexVec<Local, usize>,
+    location: Location,
+) -> Option<Rewrite<'tcx>> {
+    let bb_data = &body[location.block];
+    let (temp, rvalue) = match &bb_data.statements[location.statement_index].kind {
+        StatementKind::Assign(box (place, rvalue)) => (place.as_local()?, rvalue),
+        _ => return None,
+    };
+    // The assignment and a single use.
+    if body.local_kind(temp) != LocalKind::Temp || use_counts[temp] != 2 {
+        return None;
+    }
+    let (use_location, use_kind) = find_use(body, location, temp)?;
+
+    match (rvalue, use_kind) {
+        (
+            Rvalue::Aggregate(box AggregateKind::Adt(adt_def, _, substs, _, None), operands),
+            UseKind::Operand(use_operand),
+        ) => {
+            let field = wrapped_field(tcx, param_env, adt_def, substs)?;
+            let use_place = use_operand.place()?;
+            match use_place.projection.first() {
+                Some(&ProjectionElem::Field(f, _)) if f.index() == field => {}
+                _ => return None,
+            }
+            let rest = &use_place.projection[1..];
+            let replacement = match &operands[field] {
+                Operand::Constant(constant) if rest.is_empty() => {
+                    Operand::Constant(constant.clone())
+                }
+                Operand::Constant(_) => return None,
+                wrapped => {
+                    let wrapped_place = wrapped.place().unwrap();
+                    if !is_unused_between(body, wrapped_place, location, use_location) {
+                        return None;
+                    }
+                    let mut projection = wrapped_place.projection.to_vec();
+                    projection.extend(rest.iter().cloned());
+                    let place = Place {
+                        local: wrapped_place.local,
+                        projection: tcx.intern_place_elems(&projection),
+                    };
+                    weakest_operand(wrapped, use_operand, place)
+                }
+            };
+            Some(Rewrite { temp, use_location, kind: RewriteKind::Unwrap(replacement) })
+        }
+        (Rvalue::Use(unwrapped), UseKind::Aggregate(use_operand, adt_def, substs, field)) => {
+            if wrapped_field(tcx, param_env, adt_def, substs) != Some(field) {
+                return None;
+            }
+            let unwrapped_place = unwrapped.place()?;
+            let (last, base) = unwrapped_place.projection.split_last()?;
+            match *last {
+                ProjectionElem::Field(f, _) if f.index() == field => {}
+                _ => return None,
+            }
+            let base_ty = Place::ty_from(unwrapped_place.local, base, body, tcx).ty;
+            match base_ty.kind {
+                ty::Adt(base_def, base_substs) if base_def == adt_def && base_substs == substs => {}
+                _ => return None,
+            }
+            if !is_unused_between(body, unwrapped_place, location, use_location) {
+                return None;
+            }
+            let place =
+                Place { local: unwrapped_place.local, projection: tcx.intern_place_elems(base) };
+            let replacement = weakest_operand(unwrapped, use_operand, place);
+            Some(Rewrite { temp, use_location, kind: RewriteKind::Rewrap(replacement) })
+        }
+        _ => None,
+    }
+}
+
+/// Returns the index of the field of the `#[repr(transparent)]` struct `adt_def` holding the
+/// wrapped value, the only one that may not be zero-sized, if the other fields need no drop.
+fn wrapped_field<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    adt_def: &'tcx AdtDef,
+    substs: ty::subst::SubstsRef<'tcx>,
+) -> Option<usize> {
+    if !adt_def.repr.transparent() || !adt_def.is_struct() {
+        return None;
+    }
+    let field_tys: Vec<_> =
+        adt_def.non_enum_variant().fields.iter().map(|field| field.ty(tcx, substs)).collect();
+    let mut non_zst_fields = field_tys.iter().enumerate().filter(|(_, &ty)| {
+        match tcx.layout_of(param_env.and(ty)) {
+            Ok(layout) => !layout.is_zst(),
+            Err(_) => true,
+        }
+    });
+    let wrapped = match (non_zst_fields.next(), non_zst_fields.next()) {
+        (Some((index, _)), None) => index,
+        _ => return None,
+    };
+    // The other fields are dropped with the wrapper, or not at all once it is removed.
+    let others_need_drop = field_tys
+        .iter()
+        .enumerate()
+        .any(|(index, ty)| index != wrapped && ty.needs_drop(tcx, param_env));
+    if others_need_drop { None } else { Some(wrapped) }
+}
+
+/// The single use of a temporary that can be rewritten.
+enum UseKind<'tcx, 'a> {
+    /// An operand reading the temporary.
+    Operand(&'a Operand<'tcx>),
+    /// An aggregate of a `#[repr(transparent)]` struct, whose wrapped field is the temporary.
+    Aggregate(&'a Operand<'tcx>, &'tcx AdtDef, ty::subst::SubstsRef<'tcx>, usize),
+}
+
+/// Finds the use of `temp` after `location` in its block, as an operand of a statement or of the
+/// terminator.
+fn find_use<'tcx, 'a>(
+    body: &'a Body<'tcx>,
+    location: Location,
+    temp: Local,
+) -> Option<(Location, UseKind<'tcx, 'a>)> {
+    let bb_data = &body[location.block];
+    for statement_index in location.statement_index + 1..=bb_data.statements.len() {
+        let use_location = Location { block: location.block, statement_index };
+        let mut finder = LocalFinder { local: temp, found: false };
+        match bb_data.statements.get(statement_index) {
+            Some(statement) => finder.visit_statement(statement, use_location),
+            None => finder.visit_terminator(bb_data.terminator(), use_location),
+        }
+        if !finder.found {
+            continue;
+        }
+        let statement = bb_data.statements.get(statement_index)?;
+        let rvalue = match &statement.kind {
+            StatementKind::Assign(box (dest, rvalue)) if dest.local != temp => rvalue,
+            _ => return None,
+        };
+        let use_kind = match rvalue {
+            Rvalue::Use(operand) => UseKind::Operand(operand),
+            Rvalue::Aggregate(box AggregateKind::Adt(adt_def, _, substs, _, None), operands) => {
+                let wrapped = operands
+                    .iter()
+                    .position(|operand| operand.place().map(|place| place.local) == Some(temp))?;
+                if operands[wrapped].place()?.projection.is_empty() {
+                    UseKind::Aggregate(&operands[wrapped], adt_def, substs, wrapped)
+                } else {
+                    UseKind::Operand(&operands[wrapped])
+                }
+            }
+            _ => return None,
+        };
+        return Some((use_location, use_kind));
+    }
+    None
+}
+
+/// Returns whether the local of `place`, which must not be behind a pointer, is not used from
+/// the statement after `start` up to `end`, excluded.
+fn is_unused_between(body: &Body<'_>, place: &Place<'_>, start: Location, end: Location) -> bool {
+    if place.projection.iter().any(|elem| elem == &ProjectionElem::Deref) {
+        return false;
+    }
+    let statements = &body[start.block].statements;
+    let mut finder = LocalFinder { local: place.local, found: false };
+    for statement_index in start.statement_index + 1..end.statement_index {
+        let location = Location { block: start.block, statement_index };
+        finder.visit_statement(&statements[statement_index], location);
+    }
+    !finder.found
+}
+
+/// Returns an operand reading `place` that moves it if both `wrapped` and `use_operand` do.
+fn weakest_operand<'tcx>(
+    wrapped: &Operand<'tcx>,
+    use_operand: &Operand<'tcx>,
+    place: Place<'tcx>,
+) -> Operand<'tcx> {
+    match (wrapped, use_operand) {
+        (Operand::Move(_), Operand::Move(_)) => Operand::Move(place),
+        _ => Operand::Copy(place),
+    }
+}
+
+fn apply_rewrite<'tcx>(body: &mut BodyAndCache<'tcx>, location: Location, rewrite: Rewrite<'tcx>) {
+    let Rewrite { temp, use_location, kind } = rewrite;
+    let bb_data = &mut body[location.block];
+    bb_data.statements[location.statement_index].make_nop();
+    let rvalue = match &mut bb_data.statements[use_location.statement_index].kind {
+        StatementKind::Assign(box (_, rvalue)) => rvalue,
+        _ => bug!("unexpected use of {:?} at {:?}", temp, use_location),
+    };
+    match kind {
+        RewriteKind::Rewrap(replacement) => *rvalue = Rvalue::Use(replacement),
+        RewriteKind::Unwrap(replacement) => {
+            let operand = match rvalue {
+                Rvalue::Use(operand) => operand,
+                Rvalue::Aggregate(_, operands) => operands
+                    .iter_mut()
+                    .find(|operand| operand.place().map(|place| place.local) == Some(temp))
+                    .unwrap(),
+                _ => bug!("unexpected use of {:?} at {:?}", temp, use_location),
+            };
+            *operand = replacement;
+        }
+    }
+    for statement in &mut bb_data.statements {
+        match statement.kind {
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local)
+                if local == temp =>
+            {
+                statement.make_nop();
+            }
+            _ => {}
+        }
+    }
+}
+
+struct UseCounts {
+    counts: IndexVec<Local, usize>,
+}
+
+impl Visitor<'_> for UseCounts {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if !context.is_storage_marker() {
+            self.counts[local] += 1;
+        }
+    }
+}
+
+struct LocalFinder {
+    local: Local,
+    found: bool,
+}
+
+impl Visitor<'_> for LocalFinder {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if local == self.local && !context.is_storage_marker() {
+            self.found = true;
+        }
+    }
+}
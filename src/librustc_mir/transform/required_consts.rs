@@ -0,0 +1,50 @@
+//! Collects the constants a body requires to evaluate successfully.
+//!
+//! Uses of constants can be optimized away, e.g. `let _ = T::CONST;` leaves nothing behind in
+//! optimized MIR. We still want an erroneous `T::CONST` to be reported for every instantiation
+//! that gets codegened, so we record all unevaluated constants before optimizing and let the
+//! monomorphization collector evaluate them.
+
+use rustc::mir::visit::Visitor;
+use rustc::mir::{traversal, Body, Constant, Location, RequiredConst};
+use rustc::ty::{self, TyCtxt};
+use rustc_hir::def_id::DefId;
+
+pub struct RequiredConstsVisitor<'a, 'tcx> {
+    required_consts: &'a mut Vec<RequiredConst<'tcx>>,
+}
+
+impl<'a, 'tcx> RequiredConstsVisitor<'a, 'tcx> {
+    pub fn new(required_consts: &'a mut Vec<RequiredConst<'tcx>>) -> Self {
+        RequiredConstsVisitor { required_consts }
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for RequiredConstsVisitor<'a, 'tcx> {
+    fn visit_constant(&mut self, constant: &Constant<'tcx>, _: Location) {
+        match constant.literal.val {
+            // Promoteds may fail to evaluate without that being an error (the failure is
+            // reported as a lint when the promoted is actually reached), so skip them.
+            ty::ConstKind::Unevaluated(_, _, None) => {
+                self.required_consts
+                    .push(RequiredConst { span: constant.span, literal: constant.literal });
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(super) fn mir_required_consts<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+) -> &'tcx [RequiredConst<'tcx>] {
+    let (body, _) = tcx.mir_validated(def_id);
+    let body: &Body<'tcx> = &body.borrow();
+
+    let mut required_consts = Vec::new();
+    let mut required_consts_visitor = RequiredConstsVisitor::new(&mut required_consts);
+    for (bb, bb_data) in traversal::reverse_postorder(body) {
+        required_consts_visitor.visit_basic_block_data(bb, bb_data);
+    }
+    tcx.arena.alloc_from_iter(required_consts)
+}
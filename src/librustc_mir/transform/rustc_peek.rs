@@ -4,7 +4,10 @@ use rustc_span::Span;
 use rustc_target::spec::abi::Abi;
 
 use crate::transform::{MirPass, MirSource};
-use rustc::mir::{self, Body, BodyAndCache, Local, Location};
+use crate::util::int_ranges::IntRanges;
+use crate::util::liveness::liveness_of_locals;
+use crate::util::ssa::{SsaDef, SsaRenaming};
+use rustc::mir::{self, Body, BodyAndCache, Local, Location, ReadOnlyBodyAndCache};
 use rustc::ty::{self, Ty, TyCtxt};
 use rustc_hir::def_id::DefId;
 use rustc_index::bit_set::BitSet;
@@ -12,8 +15,9 @@ use rustc_index::bit_set::BitSet;
 use crate::dataflow::generic::{Analysis, Results, ResultsCursor};
 use crate::dataflow::move_paths::{HasMoveData, MoveData};
 use crate::dataflow::move_paths::{LookupResult, MovePathIndex};
-use crate::dataflow::MaybeMutBorrowedLocals;
+use crate::dataflow::{MaybeBorrowedLocals, MaybeMutBorrowedLocals};
 use crate::dataflow::MoveDataParamEnv;
+use crate::dataflow::{tainted_sink_calls, MaybeTaintedLocals, TaintSummaries};
 use crate::dataflow::{
     DefinitelyInitializedPlaces, MaybeInitializedPlaces, MaybeUninitializedPlaces,
 };
@@ -61,6 +65,32 @@ impl<'tcx> MirPass<'tcx> for SanityCheck {
         if has_rustc_mir_with(&attributes, sym::rustc_peek_indirectly_mutable).is_some() {
             sanity_check_via_rustc_peek(tcx, body, def_id, &attributes, &flow_mut_borrowed);
         }
+        if has_rustc_mir_with(&attributes, sym::rustc_peek_escaped).is_some() {
+            let flow_borrowed = MaybeBorrowedLocals::all_borrows()
+                .into_engine(tcx, body, def_id)
+                .iterate_to_fixpoint();
+            sanity_check_via_rustc_peek(tcx, body, def_id, &attributes, &flow_borrowed);
+        }
+        if has_rustc_mir_with(&attributes, sym::rustc_peek_liveness).is_some() {
+            sanity_check_liveness_via_rustc_peek(tcx, read_only!(body));
+        }
+        if has_rustc_mir_with(&attributes, sym::rustc_peek_range).is_some() {
+            sanity_check_ranges_via_rustc_peek(tcx, body, param_env);
+        }
+        if has_rustc_mir_with(&attributes, sym::rustc_peek_ssa).is_some() {
+            sanity_check_ssa_via_rustc_peek(tcx, read_only!(body));
+        }
+        if has_rustc_mir_with(&attributes, sym::rustc_peek_tainted).is_some() {
+            let summaries = TaintSummaries::new(tcx, def_id);
+            let flow_tainted = MaybeTaintedLocals::new(tcx, body, &summaries)
+                .into_engine(tcx, body, def_id)
+                .iterate_to_fixpoint();
+            sanity_check_via_rustc_peek(tcx, body, def_id, &attributes, &flow_tainted);
+            for call in tainted_sink_calls(tcx, body, def_id) {
+                let msg = "rustc_peek: tainted argument passed to sink";
+                tcx.sess.span_err(call.source_info.span, msg);
+            }
+        }
         if has_rustc_mir_with(&attributes, sym::stop_after_dataflow).is_some() {
             tcx.sess.fatal("stop_after_dataflow ended compilation");
         }
@@ -95,11 +125,97 @@ pub fn sanity_check_via_rustc_peek<'tcx, A>(
     debug!("sanity_check_via_rustc_peek def_id: {:?}", def_id);
 
     let mut cursor = ResultsCursor::new(body, results);
+    for (location, place, call) in peeked_places(tcx, body) {
+        cursor.seek_before(location);
+        let state = cursor.get();
+        results.analysis.peek_at(tcx, place, state, call);
+    }
+}
+
+/// Checks the calls to `rustc_peek` against the liveness of locals: the bit is set if the peeked
+/// local is live when the call returns, i.e. if its current value may still be read.
+fn sanity_check_liveness_via_rustc_peek<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: ReadOnlyBodyAndCache<'_, 'tcx>,
+) {
+    let liveness = liveness_of_locals(body);
+    for (location, place, call) in peeked_places(tcx, &body) {
+        let local = match place.as_local() {
+            Some(local) => local,
+            None => {
+                tcx.sess.span_err(call.span, "rustc_peek: argument was not a local");
+                continue;
+            }
+        };
+        // The call to `rustc_peek` ends the block.
+        if !liveness.outs[location.block].contains(local) {
+            tcx.sess.span_err(call.span, "rustc_peek: bit not set");
+        }
+    }
+}
+
+/// Reports the range computed by `IntRanges` for the place passed to each call to `rustc_peek`.
+/// Unlike the other checks, this emits an error for every call, which shows the range.
+fn sanity_check_ranges_via_rustc_peek<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+) {
+    let ranges = IntRanges::new(tcx, body, param_env);
+    for (location, place, call) in peeked_places(tcx, body) {
+        let msg = match ranges.state_before(location) {
+            Some(state) => match ranges.place_range(&state, place) {
+                Some(range) => format!("rustc_peek: range {}", range),
+                None => "rustc_peek: argument untracked".to_string(),
+            },
+            None => "rustc_peek: unreachable".to_string(),
+        };
+        tcx.sess.span_err(call.span, &msg);
+    }
+}
+
+/// Reports where the value of the local passed to each call to `rustc_peek` is defined in the SSA
+/// renaming of the locals. Like the ranges, this emits an error for every call.
+fn sanity_check_ssa_via_rustc_peek<'tcx>(tcx: TyCtxt<'tcx>, body: ReadOnlyBodyAndCache<'_, 'tcx>) {
+    let ssa = SsaRenaming::new(body);
+    for (location, place, call) in peeked_places(tcx, &body) {
+        let local = match place.as_local() {
+            Some(local) => local,
+            None => {
+                tcx.sess.span_err(call.span, "rustc_peek: argument was not a local");
+                continue;
+            }
+        };
+        let msg = if !ssa.is_renamed(local) {
+            "rustc_peek: not renamed".to_string()
+        } else {
+            match ssa.value_used_at(location, local).map(|value| ssa.value_data(value).def) {
+                Some(SsaDef::Arg) => "rustc_peek: argument".to_string(),
+                Some(SsaDef::Location(_)) => "rustc_peek: assigned".to_string(),
+                Some(SsaDef::Phi(block)) => {
+                    let phi = ssa.phis(block).iter().find(|phi| phi.local == local).unwrap();
+                    let values = phi.operands.iter().filter(|(_, value)| value.is_some()).count();
+                    format!("rustc_peek: phi of {} values", values)
+                }
+                None => "rustc_peek: no value".to_string(),
+            }
+        };
+        tcx.sess.span_err(call.span, &msg);
+    }
+}
 
+/// Returns the places passed to the calls to `rustc_peek` in `body`, with the location of the
+/// assignment to the temporary holding them, at which the dataflow state is observed. Reports the
+/// calls whose argument isn't of the expected form.
+fn peeked_places<'a, 'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &'a Body<'tcx>,
+) -> Vec<(Location, &'a mir::Place<'tcx>, PeekCall)> {
     let peek_calls = body.basic_blocks().iter_enumerated().filter_map(|(bb, block_data)| {
         PeekCall::from_terminator(tcx, block_data.terminator()).map(|call| (bb, block_data, call))
     });
 
+    let mut places = vec![];
     for (bb, block_data, call) in peek_calls {
         // Look for a sequence like the following to indicate that we should be peeking at `_1`:
         //    _2 = &_1;
@@ -124,10 +240,7 @@ pub fn sanity_check_via_rustc_peek<'tcx, A>(
             (PeekCallKind::ByRef, mir::Rvalue::Ref(_, _, place))
             | (PeekCallKind::ByVal, mir::Rvalue::Use(mir::Operand::Move(place)))
             | (PeekCallKind::ByVal, mir::Rvalue::Use(mir::Operand::Copy(place))) => {
-                let loc = Location { block: bb, statement_index };
-                cursor.seek_before(loc);
-                let state = cursor.get();
-                results.analysis.peek_at(tcx, place, state, call);
+                places.push((Location { block: bb, statement_index }, place, call));
             }
 
             _ => {
@@ -137,6 +250,7 @@ pub fn sanity_check_via_rustc_peek<'tcx, A>(
             }
         }
     }
+    places
 }
 
 /// If `stmt` is an assignment where the LHS is the given local (with no projections), returns the
@@ -264,7 +378,10 @@ where
     }
 }
 
-impl<'tcx> RustcPeekAt<'tcx> for MaybeMutBorrowedLocals<'_, 'tcx> {
+impl<'tcx, K> RustcPeekAt<'tcx> for MaybeBorrowedLocals<K>
+where
+    MaybeBorrowedLocals<K>: Analysis<'tcx, Idx = Local>,
+{
     fn peek_at(
         &self,
         tcx: TyCtxt<'tcx>,
@@ -285,3 +402,24 @@ impl<'tcx> RustcPeekAt<'tcx> for MaybeMutBorrowedLocals<'_, 'tcx> {
         }
     }
 }
+
+impl<'tcx> RustcPeekAt<'tcx> for MaybeTaintedLocals<'_, '_, 'tcx> {
+    fn peek_at(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        place: &mir::Place<'tcx>,
+        flow_state: &BitSet<Local>,
+        call: PeekCall,
+    ) {
+        let local = if let Some(l) = place.as_local() {
+            l
+        } else {
+            tcx.sess.span_err(call.span, "rustc_peek: argument was not a local");
+            return;
+        };
+
+        if !flow_state.contains(local) {
+            tcx.sess.span_err(call.span, "rustc_peek: bit not set");
+        }
+    }
+}
@@ -28,12 +28,19 @@
 //! return.
 
 use crate::transform::{MirPass, MirSource};
-use rustc::mir::visit::{MutVisitor, MutatingUseContext, PlaceContext, Visitor};
+use crate::util::def_use::DefUseAnalysis;
+use crate::util::pass_trace;
+use crate::util::side_effects::rvalue_is_removable;
+use rustc::mir::visit::{MutVisitor, PlaceContext};
 use rustc::mir::*;
-use rustc::ty::{self, TyCtxt};
+use rustc::ty::TyCtxt;
 use rustc_index::bit_set::BitSet;
 use rustc_index::vec::{Idx, IndexVec};
 use std::borrow::Cow;
+use std::iter;
+
+#[cfg(test)]
+mod tests;
 
 pub struct SimplifyCfg {
     label: String,
@@ -105,6 +112,7 @@ impl<'a, 'tcx> CfgSimplifier<'a, 'tcx> {
                     continue;
                 }
 
+                let _block = pass_trace::enter_block(bb);
                 debug!("simplifying {:?}", bb);
 
                 let mut terminator =
@@ -115,11 +123,17 @@ impl<'a, 'tcx> CfgSimplifier<'a, 'tcx> {
                 }
 
                 let mut new_stmts = vec![];
+                let mut threaded = vec![];
                 let mut inner_changed = true;
                 while inner_changed {
                     inner_changed = false;
                     inner_changed |= self.simplify_branch(&mut terminator);
                     inner_changed |= self.merge_successor(&mut new_stmts, &mut terminator);
+                    inner_changed |= self.thread_storage_markers(
+                        &mut new_stmts,
+                        &mut threaded,
+                        &mut terminator,
+                    );
                     changed |= inner_changed;
                 }
 
@@ -180,7 +194,7 @@ impl<'a, 'tcx> CfgSimplifier<'a, 'tcx> {
         };
         self.basic_blocks[*start].terminator = terminator;
 
-        debug!("collapsing goto chain from {:?} to {:?}", *start, target);
+        mir_trace!("collapsing goto chain from {:?} to {:?}", *start, target);
 
         *changed |= *start != target;
 
@@ -207,7 +221,7 @@ impl<'a, 'tcx> CfgSimplifier<'a, 'tcx> {
             _ => return false,
         };
 
-        debug!("merging block {:?} into {:?}", target, terminator);
+        mir_trace!("merging block {:?} into {:?}", target, terminator);
         *terminator = match self.basic_blocks[target].terminator.take() {
             Some(terminator) => terminator,
             None => {
@@ -222,6 +236,55 @@ impl<'a, 'tcx> CfgSimplifier<'a, 'tcx> {
         true
     }
 
+    // thread a `goto` through a block with several predecessors that only contains storage
+    // markers, by copying the markers into the current block. Once all predecessors have been
+    // threaded, the block is dead. `threaded` contains the blocks already threaded through for
+    // the current block, so that we don't loop forever on cycles of such blocks.
+    fn thread_storage_markers(
+        &mut self,
+        new_stmts: &mut Vec<Statement<'tcx>>,
+        threaded: &mut Vec<BasicBlock>,
+        terminator: &mut Terminator<'tcx>,
+    ) -> bool {
+        let target = match terminator.kind {
+            TerminatorKind::Goto { target } if self.pred_count[target] > 1 => target,
+            _ => return false,
+        };
+        if threaded.contains(&target) {
+            return false;
+        }
+
+        let (statements, next) = match self.basic_blocks[target] {
+            BasicBlockData {
+                ref statements,
+                terminator: Some(Terminator { kind: TerminatorKind::Goto { target: next }, .. }),
+                ..
+            } if next != target
+                && statements.iter().all(|stmt| match stmt.kind {
+                    StatementKind::StorageLive(_) | StatementKind::StorageDead(_) => true,
+                    _ => false,
+                }) =>
+            {
+                (statements, next)
+            }
+            _ => return false,
+        };
+
+        mir_trace!(
+            "threading {:?} through storage markers of {:?} to {:?}",
+            terminator,
+            target,
+            next
+        );
+        new_stmts.extend(statements.iter().cloned());
+        terminator.kind = TerminatorKind::Goto { target: next };
+        self.pred_count[target] -= 1;
+        self.pred_count[next] += 1;
+        threaded.push(target);
+
+        true
+    }
+
     // turn a branch with all successors identical to a goto
     fn simplify_branch(&mut self, terminator: &mut Terminator<'tcx>) -> bool {
         match terminator.kind {
@@ -243,7 +306,7 @@ impl<'a, 'tcx> CfgSimplifier<'a, 'tcx> {
             }
         };
 
-        debug!("simplifying branch {:?}", terminator);
+        mir_trace!("simplifying branch {:?}", terminator);
         terminator.kind = TerminatorKind::Goto { target: first_succ };
         true
     }
@@ -287,20 +350,17 @@ pub fn remove_dead_blocks(body: &mut BodyAndCache<'_>) {
 
 pub struct SimplifyLocals;
 
+/// Bodies with more statements than this are only stripped of the locals that nothing but their
+/// own assignments use, without following the chains of unused locals.
+const MAX_CHAIN_STATEMENTS: usize = 10_000;
+
 impl<'tcx> MirPass<'tcx> for SimplifyLocals {
     fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
         trace!("running SimplifyLocals on {:?}", source);
         let locals = {
-            let read_only_cache = read_only!(body);
-            let mut marker = DeclMarker { locals: BitSet::new_empty(body.local_decls.len()), body };
-            marker.visit_body(read_only_cache);
-            // Return pointer and arguments are always live
-            marker.locals.insert(RETURN_PLACE);
-            for arg in body.args_iter() {
-                marker.locals.insert(arg);
-            }
-
-            marker.locals
+            let statement_count: usize =
+                body.basic_blocks().iter().map(|block| block.statements.len()).sum();
+            used_locals(read_only!(body), statement_count <= MAX_CHAIN_STATEMENTS)
         };
 
         let map = make_local_map(&mut body.local_decls, locals);
@@ -310,6 +370,62 @@ impl<'tcx> MirPass<'tcx> for SimplifyLocals {
     }
 }
 
+/// Finds the locals that are used. The return pointer, the arguments and the locals with debug
+/// info are always used.
+///
+/// Storage markers are not uses, and neither are the assignments of values computed without
+/// side effects to a local, since `ConstProp` and `CopyProp` can remove the other uses of many
+/// of these locals. With `follow_chains`, the locals read or borrowed by such an assignment are
+/// only used if the assigned local is, so that a local whose only borrow is never used is
+/// removed along with the borrow.
+fn used_locals(body: ReadOnlyBodyAndCache<'_, '_>, follow_chains: bool) -> BitSet<Local> {
+    let mut def_use_analysis = DefUseAnalysis::new(&body);
+    def_use_analysis.analyze(body);
+
+    // The locals whose value or address escapes into each local through its assignments.
+    let mut escapes_into: IndexVec<Local, Vec<Local>> =
+        IndexVec::from_elem(vec![], &body.local_decls);
+    let mut worklist: Vec<_> = iter::once(RETURN_PLACE).chain(body.args_iter()).collect();
+    worklist.extend(
+        body.var_debug_info.iter().filter_map(|info| info.value.place()).map(|place| place.local),
+    );
+    for local in body.local_decls.indices() {
+        for place_use in &def_use_analysis.local_info(local).defs_and_uses {
+            if place_use.context.is_storage_marker() {
+                continue;
+            }
+            match removable_assignment(&body, place_use.location) {
+                Some(assigned) if assigned == local => {}
+                Some(assigned) if follow_chains => escapes_into[assigned].push(local),
+                _ => worklist.push(local),
+            }
+        }
+    }
+
+    let mut used = BitSet::new_empty(body.local_decls.len());
+    while let Some(local) = worklist.pop() {
+        if used.insert(local) {
+            worklist.append(&mut escapes_into[local]);
+        }
+    }
+    used
+}
+
+/// Returns the local assigned by the statement at `location`, if the statement can be removed
+/// when that local is unused.
+fn removable_assignment(body: &Body<'_>, location: Location) -> Option<Local> {
+    let statement = body.basic_blocks()[location.block].statements.get(location.statement_index)?;
+    match &statement.kind {
+        StatementKind::Assign(box (place, rvalue))
+            if !place.is_indirect() && rvalue_is_removable(rvalue) =>
+        {
+            trace!("skipping store of {:?} to {:?}", rvalue, place);
+            Some(place.local)
+        }
+        _ => None,
+    }
+}
+
 /// Construct the mapping while swapping out unused stuff out from the `vec`.
 fn make_local_map<V>(
     vec: &mut IndexVec<Local, V>,
@@ -328,52 +444,6 @@ fn make_local_map<V>(
     map
 }
 
-struct DeclMarker<'a, 'tcx> {
-    pub locals: BitSet<Local>,
-    pub body: &'a Body<'tcx>,
-}
-
-impl<'a, 'tcx> Visitor<'tcx> for DeclMarker<'a, 'tcx> {
-    fn visit_local(&mut self, local: &Local, ctx: PlaceContext, location: Location) {
-        // Ignore storage markers altogether, they get removed along with their otherwise unused
-        // decls.
-        // FIXME: Extend this to all non-uses.
-        if ctx.is_storage_marker() {
-            return;
-        }
-
-        // Ignore stores of constants because `ConstProp` and `CopyProp` can remove uses of many
-        // of these locals. However, if the local is still needed, then it will be referenced in
-        // another place and we'll mark it as being used there.
-        if ctx == PlaceContext::MutatingUse(MutatingUseContext::Store)
-            || ctx == PlaceContext::MutatingUse(MutatingUseContext::Projection)
-        {
-            let block = &self.body.basic_blocks()[location.block];
-            if location.statement_index != block.statements.len() {
-                let stmt = &block.statements[location.statement_index];
-
-                if let StatementKind::Assign(box (p, Rvalue::Use(Operand::Constant(c)))) =
-                    &stmt.kind
-                {
-                    match c.literal.val {
-                        // Keep assignments from unevaluated constants around, since the evaluation
-                        // may report errors, even if the use of the constant is dead code.
-                        ty::ConstKind::Unevaluated(..) => {}
-                        _ => {
-                            if !p.is_indirect() {
-                                trace!("skipping store of const value {:?} to {:?}", c, p);
-                                return;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        self.locals.insert(*local);
-    }
-}
-
 struct LocalUpdater<'tcx> {
     map: IndexVec<Local, Option<Local>>,
     tcx: TyCtxt<'tcx>,
@@ -0,0 +1,104 @@
+use rustc::mir::*;
+use rustc_index::vec::{Idx, IndexVec};
+use rustc_span::DUMMY_SP;
+
+use super::*;
+
+fn source_info() -> SourceInfo {
+    SourceInfo { scope: OUTERMOST_SOURCE_SCOPE, span: DUMMY_SP }
+}
+
+fn statement(kind: StatementKind<'static>) -> Statement<'static> {
+    Statement { source_info: source_info(), kind }
+}
+
+/// `_1 = _2`, which is neither a storage marker nor removed by `SimplifyCfg`.
+fn assign() -> Statement<'static> {
+    let rvalue = Rvalue::Use(Operand::Copy(Place::from(Local::new(2))));
+    statement(StatementKind::Assign(box (Place::from(Local::new(1)), rvalue)))
+}
+
+fn block(
+    statements: Vec<Statement<'static>>,
+    kind: TerminatorKind<'static>,
+) -> BasicBlockData<'static> {
+    BasicBlockData {
+        statements,
+        terminator: Some(Terminator { source_info: source_info(), kind }),
+        is_cleanup: false,
+    }
+}
+
+fn goto(target: usize) -> TerminatorKind<'static> {
+    TerminatorKind::Goto { target: BasicBlock::new(target) }
+}
+
+/// A branch to `bb1` and `bb2`, which can't be simplified.
+fn branch() -> TerminatorKind<'static> {
+    TerminatorKind::FalseEdges {
+        real_target: BasicBlock::new(1),
+        imaginary_target: BasicBlock::new(2),
+    }
+}
+
+fn simplify(blocks: IndexVec<BasicBlock, BasicBlockData<'static>>) -> BodyAndCache<'static> {
+    let mut body = BodyAndCache::new(Body::new_cfg_only(blocks));
+    simplify_cfg(&mut body);
+    body
+}
+
+#[test]
+fn threads_gotos_through_storage_markers() {
+    let storage_dead = statement(StatementKind::StorageDead(Local::new(1)));
+    let mut blocks = IndexVec::new();
+    blocks.push(block(vec![], branch()));
+    blocks.push(block(vec![assign()], goto(3)));
+    blocks.push(block(vec![assign()], goto(3)));
+    blocks.push(block(vec![storage_dead.clone()], goto(4)));
+    blocks.push(block(vec![], TerminatorKind::Return));
+
+    let body = simplify(blocks);
+    // The marker is copied into both predecessors, and its block removed.
+    assert_eq!(body.basic_blocks().len(), 4);
+    for bb in 1..=2 {
+        let bb_data = &body[BasicBlock::new(bb)];
+        let kinds: Vec<_> = bb_data.statements.iter().map(|s| s.kind.clone()).collect();
+        assert_eq!(kinds, vec![assign().kind, storage_dead.kind.clone()]);
+        assert!(bb_data.terminator().kind == goto(3));
+    }
+    assert!(body[BasicBlock::new(3)].terminator().kind == TerminatorKind::Return);
+}
+
+#[test]
+fn keeps_storage_markers_of_other_predecessors() {
+    // `bb3` is also the real target of the branch, so it stays for it.
+    let storage_dead = statement(StatementKind::StorageDead(Local::new(1)));
+    let mut blocks = IndexVec::new();
+    blocks.push(block(
+        vec![],
+        TerminatorKind::FalseEdges {
+            real_target: BasicBlock::new(3),
+            imaginary_target: BasicBlock::new(1),
+        },
+    ));
+    blocks.push(block(vec![assign()], goto(3)));
+    blocks.push(block(vec![], TerminatorKind::Return));
+    blocks.push(block(vec![storage_dead.clone()], goto(2)));
+
+    let body = simplify(blocks);
+    assert_eq!(body.basic_blocks().len(), 4);
+    assert_eq!(body[BasicBlock::new(1)].statements.len(), 2);
+    assert!(body[BasicBlock::new(1)].terminator().kind == goto(2));
+    assert_eq!(body[BasicBlock::new(3)].statements.len(), 1);
+}
+
+#[test]
+fn terminates_on_cycles_of_storage_markers() {
+    let mut blocks = IndexVec::new();
+    blocks.push(block(vec![], branch()));
+    blocks.push(block(vec![statement(StatementKind::StorageLive(Local::new(1)))], goto(2)));
+    blocks.push(block(vec![statement(StatementKind::StorageDead(Local::new(1)))], goto(1)));
+
+    let body = simplify(blocks);
+    assert_eq!(body.basic_blocks().len(), 3);
+}
@@ -0,0 +1,270 @@
+//! Removes redundant casts.
+//!
+//! Casts to the type their operand already has are replaced by a plain use of the operand.
+//!
+//! Chains of integer casts through single-use temporaries, as generated by macros and derives,
+//! e.g.
+//!
+//! ```rust
+//! _1 = _0 as u32 (Misc);   // _0: u8
+//! _2 = move _1 as usize (Misc);
+//! _3 = move _2 as u32 (Misc);
+//! ```
+//!
+//! are collapsed into a single cast whenever that cast computes the same value:
+//!
+//! ```rust
+//! _1 = _0 as u32 (Misc);
+//! _2 = move _1;
+//! _3 = move _2;
+//! ```
+//!
+//! The intermediate temporaries are retyped accordingly, and are cleaned up by later passes.
+//! This also lets `ConstProp` see through the casts.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::def_use::DefUseAnalysis;
+use rustc::mir::visit::{MutVisitor, Visitor};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::layout::Size;
+use rustc::ty::{ParamEnv, Ty, TyCtxt};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+
+pub struct SimplifyCasts;
+
+impl<'tcx> MirPass<'tcx> for SimplifyCasts {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let (rvalues, local_tys) = {
+            let read_only_cache = read_only!(body);
+            let mut def_use_analysis = DefUseAnalysis::new(body);
+            def_use_analysis.analyze(read_only_cache);
+            let mut finder = CastFinder {
+                tcx,
+                param_env: tcx.param_env(source.def_id()),
+                body,
+                def_use_analysis: &def_use_analysis,
+                rvalues: FxHashMap::default(),
+                local_tys: FxIndexMap::default(),
+            };
+            finder.visit_body(read_only_cache);
+            (finder.rvalues, finder.local_tys)
+        };
+        if rvalues.is_empty() {
+            return;
+        }
+
+        for (local, ty) in local_tys {
+            body.local_decls[local].ty = ty;
+        }
+        MutVisitor::visit_body(&mut CastReplacer { tcx, rvalues }, body);
+    }
+}
+
+struct CastFinder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    def_use_analysis: &'a DefUseAnalysis,
+    rvalues: FxHashMap<Location, Rvalue<'tcx>>,
+    local_tys: FxIndexMap<Local, Ty<'tcx>>,
+}
+
+/// One cast of a chain of integer casts.
+struct ChainLink<'a, 'tcx> {
+    /// The location of the cast.
+    location: Location,
+    operand: &'a Operand<'tcx>,
+    /// The temporary the cast is assigned to. `None` for the outermost cast.
+    temp: Option<Local>,
+}
+
+impl CastFinder<'a, 'tcx> {
+    /// Returns the size of `ty` and whether it is signed, if it is an integer.
+    fn int_layout(&self, ty: Ty<'tcx>) -> Option<(Size, bool)> {
+        if !ty.is_integral() {
+            return None;
+        }
+        let layout = self.tcx.layout_of(self.param_env.and(ty)).ok()?;
+        Some((layout.size, ty.is_signed()))
+    }
+
+    /// If `operand` moves a single-use temporary whose only definition is an integer cast,
+    /// returns that cast.
+    fn cast_def(&self, operand: &Operand<'tcx>) -> Option<ChainLink<'a, 'tcx>> {
+        let temp = match operand {
+            Operand::Move(place) => place.as_local()?,
+            _ => return None,
+        };
+        if self.body.local_kind(temp) != LocalKind::Temp {
+            return None;
+        }
+
+        let info = self.def_use_analysis.local_info(temp);
+        if info.def_count() != 1 || info.use_count() != 1 {
+            return None;
+        }
+        let location = info.defs_not_including_drop().next()?.location;
+        if self.rvalues.contains_key(&location) {
+            return None;
+        }
+        let statement = self.body[location.block].statements.get(location.statement_index)?;
+        match &statement.kind {
+            StatementKind::Assign(box (place, Rvalue::Cast(CastKind::Misc, operand, _)))
+                if place.as_local() == Some(temp) =>
+            {
+                Some(ChainLink { location, operand, temp: Some(temp) })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if `place` is a temporary that is only used as the operand of an integer
+    /// cast, i.e., if it is not the outermost cast of a chain.
+    fn feeds_int_cast(&self, place: &Place<'tcx>) -> bool {
+        let temp = match place.as_local() {
+            Some(temp) if self.body.local_kind(temp) == LocalKind::Temp => temp,
+            _ => return false,
+        };
+        let info = self.def_use_analysis.local_info(temp);
+        if info.def_count() != 1 || info.use_count() != 1 {
+            return false;
+        }
+        let use_location =
+            match info.defs_and_uses.iter().find(|u| u.context.is_nonmutating_use()) {
+                Some(place_use) => place_use.location,
+                None => return false,
+            };
+        match self.body[use_location.block].statements.get(use_location.statement_index) {
+            Some(Statement {
+                kind:
+                    StatementKind::Assign(box (
+                        _,
+                        Rvalue::Cast(CastKind::Misc, Operand::Move(operand), ty),
+                    )),
+                ..
+            }) => operand.as_local() == Some(temp) && ty.is_integral(),
+            _ => false,
+        }
+    }
+
+    /// Checks whether casting through all of `tys` is the same as casting from the first to the
+    /// last type directly.
+    fn is_collapsible(&self, tys: &[Ty<'tcx>]) -> Option<bool> {
+        let (source_size, source_signed) = self.int_layout(tys[0])?;
+
+        // We track how the current value relates to the original one: it consists of the low
+        // `known` bits of the original value, extended to the current size, with sign extension
+        // if `sign_extended`.
+        let mut known = source_size;
+        let mut sign_extended = source_signed;
+        let (mut current_size, mut current_signed) = (source_size, source_signed);
+        for &ty in &tys[1..] {
+            let (size, signed) = self.int_layout(ty)?;
+            if size <= known {
+                known = size;
+            } else if size > current_size {
+                if known == current_size {
+                    sign_extended = current_signed;
+                } else if sign_extended && !current_signed {
+                    // Zero-extending a sign extension cannot be expressed by a single cast.
+                    return Some(false);
+                }
+            }
+            current_size = size;
+            current_signed = signed;
+        }
+
+        // A direct cast truncates to the target size, or extends according to the source type.
+        Some(if current_size <= source_size {
+            known == current_size
+        } else {
+            known == source_size && sign_extended == source_signed
+        })
+    }
+}
+
+impl Visitor<'tcx> for CastFinder<'a, 'tcx> {
+    fn visit_assign(&mut self, place: &Place<'tcx>, rvalue: &Rvalue<'tcx>, location: Location) {
+        let (kind, operand, target_ty) = match rvalue {
+            Rvalue::Cast(kind, operand, target_ty) => (kind, operand, *target_ty),
+            _ => return,
+        };
+
+        // Casts in the middle of a chain are handled together with the outermost cast.
+        if let CastKind::Misc = kind {
+            if self.feeds_int_cast(place) {
+                return;
+            }
+        }
+
+        if self.tcx.erase_regions(&operand.ty(self.body, self.tcx))
+            == self.tcx.erase_regions(&target_ty)
+        {
+            debug!("removing no-op cast at {:?}", location);
+            self.rvalues.insert(location, Rvalue::Use(operand.clone()));
+            return;
+        }
+
+        if self.int_layout(target_ty).is_none() {
+            return;
+        }
+        let mut chain = vec![ChainLink { location, operand, temp: None }];
+        while let Some(link) = self.cast_def(chain.last().unwrap().operand) {
+            chain.push(link);
+        }
+        if chain.len() < 2 {
+            return;
+        }
+
+        // The types along the chain, from the innermost operand to `target_ty`.
+        let mut tys: Vec<_> =
+            chain.iter().rev().map(|link| link.operand.ty(self.body, self.tcx)).collect();
+        tys.push(target_ty);
+
+        // Find the longest collapsible suffix of the chain.
+        let start = (0..chain.len() - 1)
+            .find(|&start| self.is_collapsible(&tys[start..]).unwrap_or(false));
+        let start = match start {
+            Some(start) => start,
+            None => return,
+        };
+        // `chain` is ordered from outermost to innermost cast.
+        let innermost = chain.len() - 1 - start;
+        debug!("collapsing casts through {:?} into {:?} at {:?}", &tys[start..], place, location);
+
+        let link = &chain[innermost];
+        self.rvalues
+            .insert(link.location, Rvalue::Cast(CastKind::Misc, link.operand.clone(), target_ty));
+        for link in &chain[..innermost] {
+            self.rvalues.insert(link.location, Rvalue::Use(link.operand.clone()));
+        }
+        for link in &chain[..=innermost] {
+            if let Some(temp) = link.temp {
+                self.local_tys.insert(temp, target_ty);
+            }
+        }
+    }
+}
+
+struct CastReplacer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    rvalues: FxHashMap<Location, Rvalue<'tcx>>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for CastReplacer<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &mut Rvalue<'tcx>, location: Location) {
+        if let Some(new_rvalue) = self.rvalues.remove(&location) {
+            *rvalue = new_rvalue;
+        }
+        self.super_rvalue(rvalue, location)
+    }
+}
@@ -0,0 +1,287 @@
+//! Switches directly on the integer compared to a constant, rather than on the result of the
+//! comparison:
+//!
+//! ```rust
+//! _2 = Eq(_1, const 7i32);
+//! switchInt(move _2) -> [false: bb1, otherwise: bb2];
+//! ```
+//!
+//! becomes
+//!
+//! ```rust
+//! switchInt(_1) -> [7i32: bb2, otherwise: bb1];
+//! ```
+//!
+//! The ordered comparisons against a bound of the type are equalities: `_1 <= const 0u8` is
+//! `_1 == const 0u8`, so they are switched on the same way. The comparison itself is removed
+//! when its result is not used elsewhere.
+//!
+//! The `StorageDead` of the compared local between the comparison and the switch is moved to
+//! the targets, provided the switch is their only predecessor.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::mir::interpret::truncate;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::{ParamEnv, Ty, TyCtxt};
+use std::borrow::Cow;
+
+pub struct SimplifyComparisonIntegral;
+
+impl<'tcx> MirPass<'tcx> for SimplifyComparisonIntegral {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let def_id = source.def_id();
+        let param_env = tcx.param_env(def_id);
+        body.ensure_predecessors();
+        let optimizations = {
+            let body = body.unwrap_read_only();
+            body.basic_blocks()
+                .indices()
+                .filter_map(|bb| find_optimization(tcx, param_env, body, bb))
+                .collect::<Vec<_>>()
+        };
+
+        let mut moved_storage_deads = vec![];
+        for opt in optimizations {
+            if !tcx.consider_optimizing(|| {
+                format!("SimplifyComparisonIntegral {:?} {:?}", def_id, opt.bb)
+            }) {
+                return;
+            }
+            mir_trace!("switching on {:?} in {:?}", opt.place, opt.bb);
+            moved_storage_deads.extend(apply_optimization(body, opt));
+        }
+        // Last, as they shift the statements of the targets.
+        for (target, statement) in moved_storage_deads {
+            body[target].statements.insert(0, statement);
+        }
+    }
+}
+
+/// A switch on the comparison of `place` to `value`, to replace by a switch on `place`.
+struct Optimization<'tcx> {
+    bb: BasicBlock,
+    /// The index of the comparison in the statements of `bb`.
+    statement_index: usize,
+    place: Place<'tcx>,
+    switch_ty: Ty<'tcx>,
+    value: u128,
+    /// The targets of the new switch: the one taken when `place` is `value`, and `otherwise`.
+    targets: Vec<BasicBlock>,
+    target_weights: Option<Vec<u32>>,
+    /// Whether the result of the comparison is only used by the switch.
+    result_unused: bool,
+    /// The indices of the `StorageDead` of `place.local` between the comparison and the switch.
+    storage_deads: Vec<usize>,
+}
+
+fn find_optimization<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: ReadOnlyBodyAndCache<'_, 'tcx>,
+    bb: BasicBlock,
+) -> Option<Optimization<'tcx>> {
+    let bb_data = &body[bb];
+    let (discr, values, targets, target_weights) = match &bb_data.terminator().kind {
+        TerminatorKind::SwitchInt { discr, switch_ty, values, targets, target_weights }
+            if *switch_ty == tcx.types.bool && values.len() == 1 =>
+        {
+            (discr, values, targets, target_weights)
+        }
+        _ => return None,
+    };
+    let result = discr.place()?.as_local()?;
+
+    // The last statement using the result must be the comparison.
+    let statement_index = bb_data
+        .statements
+        .iter()
+        .rposition(|statement| uses_local(statement, result, |_| true))?;
+    let (op, lhs, rhs) = match &bb_data.statements[statement_index].kind {
+        StatementKind::Assign(box (dest, Rvalue::BinaryOp(op, lhs, rhs)))
+            if dest.as_local() == Some(result) =>
+        {
+            (*op, lhs, rhs)
+        }
+        _ => return None,
+    };
+    let (place, constant, op) = match (lhs, rhs) {
+        (Operand::Copy(place), Operand::Constant(constant))
+        | (Operand::Move(place), Operand::Constant(constant)) => (place, constant, op),
+        (Operand::Constant(constant), Operand::Copy(place))
+        | (Operand::Constant(constant), Operand::Move(place)) => (place, constant, flip(op)?),
+        _ => return None,
+    };
+    // A place behind a reference may be written through another pointer.
+    if place.projection.iter().any(|elem| elem == &ProjectionElem::Deref) {
+        return None;
+    }
+    let switch_ty = place.ty(*body, tcx).ty;
+    if !switch_ty.is_integral() {
+        return None;
+    }
+    let bits = constant.literal.try_eval_bits(tcx, param_env, switch_ty)?;
+    let (value, true_on_equal) = equality(tcx, param_env, switch_ty, op, bits)?;
+
+    let mut storage_deads = vec![];
+    for (index, statement) in bb_data.statements.iter().enumerate().skip(statement_index + 1) {
+        match statement.kind {
+            StatementKind::StorageDead(local) if local == place.local => {
+                storage_deads.push(index);
+            }
+            StatementKind::Nop | StatementKind::StorageLive(_) | StatementKind::StorageDead(_) => {}
+            StatementKind::Assign(box (ref dest, _))
+                if !dest.projection.iter().any(|elem| elem == &ProjectionElem::Deref)
+                    && !uses_local(statement, place.local, |context| {
+                        context.is_mutating_use()
+                    }) => {}
+            _ => return None,
+        }
+    }
+
+    // The switch goes to its first target when the comparison is `values[0]`, which it is when
+    // `place` is `value` if the comparison is then `true_on_equal`.
+    let (eq, ne) = if values[0] == true_on_equal as u128 { (0, 1) } else { (1, 0) };
+    if !storage_deads.is_empty()
+        && targets.iter().any(|&target| body.predecessors_for(target) != [bb])
+    {
+        return None;
+    }
+
+    let mut uses = UseCount { local: result, count: 0 };
+    uses.visit_body(body);
+    Some(Optimization {
+        bb,
+        statement_index,
+        place: place.clone(),
+        switch_ty,
+        value,
+        targets: vec![targets[eq], targets[ne]],
+        target_weights: target_weights.as_ref().map(|weights| vec![weights[eq], weights[ne]]),
+        // The assignment and the switch.
+        result_unused: uses.count == 2,
+        storage_deads,
+    })
+}
+
+/// Applies `opt` to `body`, and returns the `StorageDead` statements to move to the targets.
+fn apply_optimization<'tcx>(
+    body: &mut BodyAndCache<'tcx>,
+    opt: Optimization<'tcx>,
+) -> Vec<(BasicBlock, Statement<'tcx>)> {
+    let bb_data = &mut body[opt.bb];
+    let source_info = bb_data.statements[opt.statement_index].source_info;
+    if opt.result_unused {
+        bb_data.statements[opt.statement_index].make_nop();
+    } else if let StatementKind::Assign(box (_, Rvalue::BinaryOp(_, lhs, rhs))) =
+        &mut bb_data.statements[opt.statement_index].kind
+    {
+        // The switch reads `place` after the comparison.
+        for operand in &mut [lhs, rhs] {
+            if let Operand::Move(place) = operand {
+                **operand = Operand::Copy(place.clone());
+            }
+        }
+    }
+    for &index in &opt.storage_deads {
+        bb_data.statements[index].make_nop();
+    }
+    bb_data.terminator_mut().kind = TerminatorKind::SwitchInt {
+        discr: Operand::Copy(opt.place.clone()),
+        switch_ty: opt.switch_ty,
+        values: Cow::from(vec![opt.value]),
+        targets: opt.targets.clone(),
+        target_weights: opt.target_weights,
+    };
+
+    if opt.storage_deads.is_empty() {
+        return vec![];
+    }
+    let storage_dead = Statement { source_info, kind: StatementKind::StorageDead(opt.place.local) };
+    opt.targets.iter().map(|&target| (target, storage_dead.clone())).collect()
+}
+
+/// Returns the comparison `op` with its operands swapped.
+fn flip(op: BinOp) -> Option<BinOp> {
+    Some(match op {
+        BinOp::Eq => BinOp::Eq,
+        BinOp::Ne => BinOp::Ne,
+        BinOp::Lt => BinOp::Gt,
+        BinOp::Le => BinOp::Ge,
+        BinOp::Gt => BinOp::Lt,
+        BinOp::Ge => BinOp::Le,
+        _ => return None,
+    })
+}
+
+/// Returns the value that `place op bits` compares `place` to for equality, and whether the
+/// comparison is true or false when they are equal, if it is an equality.
+fn equality<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    ty: Ty<'tcx>,
+    op: BinOp,
+    bits: u128,
+) -> Option<(u128, bool)> {
+    let size = tcx.layout_of(param_env.and(ty)).ok()?.size;
+    let (min, max) = if ty.is_signed() {
+        let min = 1 << (size.bits() - 1);
+        (min, min - 1)
+    } else {
+        (0, truncate(u128::max_value(), size))
+    };
+    match op {
+        BinOp::Eq => Some((bits, true)),
+        BinOp::Ne => Some((bits, false)),
+        BinOp::Le if bits == min => Some((min, true)),
+        BinOp::Gt if bits == min => Some((min, false)),
+        BinOp::Ge if bits == max => Some((max, true)),
+        BinOp::Lt if bits == max => Some((max, false)),
+        _ => None,
+    }
+}
+
+/// Returns whether `statement` uses `local` in a context accepted by `filter`, other than its
+/// storage statements.
+fn uses_local(
+    statement: &Statement<'_>,
+    local: Local,
+    filter: impl Fn(PlaceContext) -> bool,
+) -> bool {
+    struct Finder<F> {
+        local: Local,
+        filter: F,
+        found: bool,
+    }
+
+    impl<'tcx, F: Fn(PlaceContext) -> bool> Visitor<'tcx> for Finder<F> {
+        fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+            if local == self.local && !context.is_storage_marker() && (self.filter)(context) {
+                self.found = true;
+            }
+        }
+    }
+
+    let mut finder = Finder { local, filter, found: false };
+    finder.visit_statement(statement, Location::START);
+    finder.found
+}
+
+/// Counts the uses of `local`, other than its storage statements.
+struct UseCount {
+    local: Local,
+    count: usize,
+}
+
+impl Visitor<'_> for UseCount {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if local == self.local && !context.is_storage_marker() {
+            self.count += 1;
+        }
+    }
+}
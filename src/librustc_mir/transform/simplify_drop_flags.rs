@@ -0,0 +1,219 @@
+//! Removes the drop flags whose value is known where they are tested. Drop elaboration guards the
+//! drops of the values that may have been moved out with a flag, which is only set and tested:
+//!
+//! ```rust
+//! _5 = const false;
+//! ...
+//! switchInt(_5) -> [false: bb4, otherwise: bb3];
+//! ```
+//!
+//! Once `ConstProp` and `SimplifyBranches` removed the branches moving the value, the flag often
+//! has a single value reaching the test, which becomes a `goto`. The assignments of the flags that
+//! are no longer tested are removed, and the flags themselves by `SimplifyLocals`.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::{self, TyCtxt};
+use rustc_data_structures::work_queue::WorkQueue;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::{Idx, IndexVec};
+
+pub struct SimplifyDropFlags;
+
+rustc_index::newtype_index! {
+    /// A drop flag, among the drop flags of a body only.
+    struct FlagIndex {
+        DEBUG_FORMAT = "flag{}"
+    }
+}
+
+impl<'tcx> MirPass<'tcx> for SimplifyDropFlags {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let def_id = source.def_id();
+        let (flags, known_tests) = {
+            let body: &Body<'tcx> = body;
+            let flags = drop_flags(tcx, body);
+            if flags.is_empty() {
+                return;
+            }
+            let known_tests = known_flag_tests(tcx, body, &flags);
+            (flags, known_tests)
+        };
+
+        let mut tested = BitSet::new_empty(body.local_decls.len());
+        for (bb, bb_data) in body.basic_blocks().iter_enumerated() {
+            if let Some(flag) = tested_flag(&bb_data.terminator().kind, &flags) {
+                if !known_tests.iter().any(|&(known_bb, _)| known_bb == bb) {
+                    tested.insert(flag);
+                }
+            }
+        }
+
+        for (bb, target) in known_tests {
+            if !tcx.consider_optimizing(|| format!("SimplifyDropFlags {:?} {:?}", def_id, bb)) {
+                return;
+            }
+            mir_trace!("the drop flag tested in {:?} is known, going to {:?}", bb, target);
+            body[bb].terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+
+        // The flags that are no longer tested are only assigned.
+        for bb_data in body.basic_blocks_mut() {
+            for statement in &mut bb_data.statements {
+                if let StatementKind::Assign(box (place, _)) = &statement.kind {
+                    let local = place.local;
+                    if flags.contains(local) && !tested.contains(local) {
+                        statement.make_nop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the internal `bool` locals that are only assigned constants and tested by `switchInt`,
+/// which is how drop elaboration uses its flags.
+fn drop_flags(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> BitSet<Local> {
+    let mut flags = BitSet::new_empty(body.local_decls.len());
+    for (local, decl) in body.local_decls.iter_enumerated() {
+        if decl.internal && decl.ty == tcx.types.bool && local != RETURN_PLACE {
+            flags.insert(local);
+        }
+    }
+    let mut uses = FlagUses { flags: &mut flags };
+    for (bb, data) in body.basic_blocks().iter_enumerated() {
+        uses.visit_basic_block_data(bb, data);
+    }
+    flags
+}
+
+struct FlagUses<'a> {
+    flags: &'a mut BitSet<Local>,
+}
+
+impl Visitor<'tcx> for FlagUses<'_> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        match &statement.kind {
+            StatementKind::Assign(box (place, Rvalue::Use(Operand::Constant(_))))
+                if place.as_local().is_some() => {}
+            _ => self.super_statement(statement, location),
+        }
+    }
+
+    fn visit_terminator_kind(&mut self, kind: &TerminatorKind<'tcx>, location: Location) {
+        match kind {
+            TerminatorKind::SwitchInt { discr: Operand::Copy(place), .. }
+            | TerminatorKind::SwitchInt { discr: Operand::Move(place), .. }
+                if place.as_local().is_some() => {}
+            _ => self.super_terminator_kind(kind, location),
+        }
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        // Any other use of the local, even a copy, makes its value escape the tests.
+        match context {
+            PlaceContext::NonUse(_) => {}
+            _ => {
+                self.flags.remove(local);
+            }
+        }
+    }
+}
+
+/// Returns the flag tested by `kind`, if it tests one.
+fn tested_flag(kind: &TerminatorKind<'_>, flags: &BitSet<Local>) -> Option<Local> {
+    match kind {
+        TerminatorKind::SwitchInt { discr: Operand::Copy(place), .. }
+        | TerminatorKind::SwitchInt { discr: Operand::Move(place), .. } => {
+            place.as_local().filter(|&local| flags.contains(local))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the blocks testing a flag whose value is known there, with the target taken.
+fn known_flag_tests(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    flags: &BitSet<Local>,
+) -> Vec<(BasicBlock, BasicBlock)> {
+    // The states below only have an entry for each flag, rather than for each local.
+    let mut flag_indices: IndexVec<Local, Option<FlagIndex>> =
+        IndexVec::from_elem(None, &body.local_decls);
+    for (index, flag) in flags.iter().enumerate() {
+        flag_indices[flag] = Some(FlagIndex::new(index));
+    }
+
+    // The value of each flag on entry of each block. `None` for the blocks not reached yet, and
+    // for each flag, `None` if its value is unknown.
+    let mut entry_states: IndexVec<BasicBlock, Option<IndexVec<FlagIndex, Option<bool>>>> =
+        IndexVec::from_elem(None, body.basic_blocks());
+    entry_states[START_BLOCK] = Some(IndexVec::from_elem_n(None, flags.count()));
+    let mut dirty_queue: WorkQueue<BasicBlock> = WorkQueue::with_none(body.basic_blocks().len());
+    dirty_queue.insert(START_BLOCK);
+    while let Some(bb) = dirty_queue.pop() {
+        let state = exit_state(tcx, body, &flag_indices, bb, entry_states[bb].clone().unwrap());
+        for &target in body[bb].terminator().successors() {
+            let changed = match &mut entry_states[target] {
+                entry @ None => {
+                    *entry = Some(state.clone());
+                    true
+                }
+                Some(entry) => {
+                    let mut changed = false;
+                    for (value, &incoming) in entry.iter_mut().zip(state.iter()) {
+                        if value.is_some() && *value != incoming {
+                            *value = None;
+                            changed = true;
+                        }
+                    }
+                    changed
+                }
+            };
+            if changed {
+                dirty_queue.insert(target);
+            }
+        }
+    }
+
+    body.basic_blocks()
+        .iter_enumerated()
+        .filter_map(|(bb, bb_data)| {
+            let (values, targets) = match &bb_data.terminator().kind {
+                TerminatorKind::SwitchInt { values, targets, .. } => (values, targets),
+                _ => return None,
+            };
+            let flag = tested_flag(&bb_data.terminator().kind, flags)?;
+            let state = exit_state(tcx, body, &flag_indices, bb, entry_states[bb].clone()?);
+            let value = state[flag_indices[flag]?]? as u128;
+            let index = values.iter().position(|&v| v == value).unwrap_or(values.len());
+            Some((bb, targets[index]))
+        })
+        .collect()
+}
+
+/// Applies the assignments of the flags in `bb` to `state`.
+fn exit_state(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    flag_indices: &IndexVec<Local, Option<FlagIndex>>,
+    bb: BasicBlock,
+    mut state: IndexVec<FlagIndex, Option<bool>>,
+) -> IndexVec<FlagIndex, Option<bool>> {
+    for statement in &body[bb].statements {
+        if let StatementKind::Assign(box (place, Rvalue::Use(Operand::Constant(constant)))) =
+            &statement.kind
+        {
+            if let Some(flag) = flag_indices[place.local] {
+                state[flag] = constant.literal.try_eval_bool(tcx, ty::ParamEnv::empty());
+            }
+        }
+    }
+    state
+}
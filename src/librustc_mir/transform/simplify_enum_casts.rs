@@ -0,0 +1,80 @@
+//! Rewrites casts of fieldless enums to integers into reads of their discriminant.
+//!
+//! ```rust
+//! _2 = _1 as u8 (Misc);   // _1: State
+//! ```
+//!
+//! becomes
+//!
+//! ```rust
+//! _3 = discriminant(_1);  // _3: isize, the discriminant type of `State`
+//! _2 = move _3 as u8 (Misc);
+//! ```
+//!
+//! The cast is dropped if the discriminant already has the target type. Unlike the cast, the
+//! discriminant read is understood by `ConstProp` and by the passes simplifying switches, which
+//! helps hand-written state machines.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::patch::MirPatch;
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::util::IntTypeExt;
+use rustc::ty::{self, TyCtxt};
+
+pub struct SimplifyEnumCasts;
+
+impl<'tcx> MirPass<'tcx> for SimplifyEnumCasts {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let mut patch = MirPatch::new(body);
+        let (basic_blocks, local_decls) = body.basic_blocks_and_local_decls_mut();
+        for (block, data) in basic_blocks.iter_enumerated_mut() {
+            for (statement_index, statement) in data.statements.iter_mut().enumerate() {
+                let (operand, target_ty) = match &mut statement.kind {
+                    StatementKind::Assign(box (_, Rvalue::Cast(CastKind::Misc, operand, ty))) => {
+                        (operand, *ty)
+                    }
+                    _ => continue,
+                };
+                let place = match operand.place() {
+                    Some(place) => place.clone(),
+                    None => continue,
+                };
+                let discr_ty = match place.ty(&*local_decls, tcx).ty.kind {
+                    ty::Adt(adt_def, _)
+                        if adt_def.is_enum()
+                            && adt_def.is_payloadfree()
+                            && !adt_def.variants.is_empty() =>
+                    {
+                        adt_def.repr.discr_type().to_ty(tcx)
+                    }
+                    _ => continue,
+                };
+
+                let location = Location { block, statement_index };
+                if !tcx.consider_optimizing(|| {
+                    format!("SimplifyEnumCasts {:?} {:?}", source.def_id(), location)
+                }) {
+                    break;
+                }
+                debug!("reading the discriminant of {:?} at {:?}", place, location);
+
+                let temp = patch.new_temp(discr_ty, statement.source_info.span);
+                patch.add_assign(location, Place::from(temp), Rvalue::Discriminant(place));
+                let discr = Operand::Move(Place::from(temp));
+                if let StatementKind::Assign(box (_, rvalue)) = &mut statement.kind {
+                    *rvalue = if discr_ty == target_ty {
+                        Rvalue::Use(discr)
+                    } else {
+                        Rvalue::Cast(CastKind::Misc, discr, target_ty)
+                    };
+                }
+            }
+        }
+        patch.apply(body);
+    }
+}
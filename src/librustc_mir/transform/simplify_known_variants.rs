@@ -0,0 +1,88 @@
+//! Removes the switches on the discriminant of enums whose variant is already known, like the
+//! match inside an inlined `unwrap` of a value checked to be `Some` before:
+//!
+//! ```rust
+//! if let Some(_) = x {
+//!     x.unwrap()
+//! }
+//! ```
+//!
+//! The switch becomes a `goto` to the arm of the known variant, which leaves the arm calling
+//! `panic` unreachable. The variants are computed by the `KnownVariants` analysis.
+
+use crate::dataflow::generic::Analysis;
+use crate::dataflow::KnownVariants;
+use crate::transform::{MirPass, MirSource};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::TyCtxt;
+
+pub struct SimplifyKnownVariants;
+
+impl<'tcx> MirPass<'tcx> for SimplifyKnownVariants {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let def_id = source.def_id();
+        let known_targets = {
+            let body: &Body<'tcx> = body;
+            let mut cursor = KnownVariants::new(body)
+                .into_engine(tcx, body, def_id)
+                .iterate_to_fixpoint()
+                .into_results_cursor(body);
+            let mut known_targets = vec![];
+            for (bb, bb_data) in body.basic_blocks().iter_enumerated() {
+                let (switched_on, values, targets) = match switch_on_discriminant(bb_data) {
+                    Some(switch) => switch,
+                    None => continue,
+                };
+                cursor.seek_before(body.terminator_loc(bb));
+                let variant = match cursor.analysis().known_variant(cursor.get(), switched_on) {
+                    Some(variant) => variant,
+                    None => continue,
+                };
+                let ty = switched_on.ty(body, tcx).ty;
+                let discr = match ty.discriminant_for_variant(tcx, variant) {
+                    Some(discr) => discr.val,
+                    None => continue,
+                };
+                let target = values
+                    .iter()
+                    .position(|&value| value == discr)
+                    .map_or_else(|| *targets.last().unwrap(), |index| targets[index]);
+                known_targets.push((bb, target));
+            }
+            known_targets
+        };
+
+        for (bb, target) in known_targets {
+            if !tcx.consider_optimizing(|| format!("SimplifyKnownVariants {:?} {:?}", def_id, bb))
+            {
+                return;
+            }
+            mir_trace!("switch in {:?} always goes to {:?}", bb, target);
+            body[bb].terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+    }
+}
+
+/// If `bb_data` ends by switching on the discriminant of some place, returns that place and the
+/// values and targets of the switch.
+fn switch_on_discriminant<'a, 'tcx>(
+    bb_data: &'a BasicBlockData<'tcx>,
+) -> Option<(&'a Place<'tcx>, &'a [u128], &'a [BasicBlock])> {
+    let (discr, values, targets) = match &bb_data.terminator().kind {
+        TerminatorKind::SwitchInt { discr, values, targets, .. } => {
+            (discr.place()?, values, targets)
+        }
+        _ => return None,
+    };
+    match &bb_data.statements.last()?.kind {
+        StatementKind::Assign(box (place, Rvalue::Discriminant(switched_on))) if place == discr => {
+            Some((switched_on, &values[..], &targets[..]))
+        }
+        _ => None,
+    }
+}
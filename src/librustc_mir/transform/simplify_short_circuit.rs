@@ -0,0 +1,134 @@
+//! Threads the jumps out of the blocks of `&&` and `||` whose result is known.
+//!
+//! The lowering of `a && b` assigns the result of each path to the destination in its own block,
+//! and then switches on it in the block joining these paths:
+//!
+//! ```rust
+//! bb2: {
+//!     _1 = const true;
+//!     goto -> bb4;
+//! }
+//! bb3: {
+//!     _1 = const false;
+//!     goto -> bb4;
+//! }
+//! bb4: {
+//!     StorageDead(_2);
+//!     switchInt(_1) -> [false: bb6, otherwise: bb5];
+//! }
+//! ```
+//!
+//! The value switched on is known in each predecessor, but as it is assigned twice, `ConstProp`
+//! can't propagate it. Here, `bb2` jumps to `bb5` and `bb3` to `bb6` instead, after the
+//! `StorageDead` statements of `bb4`, which is then removed. This is repeated along the chains
+//! of `&&` and `||`, so that once the branches on constant operands are taken, as with
+//! `cfg!(unix) && ..`, only the paths that can be taken remain.
+//!
+//! The assignments are kept, and are removed by `SimplifyLocals` if the result is unused.
+
+use crate::transform::{simplify, MirPass, MirSource};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::{self, TyCtxt};
+
+pub struct SimplifyShortCircuit;
+
+impl<'tcx> MirPass<'tcx> for SimplifyShortCircuit {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let param_env = tcx.param_env(source.def_id());
+        let mut threaded = false;
+        'blocks: for bb in body.basic_blocks().indices() {
+            // Each jump threaded can lead to another joining block switching on the same value.
+            // The number of jumps is bounded in case these blocks form a loop.
+            for _ in 0..body.basic_blocks().len() {
+                let (join, target) = match known_target(tcx, param_env, body, bb) {
+                    Some(known) => known,
+                    None => break,
+                };
+                if !tcx.consider_optimizing(|| {
+                    format!("SimplifyShortCircuit {:?} {:?}", source.def_id(), bb)
+                }) {
+                    break 'blocks;
+                }
+                mir_trace!("threading the jump from {:?} through {:?} to {:?}", bb, join, target);
+
+                let join_statements = body.basic_blocks()[join].statements.clone();
+                let bb_data = &mut body.basic_blocks_mut()[bb];
+                bb_data.statements.extend(join_statements);
+                bb_data.terminator_mut().kind = TerminatorKind::Goto { target };
+                threaded = true;
+            }
+        }
+
+        if threaded {
+            simplify::remove_dead_blocks(body);
+        }
+    }
+}
+
+/// If `bb` assigns a constant to a local and then jumps to a block only switching on that local,
+/// returns that block and the target taken for the constant.
+fn known_target<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+    bb: BasicBlock,
+) -> Option<(BasicBlock, BasicBlock)> {
+    let bb_data = &body.basic_blocks()[bb];
+    let join = match bb_data.terminator().kind {
+        TerminatorKind::Goto { target } => target,
+        _ => return None,
+    };
+
+    // The assignment to the local can be followed by the storage statements copied from the
+    // joining blocks already threaded through.
+    let trailing = bb_data
+        .statements
+        .iter()
+        .rev()
+        .take_while(|statement| match statement.kind {
+            StatementKind::StorageDead(_) | StatementKind::Nop => true,
+            _ => false,
+        })
+        .count();
+    let assign_index = bb_data.statements.len().checked_sub(trailing + 1)?;
+    let (local, constant) = match &bb_data.statements[assign_index].kind {
+        StatementKind::Assign(box (place, Rvalue::Use(Operand::Constant(constant)))) => {
+            (place.as_local()?, constant)
+        }
+        _ => return None,
+    };
+
+    let join_data = &body.basic_blocks()[join];
+    let keeps_local = bb_data.statements[assign_index + 1..]
+        .iter()
+        .chain(&join_data.statements)
+        .all(|statement| match statement.kind {
+            StatementKind::StorageDead(dead) => dead != local,
+            StatementKind::Nop => true,
+            _ => false,
+        });
+    if !keeps_local {
+        return None;
+    }
+
+    match &join_data.terminator().kind {
+        TerminatorKind::SwitchInt { discr, switch_ty, values, targets, .. }
+            if discr.place().and_then(|place| place.as_local()) == Some(local) =>
+        {
+            let bits = constant.literal.try_eval_bits(tcx, param_env, *switch_ty)?;
+            let (otherwise, targets) = targets.split_last().unwrap();
+            let target = values
+                .iter()
+                .zip(targets)
+                .find(|&(&value, _)| value == bits)
+                .map_or(*otherwise, |(_, &target)| target);
+            Some((join, target))
+        }
+        _ => None,
+    }
+}
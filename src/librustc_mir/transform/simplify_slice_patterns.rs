@@ -0,0 +1,222 @@
+//! Simplifies slice patterns matched against slices that are known to be unsized arrays.
+//!
+//! Slice patterns on a `&[T]` test the length of the slice and then read the elements via
+//! `ConstantIndex` projections of the slice. When the slice was created in the same body by
+//! unsizing a reference to an array, e.g. in
+//!
+//! ```rust
+//! let s: &[u8] = &array;
+//! match s {
+//!     [a, b] => ...,
+//!     _ => ...,
+//! }
+//! ```
+//!
+//! the length is known statically. We replace `Len(*s)` with the length of the array, which
+//! lets `ConstProp` and `SimplifyBranches` remove the length tests entirely, and turn the
+//! element reads into constant-index projections of the array itself:
+//!
+//! ```rust
+//! _5 = _3;
+//! _2 = move _3 as &[u8] (Pointer(Unsize));
+//! ...
+//! _4 = const 2usize;
+//! ...
+//! _6 = (*_5)[0 of 2];
+//! ```
+//!
+//! Subslice projections are left alone, since projecting them out of the array would change
+//! the type of the resulting place.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::def_use::DefUseAnalysis;
+use rustc::mir::visit::{MutVisitor, PlaceContext};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::adjustment::PointerCast;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir as hir;
+use rustc_span::Span;
+
+pub struct SimplifySlicePatterns;
+
+impl<'tcx> MirPass<'tcx> for SimplifySlicePatterns {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let param_env = tcx.param_env(source.def_id());
+        let candidates = {
+            let read_only_cache = read_only!(body);
+            let mut def_use_analysis = DefUseAnalysis::new(body);
+            def_use_analysis.analyze(read_only_cache);
+            find_unsized_arrays(tcx, param_env, body, &def_use_analysis)
+        };
+        if candidates.is_empty() {
+            return;
+        }
+
+        // Give each unsized array a fresh copy of its thin pointer. The original pointer is
+        // usually a temporary whose storage ends right after the cast.
+        let mut slices = FxHashMap::default();
+        let mut copies = Vec::with_capacity(candidates.len());
+        for (slice, array) in candidates {
+            let span = body.local_decls[slice].source_info.span;
+            let thin = body.local_decls.push(LocalDecl::new_temp(array.thin_ty, span));
+            copies.push((array.cast_location, thin, array.thin_operand.to_copy()));
+            slices.insert(slice, (thin, array.len, span));
+        }
+
+        MutVisitor::visit_body(&mut SlicePatternVisitor { tcx, slices }, body);
+
+        // Insert back to front so that earlier locations stay valid.
+        copies.sort_by_key(|&(location, _, _)| location);
+        for (location, thin, operand) in copies.into_iter().rev() {
+            let block = &mut body.basic_blocks_mut()[location.block];
+            let source_info = block.statements[location.statement_index].source_info;
+            block.statements.insert(
+                location.statement_index,
+                Statement {
+                    source_info,
+                    kind: StatementKind::Assign(box (Place::from(thin), Rvalue::Use(operand))),
+                },
+            );
+        }
+    }
+}
+
+/// A slice local that is the result of unsizing a reference to an array.
+struct UnsizedArray<'tcx> {
+    cast_location: Location,
+    thin_operand: Operand<'tcx>,
+    thin_ty: Ty<'tcx>,
+    len: u32,
+}
+
+/// Finds locals with a single definition of the form `_s = _t as &[T] (Pointer(Unsize))`,
+/// where `_t: &[T; N]`.
+fn find_unsized_arrays<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+    def_use_analysis: &DefUseAnalysis,
+) -> Vec<(Local, UnsizedArray<'tcx>)> {
+    let mut candidates = Vec::new();
+    for (bb, bb_data) in body.basic_blocks().iter_enumerated() {
+        for (statement_index, statement) in bb_data.statements.iter().enumerate() {
+            let (local, operand) = match &statement.kind {
+                StatementKind::Assign(box (
+                    place,
+                    Rvalue::Cast(CastKind::Pointer(PointerCast::Unsize), operand, _),
+                )) => match place.as_local() {
+                    Some(local) if body.local_kind(local) != LocalKind::Arg => (local, operand),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            if def_use_analysis.local_info(local).def_count() != 1 {
+                continue;
+            }
+
+            // Writes through a copy of a `&mut` would alias the slice, so only handle shared
+            // references.
+            let thin_ty = operand.ty(body, tcx);
+            let len = match thin_ty.kind {
+                ty::Ref(_, pointee, hir::Mutability::Not) => match pointee.kind {
+                    ty::Array(_, len) => len.try_eval_usize(tcx, param_env),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let len = match len {
+                Some(len) if len <= u32::max_value() as u64 => len as u32,
+                _ => continue,
+            };
+
+            debug!("found unsized array {:?} of length {}", local, len);
+            candidates.push((
+                local,
+                UnsizedArray {
+                    cast_location: Location { block: bb, statement_index },
+                    thin_operand: operand.clone(),
+                    thin_ty,
+                    len,
+                },
+            ));
+        }
+    }
+    candidates
+}
+
+struct SlicePatternVisitor<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// Maps each unsized array slice to the copy of its thin pointer, the array length and
+    /// the span of the slice.
+    slices: FxHashMap<Local, (Local, u32, Span)>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for SlicePatternVisitor<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_place(&mut self, place: &mut Place<'tcx>, context: PlaceContext, location: Location) {
+        if let Some(&(thin, len, _)) = self.slices.get(&place.local) {
+            if let Some((offset, min_length, from_end, rest)) =
+                match_constant_index(place.projection.as_ref())
+            {
+                // Projections behind a failed length test are unreachable, but would be out of
+                // bounds for the array, so leave them alone.
+                let in_bounds = if from_end { offset <= len } else { offset < len };
+                if min_length <= len && in_bounds {
+                    let offset = if from_end { len - offset } else { offset };
+                    debug!("replacing {:?} with constant index {} of {:?}", place, offset, thin);
+                    let mut projection = vec![
+                        ProjectionElem::Deref,
+                        ProjectionElem::ConstantIndex { offset, min_length: len, from_end: false },
+                    ];
+                    projection.extend_from_slice(rest);
+                    *place =
+                        Place { local: thin, projection: self.tcx.intern_place_elems(&projection) };
+                }
+            }
+        }
+
+        self.super_place(place, context, location)
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &mut Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Len(place) = rvalue {
+            if let PlaceRef { local, projection: &[ProjectionElem::Deref] } = place.as_ref() {
+                if let Some(&(_, len, span)) = self.slices.get(&local) {
+                    debug!("replacing `Len` of unsized array {:?} with {}", local, len);
+                    *rvalue = Rvalue::Use(Operand::Constant(box Constant {
+                        span,
+                        user_ty: None,
+                        literal: ty::Const::from_usize(self.tcx, len as u64),
+                    }));
+                    return;
+                }
+            }
+        }
+
+        self.super_rvalue(rvalue, location)
+    }
+}
+
+/// Matches `(*_S)[OFFSET of MIN_LENGTH]`, followed by any further projections.
+fn match_constant_index<'a, 'tcx>(
+    projection: &'a [PlaceElem<'tcx>],
+) -> Option<(u32, u32, bool, &'a [PlaceElem<'tcx>])> {
+    match projection {
+        [
+            ProjectionElem::Deref,
+            ProjectionElem::ConstantIndex { offset, min_length, from_end },
+            rest @ ..,
+        ] => Some((*offset, *min_length, *from_end, rest)),
+        _ => None,
+    }
+}
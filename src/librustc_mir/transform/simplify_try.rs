@@ -9,11 +9,13 @@
 //!
 //! into just `x`.
 
-use crate::transform::{simplify, MirPass, MirSource};
+use crate::transform::{optimize_for_size, simplify, MirPass, MirSource};
+use crate::util::match_identity::{match_arm_identity, ArmIdentity, NotIdentity};
+use crate::util::remarks::{emit_remark, RemarkKind};
 use itertools::Itertools as _;
 use rustc::mir::*;
-use rustc::ty::{Ty, TyCtxt};
-use rustc_target::abi::VariantIdx;
+use rustc::ty::TyCtxt;
+use rustc_index::vec::IndexVec;
 
 /// Simplifies arms of form `Variant(x) => Variant(x)` to just a move.
 ///
@@ -30,131 +32,71 @@ use rustc_target::abi::VariantIdx;
 /// ```rust
 /// _LOCAL_0 = move _LOCAL_1
 /// ```
+///
+/// The arms are recognized by `util::match_identity`, see there for the exact forms accepted.
 pub struct SimplifyArmIdentity;
 
 impl<'tcx> MirPass<'tcx> for SimplifyArmIdentity {
-    fn run_pass(&self, _: TyCtxt<'tcx>, _: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
-        let (basic_blocks, local_decls) = body.basic_blocks_and_local_decls_mut();
-        for bb in basic_blocks {
-            // Need 3 statements:
-            let (s0, s1, s2) = match &mut *bb.statements {
-                [s0, s1, s2] => (s0, s1, s2),
-                _ => continue,
-            };
-
-            // Pattern match on the form we want:
-            let (local_tmp_s0, local_1, vf_s0) = match match_get_variant_field(s0) {
-                None => continue,
-                Some(x) => x,
-            };
-            let (local_tmp_s1, local_0, vf_s1) = match match_set_variant_field(s1) {
-                None => continue,
-                Some(x) => x,
-            };
-            if local_tmp_s0 != local_tmp_s1
-                // Avoid moving into ourselves.
-                || local_0 == local_1
-                // The field-and-variant information match up.
-                || vf_s0 != vf_s1
-                // Source and target locals have the same type.
-                // FIXME(Centril | oli-obk): possibly relax to same layout?
-                || local_decls[local_0].ty != local_decls[local_1].ty
-                // We're setting the discriminant of `local_0` to this variant.
-                || Some((local_0, vf_s0.var_idx)) != match_set_discr(s2)
-            {
-                continue;
-            }
-
-            // Right shape; transform!
-            s0.source_info = s2.source_info;
-            match &mut s0.kind {
-                StatementKind::Assign(box (place, rvalue)) => {
-                    *place = local_0.into();
-                    *rvalue = Rvalue::Use(Operand::Move(local_1.into()));
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, _: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let name = self.name();
+        let mut arms = vec![];
+        for (bb, bb_data) in body.basic_blocks().iter_enumerated() {
+            match match_arm_identity(&body.local_decls, bb_data) {
+                Ok(ArmIdentity { dest, source: Some(source), statements, .. }) => {
+                    arms.push((bb, dest, source, statements))
+                }
+                Ok(ArmIdentity { source: None, .. }) | Err(NotIdentity::Shape) => {}
+                Err(reason) => {
+                    let span = bb_data.terminator().source_info.span;
+                    emit_remark(tcx, &name, RemarkKind::Missed, span, || {
+                        reason.description().to_string()
+                    });
                 }
-                _ => unreachable!(),
             }
-            s1.make_nop();
-            s2.make_nop();
         }
-    }
-}
-
-/// Match on:
-/// ```rust
-/// _LOCAL_INTO = ((_LOCAL_FROM as Variant).FIELD: TY);
-/// ```
-fn match_get_variant_field<'tcx>(stmt: &Statement<'tcx>) -> Option<(Local, Local, VarField<'tcx>)> {
-    match &stmt.kind {
-        StatementKind::Assign(box (place_into, rvalue_from)) => match rvalue_from {
-            Rvalue::Use(Operand::Copy(pf)) | Rvalue::Use(Operand::Move(pf)) => {
-                let local_into = place_into.as_local()?;
-                let (local_from, vf) = match_variant_field_place(&pf)?;
-                Some((local_into, local_from, vf))
-            }
-            _ => None,
-        },
-        _ => None,
-    }
-}
 
-/// Match on:
-/// ```rust
-/// ((_LOCAL_FROM as Variant).FIELD: TY) = move _LOCAL_INTO;
-/// ```
-fn match_set_variant_field<'tcx>(stmt: &Statement<'tcx>) -> Option<(Local, Local, VarField<'tcx>)> {
-    match &stmt.kind {
-        StatementKind::Assign(box (place_from, rvalue_into)) => match rvalue_into {
-            Rvalue::Use(Operand::Move(place_into)) => {
-                let local_into = place_into.as_local()?;
-                let (local_from, vf) = match_variant_field_place(&place_from)?;
-                Some((local_into, local_from, vf))
+        for (bb, local_0, local_1, statements) in arms {
+            // Right shape; transform!
+            let (&first, rest) = statements.split_first().unwrap();
+            let &last = statements.last().unwrap();
+            let mut source_info = body[bb].statements[last].source_info;
+            source_info.scope = body.source_scope_for_pass(source_info.scope, &name);
+            let bb = &mut body.basic_blocks_mut()[bb];
+            let s0 = &mut bb.statements[first];
+            s0.source_info = source_info;
+            s0.kind = StatementKind::Assign(box (
+                local_0.into(),
+                Rvalue::Use(Operand::Move(local_1.into())),
+            ));
+            for &index in rest {
+                bb.statements[index].make_nop();
             }
-            _ => None,
-        },
-        _ => None,
-    }
-}
-
-/// Match on:
-/// ```rust
-/// discriminant(_LOCAL_TO_SET) = VAR_IDX;
-/// ```
-fn match_set_discr<'tcx>(stmt: &Statement<'tcx>) -> Option<(Local, VariantIdx)> {
-    match &stmt.kind {
-        StatementKind::SetDiscriminant { place, variant_index } => {
-            Some((place.as_local()?, *variant_index))
+            emit_remark(tcx, &name, RemarkKind::Applied, source_info.span, || {
+                format!("replaced the match arm by a move of `{:?}` to `{:?}`", local_1, local_0)
+            });
         }
-        _ => None,
-    }
-}
-
-#[derive(PartialEq)]
-struct VarField<'tcx> {
-    field: Field,
-    field_ty: Ty<'tcx>,
-    var_idx: VariantIdx,
-}
-
-/// Match on `((_LOCAL as Variant).FIELD: TY)`.
-fn match_variant_field_place<'tcx>(place: &Place<'tcx>) -> Option<(Local, VarField<'tcx>)> {
-    match place.as_ref() {
-        PlaceRef {
-            local,
-            projection: &[ProjectionElem::Downcast(_, var_idx), ProjectionElem::Field(field, ty)],
-        } => Some((local, VarField { field, field_ty: ty, var_idx })),
-        _ => None,
     }
 }
 
 /// Simplifies `SwitchInt(_) -> [targets]`,
 /// where all the `targets` have the same form,
 /// into `goto -> target_first`.
+///
+/// In functions optimized for size, the targets having the same form as an earlier target are
+/// redirected to it even if the other targets differ, which deduplicates their blocks.
+///
+/// When the switch block is the only predecessor of `target_first`, the body of `target_first`
+/// is hoisted into the switch block instead, which removes the `goto` as well.
 pub struct SimplifyBranchSame;
 
 impl<'tcx> MirPass<'tcx> for SimplifyBranchSame {
-    fn run_pass(&self, _: TyCtxt<'tcx>, _: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let for_size = optimize_for_size(tcx, source);
         let mut did_remove_blocks = false;
+        // The predecessors before any switch is simplified. Hoisting a block into the switch block
+        // leaves the hoisted block in place until the dead blocks are removed, so the blocks that
+        // have a single predecessor here still have at most one live predecessor below.
+        let predecessors = body.predecessors().clone();
         let bbs = body.basic_blocks_mut();
         for bb_idx in bbs.indices() {
             let targets = match &bbs[bb_idx].terminator().kind {
@@ -165,34 +107,51 @@ impl<'tcx> MirPass<'tcx> for SimplifyBranchSame {
             let mut iter_bbs_reachable = targets
                 .iter()
                 .map(|idx| (*idx, &bbs[*idx]))
-                .filter(|(_, bb)| {
-                    // Reaching `unreachable` is UB so assume it doesn't happen.
-                    bb.terminator().kind != TerminatorKind::Unreachable
-                    // But `asm!(...)` could abort the program,
-                    // so we cannot assume that the `unreachable` terminator itself is reachable.
-                    // FIXME(Centril): use a normalization pass instead of a check.
-                    || bb.statements.iter().any(|stmt| match stmt.kind {
-                        StatementKind::InlineAsm(..) => true,
-                        _ => false,
-                    })
-                })
+                // Reaching `unreachable` is UB so assume it doesn't happen.
+                .filter(|(_, bb)| !bb.is_empty_unreachable())
                 .peekable();
 
             // We want to `goto -> bb_first`.
             let bb_first = iter_bbs_reachable.peek().map(|(idx, _)| *idx).unwrap_or(targets[0]);
 
             // All successor basic blocks should have the exact same form.
-            let all_successors_equivalent =
-                iter_bbs_reachable.map(|(_, bb)| bb).tuple_windows().all(|(bb_l, bb_r)| {
-                    bb_l.is_cleanup == bb_r.is_cleanup
-                        && bb_l.terminator().kind == bb_r.terminator().kind
-                        && bb_l.statements.iter().eq_by(&bb_r.statements, |x, y| x.kind == y.kind)
-                });
+            let all_successors_equivalent = iter_bbs_reachable
+                .map(|(_, bb)| bb)
+                .tuple_windows()
+                .all(|(bb_l, bb_r)| same_form(bb_l, bb_r));
 
             if all_successors_equivalent {
-                // Replace `SwitchInt(..) -> [bb_first, ..];` with a `goto -> bb_first;`.
-                bbs[bb_idx].terminator_mut().kind = TerminatorKind::Goto { target: bb_first };
+                if can_hoist(bbs, &predecessors, bb_idx, bb_first) {
+                    // Replace `SwitchInt(..) -> [bb_first, ..];` with the body of `bb_first`.
+                    let first = bbs[bb_first].clone();
+                    let switch_bb = &mut bbs[bb_idx];
+                    switch_bb.statements.extend(first.statements);
+                    switch_bb.terminator = first.terminator;
+                } else {
+                    // Replace `SwitchInt(..) -> [bb_first, ..];` with a `goto -> bb_first;`.
+                    bbs[bb_idx].terminator_mut().kind = TerminatorKind::Goto { target: bb_first };
+                }
                 did_remove_blocks = true;
+            } else if for_size {
+                let deduplicated: Vec<_> = targets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &target)| {
+                        targets[..i]
+                            .iter()
+                            .copied()
+                            .find(|&earlier| same_form(&bbs[earlier], &bbs[target]))
+                            .unwrap_or(target)
+                    })
+                    .collect();
+                if deduplicated != *targets {
+                    if let TerminatorKind::SwitchInt { targets, .. } =
+                        &mut bbs[bb_idx].terminator_mut().kind
+                    {
+                        *targets = deduplicated;
+                    }
+                    did_remove_blocks = true;
+                }
             }
         }
 
@@ -202,3 +161,24 @@ impl<'tcx> MirPass<'tcx> for SimplifyBranchSame {
         }
     }
 }
+
+/// Whether the body of `target` can be moved into `switch_bb`, the only block jumping to it.
+fn can_hoist(
+    bbs: &IndexVec<BasicBlock, BasicBlockData<'_>>,
+    predecessors: &IndexVec<BasicBlock, Vec<BasicBlock>>,
+    switch_bb: BasicBlock,
+    target: BasicBlock,
+) -> bool {
+    // The start block is also entered from the caller.
+    target != START_BLOCK
+        && target != switch_bb
+        && bbs[target].is_cleanup == bbs[switch_bb].is_cleanup
+        && predecessors[target].iter().all(|&pred| pred == switch_bb)
+}
+
+/// Whether the blocks do the same thing, ignoring their source info.
+fn same_form(bb_l: &BasicBlockData<'_>, bb_r: &BasicBlockData<'_>) -> bool {
+    bb_l.is_cleanup == bb_r.is_cleanup
+        && bb_l.terminator().kind == bb_r.terminator().kind
+        && bb_l.statements.iter().eq_by(&bb_r.statements, |x, y| x.kind == y.kind)
+}
@@ -0,0 +1,340 @@
+//! Sinks side-effect-free assignments into the successor of a `SwitchInt` that uses them.
+//!
+//! For example, in
+//!
+//! ```rust
+//! bb0: {
+//!     StorageLive(_3);
+//!     _3 = Add(_1, _2);
+//!     switchInt(_4) -> [0: bb1, otherwise: bb2];
+//! }
+//! ```
+//!
+//! where `_3` is only used in `bb2` (and blocks dominated by it), the addition is only needed
+//! when going to `bb2`, so we move it there:
+//!
+//! ```rust
+//! bb0: {
+//!     switchInt(_4) -> [0: bb1, otherwise: bb2];
+//! }
+//!
+//! bb2: {
+//!     StorageLive(_3);
+//!     _3 = Add(_1, _2);
+//!     ...
+//! }
+//! ```
+//!
+//! The `StorageLive` of the assigned local moves along with the assignment. `StorageDead`s of
+//! temporaries only used by the assignment move as well, right after it.
+//!
+//! Liveness tells which successor needs the assigned local. Statements are only sunk if nothing
+//! after them in their block touches the locals they read, and if the memory they read can't be
+//! written in between: they must not read locals whose address is taken, and must not read
+//! through a pointer if a later statement of their block writes through one or is inline
+//! assembly.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::def_use::DefUseAnalysis;
+use crate::util::liveness::{self, DefUse, LivenessResult};
+use crate::util::side_effects::{rvalue_side_effects, statement_side_effects, SideEffects};
+use rustc::mir::visit::{NonUseContext, PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_index::bit_set::BitSet;
+
+pub struct SinkStatements;
+
+impl<'tcx> MirPass<'tcx> for SinkStatements {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        let mut def_use_analysis = DefUseAnalysis::new(body);
+
+        // Each round sinks at most one statement per block, as sinking a statement may allow
+        // sinking the statements computing its operands in the next round.
+        loop {
+            def_use_analysis.analyze(read_only!(body));
+            let liveness = liveness::liveness_of_locals(read_only!(body));
+            let sinks = find_sinks(read_only!(body), &def_use_analysis, &liveness);
+            if sinks.is_empty() {
+                break;
+            }
+
+            for sink in sinks {
+                if !tcx.consider_optimizing(|| {
+                    format!("SinkStatements {:?} {:?} to {:?}", source.def_id(), sink.from, sink.to)
+                }) {
+                    return;
+                }
+                debug!("sinking {:?} to {:?}", sink.from, sink.to);
+                apply_sink(body, sink);
+            }
+        }
+    }
+}
+
+/// A statement to move to the start of the block `to`.
+struct Sink {
+    from: Location,
+    to: BasicBlock,
+    /// The index of the `StorageLive` of the assigned local in the same block, if any.
+    storage_live: Option<usize>,
+    /// The indices of the `StorageDead`s to move after the statement, in the same block.
+    storage_deads: Vec<usize>,
+}
+
+fn find_sinks(
+    body: ReadOnlyBodyAndCache<'_, '_>,
+    def_use_analysis: &DefUseAnalysis,
+    liveness: &LivenessResult,
+) -> Vec<Sink> {
+    let mut borrowed = BorrowedLocals { locals: BitSet::new_empty(body.local_decls.len()) };
+    borrowed.visit_body(body);
+
+    let mut sinks = vec![];
+    for (bb, bb_data) in body.basic_blocks().iter_enumerated() {
+        if let TerminatorKind::SwitchInt { .. } = bb_data.terminator().kind {
+            // Locals touched by the statements we scanned so far and the terminator.
+            let mut touched = LocalsTouched { locals: FxHashMap::default(), writes_memory: false };
+            touched.visit_terminator(bb_data.terminator(), body.terminator_loc(bb));
+
+            for statement_index in (0..bb_data.statements.len()).rev() {
+                let location = Location { block: bb, statement_index };
+                let statement = &bb_data.statements[statement_index];
+                if let Some(sink) = try_sink(
+                    body,
+                    def_use_analysis,
+                    liveness,
+                    &borrowed.locals,
+                    location,
+                    statement,
+                    &touched,
+                ) {
+                    sinks.push(sink);
+                    break;
+                }
+                touched.visit_statement(statement, location);
+            }
+        }
+    }
+    sinks
+}
+
+fn try_sink(
+    body: ReadOnlyBodyAndCache<'_, 'tcx>,
+    def_use_analysis: &DefUseAnalysis,
+    liveness: &LivenessResult,
+    borrowed: &BitSet<Local>,
+    location: Location,
+    statement: &Statement<'tcx>,
+    touched: &LocalsTouched,
+) -> Option<Sink> {
+    let (local, rvalue) = match &statement.kind {
+        StatementKind::Assign(box (place, rvalue)) => (place.as_local()?, rvalue),
+        _ => return None,
+    };
+    match body.local_kind(local) {
+        LocalKind::Temp | LocalKind::Var => {}
+        LocalKind::Arg | LocalKind::ReturnPointer => return None,
+    }
+    if touched.locals.contains_key(&local) || borrowed.contains(local) {
+        return None;
+    }
+    if !rvalue_side_effects(rvalue).can_sink() {
+        return None;
+    }
+    let info = def_use_analysis.local_info(local);
+    if info.def_count() != 1 {
+        return None;
+    }
+
+    // The local must be live into a single successor, which only we can reach.
+    let mut live_successors = body[location.block]
+        .terminator()
+        .successors()
+        .filter(|&&succ| is_live_on_entry(body, liveness, succ, local));
+    let target = *live_successors.next()?;
+    if live_successors.any(|&succ| succ != target)
+        || target == location.block
+        || body.predecessors_for(target).iter().any(|&pred| pred != location.block)
+        || body[target].is_cleanup
+    {
+        return None;
+    }
+
+    // The operands must not change between the statement and the end of the block.
+    let mut reads = RvalueReads { locals: vec![], indirect: false };
+    reads.visit_rvalue(rvalue, location);
+    if reads.indirect && touched.writes_memory {
+        return None;
+    }
+    let mut storage_deads = vec![];
+    for read in reads.locals {
+        if borrowed.contains(read) {
+            return None;
+        }
+        match touched.locals.get(&read) {
+            None => {}
+            // A temporary only used here may die right after the statement instead.
+            Some(&Touched::StorageDead(index))
+                if def_use_analysis.local_info(read).use_count() == 1 =>
+            {
+                storage_deads.push(index)
+            }
+            Some(_) => return None,
+        }
+    }
+
+    let storage_live = body[location.block].statements[..location.statement_index]
+        .iter()
+        .rposition(|statement| match statement.kind {
+            StatementKind::StorageLive(live) => live == local,
+            _ => false,
+        });
+    Some(Sink { from: location, to: target, storage_live, storage_deads })
+}
+
+/// Whether `local` is live at the start of `block`, from the accesses in `block` and the locals
+/// live out of it.
+fn is_live_on_entry(
+    body: ReadOnlyBodyAndCache<'_, '_>,
+    liveness: &LivenessResult,
+    block: BasicBlock,
+    local: Local,
+) -> bool {
+    let bb_data = &body[block];
+    let mut access = LocalAccess { local, used: false, defined: false };
+    for (statement_index, statement) in bb_data.statements.iter().enumerate() {
+        access.visit_statement(statement, Location { block, statement_index });
+        if access.used || access.defined {
+            return access.used;
+        }
+    }
+    access.visit_terminator(bb_data.terminator(), body.terminator_loc(block));
+    if access.used || access.defined {
+        return access.used;
+    }
+    liveness.outs[block].contains(local)
+}
+
+fn apply_sink(body: &mut BodyAndCache<'_>, sink: Sink) {
+    let Sink { from, to, storage_live, storage_deads } = sink;
+    let statements = &mut body.basic_blocks_mut()[from.block].statements;
+
+    // Remove the statements back to front, so that the indices stay valid.
+    let mut indices: Vec<_> = storage_live.into_iter().chain(Some(from.statement_index)).collect();
+    indices.extend(storage_deads);
+    indices.sort();
+    indices.dedup();
+    let mut moved: Vec<_> = indices.iter().rev().map(|&index| statements.remove(index)).collect();
+
+    // In order, the moved statements are the `StorageLive`, the assignment and the
+    // `StorageDead`s.
+    moved.reverse();
+    body.basic_blocks_mut()[to].statements.splice(0..0, moved);
+}
+
+#[derive(Copy, Clone)]
+enum Touched {
+    /// The local is only touched by a `StorageDead` at the given statement index.
+    StorageDead(usize),
+    Other,
+}
+
+struct LocalsTouched {
+    locals: FxHashMap<Local, Touched>,
+    /// Whether a statement we scanned writes through a pointer or is inline assembly.
+    writes_memory: bool,
+}
+
+impl Visitor<'_> for LocalsTouched {
+    fn visit_statement(&mut self, statement: &Statement<'_>, location: Location) {
+        self.writes_memory |= statement_side_effects(statement) >= SideEffects::WritesMemory;
+        self.super_statement(statement, location);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, location: Location) {
+        let touched = match context {
+            PlaceContext::NonUse(NonUseContext::StorageDead)
+                if !self.locals.contains_key(&local) =>
+            {
+                Touched::StorageDead(location.statement_index)
+            }
+            _ => Touched::Other,
+        };
+        self.locals.insert(local, touched);
+    }
+}
+
+/// Collects the locals an rvalue reads.
+struct RvalueReads {
+    locals: Vec<Local>,
+    /// Whether the rvalue reads through a pointer.
+    indirect: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for RvalueReads {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        self.indirect |= place.is_indirect();
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self, &local: &Local, _: PlaceContext, _: Location) {
+        self.locals.push(local);
+    }
+}
+
+/// Whether a statement or terminator reads or writes a local. Uses win over definitions, as the
+/// operands are evaluated before the result is written.
+struct LocalAccess {
+    local: Local,
+    used: bool,
+    defined: bool,
+}
+
+impl Visitor<'_> for LocalAccess {
+    fn visit_statement(&mut self, statement: &Statement<'_>, location: Location) {
+        match &statement.kind {
+            // The visitor sees all asm outputs as definitions, but the outputs that are read as
+            // well, and the indirect ones, use the local.
+            StatementKind::InlineAsm(asm) => {
+                self.used |= asm.reads_local(self.local);
+                self.defined |= asm.writes_local(self.local);
+            }
+            _ => self.super_statement(statement, location),
+        }
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if local == self.local {
+            match liveness::categorize(context) {
+                Some(DefUse::Def) => self.defined = true,
+                Some(DefUse::Use) | Some(DefUse::Drop) => self.used = true,
+                None => {}
+            }
+        }
+    }
+}
+
+/// Collects the locals whose address is taken anywhere in the body, which may be read or written
+/// through a pointer.
+struct BorrowedLocals {
+    locals: BitSet<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        match rvalue {
+            Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) if !place.is_indirect() => {
+                self.locals.insert(place.local);
+            }
+            _ => {}
+        }
+        self.super_rvalue(rvalue, location);
+    }
+}
@@ -96,7 +96,7 @@ impl<'tcx> MirPass<'tcx> for UninhabitedEnumBranching {
 
             trace!("allowed_variants = {:?}", allowed_variants);
 
-            if let TerminatorKind::SwitchInt { values, targets, .. } =
+            if let TerminatorKind::SwitchInt { values, targets, target_weights, .. } =
                 &mut body.basic_blocks_mut()[bb].terminator_mut().kind
             {
                 let vals = &*values;
@@ -104,19 +104,25 @@ impl<'tcx> MirPass<'tcx> for UninhabitedEnumBranching {
 
                 let mut matched_values = Vec::with_capacity(allowed_variants.len());
                 let mut matched_targets = Vec::with_capacity(allowed_variants.len() + 1);
+                let mut matched_indices = Vec::with_capacity(allowed_variants.len() + 1);
 
-                for (val, target) in zipped {
+                for (i, (val, target)) in zipped.enumerate() {
                     if allowed_variants.contains(val) {
                         matched_values.push(*val);
                         matched_targets.push(*target);
+                        matched_indices.push(i);
                     } else {
                         trace!("eliminating {:?} -> {:?}", val, target);
                     }
                 }
 
                 // handle the "otherwise" branch
+                matched_indices.push(targets.len() - 1);
                 matched_targets.push(targets.pop().unwrap());
 
+                if let Some(weights) = target_weights {
+                    *weights = matched_indices.iter().map(|&i| weights[i]).collect();
+                }
                 *values = matched_values.into();
                 *targets = matched_targets;
             } else {
@@ -5,53 +5,54 @@
 use crate::transform::simplify;
 use crate::transform::{MirPass, MirSource};
 use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
 use rustc::ty::TyCtxt;
-use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
 use std::borrow::Cow;
 
 pub struct UnreachablePropagation;
 
 impl MirPass<'_> for UnreachablePropagation {
-    fn run_pass<'tcx>(&self, tcx: TyCtxt<'tcx>, _: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
-        if tcx.sess.opts.debugging_opts.mir_opt_level < 3 {
-            // Enable only under -Zmir-opt-level=3 as in some cases (check the deeply-nested-opt
-            // perf benchmark) LLVM may spend quite a lot of time optimizing the generated code.
-            return;
-        }
+    fn risk(&self) -> MirPassRisk {
+        // In some cases (check the deeply-nested-opt perf benchmark) LLVM may spend quite a lot
+        // of time optimizing the generated code.
+        MirPassRisk::UnsoundKnownIssues
+    }
 
+    fn run_pass<'tcx>(&self, _: TyCtxt<'tcx>, _: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
         let mut unreachable_blocks = FxHashSet::default();
-        let mut replacements = FxHashMap::default();
+        let mut replacements = FxIndexMap::default();
 
         for (bb, bb_data) in traversal::postorder(body) {
             let terminator = bb_data.terminator();
-            // HACK: If the block contains any asm statement it is not regarded as unreachable.
-            // This is a temporary solution that handles possibly diverging asm statements.
+            // `NormalizeUnreachable` leaves the statements of the unreachable blocks only if they
+            // contain an asm statement with side effects, which could diverge.
             // Accompanying testcases: mir-opt/unreachable_asm.rs and mir-opt/unreachable_asm_2.rs
-            let asm_stmt_in_block = || {
-                bb_data.statements.iter().any(|stmt: &Statement<'_>| match stmt.kind {
-                    StatementKind::InlineAsm(..) => true,
-                    _ => false,
-                })
-            };
-
-            if terminator.kind == TerminatorKind::Unreachable && !asm_stmt_in_block() {
+            if bb_data.is_empty_unreachable() {
                 unreachable_blocks.insert(bb);
             } else {
                 let is_unreachable = |succ: BasicBlock| unreachable_blocks.contains(&succ);
                 let terminator_kind_opt = remove_successors(&terminator.kind, is_unreachable);
 
                 if let Some(terminator_kind) = terminator_kind_opt {
-                    if terminator_kind == TerminatorKind::Unreachable && !asm_stmt_in_block() {
+                    let unreachable = terminator_kind == TerminatorKind::Unreachable
+                        && !bb_data.statements_may_diverge();
+                    if unreachable {
                         unreachable_blocks.insert(bb);
                     }
-                    replacements.insert(bb, terminator_kind);
+                    replacements.insert(bb, (terminator_kind, unreachable));
                 }
             }
         }
 
         let replaced = !replacements.is_empty();
-        for (bb, terminator_kind) in replacements {
-            body.basic_blocks_mut()[bb].terminator_mut().kind = terminator_kind;
+        for (bb, (terminator_kind, unreachable)) in replacements {
+            let bb_data = &mut body.basic_blocks_mut()[bb];
+            bb_data.terminator_mut().kind = terminator_kind;
+            if unreachable {
+                // Keep the unreachable blocks normalized.
+                bb_data.statements.clear();
+            }
         }
 
         if replaced {
@@ -69,19 +70,28 @@ where
 {
     match *terminator_kind {
         TerminatorKind::Goto { target } if predicate(target) => Some(TerminatorKind::Unreachable),
-        TerminatorKind::SwitchInt { ref discr, switch_ty, ref values, ref targets } => {
+        TerminatorKind::SwitchInt {
+            ref discr,
+            switch_ty,
+            ref values,
+            ref targets,
+            ref target_weights,
+        } => {
             let original_targets_len = targets.len();
             let (otherwise, targets) = targets.split_last().unwrap();
             let retained = values
                 .iter()
                 .zip(targets.iter())
-                .filter(|(_, &t)| !predicate(t))
+                .enumerate()
+                .filter(|(_, (_, &t))| !predicate(t))
                 .collect::<Vec<_>>();
-            let mut values = retained.iter().map(|&(v, _)| *v).collect::<Vec<_>>();
-            let mut targets = retained.iter().map(|&(_, d)| *d).collect::<Vec<_>>();
+            let mut values = retained.iter().map(|&(_, (v, _))| *v).collect::<Vec<_>>();
+            let mut targets = retained.iter().map(|&(_, (_, d))| *d).collect::<Vec<_>>();
+            let mut kept = retained.iter().map(|&(i, _)| i).collect::<Vec<_>>();
 
             if !predicate(*otherwise) {
                 targets.push(*otherwise);
+                kept.push(original_targets_len - 1);
             } else {
                 values.pop();
             }
@@ -93,11 +103,15 @@ where
             } else if targets.len() == 1 {
                 Some(TerminatorKind::Goto { target: targets[0] })
             } else if original_targets_len != retained_targets_len {
+                let target_weights = target_weights
+                    .as_ref()
+                    .map(|weights| kept.iter().map(|&i| weights[i]).collect());
                 Some(TerminatorKind::SwitchInt {
                     discr: discr.clone(),
                     switch_ty,
                     values: Cow::from(values),
                     targets,
+                    target_weights,
                 })
             } else {
                 None
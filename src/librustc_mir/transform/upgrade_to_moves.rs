@@ -0,0 +1,205 @@
+//! Turns the copies of locals that are the last use of the local into moves:
+//!
+//! ```rust
+//! _2 = [const 0u8; 4096];
+//! _3 = const consume(_2) -> bb1;  // `_2` is dead after the call
+//! ```
+//!
+//! becomes
+//!
+//! ```rust
+//! _2 = [const 0u8; 4096];
+//! _3 = const consume(move _2) -> bb1;
+//! ```
+//!
+//! MIR building copies the values of `Copy` types even at their last use. Codegen can pass a moved
+//! local to a call in place instead of copying it to a temporary, which matters for large values.
+//! Locals whose address is taken are left alone, as they may still be read through a pointer.
+
+use crate::transform::{MirPass, MirSource};
+use crate::util::liveness::{self, DefUse, LiveVarSet};
+use rustc::mir::visit::{
+    MutVisitor, MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor,
+};
+use rustc::mir::*;
+use rustc::session::config::MirPassRisk;
+use rustc::ty::TyCtxt;
+use rustc_index::bit_set::BitSet;
+
+pub struct UpgradeToMoves;
+
+impl<'tcx> MirPass<'tcx> for UpgradeToMoves {
+    fn risk(&self) -> MirPassRisk {
+        MirPassRisk::Experimental
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        body.ensure_predecessors();
+        let upgrades = final_copies(body.unwrap_read_only());
+
+        for (location, local) in upgrades {
+            if !tcx.consider_optimizing(|| {
+                format!("UpgradeToMoves {:?} {:?}", source.def_id(), location)
+            }) {
+                return;
+            }
+            mir_trace!("moving {:?} at {:?}", local, location);
+            let mut upgrade = Upgrade { tcx, local };
+            let bb_data = &mut body.basic_blocks_mut()[location.block];
+            if location.statement_index < bb_data.statements.len() {
+                let statement = &mut bb_data.statements[location.statement_index];
+                upgrade.visit_statement(statement, location);
+            } else {
+                upgrade.visit_terminator(bb_data.terminator_mut(), location);
+            }
+        }
+    }
+}
+
+/// Returns the locations copying a local for the last time, with the local copied.
+fn final_copies(body: ReadOnlyBodyAndCache<'_, 'tcx>) -> Vec<(Location, Local)> {
+    let mut borrowed = BitSet::new_empty(body.local_decls.len());
+    BorrowedLocals { borrowed: &mut borrowed }.visit_body(body);
+    let liveness = liveness::liveness_of_locals(body);
+
+    let mut upgrades = vec![];
+    for (bb, bb_data) in body.basic_blocks().iter_enumerated() {
+        let mut live = liveness.outs[bb].clone();
+        let terminator_location = body.terminator_loc(bb);
+        let accesses = Accesses::of_terminator(bb_data.terminator(), terminator_location);
+        if let TerminatorKind::Call { .. } = bb_data.terminator().kind {
+            let copies = accesses.last_copies(&live, &borrowed);
+            upgrades.extend(copies.map(|local| (terminator_location, local)));
+        }
+        accesses.apply(&mut live);
+        for (statement_index, statement) in bb_data.statements.iter().enumerate().rev() {
+            let location = Location { block: bb, statement_index };
+            let mut accesses = Accesses::default();
+            accesses.visit_statement(statement, location);
+            if let StatementKind::Assign(..) = statement.kind {
+                let copies = accesses.last_copies(&live, &borrowed);
+                upgrades.extend(copies.map(|local| (location, local)));
+            }
+            accesses.apply(&mut live);
+        }
+    }
+    upgrades
+}
+
+/// The accesses to locals made by a statement or terminator.
+#[derive(Default)]
+struct Accesses {
+    /// Each local accessed, with how.
+    accesses: Vec<(Local, PlaceContext)>,
+}
+
+impl Accesses {
+    fn of_terminator(terminator: &Terminator<'_>, location: Location) -> Self {
+        let mut accesses = Accesses::default();
+        accesses.visit_terminator(terminator, location);
+        accesses
+    }
+
+    /// Returns the locals copied for the last time: not live after the access, not borrowed, and
+    /// accessed only once there, so that the move cannot overlap another access.
+    fn last_copies<'a>(
+        &'a self,
+        live_after: &'a LiveVarSet,
+        borrowed: &'a BitSet<Local>,
+    ) -> impl Iterator<Item = Local> + 'a {
+        self.accesses.iter().filter_map(move |&(local, context)| match context {
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy)
+                if !live_after.contains(local)
+                    && !borrowed.contains(local)
+                    && self.accesses.iter().filter(|&&(other, _)| other == local).count() == 1 =>
+            {
+                Some(local)
+            }
+            _ => None,
+        })
+    }
+
+    /// Updates the live locals after the access to the ones live before it.
+    fn apply(&self, live: &mut LiveVarSet) {
+        // Within a single location, uses happen before definitions.
+        for &(local, context) in &self.accesses {
+            if let Some(DefUse::Def) = liveness::categorize(context) {
+                live.remove(local);
+            }
+        }
+        for &(local, context) in &self.accesses {
+            match liveness::categorize(context) {
+                Some(DefUse::Use) | Some(DefUse::Drop) => {
+                    live.insert(local);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Visitor<'tcx> for Accesses {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        // Only the copies of whole locals are upgraded.
+        let context = if place.projection.is_empty() {
+            context
+        } else if context.is_mutating_use() {
+            PlaceContext::MutatingUse(MutatingUseContext::Projection)
+        } else {
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::Projection)
+        };
+        self.accesses.push((place.local, context));
+        for elem in place.projection.iter() {
+            if let ProjectionElem::Index(index) = elem {
+                let context = PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy);
+                self.visit_local(index, context, location);
+            }
+        }
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        self.accesses.push((local, context));
+    }
+}
+
+struct BorrowedLocals<'a> {
+    borrowed: &'a mut BitSet<Local>,
+}
+
+impl Visitor<'_> for BorrowedLocals<'_> {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        match context {
+            PlaceContext::MutatingUse(MutatingUseContext::Borrow)
+            | PlaceContext::MutatingUse(MutatingUseContext::AddressOf)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::SharedBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::ShallowBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::UniqueBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::AddressOf) => {
+                self.borrowed.insert(local);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Turns the copy of `local` into a move.
+struct Upgrade<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    local: Local,
+}
+
+impl<'tcx> MutVisitor<'tcx> for Upgrade<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        if let Operand::Copy(place) = operand {
+            if place.as_local() == Some(self.local) {
+                *operand = Operand::Move(place.clone());
+                return;
+            }
+        }
+        self.super_operand(operand, location);
+    }
+}
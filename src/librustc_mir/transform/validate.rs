@@ -0,0 +1,91 @@
+//! Checks that a body only contains the statements and terminators allowed in its `MirPhase`,
+//! which catches the passes run in the wrong order, or adding what an earlier phase removed.
+//! The pass manager runs the check after every pass in debug builds.
+
+use crate::transform::MirSource;
+use rustc::mir::visit::Visitor;
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+
+/// Checks that `body`, in the phase `phase` after the pass `pass_name`, only uses what is allowed
+/// in that phase.
+pub fn validate_phase<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    source: MirSource<'tcx>,
+    pass_name: &str,
+    body: &Body<'tcx>,
+    phase: MirPhase,
+) {
+    let mut validator = PhaseValidator { tcx, source, pass_name, body, phase };
+    for (bb, data) in body.basic_blocks().iter_enumerated() {
+        validator.visit_basic_block_data(bb, data);
+    }
+}
+
+struct PhaseValidator<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    source: MirSource<'tcx>,
+    pass_name: &'a str,
+    body: &'a Body<'tcx>,
+    phase: MirPhase,
+}
+
+impl PhaseValidator<'_, 'tcx> {
+    /// Reports `what` at `source_info`, along with the passes that synthesized or rewrote the code
+    /// there, which are the first suspects.
+    fn fail(&self, source_info: SourceInfo, what: &str) {
+        let passes = self.body.source_scope_passes(source_info.scope);
+        let rewritten_by = if passes.is_empty() {
+            String::new()
+        } else {
+            let passes: Vec<_> = passes.iter().map(|pass| pass.to_string()).collect();
+            format!(" (in code rewritten by {})", passes.join(" after "))
+        };
+        span_bug!(
+            source_info.span,
+            "{} in `{}` after {}, which is not allowed in phase {:?}{}",
+            what,
+            self.tcx.def_path_str(self.source.def_id()),
+            self.pass_name,
+            self.phase,
+            rewritten_by,
+        );
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for PhaseValidator<'_, 'tcx> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        let source_info = statement.source_info;
+        match &statement.kind {
+            StatementKind::FakeRead(..) if self.phase >= MirPhase::DropsLowered => {
+                self.fail(source_info, "`FakeRead`")
+            }
+            StatementKind::AscribeUserType(..) if self.phase >= MirPhase::DropsLowered => {
+                self.fail(source_info, "`AscribeUserType`")
+            }
+            StatementKind::Assign(box (_, Rvalue::Ref(_, BorrowKind::Shallow, _)))
+                if self.phase >= MirPhase::DropsLowered =>
+            {
+                self.fail(source_info, "a shallow borrow")
+            }
+            StatementKind::Assume(..) if self.phase < MirPhase::DropsLowered => {
+                self.fail(source_info, "`Assume`")
+            }
+            _ => {}
+        }
+        self.super_statement(statement, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        if self.phase >= MirPhase::DropsLowered {
+            let source_info = terminator.source_info;
+            match &terminator.kind {
+                TerminatorKind::DropAndReplace { .. } => self.fail(source_info, "`DropAndReplace`"),
+                TerminatorKind::FalseEdges { .. } => self.fail(source_info, "`FalseEdges`"),
+                TerminatorKind::FalseUnwind { .. } => self.fail(source_info, "`FalseUnwind`"),
+                _ => {}
+            }
+        }
+        self.super_terminator(terminator, location);
+    }
+}
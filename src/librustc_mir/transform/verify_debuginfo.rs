@@ -0,0 +1,124 @@
+//! Implements `-Z verify-debuginfo-mir`, which warns about user variables whose `VarDebugInfo`
+//! stops describing their value, e.g. because it refers to a local that was removed, or to a
+//! local that is no longer written. The check runs after every pass, so that each degradation is
+//! attributed to the pass that caused it.
+
+use crate::transform::MirSource;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc_ast::ast::Name;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_index::bit_set::BitSet;
+use rustc_span::Span;
+
+pub struct DebugInfoVerifier {
+    /// The variables already known to have lost their debuginfo, identified by name and span,
+    /// as passes may reorder `var_debug_info`.
+    lost: FxHashSet<(Name, Span)>,
+}
+
+impl DebugInfoVerifier {
+    pub fn new(tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &Body<'tcx>) -> Self {
+        let lost = lost_debuginfo(tcx, source, body)
+            .map(|(var, _)| (var.name, var.source_info.span))
+            .collect();
+        DebugInfoVerifier { lost }
+    }
+
+    /// Warns about the variables that lost their debuginfo during the pass `pass`.
+    pub fn check_after_pass(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        source: MirSource<'tcx>,
+        pass: &str,
+        body: &Body<'tcx>,
+    ) {
+        for (var, loss) in lost_debuginfo(tcx, source, body) {
+            if !self.lost.insert((var.name, var.source_info.span)) {
+                continue;
+            }
+            let problem = match loss {
+                Loss::RemovedLocal => "no longer exists",
+                Loss::NeverWritten => "is never written",
+            };
+            let message = format!(
+                "debuginfo for `{}` refers to `{:?}`, which {}",
+                var.name, var.value, problem
+            );
+            let note = format!(
+                "first seen after the MIR pass `{}` on `{}`",
+                pass,
+                tcx.def_path_str(source.def_id())
+            );
+            tcx.sess.struct_span_warn(var.source_info.span, &message).note(&note).emit();
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Loss {
+    /// The local does not exist anymore.
+    RemovedLocal,
+    /// The local is not an argument, and nothing writes to it.
+    NeverWritten,
+}
+
+/// Returns the user variables whose debuginfo cannot describe their value anymore, and only
+/// considers the variables that are in scope of some code, as the debugger can't show the
+/// others anyway.
+fn lost_debuginfo<'a, 'tcx>(
+    tcx: TyCtxt<'tcx>,
+    source: MirSource<'tcx>,
+    body: &'a Body<'tcx>,
+) -> impl Iterator<Item = (&'a VarDebugInfo<'tcx>, Loss)> + 'a {
+    let mut collector = LocalAndScopeCollector {
+        written: BitSet::new_empty(body.local_decls.len()),
+        scopes: BitSet::new_empty(body.source_scopes.len()),
+    };
+    for (bb, data) in body.basic_blocks().iter_enumerated() {
+        collector.visit_basic_block_data(bb, data);
+    }
+    for arg in body.args_iter() {
+        collector.written.insert(arg);
+    }
+    let LocalAndScopeCollector { written, scopes } = collector;
+    let param_env = tcx.param_env(source.def_id());
+
+    body.var_debug_info.iter().filter_map(move |var| {
+        // Constants are always available.
+        let local = var.value.place()?.local;
+        let visible = scopes.iter().any(|scope| body.is_sub_scope(scope, var.source_info.scope));
+        if !visible {
+            return None;
+        }
+        if local.index() >= body.local_decls.len() {
+            return Some((var, Loss::RemovedLocal));
+        }
+        // Zero-sized variables don't need to be written to have their value.
+        let ty = body.local_decls[local].ty;
+        let is_zst = tcx.layout_of(param_env.and(ty)).map_or(false, |layout| layout.is_zst());
+        if !written.contains(local) && !is_zst {
+            return Some((var, Loss::NeverWritten));
+        }
+        None
+    })
+}
+
+/// Collects the locals written to, and the scopes used by statements and terminators.
+struct LocalAndScopeCollector {
+    written: BitSet<Local>,
+    scopes: BitSet<SourceScope>,
+}
+
+impl Visitor<'tcx> for LocalAndScopeCollector {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if context.is_mutating_use() {
+            self.written.insert(local);
+        }
+    }
+
+    fn visit_source_info(&mut self, source_info: &SourceInfo) {
+        self.scopes.insert(source_info.scope);
+    }
+}
@@ -34,6 +34,30 @@ where
     }
 }
 
+/// Returns the indices of the `Deref`s in the projection of `place` that read their pointer from
+/// a misaligned address, e.g. the `Deref` of a `Box` that is a field of a packed struct.
+pub fn disaligned_derefs<'tcx, L>(
+    tcx: TyCtxt<'tcx>,
+    local_decls: &L,
+    param_env: ty::ParamEnv<'tcx>,
+    place: &Place<'tcx>,
+) -> Vec<usize>
+where
+    L: HasLocalDecls<'tcx>,
+{
+    place
+        .projection
+        .iter()
+        .enumerate()
+        .filter(|&(index, elem)| {
+            let projection = tcx.intern_place_elems(&place.projection[..index]);
+            let pointer = Place { local: place.local, projection };
+            *elem == ProjectionElem::Deref && is_disaligned(tcx, local_decls, param_env, &pointer)
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
 fn is_within_packed<'tcx, L>(tcx: TyCtxt<'tcx>, local_decls: &L, place: &Place<'tcx>) -> bool
 where
     L: HasLocalDecls<'tcx>,
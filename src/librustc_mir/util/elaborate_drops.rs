@@ -537,6 +537,7 @@ where
                     switch_ty: discr_ty,
                     values: From::from(values.to_owned()),
                     targets: blocks,
+                    target_weights: None,
                 },
             }),
             is_cleanup: unwind.is_cleanup(),
@@ -725,6 +726,7 @@ where
                         self.drop_loop_pair(ety, false, len.clone()),
                         self.drop_loop_pair(ety, true, len.clone()),
                     ],
+                    target_weights: None,
                 },
             }),
         };
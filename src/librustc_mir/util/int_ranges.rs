@@ -0,0 +1,688 @@
+//! A forward analysis computing the range of values each integer local may hold.
+//!
+//! Ranges come from constants, from the discriminants of the variants of enums, from arithmetic on
+//! other ranges, and from the edges of the branches testing a local, either directly with
+//! `switchInt` or through a comparison:
+//!
+//! ```rust
+//! _3 = Lt(_1, const 10u8);
+//! switchInt(move _3) -> [false: bb2, otherwise: bb1];  // `_1` is in `0..=9` in `bb1`
+//! ```
+//!
+//! The comparisons in `assume` statements refine the ranges in the same way, for the rest of the
+//! block. The lengths of arrays are known as well.
+//!
+//! The results of checked operations are tracked as well, as their value is only used when the
+//! operation didn't overflow. Copies are remembered for as long as both locals hold the same
+//! value, so that testing the copy refines the original, as in the comparison of `_4 = _1`.
+//!
+//! Locals whose address is taken are not tracked, and neither are `u128` ones, whose values don't
+//! fit the signed bounds used here. Ranges that keep growing around loops are widened to the
+//! bounds of their type, which ensures termination.
+
+use rustc::mir::interpret::sign_extend;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc::ty::layout::Size;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_ast::ast;
+use rustc_data_structures::work_queue::WorkQueue;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use std::fmt;
+
+#[cfg(test)]
+mod tests;
+
+/// After how many changes of the entry state of a block its growing ranges are widened.
+const WIDENING_DELAY: usize = 3;
+
+/// An inclusive range of integers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IntRange {
+    pub lo: i128,
+    pub hi: i128,
+}
+
+impl IntRange {
+    pub fn singleton(value: i128) -> Self {
+        IntRange { lo: value, hi: value }
+    }
+
+    /// Returns the range of the values of `ty`, or `None` if it's not a tracked integer type.
+    pub fn of_type(tcx: TyCtxt<'_>, ty: Ty<'_>) -> Option<Self> {
+        let (size, signed) = int_size_and_signed(tcx, ty)?;
+        if signed {
+            let hi = i128::MAX >> (128 - size.bits());
+            Some(IntRange { lo: -hi - 1, hi })
+        } else {
+            Some(IntRange { lo: 0, hi: i128::MAX >> (127 - size.bits()) })
+        }
+    }
+
+    pub fn contains(&self, value: i128) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    pub fn contains_range(&self, other: IntRange) -> bool {
+        self.lo <= other.lo && other.hi <= self.hi
+    }
+
+    pub fn as_singleton(&self) -> Option<i128> {
+        if self.lo == self.hi { Some(self.lo) } else { None }
+    }
+
+    pub fn intersect(self, other: IntRange) -> Option<Self> {
+        let range = IntRange { lo: self.lo.max(other.lo), hi: self.hi.min(other.hi) };
+        if range.lo <= range.hi { Some(range) } else { None }
+    }
+
+    pub fn hull(self, other: IntRange) -> Self {
+        IntRange { lo: self.lo.min(other.lo), hi: self.hi.max(other.hi) }
+    }
+
+    /// Removes `value` from the range, which is only possible if it is one of the bounds.
+    fn without(self, value: i128) -> Option<Self> {
+        if self.as_singleton() == Some(value) {
+            None
+        } else if self.lo == value {
+            Some(IntRange { lo: value + 1, ..self })
+        } else if self.hi == value {
+            Some(IntRange { hi: value - 1, ..self })
+        } else {
+            Some(self)
+        }
+    }
+
+    /// The range of the result of `op`, or `None` if it can't be bounded with the operations on
+    /// `i128`. `lhs_bits` is the size of the type of the left operand. Whether the result fits
+    /// its type is not checked.
+    pub fn binary_op(op: BinOp, l: IntRange, r: IntRange, lhs_bits: u64) -> Option<Self> {
+        let from_bounds = |bounds: &[Option<i128>]| -> Option<Self> {
+            let mut range: Option<IntRange> = None;
+            for &bound in bounds {
+                let bound = IntRange::singleton(bound?);
+                range = Some(range.map_or(bound, |range| range.hull(bound)));
+            }
+            range
+        };
+        match op {
+            BinOp::Add => from_bounds(&[l.lo.checked_add(r.lo), l.hi.checked_add(r.hi)]),
+            BinOp::Sub => from_bounds(&[l.lo.checked_sub(r.hi), l.hi.checked_sub(r.lo)]),
+            BinOp::Mul => from_bounds(&[
+                l.lo.checked_mul(r.lo),
+                l.lo.checked_mul(r.hi),
+                l.hi.checked_mul(r.lo),
+                l.hi.checked_mul(r.hi),
+            ]),
+            BinOp::Div if l.lo >= 0 && r.lo > 0 => {
+                Some(IntRange { lo: l.lo / r.hi, hi: l.hi / r.lo })
+            }
+            BinOp::Rem if l.lo >= 0 && r.lo > 0 => {
+                Some(IntRange { lo: 0, hi: l.hi.min(r.hi - 1) })
+            }
+            BinOp::BitAnd if l.lo >= 0 && r.lo >= 0 => {
+                Some(IntRange { lo: 0, hi: l.hi.min(r.hi) })
+            }
+            // Shifting by the size of the type or more doesn't shift by that amount.
+            BinOp::Shr if l.lo >= 0 && r.lo >= 0 && r.hi < lhs_bits as i128 => {
+                Some(IntRange { lo: l.lo >> r.hi, hi: l.hi >> r.lo })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the size and signedness of the tracked integer type `ty`.
+fn int_size_and_signed(tcx: TyCtxt<'_>, ty: Ty<'_>) -> Option<(Size, bool)> {
+    let pointer_size = tcx.data_layout.pointer_size;
+    let bits = |bit_width: Option<usize>| {
+        bit_width.map_or(pointer_size, |bits| Size::from_bits(bits as u64))
+    };
+    match ty.kind {
+        ty::Int(ity) => Some((bits(ity.bit_width()), true)),
+        ty::Uint(uty) if uty != ast::UintTy::U128 => Some((bits(uty.bit_width()), false)),
+        _ => None,
+    }
+}
+
+impl fmt::Display for IntRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.lo, self.hi)
+    }
+}
+
+/// A side of a comparison.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Term {
+    Local(Local),
+    Const(i128),
+}
+
+/// A comparison whose result was assigned to a local.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Condition {
+    result: Local,
+    op: BinOp,
+    lhs: Term,
+    rhs: Term,
+}
+
+/// What is known about the integer locals at some point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeState {
+    /// The range of each tracked local, `None` for the others.
+    ranges: IndexVec<Local, Option<IntRange>>,
+    /// The local each local was copied from, while both still hold the same value.
+    copy_of: IndexVec<Local, Option<Local>>,
+    /// The last comparison assigned to a local, while neither side was changed.
+    condition: Option<Condition>,
+}
+
+impl RangeState {
+    /// Returns the range of the tracked local `local`.
+    pub fn local(&self, local: Local) -> Option<IntRange> {
+        self.ranges[local]
+    }
+
+    /// Joins `other` into `self`, widening the ranges that grow to the bounds of their type if
+    /// `widen` is set. Returns whether `self` changed.
+    fn join(
+        &mut self,
+        other: &RangeState,
+        widen: bool,
+        tracked: &IndexVec<Local, Option<IntRange>>,
+    ) -> bool {
+        let mut changed = false;
+        for (local, range) in self.ranges.iter_enumerated_mut() {
+            let (old, new) = match (*range, other.ranges[local]) {
+                (Some(old), Some(new)) => (old, new),
+                _ => continue,
+            };
+            let mut joined = old.hull(new);
+            if widen && joined != old {
+                let full = tracked[local].unwrap();
+                if joined.lo < old.lo {
+                    joined.lo = full.lo;
+                }
+                if joined.hi > old.hi {
+                    joined.hi = full.hi;
+                }
+            }
+            if joined != old {
+                *range = Some(joined);
+                changed = true;
+            }
+        }
+        for (local, copy_of) in self.copy_of.iter_enumerated_mut() {
+            if copy_of.is_some() && *copy_of != other.copy_of[local] {
+                *copy_of = None;
+                changed = true;
+            }
+        }
+        if self.condition.is_some() && self.condition != other.condition {
+            self.condition = None;
+            changed = true;
+        }
+        changed
+    }
+}
+
+/// The integer ranges of the locals of a body, computed once for all its locations.
+pub struct IntRanges<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    /// The range of the type of each tracked local, or of its first field for the results of
+    /// checked operations.
+    tracked: IndexVec<Local, Option<IntRange>>,
+    /// The state on entry to each block, or `None` if it is unreachable.
+    entry_states: IndexVec<BasicBlock, Option<RangeState>>,
+}
+
+impl<'a, 'tcx> IntRanges<'a, 'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>, body: &'a Body<'tcx>, param_env: ty::ParamEnv<'tcx>) -> Self {
+        let mut borrowed = BitSet::new_empty(body.local_decls.len());
+        for data in body.basic_blocks() {
+            for statement in &data.statements {
+                if let StatementKind::Assign(box (_, Rvalue::Ref(_, _, place)))
+                | StatementKind::Assign(box (_, Rvalue::AddressOf(_, place))) = &statement.kind
+                {
+                    borrowed.insert(place.local);
+                }
+            }
+        }
+        let tracked = body
+            .local_decls
+            .iter_enumerated()
+            .map(|(local, decl)| {
+                if borrowed.contains(local) {
+                    return None;
+                }
+                match decl.ty.kind {
+                    // The results of checked operations.
+                    ty::Tuple(tys) if tys.len() == 2 && tys.type_at(1).is_bool() => {
+                        IntRange::of_type(tcx, tys.type_at(0))
+                    }
+                    _ => IntRange::of_type(tcx, decl.ty),
+                }
+            })
+            .collect::<IndexVec<Local, _>>();
+
+        let mut ranges = IntRanges {
+            tcx,
+            param_env,
+            body,
+            entry_states: IndexVec::from_elem(None, body.basic_blocks()),
+            tracked,
+        };
+        ranges.iterate_to_fixpoint();
+        ranges
+    }
+
+    fn iterate_to_fixpoint(&mut self) {
+        let body = self.body;
+        self.entry_states[START_BLOCK] = Some(RangeState {
+            ranges: self.tracked.clone(),
+            copy_of: IndexVec::from_elem(None, &body.local_decls),
+            condition: None,
+        });
+        let mut changes = IndexVec::from_elem(0, body.basic_blocks());
+        let mut dirty_queue: WorkQueue<BasicBlock> =
+            WorkQueue::with_none(body.basic_blocks().len());
+        dirty_queue.insert(START_BLOCK);
+        while let Some(bb) = dirty_queue.pop() {
+            let mut state = self.entry_states[bb].clone().unwrap();
+            for statement in &body[bb].statements {
+                self.apply_statement(&mut state, statement);
+            }
+            for (target, edge_state) in self.terminator_edges(state, body[bb].terminator()) {
+                let changed = match &mut self.entry_states[target] {
+                    entry @ None => {
+                        *entry = Some(edge_state);
+                        true
+                    }
+                    Some(entry) => {
+                        let widen = changes[target] >= WIDENING_DELAY;
+                        entry.join(&edge_state, widen, &self.tracked)
+                    }
+                };
+                if changed {
+                    changes[target] += 1;
+                    dirty_queue.insert(target);
+                }
+            }
+        }
+    }
+
+    /// Returns the state right before `location`, or `None` if it is unreachable.
+    pub fn state_before(&self, location: Location) -> Option<RangeState> {
+        let mut state = self.entry_states[location.block].clone()?;
+        for statement in &self.body[location.block].statements[..location.statement_index] {
+            self.apply_statement(&mut state, statement);
+        }
+        Some(state)
+    }
+
+    /// Returns the range of `place` in `state`, or `None` if it isn't an integer.
+    pub fn place_range(&self, state: &RangeState, place: &Place<'tcx>) -> Option<IntRange> {
+        let tracked = match place.as_ref() {
+            PlaceRef { local, projection: &[] } => state.ranges[local],
+            // The value of a checked operation.
+            PlaceRef { local, projection: &[ProjectionElem::Field(field, _)] }
+                if field.index() == 0 =>
+            {
+                state.ranges[local]
+            }
+            _ => None,
+        };
+        tracked.or_else(|| IntRange::of_type(self.tcx, place.ty(self.body, self.tcx).ty))
+    }
+
+    /// Returns the range of `operand` in `state`, or `None` if it isn't an integer.
+    pub fn operand_range(&self, state: &RangeState, operand: &Operand<'tcx>) -> Option<IntRange> {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) => self.place_range(state, place),
+            Operand::Constant(constant) => {
+                let ty = constant.literal.ty;
+                let full = IntRange::of_type(self.tcx, ty)?;
+                match constant.literal.try_eval_bits(self.tcx, self.param_env, ty) {
+                    Some(bits) => Some(IntRange::singleton(self.bits_to_i128(bits, ty)?)),
+                    None => Some(full),
+                }
+            }
+        }
+    }
+
+    /// Whether the comparison in `operand` evaluates to `holds` whenever `state` holds.
+    pub fn condition_is(&self, state: &RangeState, operand: &Operand<'tcx>, holds: bool) -> bool {
+        !self.refine_condition(&mut state.clone(), operand, !holds)
+    }
+
+    /// Converts the bits of a value of the integer type `ty`.
+    pub fn bits_to_i128(&self, bits: u128, ty: Ty<'tcx>) -> Option<i128> {
+        match int_size_and_signed(self.tcx, ty)? {
+            (size, true) => Some(sign_extend(bits, size) as i128),
+            (_, false) => Some(bits as i128),
+        }
+    }
+
+    fn rvalue_range(&self, state: &RangeState, rvalue: &Rvalue<'tcx>) -> Option<IntRange> {
+        match rvalue {
+            Rvalue::Use(operand) | Rvalue::Cast(CastKind::Misc, operand, _) => {
+                self.operand_range(state, operand)
+            }
+            Rvalue::BinaryOp(op, l, r) | Rvalue::CheckedBinaryOp(op, l, r) => {
+                let (size, _) = int_size_and_signed(self.tcx, l.ty(self.body, self.tcx))?;
+                let l = self.operand_range(state, l)?;
+                let r = self.operand_range(state, r)?;
+                IntRange::binary_op(*op, l, r, size.bits())
+            }
+            Rvalue::Len(place) => match place.ty(self.body, self.tcx).ty.kind {
+                ty::Array(_, len) => {
+                    let len = len.try_eval_usize(self.tcx, self.param_env)?;
+                    Some(IntRange::singleton(len as i128))
+                }
+                _ => None,
+            },
+            Rvalue::Discriminant(place) => match place.ty(self.body, self.tcx).ty.kind {
+                ty::Adt(adt_def, _) if adt_def.is_enum() => {
+                    let mut range: Option<IntRange> = None;
+                    for (_, discr) in adt_def.discriminants(self.tcx) {
+                        let value = IntRange::singleton(self.bits_to_i128(discr.val, discr.ty)?);
+                        range = Some(range.map_or(value, |range| range.hull(value)));
+                    }
+                    range
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn apply_statement(&self, state: &mut RangeState, statement: &Statement<'tcx>) {
+        let (place, rvalue) = match &statement.kind {
+            StatementKind::Assign(box (place, rvalue)) => (place, rvalue),
+            // The program guarantees that the assumed condition holds.
+            StatementKind::Assume(box cond) => {
+                self.refine_condition(state, cond, true);
+                return;
+            }
+            _ => {
+                ForgetWritten { ranges: self, state }.visit_statement(statement, Location::START);
+                return;
+            }
+        };
+        let value = self.rvalue_range(state, rvalue);
+        let copied = match rvalue {
+            Rvalue::Use(Operand::Copy(from)) | Rvalue::Use(Operand::Move(from)) => {
+                from.as_local().filter(|&from| self.tracked[from].is_some())
+            }
+            _ => None,
+        };
+        let condition = self.condition(state, rvalue);
+        ForgetWritten { ranges: self, state }.visit_statement(statement, Location::START);
+
+        let local = match place.as_local() {
+            Some(local) => local,
+            None => return,
+        };
+        if let Some(full) = self.tracked[local] {
+            let range = match (rvalue, value) {
+                // Overflowing makes the operation panic.
+                (Rvalue::CheckedBinaryOp(..), Some(value)) => value.intersect(full),
+                (_, Some(value)) if full.contains_range(value) => Some(value),
+                _ => None,
+            };
+            state.ranges[local] = Some(range.unwrap_or(full));
+            if let Some(from) = copied {
+                if from != local {
+                    state.copy_of[local] = Some(state.copy_of[from].unwrap_or(from));
+                }
+            }
+        }
+        if let Some(condition) = condition {
+            state.condition = Some(Condition { result: local, ..condition });
+        }
+    }
+
+    /// Returns the comparison made by `rvalue`, with a dummy result local.
+    fn condition(&self, state: &RangeState, rvalue: &Rvalue<'tcx>) -> Option<Condition> {
+        let (op, lhs, rhs) = match rvalue {
+            Rvalue::BinaryOp(op @ BinOp::Lt, l, r)
+            | Rvalue::BinaryOp(op @ BinOp::Le, l, r)
+            | Rvalue::BinaryOp(op @ BinOp::Gt, l, r)
+            | Rvalue::BinaryOp(op @ BinOp::Ge, l, r)
+            | Rvalue::BinaryOp(op @ BinOp::Eq, l, r)
+            | Rvalue::BinaryOp(op @ BinOp::Ne, l, r) => (*op, l, r),
+            _ => return None,
+        };
+        let term = |operand: &Operand<'tcx>| match operand {
+            Operand::Copy(place) | Operand::Move(place) => {
+                let local = place.as_local().filter(|&local| state.ranges[local].is_some())?;
+                Some(Term::Local(state.copy_of[local].unwrap_or(local)))
+            }
+            Operand::Constant(_) => {
+                Some(Term::Const(self.operand_range(state, operand)?.as_singleton()?))
+            }
+        };
+        match (term(lhs)?, term(rhs)?) {
+            (Term::Const(_), Term::Const(_)) => None,
+            (lhs, rhs) => Some(Condition { result: RETURN_PLACE, op, lhs, rhs }),
+        }
+    }
+
+    /// Returns the state on each outgoing edge of `terminator`, dropping the unreachable ones.
+    fn terminator_edges(
+        &self,
+        mut state: RangeState,
+        terminator: &Terminator<'tcx>,
+    ) -> Vec<(BasicBlock, RangeState)> {
+        match &terminator.kind {
+            TerminatorKind::SwitchInt { discr, values, targets, .. } => targets
+                .iter()
+                .enumerate()
+                .filter_map(|(index, &target)| {
+                    let mut edge_state = state.clone();
+                    let value = values.get(index).copied();
+                    if self.refine_switch(&mut edge_state, discr, values, value) {
+                        Some((target, edge_state))
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+            TerminatorKind::Assert { cond, expected, target, cleanup, .. } => {
+                let mut edge_state = state.clone();
+                let mut edges = vec![];
+                if self.refine_condition(&mut edge_state, cond, *expected) {
+                    edges.push((*target, edge_state));
+                }
+                edges.extend((*cleanup).map(|cleanup| (cleanup, state)));
+                edges
+            }
+            _ => {
+                ForgetWritten { ranges: self, state: &mut state }
+                    .visit_terminator(terminator, Location::START);
+                terminator.successors().map(|&target| (target, state.clone())).collect()
+            }
+        }
+    }
+
+    /// Refines `state` on the edge of a switch on `discr` taken for `value`, or for none of
+    /// `values`. Returns whether the edge can be taken.
+    fn refine_switch(
+        &self,
+        state: &mut RangeState,
+        discr: &Operand<'tcx>,
+        values: &[u128],
+        value: Option<u128>,
+    ) -> bool {
+        let local = match discr.place().and_then(|place| place.as_local()) {
+            Some(local) => local,
+            None => return true,
+        };
+        if state.ranges[local].is_some() {
+            let ty = self.body.local_decls[local].ty;
+            return match value {
+                Some(value) => match self.bits_to_i128(value, ty) {
+                    Some(value) => self.refine_local(state, local, |range| {
+                        range.intersect(IntRange::singleton(value))
+                    }),
+                    None => true,
+                },
+                None => values.iter().filter_map(|&value| self.bits_to_i128(value, ty)).all(
+                    |value| self.refine_local(state, local, |range| range.without(value)),
+                ),
+            };
+        }
+        // A switch on a boolean only has a `0` (false) or `1` (true) value besides `otherwise`.
+        let holds = match (value, values) {
+            (Some(value), _) => value != 0,
+            (None, [0]) => true,
+            (None, [1]) => false,
+            _ => return true,
+        };
+        self.refine_condition(state, discr, holds)
+    }
+
+    /// Refines `state` for the comparison in `operand` evaluating to `holds`. Returns whether
+    /// this is possible.
+    fn refine_condition(
+        &self,
+        state: &mut RangeState,
+        operand: &Operand<'tcx>,
+        holds: bool,
+    ) -> bool {
+        let local = match operand.place().and_then(|place| place.as_local()) {
+            Some(local) => local,
+            None => return true,
+        };
+        let Condition { op, lhs, rhs, .. } = match state.condition {
+            Some(condition) if condition.result == local => condition,
+            _ => return true,
+        };
+        let op = if holds {
+            op
+        } else {
+            match op {
+                BinOp::Lt => BinOp::Ge,
+                BinOp::Le => BinOp::Gt,
+                BinOp::Gt => BinOp::Le,
+                BinOp::Ge => BinOp::Lt,
+                BinOp::Eq => BinOp::Ne,
+                _ => BinOp::Eq,
+            }
+        };
+        // Only `<`, `<=`, `==` and `!=` are left.
+        let (op, lhs, rhs) = match op {
+            BinOp::Gt => (BinOp::Lt, rhs, lhs),
+            BinOp::Ge => (BinOp::Le, rhs, lhs),
+            _ => (op, lhs, rhs),
+        };
+
+        let range = |term| match term {
+            Term::Local(local) => state.ranges[local].unwrap(),
+            Term::Const(value) => IntRange::singleton(value),
+        };
+        let (l, r) = (range(lhs), range(rhs));
+        let refined = || -> Option<(IntRange, IntRange)> {
+            match op {
+                BinOp::Lt => {
+                    let l = l.intersect(IntRange { lo: i128::MIN, hi: r.hi.checked_sub(1)? })?;
+                    let r = r.intersect(IntRange { lo: l.lo.checked_add(1)?, hi: i128::MAX })?;
+                    Some((l, r))
+                }
+                BinOp::Le => {
+                    let l = l.intersect(IntRange { lo: i128::MIN, hi: r.hi })?;
+                    let r = r.intersect(IntRange { lo: l.lo, hi: i128::MAX })?;
+                    Some((l, r))
+                }
+                BinOp::Eq => l.intersect(r).map(|range| (range, range)),
+                _ => {
+                    let l = match r.as_singleton() {
+                        Some(value) => l.without(value)?,
+                        None => l,
+                    };
+                    let r = match l.as_singleton() {
+                        Some(value) => r.without(value)?,
+                        None => r,
+                    };
+                    Some((l, r))
+                }
+            }
+        };
+        let (l, r) = match refined() {
+            Some(refined) => refined,
+            None => return false,
+        };
+        for &(term, refined) in &[(lhs, l), (rhs, r)] {
+            if let Term::Local(local) = term {
+                if !self.refine_local(state, local, |range| range.intersect(refined)) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Refines the range of `local`, and of the locals holding the same value. Returns whether
+    /// the range is still non-empty.
+    fn refine_local(
+        &self,
+        state: &mut RangeState,
+        local: Local,
+        refine: impl Fn(IntRange) -> Option<IntRange>,
+    ) -> bool {
+        let original = state.copy_of[local].unwrap_or(local);
+        let range = match (state.ranges[original], state.ranges[local]) {
+            (Some(original_range), Some(range)) => {
+                original_range.intersect(range).and_then(&refine)
+            }
+            _ => return true,
+        };
+        let range = match range {
+            Some(range) => range,
+            None => return false,
+        };
+        for other in state.ranges.indices() {
+            if other == original || state.copy_of[other] == Some(original) {
+                state.ranges[other] = Some(range);
+            }
+        }
+        true
+    }
+
+    /// Forgets everything known about `local`.
+    fn forget(&self, state: &mut RangeState, local: Local) {
+        state.ranges[local] = self.tracked[local];
+        state.copy_of[local] = None;
+        for copy_of in state.copy_of.iter_mut() {
+            if *copy_of == Some(local) {
+                *copy_of = None;
+            }
+        }
+        if let Some(condition) = state.condition {
+            if condition.result == local
+                || condition.lhs == Term::Local(local)
+                || condition.rhs == Term::Local(local)
+            {
+                state.condition = None;
+            }
+        }
+    }
+}
+
+/// Forgets the ranges of the locals written to.
+struct ForgetWritten<'b, 'a, 'tcx> {
+    ranges: &'b IntRanges<'a, 'tcx>,
+    state: &'b mut RangeState,
+}
+
+impl Visitor<'_> for ForgetWritten<'_, '_, '_> {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if context.is_mutating_use() || context.is_storage_marker() {
+            self.ranges.forget(self.state, local);
+        }
+    }
+}
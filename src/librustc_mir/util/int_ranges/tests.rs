@@ -0,0 +1,28 @@
+use rustc::mir::BinOp;
+
+use super::*;
+
+fn range(lo: i128, hi: i128) -> IntRange {
+    IntRange { lo, hi }
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(IntRange::binary_op(BinOp::Add, range(0, 10), range(1, 2), 8), Some(range(1, 12)));
+    assert_eq!(IntRange::binary_op(BinOp::Sub, range(0, 10), range(1, 2), 8), Some(range(-2, 9)));
+    assert_eq!(IntRange::binary_op(BinOp::Mul, range(-2, 3), range(4, 5), 8), Some(range(-10, 15)));
+    assert_eq!(IntRange::binary_op(BinOp::Add, range(0, i128::MAX), range(1, 1), 128), None);
+}
+
+#[test]
+fn shr() {
+    assert_eq!(
+        IntRange::binary_op(BinOp::Shr, range(16, 255), range(1, 4), 8),
+        Some(range(1, 127))
+    );
+    assert_eq!(IntRange::binary_op(BinOp::Shr, range(0, 255), range(0, 7), 8), Some(range(0, 255)));
+    // The shift amount is not below the number of bits of `u8`.
+    assert_eq!(IntRange::binary_op(BinOp::Shr, range(0, 255), range(0, 8), 8), None);
+    assert_eq!(IntRange::binary_op(BinOp::Shr, range(0, 255), range(8, 8), 8), None);
+    assert_eq!(IntRange::binary_op(BinOp::Shr, range(0, 255), range(-1, 2), 8), None);
+}
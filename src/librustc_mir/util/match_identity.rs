@@ -0,0 +1,303 @@
+//! Recognizes match arms of the form `Variant(x..) => Variant(x..)`.
+//!
+//! This is shared between the `SimplifyArmIdentity` optimization and the `redundant_match_arms`
+//! lint, so that the lint fires exactly for the arms the optimization can remove. It works on
+//! both unoptimized MIR, where the arm builds its result with an aggregate:
+//!
+//! ```rust
+//! _LOCAL_TMP = ((_LOCAL_1 as Variant).FIELD: TY);
+//! _LOCAL_TMP2 = _LOCAL_TMP;
+//! _LOCAL_0 = Enum::Variant(move _LOCAL_TMP2);
+//! ```
+//!
+//! and on deaggregated MIR:
+//!
+//! ```rust
+//! _LOCAL_TMP = ((_LOCAL_1 as Variant).FIELD: TY);
+//! ((_LOCAL_0 as Variant).FIELD: TY) = move _LOCAL_TMP;
+//! discriminant(_LOCAL_0) = VAR_IDX;
+//! ```
+//!
+//! Storage markers, fake reads and type ascriptions are not part of the pattern and are ignored.
+//! The temporaries are assumed not to be used outside of the arm's block.
+//!
+//! The fields may also go through the residue of an inlined identity closure, like the one passed
+//! to `Option::map`, which packs its argument into a tuple and unpacks it again:
+//!
+//! ```rust
+//! _LOCAL_TMP = ((_LOCAL_1 as Variant).FIELD: TY);
+//! _LOCAL_TUPLE = (move _LOCAL_TMP,);
+//! _LOCAL_TMP2 = move (_LOCAL_TUPLE.0: TY);
+//! _LOCAL_CLOSURE = move _LOCAL_ARG;
+//! _LOCAL_0 = Enum::Variant(move _LOCAL_TMP2);
+//! ```
+//!
+//! Moves between locals unrelated to the pattern, like the one of the closure above, are kept
+//! and reported as such. Calls returning their argument unchanged, which `is_identity_call`
+//! recognizes, are turned into moves by the `RemoveIdentityCalls` pass, so they end up in this
+//! form too.
+
+use rustc::mir::*;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_index::vec::{Idx, IndexVec};
+use rustc_span::symbol::sym;
+use rustc_target::abi::VariantIdx;
+
+/// A block that sets `dest` to the `variant` of its enum type, with all fields copied or moved
+/// from the same fields of `source`.
+#[derive(Debug)]
+pub struct ArmIdentity {
+    pub dest: Local,
+    /// The local the fields are taken from, or `None` if `variant` has no fields.
+    pub source: Option<Local>,
+    pub variant: VariantIdx,
+    /// The indices of the statements making up the pattern, in order.
+    pub statements: Vec<usize>,
+    /// The indices of the moves between other locals found in between.
+    pub unrelated: Vec<usize>,
+}
+
+/// Why a block is not an identity arm.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NotIdentity {
+    /// The statements don't have the shape of an identity arm.
+    Shape,
+    /// The arm rebuilds its destination from itself.
+    SameLocal,
+    /// The local the fields are taken from has a different type than the destination.
+    DifferentTypes,
+}
+
+impl NotIdentity {
+    pub fn description(self) -> &'static str {
+        match self {
+            NotIdentity::Shape => "the statements are not an identity arm",
+            NotIdentity::SameLocal => "source and target locals are the same",
+            NotIdentity::DifferentTypes => "source and target locals have different types",
+        }
+    }
+}
+
+/// Checks whether the statements of `block` are an identity arm. The terminator of `block` is
+/// not looked at.
+pub fn match_arm_identity<'tcx>(
+    local_decls: &IndexVec<Local, LocalDecl<'tcx>>,
+    block: &BasicBlockData<'tcx>,
+) -> Result<ArmIdentity, NotIdentity> {
+    let arm = match_arm_shape(local_decls, block).ok_or(NotIdentity::Shape)?;
+    if let Some(source) = arm.source {
+        // Avoid moving into ourselves.
+        if source == arm.dest {
+            return Err(NotIdentity::SameLocal);
+        }
+        // Source and target locals have the same type.
+        // FIXME(Centril | oli-obk): possibly relax to same layout?
+        if local_decls[source].ty != local_decls[arm.dest].ty {
+            return Err(NotIdentity::DifferentTypes);
+        }
+    }
+    Ok(arm)
+}
+
+/// Matches the statements of an identity arm, without checking that its source can replace its
+/// destination.
+fn match_arm_shape<'tcx>(
+    local_decls: &IndexVec<Local, LocalDecl<'tcx>>,
+    block: &BasicBlockData<'tcx>,
+) -> Option<ArmIdentity> {
+    // The variant fields the temporaries hold.
+    let mut temps = FxHashMap::<Local, VarField<'tcx>>::default();
+    // The variant fields the fields of temporary tuples hold.
+    let mut tuple_fields = FxHashMap::<(Local, Field), VarField<'tcx>>::default();
+    // The locals written or read by the unrelated moves.
+    let mut unrelated_locals = vec![];
+    // The variant fields assigned to the fields of `dest`.
+    let mut fields = FxHashMap::<Field, VarField<'tcx>>::default();
+    let mut dest = None;
+    let mut dest_variant = None;
+    let mut statements = vec![];
+    let mut unrelated = vec![];
+
+    for (index, statement) in block.statements.iter().enumerate() {
+        match &statement.kind {
+            StatementKind::StorageLive(_)
+            | StatementKind::StorageDead(_)
+            | StatementKind::FakeRead(..)
+            | StatementKind::AscribeUserType(..)
+            | StatementKind::Nop => continue,
+
+            // Nothing may be written after the discriminant of `dest` is set.
+            _ if dest_variant.is_some() => return None,
+
+            StatementKind::Assign(box (place, Rvalue::Use(Operand::Copy(from))))
+            | StatementKind::Assign(box (place, Rvalue::Use(Operand::Move(from)))) => {
+                if let Some(into) = place.as_local() {
+                    // `_LOCAL_TMP = ((_LOCAL_1 as Variant).FIELD: TY)`,
+                    // `_LOCAL_TMP2 = _LOCAL_TMP` or `_LOCAL_TMP2 = move (_LOCAL_TUPLE.0: TY)`.
+                    let value = match_variant_field_place(from)
+                        .or_else(|| temps.get(&from.as_local()?).copied())
+                        .or_else(|| tuple_fields.get(&match_tuple_field_place(from)?).copied());
+                    match value {
+                        Some(value) => {
+                            temps.insert(into, value);
+                        }
+                        None => {
+                            // `_LOCAL_CLOSURE = move _LOCAL_ARG`.
+                            unrelated_locals.extend(&[into, from.as_local()?]);
+                            unrelated.push(index);
+                            continue;
+                        }
+                    }
+                } else {
+                    // `((_LOCAL_0 as Variant).FIELD: TY) = move _LOCAL_TMP`.
+                    let target = match_variant_field_place(place)?;
+                    let value = match from.as_local().and_then(|tmp| temps.get(&tmp)) {
+                        // The same field of the same variant, read from another local.
+                        Some(&value) if VarField { local: target.local, ..value } == target => {
+                            value
+                        }
+                        _ => return None,
+                    };
+                    set_dest(&mut dest, target.local)?;
+                    if fields.insert(target.field, value).is_some() {
+                        return None;
+                    }
+                }
+            }
+
+            // `_LOCAL_0 = Enum::Variant(move _LOCAL_TMP2, ..)`.
+            StatementKind::Assign(box (
+                place,
+                Rvalue::Aggregate(box AggregateKind::Adt(_, var_idx, _, _, None), operands),
+            )) => {
+                set_dest(&mut dest, place.as_local()?)?;
+                for (i, operand) in operands.iter().enumerate() {
+                    let value = match operand.place().and_then(|p| p.as_local()) {
+                        Some(tmp) => *temps.get(&tmp)?,
+                        None => return None,
+                    };
+                    if value.var_idx != *var_idx || value.field != Field::new(i) {
+                        return None;
+                    }
+                    if fields.insert(value.field, value).is_some() {
+                        return None;
+                    }
+                }
+                dest_variant = Some(*var_idx);
+            }
+
+            // `_LOCAL_TUPLE = (move _LOCAL_TMP, ..)`.
+            StatementKind::Assign(box (
+                place,
+                Rvalue::Aggregate(box AggregateKind::Tuple, operands),
+            )) => {
+                let tuple = place.as_local()?;
+                for (i, operand) in operands.iter().enumerate() {
+                    let value = *temps.get(&operand.place()?.as_local()?)?;
+                    tuple_fields.insert((tuple, Field::new(i)), value);
+                }
+            }
+
+            // `discriminant(_LOCAL_0) = VAR_IDX`.
+            StatementKind::SetDiscriminant { place, variant_index } => {
+                set_dest(&mut dest, place.as_local()?)?;
+                dest_variant = Some(*variant_index);
+            }
+
+            _ => return None,
+        }
+        statements.push(index);
+    }
+
+    let dest = dest?;
+    let variant = dest_variant?;
+    let mut source = None;
+    for value in fields.values() {
+        if value.var_idx != variant || *source.get_or_insert(value.local) != value.local {
+            return None;
+        }
+    }
+
+    // All fields of the variant must have been set.
+    let dest_ty = local_decls[dest].ty;
+    let variant_fields = match dest_ty.kind {
+        ty::Adt(adt_def, _) if adt_def.is_enum() => adt_def.variants[variant].fields.len(),
+        _ => return None,
+    };
+    if fields.len() != variant_fields {
+        return None;
+    }
+
+    // The unrelated moves must stay out of the way of the pattern.
+    for local in unrelated_locals {
+        if local == dest
+            || Some(local) == source
+            || temps.contains_key(&local)
+            || tuple_fields.keys().any(|&(tuple, _)| tuple == local)
+        {
+            return None;
+        }
+    }
+
+    Some(ArmIdentity { dest, source, variant, statements, unrelated })
+}
+
+/// Records `local` as the destination of the arm, failing if the arm already writes to a
+/// different local.
+fn set_dest(dest: &mut Option<Local>, local: Local) -> Option<()> {
+    if *dest.get_or_insert(local) == local { Some(()) } else { None }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct VarField<'tcx> {
+    local: Local,
+    field: Field,
+    field_ty: Ty<'tcx>,
+    var_idx: VariantIdx,
+}
+
+/// Match on `(_LOCAL.FIELD: TY)`.
+fn match_tuple_field_place(place: &Place<'_>) -> Option<(Local, Field)> {
+    match place.as_ref() {
+        PlaceRef { local, projection: &[ProjectionElem::Field(field, _)] } => Some((local, field)),
+        _ => None,
+    }
+}
+
+/// Match on `((_LOCAL as Variant).FIELD: TY)`.
+fn match_variant_field_place<'tcx>(place: &Place<'tcx>) -> Option<VarField<'tcx>> {
+    match place.as_ref() {
+        PlaceRef {
+            local,
+            projection: &[ProjectionElem::Downcast(_, var_idx), ProjectionElem::Field(field, ty)],
+        } => Some(VarField { local, field, field_ty: ty, var_idx }),
+        _ => None,
+    }
+}
+
+/// Checks whether calling `func` returns its argument unchanged. These are `convert::identity`,
+/// and the `From::from` and `Into::into` conversions of a type into itself, which coherence
+/// guarantees to be the reflexive impls of the standard library, even for generic types.
+pub fn is_identity_call<'tcx>(tcx: TyCtxt<'tcx>, func: &Operand<'tcx>) -> bool {
+    let (def_id, substs) = match func {
+        Operand::Constant(constant) => match constant.literal.ty.kind {
+            ty::FnDef(def_id, substs) => (def_id, substs),
+            _ => return false,
+        },
+        _ => return false,
+    };
+    if tcx.is_diagnostic_item(sym::convert_identity, def_id) {
+        return true;
+    }
+    match tcx.trait_of_item(def_id) {
+        Some(trait_def_id)
+            if tcx.is_diagnostic_item(sym::from_trait, trait_def_id)
+                || tcx.is_diagnostic_item(sym::into_trait, trait_def_id) =>
+        {
+            // `<U as From<T>>::from` and `<T as Into<U>>::into`.
+            substs.len() == 2 && substs.type_at(0) == substs.type_at(1)
+        }
+        _ => false,
+    }
+}
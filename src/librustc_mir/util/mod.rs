@@ -7,11 +7,19 @@ pub mod patch;
 mod alignment;
 pub mod collect_writes;
 mod graphviz;
+pub mod int_ranges;
 pub mod liveness;
+pub mod match_identity;
+pub mod pass_trace;
+pub mod ssa;
 pub(crate) mod pretty;
+pub mod remarks;
+pub mod side_effects;
+mod spanview;
 
 pub use self::aggregate::expand_aggregate;
-pub use self::alignment::is_disaligned;
+pub use self::alignment::{disaligned_derefs, is_disaligned};
 pub use self::graphviz::write_node_label as write_graphviz_node_label;
 pub use self::graphviz::{graphviz_safe_def_name, write_mir_graphviz};
 pub use self::pretty::{dump_enabled, dump_mir, write_mir_pretty, PassWhere};
+pub use self::spanview::write_mir_fn_spanview;
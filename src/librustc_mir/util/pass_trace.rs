@@ -0,0 +1,90 @@
+//! Traces the decisions of the MIR passes selected with `-Z trace-mir-pass`.
+//!
+//! The pass manager enters a span for each pass it runs on a body, and the passes can enter a
+//! nested span for the block they are looking at with `enter_block`. The events logged with
+//! `mir_trace!` in these spans are printed to stderr, prefixed by the pass, the item and the
+//! block, when the filter selects the pass and the item. The events are also logged at the
+//! `debug` level, as any other log message.
+//!
+//! The filter has the same syntax as the one of `-Z dump-mir`, e.g.
+//! `-Z trace-mir-pass='SimplifyCfg & main'`.
+
+use crate::transform::MirSource;
+use crate::util::pretty;
+use rustc::mir::BasicBlock;
+use rustc::ty::TyCtxt;
+use std::cell::RefCell;
+use std::fmt;
+
+/// The pass being traced, and the block it is looking at.
+struct Span {
+    pass: String,
+    item: String,
+    block: Option<BasicBlock>,
+}
+
+thread_local! {
+    /// The span of the innermost pass being run, if it is traced. Passes can run passes on other
+    /// bodies, through the queries they use.
+    static CURRENT: RefCell<Option<Span>> = RefCell::new(None);
+}
+
+/// Runs `f`, which runs the pass `pass_name` on `source`, in the span of that pass.
+pub fn in_pass<R>(
+    tcx: TyCtxt<'tcx>,
+    pass_name: &str,
+    source: MirSource<'tcx>,
+    f: impl FnOnce() -> R,
+) -> R {
+    let span = match &tcx.sess.opts.debugging_opts.trace_mir_pass {
+        Some(filters) if pretty::filter_matches(tcx, filters, pass_name, source) => Some(Span {
+            pass: pass_name.to_string(),
+            item: pretty::item_path(tcx, source),
+            block: None,
+        }),
+        _ => None,
+    };
+    let outer = CURRENT.with(|current| current.replace(span));
+    let result = f();
+    CURRENT.with(|current| *current.borrow_mut() = outer);
+    result
+}
+
+/// Enters the span of `block` in the span of the current pass, until the guard is dropped.
+pub fn enter_block(block: BasicBlock) -> BlockGuard {
+    let outer = CURRENT.with(|current| {
+        current.borrow_mut().as_mut().map(|span| span.block.replace(block))
+    });
+    BlockGuard { outer }
+}
+
+#[must_use]
+pub struct BlockGuard {
+    /// The block of the outer span, if the current pass is traced.
+    outer: Option<Option<BasicBlock>>,
+}
+
+impl Drop for BlockGuard {
+    fn drop(&mut self) {
+        if let Some(outer) = self.outer {
+            CURRENT.with(|current| {
+                if let Some(span) = current.borrow_mut().as_mut() {
+                    span.block = outer;
+                }
+            });
+        }
+    }
+}
+
+/// Logs `args` in the current span, see `mir_trace!`.
+pub fn event(args: fmt::Arguments<'_>) {
+    debug!("{}", args);
+    CURRENT.with(|current| {
+        if let Some(span) = &*current.borrow() {
+            match span.block {
+                Some(block) => eprintln!("[{} {} {:?}] {}", span.pass, span.item, block, args),
+                None => eprintln!("[{} {}] {}", span.pass, span.item, args),
+            }
+        }
+    });
+}
@@ -1,4 +1,5 @@
 use super::graphviz::write_mir_fn_graphviz;
+use super::spanview::write_mir_fn_spanview;
 use crate::transform::MirSource;
 use rustc::mir::visit::Visitor;
 use rustc::mir::*;
@@ -49,10 +50,13 @@ pub enum PassWhere {
 /// where `<filter>` takes the following forms:
 ///
 /// - `all` -- dump MIR for all fns, all passes, all everything
-/// - a filter defined by a set of substrings combined with `&` and `|`
+/// - a filter defined by a set of terms combined with `&` and `|`
 ///   (`&` has higher precedence). At least one of the `|`-separated groups
 ///   must match; an `|`-separated group matches if all of its `&`-separated
-///   substrings are matched.
+///   terms are matched. A term is a substring of the name, a glob pattern
+///   matching the whole name, or a kind of body (see `filter_matches`).
+/// - a `,`-separated list of such filters, those starting with `!` excluding
+///   what they match.
 ///
 /// Example:
 ///
@@ -62,6 +66,8 @@ pub enum PassWhere {
 ///   or `typeck` appears in the name.
 /// - `foo & nll | bar & typeck` == match if `foo` and `nll` both appear in the name
 ///   or `typeck` and `bar` both appear in the name.
+/// - `mycrate::parser::*::parse_expr,!*test*` == match the functions named `parse_expr` in the
+///   modules of `parser`, unless `test` appears in the name.
 pub fn dump_mir<'tcx, F>(
     tcx: TyCtxt<'tcx>,
     pass_num: Option<&dyn Display>,
@@ -94,18 +100,104 @@ pub fn dump_mir<'tcx, F>(
 }
 
 pub fn dump_enabled<'tcx>(tcx: TyCtxt<'tcx>, pass_name: &str, source: MirSource<'tcx>) -> bool {
-    let filters = match tcx.sess.opts.debugging_opts.dump_mir {
-        None => return false,
-        Some(ref filters) => filters,
+    match tcx.sess.opts.debugging_opts.dump_mir {
+        None => false,
+        Some(ref filters) => filter_matches(tcx, filters, pass_name, source),
+    }
+}
+
+/// Whether a filter in the syntax of `-Z dump-mir` selects the pass `pass_name` on `source`.
+///
+/// The filter is a `,`-separated list of selections, and of exclusions starting with `!`. It
+/// matches what a selection matches, or everything if there are only exclusions, unless an
+/// exclusion matches it. Each selection or exclusion is a `|`-separated list of alternatives,
+/// each a `&`-separated list of terms, which are one of:
+///
+/// - `all`, matching everything,
+/// - `kind=item`, `kind=promoted` or `kind=shim`, matching the bodies of that kind,
+/// - a glob pattern, containing `*` or `?`, matching the whole pass name, or the whole item path
+///   with or without its crate name,
+/// - or a substring of the pass name or of the item path.
+pub fn filter_matches<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    filters: &str,
+    pass_name: &str,
+    source: MirSource<'tcx>,
+) -> bool {
+    let node_path = item_path(tcx, source);
+    let crate_path = format!("{}::{}", tcx.crate_name(source.def_id().krate), node_path);
+    let kind = if source.promoted.is_some() {
+        "promoted"
+    } else if let ty::InstanceDef::Item(_) = source.instance {
+        "item"
+    } else {
+        "shim"
     };
-    let node_path = ty::print::with_forced_impl_filename_line(|| {
+    let term_matches = |term: &str| {
+        if term == "all" {
+            true
+        } else if term.starts_with("kind=") {
+            term["kind=".len()..] == *kind
+        } else if term.contains(|c| c == '*' || c == '?') {
+            glob_matches(term, pass_name)
+                || glob_matches(term, &node_path)
+                || glob_matches(term, &crate_path)
+        } else {
+            pass_name.contains(term) || node_path.contains(term)
+        }
+    };
+    let alternatives_match = |filter: &str| {
+        filter
+            .split('|')
+            .any(|or_filter| or_filter.split('&').all(|and_filter| term_matches(and_filter.trim())))
+    };
+
+    let (exclusions, selections): (Vec<&str>, Vec<&str>) = filters
+        .split(',')
+        .map(str::trim)
+        .filter(|filter| !filter.is_empty())
+        .partition(|filter| filter.starts_with('!'));
+    (selections.is_empty() || selections.iter().any(|filter| alternatives_match(filter)))
+        && !exclusions.iter().any(|filter| alternatives_match(&filter[1..]))
+}
+
+/// Whether `text` matches the glob `pattern`, where `*` matches any sequence of characters and
+/// `?` any single character.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // The position after the last `*` seen, and the position in `text` it matches up to.
+    let mut star = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            // Backtrack, letting the last `*` match one more character.
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// The path of the item of `source`, as matched by the filters.
+pub fn item_path<'tcx>(tcx: TyCtxt<'tcx>, source: MirSource<'tcx>) -> String {
+    ty::print::with_forced_impl_filename_line(|| {
         // see notes on #41697 below
         tcx.def_path_str(source.def_id())
-    });
-    filters.split('|').any(|or_filter| {
-        or_filter.split('&').all(|and_filter| {
-            and_filter == "all" || pass_name.contains(and_filter) || node_path.contains(and_filter)
-        })
     })
 }
 
@@ -148,6 +240,14 @@ fn dump_matched_mir_node<'tcx, F>(
             write_mir_fn_graphviz(tcx, source.def_id(), body, false, &mut file)?;
         };
     }
+
+    if let Some(spanview) = tcx.sess.opts.debugging_opts.dump_mir_spanview {
+        let _: io::Result<()> = try {
+            let mut file =
+                create_dump_file(tcx, "html", pass_num, pass_name, disambiguator, source)?;
+            write_mir_fn_spanview(tcx, source.def_id(), body, spanview, &mut file)?;
+        };
+    }
 }
 
 /// Returns the path to the filename where we should dump a given MIR.
@@ -443,7 +543,7 @@ fn write_scope_tree(
 
         let indented_debug_info = format!(
             "{0:1$}debug {2} => {3:?};",
-            INDENT, indent, var_debug_info.name, var_debug_info.place,
+            INDENT, indent, var_debug_info.name, var_debug_info.value,
         );
 
         writeln!(
@@ -496,7 +596,17 @@ fn write_scope_tree(
 
     for &child in children {
         assert_eq!(body.source_scopes[child].parent_scope, Some(parent));
-        writeln!(w, "{0:1$}scope {2} {{", "", indent, child.index())?;
+        match body.source_scopes[child].synthesized_by {
+            Some(pass) => writeln!(
+                w,
+                "{0:1$}scope {2} {{ // synthesized by {3}",
+                "",
+                indent,
+                child.index(),
+                pass
+            )?,
+            None => writeln!(w, "{0:1$}scope {2} {{", "", indent, child.index())?,
+        }
         write_scope_tree(tcx, body, scope_tree, w, child, depth + 1)?;
         writeln!(w, "{0:1$}}}", "", depth * INDENT.len())?;
     }
@@ -0,0 +1,39 @@
+//! Implements `-Z mir-opt-remarks`, through which MIR passes report the optimizations they apply,
+//! and the ones they miss along with the reason, as notes on the affected code. Tools can get the
+//! remarks as JSON by passing `--error-format=json`.
+
+use rustc::ty::TyCtxt;
+use rustc_span::Span;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RemarkKind {
+    Applied,
+    Missed,
+}
+
+/// Whether `-Z mir-opt-remarks` asks for the remarks of the pass named `pass`.
+pub fn remarks_enabled(tcx: TyCtxt<'_>, pass: &str) -> bool {
+    match &tcx.sess.opts.debugging_opts.mir_opt_remarks {
+        None => false,
+        Some(filter) => filter == "all" || filter.split(',').any(|name| name.trim() == pass),
+    }
+}
+
+/// Reports that `pass` applied or missed an optimization of the code at `span`. `message` is
+/// only called if the remarks of `pass` are enabled.
+pub fn emit_remark(
+    tcx: TyCtxt<'_>,
+    pass: &str,
+    kind: RemarkKind,
+    span: Span,
+    message: impl FnOnce() -> String,
+) {
+    if !remarks_enabled(tcx, pass) {
+        return;
+    }
+    let kind = match kind {
+        RemarkKind::Applied => "applied",
+        RemarkKind::Missed => "not applied",
+    };
+    tcx.sess.span_note_without_error(span, &format!("{} {}: {}", pass, kind, message()));
+}
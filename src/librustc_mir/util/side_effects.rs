@@ -0,0 +1,191 @@
+//! Classifies MIR statements, rvalues and terminators by the side effects of executing them, so
+//! that the passes removing, sinking or hoisting code agree on what they may touch.
+//!
+//! The classification is about executing the code: constants are values, although removing the
+//! last use of one that is not evaluated yet may hide an error, see `rvalue_is_removable`. It
+//! only looks at the MIR itself, not at the types involved.
+
+use rustc::mir::*;
+use rustc::ty;
+
+#[cfg(test)]
+mod tests;
+
+/// The side effects of executing a piece of MIR, ordered from the least to the most restrictive:
+/// each kind allows what the following ones allow.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SideEffects {
+    /// Only computes a value from its operands, which are locals or constants. It can be
+    /// removed, moved, or executed on more paths than it was.
+    Pure,
+    /// Computes a value from its operands, but is undefined behavior for some of them: dividing
+    /// by zero, shifting by too many bits, casting a float out of the range of an integer type,
+    /// indexing out of bounds, reading a field of a variant that is not the active one, or
+    /// borrowing a place that is dangling or misaligned, e.g. a field of a packed struct. It can
+    /// be removed, or moved to a subset of the paths it was executed on.
+    UbSensitive,
+    /// Also reads memory through a pointer, so its value depends on when it is executed. It can
+    /// be removed, or moved to a subset of the paths it was executed on if the memory it reads is
+    /// not written in between.
+    ReadOnly,
+    /// Writes memory through a pointer, or allocates it. It can't be removed, but doesn't affect
+    /// the control flow or anything outside of the memory of the program.
+    WritesMemory,
+    /// May not complete normally, or has effects outside of the memory of the program: calls,
+    /// panics, returns or inline assembly with side effects.
+    Divergent,
+}
+
+impl SideEffects {
+    /// Whether the code can be removed when its result is unused.
+    pub fn is_removable(self) -> bool {
+        self <= SideEffects::ReadOnly
+    }
+
+    /// Whether the code can be moved to a subset of the paths it was executed on. Code reading
+    /// memory additionally requires that the memory is not written in between.
+    pub fn can_sink(self) -> bool {
+        self <= SideEffects::ReadOnly
+    }
+
+    /// Whether the code can be executed on paths it wasn't executed on.
+    pub fn can_hoist(self) -> bool {
+        self == SideEffects::Pure
+    }
+}
+
+/// The side effects of reading `place`, or of computing its address.
+pub fn place_side_effects(place: &Place<'_>) -> SideEffects {
+    place
+        .projection
+        .iter()
+        .map(|elem| match elem {
+            ProjectionElem::Deref => SideEffects::ReadOnly,
+            ProjectionElem::Index(_)
+            | ProjectionElem::ConstantIndex { .. }
+            | ProjectionElem::Subslice { .. }
+            | ProjectionElem::Downcast(..) => SideEffects::UbSensitive,
+            ProjectionElem::Field(..) => SideEffects::Pure,
+        })
+        .max()
+        .unwrap_or(SideEffects::Pure)
+}
+
+pub fn operand_side_effects(operand: &Operand<'_>) -> SideEffects {
+    match operand {
+        Operand::Copy(place) | Operand::Move(place) => place_side_effects(place),
+        Operand::Constant(_) => SideEffects::Pure,
+    }
+}
+
+pub fn rvalue_side_effects(rvalue: &Rvalue<'_>) -> SideEffects {
+    match rvalue {
+        Rvalue::Use(operand) | Rvalue::Repeat(operand, _) | Rvalue::UnaryOp(_, operand) => {
+            operand_side_effects(operand)
+        }
+        // Casting a float that is out of the range of the integer type is UB.
+        Rvalue::Cast(_, operand, _) => SideEffects::UbSensitive.max(operand_side_effects(operand)),
+        Rvalue::BinaryOp(op, lhs, rhs) => {
+            let op = match op {
+                // Unchecked shifts by at least the number of bits of `lhs` are UB.
+                BinOp::Div | BinOp::Rem | BinOp::Offset | BinOp::Shl | BinOp::Shr => {
+                    SideEffects::UbSensitive
+                }
+                _ => SideEffects::Pure,
+            };
+            op.max(operand_side_effects(lhs)).max(operand_side_effects(rhs))
+        }
+        Rvalue::CheckedBinaryOp(_, lhs, rhs) => {
+            operand_side_effects(lhs).max(operand_side_effects(rhs))
+        }
+        Rvalue::Aggregate(_, operands) => {
+            operands.iter().map(operand_side_effects).max().unwrap_or(SideEffects::Pure)
+        }
+        Rvalue::Ref(_, _, place) => SideEffects::UbSensitive.max(place_side_effects(place)),
+        Rvalue::AddressOf(_, place) | Rvalue::Len(place) | Rvalue::Discriminant(place) => {
+            place_side_effects(place)
+        }
+        Rvalue::NullaryOp(NullOp::SizeOf, _) => SideEffects::Pure,
+        Rvalue::NullaryOp(NullOp::Box, _) => SideEffects::WritesMemory,
+    }
+}
+
+pub fn statement_side_effects(statement: &Statement<'_>) -> SideEffects {
+    match &statement.kind {
+        StatementKind::Assign(box (place, rvalue)) => {
+            assigned_place_side_effects(place).max(rvalue_side_effects(rvalue))
+        }
+        StatementKind::SetDiscriminant { place, .. } => assigned_place_side_effects(place),
+        StatementKind::Retag(..) => SideEffects::UbSensitive,
+        StatementKind::Assume(operand) => {
+            SideEffects::UbSensitive.max(operand_side_effects(operand))
+        }
+        StatementKind::InlineAsm(asm) if asm.has_side_effects() => SideEffects::Divergent,
+        // Asm only computing its outputs from its inputs may still trap, e.g. when dividing by
+        // zero, so it is at least `UbSensitive`.
+        StatementKind::InlineAsm(asm) => asm
+            .outputs
+            .iter()
+            .map(assigned_place_side_effects)
+            .chain(asm.inputs.iter().map(|(_, input)| operand_side_effects(input)))
+            .fold(SideEffects::UbSensitive, SideEffects::max),
+        StatementKind::FakeRead(..)
+        | StatementKind::StorageLive(_)
+        | StatementKind::StorageDead(_)
+        | StatementKind::AscribeUserType(..)
+        | StatementKind::Nop => SideEffects::Pure,
+    }
+}
+
+pub fn terminator_side_effects(terminator: &Terminator<'_>) -> SideEffects {
+    match &terminator.kind {
+        TerminatorKind::SwitchInt { discr, .. } => operand_side_effects(discr),
+        TerminatorKind::Goto { .. }
+        | TerminatorKind::FalseEdges { .. }
+        | TerminatorKind::FalseUnwind { .. } => SideEffects::Pure,
+        TerminatorKind::Unreachable => SideEffects::UbSensitive,
+        TerminatorKind::Resume
+        | TerminatorKind::Abort
+        | TerminatorKind::Return
+        | TerminatorKind::Drop { .. }
+        | TerminatorKind::DropAndReplace { .. }
+        | TerminatorKind::Call { .. }
+        | TerminatorKind::Assert { .. }
+        | TerminatorKind::Yield { .. }
+        | TerminatorKind::GeneratorDrop => SideEffects::Divergent,
+    }
+}
+
+/// Whether the assignment of `rvalue` to an unused local can be removed. Assignments from
+/// unevaluated constants are kept around, since the evaluation may report errors, even if the
+/// use of the constant is dead code.
+pub fn rvalue_is_removable(rvalue: &Rvalue<'_>) -> bool {
+    let is_evaluated = |operand: &Operand<'_>| match operand {
+        Operand::Constant(constant) => match constant.literal.val {
+            ty::ConstKind::Unevaluated(..) => false,
+            _ => true,
+        },
+        Operand::Copy(_) | Operand::Move(_) => true,
+    };
+    let operands_evaluated = match rvalue {
+        Rvalue::Use(operand)
+        | Rvalue::Repeat(operand, _)
+        | Rvalue::Cast(_, operand, _)
+        | Rvalue::UnaryOp(_, operand) => is_evaluated(operand),
+        Rvalue::BinaryOp(_, lhs, rhs) | Rvalue::CheckedBinaryOp(_, lhs, rhs) => {
+            is_evaluated(lhs) && is_evaluated(rhs)
+        }
+        Rvalue::Aggregate(_, operands) => operands.iter().all(is_evaluated),
+        Rvalue::Ref(..)
+        | Rvalue::AddressOf(..)
+        | Rvalue::Len(_)
+        | Rvalue::Discriminant(_)
+        | Rvalue::NullaryOp(..) => true,
+    };
+    operands_evaluated && rvalue_side_effects(rvalue).is_removable()
+}
+
+/// The side effects of writing to `place`, besides computing the value written.
+fn assigned_place_side_effects(place: &Place<'_>) -> SideEffects {
+    if place.is_indirect() { SideEffects::WritesMemory } else { place_side_effects(place) }
+}
@@ -0,0 +1,130 @@
+use rustc::mir::*;
+use rustc_ast::ast::{AsmDialect, StrStyle};
+use rustc_hir as hir;
+use rustc_index::vec::Idx;
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::DUMMY_SP;
+
+use super::*;
+
+fn operand(local: usize) -> Operand<'static> {
+    Operand::Copy(Place::from(Local::new(local)))
+}
+
+fn statement(kind: StatementKind<'static>) -> Statement<'static> {
+    Statement { source_info: SourceInfo { scope: OUTERMOST_SOURCE_SCOPE, span: DUMMY_SP }, kind }
+}
+
+fn terminator(kind: TerminatorKind<'static>) -> Terminator<'static> {
+    Terminator { source_info: SourceInfo { scope: OUTERMOST_SOURCE_SCOPE, span: DUMMY_SP }, kind }
+}
+
+#[test]
+fn arithmetic() {
+    let add = Rvalue::BinaryOp(BinOp::Add, operand(1), operand(2));
+    assert_eq!(rvalue_side_effects(&add), SideEffects::Pure);
+    let checked = Rvalue::CheckedBinaryOp(BinOp::Mul, operand(1), operand(2));
+    assert_eq!(rvalue_side_effects(&checked), SideEffects::Pure);
+
+    // Dividing by zero and shifting by too many bits are UB, so these must not be hoisted above
+    // the checks of the divisor or of the shift amount.
+    for &op in &[BinOp::Div, BinOp::Rem, BinOp::Offset, BinOp::Shl, BinOp::Shr] {
+        let rvalue = Rvalue::BinaryOp(op, operand(1), operand(2));
+        assert_eq!(rvalue_side_effects(&rvalue), SideEffects::UbSensitive);
+    }
+    let checked_shift = Rvalue::CheckedBinaryOp(BinOp::Shl, operand(1), operand(2));
+    assert_eq!(rvalue_side_effects(&checked_shift), SideEffects::Pure);
+}
+
+#[test]
+fn places() {
+    let local = Place::from(Local::new(1));
+    assert_eq!(rvalue_side_effects(&Rvalue::Len(local.clone())), SideEffects::Pure);
+    assert_eq!(rvalue_side_effects(&Rvalue::Discriminant(local.clone())), SideEffects::Pure);
+    assert_eq!(
+        rvalue_side_effects(&Rvalue::AddressOf(Mutability::Not, local.clone())),
+        SideEffects::Pure
+    );
+    let aggregate = Rvalue::Aggregate(Box::new(AggregateKind::Tuple), vec![operand(1), operand(2)]);
+    assert_eq!(rvalue_side_effects(&aggregate), SideEffects::Pure);
+}
+
+#[test]
+fn statements() {
+    let local = Place::from(Local::new(1));
+    let assign = StatementKind::Assign(box (local.clone(), Rvalue::Use(operand(2))));
+    assert_eq!(statement_side_effects(&statement(assign)), SideEffects::Pure);
+    let divide = Rvalue::BinaryOp(BinOp::Div, operand(2), operand(3));
+    let assign = StatementKind::Assign(box (local.clone(), divide));
+    assert_eq!(statement_side_effects(&statement(assign)), SideEffects::UbSensitive);
+
+    let storage_live = StatementKind::StorageLive(Local::new(1));
+    assert_eq!(statement_side_effects(&statement(storage_live)), SideEffects::Pure);
+    let retag = StatementKind::Retag(RetagKind::Default, box local);
+    assert_eq!(statement_side_effects(&statement(retag)), SideEffects::UbSensitive);
+    let assume = StatementKind::Assume(box operand(1));
+    assert_eq!(statement_side_effects(&statement(assume)), SideEffects::UbSensitive);
+}
+
+#[test]
+fn inline_asm() {
+    rustc_ast::attr::with_default_globals(|| {
+        let output = |constraint: &str, is_indirect| hir::InlineAsmOutput {
+            constraint: Symbol::intern(constraint),
+            is_rw: false,
+            is_indirect,
+            span: DUMMY_SP,
+        };
+        let asm = |outputs: Vec<hir::InlineAsmOutput>, inputs: &[&str], clobbers: &[Symbol]| {
+            let output_places = outputs.iter().map(|_| Place::from(Local::new(1))).collect();
+            let input_operands = inputs.iter().map(|_| (DUMMY_SP, operand(2))).collect();
+            statement(StatementKind::InlineAsm(box InlineAsm {
+                asm: hir::InlineAsmInner {
+                    asm: Symbol::intern("bswap $0"),
+                    asm_str_style: StrStyle::Cooked,
+                    outputs,
+                    inputs: inputs.iter().map(|&input| Symbol::intern(input)).collect(),
+                    clobbers: clobbers.to_vec(),
+                    volatile: false,
+                    alignstack: false,
+                    dialect: AsmDialect::Att,
+                },
+                outputs: output_places,
+                inputs: input_operands,
+            }))
+        };
+
+        // Asm computing its outputs from its inputs is removable, but not hoistable.
+        let pure = asm(vec![output("=r", false)], &["r"], &[]);
+        assert_eq!(statement_side_effects(&pure), SideEffects::UbSensitive);
+        let memory_clobber = asm(vec![output("=r", false)], &["r"], &[sym::memory]);
+        assert_eq!(statement_side_effects(&memory_clobber), SideEffects::Divergent);
+        let indirect_output = asm(vec![output("=*m", true)], &["r"], &[]);
+        assert_eq!(statement_side_effects(&indirect_output), SideEffects::Divergent);
+        let memory_input = asm(vec![output("=r", false)], &["m"], &[]);
+        assert_eq!(statement_side_effects(&memory_input), SideEffects::Divergent);
+    });
+}
+
+#[test]
+fn terminators() {
+    let goto = TerminatorKind::Goto { target: START_BLOCK };
+    assert_eq!(terminator_side_effects(&terminator(goto)), SideEffects::Pure);
+    let unreachable = TerminatorKind::Unreachable;
+    assert_eq!(terminator_side_effects(&terminator(unreachable)), SideEffects::UbSensitive);
+    for kind in vec![TerminatorKind::Return, TerminatorKind::Abort, TerminatorKind::Resume] {
+        assert_eq!(terminator_side_effects(&terminator(kind)), SideEffects::Divergent);
+    }
+}
+
+#[test]
+fn uses() {
+    assert!(SideEffects::Pure.can_hoist());
+    assert!(!SideEffects::UbSensitive.can_hoist());
+
+    assert!(SideEffects::ReadOnly.is_removable());
+    assert!(SideEffects::ReadOnly.can_sink());
+    assert!(!SideEffects::WritesMemory.is_removable());
+    assert!(!SideEffects::WritesMemory.can_sink());
+    assert!(!SideEffects::Divergent.is_removable());
+}
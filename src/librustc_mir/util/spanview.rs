@@ -0,0 +1,195 @@
+//! Implements `-Z dump-mir-spanview`, which writes the source of a function as HTML, with the
+//! spans of its MIR statements, terminators or basic blocks highlighted. Hovering over a piece of
+//! source shows the MIR covering it. This makes it easy to check that passes rewriting
+//! `SourceInfo` keep the spans meaningful.
+//!
+//! Spans may overlap without being nested, so the source is split at every span boundary. Each
+//! piece is colored after the smallest span covering it, and shaded darker the more spans cover
+//! it.
+
+use rustc::mir::*;
+use rustc::session::config::MirSpanview;
+use rustc::ty::TyCtxt;
+use rustc_hir::def_id::DefId;
+use rustc_span::{BytePos, Pos, Span};
+use std::io::{self, Write};
+
+#[cfg(test)]
+mod tests;
+
+/// The number of distinct background colors, assigned to basic blocks in turn.
+const NUM_COLORS: usize = 6;
+
+const HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<style>
+    body { font-family: monospace; }
+    .code { white-space: pre; }
+    .code:hover { outline: 1px solid black; }
+    .bb0 { background-color: rgba(255, 80, 80, var(--alpha)); }
+    .bb1 { background-color: rgba(80, 200, 80, var(--alpha)); }
+    .bb2 { background-color: rgba(80, 120, 255, var(--alpha)); }
+    .bb3 { background-color: rgba(255, 200, 0, var(--alpha)); }
+    .bb4 { background-color: rgba(200, 80, 255, var(--alpha)); }
+    .bb5 { background-color: rgba(0, 200, 200, var(--alpha)); }
+</style>"#;
+
+/// A piece of MIR with a span in the function's source.
+struct SpanViewable {
+    span: Span,
+    block: BasicBlock,
+    /// The MIR covered by the span, shown as a tooltip.
+    tooltip: String,
+}
+
+/// Writes the source of the function `def_id` as an HTML page, highlighting the spans selected
+/// by `spanview`. Writes nothing if the source of the function is not available.
+pub fn write_mir_fn_spanview<'tcx, W>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    body: &Body<'tcx>,
+    spanview: MirSpanview,
+    w: &mut W,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    let body_span = match tcx.hir().span_if_local(def_id) {
+        Some(span) => span,
+        None => return Ok(()),
+    };
+    let source = match tcx.sess.source_map().span_to_snippet(body_span) {
+        Ok(source) => source,
+        Err(_) => return Ok(()),
+    };
+
+    let viewables = span_viewables(body, body_span, spanview);
+
+    writeln!(w, "{}", HEADER)?;
+    writeln!(w, "<title>{}</title>", escape_html(&tcx.def_path_str(def_id)))?;
+    writeln!(w, "</head>")?;
+    writeln!(w, "<body>")?;
+    write!(w, r#"<div class="code">"#)?;
+    for (lo, hi, covering) in source_pieces(body_span, &viewables) {
+        let text = &source[offset(body_span, lo)..offset(body_span, hi)];
+        if covering.is_empty() {
+            write!(w, "{}", escape_html(text))?;
+            continue;
+        }
+        let tooltip: Vec<_> = covering.iter().map(|viewable| viewable.tooltip.as_str()).collect();
+        // Shade the piece darker the more spans cover it, but keep the text readable.
+        let alpha = (covering.len() as f32 * 0.15).min(0.75);
+        write!(
+            w,
+            r#"<span class="code bb{}" style="--alpha: {:.2}" title="{}">{}</span>"#,
+            covering[0].block.index() % NUM_COLORS,
+            alpha,
+            escape_html(&tooltip.join("\n")),
+            escape_html(text)
+        )?;
+    }
+    writeln!(w, "</div>")?;
+    writeln!(w, "</body>")?;
+    writeln!(w, "</html>")
+}
+
+/// Splits the source of the function at every boundary of the spans in `viewables`, and returns
+/// the start and end of each piece with the viewables covering it, the smallest first.
+fn source_pieces<'a>(
+    body_span: Span,
+    viewables: &'a [SpanViewable],
+) -> Vec<(BytePos, BytePos, Vec<&'a SpanViewable>)> {
+    let mut boundaries: Vec<BytePos> = viewables
+        .iter()
+        .flat_map(|viewable| vec![viewable.span.lo(), viewable.span.hi()])
+        .chain(vec![body_span.lo(), body_span.hi()])
+        .collect();
+    boundaries.sort();
+    boundaries.dedup();
+
+    let mut by_lo: Vec<&SpanViewable> = viewables.iter().collect();
+    by_lo.sort_by_key(|viewable| viewable.span.lo());
+    let mut by_lo = by_lo.into_iter().peekable();
+    let size = |viewable: &SpanViewable| viewable.span.hi() - viewable.span.lo();
+
+    // Sweep over the pieces, keeping the viewables covering the current one sorted by size.
+    let mut covering: Vec<&SpanViewable> = vec![];
+    let mut pieces = vec![];
+    for piece in boundaries.windows(2) {
+        let (lo, hi) = (piece[0], piece[1]);
+        covering.retain(|viewable| viewable.span.hi() > lo);
+        while by_lo.peek().map_or(false, |viewable| viewable.span.lo() <= lo) {
+            let viewable = by_lo.next().unwrap();
+            let index = covering
+                .binary_search_by_key(&size(viewable), |&covering| size(covering))
+                .unwrap_or_else(|index| index);
+            covering.insert(index, viewable);
+        }
+        pieces.push((lo, hi, covering.clone()));
+    }
+    pieces
+}
+
+/// Collects the spans to highlight. Spans outside of the function, e.g. those of code expanded
+/// from macros defined elsewhere, and empty spans are left out.
+fn span_viewables(body: &Body<'_>, body_span: Span, spanview: MirSpanview) -> Vec<SpanViewable> {
+    let in_body = |span: Span| body_span.contains(span) && span.lo() < span.hi();
+    let mut viewables = vec![];
+    for (block, data) in body.basic_blocks().iter_enumerated() {
+        let terminator = data.terminator();
+        let terminator_location = body.terminator_loc(block);
+        let terminator_tooltip = format!("{:?}: {:?}", terminator_location, terminator.kind);
+        match spanview {
+            MirSpanview::Statement => {
+                for (statement_index, statement) in data.statements.iter().enumerate() {
+                    let span = statement.source_info.span;
+                    if in_body(span) {
+                        let location = Location { block, statement_index };
+                        let tooltip = format!("{:?}: {:?}", location, statement);
+                        viewables.push(SpanViewable { span, block, tooltip });
+                    }
+                }
+                let span = terminator.source_info.span;
+                if in_body(span) {
+                    viewables.push(SpanViewable { span, block, tooltip: terminator_tooltip });
+                }
+            }
+            MirSpanview::Terminator => {
+                let span = terminator.source_info.span;
+                if in_body(span) {
+                    viewables.push(SpanViewable { span, block, tooltip: terminator_tooltip });
+                }
+            }
+            MirSpanview::Block => {
+                let mut span: Option<Span> = None;
+                let mut extend = |other: Span| {
+                    if in_body(other) {
+                        span = Some(span.map_or(other, |span| span.to(other)));
+                    }
+                };
+                let mut lines = vec![format!("{:?}:", block)];
+                for statement in &data.statements {
+                    extend(statement.source_info.span);
+                    lines.push(format!("    {:?}", statement));
+                }
+                extend(terminator.source_info.span);
+                lines.push(format!("    {:?}", terminator.kind));
+                if let Some(span) = span {
+                    viewables.push(SpanViewable { span, block, tooltip: lines.join("\n") });
+                }
+            }
+        }
+    }
+    viewables
+}
+
+/// The offset of `pos` in the source of the function.
+fn offset(body_span: Span, pos: BytePos) -> usize {
+    (pos - body_span.lo()).to_usize()
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace("&", "&amp;").replace("<", "&lt;").replace(">", "&gt;").replace("\"", "&quot;")
+}
@@ -0,0 +1,73 @@
+use rustc::mir::BasicBlock;
+use rustc_index::vec::Idx;
+use rustc_span::{BytePos, Span};
+
+use super::*;
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+fn viewable(lo: u32, hi: u32, tooltip: &str) -> SpanViewable {
+    SpanViewable { span: span(lo, hi), block: BasicBlock::new(0), tooltip: tooltip.to_string() }
+}
+
+/// Returns the pieces as `(lo, hi, tooltips)`.
+fn pieces(body_span: Span, viewables: &[SpanViewable]) -> Vec<(u32, u32, Vec<&str>)> {
+    source_pieces(body_span, viewables)
+        .into_iter()
+        .map(|(lo, hi, covering)| {
+            let tooltips = covering.iter().map(|viewable| viewable.tooltip.as_str()).collect();
+            (lo.0, hi.0, tooltips)
+        })
+        .collect()
+}
+
+#[test]
+fn no_spans() {
+    assert_eq!(pieces(span(10, 20), &[]), vec![(10, 20, vec![])]);
+}
+
+#[test]
+fn nested_spans() {
+    let viewables = [viewable(10, 20, "outer"), viewable(12, 15, "inner")];
+    assert_eq!(
+        pieces(span(0, 30), &viewables),
+        vec![
+            (0, 10, vec![]),
+            (10, 12, vec!["outer"]),
+            (12, 15, vec!["inner", "outer"]),
+            (15, 20, vec!["outer"]),
+            (20, 30, vec![]),
+        ]
+    );
+}
+
+#[test]
+fn overlapping_spans() {
+    // The second span ends after the first one, but starts within it.
+    let viewables = [viewable(4, 10, "a"), viewable(6, 16, "b"), viewable(10, 12, "c")];
+    assert_eq!(
+        pieces(span(4, 16), &viewables),
+        vec![
+            (4, 6, vec!["a"]),
+            (6, 10, vec!["a", "b"]),
+            (10, 12, vec!["c", "b"]),
+            (12, 16, vec!["b"]),
+        ]
+    );
+}
+
+#[test]
+fn many_spans() {
+    // Adjacent spans, each covering a single piece, and one span covering them all.
+    let mut viewables: Vec<_> =
+        (0..1000).map(|i| viewable(i * 2, i * 2 + 2, "statement")).collect();
+    viewables.push(viewable(0, 2000, "block"));
+    let pieces = pieces(span(0, 2000), &viewables);
+    assert_eq!(pieces.len(), 1000);
+    for (i, (lo, hi, tooltips)) in pieces.into_iter().enumerate() {
+        assert_eq!((lo, hi), (i as u32 * 2, i as u32 * 2 + 2));
+        assert_eq!(tooltips, vec!["statement", "block"]);
+    }
+}
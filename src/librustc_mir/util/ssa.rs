@@ -0,0 +1,360 @@
+//! An SSA-like view of MIR locals.
+//!
+//! MIR itself is not in SSA form: a local may be assigned any number of times. Some
+//! optimizations are much simpler to express on SSA values though, so this module computes a
+//! pruned SSA renaming of the locals of a body *without* changing the body itself. Passes can
+//! query which value each use of a local reads, where each value is defined and which phis
+//! would be needed at block entries, and translate their results back to the original locals.
+//!
+//! Only locals whose value can be tracked precisely are renamed: their address must never be
+//! taken, they must never be written to through a projection (e.g. `_1.0 = ...`), and they must
+//! not be the target of a `DropAndReplace`. All other locals are ignored.
+//!
+//! A few simplifications are made:
+//!
+//! - The destination of a `Call` is considered defined at the call terminator, even on the
+//!   unwind path. The same approximation is made by `liveness`.
+//! - `StorageLive` and `StorageDead` end the current value of a local; reading a local after
+//!   that reads no value at all (`None`).
+//! - Moving out of a local does not end its value.
+
+use crate::util::liveness::{self, LivenessResult};
+use rustc::mir::visit::{MutatingUseContext, NonMutatingUseContext, NonUseContext};
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::graph::dominators::{dominance_frontiers, Dominators};
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+
+rustc_index::newtype_index! {
+    pub struct SsaValue {
+        DEBUG_FORMAT = "v{}"
+    }
+}
+
+/// Where an SSA value is defined.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SsaDef {
+    /// The value of an argument on function entry.
+    Arg,
+    /// A phi at the start of the given block.
+    Phi(BasicBlock),
+    /// An assignment at the given location.
+    Location(Location),
+}
+
+#[derive(Clone, Debug)]
+pub struct SsaValueData {
+    pub local: Local,
+    pub def: SsaDef,
+}
+
+/// A phi placed at the start of a block, merging the values of `local` coming in from each
+/// predecessor.
+#[derive(Clone, Debug)]
+pub struct Phi {
+    pub local: Local,
+    pub value: SsaValue,
+    /// The value flowing in from each (reachable) predecessor, or `None` if the local is
+    /// uninitialized or its storage is dead on that edge.
+    pub operands: Vec<(BasicBlock, Option<SsaValue>)>,
+}
+
+pub struct SsaRenaming {
+    renamed: BitSet<Local>,
+    values: IndexVec<SsaValue, SsaValueData>,
+    uses: IndexVec<SsaValue, Vec<Location>>,
+    phis: IndexVec<BasicBlock, Vec<Phi>>,
+    used_at: FxHashMap<(Location, Local), Option<SsaValue>>,
+    defined_at: FxHashMap<(Location, Local), SsaValue>,
+}
+
+impl SsaRenaming {
+    pub fn new(body: ReadOnlyBodyAndCache<'_, '_>) -> SsaRenaming {
+        let mut collector = AccessCollector {
+            renamed: BitSet::new_filled(body.local_decls.len()),
+            accesses: IndexVec::from_elem(vec![], body.basic_blocks()),
+        };
+        collector.visit_body(body);
+        let AccessCollector { renamed, mut accesses } = collector;
+
+        // Within a single location, uses happen before definitions. E.g. for `_1 = Add(_1, _2)`
+        // the visitor sees the destination first.
+        for block_accesses in &mut accesses {
+            block_accesses.sort_by_key(|&(statement_index, _, access)| {
+                (statement_index, access != Access::Use)
+            });
+        }
+
+        let dominators = body.dominators();
+        let liveness = liveness::liveness_of_locals(body);
+
+        let mut renaming = SsaRenaming {
+            renamed,
+            values: IndexVec::new(),
+            uses: IndexVec::new(),
+            phis: IndexVec::from_elem(vec![], body.basic_blocks()),
+            used_at: FxHashMap::default(),
+            defined_at: FxHashMap::default(),
+        };
+        renaming.place_phis(body, &dominators, &liveness, &accesses);
+        renaming.rename(body, &dominators, &accesses);
+        renaming
+    }
+
+    /// Returns `true` if `local` has been renamed, i.e., it is tracked by this analysis.
+    pub fn is_renamed(&self, local: Local) -> bool {
+        self.renamed.contains(local)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = SsaValue> + ExactSizeIterator {
+        self.values.indices()
+    }
+
+    pub fn value_data(&self, value: SsaValue) -> &SsaValueData {
+        &self.values[value]
+    }
+
+    /// Returns the locations reading `value`. Reads by phis are not included, see `phis`.
+    pub fn uses(&self, value: SsaValue) -> &[Location] {
+        &self.uses[value]
+    }
+
+    /// Returns the phis at the start of `block`.
+    pub fn phis(&self, block: BasicBlock) -> &[Phi] {
+        &self.phis[block]
+    }
+
+    /// Returns the value of `local` read at `location`.
+    ///
+    /// Returns `None` if `local` is not renamed, not read at `location`, or if no value reaches
+    /// the read (which can only happen in unreachable code once borrowck has run).
+    pub fn value_used_at(&self, location: Location, local: Local) -> Option<SsaValue> {
+        self.used_at.get(&(location, local)).cloned().flatten()
+    }
+
+    /// Returns the value of `local` defined at `location`, if any.
+    pub fn value_defined_at(&self, location: Location, local: Local) -> Option<SsaValue> {
+        self.defined_at.get(&(location, local)).cloned()
+    }
+
+    fn new_value(&mut self, local: Local, def: SsaDef) -> SsaValue {
+        self.uses.push(vec![]);
+        self.values.push(SsaValueData { local, def })
+    }
+
+    /// Places a phi for each renamed local in the iterated dominance frontier of its
+    /// definitions, as long as the local is live on entry to the block.
+    fn place_phis(
+        &mut self,
+        body: ReadOnlyBodyAndCache<'_, '_>,
+        dominators: &Dominators<BasicBlock>,
+        liveness: &LivenessResult,
+        accesses: &IndexVec<BasicBlock, Vec<(usize, Local, Access)>>,
+    ) {
+        let frontiers = dominance_frontiers(body, dominators);
+
+        // The blocks (re)defining each local. Every local is defined on entry, either as an
+        // argument or as uninitialized.
+        let mut def_blocks = IndexVec::from_elem(vec![START_BLOCK], &body.local_decls);
+        // The first access to each local in each block.
+        let mut first_access = FxHashMap::default();
+        for (block, block_accesses) in accesses.iter_enumerated() {
+            for &(_, local, access) in block_accesses {
+                if !self.renamed.contains(local) {
+                    continue;
+                }
+                first_access.entry((block, local)).or_insert(access);
+                if access != Access::Use && def_blocks[local].last() != Some(&block) {
+                    def_blocks[local].push(block);
+                }
+            }
+        }
+
+        let live_on_entry = |block: BasicBlock, local: Local| {
+            match first_access.get(&(block, local)) {
+                Some(&access) => access == Access::Use,
+                None => liveness.outs[block].contains(local),
+            }
+        };
+
+        let renamed: Vec<_> = self.renamed.iter().collect();
+        for local in renamed {
+            let mut has_phi = BitSet::new_empty(body.basic_blocks().len());
+            let mut work_list = def_blocks[local].clone();
+            while let Some(block) = work_list.pop() {
+                for &frontier in &frontiers[block] {
+                    if !live_on_entry(frontier, local) || !has_phi.insert(frontier) {
+                        continue;
+                    }
+                    let value = self.new_value(local, SsaDef::Phi(frontier));
+                    self.phis[frontier].push(Phi { local, value, operands: vec![] });
+                    work_list.push(frontier);
+                }
+            }
+        }
+    }
+
+    /// Walks the dominator tree, assigning the reaching value to each use.
+    fn rename(
+        &mut self,
+        body: ReadOnlyBodyAndCache<'_, '_>,
+        dominators: &Dominators<BasicBlock>,
+        accesses: &IndexVec<BasicBlock, Vec<(usize, Local, Access)>>,
+    ) {
+        let mut children = IndexVec::from_elem(vec![], body.basic_blocks());
+        for block in body.basic_blocks().indices() {
+            if block != START_BLOCK && dominators.is_reachable(block) {
+                children[dominators.immediate_dominator(block)].push(block);
+            }
+        }
+
+        let mut stacks: IndexVec<Local, Vec<Option<SsaValue>>> =
+            IndexVec::from_elem(vec![], &body.local_decls);
+        for local in body.args_iter() {
+            if self.renamed.contains(local) {
+                let value = self.new_value(local, SsaDef::Arg);
+                stacks[local].push(Some(value));
+            }
+        }
+
+        enum Step {
+            Enter(BasicBlock),
+            /// Leave a block, popping the values it pushed for each of the given locals.
+            Exit(Vec<Local>),
+        }
+
+        let mut steps = vec![Step::Enter(START_BLOCK)];
+        while let Some(step) = steps.pop() {
+            let block = match step {
+                Step::Enter(block) => block,
+                Step::Exit(pushed) => {
+                    for local in pushed {
+                        stacks[local].pop();
+                    }
+                    continue;
+                }
+            };
+
+            let mut pushed = vec![];
+            for phi in &self.phis[block] {
+                stacks[phi.local].push(Some(phi.value));
+                pushed.push(phi.local);
+            }
+
+            for &(statement_index, local, access) in &accesses[block] {
+                if !self.renamed.contains(local) {
+                    continue;
+                }
+                let location = Location { block, statement_index };
+                match access {
+                    Access::Use => {
+                        let value = stacks[local].last().cloned().flatten();
+                        self.used_at.insert((location, local), value);
+                        if let Some(value) = value {
+                            if self.uses[value].last() != Some(&location) {
+                                self.uses[value].push(location);
+                            }
+                        }
+                    }
+                    Access::Def => {
+                        let value = self.new_value(local, SsaDef::Location(location));
+                        self.defined_at.insert((location, local), value);
+                        stacks[local].push(Some(value));
+                        pushed.push(local);
+                    }
+                    Access::Kill => {
+                        stacks[local].push(None);
+                        pushed.push(local);
+                    }
+                }
+            }
+
+            let mut successors: Vec<_> = body[block].terminator().successors().cloned().collect();
+            successors.sort();
+            successors.dedup();
+            for successor in successors {
+                for phi in &mut self.phis[successor] {
+                    let value = stacks[phi.local].last().cloned().flatten();
+                    phi.operands.push((block, value));
+                }
+            }
+
+            steps.push(Step::Exit(pushed));
+            steps.extend(children[block].iter().rev().map(|&child| Step::Enter(child)));
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Access {
+    Use,
+    /// Assigns a new value to the whole local.
+    Def,
+    /// Ends the current value of the local, without assigning a new one.
+    Kill,
+}
+
+/// Collects all accesses to locals in program order, and finds the locals that can be renamed.
+struct AccessCollector {
+    renamed: BitSet<Local>,
+    accesses: IndexVec<BasicBlock, Vec<(usize, Local, Access)>>,
+}
+
+impl<'tcx> Visitor<'tcx> for AccessCollector {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        // Anything done to a place behind a dereference only reads the local itself.
+        let context = if place.is_indirect() {
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy)
+        } else {
+            context
+        };
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, location: Location) {
+        let access = match context {
+            PlaceContext::MutatingUse(MutatingUseContext::Store)
+            | PlaceContext::MutatingUse(MutatingUseContext::Call) => Access::Def,
+
+            PlaceContext::MutatingUse(MutatingUseContext::Drop)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::Move)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::Inspect)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::Projection) => Access::Use,
+
+            PlaceContext::NonUse(NonUseContext::StorageLive)
+            | PlaceContext::NonUse(NonUseContext::StorageDead) => Access::Kill,
+
+            PlaceContext::NonUse(NonUseContext::AscribeUserTy)
+            | PlaceContext::NonUse(NonUseContext::VarDebugInfo) => return,
+
+            // The address of the local escapes, or it is partially written to.
+            PlaceContext::MutatingUse(MutatingUseContext::AsmOutput)
+            | PlaceContext::MutatingUse(MutatingUseContext::Borrow)
+            | PlaceContext::MutatingUse(MutatingUseContext::AddressOf)
+            | PlaceContext::MutatingUse(MutatingUseContext::Projection)
+            | PlaceContext::MutatingUse(MutatingUseContext::Retag)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::SharedBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::ShallowBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::UniqueBorrow)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::AddressOf) => {
+                self.renamed.remove(local);
+                return;
+            }
+        };
+        self.accesses[location.block].push((location.statement_index, local, access));
+    }
+
+    fn visit_terminator_kind(&mut self, kind: &TerminatorKind<'tcx>, location: Location) {
+        // `DropAndReplace` both reads and redefines its place, which is more than this analysis
+        // wants to model. It is gone after drop elaboration anyway.
+        if let TerminatorKind::DropAndReplace { location: place, .. } = kind {
+            if let Some(local) = place.as_local() {
+                self.renamed.remove(local);
+            }
+        }
+        self.super_terminator_kind(kind, location);
+    }
+}
@@ -14,7 +14,7 @@ use rustc::middle::region;
 use rustc::mir::*;
 use rustc::ty::layout::VariantIdx;
 use rustc::ty::{self, CanonicalUserTypeAnnotation, Ty};
-use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::HirId;
 use rustc_index::bit_set::BitSet;
 use rustc_span::Span;
@@ -171,11 +171,11 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         scrutinee_span: Span,
         match_has_guard: bool,
         candidates: &mut [&mut Candidate<'pat, 'tcx>],
-    ) -> Vec<(Place<'tcx>, Local)> {
+    ) -> Vec<(Place<'tcx>, Local, MatchedPlace)> {
         // The set of places that we are creating fake borrows of. If there are
         // no match guards then we don't need any fake borrows, so don't track
         // them.
-        let mut fake_borrows = if match_has_guard { Some(FxHashSet::default()) } else { None };
+        let mut fake_borrows = if match_has_guard { Some(FxHashMap::default()) } else { None };
 
         let mut otherwise = None;
 
@@ -223,13 +223,14 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         scrutinee_span: Span,
         arm_candidates: Vec<(&'_ Arm<'tcx>, Candidate<'_, 'tcx>)>,
         outer_source_info: SourceInfo,
-        fake_borrow_temps: Vec<(Place<'tcx>, Local)>,
+        fake_borrow_temps: Vec<(Place<'tcx>, Local, MatchedPlace)>,
     ) -> BlockAnd<()> {
         let match_scope = self.scopes.topmost();
 
         let arm_end_blocks: Vec<_> = arm_candidates
             .into_iter()
-            .map(|(arm, candidate)| {
+            .enumerate()
+            .map(|(arm_index, (arm, candidate))| {
                 debug!("lowering arm {:?}\ncanidate = {:?}", arm, candidate);
 
                 let arm_source_info = self.source_info(arm.span);
@@ -247,7 +248,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                     let arm_block = this.bind_pattern(
                         outer_source_info,
                         candidate,
-                        arm.guard.as_ref().map(|g| (g, match_scope)),
+                        arm.guard.as_ref().map(|g| (g, match_scope, arm_index)),
                         &fake_borrow_temps,
                         scrutinee_span,
                         Some(arm.scope),
@@ -277,15 +278,16 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
     /// Binds the variables and ascribes types for a given `match` arm or
     /// `let` binding.
     ///
-    /// Also check if the guard matches, if it's provided.
+    /// Also check if the guard matches, if it's provided, along with the
+    /// index of its arm.
     /// `arm_scope` should be `Some` if and only if this is called for a
     /// `match` arm.
     fn bind_pattern(
         &mut self,
         outer_source_info: SourceInfo,
         candidate: Candidate<'_, 'tcx>,
-        guard: Option<(&Guard<'tcx>, region::Scope)>,
-        fake_borrow_temps: &Vec<(Place<'tcx>, Local)>,
+        guard: Option<(&Guard<'tcx>, region::Scope, usize)>,
+        fake_borrow_temps: &Vec<(Place<'tcx>, Local, MatchedPlace)>,
         scrutinee_span: Span,
         arm_scope: Option<region::Scope>,
     ) -> BasicBlock {
@@ -867,7 +869,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         start_block: BasicBlock,
         otherwise_block: &mut Option<BasicBlock>,
         candidates: &mut [&mut Candidate<'pat, 'tcx>],
-        fake_borrows: &mut Option<FxHashSet<Place<'tcx>>>,
+        fake_borrows: &mut Option<FxHashMap<Place<'tcx>, MatchedPlace>>,
     ) {
         debug!(
             "matched_candidate(span={:?}, candidates={:?}, start_block={:?}, otherwise_block={:?})",
@@ -914,7 +916,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         start_block: BasicBlock,
         otherwise_block: &mut Option<BasicBlock>,
         candidates: &mut [&mut Candidate<'_, 'tcx>],
-        fake_borrows: &mut Option<FxHashSet<Place<'tcx>>>,
+        fake_borrows: &mut Option<FxHashMap<Place<'tcx>, MatchedPlace>>,
     ) {
         // The candidates are sorted by priority. Check to see whether the
         // higher priority candidates (and hence at the front of the slice)
@@ -985,7 +987,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         &mut self,
         matched_candidates: &mut [&mut Candidate<'_, 'tcx>],
         start_block: BasicBlock,
-        fake_borrows: &mut Option<FxHashSet<Place<'tcx>>>,
+        fake_borrows: &mut Option<FxHashMap<Place<'tcx>, MatchedPlace>>,
     ) -> Option<BasicBlock> {
         debug_assert!(
             !matched_candidates.is_empty(),
@@ -1021,10 +1023,11 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                 {
                     let proj_base = &source.projection[..i];
 
-                    fake_borrows.insert(Place {
+                    let place = Place {
                         local: source.local,
                         projection: self.hir.tcx().intern_place_elems(proj_base),
-                    });
+                    };
+                    fake_borrows.entry(place).or_insert(MatchedPlace::Dereferenced);
                 }
             }
         }
@@ -1122,7 +1125,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         candidates: &mut [&mut Candidate<'_, 'tcx>],
         block: BasicBlock,
         otherwise_block: &mut Option<BasicBlock>,
-        fake_borrows: &mut Option<FxHashSet<Place<'tcx>>>,
+        fake_borrows: &mut Option<FxHashMap<Place<'tcx>, MatchedPlace>>,
     ) {
         let (first_candidate, remaining_candidates) = candidates.split_first_mut().unwrap();
 
@@ -1178,7 +1181,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         pats: &'pat [Pat<'tcx>],
         or_span: Span,
         place: Place<'tcx>,
-        fake_borrows: &mut Option<FxHashSet<Place<'tcx>>>,
+        fake_borrows: &mut Option<FxHashMap<Place<'tcx>, MatchedPlace>>,
     ) {
         debug!("test_or_pattern:\ncandidate={:#?}\npats={:#?}", candidate, pats);
         let mut or_candidates: Vec<_> =
@@ -1352,7 +1355,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         mut candidates: &'b mut [&'c mut Candidate<'pat, 'tcx>],
         block: BasicBlock,
         otherwise_block: &mut Option<BasicBlock>,
-        fake_borrows: &mut Option<FxHashSet<Place<'tcx>>>,
+        fake_borrows: &mut Option<FxHashMap<Place<'tcx>, MatchedPlace>>,
     ) {
         // extract the match-pair from the highest priority candidate
         let match_pair = &candidates.first().unwrap().match_pairs[0];
@@ -1388,7 +1391,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         }
 
         // Insert a Shallow borrow of any places that is switched on.
-        fake_borrows.as_mut().map(|fb| fb.insert(match_place));
+        fake_borrows.as_mut().map(|fb| fb.insert(match_place, MatchedPlace::Tested));
 
         // perform the test, branching to one of N blocks. For each of
         // those N possible outcomes, create a (initially empty)
@@ -1474,8 +1477,8 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
     /// Determine the fake borrows that are needed from a set of places that
     /// have to be stable across match guards.
     ///
-    /// Returns a list of places that need a fake borrow and the temporary
-    /// that's used to store the fake borrow.
+    /// Returns a list of places that need a fake borrow, the temporary
+    /// that's used to store the fake borrow, and why the place needs it.
     ///
     /// Match exhaustiveness checking is not able to handle the case where the
     /// place being matched on is mutated in the guards. We add "fake borrows"
@@ -1495,9 +1498,9 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
     ///    by a MIR pass run after borrow checking.
     fn calculate_fake_borrows<'b>(
         &mut self,
-        fake_borrows: &'b FxHashSet<Place<'tcx>>,
+        fake_borrows: &'b FxHashMap<Place<'tcx>, MatchedPlace>,
         temp_span: Span,
-    ) -> Vec<(Place<'tcx>, Local)> {
+    ) -> Vec<(Place<'tcx>, Local, MatchedPlace)> {
         let tcx = self.hir.tcx();
 
         debug!("add_fake_borrows fake_borrows = {:?}", fake_borrows);
@@ -1505,7 +1508,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         let mut all_fake_borrows = Vec::with_capacity(fake_borrows.len());
 
         // Insert a Shallow borrow of the prefixes of any fake borrows.
-        for place in fake_borrows {
+        for (place, &matched_place) in fake_borrows {
             let mut cursor = place.projection.as_ref();
             while let [proj_base @ .., elem] = cursor {
                 cursor = proj_base;
@@ -1514,22 +1517,24 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                     // Insert a shallow borrow after a deref. For other
                     // projections the borrow of prefix_cursor will
                     // conflict with any mutation of base.
-                    all_fake_borrows.push(PlaceRef { local: place.local, projection: proj_base });
+                    let prefix = PlaceRef { local: place.local, projection: proj_base };
+                    all_fake_borrows.push((prefix, MatchedPlace::Dereferenced));
                 }
             }
 
-            all_fake_borrows.push(place.as_ref());
+            all_fake_borrows.push((place.as_ref(), matched_place));
         }
 
-        // Deduplicate and ensure a deterministic order.
+        // Deduplicate and ensure a deterministic order. The places that are
+        // both tested and dereferenced are `Tested`, which sorts first.
         all_fake_borrows.sort();
-        all_fake_borrows.dedup();
+        all_fake_borrows.dedup_by_key(|&mut (place, _)| place);
 
         debug!("add_fake_borrows all_fake_borrows = {:?}", all_fake_borrows);
 
         all_fake_borrows
             .into_iter()
-            .map(|matched_place_ref| {
+            .map(|(matched_place_ref, why)| {
                 let matched_place = Place {
                     local: matched_place_ref.local,
                     projection: tcx.intern_place_elems(matched_place_ref.projection),
@@ -1539,7 +1544,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                 let fake_borrow_temp =
                     self.local_decls.push(LocalDecl::new_temp(fake_borrow_ty, temp_span));
 
-                (matched_place, fake_borrow_temp)
+                (matched_place, fake_borrow_temp, why)
             })
             .collect()
     }
@@ -1561,8 +1566,8 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         &mut self,
         candidate: Candidate<'pat, 'tcx>,
         parent_bindings: &[(Vec<Binding<'tcx>>, Vec<Ascription<'tcx>>)],
-        guard: Option<(&Guard<'tcx>, region::Scope)>,
-        fake_borrows: &Vec<(Place<'tcx>, Local)>,
+        guard: Option<(&Guard<'tcx>, region::Scope, usize)>,
+        fake_borrows: &Vec<(Place<'tcx>, Local, MatchedPlace)>,
         scrutinee_span: Span,
         schedule_drops: bool,
     ) -> BasicBlock {
@@ -1673,7 +1678,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         //      the reference that we create for the arm.
         //    * So we eagerly create the reference for the arm and then take a
         //      reference to that.
-        if let Some((guard, region_scope)) = guard {
+        if let Some((guard, region_scope, arm_index)) = guard {
             let tcx = self.hir.tcx();
             let bindings = parent_bindings
                 .iter()
@@ -1689,7 +1694,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
 
             let re_erased = tcx.lifetimes.re_erased;
             let scrutinee_source_info = self.source_info(scrutinee_span);
-            for &(place, temp) in fake_borrows {
+            for &(place, temp, _) in fake_borrows {
                 let borrow = Rvalue::Ref(re_erased, BorrowKind::Shallow, place);
                 self.cfg.push_assign(block, scrutinee_source_info, &Place::from(temp), borrow);
             }
@@ -1706,8 +1711,10 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
             let guard_frame = self.guard_context.pop().unwrap();
             debug!("Exiting guard building context with locals: {:?}", guard_frame);
 
-            for &(_, temp) in fake_borrows {
-                let cause = FakeReadCause::ForMatchGuard;
+            // Diagnostics only need to tell the arms apart, so huge matches share the last index.
+            let arm = u16::try_from(arm_index).unwrap_or(u16::MAX);
+            for &(_, temp, matched_place) in fake_borrows {
+                let cause = FakeReadCause::ForMatchGuard(arm, matched_place);
                 self.cfg.push_fake_read(post_guard_block, guard_end, cause, Place::from(temp));
             }
 
@@ -1768,7 +1775,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
             // place they refer to can't be modified by the guard.
             for binding in by_value_bindings.clone() {
                 let local_id = self.var_local_id(binding.var_id, RefWithinGuard);
-                let cause = FakeReadCause::ForGuardBinding;
+                let cause = FakeReadCause::ForGuardBinding(arm);
                 self.cfg.push_fake_read(post_guard_block, guard_end, cause, Place::from(local_id));
             }
             assert!(schedule_drops, "patterns with guards must schedule drops");
@@ -1968,7 +1975,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         self.var_debug_info.push(VarDebugInfo {
             name,
             source_info: debug_source_info,
-            place: for_arm_body.into(),
+            value: VarDebugInfoContents::Place(for_arm_body.into()),
         });
         let locals = if has_guard.0 {
             let ref_for_guard = self.local_decls.push(LocalDecl::<'tcx> {
@@ -1985,7 +1992,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
             self.var_debug_info.push(VarDebugInfo {
                 name,
                 source_info: debug_source_info,
-                place: ref_for_guard.into(),
+                value: VarDebugInfoContents::Place(ref_for_guard.into()),
             });
             LocalsForNode::ForGuard { ref_for_guard, for_arm_body }
         } else {
@@ -215,6 +215,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                         switch_ty: discr_ty,
                         values: From::from(values),
                         targets,
+                        target_weights: None,
                     },
                 );
             }
@@ -241,6 +242,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                         switch_ty,
                         values: options.clone().into(),
                         targets: target_blocks,
+                        target_weights: None,
                     }
                 };
                 self.cfg.terminate(block, source_info, terminator);
@@ -685,6 +685,7 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
             span,
             parent_scope: Some(parent),
             local_data: ClearCrossCrate::Set(scope_local_data),
+            synthesized_by: None,
         })
     }
 
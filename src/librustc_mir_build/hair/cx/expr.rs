@@ -246,6 +246,18 @@ fn make_mirror_unadjusted<'a, 'tcx>(
             user_ty: None,
         },
 
+        hir::ExprKind::ConstBlock(ref anon_const) => {
+            let def_id = cx.tcx.hir().local_def_id(anon_const.hir_id);
+            let substs = InternalSubsts::identity_for_item(cx.tcx, def_id);
+            ExprKind::Literal {
+                literal: cx.tcx.mk_const(ty::Const {
+                    val: ty::ConstKind::Unevaluated(def_id, substs, None),
+                    ty: expr_ty,
+                }),
+                user_ty: None,
+            }
+        }
+
         hir::ExprKind::Binary(op, ref lhs, ref rhs) => {
             if cx.tables().is_method_call(expr) {
                 overloaded_operator(cx, expr, vec![lhs.to_ref(), rhs.to_ref()])
@@ -883,6 +883,8 @@ impl<'a> Parser<'a> {
                 err.span_label(match_sp, "while parsing this match expression");
                 err
             })
+        } else if self.is_const_block() {
+            self.parse_const_block(attrs)
         } else if self.eat_keyword(kw::Unsafe) {
             self.parse_block_expr(None, lo, BlockCheckMode::Unsafe(ast::UserProvided), attrs)
         } else if self.is_do_catch_block() {
@@ -1732,6 +1734,23 @@ impl<'a> Parser<'a> {
             ))
     }
 
+    /// Parses an inline `const` block: `const { ... }`.
+    fn parse_const_block(&mut self, attrs: AttrVec) -> PResult<'a, P<Expr>> {
+        let lo = self.token.span;
+        self.expect_keyword(kw::Const)?;
+        let blk_lo = self.token.span;
+        let value = self.parse_block_expr(None, blk_lo, BlockCheckMode::Default, AttrVec::new())?;
+        let span = lo.to(value.span);
+        self.sess.gated_spans.gate(sym::inline_const, span);
+        let anon_const = AnonConst { id: DUMMY_NODE_ID, value };
+        Ok(self.mk_expr(span, ExprKind::ConstBlock(anon_const), attrs))
+    }
+
+    fn is_const_block(&self) -> bool {
+        self.token.is_keyword(kw::Const)
+            && self.look_ahead(1, |t| *t == token::OpenDelim(token::Brace))
+    }
+
     fn is_certainly_not_a_block(&self) -> bool {
         self.look_ahead(1, |t| t.is_ident())
             && (
@@ -900,9 +900,12 @@ impl<'a> Parser<'a> {
         }
     }
 
-    /// Parses constness: `const` or nothing.
+    /// Parses constness: `const` or nothing. A `const` followed by a block starts an inline `const`
+    /// block instead.
     fn parse_constness(&mut self) -> Const {
-        if self.eat_keyword(kw::Const) {
+        if self.look_ahead(1, |t| *t != token::OpenDelim(token::Brace))
+            && self.eat_keyword(kw::Const)
+        {
             Const::Yes(self.normalized_prev_token.span)
         } else {
             Const::No
@@ -24,10 +24,10 @@ use rustc_hir::{HirIdSet, Node};
 use rustc_target::spec::abi::Abi;
 
 // Returns true if the given item must be inlined because it may be
-// monomorphized or it was marked with `#[inline]`. This will only return
-// true for functions.
+// monomorphized or it was marked with `#[inline]` or `#[rustc_mir_inline]`.
+// This will only return true for functions.
 fn item_might_be_inlined(tcx: TyCtxt<'tcx>, item: &hir::Item<'_>, attrs: CodegenFnAttrs) -> bool {
-    if attrs.requests_inline() {
+    if attrs.requests_mir_inline() {
         return true;
     }
 
@@ -50,7 +50,7 @@ fn method_might_be_inlined(
 ) -> bool {
     let codegen_fn_attrs = tcx.codegen_fn_attrs(impl_item.hir_id.owner_def_id());
     let generics = tcx.generics_of(tcx.hir().local_def_id(impl_item.hir_id));
-    if codegen_fn_attrs.requests_inline() || generics.requires_monomorphization(tcx) {
+    if codegen_fn_attrs.requests_mir_inline() || generics.requires_monomorphization(tcx) {
         return true;
     }
     if let hir::ImplItemKind::Method(method_sig, _) = &impl_item.kind {
@@ -172,7 +172,9 @@ impl<'a, 'tcx> ReachableContext<'a, 'tcx> {
                     hir::ImplItemKind::Method(..) => {
                         let attrs = self.tcx.codegen_fn_attrs(def_id);
                         let generics = self.tcx.generics_of(def_id);
-                        if generics.requires_monomorphization(self.tcx) || attrs.requests_inline() {
+                        if generics.requires_monomorphization(self.tcx)
+                            || attrs.requests_mir_inline()
+                        {
                             true
                         } else {
                             let impl_did = self.tcx.hir().get_parent_did(hir_id);
@@ -1,7 +1,8 @@
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lock;
 use rustc_target::abi::{Align, Size};
 use std::cmp::{self, Ordering};
+use std::time::Duration;
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct VariantInfo {
@@ -45,9 +46,36 @@ pub struct TypeSizeInfo {
     pub variants: Vec<VariantInfo>,
 }
 
+/// The size of the region inference problem of a body, for `-Z borrowck-stats`.
+#[derive(Debug)]
+pub struct BorrowckStatsInfo {
+    pub body_description: String,
+    pub region_vars: usize,
+    pub constraints: usize,
+    pub sccs: usize,
+    pub largest_scc: usize,
+    pub liveness_points: usize,
+    pub solve_time: Duration,
+}
+
+/// What a MIR pass cost over all the bodies it ran on, for `-Z mir-pass-stats`.
+#[derive(Debug, Default)]
+pub struct MirPassStatsInfo {
+    pub bodies: usize,
+    pub time: Duration,
+    /// The growth of the resident memory while the pass ran, summed over the bodies. This also
+    /// counts the queries the pass forced, like the optimization of the callees for inlining.
+    pub resident_growth: usize,
+    /// The largest growth of the resident memory while the pass ran on a single body, with that
+    /// body.
+    pub largest_growth: Option<(usize, String)>,
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
+    borrowck_stats: Lock<Vec<BorrowckStatsInfo>>,
+    mir_pass_stats: Lock<FxHashMap<String, MirPassStatsInfo>>,
 }
 
 impl CodeStats {
@@ -190,4 +218,84 @@ impl CodeStats {
             }
         }
     }
+
+    pub fn record_borrowck_stats(&self, info: BorrowckStatsInfo) {
+        self.borrowck_stats.borrow_mut().push(info);
+    }
+
+    pub fn print_borrowck_stats(&self) {
+        let mut stats = self.borrowck_stats.borrow_mut();
+
+        // Primary sort: slow-to-fast.
+        // Secondary sort: constraints, large-to-small.
+        stats.sort_by(|info1, info2| {
+            info2.solve_time.cmp(&info1.solve_time).then(info2.constraints.cmp(&info1.constraints))
+        });
+
+        for info in stats.iter() {
+            println!(
+                "borrowck-stats body: `{}`: solved in {:.3} ms",
+                info.body_description,
+                info.solve_time.as_secs_f64() * 1000.0
+            );
+            println!("borrowck-stats     region variables: {}", info.region_vars);
+            println!("borrowck-stats     outlives constraints: {}", info.constraints);
+            println!(
+                "borrowck-stats     SCCs: {}, largest SCC: {} regions",
+                info.sccs, info.largest_scc
+            );
+            println!("borrowck-stats     liveness points: {}", info.liveness_points);
+        }
+    }
+
+    /// Records a run of the MIR pass `pass_name` on the body described by `body_description`.
+    pub fn record_mir_pass_run(
+        &self,
+        pass_name: &str,
+        body_description: impl FnOnce() -> String,
+        time: Duration,
+        resident_growth: usize,
+    ) {
+        let mut stats = self.mir_pass_stats.borrow_mut();
+        let info = stats.entry(pass_name.to_string()).or_default();
+        info.bodies += 1;
+        info.time += time;
+        info.resident_growth += resident_growth;
+        if info.largest_growth.as_ref().map_or(resident_growth > 0, |&(l, _)| resident_growth > l) {
+            info.largest_growth = Some((resident_growth, body_description()));
+        }
+    }
+
+    pub fn print_mir_pass_stats(&self) {
+        let stats = self.mir_pass_stats.borrow();
+        let mut stats: Vec<_> = stats.iter().collect();
+
+        // Primary sort: most to least memory.
+        // Secondary sort: slow-to-fast, then by name for a stable output.
+        stats.sort_by(|(name1, info1), (name2, info2)| {
+            info2
+                .resident_growth
+                .cmp(&info1.resident_growth)
+                .then(info2.time.cmp(&info1.time))
+                .then(name1.cmp(name2))
+        });
+
+        let megabytes = |bytes: usize| bytes as f64 / 1_000_000.0;
+        for (name, info) in stats {
+            println!(
+                "mir-pass-stats pass `{}`: {} bodies in {:.3} ms, resident memory +{:.1} MB",
+                name,
+                info.bodies,
+                info.time.as_secs_f64() * 1000.0,
+                megabytes(info.resident_growth),
+            );
+            if let Some((growth, body_description)) = &info.largest_growth {
+                println!(
+                    "mir-pass-stats     largest growth: +{:.1} MB on `{}`",
+                    megabytes(*growth),
+                    body_description
+                );
+            }
+        }
+    }
 }
@@ -167,6 +167,120 @@ pub enum SymbolManglingVersion {
 
 impl_stable_hash_via_hash!(SymbolManglingVersion);
 
+/// Which retag statements `-Z mir-emit-retag` makes MIR building emit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MirEmitRetag {
+    /// Only retag the arguments on function entry.
+    FnEntry,
+    /// Retag on function entry, call returns and every assignment of a reference, without
+    /// treating two-phase borrows specially.
+    All,
+    /// Like `All`, but two-phase borrows get their own retag kind.
+    TwoPhaseAware,
+}
+
+/// Which spans `-Z dump-mir-spanview` highlights in the source of a function.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MirSpanview {
+    /// The span of every statement and terminator.
+    Statement,
+    /// Only the spans of terminators.
+    Terminator,
+    /// One span per basic block, covering all its statements and its terminator.
+    Block,
+}
+
+/// How `-Z const-eval-backtrace` shows the call stack of a failed constant evaluation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConstEvalBacktrace {
+    /// The frames of the functions of the current crate.
+    Short,
+    /// Every frame.
+    Full,
+}
+
+/// Which accesses a watchpoint reports.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WatchKind {
+    Read,
+    Write,
+    /// Both reads and writes.
+    Access,
+}
+
+/// A watchpoint given with `-Z interp-watchpoints`: a range of bytes of an allocation whose
+/// accesses are reported during MIR interpretation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InterpWatchpoint {
+    pub alloc_id: u64,
+    /// The first byte watched.
+    pub start: u64,
+    /// The first byte after the watched range.
+    pub end: u64,
+    pub kind: WatchKind,
+}
+
+impl FromStr for InterpWatchpoint {
+    type Err = ();
+
+    /// Parses `<alloc-id>[@<start>..<end>][:read|:write]`, where the allocation id may be
+    /// prefixed with `alloc`. Without a range, the entire allocation is watched.
+    fn from_str(s: &str) -> Result<InterpWatchpoint, ()> {
+        let (s, kind) = match s.rfind(':') {
+            Some(colon) => {
+                let kind = match &s[colon + 1..] {
+                    "read" => WatchKind::Read,
+                    "write" => WatchKind::Write,
+                    _ => return Err(()),
+                };
+                (&s[..colon], kind)
+            }
+            None => (s, WatchKind::Access),
+        };
+        let (id, range) = match s.find('@') {
+            Some(at) => (&s[..at], Some(&s[at + 1..])),
+            None => (s, None),
+        };
+        let alloc_id = id.trim_start_matches("alloc").parse().map_err(|_| ())?;
+        let (start, end) = match range {
+            Some(range) => {
+                let dots = range.find("..").ok_or(())?;
+                let start = range[..dots].parse().map_err(|_| ())?;
+                (start, range[dots + 2..].parse().map_err(|_| ())?)
+            }
+            None => (0, u64::max_value()),
+        };
+        if start >= end {
+            return Err(());
+        }
+        Ok(InterpWatchpoint { alloc_id, start, end, kind })
+    }
+}
+
+/// How far a MIR pass can be trusted. Passes that are not `Stable` only run at higher
+/// `-Z mir-opt-level`s, unless enabled with `-Z mir-enable-experimental-passes`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MirPassRisk {
+    /// Always runs; the pass itself decides what to do at each `-Z mir-opt-level`.
+    Stable,
+    /// Not yet known to be worth it, or not tested enough; runs at `-Z mir-opt-level=2`.
+    Experimental,
+    /// Known to miscompile some programs, or to make LLVM take very long on some; runs at
+    /// `-Z mir-opt-level=3`.
+    UnsoundKnownIssues,
+}
+
+impl MirPassRisk {
+    /// The `-Z mir-opt-level` from which on passes of this risk run by default.
+    pub fn default_mir_opt_level(self) -> usize {
+        match self {
+            MirPassRisk::Stable => 0,
+            MirPassRisk::Experimental => 2,
+            MirPassRisk::UnsoundKnownIssues => 3,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Hash)]
 pub enum DebugInfo {
     None,
@@ -1995,8 +2109,9 @@ impl PpMode {
 /// how the hash should be calculated when adding a new command-line argument.
 crate mod dep_tracking {
     use super::{
-        CFGuard, CrateType, DebugInfo, ErrorOutputType, LinkerPluginLto, LtoCli, OptLevel,
-        OutputTypes, Passes, Sanitizer, SwitchWithOptPath, SymbolManglingVersion,
+        CFGuard, CrateType, DebugInfo, ErrorOutputType, LinkerPluginLto, LtoCli, MirEmitRetag,
+        MirPassRisk, OptLevel, OutputTypes, Passes, Sanitizer, SwitchWithOptPath,
+        SymbolManglingVersion,
     };
     use crate::lint;
     use crate::utils::NativeLibraryKind;
@@ -2074,9 +2189,12 @@ crate mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(LinkerPluginLto);
     impl_dep_tracking_hash_via_hash!(SwitchWithOptPath);
     impl_dep_tracking_hash_via_hash!(SymbolManglingVersion);
+    impl_dep_tracking_hash_via_hash!(Option<MirEmitRetag>);
+    impl_dep_tracking_hash_via_hash!(MirPassRisk);
 
     impl_dep_tracking_hash_for_sortable_vec_of!(String);
     impl_dep_tracking_hash_for_sortable_vec_of!(PathBuf);
+    impl_dep_tracking_hash_for_sortable_vec_of!(MirPassRisk);
     impl_dep_tracking_hash_for_sortable_vec_of!(CrateType);
     impl_dep_tracking_hash_for_sortable_vec_of!((String, lint::Level));
     impl_dep_tracking_hash_for_sortable_vec_of!((
@@ -301,6 +301,42 @@ declare_lint! {
     "functions that cannot return without calling themselves"
 }
 
+declare_lint! {
+    pub UNCONDITIONAL_MUTUAL_RECURSION,
+    Warn,
+    "groups of functions that cannot return without calling each other"
+}
+
+declare_lint! {
+    pub REDUNDANT_MATCH_ARMS,
+    Allow,
+    "detects matches whose arms all rebuild the matched value"
+}
+
+declare_lint! {
+    pub UNINITIALIZED_READS,
+    Deny,
+    "detects uses of values left uninitialized by `MaybeUninit::uninit` or `mem::uninitialized`"
+}
+
+declare_lint! {
+    pub DEAD_ASSIGNMENTS,
+    Allow,
+    "detects assignments whose value is never read on any path"
+}
+
+declare_lint! {
+    pub HUGE_FUNCTION_BODY,
+    Allow,
+    "detects functions whose MIR exceeds `#![huge_function_body_limit]` statements"
+}
+
+declare_lint! {
+    pub INFINITE_LOOP_WITHOUT_SIDE_EFFECTS,
+    Allow,
+    "detects loops that never terminate and have no side effects"
+}
+
 declare_lint! {
     pub SINGLE_USE_LIFETIMES,
     Allow,
@@ -537,6 +573,12 @@ declare_lint_pass! {
         UNUSED_UNSAFE,
         UNUSED_MUT,
         UNCONDITIONAL_RECURSION,
+        UNCONDITIONAL_MUTUAL_RECURSION,
+        REDUNDANT_MATCH_ARMS,
+        HUGE_FUNCTION_BODY,
+        INFINITE_LOOP_WITHOUT_SIDE_EFFECTS,
+        UNINITIALIZED_READS,
+        DEAD_ASSIGNMENTS,
         SINGLE_USE_LIFETIMES,
         UNUSED_LIFETIMES,
         UNUSED_LABELS,
@@ -283,12 +283,23 @@ macro_rules! options {
             Some("one of: `disabled`, `trampolines`, or `aliases`");
         pub const parse_symbol_mangling_version: Option<&str> =
             Some("either `legacy` or `v0` (RFC 2603)");
+        pub const parse_mir_emit_retag: Option<&str> =
+            Some("one of: `fn-entry`, `all`, or `two-phase-aware` (the default)");
+        pub const parse_mir_spanview: Option<&str> =
+            Some("one of: `statement` (the default), `terminator`, or `block`");
+        pub const parse_mir_pass_risks: Option<&str> =
+            Some("a comma-separated list of: `experimental` or `unsound-known-issues`");
+        pub const parse_const_eval_backtrace: Option<&str> =
+            Some("either `short` or `full` (the default)");
+        pub const parse_interp_watchpoints: Option<&str> =
+            Some("a space-separated list of `<alloc-id>[@<start>..<end>][:read|:write]`");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
         use super::{$struct_name, Passes, Sanitizer, LtoCli, LinkerPluginLto, SwitchWithOptPath,
-            SymbolManglingVersion, CFGuard};
+            SymbolManglingVersion, CFGuard, MirEmitRetag, MirPassRisk, MirSpanview,
+            ConstEvalBacktrace, InterpWatchpoint};
         use rustc_target::spec::{LinkerFlavor, MergeFunctions, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
         use std::str::FromStr;
@@ -619,6 +630,69 @@ macro_rules! options {
             };
             true
         }
+
+        fn parse_mir_emit_retag(slot: &mut Option<MirEmitRetag>, v: Option<&str>) -> bool {
+            *slot = match v {
+                Some("fn-entry") => Some(MirEmitRetag::FnEntry),
+                Some("all") => Some(MirEmitRetag::All),
+                None | Some("two-phase-aware") => Some(MirEmitRetag::TwoPhaseAware),
+                _ => return false,
+            };
+            true
+        }
+
+        fn parse_mir_spanview(slot: &mut Option<MirSpanview>, v: Option<&str>) -> bool {
+            *slot = match v {
+                None | Some("statement") => Some(MirSpanview::Statement),
+                Some("terminator") => Some(MirSpanview::Terminator),
+                Some("block") => Some(MirSpanview::Block),
+                _ => return false,
+            };
+            true
+        }
+
+        fn parse_const_eval_backtrace(
+            slot: &mut Option<ConstEvalBacktrace>,
+            v: Option<&str>,
+        ) -> bool {
+            *slot = match v {
+                Some("short") => Some(ConstEvalBacktrace::Short),
+                None | Some("full") => Some(ConstEvalBacktrace::Full),
+                _ => return false,
+            };
+            true
+        }
+
+        fn parse_interp_watchpoints(slot: &mut Vec<InterpWatchpoint>, v: Option<&str>) -> bool {
+            match v {
+                Some(s) => {
+                    for watchpoint in s.split_whitespace().map(str::parse) {
+                        match watchpoint {
+                            Ok(watchpoint) => slot.push(watchpoint),
+                            Err(()) => return false,
+                        }
+                    }
+                    true
+                }
+                None => false,
+            }
+        }
+
+        fn parse_mir_pass_risks(slot: &mut Vec<MirPassRisk>, v: Option<&str>) -> bool {
+            match v {
+                Some(s) => {
+                    for risk in s.split(',') {
+                        slot.push(match risk {
+                            "experimental" => MirPassRisk::Experimental,
+                            "unsound-known-issues" => MirPassRisk::UnsoundKnownIssues,
+                            _ => return false,
+                        });
+                    }
+                    true
+                }
+                None => false,
+            }
+        }
     }
 ) }
 
@@ -737,7 +811,11 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     verify_llvm_ir: bool = (false, parse_bool, [TRACKED],
         "verify LLVM IR"),
     borrowck_stats: bool = (false, parse_bool, [UNTRACKED],
-        "gather borrowck statistics"),
+        "print the size of the region constraints of each body and the time taken to solve \
+        them, slowest first"),
+    mir_pass_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print the time spent in each MIR pass and the growth of the resident memory while it \
+        ran, summed over the bodies, largest growth first"),
     no_landing_pads: bool = (false, parse_bool, [TRACKED],
         "omit landing pads for unwinding"),
     fewer_names: bool = (false, parse_bool, [TRACKED],
@@ -774,6 +852,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "run all passes except codegen; no output"),
     treat_err_as_bug: Option<usize> = (None, parse_treat_err_as_bug, [TRACKED],
         "treat error number `val` that occurs as bug"),
+    recover_mir_pass_panics: bool = (false, parse_bool, [TRACKED],
+        "go on with the MIR from before an optional optimization pass that panics, instead of \
+         failing the compilation"),
     report_delayed_bugs: bool = (false, parse_bool, [TRACKED],
         "immediately print bugs registered with `delay_span_bug`"),
     macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
@@ -826,8 +907,17 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "print layout information for each type encountered"),
     print_mono_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "print the result of the monomorphization collection pass"),
+    print_mir_hashes: bool = (false, parse_bool, [TRACKED],
+        "record the hash of the optimized MIR of each body in the crate metadata, and print it"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
         "set the MIR optimization level (0-3, default: 1)"),
+    mir_enable_experimental_passes: Vec<MirPassRisk> = (Vec::new(), parse_mir_pass_risks,
+        [TRACKED],
+        "also run the MIR passes of these risk categories, regardless of -Zmir-opt-level \
+        (unless it is 0)"),
+    mir_opt_fixpoint_limit: usize = (0, parse_uint, [TRACKED],
+        "run the fixpoint groups of MIR passes, such as inlining and const propagation, up to \
+        this many times until the MIR stops changing (default: 0, i.e. not at all)"),
     mutable_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "emit noalias metadata for mutable references (default: no)"),
     dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
@@ -836,15 +926,36 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         `all` matches all passes and functions,
         `foo` matches all passes for functions whose name contains 'foo',
         `foo & ConstProp` only the 'ConstProp' pass for function names containing 'foo',
-        `foo | bar` all passes for function names containing 'foo' or 'bar'."),
+        `foo | bar` all passes for function names containing 'foo' or 'bar',
+        `foo::*::bar` all passes for the paths matching the glob, with or without the crate name,
+        `kind=promoted` all passes for promoted constants (or `kind=item`, `kind=shim`),
+        `foo, !*test*` as `foo`, except for the names matching the exclusion after `!`."),
 
+    trace_mir_pass: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "print the decisions of the MIR passes to stderr, for the passes and functions selected \
+        as with `-Z dump-mir`"),
     dump_mir_dir: String = (String::from("mir_dump"), parse_string, [UNTRACKED],
         "the directory the MIR is dumped into"),
     dump_mir_graphviz: bool = (false, parse_bool, [UNTRACKED],
         "in addition to `.mir` files, create graphviz `.dot` files"),
+    dump_mir_spanview: Option<MirSpanview> = (None, parse_mir_spanview, [UNTRACKED],
+        "in addition to `.mir` files, create `.html` files showing the source of each function \
+        with the spans of its `statement`s and terminators (the default), only its `terminator`s, \
+        or its basic `block`s highlighted"),
+    dump_move_paths: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "dump the move paths, and the moves out of and initializations of them at each location, \
+        gathered by `borrowck` and `ElaborateDrops` for the functions selected as with \
+        `-Z dump-mir`, to `.move_paths` and `.move_paths.dot` files"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
         "if set, exclude the pass number when dumping MIR (used in tests)"),
-    mir_emit_retag: bool = (false, parse_bool, [TRACKED],
+    mir_opt_remarks: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "report the optimizations MIR passes apply or miss, and why, as notes. `val` is `all` or \
+        a comma-separated list of pass names, e.g. `SimplifyArmIdentity,Inline`"),
+    verify_debuginfo_mir: bool = (false, parse_bool, [UNTRACKED],
+        "warn about user variables whose debuginfo is lost by a MIR pass"),
+    lint_int2ptr_casts: bool = (false, parse_bool, [TRACKED],
+        "warn about every cast of an integer to a pointer (strict provenance audit)"),
+    mir_emit_retag: Option<MirEmitRetag> = (None, parse_mir_emit_retag, [TRACKED],
         "emit Retagging MIR statements, interpreted e.g., by miri; implies -Zmir-opt-level=0"),
     perf_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some performance-related statistics"),
@@ -858,6 +969,18 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "describes how to render the `rendered` field of json diagnostics"),
     unleash_the_miri_inside_of_you: bool = (false, parse_bool, [TRACKED],
         "take the breaks off const evaluation. NOTE: this is unsound"),
+    track_alloc_history: bool = (false, parse_bool, [UNTRACKED],
+        "record where allocations are created, written to and deallocated during MIR \
+        interpretation, and show it in const-eval errors about them"),
+    const_eval_backtrace: Option<ConstEvalBacktrace> = (None, parse_const_eval_backtrace,
+        [UNTRACKED],
+        "show the call stack of a failed constant evaluation as one note per frame, pointing at \
+        what the frame was evaluating: only the frames of functions of the current crate with \
+        `short`, or all of them with `full` (the default)"),
+    interp_watchpoints: Vec<InterpWatchpoint> = (Vec::new(),
+        parse_interp_watchpoints, [UNTRACKED],
+        "report the accesses to the given memory ranges during MIR interpretation, each given as \
+        `<alloc-id>[@<start>..<end>][:read|:write]` (space separated)"),
     osx_rpath_install_name: bool = (false, parse_bool, [TRACKED],
         "pass `-install_name @rpath/...` to the macOS linker"),
     sanitizer: Option<Sanitizer> = (None, parse_sanitizer, [TRACKED],
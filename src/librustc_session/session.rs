@@ -1,5 +1,5 @@
 use crate::code_stats::CodeStats;
-pub use crate::code_stats::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
+pub use crate::code_stats::{BorrowckStatsInfo, DataTypeKind, FieldInfo, SizeKind, VariantInfo};
 
 use crate::cgu_reuse_tracker::CguReuseTracker;
 use rustc_data_structures::fingerprint::Fingerprint;
@@ -91,6 +91,13 @@ pub struct Session {
     /// The maximum blocks a const expression can evaluate.
     pub const_eval_limit: Once<usize>,
 
+    /// The maximum number of MIR statements of a function before `huge_function_body` fires.
+    pub huge_function_body_limit: Once<usize>,
+
+    /// The `-Z mir-opt-level` of the crate, unless overridden by `#![mir_opt_level]`. Use
+    /// `rustc_mir::transform::mir_opt_level` for the level a given body is optimized at.
+    pub mir_opt_level: Once<usize>,
+
     /// Map from imported macro spans (which consist of
     /// the localized span for the macro body) to the
     /// macro name and definition span in the source crate.
@@ -423,6 +430,9 @@ impl Session {
     pub fn borrowck_stats(&self) -> bool {
         self.opts.debugging_opts.borrowck_stats
     }
+    pub fn mir_pass_stats(&self) -> bool {
+        self.opts.debugging_opts.mir_pass_stats
+    }
     pub fn print_llvm_passes(&self) -> bool {
         self.opts.debugging_opts.print_llvm_passes
     }
@@ -1057,6 +1067,8 @@ fn build_session_(
         recursion_limit: Once::new(),
         type_length_limit: Once::new(),
         const_eval_limit: Once::new(),
+        huge_function_body_limit: Once::new(),
+        mir_opt_level: Once::new(),
         imported_macro_spans: OneThread::new(RefCell::new(FxHashMap::default())),
         incr_comp_session: OneThread::new(RefCell::new(IncrCompSession::NotInitialized)),
         cgu_reuse_tracker,
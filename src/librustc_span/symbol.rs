@@ -120,6 +120,7 @@ symbols! {
         abi_unadjusted,
         abi_vectorcall,
         abi_x86_interrupt,
+        abort,
         aborts,
         address,
         add_with_overflow,
@@ -152,6 +153,7 @@ symbols! {
         associated_type_bounds,
         associated_type_defaults,
         associated_types,
+        assume,
         assume_init,
         async_await,
         async_closure,
@@ -211,6 +213,7 @@ symbols! {
         const_eval_limit,
         const_extern_fn,
         const_fn,
+        const_fn_ptr_calls,
         const_fn_union,
         const_generics,
         const_if_match,
@@ -225,11 +228,15 @@ symbols! {
         const_transmute,
         const_trait_bound_opt_out,
         const_trait_impl,
+        const_trait_object_calls,
         contents,
         context,
         convert,
+        convert_identity,
         Copy,
+        copy,
         copy_closures,
+        copy_nonoverlapping,
         core,
         core_intrinsics,
         crate_id,
@@ -265,6 +272,7 @@ symbols! {
         derive,
         diagnostic,
         direct,
+        display_trait,
         doc,
         doc_alias,
         doc_cfg,
@@ -334,6 +342,7 @@ symbols! {
         from_generator,
         from_method,
         from_ok,
+        from_trait,
         from_usize,
         fundamental,
         future,
@@ -341,6 +350,7 @@ symbols! {
         FxHashSet,
         FxHashMap,
         gen_future,
+        gen_future_type,
         gen_kill,
         generators,
         generic_associated_types,
@@ -353,6 +363,8 @@ symbols! {
         Hash,
         HashSet,
         HashMap,
+        hashmap_type,
+        hashset_type,
         hexagon_target_feature,
         hidden,
         homogeneous_aggregate,
@@ -361,6 +373,7 @@ symbols! {
         html_no_source,
         html_playground_url,
         html_root_url,
+        huge_function_body_limit,
         i128,
         i128_type,
         i16,
@@ -385,10 +398,12 @@ symbols! {
         infer_outlives_requirements,
         infer_static_outlives_requirements,
         inline,
+        inline_const,
         intel,
         into_iter,
         IntoIterator,
         into_result,
+        into_trait,
         intrinsics,
         irrefutable_let_patterns,
         isize,
@@ -444,6 +459,9 @@ symbols! {
         may_dangle,
         maybe_uninit_uninit,
         maybe_uninit_zeroed,
+        mem_align_of,
+        mem_needs_drop,
+        mem_size_of,
         mem_uninitialized,
         mem_zeroed,
         member_constraints,
@@ -453,6 +471,7 @@ symbols! {
         min_align_of,
         min_const_fn,
         min_const_unsafe_fn,
+        mir_opt_level,
         mips_target_feature,
         mmx_target_feature,
         module,
@@ -626,6 +645,7 @@ symbols! {
         rustc_diagnostic_macros,
         rustc_dirty,
         rustc_dummy,
+        rustc_dump_callees,
         rustc_dump_env_program_clauses,
         rustc_dump_program_clauses,
         rustc_dump_user_substs,
@@ -638,6 +658,8 @@ symbols! {
         rustc_layout_scalar_valid_range_start,
         rustc_macro_transparency,
         rustc_mir,
+        rustc_mir_inline,
+        rustc_no_mir_inline,
         rustc_nonnull_optimization_guaranteed,
         rustc_object_lifetime_default,
         rustc_on_unimplemented,
@@ -649,7 +671,12 @@ symbols! {
         rustc_peek_definite_init,
         rustc_peek_maybe_init,
         rustc_peek_maybe_uninit,
+        rustc_peek_tainted,
         rustc_peek_indirectly_mutable,
+        rustc_peek_escaped,
+        rustc_peek_liveness,
+        rustc_peek_range,
+        rustc_peek_ssa,
         rustc_private,
         rustc_proc_macro_decls,
         rustc_promotable,
@@ -658,6 +685,9 @@ symbols! {
         rustc_std_internal_symbol,
         rustc_symbol_name,
         rustc_synthetic,
+        rustc_taint_sanitizer,
+        rustc_taint_sink,
+        rustc_taint_source,
         rustc_reservation_impl,
         rustc_test_marker,
         rustc_then_this_would_need,
@@ -677,9 +707,25 @@ symbols! {
         send_trait,
         should_panic,
         simd,
+        simd_add,
+        simd_and,
+        simd_div,
+        simd_eq,
         simd_extract,
         simd_ffi,
+        simd_ge,
+        simd_gt,
         simd_insert,
+        simd_le,
+        simd_lt,
+        simd_mul,
+        simd_ne,
+        simd_or,
+        simd_rem,
+        simd_shl,
+        simd_shr,
+        simd_sub,
+        simd_xor,
         since,
         size,
         size_of,
@@ -776,7 +822,9 @@ symbols! {
         uniform_paths,
         universal_impl_trait,
         unmarked_api,
+        unreachable,
         unreachable_code,
+        unreachable_unchecked,
         unrestricted_attribute_tokens,
         unsafe_no_drop_flag,
         unsized_locals,
@@ -807,6 +855,7 @@ symbols! {
         wrapping_add,
         wrapping_sub,
         wrapping_mul,
+        write_bytes,
         Yield,
     }
 }
@@ -218,6 +218,9 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         match expr.kind {
             ExprKind::Box(ref subexpr) => self.check_expr_box(subexpr, expected),
             ExprKind::Lit(ref lit) => self.check_lit(&lit, expected),
+            ExprKind::ConstBlock(ref anon_const) => {
+                tcx.type_of(tcx.hir().local_def_id(anon_const.hir_id))
+            }
             ExprKind::Binary(op, ref lhs, ref rhs) => self.check_binop(expr, op, lhs, rhs),
             ExprKind::Assign(ref lhs, ref rhs, ref span) => {
                 self.check_expr_assign(expr, expected, lhs, rhs, span)
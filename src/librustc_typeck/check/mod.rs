@@ -1031,6 +1031,16 @@ fn typeck_tables_of_with_fallback<'tcx>(
                     hir::TyKind::Infer => Some(AstConv::ast_ty_to_ty(&fcx, ty)),
                     _ => None,
                 })
+                .or_else(|| match tcx.hir().get(tcx.hir().get_parent_node(id)) {
+                    // The type of an inline `const` block is inferred from its body alone.
+                    Node::Expr(&hir::Expr { kind: ExprKind::ConstBlock(..), .. }) => {
+                        Some(fcx.next_ty_var(TypeVariableOrigin {
+                            kind: TypeVariableOriginKind::TypeInference,
+                            span: body.value.span,
+                        }))
+                    }
+                    _ => None,
+                })
                 .unwrap_or_else(fallback);
             let expected_type = fcx.normalize_associated_types_in(body.value.span, &expected_type);
             fcx.require_type_is_sized(expected_type, body.value.span, traits::ConstSized);
@@ -2313,6 +2313,7 @@ fn codegen_fn_attrs(tcx: TyCtxt<'_>, id: DefId) -> CodegenFnAttrs {
     let mut inline_span = None;
     let mut link_ordinal_span = None;
     let mut no_sanitize_span = None;
+    let mut mir_inline_span = None;
     for attr in attrs.iter() {
         if attr.check_name(sym::cold) {
             codegen_fn_attrs.flags |= CodegenFnAttrFlags::COLD;
@@ -2341,6 +2342,11 @@ fn codegen_fn_attrs(tcx: TyCtxt<'_>, id: DefId) -> CodegenFnAttrs {
             codegen_fn_attrs.flags |= CodegenFnAttrFlags::NO_MANGLE;
         } else if attr.check_name(sym::rustc_std_internal_symbol) {
             codegen_fn_attrs.flags |= CodegenFnAttrFlags::RUSTC_STD_INTERNAL_SYMBOL;
+        } else if attr.check_name(sym::rustc_mir_inline) {
+            mir_inline_span = Some(attr.span);
+            codegen_fn_attrs.flags |= CodegenFnAttrFlags::MIR_INLINE;
+        } else if attr.check_name(sym::rustc_no_mir_inline) {
+            codegen_fn_attrs.flags |= CodegenFnAttrFlags::NO_MIR_INLINE;
         } else if attr.check_name(sym::no_debug) {
             codegen_fn_attrs.flags |= CodegenFnAttrFlags::NO_DEBUG;
         } else if attr.check_name(sym::used) {
@@ -2529,6 +2535,13 @@ fn codegen_fn_attrs(tcx: TyCtxt<'_>, id: DefId) -> CodegenFnAttrs {
         }
     }
 
+    if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NO_MIR_INLINE) {
+        if let Some(span) = mir_inline_span {
+            let msg = "cannot use `#[rustc_mir_inline]` with `#[rustc_no_mir_inline]`";
+            tcx.sess.span_err(span, msg);
+        }
+    }
+
     // Weak lang items have the same semantics as "std internal" symbols in the
     // sense that they're preserved through all our LTO passes and only
     // strippable by the linker.
@@ -328,6 +328,7 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
         crate_name,
         lint_caps,
         register_lints: None,
+        register_mir_passes: None,
         override_queries: None,
         registry: rustc_driver::diagnostics_registry(),
     };
@@ -76,6 +76,7 @@ pub fn run(options: Options) -> i32 {
         crate_name: options.crate_name.clone(),
         lint_caps: Default::default(),
         register_lints: None,
+        register_mir_passes: None,
         override_queries: None,
         registry: rustc_driver::diagnostics_registry(),
     };
@@ -0,0 +1,21 @@
+// Checks that branches leading to calls of `#[cold]` functions are weighted as unlikely.
+
+// compile-flags: -O -C no-prepopulate-passes
+
+#![crate_type = "lib"]
+
+extern "C" {
+    #[cold]
+    fn cold_path();
+}
+
+// CHECK-LABEL: @check
+#[no_mangle]
+pub fn check(x: u32) {
+    // CHECK: br i1 %{{.*}}, label %{{.*}}, label %{{.*}}, !prof ![[WEIGHTS:[0-9]+]]
+    if x == 42 {
+        unsafe { cold_path() }
+    }
+}
+
+// CHECK: ![[WEIGHTS]] = !{!"branch_weights", i32 1, i32 2000}
@@ -0,0 +1,19 @@
+// compile-flags: -C opt-level=3
+
+// Checks that the loops without side effects are kept without `-Z insert-sideeffect`.
+
+#![crate_type = "lib"]
+
+fn infinite_loop() -> u8 {
+    loop {}
+}
+
+// CHECK-LABEL: @test
+#[no_mangle]
+fn test() -> u8 {
+    // CHECK-NOT: unreachable
+    // CHECK: call void @llvm.sideeffect()
+    // CHECK-NOT: unreachable
+    let x = infinite_loop();
+    x
+}
@@ -0,0 +1,39 @@
+// Checks that the branches on `size_of`, `align_of` and `needs_drop` are folded in debug builds:
+// by const propagation when the type is concrete, and by codegen when it is generic.
+
+// compile-flags: -C opt-level=0 -C no-prepopulate-passes
+
+#![crate_type = "lib"]
+
+use std::mem;
+
+// CHECK-LABEL: @concrete
+#[no_mangle]
+pub fn concrete() -> usize {
+    // CHECK-NOT: call
+    // CHECK-NOT: br i1
+    // CHECK: ret
+    if mem::size_of::<u32>() == 0 { 0 } else { mem::align_of::<u64>() }
+}
+
+// CHECK-LABEL: define {{.*}}generic
+// CHECK-NOT: call
+// CHECK-NOT: br i1
+// CHECK: ret
+fn generic<T>() -> usize {
+    if mem::needs_drop::<T>() { 0 } else { mem::size_of::<T>() }
+}
+
+#[no_mangle]
+pub fn instantiate() -> usize {
+    generic::<String>() + generic::<u8>()
+}
+
+// Closures and generators have no `fn_sig`, the calls to them are left alone.
+// CHECK-LABEL: @call_closure
+#[no_mangle]
+pub fn call_closure(x: u32) -> u32 {
+    // CHECK: call {{.*}}closure
+    let f = |y: u32| y + mem::size_of::<u64>() as u32;
+    f(x)
+}
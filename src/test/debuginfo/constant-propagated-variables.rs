@@ -0,0 +1,46 @@
+// min-lldb-version: 310
+
+// compile-flags:-g -Zmir-opt-level=2
+
+// The variables are only ever assigned constants, so the optimized MIR describes them by these
+// constants instead of keeping their locals around.
+
+// === GDB TESTS ===================================================================================
+
+// gdb-command:run
+
+// gdb-command:print x
+// gdb-check:$1 = 42
+// gdb-command:print y
+// gdb-check:$2 = 43
+// gdb-command:print c
+// gdb-check:$3 = true
+// gdb-command:continue
+
+// === LLDB TESTS ==================================================================================
+
+// lldb-command:run
+
+// lldb-command:print x
+// lldbg-check:[...]$0 = 42
+// lldbr-check:(i32) x = 42
+// lldb-command:print y
+// lldbg-check:[...]$1 = 43
+// lldbr-check:(i32) y = 43
+// lldb-command:print c
+// lldbg-check:[...]$2 = true
+// lldbr-check:(bool) c = true
+// lldb-command:continue
+
+#![feature(omit_gdb_pretty_printer_section)]
+#![omit_gdb_pretty_printer_section]
+
+fn main() {
+    let x = 42;
+    let y = x + 1;
+    let c = y > x;
+    zzz(); // #break
+}
+
+#[inline(never)]
+fn zzz() { () }
@@ -0,0 +1,23 @@
+// compile-flags: -Z mir-enable-experimental-passes=experimental
+
+fn eq(a: &[u8; 4], b: &[u8; 4]) -> bool {
+    *a == *b
+}
+
+fn main() {
+    eq(&[1, 2, 3, 4], &[1, 2, 3, 4]);
+}
+
+// END RUST SOURCE
+// START rustc.eq.BytewiseArrayEq.before.mir
+// bb0: {
+// ...
+//     _0 = const <[u8; 4] as std::cmp::PartialEq>::eq(move _3, move _4) -> bb1;
+// }
+// END rustc.eq.BytewiseArrayEq.before.mir
+// START rustc.eq.BytewiseArrayEq.after.mir
+// bb0: {
+// ...
+//     _0 = const core::slice::bytewise_eq::<[u8; 4]>(move _3, move _4) -> bb1;
+// }
+// END rustc.eq.BytewiseArrayEq.after.mir
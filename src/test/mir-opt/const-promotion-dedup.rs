@@ -0,0 +1,24 @@
+fn main() {
+    let _a: &[i32] = &[1, 2, 3];
+    let _b: &[i32] = &[1, 2, 3];
+    let _c: &[i32] = &[4, 5, 6];
+}
+
+// END RUST SOURCE
+// START rustc.main.PromoteTemps.after.mir
+// bb0: {
+// ...
+//     _12 = const main::promoted[1];
+//     _2 = &(*_12);
+//     _1 = move _2 as &[i32] (Pointer(Unsize));
+// ...
+//     _11 = const main::promoted[1];
+//     _5 = &(*_11);
+//     _4 = move _5 as &[i32] (Pointer(Unsize));
+// ...
+//     _10 = const main::promoted[0];
+//     _8 = &(*_10);
+//     _7 = move _8 as &[i32] (Pointer(Unsize));
+// ...
+// }
+// END rustc.main.PromoteTemps.after.mir
@@ -0,0 +1,47 @@
+// compile-flags: -C overflow-checks=off -Z mir-opt-level=2
+
+fn main() {
+    let x = 2 + 2;
+    let mut y = x;
+    y += 1;
+}
+
+// END RUST SOURCE
+// START rustc.main.ConstDebugInfo.before.mir
+// scope 1 {
+//   debug x => _1;
+//   let mut _2: i32;
+//   scope 2 {
+//     debug y => _2;
+//   }
+// }
+// bb0: {
+//   StorageLive(_1);
+//   _1 = const 4i32;
+//   ...
+// }
+// END rustc.main.ConstDebugInfo.before.mir
+// START rustc.main.ConstDebugInfo.after.mir
+// scope 1 {
+//   debug x => const 4i32;
+//   let mut _2: i32;
+//   scope 2 {
+//     debug y => _2;
+//   }
+// }
+// END rustc.main.ConstDebugInfo.after.mir
+// START rustc.main.SimplifyLocals.after.mir
+// let mut _0: ();
+// scope 1 {
+//   debug x => const 4i32;
+//   let mut _1: i32;
+//   scope 2 {
+//     debug y => _1;
+//   }
+// }
+// bb0: {
+//   StorageLive(_1);
+//   _1 = const 4i32;
+//   ...
+// }
+// END rustc.main.SimplifyLocals.after.mir
@@ -0,0 +1,20 @@
+use std::mem;
+
+fn main() {
+    let x = mem::size_of::<u32>();
+}
+
+// END RUST SOURCE
+// START rustc.main.ConstProp.before.mir
+//  bb0: {
+//      ...
+//      _1 = const std::mem::size_of::<u32>() -> bb1;
+//  }
+// END rustc.main.ConstProp.before.mir
+// START rustc.main.ConstProp.after.mir
+//  bb0: {
+//      ...
+//      _1 = const 4usize;
+//      goto -> bb1;
+//  }
+// END rustc.main.ConstProp.after.mir
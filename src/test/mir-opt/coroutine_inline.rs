@@ -0,0 +1,48 @@
+// edition:2018
+// compile-flags: -Z span_free_formats -Z mir-opt-level=2
+// ignore-tidy-linelength
+
+// Checks that the generator of an awaited `async fn` is resumed directly, and that its resume
+// function is then inlined into the generator awaiting it.
+
+#[inline(never)]
+fn marker() {}
+
+async fn callee() {
+    marker();
+}
+
+async fn caller() {
+    callee().await;
+}
+
+fn main() {
+    let _ = caller();
+}
+
+// END RUST SOURCE
+// START rustc.caller-{{closure}}.CoroutineInline.before.mir
+// ...
+//     _8 = const std::future::poll_with_tls_context::<impl std::future::Future>(move _9) -> [return: bb4, unwind: bb11];
+// ...
+// END rustc.caller-{{closure}}.CoroutineInline.before.mir
+// START rustc.caller-{{closure}}.CoroutineInline.after.mir
+// ...
+// bb12: {
+//     _17 = discriminant(_16);
+//     switchInt(move _17) -> [0isize: bb13, otherwise: bb14];
+// }
+// bb13: {
+//     _8 = std::task::Poll::<()>::Pending;
+//     goto -> bb4;
+// }
+// bb14: {
+//     _8 = std::task::Poll::<()>::Ready(move ((_16 as Complete).0: ()));
+//     goto -> bb4;
+// }
+// END rustc.caller-{{closure}}.CoroutineInline.after.mir
+// START rustc.caller-{{closure}}.Inline.after.mir
+// ...
+//     _23 = const marker() -> [return: bb16, unwind: bb11];
+// ...
+// END rustc.caller-{{closure}}.Inline.after.mir
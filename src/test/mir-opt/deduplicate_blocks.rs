@@ -0,0 +1,108 @@
+// compile-flags: -Z mir-enable-experimental-passes=experimental
+
+// Tests that the copies of the `else` block made by lowering `if let` are merged, after removing
+// the storage statements of the locals they never use.
+
+fn if_let(x: Option<u8>) -> u8 {
+    if let Some(1) = x { 1 } else { 0 }
+}
+
+fn if_let_uninit(x: Option<u8>) -> u8 {
+    if let Some(1) = x {
+        1
+    } else {
+        let _y: u8;
+        0
+    }
+}
+
+fn main() {
+    if_let(Some(1));
+    if_let_uninit(Some(1));
+}
+
+// END RUST SOURCE
+// START rustc.if_let.DeduplicateBlocks.before.mir
+// bb0: {
+//     _2 = discriminant(_1);
+//     switchInt(move _2) -> [1isize: bb2, otherwise: bb1];
+// }
+// bb1: {
+//     _0 = const 0u8;
+//     goto -> bb4;
+// }
+// bb2: {
+//     switchInt(((_1 as Some).0: u8)) -> [1u8: bb3, otherwise: bb5];
+// }
+// bb3: {
+//     _0 = const 1u8;
+//     goto -> bb4;
+// }
+// bb4: {
+//     return;
+// }
+// bb5: {
+//     _0 = const 0u8;
+//     goto -> bb4;
+// }
+// END rustc.if_let.DeduplicateBlocks.before.mir
+// START rustc.if_let.DeduplicateBlocks.after.mir
+// bb0: {
+//     _2 = discriminant(_1);
+//     switchInt(move _2) -> [1isize: bb2, otherwise: bb1];
+// }
+// bb1: {
+//     _0 = const 0u8;
+//     goto -> bb4;
+// }
+// bb2: {
+//     switchInt(((_1 as Some).0: u8)) -> [1u8: bb3, otherwise: bb1];
+// }
+// bb3: {
+//     _0 = const 1u8;
+//     goto -> bb4;
+// }
+// bb4: {
+//     return;
+// }
+// END rustc.if_let.DeduplicateBlocks.after.mir
+// START rustc.if_let_uninit.DeduplicateBlocks.before.mir
+// bb1: {
+//     StorageLive(_3);
+//     _0 = const 0u8;
+//     StorageDead(_3);
+//     goto -> bb4;
+// }
+// bb2: {
+//     switchInt(((_1 as Some).0: u8)) -> [1u8: bb3, otherwise: bb5];
+// }
+// bb3: {
+//     _0 = const 1u8;
+//     goto -> bb4;
+// }
+// bb4: {
+//     return;
+// }
+// bb5: {
+//     StorageLive(_3);
+//     _0 = const 0u8;
+//     StorageDead(_3);
+//     goto -> bb4;
+// }
+// END rustc.if_let_uninit.DeduplicateBlocks.before.mir
+// START rustc.if_let_uninit.DeduplicateBlocks.after.mir
+// bb1: {
+//     _0 = const 0u8;
+//     goto -> bb4;
+// }
+// bb2: {
+//     switchInt(((_1 as Some).0: u8)) -> [1u8: bb3, otherwise: bb1];
+// }
+// bb3: {
+//     _0 = const 1u8;
+//     goto -> bb4;
+// }
+// bb4: {
+//     return;
+// }
+// END rustc.if_let_uninit.DeduplicateBlocks.after.mir
@@ -0,0 +1,44 @@
+// compile-flags: -Z span_free_formats -Z mir-opt-level=2
+
+fn main() {
+    println!("{}", test(&()));
+}
+
+fn test(x: &()) -> u32 {
+    let y: &dyn X = x;
+    y.y()
+}
+
+trait X {
+    fn y(&self) -> u32;
+}
+
+impl X for () {
+    #[inline(never)]
+    fn y(&self) -> u32 {
+        2
+    }
+}
+
+// END RUST SOURCE
+// START rustc.test.Devirtualize.before.mir
+// ...
+// bb0: {
+// ...
+//     _2 = move _3 as &dyn X (Pointer(Unsize));
+// ...
+//     _0 = const <dyn X as X>::y(move _4) -> bb1;
+// }
+// ...
+// END rustc.test.Devirtualize.before.mir
+// START rustc.test.Devirtualize.after.mir
+// ...
+// bb0: {
+// ...
+//     _5 = _3;
+//     _2 = move _3 as &dyn X (Pointer(Unsize));
+// ...
+//     _0 = const <() as X>::y(move _5) -> bb1;
+// }
+// ...
+// END rustc.test.Devirtualize.after.mir
@@ -0,0 +1,68 @@
+// compile-flags: -Z mir-opt-level=2
+// ignore-tidy-linelength
+
+fn opt(x: Option<u32>, y: Option<u32>) -> u32 {
+    match (x, y) {
+        (Some(a), Some(b)) => 0,
+        _ => 1,
+    }
+}
+
+union U {
+    a: (Option<u32>, Option<u32>),
+    b: u64,
+}
+
+// The second field of the union may not be initialized, so its discriminant must not be read
+// before the first one is known to be `Some`.
+fn union_field(u: U) -> u32 {
+    unsafe {
+        match u.a {
+            (Some(_), Some(_)) => 0,
+            _ => 1,
+        }
+    }
+}
+
+fn main() {
+    opt(None, Some(0));
+    union_field(U { b: 0 });
+}
+
+// END RUST SOURCE
+// START rustc.opt.EarlyOtherwiseBranch.before.mir
+//  bb0: {
+//      ...
+//      _7 = discriminant((_3.0: std::option::Option<u32>));
+//      switchInt(move _7) -> [1isize: bb2, otherwise: bb1];
+//  }
+//  ...
+//  bb2: {
+//      _6 = discriminant((_3.1: std::option::Option<u32>));
+//      switchInt(move _6) -> [1isize: bb3, otherwise: bb1];
+//  }
+// END rustc.opt.EarlyOtherwiseBranch.before.mir
+// START rustc.opt.EarlyOtherwiseBranch.after.mir
+//  bb0: {
+//      ...
+//      _7 = discriminant((_3.0: std::option::Option<u32>));
+//      _6 = discriminant((_3.1: std::option::Option<u32>));
+//      _10 = Ne(_7, _6);
+//      switchInt(move _10) -> [false: bb5, otherwise: bb1];
+//  }
+//  ...
+//  bb5: {
+//      switchInt(_7) -> [1isize: bb3, otherwise: bb1];
+//  }
+// END rustc.opt.EarlyOtherwiseBranch.after.mir
+// START rustc.union_field.EarlyOtherwiseBranch.after.mir
+//  bb0: {
+//      _3 = discriminant(((_1.0: (std::option::Option<u32>, std::option::Option<u32>)).0: std::option::Option<u32>));
+//      switchInt(move _3) -> [1isize: bb2, otherwise: bb1];
+//  }
+//  ...
+//  bb2: {
+//      _2 = discriminant(((_1.0: (std::option::Option<u32>, std::option::Option<u32>)).1: std::option::Option<u32>));
+//      switchInt(move _2) -> [1isize: bb3, otherwise: bb1];
+//  }
+// END rustc.union_field.EarlyOtherwiseBranch.after.mir
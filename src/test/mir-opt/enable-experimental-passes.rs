@@ -0,0 +1,42 @@
+// compile-flags: -C overflow-checks=off -Z mir-enable-experimental-passes=experimental
+
+// Tests that experimental passes can be enabled without raising the MIR optimization level.
+
+fn sink(x: u32, y: u32, c: bool) -> u32 {
+    let z = x + y;
+    if c { z } else { 0 }
+}
+
+fn main() {
+    sink(1, 2, true);
+}
+
+// END RUST SOURCE
+// START rustc.sink.SinkStatements.before.mir
+//     bb0: {
+//         StorageLive(_4);
+//         ...
+//         _4 = Add(move _5, move _6);
+//         ...
+//         switchInt(_7) -> [false: bb1, otherwise: bb2];
+//     }
+// END rustc.sink.SinkStatements.before.mir
+// START rustc.sink.SinkStatements.after.mir
+//     bb0: {
+//         StorageLive(_7);
+//         _7 = _3;
+//         switchInt(_7) -> [false: bb1, otherwise: bb2];
+//     }
+//     ...
+//     bb2: {
+//         StorageLive(_5);
+//         _5 = _1;
+//         StorageLive(_6);
+//         _6 = _2;
+//         StorageLive(_4);
+//         _4 = Add(move _5, move _6);
+//         StorageDead(_6);
+//         StorageDead(_5);
+//         ...
+//     }
+// END rustc.sink.SinkStatements.after.mir
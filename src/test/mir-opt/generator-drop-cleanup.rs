@@ -5,6 +5,7 @@
 
 fn main() {
     let gen = || {
+        let _s = String::new();
         yield;
     };
 }
@@ -13,35 +14,23 @@ fn main() {
 
 // START rustc.main-{{closure}}.generator_drop.0.mir
 // bb0: {
-//     _7 = discriminant((*_1));
-//     switchInt(move _7) -> [0u32: bb4, 3u32: bb7, otherwise: bb8];
+//     ...
 // }
-// bb1: {
-//     StorageDead(_4);
-//     StorageDead(_3);
-//     goto -> bb5;
-// }
-// bb2: {
-//     return;
+// bb1 (cleanup): {
+//     resume;
 // }
-// bb3: {
-//     return;
+// ...
+//     drop((((*_1) as variant#3).0: std::string::String)) -> [return: bb4, unwind: bb2];
 // }
 // bb4: {
-//     goto -> bb6;
-// }
-// bb5: {
-//     goto -> bb2;
+//     goto -> bb9;
 // }
-// bb6: {
-//     goto -> bb3;
-// }
-// bb7: {
-//     StorageLive(_3);
-//     StorageLive(_4);
+// ...
+// bb8 (cleanup): {
 //     goto -> bb1;
 // }
-// bb8: {
-//     return;
+// bb9: {
+//     goto -> bb5;
 // }
+// ...
 // END rustc.main-{{closure}}.generator_drop.0.mir
@@ -0,0 +1,19 @@
+#![feature(generators, generator_trait)]
+
+// Checks that the drop shim of a generator that has nothing to drop returns
+// right away, without switching on the state.
+
+fn main() {
+    let gen = || {
+        yield;
+    };
+}
+
+// END RUST SOURCE
+
+// START rustc.main-{{closure}}.generator_drop.0.mir
+// bb0: {
+//     return;
+// }
+// }
+// END rustc.main-{{closure}}.generator_drop.0.mir
@@ -0,0 +1,26 @@
+// compile-flags: -Z span_free_formats -Z mir-opt-level=2
+
+// Tests that the `FnOnce` shim of a closure of a generic function is inlined, so that the body
+// of the closure is inlined through the `impl FnOnce` parameter of `apply`.
+
+fn main() {
+    println!("{}", foo(0u8, 14));
+}
+
+fn apply(f: impl FnOnce(i32) -> i32, x: i32) -> i32 {
+    f(x)
+}
+
+fn foo<T>(_t: T, q: i32) -> i32 {
+    apply(|_| 7, q)
+}
+
+// END RUST SOURCE
+// START rustc.foo.Inline.after.mir
+// ...
+// bb0: {
+// ...
+//     _0 = const 7i32;
+// ...
+// }
+// END rustc.foo.Inline.after.mir
@@ -0,0 +1,32 @@
+// compile-flags: -Z mir-opt-level=2 -Z mir-opt-fixpoint-limit=4
+
+// Tests that the inlining and const propagation fixpoint group unrolls a recursion on a
+// constant: each iteration inlines one more call, whose argument only becomes a constant after
+// the const propagation of the previous iteration.
+
+fn main() {
+    println!("{}", caller());
+}
+
+fn caller() -> u32 {
+    countdown(2)
+}
+
+#[inline]
+fn countdown(n: u32) -> u32 {
+    if n == 0 { 0 } else { countdown(n - 1) }
+}
+
+// END RUST SOURCE
+// START rustc.caller.PreCodegen.after.mir
+// fn caller() -> u32 {
+//     let mut _0: u32;
+//     ...
+//     bb0: {
+//         ...
+//         _0 = const 0u32;
+//         ...
+//         return;
+//     }
+// }
+// END rustc.caller.PreCodegen.after.mir
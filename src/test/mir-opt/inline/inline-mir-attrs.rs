@@ -0,0 +1,74 @@
+// Tests that `#[rustc_mir_inline]` and `#[rustc_no_mir_inline]` decide whether the MIR inliner
+// inlines a function, whatever its `#[inline]` hint for LLVM.
+
+// compile-flags: -Z span_free_formats
+
+#![feature(rustc_attrs)]
+
+fn main() {
+    call_never();
+    call_never_but_mir();
+    call_always_but_not_mir();
+    call_generic_not_mir();
+}
+
+#[inline(never)]
+fn never() -> u32 {
+    1
+}
+
+#[inline(never)]
+#[rustc_mir_inline]
+fn never_but_mir() -> u32 {
+    2
+}
+
+#[inline(always)]
+#[rustc_no_mir_inline]
+fn always_but_not_mir() -> u32 {
+    3
+}
+
+#[rustc_no_mir_inline]
+fn generic_not_mir<T>(x: T) -> T {
+    x
+}
+
+fn call_never() -> u32 {
+    never()
+}
+
+fn call_never_but_mir() -> u32 {
+    never_but_mir()
+}
+
+fn call_always_but_not_mir() -> u32 {
+    always_but_not_mir()
+}
+
+fn call_generic_not_mir() -> u32 {
+    generic_not_mir(4)
+}
+
+// END RUST SOURCE
+// START rustc.call_never.Inline.after.mir
+// bb0: {
+//   _0 = const never() -> bb1;
+// }
+// END rustc.call_never.Inline.after.mir
+// START rustc.call_never_but_mir.Inline.after.mir
+// bb0: {
+//   _0 = const 2u32;
+//   return;
+// }
+// END rustc.call_never_but_mir.Inline.after.mir
+// START rustc.call_always_but_not_mir.Inline.after.mir
+// bb0: {
+//   _0 = const always_but_not_mir() -> bb1;
+// }
+// END rustc.call_always_but_not_mir.Inline.after.mir
+// START rustc.call_generic_not_mir.Inline.after.mir
+// bb0: {
+//   _0 = const generic_not_mir::<u32>(const 4u32) -> bb1;
+// }
+// END rustc.call_generic_not_mir.Inline.after.mir
@@ -0,0 +1,37 @@
+// Tests that `#[optimize(speed)]` functions are inlined into even if `#![mir_opt_level]` turns
+// off MIR optimizations for the rest of the crate.
+
+#![feature(mir_opt_level, optimize_attribute)]
+#![mir_opt_level = "0"]
+
+fn main() {
+    hot();
+    cold();
+}
+
+#[inline(always)]
+fn answer() -> u32 {
+    42
+}
+
+#[optimize(speed)]
+fn hot() -> u32 {
+    answer()
+}
+
+fn cold() -> u32 {
+    answer()
+}
+
+// END RUST SOURCE
+// START rustc.hot.Inline.after.mir
+// bb0: {
+//   _0 = const 42u32;
+//   return;
+// }
+// END rustc.hot.Inline.after.mir
+// START rustc.cold.Inline.after.mir
+// bb0: {
+//   _0 = const answer() -> bb1;
+// }
+// END rustc.cold.Inline.after.mir
@@ -0,0 +1,20 @@
+// compile-flags: -Z span_free_formats -Z mir-opt-level=3
+
+fn clone(f: fn(u8)) -> fn(u8) {
+    f.clone()
+}
+
+fn main() {
+    clone(|_| {})(0);
+}
+
+// END RUST SOURCE
+// START rustc.clone.Inline.after.mir
+// ...
+// bb0: {
+// ...
+//     _0 = (*_2);
+// ...
+//     return;
+// }
+// END rustc.clone.Inline.after.mir
@@ -0,0 +1,39 @@
+// compile-flags: -C overflow-checks=on
+
+fn mul_pow2(x: u32) -> u32 {
+    x * 8
+}
+
+fn mul_pow2_signed(x: i32) -> i32 {
+    x * 8
+}
+
+fn mul_min(x: i32) -> i32 {
+    x * -2147483648
+}
+
+fn main() {
+    mul_pow2(1);
+    mul_pow2_signed(1);
+    mul_min(0);
+}
+
+// END RUST SOURCE
+// START rustc.mul_pow2.InstCombine.before.mir
+//     _3 = CheckedMul(move _2, const 8u32);
+// END rustc.mul_pow2.InstCombine.before.mir
+// START rustc.mul_pow2.InstCombine.after.mir
+//     _4 = Shl(_2, const 3u32);
+//     _5 = Shr(_4, const 3u32);
+//     _6 = Ne(move _5, _2);
+//     _3 = (move _4, move _6);
+// END rustc.mul_pow2.InstCombine.after.mir
+// START rustc.mul_pow2_signed.InstCombine.after.mir
+//     _4 = Shl(_2, const 3u32);
+//     _5 = Shr(_4, const 3u32);
+//     _6 = Ne(move _5, _2);
+//     _3 = (move _4, move _6);
+// END rustc.mul_pow2_signed.InstCombine.after.mir
+// START rustc.mul_min.InstCombine.after.mir
+//     _3 = CheckedMul(move _2, const std::i32::MIN);
+// END rustc.mul_min.InstCombine.after.mir
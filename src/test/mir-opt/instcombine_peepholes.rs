@@ -0,0 +1,148 @@
+// compile-flags: -C overflow-checks=off
+
+fn mul_one(x: u32) -> u32 {
+    x * 1
+}
+
+fn add_zero(x: u32) -> u32 {
+    x + 0
+}
+
+fn mul_pow2(x: i32) -> i32 {
+    x * 8
+}
+
+fn div_pow2(x: u32) -> u32 {
+    x / 16
+}
+
+fn rem_pow2(x: u32) -> u32 {
+    x % 16
+}
+
+fn double_not(x: bool) -> bool {
+    !!x
+}
+
+fn eq_true(x: bool) -> bool {
+    x == true
+}
+
+fn array_index(x: [u32; 4], i: usize) -> u32 {
+    x[i]
+}
+
+fn slice_index(x: &[u32], i: usize) -> u32 {
+    x[i]
+}
+
+enum Single {
+    Only,
+}
+
+enum Level {
+    Low,
+    High,
+}
+
+fn single_discr(x: Single) -> bool {
+    x as isize == 0
+}
+
+fn level_discr(x: Level) -> bool {
+    x as isize != 7
+}
+
+fn level_discr_unknown(x: Level) -> bool {
+    x as isize == 1
+}
+
+fn main() {
+    mul_one(1);
+    add_zero(1);
+    mul_pow2(1);
+    div_pow2(1);
+    rem_pow2(1);
+    double_not(true);
+    eq_true(true);
+    array_index([1, 2, 3, 4], 1);
+    slice_index(&[1, 2, 3, 4], 1);
+    single_discr(Single::Only);
+    level_discr(Level::Low);
+    level_discr_unknown(Level::High);
+}
+
+// END RUST SOURCE
+// START rustc.mul_one.InstCombine.before.mir
+//     _0 = Mul(move _2, const 1u32);
+// END rustc.mul_one.InstCombine.before.mir
+// START rustc.mul_one.InstCombine.after.mir
+//     _0 = move _2;
+// END rustc.mul_one.InstCombine.after.mir
+// START rustc.add_zero.InstCombine.before.mir
+//     _0 = Add(move _2, const 0u32);
+// END rustc.add_zero.InstCombine.before.mir
+// START rustc.add_zero.InstCombine.after.mir
+//     _0 = move _2;
+// END rustc.add_zero.InstCombine.after.mir
+// START rustc.mul_pow2.InstCombine.before.mir
+//     _0 = Mul(move _2, const 8i32);
+// END rustc.mul_pow2.InstCombine.before.mir
+// START rustc.mul_pow2.InstCombine.after.mir
+//     _0 = Shl(move _2, const 3u32);
+// END rustc.mul_pow2.InstCombine.after.mir
+// START rustc.div_pow2.InstCombine.before.mir
+//     _0 = Div(move _2, const 16u32);
+// END rustc.div_pow2.InstCombine.before.mir
+// START rustc.div_pow2.InstCombine.after.mir
+//     _0 = Shr(move _2, const 4u32);
+// END rustc.div_pow2.InstCombine.after.mir
+// START rustc.rem_pow2.InstCombine.before.mir
+//     _0 = Rem(move _2, const 16u32);
+// END rustc.rem_pow2.InstCombine.before.mir
+// START rustc.rem_pow2.InstCombine.after.mir
+//     _0 = BitAnd(move _2, const 15u32);
+// END rustc.rem_pow2.InstCombine.after.mir
+// START rustc.double_not.InstCombine.before.mir
+//     _2 = Not(move _3);
+//     ...
+//     _0 = Not(move _2);
+// END rustc.double_not.InstCombine.before.mir
+// START rustc.double_not.InstCombine.after.mir
+//     _2 = move _3;
+//     ...
+//     _0 = move _2;
+// END rustc.double_not.InstCombine.after.mir
+// START rustc.eq_true.InstCombine.before.mir
+//     _0 = Eq(move _2, const true);
+// END rustc.eq_true.InstCombine.before.mir
+// START rustc.eq_true.InstCombine.after.mir
+//     _0 = move _2;
+// END rustc.eq_true.InstCombine.after.mir
+// START rustc.array_index.InstCombine.before.mir
+//     _4 = Len(_1);
+// END rustc.array_index.InstCombine.before.mir
+// START rustc.array_index.InstCombine.after.mir
+//     _4 = const 4usize;
+// END rustc.array_index.InstCombine.after.mir
+// START rustc.slice_index.InstCombine.after.mir
+//     _4 = Len((*_1));
+// END rustc.slice_index.InstCombine.after.mir
+// START rustc.single_discr.InstCombine.before.mir
+//     _4 = discriminant(_3);
+//     _2 = move _4;
+//     ...
+//     _0 = Eq(move _2, const 0isize);
+// END rustc.single_discr.InstCombine.before.mir
+// START rustc.single_discr.InstCombine.after.mir
+//     _0 = const true;
+// END rustc.single_discr.InstCombine.after.mir
+// START rustc.level_discr.InstCombine.before.mir
+//     _0 = Ne(move _2, const 7isize);
+// END rustc.level_discr.InstCombine.before.mir
+// START rustc.level_discr.InstCombine.after.mir
+//     _0 = const true;
+// END rustc.level_discr.InstCombine.after.mir
+// START rustc.level_discr_unknown.InstCombine.after.mir
+//     _0 = Eq(move _2, const 1isize);
+// END rustc.level_discr_unknown.InstCombine.after.mir
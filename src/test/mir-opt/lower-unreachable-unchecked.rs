@@ -0,0 +1,50 @@
+// Checks that the branches to `unreachable_unchecked` become `assume` statements.
+
+use std::hint::unreachable_unchecked;
+
+fn get(a: &[u8; 4], i: usize) -> u8 {
+    match i < 4 {
+        true => a[i],
+        false => unsafe { unreachable_unchecked() },
+    }
+}
+
+fn get_negated(a: &[u8; 4], i: usize) -> u8 {
+    if !(i < 4) {
+        unsafe { unreachable_unchecked() }
+    }
+    a[i]
+}
+
+fn main() {
+    get(&[1, 2, 3, 4], 0);
+    get_negated(&[1, 2, 3, 4], 0);
+}
+
+// END RUST SOURCE
+// START rustc.get.LowerIntrinsics.after.mir
+// fn get(_1: &[u8; 4], _2: usize) -> u8 {
+//     ...
+//     bb0: {
+//         ...
+//         _3 = Lt(move _4, const 4usize);
+//         ...
+//         assume(_3);
+//         ...
+//     }
+//     ...
+// }
+// END rustc.get.LowerIntrinsics.after.mir
+// START rustc.get_negated.LowerIntrinsics.after.mir
+// fn get_negated(_1: &[u8; 4], _2: usize) -> u8 {
+//     ...
+//     bb0: {
+//         ...
+//         _5 = Lt(move _6, const 4usize);
+//         ...
+//         assume(move _5);
+//         ...
+//     }
+//     ...
+// }
+// END rustc.get_negated.LowerIntrinsics.after.mir
@@ -132,10 +132,10 @@ fn main() {
 // }
 // bb16: {
 //     StorageDead(_9);
-//     FakeRead(ForMatchGuard, _3);
-//     FakeRead(ForMatchGuard, _4);
-//     FakeRead(ForGuardBinding, _6);
-//     FakeRead(ForGuardBinding, _8);
+//     FakeRead(ForMatchGuard(0, Tested), _3);
+//     FakeRead(ForMatchGuard(0, Tested), _4);
+//     FakeRead(ForGuardBinding(0), _6);
+//     FakeRead(ForGuardBinding(0), _8);
 //     StorageLive(_5);
 //     _5 = (_2.1: bool);
 //     StorageLive(_7);
@@ -179,10 +179,10 @@ fn main() {
 // }
 // bb22: {                              // bindings for arm 1
 //     StorageDead(_12);
-//     FakeRead(ForMatchGuard, _3);
-//     FakeRead(ForMatchGuard, _4);
-//     FakeRead(ForGuardBinding, _6);
-//     FakeRead(ForGuardBinding, _8);
+//     FakeRead(ForMatchGuard(0, Tested), _3);
+//     FakeRead(ForMatchGuard(0, Tested), _4);
+//     FakeRead(ForGuardBinding(0), _6);
+//     FakeRead(ForGuardBinding(0), _8);
 //     StorageLive(_5);
 //     _5 = (_2.0: bool);
 //     StorageLive(_7);
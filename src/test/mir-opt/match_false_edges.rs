@@ -76,8 +76,8 @@ fn main() {
 //  }
 //  bb8: { // arm1
 //      StorageDead(_7);
-//      FakeRead(ForMatchGuard, _4);
-//      FakeRead(ForGuardBinding, _6);
+//      FakeRead(ForMatchGuard(0, Tested), _4);
+//      FakeRead(ForGuardBinding(0), _6);
 //      StorageLive(_5);
 //      _5 = ((_2 as Some).0: i32);
 //      StorageLive(_8);
@@ -153,8 +153,8 @@ fn main() {
 //  }
 //  bb8: {
 //      StorageDead(_7);
-//      FakeRead(ForMatchGuard, _4);
-//      FakeRead(ForGuardBinding, _6);
+//      FakeRead(ForMatchGuard(0, Tested), _4);
+//      FakeRead(ForGuardBinding(0), _6);
 //      StorageLive(_5);
 //      _5 = ((_2 as Some).0: i32);
 //      StorageLive(_8);
@@ -221,8 +221,8 @@ fn main() {
 //  }
 //  bb8: {
 //      StorageDead(_8);
-//      FakeRead(ForMatchGuard, _5);
-//      FakeRead(ForGuardBinding, _7);
+//      FakeRead(ForMatchGuard(0, Tested), _5);
+//      FakeRead(ForGuardBinding(0), _7);
 //      StorageLive(_6);
 //      _6 = ((_2 as Some).0: i32);
 //      _1 = const 1i32;
@@ -257,8 +257,8 @@ fn main() {
 //  }
 //  bb13: { // binding4 & arm4
 //      StorageDead(_12);
-//      FakeRead(ForMatchGuard, _5);
-//      FakeRead(ForGuardBinding, _11);
+//      FakeRead(ForMatchGuard(2, Tested), _5);
+//      FakeRead(ForGuardBinding(2), _11);
 //      StorageLive(_10);
 //      _10 = ((_2 as Some).0: i32);
 //      _1 = const 3i32;
@@ -58,7 +58,7 @@ fn main() {
 //    }
 //    bb10: {
 //        StorageDead(_9);
-//        FakeRead(ForMatchGuard, _8);
+//        FakeRead(ForMatchGuard(0, Tested), _8);
 //        _3 = const 0i32;
 //        goto -> bb14;
 //    }
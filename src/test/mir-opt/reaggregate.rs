@@ -0,0 +1,34 @@
+// Checks that the field writes of inlined MIR are merged back into an aggregate.
+//
+// compile-flags: -Zmir-opt-level=2
+
+#[inline]
+fn three() -> Option<u8> {
+    Some(3)
+}
+
+fn foo() -> Option<u8> {
+    three()
+}
+
+fn main() {
+    foo();
+}
+
+// END RUST SOURCE
+// START rustc.foo.Reaggregate.before.mir
+// fn foo() -> std::option::Option<u8> {
+//     ...
+//         ((_0 as Some).0: u8) = const 3u8;
+//         discriminant(_0) = 1;
+//     ...
+// }
+// END rustc.foo.Reaggregate.before.mir
+// START rustc.foo.Reaggregate.after.mir
+// fn foo() -> std::option::Option<u8> {
+//     ...
+//         _0 = std::option::Option::<u8>::Some(const 3u8,);
+//         nop;
+//     ...
+// }
+// END rustc.foo.Reaggregate.after.mir
@@ -42,10 +42,10 @@ fn main() {
 // }
 // bb5: {
 //     StorageDead(_8);
-//     FakeRead(ForMatchGuard, _4);
-//     FakeRead(ForMatchGuard, _5);
-//     FakeRead(ForMatchGuard, _6);
-//     FakeRead(ForMatchGuard, _7);
+//     FakeRead(ForMatchGuard(0, Tested), _4);
+//     FakeRead(ForMatchGuard(0, Dereferenced), _5);
+//     FakeRead(ForMatchGuard(0, Dereferenced), _6);
+//     FakeRead(ForMatchGuard(0, Tested), _7);
 //     _0 = const 0i32;
 //     goto -> bb7;
 // }
@@ -0,0 +1,36 @@
+// Checks that the conversion of a value into its own type is replaced by a move, which lets
+// `SimplifyArmIdentity` remove the arm.
+//
+// compile-flags: -Zmir-opt-level=1 -Zmir-enable-experimental-passes=experimental
+
+fn id(o: Option<u8>) -> Option<u8> {
+    match o {
+        Some(v) => Some(v.into()),
+        None => None,
+    }
+}
+
+fn main() {
+    id(None);
+}
+
+// END RUST SOURCE
+// START rustc.id.RemoveIdentityCalls.before.mir
+//     bb3: {
+//         ...
+//         _4 = const <u8 as std::convert::Into<u8>>::into(move _5) -> bb4;
+//     }
+// END rustc.id.RemoveIdentityCalls.before.mir
+// START rustc.id.RemoveIdentityCalls.after.mir
+//     bb3: {
+//         ...
+//         _4 = move _5;
+//         goto -> bb4;
+//     }
+// END rustc.id.RemoveIdentityCalls.after.mir
+// START rustc.id.SimplifyArmIdentity.after.mir
+//     bb3: {
+//         _0 = move _1;
+//         ...
+//     }
+// END rustc.id.SimplifyArmIdentity.after.mir
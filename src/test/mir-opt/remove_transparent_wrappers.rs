@@ -0,0 +1,32 @@
+// compile-flags: -Z mir-enable-experimental-passes=experimental
+
+#[repr(transparent)]
+struct Wrapper(u64);
+
+fn unwrap(x: u64) -> u64 {
+    Wrapper(x).0
+}
+
+fn main() {
+    unwrap(0);
+}
+
+// END RUST SOURCE
+// START rustc.unwrap.RemoveTransparentWrappers.before.mir
+//  bb0: {
+//      ...
+//      _2 = Wrapper(move _3,);
+//      ...
+//      _0 = move (_2.0: u64);
+//      ...
+//  }
+// END rustc.unwrap.RemoveTransparentWrappers.before.mir
+// START rustc.unwrap.RemoveTransparentWrappers.after.mir
+//  bb0: {
+//      ...
+//      nop;
+//      ...
+//      _0 = move _3;
+//      ...
+//  }
+// END rustc.unwrap.RemoveTransparentWrappers.after.mir
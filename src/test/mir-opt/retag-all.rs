@@ -0,0 +1,22 @@
+// compile-flags: -Z mir-emit-retag=all -Z mir-opt-level=0 -Z span_free_formats
+
+// Tests that `-Z mir-emit-retag=all` retags two-phase borrows like any other reference.
+
+fn push_len(v: &mut Vec<usize>) {
+    v.push(v.len());
+}
+
+fn main() {
+    push_len(&mut vec![]);
+}
+
+// END RUST SOURCE
+// START rustc.push_len.EraseRegions.after.mir
+//     bb0: {
+//         Retag([fn entry] _1);
+//         StorageLive(_2);
+//         _2 = &mut (*_1);
+//         Retag(_2);
+//         ...
+//     }
+// END rustc.push_len.EraseRegions.after.mir
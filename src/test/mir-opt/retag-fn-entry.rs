@@ -0,0 +1,25 @@
+// compile-flags: -Z mir-emit-retag=fn-entry -Z mir-opt-level=0 -Z span_free_formats
+
+// Tests that `-Z mir-emit-retag=fn-entry` only retags function arguments.
+
+fn foo(x: &mut i32) -> &mut i32 {
+    let y = &mut *x;
+    y
+}
+
+fn main() {
+    let mut x = 0;
+    foo(&mut x);
+}
+
+// END RUST SOURCE
+// START rustc.foo.EraseRegions.after.mir
+//     bb0: {
+//         Retag([fn entry] _1);
+//         StorageLive(_2);
+//         _2 = &mut (*_1);
+//         StorageLive(_3);
+//         ...
+//         return;
+//     }
+// END rustc.foo.EraseRegions.after.mir
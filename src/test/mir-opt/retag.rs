@@ -71,7 +71,7 @@ fn main() {
 //     ...
 //
 //     bb2: {
-//         Retag(_3);
+//         Retag([fn return] _3);
 //         ...
 //         _9 = move _3;
 //         Retag(_9);
@@ -89,7 +89,7 @@ fn main() {
 //     }
 //
 //     bb5: {
-//         Retag(_15);
+//         Retag([fn return] _15);
 //         ...
 //         _19 = const Test::foo_shr(move _20, move _22) -> [return: bb6, unwind: bb7];
 //     }
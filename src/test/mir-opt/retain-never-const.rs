@@ -23,8 +23,6 @@ fn main() {}
 // END RUST SOURCE
 // START rustc.no_codegen.PreCodegen.after.mir
 // bb0: {
-//     StorageLive(_1);
-//     _1 = const PrintName::<T>::VOID;
 //     unreachable;
 // }
 // END rustc.no_codegen.PreCodegen.after.mir
@@ -0,0 +1,31 @@
+// Checks that `SimplifyArmIdentity` sees the arms before they are deaggregated, including those
+// with several fields.
+//
+// compile-flags: -Zmir-opt-level=2
+
+enum E {
+    A(u8, u16),
+    B(u32),
+}
+
+fn id(e: E) -> E {
+    match e {
+        E::A(a, b) => E::A(a, b),
+        E::B(c) => E::B(c),
+    }
+}
+
+fn main() {
+    id(E::B(0));
+}
+
+// END RUST SOURCE
+// START rustc.id.SimplifyArmIdentity.after.mir
+// fn id(_1: E) -> E {
+//     ...
+//         _0 = move _1;
+//     ...
+//         _0 = move _1;
+//     ...
+// }
+// END rustc.id.SimplifyArmIdentity.after.mir
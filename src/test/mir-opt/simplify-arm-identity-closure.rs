@@ -0,0 +1,21 @@
+// Checks that `SimplifyArmIdentity` sees through the residue of an inlined identity closure, which
+// packs its argument into a tuple and unpacks it again.
+//
+// compile-flags: -Zmir-opt-level=2
+
+fn id(o: Option<u32>) -> Option<u32> {
+    o.map(|x| x)
+}
+
+fn main() {
+    id(None);
+}
+
+// END RUST SOURCE
+// START rustc.id.SimplifyArmIdentity.after.mir
+// fn id(_1: std::option::Option<u32>) -> std::option::Option<u32> {
+//     ...
+//         _0 = move _2;
+//     ...
+// }
+// END rustc.id.SimplifyArmIdentity.after.mir
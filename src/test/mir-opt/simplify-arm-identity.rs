@@ -26,22 +26,19 @@ fn main() {
 //     ...
 //     bb0: {
 //         StorageLive(_1);
-//         ((_1 as Foo).0: u8) = const 0u8;
-//         discriminant(_1) = 0;
+//         _1 = Src::Foo(const 0u8,);
 //         StorageLive(_2);
 //         _3 = discriminant(_1);
 //         switchInt(move _3) -> [0isize: bb3, 1isize: bb1, otherwise: bb2];
 //     }
 //     bb1: {
-//         ((_2 as Foo).0: u8) = const 0u8;
-//         discriminant(_2) = 0;
+//         _2 = Dst::Foo(const 0u8,);
 //         goto -> bb4;
 //     }
 //     ...
 //     bb3: {
 //         _4 = ((_1 as Foo).0: u8);
-//         ((_2 as Foo).0: u8) = move _4;
-//         discriminant(_2) = 0;
+//         _2 = Dst::Foo(move _4,);
 //         goto -> bb4;
 //     }
 //     ...
@@ -52,22 +49,19 @@ fn main() {
 //     ...
 //     bb0: {
 //         StorageLive(_1);
-//         ((_1 as Foo).0: u8) = const 0u8;
-//         discriminant(_1) = 0;
+//         _1 = Src::Foo(const 0u8,);
 //         StorageLive(_2);
 //         _3 = discriminant(_1);
 //         switchInt(move _3) -> [0isize: bb3, 1isize: bb1, otherwise: bb2];
 //     }
 //     bb1: {
-//         ((_2 as Foo).0: u8) = const 0u8;
-//         discriminant(_2) = 0;
+//         _2 = Dst::Foo(const 0u8,);
 //         goto -> bb4;
 //     }
 //     ...
 //     bb3: {
 //         _4 = ((_1 as Foo).0: u8);
-//         ((_2 as Foo).0: u8) = move _4;
-//         discriminant(_2) = 0;
+//         _2 = Dst::Foo(move _4,);
 //         goto -> bb4;
 //     }
 //     ...
@@ -0,0 +1,56 @@
+// Checks that a temporary whose only borrow is never used is removed along with the borrow, and
+// with the borrows of the borrow.
+//
+// compile-flags: -C overflow-checks=off
+
+fn borrow_unused(x: u32) {
+    let _ = &(x + 1);
+}
+
+fn borrow_chain_unused(x: u32) {
+    let _ = &&(x + 1);
+}
+
+fn main() {
+    borrow_unused(0);
+    borrow_chain_unused(0);
+}
+
+// END RUST SOURCE
+// START rustc.borrow_unused.SimplifyLocals.before.mir
+//     bb0: {
+//         ...
+//         _3 = Add(_1, const 1u32);
+//         _2 = &_3;
+//         ...
+//     }
+// END rustc.borrow_unused.SimplifyLocals.before.mir
+// START rustc.borrow_unused.SimplifyLocals.after.mir
+// fn borrow_unused(_1: u32) -> () {
+//     ...
+//     let mut _0: ();
+//     bb0: {
+//         ...
+//         return;
+//     }
+// }
+// END rustc.borrow_unused.SimplifyLocals.after.mir
+// START rustc.borrow_chain_unused.SimplifyLocals.before.mir
+//     bb0: {
+//         ...
+//         _4 = Add(_1, const 1u32);
+//         _3 = &_4;
+//         _2 = &_3;
+//         ...
+//     }
+// END rustc.borrow_chain_unused.SimplifyLocals.before.mir
+// START rustc.borrow_chain_unused.SimplifyLocals.after.mir
+// fn borrow_chain_unused(_1: u32) -> () {
+//     ...
+//     let mut _0: ();
+//     bb0: {
+//         ...
+//         return;
+//     }
+// }
+// END rustc.borrow_chain_unused.SimplifyLocals.after.mir
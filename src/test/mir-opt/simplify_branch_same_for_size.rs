@@ -0,0 +1,29 @@
+// Tests that switch targets are deduplicated in functions optimized for size.
+
+#![feature(optimize_attribute)]
+
+#[optimize(size)]
+fn pick(x: u8) -> u32 {
+    match x {
+        0 => 1,
+        1 => 2,
+        2 => 1,
+        _ => 2,
+    }
+}
+
+fn main() {
+    pick(0);
+}
+
+// END RUST SOURCE
+// START rustc.pick.SimplifyBranchSame.before.mir
+// bb0: {
+//     switchInt(_1) -> [0u8: bb2, 1u8: bb3, 2u8: bb4, otherwise: bb1];
+// }
+// END rustc.pick.SimplifyBranchSame.before.mir
+// START rustc.pick.SimplifyBranchSame.after.mir
+// bb0: {
+//     switchInt(_1) -> [0u8: bb1, 1u8: bb2, 2u8: bb1, otherwise: bb2];
+// }
+// END rustc.pick.SimplifyBranchSame.after.mir
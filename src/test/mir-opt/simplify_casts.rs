@@ -0,0 +1,40 @@
+// compile-flags: -Z mir-enable-experimental-passes=experimental
+
+fn chain(x: u8) -> u32 {
+    x as u32 as usize as u32
+}
+
+fn sign_change(x: i32) -> u8 {
+    x as u32 as u8
+}
+
+fn main() {
+    chain(42);
+    sign_change(42);
+}
+
+// END RUST SOURCE
+// START rustc.chain.SimplifyCasts.before.mir
+//     _3 = move _4 as u32 (Misc);
+//     ...
+//     _2 = move _3 as usize (Misc);
+//     ...
+//     _0 = move _2 as u32 (Misc);
+// END rustc.chain.SimplifyCasts.before.mir
+// START rustc.chain.SimplifyCasts.after.mir
+//     _3 = move _4 as u32 (Misc);
+//     ...
+//     _2 = move _3;
+//     ...
+//     _0 = move _2;
+// END rustc.chain.SimplifyCasts.after.mir
+// START rustc.sign_change.SimplifyCasts.before.mir
+//     _2 = move _3 as u32 (Misc);
+//     ...
+//     _0 = move _2 as u8 (Misc);
+// END rustc.sign_change.SimplifyCasts.before.mir
+// START rustc.sign_change.SimplifyCasts.after.mir
+//     _2 = move _3 as u8 (Misc);
+//     ...
+//     _0 = move _2;
+// END rustc.sign_change.SimplifyCasts.after.mir
@@ -0,0 +1,38 @@
+// compile-flags: -Z mir-enable-experimental-passes=experimental
+
+fn opt_u32(x: u32) -> u32 {
+    if x == 42 { 0 } else { 1 }
+}
+
+fn opt_lower_bound(x: u8) -> u32 {
+    if x <= 0 { 0 } else { 1 }
+}
+
+fn main() {
+    opt_u32(0);
+    opt_lower_bound(0);
+}
+
+// END RUST SOURCE
+// START rustc.opt_u32.SimplifyComparisonIntegral.before.mir
+//  bb0: {
+//      ...
+//      _2 = Eq(move _3, const 42u32);
+//      ...
+//      switchInt(_2) -> [false: bb1, otherwise: bb2];
+//  }
+// END rustc.opt_u32.SimplifyComparisonIntegral.before.mir
+// START rustc.opt_u32.SimplifyComparisonIntegral.after.mir
+//  bb0: {
+//      ...
+//      nop;
+//      ...
+//      switchInt(_3) -> [42u32: bb2, otherwise: bb1];
+//  }
+// END rustc.opt_u32.SimplifyComparisonIntegral.after.mir
+// START rustc.opt_lower_bound.SimplifyComparisonIntegral.after.mir
+//  bb0: {
+//      ...
+//      switchInt(_3) -> [0u8: bb2, otherwise: bb1];
+//  }
+// END rustc.opt_lower_bound.SimplifyComparisonIntegral.after.mir
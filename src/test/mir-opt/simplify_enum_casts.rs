@@ -0,0 +1,25 @@
+// compile-flags: -Z mir-enable-experimental-passes=experimental
+
+enum State {
+    Start,
+    Running,
+    Done,
+}
+
+fn to_u8(state: State) -> u8 {
+    state as u8
+}
+
+fn main() {
+    to_u8(State::Running);
+    let _ = (State::Start, State::Done);
+}
+
+// END RUST SOURCE
+// START rustc.to_u8.SimplifyEnumCasts.before.mir
+//     _0 = move _1 as u8 (Misc);
+// END rustc.to_u8.SimplifyEnumCasts.before.mir
+// START rustc.to_u8.SimplifyEnumCasts.after.mir
+//     _2 = discriminant(_1);
+//     _0 = move _2 as u8 (Misc);
+// END rustc.to_u8.SimplifyEnumCasts.after.mir
@@ -0,0 +1,35 @@
+// compile-flags: -Z mir-enable-experimental-passes=experimental
+
+// Checks that matching on a value whose variant is already known goes to the arm of that variant.
+
+fn unwrap_matched(x: Option<u32>) -> u32 {
+    match x {
+        Some(_) => match x {
+            Some(v) => v,
+            None => 1,
+        },
+        None => 0,
+    }
+}
+
+fn main() {
+    unwrap_matched(Some(0));
+}
+
+// END RUST SOURCE
+// START rustc.unwrap_matched.SimplifyKnownVariants.before.mir
+// fn unwrap_matched(_1: std::option::Option<u32>) -> u32 {
+//     ...
+//         _3 = discriminant(_1);
+//         switchInt(move _3) -> [0isize: bb4, 1isize: bb6, otherwise: bb5];
+//     ...
+// }
+// END rustc.unwrap_matched.SimplifyKnownVariants.before.mir
+// START rustc.unwrap_matched.SimplifyKnownVariants.after.mir
+// fn unwrap_matched(_1: std::option::Option<u32>) -> u32 {
+//     ...
+//         _3 = discriminant(_1);
+//         goto -> bb6;
+//     ...
+// }
+// END rustc.unwrap_matched.SimplifyKnownVariants.after.mir
@@ -0,0 +1,30 @@
+// Checks that the paths of `&&` go directly to the branch of the `if` they lead to, once the
+// constant operand is known.
+//
+// compile-flags: -Zmir-opt-level=1 -Zmir-enable-experimental-passes=experimental
+
+#[inline(never)]
+fn check() -> bool {
+    true
+}
+
+fn select() -> u32 {
+    if cfg!(not(never_set)) && check() { 1 } else { 0 }
+}
+
+fn main() {
+    select();
+}
+
+// END RUST SOURCE
+// START rustc.select.SimplifyCfg-final.after.mir
+//     ...
+//         _1 = const true;
+//         StorageDead(_2);
+//         _0 = const 1u32;
+//     ...
+//         _1 = const false;
+//         StorageDead(_2);
+//         _0 = const 0u32;
+//     ...
+// END rustc.select.SimplifyCfg-final.after.mir
@@ -0,0 +1,31 @@
+// compile-flags: -Z mir-enable-experimental-passes=experimental
+
+// Checks that slice patterns on unsized arrays don't test the length of the slice.
+
+fn main() {
+    test([1, 2]);
+}
+
+fn test(a: [u8; 2]) -> u8 {
+    let s: &[u8] = &a;
+    match s {
+        [x, _] => *x,
+        _ => 0,
+    }
+}
+
+// END RUST SOURCE
+// START rustc.test.SimplifySlicePatterns.before.mir
+// ...
+//     _2 = move _3 as &[u8] (Pointer(Unsize));
+// ...
+//     _4 = Len((*_2));
+// ...
+// END rustc.test.SimplifySlicePatterns.before.mir
+// START rustc.test.SimplifySlicePatterns.after.mir
+// ...
+//     _2 = move _3 as &[u8] (Pointer(Unsize));
+// ...
+//     _4 = const 2usize;
+// ...
+// END rustc.test.SimplifySlicePatterns.after.mir
@@ -51,14 +51,12 @@ fn main() {
 //     }
 //     bb1: {
 //         _10 = ((_1 as Ok).0: u32);
-//         ((_0 as Ok).0: u32) = move _10;
-//         discriminant(_0) = 0;
+//         _0 = std::result::Result::<u32, i32>::Ok(move _10,);
 //         goto -> bb3;
 //     }
 //     bb2: {
 //         _6 = ((_1 as Err).0: i32);
-//         ((_0 as Err).0: i32) = move _6;
-//         discriminant(_0) = 1;
+//         _0 = std::result::Result::<u32, i32>::Err(move _6,);
 //         goto -> bb3;
 //     }
 //     bb3: {
@@ -83,6 +81,8 @@ fn main() {
 //     let mut _11: u32;
 //     scope 1 {
 //         debug y => _10;
+//         scope 9 {
+//         }
 //     }
 //     scope 2 {
 //         debug err => _6;
@@ -93,6 +93,8 @@ fn main() {
 //             scope 8 {
 //                 debug v => _6;
 //                 let mut _12: i32;
+//                 scope 10 {
+//                 }
 //             }
 //         }
 //     }
@@ -111,13 +113,11 @@ fn main() {
 //     bb1: {
 //         _0 = move _1;
 //         nop;
-//         nop;
 //         goto -> bb3;
 //     }
 //     bb2: {
 //         _0 = move _1;
 //         nop;
-//         nop;
 //         goto -> bb3;
 //     }
 //     bb3: {
@@ -142,6 +142,8 @@ fn main() {
 //     let mut _11: u32;
 //     scope 1 {
 //         debug y => _10;
+//         scope 9 {
+//         }
 //     }
 //     scope 2 {
 //         debug err => _6;
@@ -152,6 +154,8 @@ fn main() {
 //             scope 8 {
 //                 debug v => _6;
 //                 let mut _12: i32;
+//                 scope 10 {
+//                 }
 //             }
 //         }
 //     }
@@ -165,15 +169,11 @@ fn main() {
 //     }
 //     bb0: {
 //         _5 = discriminant(_1);
-//         goto -> bb1;
-//     }
-//     bb1: {
 //         _0 = move _1;
 //         nop;
-//         nop;
-//         goto -> bb2;
+//         goto -> bb1;
 //     }
-//     bb2: {
+//     bb1: {
 //         return;
 //     }
 // }
@@ -188,6 +188,8 @@ fn main() {
 //     let _4: u32;
 //     scope 1 {
 //         debug y => _4;
+//         scope 9 {
+//         }
 //     }
 //     scope 2 {
 //         debug err => _3;
@@ -197,6 +199,8 @@ fn main() {
 //             }
 //             scope 8 {
 //                 debug v => _3;
+//                 scope 10 {
+//                 }
 //             }
 //         }
 //     }
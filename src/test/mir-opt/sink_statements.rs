@@ -0,0 +1,84 @@
+// compile-flags: -C overflow-checks=off -Z mir-opt-level=2
+
+fn sink(x: u32, y: u32, c: bool) -> u32 {
+    let z = x + y;
+    if c { z } else { 0 }
+}
+
+fn read_through_pointer(p: &u32, c: bool) -> u32 {
+    let z = *p;
+    if c { z } else { 0 }
+}
+
+fn borrowed(x: u32, c: bool) -> u32 {
+    let mut y = x;
+    let p = &mut y as *mut u32;
+    let z = y + 1;
+    unsafe { *p = 0 };
+    if c { z } else { 0 }
+}
+
+fn main() {
+    sink(1, 2, true);
+    read_through_pointer(&1, true);
+    borrowed(1, true);
+}
+
+// END RUST SOURCE
+// START rustc.sink.SinkStatements.before.mir
+//     bb0: {
+//         StorageLive(_4);
+//         ...
+//         _4 = Add(move _5, move _6);
+//         ...
+//         switchInt(_7) -> [false: bb1, otherwise: bb2];
+//     }
+// END rustc.sink.SinkStatements.before.mir
+// START rustc.sink.SinkStatements.after.mir
+//     bb0: {
+//         StorageLive(_7);
+//         _7 = _3;
+//         switchInt(_7) -> [false: bb1, otherwise: bb2];
+//     }
+//     ...
+//     bb2: {
+//         StorageLive(_5);
+//         _5 = _1;
+//         StorageLive(_6);
+//         _6 = _2;
+//         StorageLive(_4);
+//         _4 = Add(move _5, move _6);
+//         StorageDead(_6);
+//         StorageDead(_5);
+//         ...
+//     }
+// END rustc.sink.SinkStatements.after.mir
+// START rustc.read_through_pointer.SinkStatements.after.mir
+//     bb0: {
+//         StorageLive(_4);
+//         _4 = _2;
+//         switchInt(_4) -> [false: bb1, otherwise: bb2];
+//     }
+//     ...
+//     bb2: {
+//         StorageLive(_3);
+//         _3 = (*_1);
+//         ...
+//     }
+// END rustc.read_through_pointer.SinkStatements.after.mir
+// START rustc.borrowed.SinkStatements.after.mir
+//     bb0: {
+//         ...
+//         _7 = _3;
+//         ...
+//         (*_4) = const 0u32;
+//         ...
+//     }
+//     ...
+//     bb2: {
+//         StorageLive(_6);
+//         _6 = Add(move _7, const 1u32);
+//         StorageDead(_7);
+//         ...
+//     }
+// END rustc.borrowed.SinkStatements.after.mir
@@ -0,0 +1,19 @@
+// Test spanview output
+// compile-flags: -Z dump-mir-spanview
+
+fn main() {}
+
+// END RUST SOURCE
+// START rustc.main.mir_map.0.html
+// <!DOCTYPE html>
+// <html>
+// <head>
+// <meta charset="utf-8">
+// ...
+// <title>main</title>
+// </head>
+// <body>
+// ...
+// </body>
+// </html>
+// END rustc.main.mir_map.0.html
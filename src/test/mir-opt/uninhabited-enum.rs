@@ -20,9 +20,6 @@ fn main() {}
 //
 // START rustc.process_never.SimplifyLocals.after.mir
 // bb0: {
-//     StorageLive(_2);
-//     _2 = &(*_1);
-//     StorageDead(_2);
 //     unreachable;
 // }
 // END rustc.process_never.SimplifyLocals.after.mir
@@ -50,9 +50,6 @@ fn main() {
 //          goto -> bb6;
 //      }
 //      bb6: {
-//          StorageDead(_6);
-//          StorageDead(_5);
-//          StorageLive(_9);
 //          unreachable;
 //      }
 //  }
@@ -36,9 +36,6 @@ fn main() {
 //          _5 = const loop_forever() -> bb6;
 //      }
 //      bb6: {
-//          StorageDead(_6);
-//          StorageDead(_5);
-//          StorageLive(_7);
 //          unreachable;
 //      }
 //  }
@@ -56,9 +53,6 @@ fn main() {
 //          _5 = const loop_forever() -> bb5;
 //      }
 //      bb5: {
-//          StorageDead(_6);
-//          StorageDead(_5);
-//          StorageLive(_7);
 //          unreachable;
 //      }
 //  }
@@ -0,0 +1,85 @@
+//! The setup shared by the run-make tests that drive the compiler through `rustc_interface`,
+//! included with `#[path = "../auxiliary/driver_support.rs"] mod driver_support;`. The including
+//! crate declares the `extern crate`s this needs. The driver is run as
+//! `$(call RUN,driver $(TMPDIR) $(RUSTC))`, and needs the path to rustc to get the sysroot.
+
+#![allow(dead_code)]
+
+use rustc::session::config::{Input, Options, OutputType, OutputTypes};
+use rustc::session::DiagnosticOutput;
+use rustc::ty::TyCtxt;
+use rustc_interface::interface;
+use rustc_span::source_map::FileName;
+
+use std::path::PathBuf;
+
+/// The `$(TMPDIR)` the driver is run with.
+pub fn tmpdir() -> PathBuf {
+    PathBuf::from(std::env::args().nth(1).expect("expected tmpdir"))
+}
+
+/// The configuration compiling `src` with the sysroot of the `$(RUSTC)` the driver is run with,
+/// without emitting anything.
+pub fn config(src: &str) -> interface::Config {
+    let args: Vec<String> = std::env::args().collect();
+
+    // `$(RUSTC)` starts with the environment of the host rpath, then the path to rustc.
+    if args.len() < 4 {
+        panic!("expected rustc path");
+    }
+
+    let mut sysroot = PathBuf::from(&args[3]);
+    sysroot.pop();
+    sysroot.pop();
+
+    let mut opts = Options::default();
+    opts.maybe_sysroot = Some(sysroot);
+
+    if let Ok(linker) = std::env::var("RUSTC_LINKER") {
+        opts.cg.linker = Some(linker.into());
+    }
+
+    let name = FileName::anon_source_code(src);
+    let input = Input::Str { name, input: src.to_string() };
+
+    interface::Config {
+        opts,
+        crate_cfg: Default::default(),
+        input,
+        input_path: None,
+        output_file: None,
+        output_dir: None,
+        file_loader: None,
+        diagnostic_output: DiagnosticOutput::Default,
+        stderr: None,
+        crate_name: None,
+        lint_caps: Default::default(),
+        register_lints: None,
+        register_mir_passes: None,
+        override_queries: None,
+        registry: rustc_driver::diagnostics_registry(),
+    }
+}
+
+/// Like `config`, but builds `src` into the executable `$(TMPDIR)/out`, see `link`.
+pub fn exe_config(src: &str) -> interface::Config {
+    let mut config = config(src);
+    config.opts.output_types = OutputTypes::new(&[(OutputType::Exe, None)]);
+    config.output_file = Some(tmpdir().join("out"));
+    config
+}
+
+/// Compiles and links the crate of `config`.
+pub fn link(config: interface::Config) {
+    interface::run_compiler(config, |compiler| {
+        let linker = compiler.enter(|queries| queries.linker()).unwrap();
+        linker.link().unwrap();
+    });
+}
+
+/// Calls `f` with the type context of the crate of `config`.
+pub fn enter_tcx(config: interface::Config, f: impl for<'tcx> FnOnce(TyCtxt<'tcx>) + Send) {
+    interface::run_compiler(config, |compiler| {
+        compiler.enter(|queries| queries.global_ctxt().unwrap().peek_mut().enter(f))
+    });
+}
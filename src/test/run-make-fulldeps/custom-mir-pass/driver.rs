@@ -0,0 +1,66 @@
+#![feature(rustc_private)]
+
+extern crate rustc;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_mir;
+extern crate rustc_span;
+
+use rustc::mir::{BodyAndCache, StatementKind};
+use rustc::ty::TyCtxt;
+use rustc_mir::transform::{MirPass, MirPassInsertionPoint, MirSource};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[path = "../auxiliary/driver_support.rs"]
+mod driver_support;
+
+static POST_BORROWCK_RUNS: AtomicUsize = AtomicUsize::new(0);
+static PRE_CODEGEN_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// Checks that it runs before drops are elaborated, while the MIR still has its fake reads.
+struct PostBorrowck;
+
+impl<'tcx> MirPass<'tcx> for PostBorrowck {
+    fn run_pass(&self, _: TyCtxt<'tcx>, _: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        assert_eq!(PRE_CODEGEN_RUNS.load(Ordering::SeqCst), 0);
+        let has_fake_reads = body.basic_blocks().iter().any(|bb_data| {
+            bb_data.statements.iter().any(|statement| match statement.kind {
+                StatementKind::FakeRead(..) => true,
+                _ => false,
+            })
+        });
+        assert!(has_fake_reads);
+        POST_BORROWCK_RUNS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+struct PreCodegen;
+
+impl<'tcx> MirPass<'tcx> for PreCodegen {
+    fn run_pass(&self, _: TyCtxt<'tcx>, _: MirSource<'tcx>, _: &mut BodyAndCache<'tcx>) {
+        PRE_CODEGEN_RUNS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn main() {
+    let src = r#"
+    fn main() {
+        let x = Some(1);
+        match x {
+            Some(_) => {}
+            None => {}
+        }
+    }
+    "#;
+
+    let mut config = driver_support::exe_config(src);
+    config.register_mir_passes = Some(Box::new(|_, passes| {
+        passes.register(MirPassInsertionPoint::PostBorrowck, PostBorrowck);
+        passes.register(MirPassInsertionPoint::PreCodegen, PreCodegen);
+    }));
+    driver_support::link(config);
+
+    assert_eq!(POST_BORROWCK_RUNS.load(Ordering::SeqCst), 1);
+    assert_eq!(PRE_CODEGEN_RUNS.load(Ordering::SeqCst), 1);
+}
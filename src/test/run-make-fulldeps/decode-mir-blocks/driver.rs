@@ -0,0 +1,111 @@
+#![feature(rustc_private)]
+
+extern crate rustc;
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_index;
+extern crate rustc_interface;
+extern crate rustc_span;
+
+use rustc::mir::{BasicBlock, TerminatorKind};
+use rustc::ty::{self, TyCtxt};
+use rustc_hir::def_id::DefId;
+use rustc_index::vec::Idx;
+
+use std::time::{Duration, Instant};
+
+#[path = "../auxiliary/driver_support.rs"]
+mod driver_support;
+
+/// The most bodies of other crates to decode.
+const BODIES: usize = 500;
+
+/// Returns the functions called by the optimized MIR of `def_id`.
+fn callees(tcx: TyCtxt<'_>, def_id: DefId) -> Vec<DefId> {
+    let body = tcx.optimized_mir(def_id);
+    let mut callees = vec![];
+    for block_data in body.basic_blocks() {
+        if let TerminatorKind::Call { func, .. } = &block_data.terminator().kind {
+            if let ty::FnDef(callee, _) = func.ty(&**body, tcx).kind {
+                callees.push(callee);
+            }
+        }
+    }
+    callees
+}
+
+fn check(tcx: TyCtxt<'_>) {
+    // Starts with the functions of other crates called by this one, and goes on with the
+    // functions they call.
+    let mut work_list: Vec<_> = tcx.body_owners().flat_map(|def_id| callees(tcx, def_id)).collect();
+    let mut seen = vec![];
+    let mut by_block = Duration::default();
+    let mut whole = Duration::default();
+    while let Some(def_id) = work_list.pop() {
+        if seen.len() == BODIES {
+            break;
+        }
+        if def_id.is_local() || seen.contains(&def_id) || !tcx.is_mir_available(def_id) {
+            continue;
+        }
+        seen.push(def_id);
+
+        let start = Instant::now();
+        let outline = tcx.optimized_mir_outline(def_id);
+        let blocks: Vec<_> = (0..outline.block_count)
+            .map(|bb| tcx.optimized_mir_block((def_id, BasicBlock::new(bb))))
+            .collect();
+        by_block += start.elapsed();
+
+        let start = Instant::now();
+        let body = tcx.optimized_mir(def_id);
+        whole += start.elapsed();
+
+        let name = tcx.def_path_str(def_id);
+        assert_eq!(outline.span, body.span, "{}", name);
+        assert_eq!(outline.arg_count, body.arg_count, "{}", name);
+        assert_eq!(format!("{:?}", outline.local_decls), format!("{:?}", body.local_decls));
+        assert_eq!(blocks.len(), body.basic_blocks().len(), "{}", name);
+        for (block_data, whole_block_data) in blocks.iter().zip(body.basic_blocks()) {
+            assert_eq!(format!("{:?}", block_data), format!("{:?}", whole_block_data));
+        }
+
+        work_list.extend(callees(tcx, def_id));
+    }
+    assert!(!seen.is_empty());
+
+    println!(
+        "decoded {} bodies block by block in {:?}, and whole in {:?}",
+        seen.len(),
+        by_block,
+        whole
+    );
+}
+
+fn main() {
+    let src = r#"
+    use std::collections::HashMap;
+
+    pub fn count_words(text: &str) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for word in text.split_whitespace() {
+            *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn sum_of_squares(v: &[u32]) -> u32 {
+        v.iter().map(|x| x * x).sum()
+    }
+
+    pub fn sorted(mut v: Vec<String>) -> Vec<String> {
+        v.sort();
+        v.dedup();
+        v
+    }
+
+    fn main() {}
+    "#;
+
+    driver_support::enter_tcx(driver_support::config(src), |tcx| check(tcx));
+}
@@ -0,0 +1,21 @@
+pub mod parser {
+    pub mod expr {
+        pub fn parse_expr() -> u32 {
+            1
+        }
+
+        pub fn parse_expr_test() -> u32 {
+            2
+        }
+    }
+
+    pub mod stmt {
+        pub fn parse_expr() -> &'static u32 {
+            &3
+        }
+
+        pub fn parse_stmt() -> u32 {
+            4
+        }
+    }
+}
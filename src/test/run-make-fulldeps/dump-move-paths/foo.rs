@@ -0,0 +1,8 @@
+pub fn consume(pair: (String, String)) -> String {
+    let first = pair.0;
+    first
+}
+
+pub fn other(x: u32) -> u32 {
+    x
+}
@@ -0,0 +1,15 @@
+use std::ops::Add;
+
+fn double<T: Add<Output = T> + Copy>(x: T) -> T {
+    x + x
+}
+
+#[allow(dead_code)]
+fn unused<T>(x: T) -> T {
+    x
+}
+
+#[no_mangle]
+pub extern "C" fn foo_entry(x: u32) -> u64 {
+    double(x) as u64 + double(x as u64)
+}
@@ -0,0 +1,424 @@
+#![feature(rustc_private, never_type)]
+
+#[macro_use]
+extern crate rustc;
+extern crate rustc_data_structures;
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_interface;
+extern crate rustc_mir;
+extern crate rustc_span;
+
+use rustc::mir;
+use rustc::ty::layout::{LayoutOf, Size};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_mir::interpret::{
+    AllocId, Allocation, ImmTy, InterpCx, InterpResult, Memory, MemoryKind, OpTy, PlaceTy,
+    Pointer, Scalar, StackPopCleanup, ThreadId, ThreadState, WatchKind, Watchpoint, WatchpointHit,
+    MAIN_THREAD,
+};
+use rustc_span::{Span, DUMMY_SP};
+
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+
+#[path = "../auxiliary/driver_support.rs"]
+mod driver_support;
+
+/// The smallest machine that can run plain Rust code: no heap, no pointer arithmetic, no
+/// intrinsics, and panics are errors.
+struct DummyMachine {
+    /// Whether to switch to another thread at every step.
+    round_robin: bool,
+    /// The number of times `schedule` picked another thread.
+    switches: Cell<usize>,
+    /// The watchpoint hits reported so far.
+    hits: RefCell<Vec<WatchpointHit>>,
+}
+
+impl DummyMachine {
+    fn new(round_robin: bool) -> Self {
+        DummyMachine { round_robin, switches: Cell::new(0), hits: RefCell::new(vec![]) }
+    }
+}
+
+impl<'mir, 'tcx> rustc_mir::interpret::Machine<'mir, 'tcx> for DummyMachine {
+    type MemoryKinds = !;
+    type PointerTag = ();
+    type ExtraFnVal = !;
+
+    type FrameExtra = ();
+    type MemoryExtra = ();
+    type AllocExtra = ();
+
+    type MemoryMap = FxHashMap<AllocId, (MemoryKind<!>, Allocation)>;
+
+    const STATIC_KIND: Option<!> = None;
+
+    const CHECK_ALIGN: bool = true;
+
+    fn enforce_validity(_ecx: &InterpCx<'mir, 'tcx, Self>) -> bool {
+        true
+    }
+
+    fn find_mir_or_eval_fn(
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        _span: Span,
+        instance: ty::Instance<'tcx>,
+        _args: &[OpTy<'tcx>],
+        _ret: Option<(PlaceTy<'tcx>, mir::BasicBlock)>,
+        _unwind: Option<mir::BasicBlock>,
+    ) -> InterpResult<'tcx, Option<&'mir mir::Body<'tcx>>> {
+        Ok(Some(*ecx.load_mir(instance.def, None)?))
+    }
+
+    fn call_extra_fn(
+        _ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        fn_val: !,
+        _args: &[OpTy<'tcx>],
+        _ret: Option<(PlaceTy<'tcx>, mir::BasicBlock)>,
+        _unwind: Option<mir::BasicBlock>,
+    ) -> InterpResult<'tcx> {
+        match fn_val {}
+    }
+
+    fn call_intrinsic(
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        span: Span,
+        instance: ty::Instance<'tcx>,
+        args: &[OpTy<'tcx>],
+        ret: Option<(PlaceTy<'tcx>, mir::BasicBlock)>,
+        _unwind: Option<mir::BasicBlock>,
+    ) -> InterpResult<'tcx> {
+        if ecx.emulate_intrinsic(span, instance, args, ret)? {
+            return Ok(());
+        }
+        throw_unsup_format!("calling intrinsic `{}`", instance)
+    }
+
+    fn assert_panic(
+        _ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        _span: Span,
+        msg: &mir::AssertMessage<'tcx>,
+        _unwind: Option<mir::BasicBlock>,
+    ) -> InterpResult<'tcx> {
+        throw_unsup_format!("panic: {:?}", msg)
+    }
+
+    fn binary_ptr_op(
+        _ecx: &InterpCx<'mir, 'tcx, Self>,
+        _bin_op: mir::BinOp,
+        _left: ImmTy<'tcx>,
+        _right: ImmTy<'tcx>,
+    ) -> InterpResult<'tcx, (Scalar, bool, Ty<'tcx>)> {
+        throw_unsup_format!("pointer arithmetic")
+    }
+
+    fn box_alloc(
+        _ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        _dest: PlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        throw_unsup_format!("heap allocation")
+    }
+
+    fn init_allocation_extra<'b>(
+        _memory_extra: &(),
+        _id: AllocId,
+        alloc: Cow<'b, Allocation>,
+        _kind: Option<MemoryKind<!>>,
+    ) -> (Cow<'b, Allocation>, ()) {
+        (alloc, ())
+    }
+
+    fn tag_static_base_pointer(_memory_extra: &(), _id: AllocId) {}
+
+    fn schedule(ecx: &InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx, Option<ThreadId>> {
+        if !ecx.machine.round_robin {
+            return Ok(None);
+        }
+        let threads: Vec<ThreadId> = ecx.thread_ids().collect();
+        let active = ecx.active_thread().index();
+        for offset in 1..=threads.len() {
+            let thread = threads[(active + offset) % threads.len()];
+            if ecx.thread_state(thread)? == ThreadState::Enabled {
+                if thread != ecx.active_thread() {
+                    ecx.machine.switches.set(ecx.machine.switches.get() + 1);
+                }
+                return Ok(Some(thread));
+            }
+        }
+        Ok(None)
+    }
+
+    fn stack_push(_ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
+        Ok(())
+    }
+
+    fn ptr_to_int(_mem: &Memory<'mir, 'tcx, Self>, _ptr: Pointer) -> InterpResult<'tcx, u64> {
+        throw_unsup_format!("pointer-to-integer cast")
+    }
+
+    fn watchpoint_hit(ecx: &InterpCx<'mir, 'tcx, Self>, hit: &WatchpointHit) -> InterpResult<'tcx> {
+        ecx.machine.hits.borrow_mut().push(hit.clone());
+        Ok(())
+    }
+}
+
+type DummyCx<'mir, 'tcx> = InterpCx<'mir, 'tcx, DummyMachine>;
+
+fn new_ecx<'mir, 'tcx>(tcx: TyCtxt<'tcx>, round_robin: bool) -> DummyCx<'mir, 'tcx> {
+    InterpCx::new(tcx.at(DUMMY_SP), ty::ParamEnv::reveal_all(), DummyMachine::new(round_robin), ())
+}
+
+/// Returns the instance of the function of the crate called `name`.
+fn function<'tcx>(tcx: TyCtxt<'tcx>, name: &str) -> ty::Instance<'tcx> {
+    let def_id = tcx
+        .body_owners()
+        .find(|&def_id| tcx.item_name(def_id).as_str() == name)
+        .unwrap_or_else(|| panic!("no function `{}`", name));
+    ty::Instance::mono(tcx, def_id)
+}
+
+/// Pushes the frame of a call of `name`, which takes no arguments, on the stack of the active
+/// thread, and returns the place its result is written to.
+fn push_call<'mir, 'tcx>(ecx: &mut DummyCx<'mir, 'tcx>, name: &str) -> PlaceTy<'tcx> {
+    let instance = function(*ecx.tcx, name);
+    let body = *ecx.load_mir(instance.def, None).unwrap();
+    let layout = ecx.layout_of(body.return_ty()).unwrap();
+    let ret: PlaceTy<'tcx> = ecx.allocate(layout, MemoryKind::Stack).into();
+    let cleanup = StackPopCleanup::None { cleanup: false };
+    ecx.push_stack_frame(instance, DUMMY_SP, body, Some(ret), cleanup).unwrap();
+    ret
+}
+
+fn read_u32<'tcx>(ecx: &DummyCx<'_, 'tcx>, place: PlaceTy<'tcx>) -> u32 {
+    ecx.read_scalar(ecx.place_to_op(place).unwrap()).unwrap().to_u32().unwrap()
+}
+
+/// Runs `name` to completion, and returns its result.
+fn eval_u32(tcx: TyCtxt<'_>, name: &str) -> u32 {
+    let mut ecx = new_ecx(tcx, false);
+    let ret = push_call(&mut ecx, name);
+    ecx.run().unwrap();
+    read_u32(&ecx, ret)
+}
+
+fn check(tcx: TyCtxt<'_>) {
+    // Locals, arithmetic and loops.
+    assert_eq!(eval_u32(tcx, "sum_to_10"), 55);
+    // Calls, and the stack growing and shrinking.
+    assert_eq!(eval_u32(tcx, "fib_10"), 55);
+    // Projections of aggregates.
+    assert_eq!(eval_u32(tcx, "swapped"), 21);
+    assert_eq!(eval_u32(tcx, "array_sum"), 10);
+    assert_eq!(eval_u32(tcx, "enum_match"), 7);
+
+    // Stepping by hand.
+    let mut ecx = new_ecx(tcx, false);
+    let ret = push_call(&mut ecx, "fib_10");
+    let mut steps = 0;
+    while ecx.step().unwrap() {
+        steps += 1;
+    }
+    assert!(steps > 0);
+    assert!(ecx.stack().is_empty());
+    assert_eq!(read_u32(&ecx, ret), 55);
+
+    // Errors of the program are returned, not reported.
+    let mut ecx = new_ecx(tcx, false);
+    push_call(&mut ecx, "overflow");
+    assert!(ecx.run().is_err());
+}
+
+fn check_threads(tcx: TyCtxt<'_>) {
+    // Two threads running interleaved.
+    let mut ecx = new_ecx(tcx, true);
+    let main_ret = push_call(&mut ecx, "fib_10");
+    let thread = ecx.create_thread();
+    assert_eq!(ecx.set_active_thread(thread).unwrap(), MAIN_THREAD);
+    let thread_ret = push_call(&mut ecx, "sum_to_10");
+    ecx.set_active_thread(MAIN_THREAD).unwrap();
+    ecx.run().unwrap();
+    assert_eq!(read_u32(&ecx, main_ret), 55);
+    assert_eq!(read_u32(&ecx, thread_ret), 55);
+    assert!(ecx.machine.switches.get() > 2);
+    assert_eq!(ecx.thread_state(thread).unwrap(), ThreadState::Terminated);
+    // Terminated threads cannot run again.
+    assert!(ecx.set_active_thread(thread).is_err());
+
+    // The main thread waits for a worker thread, and a thread that was never started is
+    // skipped.
+    let mut ecx = new_ecx(tcx, false);
+    let main_ret = push_call(&mut ecx, "fib_10");
+    let idle = ecx.create_thread();
+    let worker = ecx.create_thread();
+    ecx.set_active_thread(worker).unwrap();
+    let worker_ret = push_call(&mut ecx, "sum_to_10");
+    ecx.set_active_thread(MAIN_THREAD).unwrap();
+    ecx.join_thread(worker).unwrap();
+    assert_eq!(ecx.thread_state(MAIN_THREAD).unwrap(), ThreadState::BlockedOnJoin(worker));
+    ecx.run().unwrap();
+    assert_eq!(read_u32(&ecx, main_ret), 55);
+    assert_eq!(read_u32(&ecx, worker_ret), 55);
+    for thread in vec![MAIN_THREAD, idle, worker] {
+        assert_eq!(ecx.thread_state(thread).unwrap(), ThreadState::Terminated);
+    }
+
+    // Misuses of the thread API are errors of the program, not of the interpreter.
+    let mut ecx = new_ecx(tcx, false);
+    let unknown = ThreadId::from_u32(7);
+    assert!(ecx.join_thread(MAIN_THREAD).is_err());
+    assert!(ecx.join_thread(unknown).is_err());
+    assert!(ecx.thread_state(unknown).is_err());
+    assert!(ecx.set_active_thread(unknown).is_err());
+
+    // Threads joining each other deadlock.
+    let mut ecx = new_ecx(tcx, false);
+    push_call(&mut ecx, "fib_10");
+    let thread = ecx.create_thread();
+    ecx.set_active_thread(thread).unwrap();
+    push_call(&mut ecx, "sum_to_10");
+    ecx.join_thread(MAIN_THREAD).unwrap();
+    ecx.set_active_thread(MAIN_THREAD).unwrap();
+    ecx.join_thread(thread).unwrap();
+    assert!(ecx.run().is_err());
+}
+
+/// Watches the first `size` bytes of `alloc_id`.
+fn watch(ecx: &mut DummyCx<'_, '_>, alloc_id: AllocId, size: u64, kind: WatchKind) {
+    let end = Size::from_bytes(size);
+    ecx.memory.add_watchpoint(Watchpoint { alloc_id, start: Size::ZERO, end, kind });
+}
+
+fn check_watchpoints(tcx: TyCtxt<'_>) {
+    // Writes to the watched range are reported after the statement doing them.
+    let mut ecx = new_ecx(tcx, false);
+    let ret = push_call(&mut ecx, "sum_to_10");
+    let ret_alloc = ret.assert_mem_place().ptr.assert_ptr().alloc_id;
+    watch(&mut ecx, ret_alloc, 4, WatchKind::Write);
+    ecx.run().unwrap();
+    let hits = ecx.machine.hits.replace(vec![]);
+    assert!(!hits.is_empty());
+    for hit in hits {
+        assert_eq!(hit.kind, WatchKind::Write);
+        assert_eq!((hit.offset, hit.size), (Size::ZERO, Size::from_bytes(4)));
+        assert!(!hit.spans.is_empty());
+    }
+
+    // Only the reads are reported by read watchpoints.
+    let mut ecx = new_ecx(tcx, false);
+    let data = tcx.body_owners().find(|&def_id| tcx.item_name(def_id).as_str() == "DATA").unwrap();
+    let data_alloc = tcx.alloc_map.lock().create_static_alloc(data);
+    let ret = push_call(&mut ecx, "read_data");
+    let ret_alloc = ret.assert_mem_place().ptr.assert_ptr().alloc_id;
+    watch(&mut ecx, data_alloc, 4, WatchKind::Read);
+    watch(&mut ecx, ret_alloc, 4, WatchKind::Read);
+    ecx.run().unwrap();
+    let hits = ecx.machine.hits.replace(vec![]);
+    assert!(!hits.is_empty());
+    for hit in hits {
+        assert_eq!((hit.watchpoint.alloc_id, hit.kind), (data_alloc, WatchKind::Read));
+    }
+
+    // A terminator writing an invalid value to the watched range fails after the write, which
+    // is still reported.
+    let mut ecx = new_ecx(tcx, false);
+    let ret = push_call(&mut ecx, "invalid_bool");
+    let ret_alloc = ret.assert_mem_place().ptr.assert_ptr().alloc_id;
+    watch(&mut ecx, ret_alloc, 1, WatchKind::Access);
+    assert!(ecx.run().is_err());
+    let hits = ecx.machine.hits.borrow();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].kind, WatchKind::Write);
+}
+
+fn main() {
+    let src = r#"
+    pub fn sum_to_10() -> u32 {
+        let mut i = 0;
+        let mut sum = 0;
+        while i < 10 {
+            i += 1;
+            sum += i;
+        }
+        sum
+    }
+
+    fn fib(n: u32) -> u32 {
+        if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+    }
+
+    pub fn fib_10() -> u32 {
+        fib(10)
+    }
+
+    struct Pair {
+        a: u32,
+        b: u32,
+    }
+
+    fn swap(p: Pair) -> Pair {
+        Pair { a: p.b, b: p.a }
+    }
+
+    pub fn swapped() -> u32 {
+        let p = swap(Pair { a: 1, b: 2 });
+        p.a * 10 + p.b
+    }
+
+    pub fn array_sum() -> u32 {
+        let a = [1, 2, 3, 4];
+        let mut sum = 0;
+        let mut i = 0;
+        while i < a.len() {
+            sum += a[i];
+            i += 1;
+        }
+        sum
+    }
+
+    enum Shape {
+        Square(u32),
+        Rect { w: u32, h: u32 },
+    }
+
+    fn area(s: &Shape) -> u32 {
+        match *s {
+            Shape::Square(side) => side * side,
+            Shape::Rect { w, h } => w * h,
+        }
+    }
+
+    pub fn enum_match() -> u32 {
+        area(&Shape::Square(2)) + area(&Shape::Rect { w: 1, h: 3 })
+    }
+
+    pub fn overflow() -> u32 {
+        let mut x: u32 = 1;
+        loop {
+            x *= 2;
+        }
+    }
+
+    static DATA: u32 = 5;
+
+    pub fn read_data() -> u32 {
+        DATA + 1
+    }
+
+    pub fn invalid_bool() -> bool {
+        unsafe { std::mem::transmute::<u8, bool>(3) }
+    }
+
+    fn main() {}
+    "#;
+
+    driver_support::enter_tcx(driver_support::config(src), |tcx| {
+        tcx.analysis(LOCAL_CRATE).unwrap();
+        check(tcx);
+        check_threads(tcx);
+        check_watchpoints(tcx);
+    });
+}
@@ -60,6 +60,7 @@ fn compile(code: String, output: PathBuf, sysroot: PathBuf) {
         crate_name: None,
         lint_caps: Default::default(),
         register_lints: None,
+        register_mir_passes: None,
         override_queries: None,
         registry: rustc_driver::diagnostics_registry(),
     };
@@ -0,0 +1,7 @@
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn main() {
+    assert_eq!(double(21), 42);
+}
@@ -0,0 +1,8 @@
+// Comments before the function shift its lines.
+
+/// Adds two numbers.
+#[inline]
+pub fn add(a: u32, b: u32) -> u32 {
+    // Wrapping on overflow in release builds.
+    a + b
+}
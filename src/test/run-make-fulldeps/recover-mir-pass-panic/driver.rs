@@ -0,0 +1,47 @@
+#![feature(rustc_private)]
+
+extern crate rustc;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_mir;
+extern crate rustc_span;
+
+use rustc::mir::BodyAndCache;
+use rustc::ty::TyCtxt;
+use rustc_mir::transform::{MirPass, MirPassInsertionPoint, MirSource};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[path = "../auxiliary/driver_support.rs"]
+mod driver_support;
+
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// Breaks the body, then panics before it is done.
+struct Panicking;
+
+impl<'tcx> MirPass<'tcx> for Panicking {
+    fn run_pass(&self, _: TyCtxt<'tcx>, _: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        RUNS.fetch_add(1, Ordering::SeqCst);
+        body.basic_blocks_mut().raw.clear();
+        panic!("the body is broken");
+    }
+}
+
+fn main() {
+    let src = r#"
+    fn main() {
+        let x = Some(1);
+        assert_eq!(x.map(|x| x + 1), Some(2));
+    }
+    "#;
+
+    let mut config = driver_support::exe_config(src);
+    config.opts.debugging_opts.recover_mir_pass_panics = true;
+    config.register_mir_passes = Some(Box::new(|_, passes| {
+        passes.register(MirPassInsertionPoint::PreOptimization, Panicking);
+    }));
+    driver_support::link(config);
+
+    assert!(RUNS.load(Ordering::SeqCst) > 0);
+}
@@ -0,0 +1,27 @@
+// compile-flags: -Z unstable-options
+
+#![feature(rustc_private)]
+
+extern crate rustc_data_structures;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
+
+#[deny(rustc::unordered_iteration)]
+fn main() {
+    let mut map: FxHashMap<u32, u32> = FxHashMap::default();
+    let set: FxHashSet<u32> = FxHashSet::default();
+    for _ in &map {}
+    //~^ ERROR iterating over a `HashMap`
+    let _: Vec<_> = set.iter().collect();
+    //~^ ERROR iterating over a `HashSet`
+    map.values_mut().for_each(|value| *value += 1);
+    //~^ ERROR iterating over a `HashMap`
+
+    // Looking up the elements doesn't depend on their order.
+    let _ = map.get(&0);
+    let _ = set.contains(&0);
+
+    // Neither does iterating over an `FxIndexMap`, in insertion order.
+    let index_map: FxIndexMap<u32, u32> = FxIndexMap::default();
+    for _ in &index_map {}
+}
@@ -0,0 +1,65 @@
+// run-pass
+
+// edition:2018
+// compile-flags: -Z mir-opt-level=2
+
+// Checks that awaiting the futures of `async fn`s works when their generators are resumed
+// directly, and inlined into the generators awaiting them.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+async fn leaf(x: u32) -> u32 {
+    YieldOnce(false).await;
+    x + 1
+}
+
+async fn middle(x: u32) -> u32 {
+    leaf(x).await + leaf(x + 1).await
+}
+
+async fn root() -> u32 {
+    middle(1).await * 2
+}
+
+fn noop_raw_waker() -> RawWaker {
+    fn clone(_: *const ()) -> RawWaker {
+        noop_raw_waker()
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+    RawWaker::new(std::ptr::null(), &VTABLE)
+}
+
+fn main() {
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(root());
+    let mut polls = 0;
+    let result = loop {
+        polls += 1;
+        if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+            break result;
+        }
+    };
+    assert_eq!(result, 10);
+    // Each call of `leaf` yields once.
+    assert_eq!(polls, 3);
+}
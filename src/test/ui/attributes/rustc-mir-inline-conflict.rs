@@ -0,0 +1,11 @@
+// A function cannot be both always and never inlined by the MIR inliner.
+
+#![feature(rustc_attrs)]
+
+#[rustc_mir_inline] //~ ERROR cannot use `#[rustc_mir_inline]` with `#[rustc_no_mir_inline]`
+#[rustc_no_mir_inline]
+fn conflicting() {}
+
+fn main() {
+    conflicting();
+}
@@ -0,0 +1,85 @@
+// Test that moving out of the contents of a `Box` and re-initializing them is tracked precisely:
+// the box is accepted again once re-initialized, and each value is dropped exactly once.
+
+// run-pass
+
+use std::cell::Cell;
+
+struct Noisy<'a>(&'a Cell<u32>, u32);
+
+impl Drop for Noisy<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + self.1);
+    }
+}
+
+struct Holder<'a> {
+    field: Box<Noisy<'a>>,
+    other: Box<Noisy<'a>>,
+}
+
+fn holder(drops: &Cell<u32>) -> Holder<'_> {
+    Holder { field: Box::new(Noisy(drops, 1)), other: Box::new(Noisy(drops, 10)) }
+}
+
+fn consume(_: Noisy<'_>) {}
+
+fn move_and_reinit(drops: &Cell<u32>) {
+    let mut holder = holder(drops);
+    consume(*holder.field);
+    assert_eq!(drops.get(), 1);
+    *holder.field = Noisy(drops, 100);
+    // Nothing is dropped by the assignment, as the old contents were moved out.
+    assert_eq!(drops.get(), 1);
+}
+
+fn move_without_reinit(drops: &Cell<u32>) {
+    let holder = holder(drops);
+    consume(*holder.field);
+    // Only the contents of `holder.other`, and the allocation of `holder.field`, are left.
+}
+
+fn conditional_move(drops: &Cell<u32>, cond: bool) {
+    let mut holder = holder(drops);
+    if cond {
+        consume(*holder.field);
+    }
+    if cond {
+        *holder.field = Noisy(drops, 100);
+    }
+    let Holder { field, other: _ } = holder;
+    consume(*field);
+}
+
+fn reinit_in_loop(drops: &Cell<u32>) {
+    let mut boxed = Box::new(Noisy(drops, 1));
+    for i in 0..3 {
+        if i > 0 {
+            // Re-initialized before the move in the next iteration.
+            *boxed = Noisy(drops, 1);
+        }
+        consume(*boxed);
+    }
+    *boxed = Noisy(drops, 1000);
+}
+
+fn main() {
+    let drops = Cell::new(0);
+    move_and_reinit(&drops);
+    assert_eq!(drops.get(), 111);
+
+    let drops = Cell::new(0);
+    move_without_reinit(&drops);
+    assert_eq!(drops.get(), 11);
+
+    let drops = Cell::new(0);
+    conditional_move(&drops, true);
+    assert_eq!(drops.get(), 111);
+    let drops = Cell::new(0);
+    conditional_move(&drops, false);
+    assert_eq!(drops.get(), 11);
+
+    let drops = Cell::new(0);
+    reinit_in_loop(&drops);
+    assert_eq!(drops.get(), 1003);
+}
@@ -0,0 +1,32 @@
+// Checks that the `move` suggestion for an escaping closure points out the captures that are
+// used after the closure, which `move` would break.
+
+fn spawn<F: FnOnce() + 'static>(f: F) {
+    f();
+}
+
+struct Handle(u32);
+
+fn used_after() {
+    let data = vec![1, 2, 3];
+    spawn(|| println!("{:?}", data)); //~ ERROR E0373
+    println!("{}", data.len());
+}
+
+fn not_clone() {
+    let handle = Handle(0);
+    spawn(|| println!("{}", handle.0)); //~ ERROR E0373
+    println!("{}", handle.0);
+}
+
+fn copy_after() {
+    let count = 3;
+    spawn(|| println!("{}", count)); //~ ERROR E0373
+    println!("{}", count);
+}
+
+fn main() {
+    used_after();
+    not_clone();
+    copy_after();
+}
@@ -0,0 +1,38 @@
+// Places inside of a union field are tracked separately, but moving out of them also moves out
+// of the other fields of the union, which overlap them.
+
+#![feature(untagged_unions)]
+
+struct NonCopy;
+
+union U {
+    a: (NonCopy, NonCopy),
+    b: NonCopy,
+}
+
+fn main() {
+    unsafe {
+        {
+            let u = U { a: (NonCopy, NonCopy) };
+            let x = u.a.0;
+            let y = u.a.1; // OK, does not overlap `u.a.0`
+        }
+        {
+            let u = U { a: (NonCopy, NonCopy) };
+            let x = u.a.0;
+            let y = u.b; //~ ERROR use of moved value: `u.b`
+        }
+        {
+            let mut u = U { a: (NonCopy, NonCopy) };
+            let x = u.a.0;
+            u.a.0 = NonCopy;
+            let y = u.a; // OK, reinitialized
+        }
+        {
+            let mut u = U { a: (NonCopy, NonCopy) };
+            let x = u.a.0;
+            u.b = NonCopy;
+            let y = u.a; // OK, writing a field reinitializes the whole union
+        }
+    }
+}
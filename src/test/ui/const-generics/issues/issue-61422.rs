@@ -2,6 +2,7 @@
 
 #![feature(const_generics)]
 //~^ WARN the feature `const_generics` is incomplete and may cause the compiler to crash
+#![allow(uninitialized_reads)]
 
 use std::mem;
 
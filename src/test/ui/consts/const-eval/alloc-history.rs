@@ -0,0 +1,11 @@
+// compile-flags: -Z track-alloc-history
+#![feature(const_raw_ptr_deref)]
+
+const fn dangling(x: u64) -> *const u64 {
+    &x
+}
+
+const X: u64 = unsafe { *dangling(42) };
+//~^ ERROR any use of this value will cause an error
+
+fn main() {}
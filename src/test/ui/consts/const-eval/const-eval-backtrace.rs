@@ -0,0 +1,19 @@
+// compile-flags: -Z const-eval-backtrace=full
+// Checks that `-Z const-eval-backtrace` notes each frame of a failed constant evaluation.
+
+#![feature(const_panic)]
+#![crate_type = "lib"]
+
+const fn check(value: u32) -> u32 {
+    if value == 0 {
+        panic!("zero");
+    }
+    value
+}
+
+const fn double(value: u32) -> u32 {
+    check(value) * 2
+}
+
+pub const DOUBLED: u32 = double(0);
+//~^ ERROR any use of this value will cause an error
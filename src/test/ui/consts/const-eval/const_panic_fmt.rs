@@ -0,0 +1,18 @@
+// Checks that the messages of formatted panics are rendered by const evaluation.
+
+#![feature(const_panic)]
+#![crate_type = "lib"]
+
+const LIMIT: u32 = 3;
+
+pub const Z: () = panic!("the limit is {}", LIMIT);
+//~^ ERROR any use of this value will cause an error
+
+pub const Y: () = panic!("{:?} and {}", "quoted", 'c');
+//~^ ERROR any use of this value will cause an error
+
+pub const X: () = panic!("{:>4} is padded", 1);
+//~^ ERROR any use of this value will cause an error
+
+pub const W: () = panic!("{:?} and {}", 1.0f64, 2.5f32);
+//~^ ERROR any use of this value will cause an error
@@ -0,0 +1,3 @@
+// compile-flags: -Z interp-watchpoints=alloc1@4..2
+
+fn main() {}
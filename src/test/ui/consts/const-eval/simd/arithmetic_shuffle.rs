@@ -0,0 +1,55 @@
+// run-pass
+#![feature(const_fn)]
+#![feature(repr_simd)]
+#![feature(platform_intrinsics)]
+#![feature(staged_api)]
+#![stable(feature = "foo", since = "1.33.7")]
+#![allow(non_camel_case_types)]
+
+#[repr(simd)] #[derive(Copy, Clone)] struct i32x4(i32, i32, i32, i32);
+#[repr(simd)] #[derive(Copy, Clone)] struct u8x2(u8, u8);
+#[repr(simd)] #[derive(Copy, Clone)] struct f32x2(f32, f32);
+
+extern "platform-intrinsic" {
+    #[rustc_const_stable(feature = "foo", since = "1.3.37")]
+    fn simd_add<T>(x: T, y: T) -> T;
+    #[rustc_const_stable(feature = "foo", since = "1.3.37")]
+    fn simd_mul<T>(x: T, y: T) -> T;
+    #[rustc_const_stable(feature = "foo", since = "1.3.37")]
+    fn simd_shl<T>(x: T, y: T) -> T;
+    #[rustc_const_stable(feature = "foo", since = "1.3.37")]
+    fn simd_xor<T>(x: T, y: T) -> T;
+    #[rustc_const_stable(feature = "foo", since = "1.3.37")]
+    fn simd_lt<T, U>(x: T, y: T) -> U;
+    #[rustc_const_stable(feature = "foo", since = "1.3.37")]
+    fn simd_shuffle4<T, U>(x: T, y: T, idx: [u32; 4]) -> U;
+}
+
+fn main() {
+    {
+        const A: i32x4 = i32x4(1, 2, 3, i32::max_value());
+        const B: i32x4 = i32x4(10, 20, 30, 1);
+        const SUM: i32x4 = unsafe { simd_add(A, B) };
+        const PRODUCT: i32x4 = unsafe { simd_mul(A, B) };
+        const SHIFTED: i32x4 = unsafe { simd_shl(A, i32x4(1, 2, 3, 0)) };
+        const LESS: i32x4 = unsafe { simd_lt(A, i32x4(2, 2, 2, 2)) };
+        assert_eq!((SUM.0, SUM.1, SUM.2, SUM.3), (11, 22, 33, i32::min_value()));
+        assert_eq!((PRODUCT.0, PRODUCT.1, PRODUCT.2), (10, 40, 90));
+        assert_eq!((SHIFTED.0, SHIFTED.1, SHIFTED.2, SHIFTED.3), (2, 8, 24, i32::max_value()));
+        assert_eq!((LESS.0, LESS.1, LESS.2, LESS.3), (-1, 0, 0, 0));
+    }
+    {
+        const X: u8x2 = unsafe { simd_xor(u8x2(0b1100, 0xff), u8x2(0b1010, 0x0f)) };
+        assert_eq!((X.0, X.1), (0b0110, 0xf0));
+    }
+    {
+        const F: f32x2 = unsafe { simd_add(f32x2(1.5, 2.), f32x2(0.5, -2.)) };
+        assert_eq!((F.0, F.1), (2., 0.));
+    }
+    {
+        const A: i32x4 = i32x4(0, 1, 2, 3);
+        const B: i32x4 = i32x4(4, 5, 6, 7);
+        const S: i32x4 = unsafe { simd_shuffle4(A, B, [7, 0, 5, 2]) };
+        assert_eq!((S.0, S.1, S.2, S.3), (7, 0, 5, 2));
+    }
+}
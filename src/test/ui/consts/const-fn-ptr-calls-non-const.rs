@@ -0,0 +1,15 @@
+// Checks that calling a function that is not a `const fn` through a function pointer is
+// rejected during evaluation.
+
+#![feature(const_fn_ptr_calls)]
+
+fn not_const() -> usize {
+    1
+}
+
+const F: fn() -> usize = not_const;
+
+fn main() {
+    let _ = [0; F()];
+    //~^ ERROR evaluation of constant value failed
+}
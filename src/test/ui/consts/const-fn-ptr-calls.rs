@@ -0,0 +1,28 @@
+// run-pass
+
+// Checks that `const fn`s can be called through a dispatch table of function pointers.
+
+#![feature(const_fn, const_fn_ptr_calls)]
+
+const fn double(x: u32) -> u32 {
+    x * 2
+}
+
+const fn triple(x: u32) -> u32 {
+    x * 3
+}
+
+const TABLE: [fn(u32) -> u32; 2] = [double, triple];
+
+const fn apply(op: usize, x: u32) -> u32 {
+    TABLE[op](x)
+}
+
+const SIX: u32 = apply(0, 3);
+const NINE: u32 = apply(1, 3);
+
+fn main() {
+    assert_eq!(SIX, 6);
+    assert_eq!(NINE, 9);
+    assert_eq!(apply(1, 4), 12);
+}
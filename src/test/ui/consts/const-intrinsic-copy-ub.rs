@@ -0,0 +1,34 @@
+// FIXME: missing sysroot spans (#53081)
+// ignore-i586-unknown-linux-gnu
+// ignore-i586-unknown-linux-musl
+// ignore-i686-unknown-linux-musl
+
+#![feature(const_mut_refs)]
+#![feature(const_intrinsic_copy)]
+#![feature(const_intrinsic_write_bytes)]
+
+use std::ptr;
+
+pub const OVERLAPPING: [u8; 4] = {
+    //~^ NOTE
+    let mut buf = [1u8, 2, 3, 4];
+    let src = &buf[0] as *const u8;
+    let dst = &mut buf[1] as *mut u8;
+    unsafe { ptr::copy_nonoverlapping(src, dst, 2) };
+    buf
+};
+
+pub const SIZE_OVERFLOW: u32 = {
+    //~^ NOTE
+    let src = 1u32;
+    let mut dst = 2u32;
+    unsafe { ptr::copy(&src as *const u32, &mut dst as *mut u32, usize::max_value()) };
+    dst
+};
+
+pub const NOT_PTR: () = {
+    //~^ NOTE
+    unsafe { ptr::write_bytes(8 as *mut u8, 0, 1) };
+};
+
+fn main() {}
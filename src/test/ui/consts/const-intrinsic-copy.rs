@@ -0,0 +1,50 @@
+// run-pass
+
+#![feature(const_mut_refs)]
+#![feature(const_intrinsic_copy)]
+#![feature(const_intrinsic_write_bytes)]
+
+use std::ptr;
+
+const COPIED: [u32; 3] = {
+    let src = [1u32, 2, 3];
+    let mut dst = [0u32; 3];
+    unsafe {
+        ptr::copy_nonoverlapping(&src as *const [u32; 3], &mut dst as *mut [u32; 3], 1);
+    }
+    dst
+};
+
+const MOVED: (u16, u16) = {
+    let mut pair = (0u16, 7u16);
+    let src = &mut pair.1 as *mut u16;
+    let dst = &mut pair.0 as *mut u16;
+    unsafe {
+        ptr::copy(src, dst, 1);
+    }
+    pair
+};
+
+const FILLED: [u8; 4] = {
+    let mut buf = [0u8; 4];
+    unsafe {
+        ptr::write_bytes(&mut buf as *mut [u8; 4], 0xab, 1);
+    }
+    buf
+};
+
+const NOTHING_COPIED: u8 = {
+    let src = 1u8;
+    let mut dst = 2u8;
+    unsafe {
+        ptr::copy_nonoverlapping(&src as *const u8, &mut dst as *mut u8, 0);
+    }
+    dst
+};
+
+fn main() {
+    assert_eq!(COPIED, [1, 2, 3]);
+    assert_eq!(MOVED, (7, 7));
+    assert_eq!(FILLED, [0xab; 4]);
+    assert_eq!(NOTHING_COPIED, 2);
+}
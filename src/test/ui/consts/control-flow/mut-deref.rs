@@ -0,0 +1,24 @@
+// Checks that a mutable dereference in a branch is reported at the assignment, and names the
+// feature gate that would allow it.
+
+#![feature(const_if_match)]
+
+const fn first_or(x: Option<u32>, default: u32) -> u32 {
+    match x {
+        Some(x) => x,
+        None => default,
+    }
+}
+
+const A: u32 = first_or(Some(1), 2);
+
+const B: u32 = {
+    let mut x = 0;
+    let y = &mut x; //~ ERROR references in constants may only refer to immutable values
+    if A > 0 {
+        *y = A; //~ ERROR constant contains unimplemented expression type
+    }
+    x
+};
+
+fn main() {}
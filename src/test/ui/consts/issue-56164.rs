@@ -6,7 +6,7 @@ const fn foo() { (||{})() }
 
 const fn bad(input: fn()) {
     input()
-    //~^ ERROR function pointers are not allowed in const fn
+    //~^ ERROR function pointer calls are not allowed in constant functions
 }
 
 fn main() {
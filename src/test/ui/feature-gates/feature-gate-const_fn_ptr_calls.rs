@@ -0,0 +1,8 @@
+const fn one() -> u32 {
+    1
+}
+
+const F: fn() -> u32 = one;
+const X: u32 = F(); //~ ERROR function pointer calls are not allowed in constants
+
+fn main() {}
@@ -0,0 +1,14 @@
+trait Answer {
+    fn answer(&self) -> u32;
+}
+
+impl Answer for () {
+    fn answer(&self) -> u32 {
+        42
+    }
+}
+
+const A: &dyn Answer = &();
+const X: u32 = A.answer(); //~ ERROR trait object method calls are not allowed in constants
+
+fn main() {}
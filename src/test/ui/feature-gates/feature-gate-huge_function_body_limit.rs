@@ -0,0 +1,4 @@
+#![huge_function_body_limit = "100"]
+//~^ ERROR the `#[huge_function_body_limit]` attribute is an experimental feature [E0658]
+
+fn main() {}
@@ -0,0 +1,4 @@
+#![mir_opt_level = "2"]
+//~^ ERROR the `#[mir_opt_level]` attribute is an experimental feature [E0658]
+
+fn main() {}
@@ -0,0 +1,37 @@
+// Checks that the resume arguments of static generators are saved across yields, and that a
+// resume argument is not dropped if the generator is dropped before it was written.
+
+// run-pass
+
+#![feature(generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, PartialEq)]
+struct DropMe(u32);
+
+impl Drop for DropMe {
+    fn drop(&mut self) {
+        DROPS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn main() {
+    let mut gen = Box::pin(static |first: DropMe| {
+        let first_ref = &first;
+        let second: DropMe = yield first_ref.0;
+        let _third: DropMe = yield first_ref.0 + second.0;
+        second.0
+    });
+
+    assert_eq!(gen.as_mut().resume(DropMe(1)), GeneratorState::Yielded(1));
+    assert_eq!(gen.as_mut().resume(DropMe(2)), GeneratorState::Yielded(3));
+    assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+
+    // Drops `first` and `second`, but `_third` was never written.
+    drop(gen);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+}
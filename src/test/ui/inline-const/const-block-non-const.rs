@@ -0,0 +1,6 @@
+#![feature(inline_const)]
+
+fn main() {
+    let x = 2u32;
+    let _ = const { x }; //~ ERROR attempt to use a non-constant value in a constant
+}
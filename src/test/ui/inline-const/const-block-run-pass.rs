@@ -0,0 +1,33 @@
+// run-pass
+// Checks the evaluation of inline `const` blocks, and the promotion of their values.
+
+#![feature(inline_const)]
+
+const fn square(x: u32) -> u32 {
+    x * x
+}
+
+fn promoted() -> &'static u32 {
+    &const { square(3) + 1 }
+}
+
+fn main() {
+    let x = const { square(4) };
+    assert_eq!(x, 16);
+
+    let bytes = const { [1u8; 4] };
+    assert_eq!(bytes, [1, 1, 1, 1]);
+
+    assert_eq!(*promoted(), 10);
+
+    let sum = const {
+        let mut i = 0u32;
+        let mut sum = 0;
+        while i < 5 {
+            sum += i;
+            i += 1;
+        }
+        sum
+    };
+    assert_eq!(sum, 10);
+}
@@ -0,0 +1,64 @@
+#![deny(dead_assignments)]
+#![allow(unused_assignments)]
+
+fn branches(c: bool) -> u32 {
+    let mut x = 0; //~ ERROR value assigned to `x` is never read
+    if c {
+        x = 1;
+    } else {
+        x = 2;
+    }
+    x
+}
+
+fn overwritten() -> u32 {
+    let mut x = 1;
+    let y = x;
+    x = 2; //~ ERROR value assigned to `x` is never read
+    y
+}
+
+fn loop_carried(n: u32) -> u32 {
+    let mut acc = 0;
+    let mut i = 0;
+    while i < n {
+        acc += i;
+        i += 1;
+    }
+    acc
+}
+
+macro_rules! reset {
+    ($v:ident) => {
+        $v = 0;
+    };
+}
+
+fn in_macro() -> u32 {
+    let mut x = 1;
+    let y = x;
+    reset!(x); //~ ERROR value assigned to `x` is never read
+    y
+}
+
+fn underscore() -> u32 {
+    let mut _x = 1;
+    _x = 2;
+    3
+}
+
+fn through_pointer() -> u32 {
+    let mut x = 1;
+    let p = &mut x as *mut u32;
+    x = 2;
+    unsafe { *p }
+}
+
+fn main() {
+    branches(true);
+    overwritten();
+    loop_carried(3);
+    in_macro();
+    underscore();
+    through_pointer();
+}
@@ -0,0 +1,23 @@
+// check-pass
+
+// Checks that the functions with more MIR statements than the limit are reported, in check
+// builds and in generic functions too.
+
+#![crate_type = "lib"]
+#![feature(huge_function_body_limit)]
+#![huge_function_body_limit = "5"]
+#![warn(huge_function_body)]
+
+use std::ops::BitOr;
+
+pub fn small(x: u32) -> u32 {
+    x
+}
+
+pub fn huge(x: u32) -> u32 { //~ WARNING this function has 6 MIR statements
+    x | x | x
+}
+
+pub fn huge_generic<T: Copy + BitOr<Output = T>>(x: T) -> T { //~ WARNING this function has 7
+    x | x | x
+}
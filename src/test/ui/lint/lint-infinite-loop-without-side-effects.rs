@@ -0,0 +1,42 @@
+// check-pass
+
+// Checks that only the loops that never terminate and have no side effects are reported, in
+// check builds and in generic functions too.
+
+#![crate_type = "lib"]
+#![warn(infinite_loop_without_side_effects)]
+
+pub fn spin() -> ! {
+    loop {} //~ WARNING this loop never terminates and has no side effects
+}
+
+pub fn spin_on_value(x: u32) -> ! {
+    let mut x = x;
+    loop { //~ WARNING this loop never terminates and has no side effects
+        x ^= 1;
+    }
+}
+
+pub fn count(n: u32) -> u32 {
+    let mut i = 0;
+    while i < n {
+        i += 1;
+    }
+    i
+}
+
+pub fn call_in_loop() -> ! {
+    loop {
+        std::thread::yield_now();
+    }
+}
+
+pub fn generic<T>(x: T) -> ! {
+    let _x = x;
+    loop {} //~ WARNING this loop never terminates and has no side effects
+}
+
+#[allow(infinite_loop_without_side_effects)]
+pub fn allowed() -> ! {
+    loop {}
+}
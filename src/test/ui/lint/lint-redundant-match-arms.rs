@@ -0,0 +1,36 @@
+#![deny(redundant_match_arms)]
+
+fn result(x: Result<u32, String>) -> Result<u32, String> {
+    match x { //~ ERROR every arm of this match rebuilds the matched value
+        Ok(v) => Ok(v),
+        Err(e) => Err(e),
+    }
+}
+
+fn option(x: Option<String>) -> Option<String> {
+    match x { //~ ERROR every arm of this match rebuilds the matched value
+        Some(v) => Some(v),
+        None => None,
+    }
+}
+
+fn changed(x: Result<u32, u32>) -> Result<u32, u32> {
+    match x {
+        Ok(v) => Ok(v + 1),
+        Err(e) => Err(e),
+    }
+}
+
+fn swapped(x: Result<u32, u32>) -> Result<u32, u32> {
+    match x {
+        Ok(v) => Err(v),
+        Err(e) => Ok(e),
+    }
+}
+
+fn main() {
+    result(Ok(0)).unwrap();
+    option(None);
+    changed(Ok(0)).unwrap();
+    swapped(Ok(0)).unwrap_err();
+}
@@ -0,0 +1,44 @@
+#![deny(unconditional_mutual_recursion)]
+#![allow(dead_code)]
+
+fn is_even(n: u32) -> bool { //~ ERROR functions cannot return without calling each other
+    !is_odd(n)
+}
+
+fn is_odd(n: u32) -> bool {
+    !is_even(n)
+}
+
+fn even(n: u32) -> bool {
+    if n == 0 { true } else { odd(n - 1) }
+}
+
+fn odd(n: u32) -> bool {
+    if n == 0 { false } else { even(n - 1) }
+}
+
+fn ping(n: u32) { //~ ERROR functions cannot return without calling each other
+    if n % 2 == 0 {
+        pong(n)
+    } else {
+        pong(n + 1)
+    }
+}
+
+fn pong(n: u32) {
+    ping(n)
+}
+
+fn a() { //~ ERROR functions cannot return without calling each other
+    b()
+}
+
+fn b() {
+    c()
+}
+
+fn c() {
+    a()
+}
+
+fn main() {}
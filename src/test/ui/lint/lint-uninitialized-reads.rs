@@ -0,0 +1,24 @@
+#![allow(deprecated)]
+
+use std::mem::{self, MaybeUninit};
+
+fn main() {
+    unsafe {
+        let x = MaybeUninit::<u32>::uninit();
+        let _y = x.assume_init(); //~ ERROR `assume_init` called on a `MaybeUninit` that was never
+
+        let mut x = MaybeUninit::<u32>::uninit();
+        x.as_mut_ptr().write(1);
+        let _y = x.assume_init();
+
+        let x = MaybeUninit::<[MaybeUninit<u8>; 4]>::uninit();
+        let _y = x.assume_init();
+
+        let x: u32 = mem::uninitialized();
+        let _y = x; //~ ERROR use of a value that was never initialized
+
+        let mut x: u32 = mem::uninitialized();
+        std::ptr::write(&mut x, 1);
+        let _y = x;
+    }
+}
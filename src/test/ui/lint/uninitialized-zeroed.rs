@@ -3,7 +3,7 @@
 // in a lint.
 
 #![feature(never_type, rustc_attrs)]
-#![allow(deprecated)]
+#![allow(deprecated, uninitialized_reads)]
 #![deny(invalid_value)]
 
 use std::mem::{self, MaybeUninit};
@@ -0,0 +1,27 @@
+// General test of the locals that may have escaped, i.e. that a pointer may point to, as computed
+// on the MIR.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+#[rustc_mir(rustc_peek_escaped,stop_after_dataflow)]
+fn foo(test: bool) -> i32 {
+    let x = 0;
+    let y = 1;
+
+    // Nothing has been borrowed yet.
+    unsafe { rustc_peek(x); } //~ ERROR rustc_peek: bit not set
+
+    let p = if test { &x } else { &2 };
+
+    // `x` may be borrowed now, but not `y`.
+    unsafe { rustc_peek(x); }
+    unsafe { rustc_peek(y); } //~ ERROR rustc_peek: bit not set
+
+    *p + y
+}
+
+fn main() {
+    foo(true);
+}
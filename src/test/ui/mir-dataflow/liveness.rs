@@ -0,0 +1,35 @@
+// General test of the liveness of locals computed on the MIR: the bit of a local is set if its
+// value may still be read after the call to `rustc_peek`.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+#[rustc_mir(rustc_peek_liveness,stop_after_dataflow)]
+fn foo(test: bool) -> i32 {
+    let mut x = 1;
+    let y = 2;
+
+    // `x` is overwritten on both paths before being read.
+    unsafe { rustc_peek(x); } //~ ERROR rustc_peek: bit not set
+
+    // `y` is read on one of the paths.
+    unsafe { rustc_peek(y); }
+
+    if test {
+        x = y;
+    } else {
+        x = 3;
+    }
+
+    // `y` is never read again.
+    unsafe { rustc_peek(y); } //~ ERROR rustc_peek: bit not set
+
+    // `x` is returned.
+    unsafe { rustc_peek(x); }
+    x
+}
+
+fn main() {
+    foo(true);
+}
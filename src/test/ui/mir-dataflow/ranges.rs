@@ -0,0 +1,41 @@
+// General test of the integer ranges computed on the MIR. Every call to `rustc_peek` reports the
+// range of its argument.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+#[rustc_mir(rustc_peek_range,stop_after_dataflow)]
+fn foo(x: u8, test: bool) -> u8 {
+    // Nothing is known about the arguments.
+    unsafe { rustc_peek(x); } //~ ERROR rustc_peek: range 0..=255
+
+    let mut y: u8 = 10;
+    if test {
+        y = 20;
+    }
+    unsafe { rustc_peek(y); } //~ ERROR rustc_peek: range 10..=20
+
+    if x < 100 {
+        unsafe { rustc_peek(x); } //~ ERROR rustc_peek: range 0..=99
+        let z = x / 2 + y;
+        unsafe { rustc_peek(z); } //~ ERROR rustc_peek: range 10..=69
+        if x > 200 {
+            unsafe { rustc_peek(x); } //~ ERROR rustc_peek: unreachable
+        }
+        return z;
+    }
+    unsafe { rustc_peek(x); } //~ ERROR rustc_peek: range 100..=255
+
+    let mut i: u8 = 0;
+    while i < 10 {
+        i += 1;
+    }
+    // The range is widened to the bounds of `u8` around the loop.
+    unsafe { rustc_peek(i); } //~ ERROR rustc_peek: range 10..=255
+    i
+}
+
+fn main() {
+    foo(0, true);
+}
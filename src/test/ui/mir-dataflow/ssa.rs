@@ -0,0 +1,38 @@
+// General test of the SSA renaming of the locals computed on the MIR. Every call to `rustc_peek`
+// reports where the value of its argument that it reads is defined.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+#[rustc_mir(rustc_peek_ssa,stop_after_dataflow)]
+fn foo(n: u32, test: bool) -> u32 {
+    unsafe { rustc_peek(n); } //~ ERROR rustc_peek: argument
+
+    let mut i = 0;
+    unsafe { rustc_peek(i); } //~ ERROR rustc_peek: assigned
+    while i < n {
+        i += 1;
+    }
+    // The value from before the loop and the one from its body are merged.
+    unsafe { rustc_peek(i); } //~ ERROR rustc_peek: phi of 2 values
+
+    let x;
+    if test {
+        x = 1;
+    } else {
+        x = 2;
+    }
+    unsafe { rustc_peek(x); } //~ ERROR rustc_peek: phi of 2 values
+
+    // Borrowed locals are not renamed, but the references to them are.
+    let y = 1;
+    let r = &y;
+    unsafe { rustc_peek(y); } //~ ERROR rustc_peek: not renamed
+    unsafe { rustc_peek(r); } //~ ERROR rustc_peek: assigned
+    i + x + *r
+}
+
+fn main() {
+    foo(10, true);
+}
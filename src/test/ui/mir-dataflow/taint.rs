@@ -0,0 +1,64 @@
+// Checks which values the taint analysis considers derived from `#[rustc_taint_source]` calls.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+#[rustc_taint_source]
+fn read_input() -> u32 {
+    42
+}
+
+#[rustc_taint_source]
+fn read_into(buf: &mut u32) {
+    *buf = 42;
+}
+
+#[rustc_taint_sanitizer]
+fn check(x: u32) -> u32 {
+    x
+}
+
+#[rustc_taint_sink]
+fn run(_x: u32) {}
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn constant(_x: u32) -> u32 {
+    0
+}
+
+#[rustc_mir(rustc_peek_tainted, stop_after_dataflow)]
+fn foo(arg: u32) {
+    unsafe { rustc_peek(arg) }; //~ ERROR rustc_peek: bit not set
+
+    let x = read_input();
+    unsafe { rustc_peek(x) };
+
+    // Through arithmetic, aggregates and calls of local functions.
+    let y = x + 1;
+    unsafe { rustc_peek(y) };
+    let pair = (y, 0);
+    unsafe { rustc_peek(pair) };
+    let d = double(x);
+    unsafe { rustc_peek(d) };
+    let c = constant(x);
+    unsafe { rustc_peek(c) }; //~ ERROR rustc_peek: bit not set
+
+    let z = check(y);
+    unsafe { rustc_peek(z) }; //~ ERROR rustc_peek: bit not set
+
+    // Through the buffer filled by a source.
+    let mut buf = 0;
+    read_into(&mut buf);
+    unsafe { rustc_peek(buf) };
+
+    run(z);
+    run(pair.0); //~ ERROR rustc_peek: tainted argument passed to sink
+}
+
+fn main() {
+    foo(0);
+}
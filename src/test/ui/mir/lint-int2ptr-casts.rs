@@ -0,0 +1,17 @@
+// compile-flags: -Z lint-int2ptr-casts
+// check-pass
+
+// Checks that casts of integers to pointers are reported, but not casts between pointers or of
+// pointers to integers.
+
+fn from_addr(addr: usize) -> *const u8 {
+    addr as *const u8 //~ WARNING cast of integer `usize` to pointer `*const u8`
+}
+
+fn main() {
+    let x = 0u32;
+    let p = &x as *const u32;
+    let _ = p as *const u8;
+    let _ = p as usize;
+    let _ = from_addr(4);
+}
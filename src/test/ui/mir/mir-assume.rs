@@ -0,0 +1,53 @@
+// run-pass
+// compile-flags: -Z mir-opt-level=2
+// ignore-wasm32-bare compiled with panic=abort by default
+// Checks that the `assume` statements lowered from the intrinsic and from the branches to
+// `unreachable_unchecked` are kept, and that the bounds checks removed by `RemoveBoundsChecks` are
+// only the ones that can't fail.
+
+#![feature(core_intrinsics)]
+
+use std::hint::unreachable_unchecked;
+use std::intrinsics::assume;
+use std::panic;
+
+fn get_assumed(a: &[u8; 4], i: usize) -> u8 {
+    unsafe {
+        assume(i < 4);
+    }
+    a[i]
+}
+
+fn get_unchecked(a: &[u8; 4], i: usize) -> u8 {
+    if !(i < 4) {
+        unsafe { unreachable_unchecked() }
+    }
+    a[i]
+}
+
+fn get_tested(a: &[u8; 4], i: usize) -> u8 {
+    if i < 4 { a[i] } else { 0 }
+}
+
+fn get(a: &[u8; 4], i: usize) -> u8 {
+    a[i]
+}
+
+fn is_small(x: u32) -> bool {
+    let small = x < 10;
+    unsafe {
+        assume(small);
+    }
+    small
+}
+
+fn main() {
+    let a = [1, 2, 3, 4];
+    assert_eq!(get_assumed(&a, 3), 4);
+    assert_eq!(get_unchecked(&a, 1), 2);
+    assert_eq!(get_tested(&a, 2), 3);
+    assert_eq!(get_tested(&a, 4), 0);
+    assert_eq!(get(&a, 0), 1);
+    assert!(panic::catch_unwind(|| get(&a, 4)).is_err());
+    assert!(is_small(3));
+}
@@ -0,0 +1,21 @@
+// run-pass
+// compile-flags: -Z mir-opt-level=2
+// Checks that arrays compared bytewise by the `BytewiseArrayEq` MIR pass compare correctly.
+
+fn eq<T: PartialEq>(a: &T, b: &T) -> bool {
+    *a == *b
+}
+
+fn main() {
+    assert!([1u8, 2, 3] == [1, 2, 3]);
+    assert!([1u8, 2, 3] != [1, 2, 4]);
+    assert!([-1i32, 0, 1] == [-1, 0, 1]);
+    assert!([-1i32, 0, 1] != [-1, 0, 2]);
+    assert!(['a', 'b'] == ['a', 'b']);
+    assert!(['a', 'b'] != ['b', 'a']);
+    assert!([true, false] == [true, false]);
+    assert!([true, false] != [false, false]);
+    assert!([0u64; 0] == []);
+    assert!(eq(&[u128::max_value(); 3], &[u128::max_value(); 3]));
+    assert!(!eq(&[1usize, 2], &[1, 3]));
+}
@@ -0,0 +1,44 @@
+// Checks the callees collected from the MIR for the call graph.
+
+#![feature(rustc_attrs)]
+#![allow(dead_code)]
+
+trait Speak {
+    fn speak(&self);
+}
+
+struct Dog;
+
+impl Speak for Dog {
+    fn speak(&self) {}
+}
+
+fn local() {}
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+#[rustc_dump_callees]
+fn static_dispatch(c: bool) {
+    local(); //~ ERROR callee: `local`, always
+    if c {
+        Dog.speak(); //~ ERROR callee: `<Dog as Speak>::speak`
+    }
+}
+
+#[rustc_dump_callees]
+fn dynamic_dispatch<T: Speak>(t: &T, speaker: &dyn Speak, f: fn()) {
+    t.speak(); //~ ERROR callee: unknown
+    speaker.speak(); //~ ERROR callee: unknown
+    f(); //~ ERROR callee: unknown
+}
+
+#[rustc_dump_callees]
+fn callbacks(x: Option<u32>) -> Option<u32> {
+    x.map(double)
+    //~^ ERROR callee: extern `std::option::Option::<T>::map`
+    //~| ERROR callee: `double`
+}
+
+fn main() {}
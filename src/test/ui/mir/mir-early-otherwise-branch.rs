@@ -0,0 +1,42 @@
+// Checks the matches on several enums, whose discriminants are compared up front.
+
+// run-pass
+
+fn both(x: Option<u32>, y: Option<u32>) -> u32 {
+    match (x, y) {
+        (Some(a), Some(b)) => a + b,
+        _ => 0,
+    }
+}
+
+fn same_variant(x: Result<u8, i8>, y: Result<u16, i16>) -> i32 {
+    match (x, y) {
+        (Ok(a), Ok(b)) => a as i32 + b as i32,
+        (Err(a), Err(b)) => a as i32 + b as i32,
+        _ => 1000,
+    }
+}
+
+fn three(x: Option<u8>, y: Option<u8>, z: Option<u8>) -> u8 {
+    match (x, y, z) {
+        (Some(a), Some(b), Some(c)) => a + b + c,
+        _ => 0,
+    }
+}
+
+fn main() {
+    assert_eq!(both(Some(1), Some(2)), 3);
+    assert_eq!(both(Some(1), None), 0);
+    assert_eq!(both(None, Some(2)), 0);
+    assert_eq!(both(None, None), 0);
+
+    assert_eq!(same_variant(Ok(1), Ok(2)), 3);
+    assert_eq!(same_variant(Err(-1), Err(-2)), -3);
+    assert_eq!(same_variant(Ok(1), Err(-2)), 1000);
+    assert_eq!(same_variant(Err(-1), Ok(2)), 1000);
+
+    assert_eq!(three(Some(1), Some(2), Some(3)), 6);
+    assert_eq!(three(Some(1), Some(2), None), 0);
+    assert_eq!(three(Some(1), None, Some(3)), 0);
+    assert_eq!(three(None, Some(2), Some(3)), 0);
+}
@@ -0,0 +1,51 @@
+// run-pass
+// compile-flags: -Z mir-opt-level=2
+// Checks that the fields moved directly out of a box by `ForwardBoxMoves` are dropped once, along
+// with the fields left in the box.
+
+use std::cell::Cell;
+
+struct Noisy<'a>(&'a Cell<u32>, u32);
+
+impl Drop for Noisy<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + self.1);
+    }
+}
+
+struct Big<'a> {
+    first: Noisy<'a>,
+    second: Noisy<'a>,
+    padding: [u64; 64],
+}
+
+fn big(drops: &Cell<u32>) -> Box<Big<'_>> {
+    Box::new(Big { first: Noisy(drops, 1), second: Noisy(drops, 10), padding: [7; 64] })
+}
+
+fn through_block(drops: &Cell<u32>) -> u64 {
+    let boxed = big(drops);
+    let Big { first, second, padding } = { *boxed };
+    drop(second);
+    assert_eq!(drops.get(), 10);
+    drop(first);
+    padding[63]
+}
+
+fn through_variable(drops: &Cell<u32>) -> u64 {
+    let boxed = big(drops);
+    let value = *boxed;
+    let Big { first, padding, .. } = value;
+    drop(first);
+    padding[0]
+}
+
+fn main() {
+    let drops = Cell::new(0);
+    assert_eq!(through_block(&drops), 7);
+    assert_eq!(drops.get(), 11);
+
+    let drops = Cell::new(0);
+    assert_eq!(through_variable(&drops), 7);
+    assert_eq!(drops.get(), 11);
+}
@@ -0,0 +1,39 @@
+// compile-flags: -Z mir-opt-remarks=Inline
+// build-pass
+// dont-check-compiler-stderr
+
+// Checks that the iterator adapter chains over local closures are inlined in unoptimized builds,
+// and that the other calls are left alone. The notes for the calls inlined out of the adapters
+// point into libcore, hence the unchecked stderr.
+
+#![crate_type = "lib"]
+
+pub struct Countdown(u32);
+
+impl Iterator for Countdown {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.0 == 0 {
+            return None;
+        }
+        self.0 -= 1;
+        Some(self.0)
+    }
+}
+
+pub fn first_doubled(c: Countdown) -> Option<u32> {
+    c.map(|x| x * 2).next()
+    //~^ NOTE Inline applied
+    //~| NOTE Inline applied
+}
+
+#[inline]
+fn apply<F: Fn(u32) -> u32>(f: F, x: u32) -> u32 {
+    f(x)
+}
+
+// The closure is not passed to an iterator adapter.
+pub fn not_a_chain(x: u32) -> u32 {
+    apply(|x| x + 1, x)
+}
@@ -0,0 +1,50 @@
+// run-pass
+// compile-flags: -Z mir-opt-level=2
+// Checks that the switches narrowed by the `NarrowSwitches` MIR pass still take the right
+// targets.
+
+#[derive(Copy, Clone)]
+enum Shade {
+    Light = -1,
+    Medium = 3,
+    Dark = 10,
+}
+
+fn quarter(x: u8) -> u32 {
+    match x % 4 {
+        0 => 10,
+        1 => 20,
+        2 => 30,
+        3 => 40,
+        _ => 50,
+    }
+}
+
+fn small(x: i8) -> i32 {
+    if x >= -1 && x < 2 {
+        match x {
+            -1 => -10,
+            0 => 0,
+            1 => 10,
+            7 => 70,
+            _ => 100,
+        }
+    } else {
+        1000
+    }
+}
+
+fn shade(s: Shade) -> u32 {
+    match s as isize {
+        -1 => 1,
+        3 => 2,
+        10 => 3,
+        _ => 4,
+    }
+}
+
+fn main() {
+    assert_eq!([quarter(0), quarter(5), quarter(10), quarter(255)], [10, 20, 30, 40]);
+    assert_eq!([small(-1), small(0), small(1), small(7), small(-128)], [-10, 0, 10, 1000, 1000]);
+    assert_eq!([shade(Shade::Light), shade(Shade::Medium), shade(Shade::Dark)], [1, 2, 3]);
+}
@@ -0,0 +1,24 @@
+// run-pass
+// Checks that the calls to `unreachable_unchecked` that `NormalizeUnreachable` turns into
+// `unreachable` terminators leave the reachable code alone.
+
+#![feature(core_intrinsics)]
+
+use std::hint::unreachable_unchecked;
+use std::intrinsics;
+
+fn first_digit(s: &str) -> u32 {
+    match s.chars().next().and_then(|c| c.to_digit(10)) {
+        Some(digit) => digit,
+        None => unsafe { unreachable_unchecked() },
+    }
+}
+
+fn checked_half(x: u32) -> u32 {
+    if x % 2 == 0 { x / 2 } else { unsafe { intrinsics::unreachable() } }
+}
+
+fn main() {
+    assert_eq!(first_digit("42"), 4);
+    assert_eq!(checked_half(10), 5);
+}
@@ -0,0 +1,21 @@
+// compile-flags: -Z mir-opt-remarks=SimplifyArmIdentity
+// build-pass
+
+// Checks that `SimplifyArmIdentity` reports the match arms it replaces, and the ones it can't.
+
+#![crate_type = "lib"]
+#![allow(redundant_match_arms)]
+
+pub fn id(x: Result<u32, i32>) -> Result<u32, i32> {
+    match x {
+        Ok(v) => Ok(v), //~ NOTE SimplifyArmIdentity applied
+        Err(e) => Err(e), //~ NOTE SimplifyArmIdentity applied
+    }
+}
+
+pub fn convert(x: Result<u32, ()>) -> Result<u32, u8> {
+    match x {
+        Ok(v) => Ok(v), //~ NOTE SimplifyArmIdentity not applied
+        Err(()) => Err(0),
+    }
+}
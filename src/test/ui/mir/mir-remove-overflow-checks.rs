@@ -0,0 +1,32 @@
+// run-pass
+// compile-flags: -C overflow-checks=on -Z mir-opt-level=2
+// ignore-wasm32-bare compiled with panic=abort by default
+// Checks that the `RemoveOverflowChecks` MIR pass only removes the overflow checks that can't
+// fail.
+
+use std::panic;
+
+fn count_to_ten() -> u8 {
+    let mut i = 0u8;
+    while i < 10 {
+        i += 1;
+    }
+    i
+}
+
+fn add(x: u8, y: u8) -> u8 {
+    if x < 100 && y < 100 { x + y } else { x + 1 }
+}
+
+fn shift(x: u32, amount: u32) -> u32 {
+    if amount < 32 { x << amount } else { x >> amount }
+}
+
+fn main() {
+    assert_eq!(count_to_ten(), 10);
+    assert_eq!(add(99, 99), 198);
+    assert_eq!(add(200, 0), 201);
+    assert_eq!(shift(1, 31), 1 << 31);
+    assert!(panic::catch_unwind(|| add(255, 0)).is_err());
+    assert!(panic::catch_unwind(|| shift(1, 32)).is_err());
+}
@@ -0,0 +1,53 @@
+// Checks the values wrapped in `#[repr(transparent)]` structs that are unwrapped again, which
+// are moved without the wrapper.
+
+// run-pass
+
+use std::marker::PhantomData;
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Meters(u64);
+
+#[repr(transparent)]
+struct Tagged<T, Tag>(T, PhantomData<Tag>);
+
+#[repr(transparent)]
+struct Outer(Meters);
+
+struct Pair(u8, u32);
+
+fn unwrap(x: u64) -> u64 {
+    Meters(x).0
+}
+
+fn unwrap_tagged(x: String) -> String {
+    Tagged::<String, ()>(x, PhantomData).0
+}
+
+fn unwrap_nested(x: u64) -> u64 {
+    (Outer(Meters(x)).0).0
+}
+
+fn project(pair: Pair) -> u32 {
+    Tagged::<Pair, u8>(pair, PhantomData).0 .1
+}
+
+fn rewrap(x: Meters) -> Meters {
+    let inner = x.0;
+    Meters(inner)
+}
+
+fn copied(x: u64) -> (u64, u64) {
+    let y = Meters(x).0;
+    (x, y)
+}
+
+fn main() {
+    assert_eq!(unwrap(3), 3);
+    assert_eq!(unwrap_tagged(String::from("tag")), "tag");
+    assert_eq!(unwrap_nested(7), 7);
+    assert_eq!(project(Pair(1, 2)), 2);
+    assert_eq!(rewrap(Meters(5)), Meters(5));
+    assert_eq!(copied(9), (9, 9));
+}
@@ -0,0 +1,52 @@
+// Checks the branches on the comparisons of integers to constants, which are switched on the
+// integer directly.
+
+// run-pass
+
+fn eq(x: i8) -> u32 {
+    if x == -3 { 1 } else { 2 }
+}
+
+fn ne(x: u64) -> u32 {
+    if x != 7 { 1 } else { 2 }
+}
+
+fn flipped(x: i32) -> u32 {
+    if 5 == x { 1 } else { 2 }
+}
+
+fn bounds(x: i8, y: u16) -> u32 {
+    let mut result = 0;
+    if x <= i8::min_value() {
+        result += 1;
+    }
+    if x > i8::min_value() {
+        result += 10;
+    }
+    if y >= u16::max_value() {
+        result += 100;
+    }
+    if y < u16::max_value() {
+        result += 1000;
+    }
+    result
+}
+
+fn result_reused(x: u8) -> (bool, u32) {
+    let is_zero = x == 0;
+    (is_zero, if is_zero { 1 } else { 2 })
+}
+
+fn main() {
+    assert_eq!(eq(-3), 1);
+    assert_eq!(eq(3), 2);
+    assert_eq!(ne(7), 2);
+    assert_eq!(ne(8), 1);
+    assert_eq!(flipped(5), 1);
+    assert_eq!(flipped(-5), 2);
+    assert_eq!(bounds(i8::min_value(), u16::max_value()), 101);
+    assert_eq!(bounds(0, 0), 1010);
+    assert_eq!(bounds(i8::max_value(), u16::max_value() - 1), 1010);
+    assert_eq!(result_reused(0), (true, 1));
+    assert_eq!(result_reused(1), (false, 2));
+}
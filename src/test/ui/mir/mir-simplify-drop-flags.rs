@@ -0,0 +1,48 @@
+// run-pass
+// compile-flags: -Z mir-opt-level=2
+// Checks that the drop flags removed by `SimplifyDropFlags` leave each value dropped once.
+
+use std::cell::Cell;
+
+struct Noisy<'a>(&'a Cell<u32>);
+
+impl Drop for Noisy<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn consume(_: Noisy<'_>) {}
+
+fn maybe_move(drops: &Cell<u32>, cond: bool) {
+    let x = Noisy(drops);
+    if cond {
+        consume(x);
+    }
+}
+
+fn never_move(drops: &Cell<u32>) {
+    let x = Noisy(drops);
+    if false {
+        consume(x);
+    }
+}
+
+fn always_move(drops: &Cell<u32>) {
+    let x = Noisy(drops);
+    if true {
+        consume(x);
+    }
+}
+
+fn main() {
+    let drops = Cell::new(0);
+    maybe_move(&drops, true);
+    assert_eq!(drops.get(), 1);
+    maybe_move(&drops, false);
+    assert_eq!(drops.get(), 2);
+    never_move(&drops);
+    assert_eq!(drops.get(), 3);
+    always_move(&drops);
+    assert_eq!(drops.get(), 4);
+}
@@ -0,0 +1,42 @@
+// run-pass
+// compile-flags: -Z mir-opt-level=2
+// Checks that `UpgradeToMoves` only moves the locals that are not read afterwards.
+
+fn sum(values: [u64; 64]) -> u64 {
+    values.iter().sum()
+}
+
+fn last_use(seed: u64) -> u64 {
+    let values = [seed; 64];
+    sum(values)
+}
+
+fn used_after(seed: u64) -> u64 {
+    let values = [seed; 64];
+    let first = sum(values);
+    first + values[0]
+}
+
+fn used_in_loop(seed: u64) -> u64 {
+    let values = [seed; 64];
+    let mut total = 0;
+    for _ in 0..3 {
+        total += sum(values);
+    }
+    total
+}
+
+fn borrowed(seed: u64) -> u64 {
+    let mut values = [seed; 64];
+    let ptr = &mut values as *mut [u64; 64];
+    let first = sum(values);
+    unsafe { (*ptr)[0] = 0 };
+    first + sum(values)
+}
+
+fn main() {
+    assert_eq!(last_use(1), 64);
+    assert_eq!(used_after(2), 130);
+    assert_eq!(used_in_loop(1), 192);
+    assert_eq!(borrowed(1), 127);
+}
@@ -0,0 +1,22 @@
+// compile-flags: -Z trace-mir-pass=LowerIntrinsics&traced
+// build-pass
+
+// Checks that `-Z trace-mir-pass` prints the decisions of the selected pass, and only on the
+// selected functions.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::assume;
+
+fn traced(x: u32) {
+    unsafe { assume(x < 10) }
+}
+
+fn not_traced(x: u32) {
+    unsafe { assume(x < 10) }
+}
+
+fn main() {
+    traced(1);
+    not_traced(2);
+}
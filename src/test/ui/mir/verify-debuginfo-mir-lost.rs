@@ -0,0 +1,18 @@
+// compile-flags: -Z verify-debuginfo-mir -C debuginfo=2
+// build-pass
+
+// Checks that a variable whose local is no longer written is reported, along with the pass that
+// lost it. `SimplifyArmIdentity` replaces the arm below by a move of `x` to the return place, and
+// leaves `v` without a value.
+
+fn identity(x: Option<u32>) -> Option<u32> {
+    match x {
+        Some(v) => Some(v),
+        //~^ WARNING debuginfo for `v` refers to `_3`, which is never written
+        None => None,
+    }
+}
+
+fn main() {
+    identity(Some(1));
+}
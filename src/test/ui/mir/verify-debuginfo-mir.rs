@@ -0,0 +1,25 @@
+// compile-flags: -Z verify-debuginfo-mir -C debuginfo=2
+// build-pass
+
+// Checks that the default MIR optimizations keep the debuginfo of these variables intact.
+
+fn add(x: u32, y: u32) -> u32 {
+    let sum = x + y;
+    let doubled = sum * 2;
+    doubled
+}
+
+fn pick(c: bool) -> Option<u32> {
+    let value = if c { Some(1) } else { None };
+    match value {
+        Some(v) => Some(v + 1),
+        None => None,
+    }
+}
+
+fn main() {
+    let unit = ();
+    let _ = unit;
+    add(1, 2);
+    pick(true);
+}
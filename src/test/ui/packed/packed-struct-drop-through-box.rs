@@ -0,0 +1,36 @@
+// run-pass
+// Checks dropping the rest of a box that is a field of a packed struct, after moving out of its
+// contents, which reads the box pointer from a misaligned address.
+use std::cell::Cell;
+use std::mem;
+
+struct Aligned<'a> {
+    drop_count: &'a Cell<usize>
+}
+
+#[inline(never)]
+fn check_align(ptr: *const Aligned) {
+    assert_eq!(ptr as usize % mem::align_of::<Aligned>(),
+               0);
+}
+
+impl<'a> Drop for Aligned<'a> {
+    fn drop(&mut self) {
+        check_align(self);
+        self.drop_count.set(self.drop_count.get() + 1);
+    }
+}
+
+#[repr(packed)]
+struct Packed<'a>(u8, Box<(Aligned<'a>, Aligned<'a>)>);
+
+fn main() {
+    let drop_count = &Cell::new(0);
+    {
+        let p = Packed(0, Box::new((Aligned { drop_count }, Aligned { drop_count })));
+        let first = (*p.1).0;
+        drop(first);
+        assert_eq!(drop_count.get(), 1);
+    }
+    assert_eq!(drop_count.get(), 2);
+}
@@ -0,0 +1,30 @@
+// run-pass
+// compile-flags: -Zmir-opt-level=2
+
+#![feature(track_caller)]
+
+use std::panic::Location;
+
+#[inline]
+#[track_caller]
+fn tracked() -> &'static Location<'static> {
+    Location::caller()
+}
+
+#[inline]
+#[track_caller]
+fn nested() -> &'static Location<'static> {
+    tracked()
+}
+
+fn main() {
+    let loc = tracked();
+    assert_eq!(loc.file(), file!());
+    assert_eq!(loc.line(), 21);
+    assert_eq!(loc.column(), 15);
+
+    // The caller location of the inlined `tracked` is the one `nested` was called from.
+    let loc = nested();
+    assert_eq!(loc.line(), 27);
+    assert_eq!(loc.column(), 15);
+}
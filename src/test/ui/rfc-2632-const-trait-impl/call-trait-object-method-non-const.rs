@@ -0,0 +1,23 @@
+// Checks that calling a method of a trait object whose impl is not `const` is rejected during
+// evaluation.
+
+#![feature(const_trait_object_calls)]
+
+trait Shape {
+    fn area(&self) -> usize;
+}
+
+struct Square(usize);
+
+impl Shape for Square {
+    fn area(&self) -> usize {
+        self.0 * self.0
+    }
+}
+
+const SHAPE: &dyn Shape = &Square(2);
+
+fn main() {
+    let _ = [0; SHAPE.area()];
+    //~^ ERROR evaluation of constant value failed
+}
@@ -0,0 +1,41 @@
+// run-pass
+
+// Checks that the methods of trait objects can be called in constants when the impls they
+// dispatch to are `const`.
+
+#![allow(incomplete_features)]
+#![feature(const_trait_impl)]
+#![feature(const_trait_object_calls)]
+#![feature(const_fn)]
+
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square(u32);
+struct Rect(u32, u32);
+
+impl const Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+impl const Shape for Rect {
+    fn area(&self) -> u32 {
+        self.0 * self.1
+    }
+}
+
+const SHAPES: [&dyn Shape; 2] = [&Square(3), &Rect(2, 5)];
+
+const fn area_of(shape: &dyn Shape) -> u32 {
+    shape.area()
+}
+
+const TOTAL: u32 = area_of(SHAPES[0]) + area_of(SHAPES[1]);
+
+fn main() {
+    assert_eq!(TOTAL, 19);
+    assert_eq!(area_of(SHAPES[1]), 10);
+}
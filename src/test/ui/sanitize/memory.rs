@@ -15,6 +15,7 @@
 #![feature(core_intrinsics)]
 #![feature(start)]
 #![feature(test)]
+#![allow(uninitialized_reads)]
 
 use std::hint::black_box;
 use std::mem::MaybeUninit;
@@ -57,7 +57,7 @@ unsafe fn parent_sibling_borrow() {
 unsafe fn parent_sibling_move() {
     let u = U { x: ((MockVec::new(), MockVec::new()), MockVec::new()) };
     let a = u.x.0;
-    let b = u.y; //~ ERROR use of moved value: `u`
+    let b = u.y; //~ ERROR use of moved value: `u.y`
 }
 
 unsafe fn grandparent_sibling_borrow() {
@@ -70,7 +70,7 @@ unsafe fn grandparent_sibling_borrow() {
 unsafe fn grandparent_sibling_move() {
     let u = U { x: ((MockVec::new(), MockVec::new()), MockVec::new()) };
     let a = (u.x.0).0;
-    let b = u.y; //~ ERROR use of moved value: `u`
+    let b = u.y; //~ ERROR use of moved value: `u.y`
 }
 
 unsafe fn deref_sibling_borrow() {